@@ -0,0 +1,112 @@
+//! Benchmarks for drawing whole frames and individual widgets into an off-screen buffer.
+//!
+//! Run with `cargo bench`. Frames are drawn at a fixed size with a fixed clock, so the numbers
+//! only move when the rendering code does.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use tokio_console_mocks::draw_frame;
+use tokio_console_mocks::fixtures;
+use tokio_console_mocks::glyphs::GLYPH_SET_NERD_FONT;
+use tokio_console_mocks::state::AppState;
+use tui::backend::TestBackend;
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::style::Color;
+use tui::widgets::Widget;
+use tui::Terminal;
+use turbowish_widgets::{
+    AutoScale, BarChart, BoxFrame, ChartCache, Powerline, ScrollState, Scrollbar, SegmentedControl,
+    SCROLLBAR_SYMBOLS_NERD_FONT,
+};
+
+static TASK_COUNTS: [usize; 3] = [10, 1_000, 50_000];
+static LABELS: [&str; 4] = [
+    "All (405)",
+    "Running (3)",
+    "Sleeping (402)",
+    "Deadlocked (0)",
+];
+
+fn bench_draw_frame(criterion: &mut Criterion) {
+    let clock = fixtures::fixed_clock();
+    let mut group = criterion.benchmark_group("draw_frame");
+    for &task_count in &TASK_COUNTS {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::repeated(task_count)));
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        group.bench_function(BenchmarkId::from_parameter(task_count), |bencher| {
+            bencher.iter(|| {
+                terminal
+                    .draw(|frame| draw_frame(frame, &clock, &GLYPH_SET_NERD_FONT, &mut state))
+                    .unwrap();
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_widget<W, F>(criterion: &mut Criterion, name: &str, area: Rect, mut widget: F)
+where
+    W: Widget,
+    F: FnMut() -> W,
+{
+    let mut buffer = Buffer::empty(area);
+    criterion.bench_function(name, |bencher| {
+        bencher.iter(|| {
+            buffer.reset();
+            widget().render(area, &mut buffer);
+            black_box(&buffer);
+        })
+    });
+}
+
+fn bench_widgets(criterion: &mut Criterion) {
+    let data: Vec<f32> = (0..400).map(|index| (index % 37) as f32).collect();
+    bench_widget(
+        criterion,
+        "bar_chart/braille",
+        Rect::new(0, 0, 100, 8),
+        || BarChart::auto_scaled(&data, AutoScale::default(), Color::Green).axis(Color::Gray),
+    );
+    let chart_cache = ChartCache::default();
+    bench_widget(
+        criterion,
+        "bar_chart/braille_cached",
+        Rect::new(0, 0, 100, 8),
+        || {
+            BarChart::auto_scaled(&data, AutoScale::default(), Color::Green)
+                .axis(Color::Gray)
+                .cached(&chart_cache, 0)
+        },
+    );
+    bench_widget(criterion, "powerline", Rect::new(0, 0, 80, 1), || {
+        Powerline::new(&LABELS)
+    });
+    bench_widget(
+        criterion,
+        "segmented_control",
+        Rect::new(0, 0, 80, 1),
+        || SegmentedControl::new(&LABELS, 1, Color::Black, Color::Gray),
+    );
+    bench_widget(criterion, "box_frame", Rect::new(0, 0, 120, 40), || {
+        BoxFrame::new("Tasks")
+            .aux_label("updated 0.2s ago")
+            .footer_label("405 tasks")
+            .border_color(Color::Red)
+    });
+    let mut scroll_state = ScrollState::default();
+    scroll_state.set_lengths(50_000, 30);
+    scroll_state.scroll_by(20_000);
+    let area = Rect::new(0, 0, 1, 40);
+    let mut buffer = Buffer::empty(area);
+    criterion.bench_function("scrollbar", |bencher| {
+        bencher.iter(|| {
+            buffer.reset();
+            Scrollbar::new(&mut scroll_state, &SCROLLBAR_SYMBOLS_NERD_FONT, Color::Gray)
+                .render(area, &mut buffer);
+            black_box(&buffer);
+        })
+    });
+}
+
+criterion_group!(benches, bench_draw_frame, bench_widgets);
+criterion_main!(benches);
@@ -0,0 +1,53 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use tui::layout::Alignment;
+use tui::style::Color;
+use tui::widgets::Borders;
+use turbowish_widgets::{BorderSet, BoxFrame};
+
+mod common;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    canvas: common::Canvas,
+    label: String,
+    aux_label: Option<String>,
+    footer_label: Option<String>,
+    alignment: u8,
+    border_set: u8,
+    borders: u8,
+    padding: u8,
+}
+
+fuzz_target!(|input: Input| {
+    let label_alignment = match input.alignment % 3 {
+        0 => Alignment::Left,
+        1 => Alignment::Center,
+        _ => Alignment::Right,
+    };
+    let border_set = match input.border_set % 5 {
+        0 => BorderSet::Rounded,
+        1 => BorderSet::Square,
+        2 => BorderSet::Double,
+        3 => BorderSet::Thick,
+        _ => BorderSet::Ascii,
+    };
+    let mut box_frame = BoxFrame::new(&input.label)
+        .label_alignment(label_alignment)
+        .border_color(Color::Red)
+        .border_set(border_set)
+        .borders(Borders::from_bits_truncate(input.borders.into()))
+        .padding(input.padding.into(), input.padding.into());
+    if let Some(ref aux_label) = input.aux_label {
+        box_frame = box_frame.aux_label(aux_label);
+    }
+    if let Some(ref footer_label) = input.footer_label {
+        box_frame = box_frame.footer_label(footer_label);
+    }
+    let (area, inner) = (input.canvas.area(), box_frame.inner(input.canvas.area()));
+    assert!(inner.x >= area.x && inner.right() <= area.right());
+    assert!(inner.y >= area.y && inner.bottom() <= area.bottom());
+    input.canvas.render(box_frame);
+});
@@ -0,0 +1,45 @@
+//! Shared setup for the fuzz targets, which feed arbitrary labels and areas to the widgets that
+//! lay out text, looking for panics from width arithmetic or writes outside the buffer. Run one
+//! with `cargo +nightly fuzz run powerline` (or `segmented_control`, `box_frame`).
+
+use arbitrary::Arbitrary;
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::widgets::Widget;
+
+/// A buffer and an area to render into. The area isn't tied to the buffer, so it can be empty,
+/// hang off the buffer's edges, or miss it entirely. Everything is kept within `u8` so that the
+/// area's right and bottom edges can't overflow `u16`, which tui's `Rect` doesn't guard against.
+#[derive(Arbitrary, Debug)]
+pub struct Canvas {
+    buffer_width: u8,
+    buffer_height: u8,
+    x: u8,
+    y: u8,
+    width: u8,
+    height: u8,
+}
+
+impl Canvas {
+    pub fn render<W>(&self, widget: W)
+    where
+        W: Widget,
+    {
+        let mut buffer = Buffer::empty(Rect::new(
+            0,
+            0,
+            self.buffer_width as u16,
+            self.buffer_height as u16,
+        ));
+        widget.render(self.area(), &mut buffer);
+    }
+
+    pub fn area(&self) -> Rect {
+        Rect::new(
+            self.x as u16,
+            self.y as u16,
+            self.width as u16,
+            self.height as u16,
+        )
+    }
+}
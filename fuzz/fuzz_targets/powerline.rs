@@ -0,0 +1,34 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use turbowish_widgets::{MainVisibility, Powerline, PowerlineDirection};
+
+mod common;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    canvas: common::Canvas,
+    labels: Vec<String>,
+    right_to_left: bool,
+    main_visible: bool,
+}
+
+fuzz_target!(|input: Input| {
+    let labels: Vec<&str> = input.labels.iter().map(|label| &label[..]).collect();
+    let direction = if input.right_to_left {
+        PowerlineDirection::RightToLeft
+    } else {
+        PowerlineDirection::LeftToRight
+    };
+    let main_visibility = if input.main_visible {
+        MainVisibility::Visible
+    } else {
+        MainVisibility::Invisible
+    };
+    input.canvas.render(
+        Powerline::new(&labels)
+            .direction(direction)
+            .main_visibility(main_visibility),
+    );
+});
@@ -0,0 +1,26 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use tui::style::Color;
+use turbowish_widgets::SegmentedControl;
+
+mod common;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    canvas: common::Canvas,
+    labels: Vec<String>,
+    // Deliberately unconstrained, so out-of-range selections are covered too.
+    selected_index: u32,
+}
+
+fuzz_target!(|input: Input| {
+    let labels: Vec<&str> = input.labels.iter().map(|label| &label[..]).collect();
+    input.canvas.render(SegmentedControl::new(
+        &labels,
+        input.selected_index,
+        Color::Black,
+        Color::Gray,
+    ));
+});
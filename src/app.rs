@@ -0,0 +1,428 @@
+//! The state the user can change through input, and how input changes it.
+//!
+//! Keeping this apart from the terminal lets tests drive the whole UI headlessly: events go in
+//! through `App::handle_event`, and frames come out of `App::draw` into any backend.
+
+use crate::clock::Clock;
+use crate::components::{
+    self, Component, Knobs, KnobsMessage, PerformanceScreen, PerformanceScreenMessage, Propagation,
+    TitleBar, TitleBarMessage,
+};
+use crate::config;
+use crate::error::ConsoleError;
+use crate::export::{self, ExportFormat};
+use crate::fixtures::{self, Demo};
+use crate::format;
+use crate::glyphs::GlyphSet;
+use crate::keymap::{self, KeyScope};
+use crate::link::SimulatedLink;
+use crate::state::{Action, AppState, NotificationKind, Snapshot, PERFORMANCE_SAMPLE_INTERVAL};
+use crate::theme;
+use crate::{
+    draw_error_screen, draw_frame, BOOKMARKS_LOAD_FAILED_LABEL, BOOKMARKS_SAVE_FAILED_LABEL,
+    CONNECTED_TO_LABEL, EXPORTED_LABEL, EXPORTED_TO_LABEL, EXPORT_FAILED_LABEL,
+    EXPORT_SAMPLE_COUNT_LABELS, SESSION_LOAD_FAILED_LABEL, THEME_PREVIEW_KEYS_LABEL,
+    THEME_PREVIEW_LABEL,
+};
+use chrono::{DateTime, Local};
+use crossterm::event::{Event, KeyCode};
+use std::collections::VecDeque;
+use std::io;
+use std::mem;
+use std::path::Path;
+use std::time::Duration;
+use tui::backend::Backend;
+use tui::Frame;
+
+pub struct App {
+    pub clock: Box<dyn Clock>,
+    /// Recoverable errors are shown in place of the dashboard until the user retries.
+    pub error: Option<ConsoleError>,
+    pub state: AppState,
+    /// Keeps the mock target changing as `tick` samples it, for `--demo`.
+    pub demo: Option<Demo>,
+    /// Makes the mock target's updates arrive late or not at all, for `--latency` and `--loss`.
+    pub link: Option<SimulatedLink>,
+    /// How often to switch to the next theme, for comparing them with `--theme-preview`.
+    pub theme_preview_interval: Option<Duration>,
+    /// When the last update came through the link, if there is one, and how long it had taken.
+    last_update: Option<(DateTime<Local>, Duration)>,
+    /// When `tick` next switches themes. Unset until the first tick of the preview.
+    next_theme_at: Option<DateTime<Local>>,
+    /// When `tick` next spawns a mock task. Unset until the first tick.
+    next_spawn_at: Option<DateTime<Local>>,
+    /// When `tick` next takes a performance sample. Unset until the first tick.
+    next_sample_at: Option<DateTime<Local>>,
+    /// The tasks `tick` has spawned that it may still have to finish, oldest first.
+    spawned_task_ids: VecDeque<u64>,
+}
+
+const TASK_SPAWN_INTERVAL_SECONDS: i64 = 2;
+/// The most tasks `tick` keeps spawned at once, so that a session left running doesn't pile
+/// them up forever. Past this, spawning a task finishes the oldest one still there.
+const SPAWNED_TASK_LIMIT: usize = 1000;
+/// How finely the snapshot's age is kept between updates through the link, in milliseconds.
+/// The performance pane shows the age, so keeping it any finer would redraw on every tick.
+const SNAPSHOT_AGE_STEP_MILLIS: u64 = 100;
+
+/// What a frame drawn now would show, as far as the app can tell: frames drawn at the same
+/// generation look alike.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Generation {
+    snapshot: u64,
+    state: u64,
+    /// The clock only shows whole seconds.
+    second: i64,
+    /// Toasts go away on their own, in between seconds.
+    toast_shown: bool,
+}
+
+/// What the main loop should do in response to an event, beyond redrawing.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Command {
+    Quit,
+    Suspend,
+    /// Put this text on the system clipboard.
+    Copy(String),
+    /// Write the performance history to a file, with `App::export`.
+    Export(ExportFormat),
+    /// Keep the bookmarks for next time, with `App::save_bookmarks`.
+    SaveBookmarks,
+}
+
+impl App {
+    /// Starts with a notification for the error, if there is one, so that it can be looked
+    /// back on after retrying.
+    pub fn new(clock: Box<dyn Clock>, snapshot: Snapshot, error: Option<ConsoleError>) -> App {
+        let mut app = App {
+            clock,
+            error,
+            state: AppState::new(snapshot),
+            demo: None,
+            link: None,
+            theme_preview_interval: None,
+            last_update: None,
+            next_theme_at: None,
+            next_spawn_at: None,
+            next_sample_at: None,
+            spawned_task_ids: VecDeque::new(),
+        };
+        if let Some(ref error) = app.error {
+            let message = error.to_string();
+            app.notify(NotificationKind::Connection, message);
+        }
+        app
+    }
+
+    // Notifies as of the clock's time, which the error screen doesn't keep the state up to.
+    fn notify(&mut self, kind: NotificationKind, message: String) {
+        TitleBar.update(&mut self.state, TitleBarMessage::Tick(self.clock.now()));
+        self.state.notify(kind, message);
+    }
+
+    /// Brings the mock data up to the clock's time. Like a busy server, the mock target spawns
+    /// a new connection handler every couple of seconds, finishing the oldest once there are
+    /// `SPAWNED_TASK_LIMIT` of them, and its performance is sampled every second. In the demo,
+    /// the target moves on with each sample too.
+    ///
+    /// Over a simulated link, samples can arrive late, which the snapshot's age shows, or be
+    /// lost, and losing enough of them in a row drops the connection.
+    ///
+    /// In the theme preview, the themes take turns, on the error screen too.
+    pub fn tick(&mut self) {
+        let now = self.clock.now();
+        if let Some(interval) = self.theme_preview_interval {
+            let interval = chrono::Duration::from_std(interval).unwrap();
+            if due(&mut self.next_theme_at, now, interval) {
+                self.show_next_theme();
+            }
+        }
+        // Nothing arrives from a target the console isn't connected to.
+        if self.error.is_some() {
+            return;
+        }
+        // The snapshot the console started with counts as the first update.
+        if self.link.is_some() && self.last_update.is_none() {
+            self.last_update = Some((now, self.state.snapshot.age));
+        }
+        if due(
+            &mut self.next_spawn_at,
+            now,
+            chrono::Duration::seconds(TASK_SPAWN_INTERVAL_SECONDS),
+        ) {
+            let index = self.state.snapshot.tasks.len() as u32;
+            self.state.spawn_task(fixtures::spawned_task(index));
+            self.spawned_task_ids.extend(self.state.newest_task_id());
+            if self.spawned_task_ids.len() > SPAWNED_TASK_LIMIT {
+                if let Some(task_id) = self.spawned_task_ids.pop_front() {
+                    self.state.complete_task(task_id);
+                }
+            }
+        }
+        let sample_interval = chrono::Duration::from_std(PERFORMANCE_SAMPLE_INTERVAL).unwrap();
+        if due(&mut self.next_sample_at, now, sample_interval) {
+            match self.link.as_mut().map(SimulatedLink::send) {
+                None => self.receive_sample(),
+                Some(Some(delay)) => {
+                    self.receive_sample();
+                    self.last_update = Some((now, delay));
+                }
+                Some(None) if self.link.as_mut().is_some_and(SimulatedLink::take_timeout) => {
+                    let target = self.state.snapshot.target.clone();
+                    let error = ConsoleError::ConnectionLost { target };
+                    self.notify(NotificationKind::Connection, error.to_string());
+                    self.error = Some(error);
+                    self.state.changed();
+                }
+                Some(None) => {}
+            }
+        }
+        // Like the clock, the age only moves on, and calls for a redraw, once it shows a
+        // different value.
+        if let Some((updated_at, delay)) = self.last_update {
+            let since = (now - updated_at).to_std().unwrap_or_default();
+            let age_millis = (delay + since).as_millis() as u64;
+            let age = Duration::from_millis(age_millis - age_millis % SNAPSHOT_AGE_STEP_MILLIS);
+            let snapshot = &mut self.state.snapshot;
+            if snapshot.age != age {
+                snapshot.age = age;
+                snapshot.changed();
+            }
+        }
+    }
+
+    /// Where the snapshot, the UI state, and the clock are up to. Drawing again is only worth
+    /// it once this changes.
+    pub fn generation(&self) -> Generation {
+        let now = self.clock.now();
+        let toast_shown = self
+            .state
+            .toast
+            .as_ref()
+            .is_some_and(|toast| toast.expires_at.is_none_or(|expires_at| now < expires_at));
+        Generation {
+            snapshot: self.state.snapshot.generation,
+            state: self.state.generation,
+            second: now.timestamp(),
+            toast_shown,
+        }
+    }
+
+    // Switches to the next theme, and says which it is.
+    fn show_next_theme(&mut self) {
+        self.state.theme = theme::next_theme(self.state.theme);
+        self.state.changed();
+        let message = format!(
+            "{} {} {}",
+            THEME_PREVIEW_LABEL, self.state.theme.name, THEME_PREVIEW_KEYS_LABEL
+        );
+        self.state.show_toast(message);
+    }
+
+    // Takes the performance sample that's due, from the demo if there is one.
+    fn receive_sample(&mut self) {
+        let index = self.state.snapshot.performance.history.len() as u32;
+        let sample = match self.demo {
+            Some(ref mut demo) => demo.advance(&mut self.state),
+            None => fixtures::performance_sample(index),
+        };
+        self.state.record_performance(sample);
+        for runtime in 0..self.state.snapshot.runtimes.len() {
+            self.state.record_runtime_performance(
+                runtime,
+                fixtures::runtime_performance_sample(runtime, index),
+            );
+        }
+    }
+
+    /// Writes the performance history the performance pane shows to a new file in `dir`, and
+    /// notifies the user of where, or what went wrong.
+    pub fn export(&mut self, format: ExportFormat, dir: &Path) {
+        let state = &self.state;
+        let runtime = state
+            .performance_runtime
+            .and_then(|runtime| state.snapshot.runtimes.get(runtime))
+            .map(|runtime| &runtime.name[..]);
+        let newest_at = self.clock.now() - chrono::Duration::from_std(state.snapshot.age).unwrap();
+        let performance = state.performance();
+        let message = match export::export(dir, format, performance, runtime, newest_at) {
+            Ok(path) => format!(
+                "{} {} {} {}",
+                EXPORTED_LABEL,
+                format::count(performance.history.len(), &EXPORT_SAMPLE_COUNT_LABELS),
+                EXPORTED_TO_LABEL,
+                path.display()
+            ),
+            Err(error) => format!("{} {}", EXPORT_FAILED_LABEL, error),
+        };
+        self.notify(NotificationKind::Export, message);
+    }
+
+    /// Handles an event the way the DOM does: the app's global bindings capture it first, then
+    /// the components pass it down to the widget it's aimed at and back up, and whatever's
+    /// left over bubbles up to the app's remaining bindings. The bindings are in `keymap`.
+    ///
+    /// Events that something handles change the UI state's generation, and the rest leave it
+    /// alone.
+    pub fn handle_event(&mut self, event: Event) -> Option<Command> {
+        let command = self.route_event(event);
+        if command.is_some() {
+            self.state.changed();
+        }
+        command.flatten()
+    }
+
+    // Handles an event as `handle_event` describes, returning `None` if nothing took it.
+    fn route_event(&mut self, event: Event) -> Option<Option<Command>> {
+        let performance_screen_open = self.state.performance_screen_open;
+
+        // Capture: keys that work no matter what has the focus.
+        if let Event::Key(ref key) = event {
+            // The knobs panel is for whoever's reviewing the mockups rather than part of the
+            // design, so its key is left out of the keymap, and of the help that lists it.
+            if key.code == KeyCode::F(12) && self.error.is_none() {
+                Knobs.update(&mut self.state, KnobsMessage::Toggle);
+                return Some(None);
+            }
+            // So is the frame time readout, which is for working on the renderer.
+            #[cfg(feature = "profiling")]
+            if key.code == KeyCode::F(9) {
+                self.state.frame_times_shown = !self.state.frame_times_shown;
+                return Some(None);
+            }
+            if let Some(action) = keymap::action_for(key, KeyScope::Always, performance_screen_open)
+            {
+                return Some(self.perform(action));
+            }
+        }
+
+        if self.error.is_none()
+            && components::dispatch(&mut self.state, &event) == Propagation::Stop
+        {
+            return Some(self.take_request());
+        }
+
+        // Bubble.
+        let key = match event {
+            Event::Key(key) => key,
+            // Everything moves to fit the new size.
+            Event::Resize(..) => return Some(None),
+            _ => return None,
+        };
+        // The next theme is shown for a whole interval.
+        if self.theme_preview_interval.is_some() && key.code == KeyCode::Char('t') {
+            self.show_next_theme();
+            self.next_theme_at = None;
+            return Some(None);
+        }
+        if self.error.is_some() {
+            // The error screen lists its own keys.
+            return match key.code {
+                KeyCode::Char('r') => {
+                    self.error = None;
+                    let message = format!("{} {}", CONNECTED_TO_LABEL, self.state.snapshot.target);
+                    self.notify(NotificationKind::Connection, message);
+                    Some(None)
+                }
+                KeyCode::Char('q') | KeyCode::Esc => Some(Some(Command::Quit)),
+                _ => None,
+            };
+        }
+        let action = keymap::action_for(&key, KeyScope::Main, performance_screen_open)?;
+        Some(self.perform(action))
+    }
+
+    // Takes a bound action, leaving what the main loop has to do to it.
+    fn perform(&mut self, action: Action) -> Option<Command> {
+        match action {
+            Action::Quit => return Some(Command::Quit),
+            Action::Suspend => return Some(Command::Suspend),
+            Action::Export(format) => return Some(Command::Export(format)),
+            Action::Back if self.state.focused.is_some() => self.state.focused = None,
+            Action::Back if self.state.performance_screen_open => {
+                PerformanceScreen.update(&mut self.state, PerformanceScreenMessage::Close)
+            }
+            Action::Back => return Some(Command::Quit),
+            action => {
+                components::perform_action(&mut self.state, action);
+                return self.take_request();
+            }
+        }
+        None
+    }
+
+    /// Reads the target's bookmarks from the config directory `dir`, in place of any there
+    /// were, and puts up a toast if they can't be read.
+    pub fn load_bookmarks(&mut self, dir: &Path) {
+        match config::load_bookmarks(dir, &self.state.snapshot.target) {
+            Ok(task_ids) => self.state.bookmarked_task_ids = task_ids,
+            Err(error) => self
+                .state
+                .show_toast(format!("{} {}", BOOKMARKS_LOAD_FAILED_LABEL, error)),
+        }
+    }
+
+    /// Writes the target's bookmarks to the config directory `dir`, and puts up a toast if they
+    /// can't be written.
+    pub fn save_bookmarks(&mut self, dir: &Path) {
+        let state = &self.state;
+        if let Err(error) =
+            config::save_bookmarks(dir, &state.snapshot.target, &state.bookmarked_task_ids)
+        {
+            self.state
+                .show_toast(format!("{} {}", BOOKMARKS_SAVE_FAILED_LABEL, error));
+        }
+    }
+
+    /// Puts the state back the way the last session with the target left it, reading the
+    /// session from the config directory `dir`, and puts up a toast if it can't be read.
+    pub fn load_session(&mut self, dir: &Path) {
+        let target = self.state.snapshot.target.clone();
+        if let Err(error) = config::load_session(dir, &target, &mut self.state) {
+            self.state
+                .show_toast(format!("{} {}", SESSION_LOAD_FAILED_LABEL, error));
+        }
+    }
+
+    /// Keeps the state for the next session with the target, in the config directory `dir`.
+    /// This is done as the app quits, so errors are left to the caller to report.
+    pub fn save_session(&self, dir: &Path) -> io::Result<()> {
+        config::save_session(dir, &self.state.snapshot.target, &self.state)
+    }
+
+    // Hands over what the components asked the main loop to do, if anything.
+    fn take_request(&mut self) -> Option<Command> {
+        if mem::take(&mut self.state.bookmarks_changed) {
+            return Some(Command::SaveBookmarks);
+        }
+        if let Some(format) = self.state.export_request.take() {
+            return Some(Command::Export(format));
+        }
+        self.state.clipboard_request.take().map(Command::Copy)
+    }
+
+    pub fn draw<B>(&mut self, frame: &mut Frame<B>, glyph_set: &GlyphSet)
+    where
+        B: Backend,
+    {
+        match self.error {
+            Some(ref error) => draw_error_screen(frame, glyph_set, self.state.theme, error),
+            None => draw_frame(frame, &*self.clock, glyph_set, &mut self.state),
+        }
+    }
+}
+
+// Whether something that happens every `interval` is due at `now`, scheduling the next time if
+// so. The first call only schedules it.
+fn due(
+    next_at: &mut Option<DateTime<Local>>,
+    now: DateTime<Local>,
+    interval: chrono::Duration,
+) -> bool {
+    if next_at.is_some_and(|next_at| now < next_at) {
+        return false;
+    }
+    let is_due = next_at.is_some();
+    *next_at = Some(now + interval);
+    is_due
+}
@@ -0,0 +1,297 @@
+use std::io;
+use tui::backend::Backend;
+use tui::buffer::Cell;
+use tui::layout::Rect;
+use tui::style::Color;
+
+// The 16 ANSI colors, with the RGB values of Windows' default "Campbell" console scheme. Colors
+// the terminal can't display are replaced with the closest of these.
+static ANSI_16_PALETTE: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0x0c, 0x0c, 0x0c)),
+    (Color::Red, (0xc5, 0x0f, 0x1f)),
+    (Color::Green, (0x13, 0xa1, 0x0e)),
+    (Color::Yellow, (0xc1, 0x9c, 0x00)),
+    (Color::Blue, (0x00, 0x37, 0xda)),
+    (Color::Magenta, (0x88, 0x17, 0x98)),
+    (Color::Cyan, (0x3a, 0x96, 0xdd)),
+    (Color::Gray, (0xcc, 0xcc, 0xcc)),
+    (Color::DarkGray, (0x76, 0x76, 0x76)),
+    (Color::LightRed, (0xe7, 0x48, 0x56)),
+    (Color::LightGreen, (0x16, 0xc6, 0x0c)),
+    (Color::LightYellow, (0xf9, 0xf1, 0xa5)),
+    (Color::LightBlue, (0x3b, 0x78, 0xff)),
+    (Color::LightMagenta, (0xb4, 0x00, 0x9e)),
+    (Color::LightCyan, (0x61, 0xd6, 0xd6)),
+    (Color::White, (0xf2, 0xf2, 0xf2)),
+];
+static XTERM_COLOR_CUBE_LEVELS: [u8; 6] = [0x00, 0x5f, 0x87, 0xaf, 0xd7, 0xff];
+
+/// How many colors the terminal can display.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ColorDepth {
+    /// 24-bit RGB colors.
+    TrueColor,
+    /// The xterm 256-color palette.
+    Ansi256,
+    /// Only the 16 ANSI colors, as in the legacy Windows console.
+    Ansi16,
+}
+
+/// A backend that converts colors the terminal can't display to the nearest ones it can before
+/// passing cells on to the real backend. With `ColorDepth::TrueColor` cells pass through as-is.
+///
+/// The conversion doesn't depend on the platform, so it can be exercised against a `TestBackend`
+/// anywhere.
+pub struct ColorDepthBackend<B> {
+    backend: B,
+    color_depth: ColorDepth,
+}
+
+impl<B> ColorDepthBackend<B>
+where
+    B: Backend,
+{
+    pub fn new(backend: B, color_depth: ColorDepth) -> ColorDepthBackend<B> {
+        ColorDepthBackend {
+            backend,
+            color_depth,
+        }
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+}
+
+impl<B> Backend for ColorDepthBackend<B>
+where
+    B: Backend,
+{
+    fn draw<'a, I>(&mut self, content: I) -> Result<(), io::Error>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        if self.color_depth == ColorDepth::TrueColor {
+            return self.backend.draw(content);
+        }
+
+        let color_depth = self.color_depth;
+        let cells: Vec<(u16, u16, Cell)> = content
+            .map(|(x, y, cell)| {
+                let mut cell = cell.clone();
+                cell.fg = reduce_color(cell.fg, color_depth);
+                cell.bg = reduce_color(cell.bg, color_depth);
+                (x, y, cell)
+            })
+            .collect();
+        self.backend
+            .draw(cells.iter().map(|(x, y, cell)| (*x, *y, cell)))
+    }
+
+    fn hide_cursor(&mut self) -> Result<(), io::Error> {
+        self.backend.hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> Result<(), io::Error> {
+        self.backend.show_cursor()
+    }
+
+    fn get_cursor(&mut self) -> Result<(u16, u16), io::Error> {
+        self.backend.get_cursor()
+    }
+
+    fn set_cursor(&mut self, x: u16, y: u16) -> Result<(), io::Error> {
+        self.backend.set_cursor(x, y)
+    }
+
+    fn clear(&mut self) -> Result<(), io::Error> {
+        self.backend.clear()
+    }
+
+    fn size(&self) -> Result<Rect, io::Error> {
+        self.backend.size()
+    }
+
+    fn flush(&mut self) -> Result<(), io::Error> {
+        self.backend.flush()
+    }
+}
+
+/// Returns the closest color to `color` that a terminal with `color_depth` can display.
+pub fn reduce_color(color: Color, color_depth: ColorDepth) -> Color {
+    match (color_depth, color) {
+        (ColorDepth::TrueColor, _) => color,
+        (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => nearest_xterm_256_color((r, g, b)),
+        (ColorDepth::Ansi256, _) => color,
+        (ColorDepth::Ansi16, Color::Rgb(r, g, b)) => nearest_ansi_16_color((r, g, b)),
+        (ColorDepth::Ansi16, Color::Indexed(index)) if index < 16 => {
+            ANSI_16_PALETTE[index as usize].0
+        }
+        (ColorDepth::Ansi16, Color::Indexed(index)) => {
+            nearest_ansi_16_color(xterm_indexed_color_to_rgb(index))
+        }
+        (ColorDepth::Ansi16, _) => color,
+    }
+}
+
+/// The RGB value `color` is displayed in, taking the named colors as the Campbell scheme has
+/// them, or `None` for `Color::Reset`, which is whatever the terminal's default is.
+pub fn color_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Reset => None,
+        Color::Rgb(r, g, b) => Some((r, g, b)),
+        Color::Indexed(index) if index < 16 => Some(ANSI_16_PALETTE[index as usize].1),
+        Color::Indexed(index) => Some(xterm_indexed_color_to_rgb(index)),
+        _ => ANSI_16_PALETTE
+            .iter()
+            .find(|&&(named, _)| named == color)
+            .map(|&(_, rgb)| rgb),
+    }
+}
+
+fn nearest_ansi_16_color((r, g, b): (u8, u8, u8)) -> Color {
+    let distance = |&(_, (pr, pg, pb)): &(Color, (u8, u8, u8))| {
+        let (dr, dg, db) = (
+            r as i32 - pr as i32,
+            g as i32 - pg as i32,
+            b as i32 - pb as i32,
+        );
+        dr * dr + dg * dg + db * db
+    };
+    ANSI_16_PALETTE
+        .iter()
+        .min_by_key(|entry| distance(entry))
+        .unwrap()
+        .0
+}
+
+// Picks the closer of the nearest color cube entry and the nearest grayscale ramp entry. The
+// first 16 indices are left out, since terminals tend to redefine them.
+fn nearest_xterm_256_color((r, g, b): (u8, u8, u8)) -> Color {
+    let nearest_cube_level = |component: u8| {
+        (0..XTERM_COLOR_CUBE_LEVELS.len())
+            .min_by_key(|&level| (XTERM_COLOR_CUBE_LEVELS[level] as i32 - component as i32).abs())
+            .unwrap() as u8
+    };
+    let cube_index =
+        16 + 36 * nearest_cube_level(r) + 6 * nearest_cube_level(g) + nearest_cube_level(b);
+
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_index = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+
+    let distance = |index: u8| {
+        let (pr, pg, pb) = xterm_indexed_color_to_rgb(index);
+        let (dr, dg, db) = (
+            r as i32 - pr as i32,
+            g as i32 - pg as i32,
+            b as i32 - pb as i32,
+        );
+        dr * dr + dg * dg + db * db
+    };
+    if distance(gray_index) < distance(cube_index) {
+        Color::Indexed(gray_index)
+    } else {
+        Color::Indexed(cube_index)
+    }
+}
+
+// Indices 16-231 are a 6x6x6 color cube and 232-255 a grayscale ramp.
+fn xterm_indexed_color_to_rgb(index: u8) -> (u8, u8, u8) {
+    if index >= 232 {
+        let level = 8 + (index - 232) * 10;
+        return (level, level, level);
+    }
+    let cube_index = (index - 16) as usize;
+    (
+        XTERM_COLOR_CUBE_LEVELS[cube_index / 36],
+        XTERM_COLOR_CUBE_LEVELS[cube_index / 6 % 6],
+        XTERM_COLOR_CUBE_LEVELS[cube_index % 6],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tui::backend::TestBackend;
+    use tui::style::Style;
+    use tui::widgets::Paragraph;
+    use tui::Terminal;
+
+    #[test]
+    fn true_color_passes_colors_through() {
+        let color = Color::Rgb(0x2e, 0x34, 0x40);
+        assert_eq!(reduce_color(color, ColorDepth::TrueColor), color);
+    }
+
+    #[test]
+    fn ansi_16_maps_to_nearest_named_color() {
+        assert_eq!(
+            reduce_color(Color::Rgb(0x88, 0xc0, 0xd0), ColorDepth::Ansi16),
+            Color::LightCyan
+        );
+        assert_eq!(
+            reduce_color(Color::Rgb(0x3b, 0x42, 0x52), ColorDepth::Ansi16),
+            Color::DarkGray
+        );
+        assert_eq!(
+            reduce_color(Color::Indexed(9), ColorDepth::Ansi16),
+            Color::LightRed
+        );
+        assert_eq!(
+            reduce_color(Color::Indexed(231), ColorDepth::Ansi16),
+            Color::White
+        );
+        assert_eq!(reduce_color(Color::Green, ColorDepth::Ansi16), Color::Green);
+        assert_eq!(reduce_color(Color::Reset, ColorDepth::Ansi16), Color::Reset);
+    }
+
+    #[test]
+    fn ansi_256_maps_to_nearest_palette_entry() {
+        assert_eq!(
+            reduce_color(Color::Rgb(0xff, 0x00, 0x00), ColorDepth::Ansi256),
+            Color::Indexed(196)
+        );
+        assert_eq!(
+            reduce_color(Color::Rgb(0x2e, 0x34, 0x40), ColorDepth::Ansi256),
+            Color::Indexed(237)
+        );
+        assert_eq!(
+            reduce_color(Color::Indexed(9), ColorDepth::Ansi256),
+            Color::Indexed(9)
+        );
+        assert_eq!(reduce_color(Color::Red, ColorDepth::Ansi256), Color::Red);
+    }
+
+    #[test]
+    fn colors_have_rgb_values_except_the_default() {
+        assert_eq!(
+            color_rgb(Color::Rgb(0x2e, 0x34, 0x40)),
+            Some((0x2e, 0x34, 0x40))
+        );
+        assert_eq!(color_rgb(Color::LightRed), Some((0xe7, 0x48, 0x56)));
+        assert_eq!(color_rgb(Color::Indexed(9)), Some((0xe7, 0x48, 0x56)));
+        assert_eq!(color_rgb(Color::Indexed(196)), Some((0xff, 0x00, 0x00)));
+        assert_eq!(color_rgb(Color::Indexed(232)), Some((0x08, 0x08, 0x08)));
+        assert_eq!(color_rgb(Color::Reset), None);
+    }
+
+    #[test]
+    fn backend_reduces_drawn_cells() {
+        let backend = ColorDepthBackend::new(TestBackend::new(4, 1), ColorDepth::Ansi16);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|frame| {
+                let style = Style::default()
+                    .fg(Color::Rgb(0xe5, 0xe9, 0xf0))
+                    .bg(Color::Rgb(0xc5, 0x10, 0x20));
+                frame.render_widget(Paragraph::new("ab").style(style), frame.size());
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().backend().buffer();
+        assert_eq!(buffer.get(0, 0).symbol, "a");
+        assert_eq!(buffer.get(0, 0).fg, Color::White);
+        assert_eq!(buffer.get(0, 0).bg, Color::Red);
+        assert_eq!(buffer.get(3, 0).bg, Color::Red);
+    }
+}
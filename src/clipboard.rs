@@ -0,0 +1,153 @@
+//! Putting text on the system clipboard.
+//!
+//! The text is sent to the terminal in an OSC 52 escape sequence, which asks the terminal to
+//! copy it. That works over SSH and inside tmux, with no access to the local clipboard needed,
+//! but only in terminals that support the sequence; the others ignore it.
+//!
+//! There's no asking the terminal whether it supports the sequence, so in local sessions the
+//! text is also handed to the platform's clipboard tool, such as `pbcopy` or `xclip`. Over SSH
+//! that would fill the remote machine's clipboard rather than the user's, so it's left to the
+//! terminal.
+
+use std::env;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+static BASE64_ALPHABET: &'static [u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+// Set by the SSH server in the sessions it starts.
+static SSH_VARS: [&'static str; 2] = ["SSH_CONNECTION", "SSH_TTY"];
+#[cfg_attr(any(windows, target_os = "macos"), allow(dead_code))]
+static WAYLAND_DISPLAY_VAR: &'static str = "WAYLAND_DISPLAY";
+#[cfg_attr(any(windows, target_os = "macos"), allow(dead_code))]
+static X_DISPLAY_VAR: &'static str = "DISPLAY";
+// Each is a program and its arguments, which reads the text to copy from standard input.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+static PBCOPY_COMMAND: &'static [&'static str] = &["pbcopy"];
+#[cfg_attr(not(windows), allow(dead_code))]
+static CLIP_COMMAND: &'static [&'static str] = &["clip"];
+#[cfg_attr(any(windows, target_os = "macos"), allow(dead_code))]
+static WL_COPY_COMMAND: &'static [&'static str] = &["wl-copy"];
+#[cfg_attr(any(windows, target_os = "macos"), allow(dead_code))]
+static XCLIP_COMMAND: &'static [&'static str] = &["xclip", "-selection", "clipboard"];
+#[cfg_attr(any(windows, target_os = "macos"), allow(dead_code))]
+static XSEL_COMMAND: &'static [&'static str] = &["xsel", "--clipboard", "--input"];
+
+/// Asks the terminal on the other end of `out` to put `text` on the clipboard.
+pub fn copy<W>(out: &mut W, text: &str) -> io::Result<()>
+where
+    W: Write,
+{
+    write!(out, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    out.flush()
+}
+
+/// Puts `text` on the clipboard with the first of the platform's clipboard tools that's
+/// installed, unless the session is over SSH. Returns whether one of them took it.
+pub fn copy_locally(text: &str) -> bool {
+    local_clipboard_commands(|name| env::var_os(name).is_some())
+        .iter()
+        .any(|command| run_clipboard_command(command, text).is_ok())
+}
+
+// The clipboard tools to try, in order. On Linux and the BSDs that depends on which display
+// server is running, if any.
+fn local_clipboard_commands<F>(is_set: F) -> Vec<&'static [&'static str]>
+where
+    F: Fn(&str) -> bool,
+{
+    if SSH_VARS.iter().any(|&name| is_set(name)) {
+        return vec![];
+    }
+    if cfg!(target_os = "macos") {
+        return vec![PBCOPY_COMMAND];
+    }
+    if cfg!(windows) {
+        return vec![CLIP_COMMAND];
+    }
+    let mut commands = vec![];
+    if is_set(WAYLAND_DISPLAY_VAR) {
+        commands.push(WL_COPY_COMMAND);
+    }
+    if is_set(X_DISPLAY_VAR) {
+        commands.push(XCLIP_COMMAND);
+        commands.push(XSEL_COMMAND);
+    }
+    commands
+}
+
+// Fails if the tool isn't installed or doesn't exit successfully. Its output would land on the
+// dashboard, so it's thrown away.
+fn run_clipboard_command(command: &[&str], text: &str) -> io::Result<()> {
+    let mut child = Command::new(command[0])
+        .args(&command[1..])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    // The tool reads until its standard input closes, which happens as the handle drops.
+    child
+        .stdin
+        .take()
+        .expect("standard input is piped")
+        .write_all(text.as_bytes())?;
+    if child.wait()?.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("{} failed", command[0])))
+    }
+}
+
+// Encodes `bytes` as padded base64, which is what OSC 52 expects.
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (index, &byte)| {
+                group | (byte as u32) << (16 - 8 * index)
+            });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (group >> (18 - 6 * index)) & 0x3f;
+                encoded.push(BASE64_ALPHABET[sextet as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copying_sends_the_text_base64_encoded() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+
+        let mut out = vec![];
+        copy(&mut out, "remote=127.0.0.1").unwrap();
+        assert_eq!(out, b"\x1b]52;c;cmVtb3RlPTEyNy4wLjAuMQ==\x07");
+    }
+
+    #[test]
+    fn ssh_sessions_leave_the_clipboard_to_the_terminal() {
+        let is_set = |vars: &'static [&'static str]| move |name: &str| vars.contains(&name);
+        assert!(local_clipboard_commands(is_set(&["SSH_TTY", "DISPLAY"])).is_empty());
+        if cfg!(any(windows, target_os = "macos")) {
+            assert_eq!(local_clipboard_commands(is_set(&[])).len(), 1);
+        } else {
+            assert!(local_clipboard_commands(is_set(&[])).is_empty());
+            assert_eq!(
+                local_clipboard_commands(is_set(&["WAYLAND_DISPLAY", "DISPLAY"])),
+                [WL_COPY_COMMAND, XCLIP_COMMAND, XSEL_COMMAND]
+            );
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Where the UI gets the current time from.
+//!
+//! Drawing code asks a `Clock` instead of calling `Local::now()` itself, so tests, benchmarks,
+//! and screenshots can pin the time and get the same output on every run.
+
+use chrono::{DateTime, Local};
+use std::time::Duration;
+
+pub trait Clock {
+    fn now(&self) -> DateTime<Local>;
+
+    /// How long until the seconds shown in the title bar next change.
+    fn time_until_next_second(&self) -> Duration {
+        let elapsed_millis = self.now().timestamp_subsec_millis().min(999);
+        Duration::from_millis((1000 - elapsed_millis) as u64)
+    }
+}
+
+/// The system's wall clock, in the local time zone.
+#[derive(Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// A clock that's stopped at one instant.
+#[derive(Clone, Copy)]
+pub struct FixedClock {
+    now: DateTime<Local>,
+}
+
+impl FixedClock {
+    pub fn new(now: DateTime<Local>) -> FixedClock {
+        FixedClock { now }
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Local> {
+        self.now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn fixed_clock_waits_for_the_next_second() {
+        let clock = FixedClock::new(Local.ymd(2021, 3, 4).and_hms_milli(13, 37, 0, 250));
+        assert_eq!(clock.now(), clock.now());
+        assert_eq!(clock.time_until_next_second(), Duration::from_millis(750));
+    }
+}
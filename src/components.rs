@@ -0,0 +1,5310 @@
+//! The parts of the main screen, each drawing its own slice of the layout.
+//!
+//! Components keep no state of their own: what they show, and everything input can change, lives
+//! in the `AppState`, which only changes in response to messages passed to a component's
+//! `update`. A component draws itself in two steps: `layout` adds its nodes to the frame's stretch
+//! tree, and once the whole tree has been laid out, `view` draws its widgets into the nodes it
+//! added. Input handling in `App` translates events into messages for the component they
+//! concern, so `draw_frame` only has to put the components together.
+//!
+//! Input events travel along a path from the app down to the widget they're aimed at and back
+//! up: keys are aimed at the focused widget and clicks at the widget under the pointer. The app
+//! and each component on the way see the event first while it's being captured, the component
+//! then handles it on behalf of the target widget, and finally everything sees it again as it
+//! bubbles back up, until something consumes it. A widget that takes text can consume keys at
+//! the target, before the app's own bindings see them as it bubbles up.
+
+use crate::fixtures::Knob;
+use crate::format::{self, Quantity, UnitStyle};
+use crate::glyphs::GlyphSet;
+use crate::history;
+use crate::hyperlink::{attribute_link, Hyperlink};
+use crate::keymap::{KeyCategory, KEY_BINDINGS};
+use crate::profiling;
+use crate::state::{
+    tasks_table_rows, visible_tasks, wait_for_graph, Action, AppState, CachedLabel, HistoryView,
+    NotificationKind, Overlay, TaskAction, TaskSortKey, TasksSort, TasksTab, TasksTableRow,
+    TimeWindow, Toast, ViewMode, WaitForNode, PERFORMANCE_SAMPLE_INTERVAL,
+};
+use crate::strings::{self, Strings};
+use crate::theme::{Theme, THEMES};
+use crate::{
+    create_task_group_table_cells, create_task_table_cells, fit_task_table_cells, pane_box_frame,
+    select_table_columns, stripe_task_table_row, task_table_row_text, title_bar_powerline,
+    BusyPeriod, SpawnSite, Task, ACTION_BACK_LABELS, ACTION_COPY_SELECTED_TASK_LABEL,
+    ACTION_CYCLE_TIME_WINDOW_LABEL, ACTION_EXPORT_LABELS, ACTION_OPEN_COLUMN_SETTINGS_LABEL,
+    ACTION_OPEN_COMMAND_PALETTE_LABEL, ACTION_OPEN_HELP_LABEL,
+    ACTION_OPEN_PERFORMANCE_SETTINGS_LABEL, ACTION_OPEN_SELECTED_TASK_ACTIONS_LABEL,
+    ACTION_OPEN_SELECTED_TASK_ATTRIBUTES_LABEL, ACTION_OPEN_TASK_LABEL, ACTION_PAN_HISTORY_LABELS,
+    ACTION_QUIT_LABEL, ACTION_RESET_HISTORY_VIEW_LABEL, ACTION_SCROLL_COLUMNS_LABELS,
+    ACTION_SCROLL_ROWS_LABELS, ACTION_SELECT_TASKS_TAB_LABELS, ACTION_SELECT_THEME_LABEL,
+    ACTION_SELECT_VIEW_MODE_LABELS, ACTION_SELECT_WORKSPACE_LABELS, ACTION_STEP_FOCUS_LABELS,
+    ACTION_SUSPEND_LABEL, ACTION_TOGGLE_BOOKMARK_LABELS, ACTION_TOGGLE_COMPARISON_LABELS,
+    ACTION_TOGGLE_FOLLOW_LABELS, ACTION_TOGGLE_GROUPING_LABELS, ACTION_TOGGLE_NAME_FILTER_LABELS,
+    ACTION_TOGGLE_NOTIFICATIONS_LABELS, ACTION_TOGGLE_PERFORMANCE_SCREEN_LABELS,
+    ACTION_TOGGLE_PRESENTATION_LABELS, ACTION_UNDO_LABEL, ACTION_ZOOM_HISTORY_LABELS,
+    ACTION_ZOOM_TIMELINE_LABELS, AUTO_SIZE, BOOKMARK_TOGGLED_LABELS, COLUMN_SETTINGS_KEYS_LABEL,
+    COLUMN_SETTINGS_LABEL, COMMAND_PALETTE_EMPTY_LABEL, COMMAND_PALETTE_KEYS_LABEL,
+    COMMAND_PALETTE_LABEL, COMMAND_PALETTE_PLACEHOLDER_LABEL, COMMAND_PALETTE_ROW_COUNT,
+    COMMAND_PALETTE_WIDTH, CONFIRM_ABORT_KEYS_LABEL, CONFIRM_ABORT_LABEL,
+    CONFIRM_ABORT_MESSAGE_LABEL, CONFIRM_DIALOG_WIDTH, COPIED_ATTRIBUTES_LABEL, COPIED_LABEL,
+    COPIED_ROW_LABEL, HELP_CATEGORY_LABELS, HELP_KEYS_LABEL, HELP_LABEL, HELP_POPUP_WIDTH,
+    KNOBS_KEYS_LABEL, KNOBS_LABEL, KNOB_LABELS, KNOB_LARGE_STEP, KNOB_SWITCH_LABELS,
+    KNOB_TURN_LABELS, NOTIFICATIONS_EMPTY_LABEL, NOTIFICATIONS_KEYS_LABEL, NOTIFICATIONS_LABEL,
+    NOTIFICATIONS_PANE_HEIGHT, NOTIFICATION_COUNT_LABELS, NOTIFICATION_KIND_LABELS,
+    NOTIFICATION_TIME_FORMAT, PERFORMANCE_EXPANDED_GRAPH_HEIGHT, PERFORMANCE_GRAPH_AUTO_SCALE,
+    PERFORMANCE_LATENCY_CRITICAL_MS, PERFORMANCE_LATENCY_WARNING_MS, PERFORMANCE_METRICS,
+    PERFORMANCE_PERCENTILES, PERFORMANCE_PERCENTILE_LABELS, PERFORMANCE_SEGMENT_PERCENTILE_WIDTH,
+    PERFORMANCE_SEGMENT_VALUE_WIDTH, PERFORMANCE_STALE_AGE, TASKS_TABLE_COLUMNS,
+    TASKS_TABLE_FROZEN_COLUMN_COUNT, TASKS_TIMELINE_LABEL_WIDTH, TASKS_TIMELINE_PAN_STEP,
+    TASK_ACTION_DONE_LABELS, TASK_ACTION_LABELS, TASK_ACTION_MENU_LABEL,
+    TASK_ATTRIBUTES_EMPTY_LABEL, TASK_ATTRIBUTES_KEYS_LABEL, TASK_ATTRIBUTES_LABEL,
+    TASK_ATTRIBUTES_POPUP_WIDTH, TASK_SPAWN_SITE_LABEL, TIME_FORMAT,
+    TITLE_BAR_RUNTIME_COUNT_LABELS, TITLE_BAR_THREAD_COUNT_LABELS, TOAST_DURATION_SECONDS,
+    TOAST_UNDO_LABEL,
+};
+use chrono::{DateTime, Local};
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::time::Duration;
+use stretch::geometry::{Point, Rect, Size};
+use stretch::style::{AlignSelf, Dimension, FlexDirection};
+use tui::backend::Backend;
+use tui::layout::{Alignment, Constraint, Rect as TuiRect};
+use tui::style::{Modifier, Style as TuiStyle};
+use tui::symbols::Marker;
+use tui::text::{Span, Spans};
+use tui::widgets::{Axis, Chart, Clear, Dataset, GraphType, Paragraph, Row, Table, Widget, Wrap};
+use tui::Frame;
+use turbowish_widgets::layout::{
+    fit_table_column_widths, world_rect, LayoutTree, Node, RectExt, SizeExt, ToRect,
+};
+use turbowish_widgets::renderer::{place_popup, Renderer, WidgetId, WidgetRegistry};
+use turbowish_widgets::text::{
+    align_spans, display_width, fuzzy_match, truncate_spans, wrapped_height,
+};
+use turbowish_widgets::{
+    ui, BarChart, BigText, BoxFrame, ChartSymbols, CodeStyles, CodeView, GraphNode, GraphView,
+    MainVisibility, Markdown, MarkdownStyles, Measure, Powerline, PowerlineDirection, Ring,
+    Scrollbar, SegmentedControl, TextInput, TimeRuler, Timeline, TimelineRow,
+};
+
+pub static MENU_BUTTON_ID: WidgetId = WidgetId("menu-button");
+pub static NOTIFICATIONS_BUTTON_ID: WidgetId = WidgetId("notifications-button");
+pub static PERFORMANCE_EXPAND_BUTTON_ID: WidgetId = WidgetId("performance-expand-button");
+pub static PERFORMANCE_COLLAPSE_BUTTON_ID: WidgetId = WidgetId("performance-collapse-button");
+pub static PERFORMANCE_TIME_WINDOW_ID: WidgetId = WidgetId("performance-time-window");
+pub static PERFORMANCE_RUNTIME_ID: WidgetId = WidgetId("performance-runtime");
+pub static TASKS_TABS_ID: WidgetId = WidgetId("tasks-tabs");
+pub static TASKS_VIEW_MODE_ID: WidgetId = WidgetId("tasks-view-mode");
+pub static TASKS_TABLE_ID: WidgetId = WidgetId("tasks-table");
+pub static TASKS_SCROLLBAR_ID: WidgetId = WidgetId("tasks-scrollbar");
+pub static NOTIFICATIONS_LIST_ID: WidgetId = WidgetId("notifications-list");
+pub static NOTIFICATIONS_SCROLLBAR_ID: WidgetId = WidgetId("notifications-scrollbar");
+pub static TASK_ACTION_MENU_ID: WidgetId = WidgetId("task-action-menu");
+pub static CONFIRM_DIALOG_ID: WidgetId = WidgetId("confirm-dialog");
+pub static TASK_ATTRIBUTES_ID: WidgetId = WidgetId("task-attributes");
+pub static COLUMN_SETTINGS_ID: WidgetId = WidgetId("column-settings");
+pub static PERFORMANCE_SETTINGS_ID: WidgetId = WidgetId("performance-settings");
+pub static COMMAND_PALETTE_ID: WidgetId = WidgetId("command-palette");
+pub static HELP_ID: WidgetId = WidgetId("help");
+pub static KNOBS_ID: WidgetId = WidgetId("knobs");
+
+pub trait Component {
+    /// What can happen to the component.
+    type Message;
+    /// The nodes `layout` added, for `view` to draw into.
+    type Layout;
+
+    fn update(&self, state: &mut AppState, message: Self::Message);
+
+    /// Adds the component's nodes to `parent`. Some nodes' sizes depend on the glyphs that
+    /// will be drawn in them.
+    fn layout(
+        &self,
+        state: &AppState,
+        glyph_set: &GlyphSet,
+        stretch: &mut LayoutTree,
+        parent: Node,
+    ) -> Self::Layout;
+
+    /// Draws the component into the nodes `layout` added, after the layout has been computed.
+    /// This takes the state mutably because some widgets, such as scrollbars, note where they
+    /// were drawn so that later clicks can find them.
+    fn view<B>(
+        &self,
+        state: &mut AppState,
+        frame: &mut Frame<B>,
+        context: &mut ViewContext,
+        layout: &Self::Layout,
+    ) where
+        B: Backend;
+
+    /// Whether `id` names one of the component's widgets, which puts the component on the path
+    /// of events aimed at it.
+    fn contains(&self, _id: WidgetId) -> bool {
+        false
+    }
+
+    /// Offers the component an event aimed at `target`, one of its widgets. By default
+    /// components let everything through.
+    fn handle_event(
+        &self,
+        _state: &mut AppState,
+        _event: &Event,
+        _target: WidgetId,
+        _phase: Phase,
+    ) -> Propagation {
+        Propagation::Continue
+    }
+}
+
+/// Where an event is on its way to its target and back.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Phase {
+    /// On the way down, so that parents get a chance to intercept it.
+    Capture,
+    /// At the target widget.
+    Target,
+    /// On the way back up, for whatever the target didn't want.
+    Bubble,
+}
+
+/// Whether an event should go on to the next handler on its path.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Propagation {
+    /// The handler consumed the event.
+    Stop,
+    Continue,
+}
+
+/// What every component's `view` can see of the frame being drawn.
+pub struct ViewContext<'a> {
+    /// The frame's stretch tree, with its layout computed.
+    pub stretch: &'a LayoutTree,
+    pub root: Node,
+    pub glyph_set: &'a GlyphSet,
+    pub theme: &'a Theme,
+    pub focused: Option<WidgetId>,
+    /// Where the interactive widgets drawn so far in this frame went.
+    pub registry: WidgetRegistry,
+    /// The links drawn so far in this frame.
+    pub hyperlinks: Vec<Hyperlink>,
+}
+
+impl<'a> ViewContext<'a> {
+    pub fn new(
+        stretch: &'a LayoutTree,
+        root: Node,
+        glyph_set: &'a GlyphSet,
+        theme: &'a Theme,
+        focused: Option<WidgetId>,
+    ) -> ViewContext<'a> {
+        ViewContext {
+            stretch,
+            root,
+            glyph_set,
+            theme,
+            focused,
+            registry: WidgetRegistry::new(),
+            hyperlinks: vec![],
+        }
+    }
+
+    /// Names an interactive widget's node, and highlights it if it has the focus.
+    pub fn identify(&self, renderer: &mut Renderer, node: Node, id: WidgetId) {
+        renderer.set_id(node, id);
+        if self.focused == Some(id) {
+            renderer.set_bg_color(node, self.theme.focus_bg);
+        }
+    }
+
+    /// Draws `node` and its descendants, wherever the node ended up on screen, and adds the
+    /// widgets the renderer identified to the frame's registry.
+    pub fn render<B>(&mut self, frame: &mut Frame<B>, renderer: &mut Renderer, node: Node)
+    where
+        B: Backend,
+    {
+        let _scope = profiling::scope(profiling::Phase::Render);
+        let world_rect = world_rect(self.stretch, self.root, node).unwrap();
+        let local_rect = self.stretch.layout(node).to_rect();
+        let parent_world_position = Point {
+            x: world_rect.x - local_rect.x,
+            y: world_rect.y - local_rect.y,
+        };
+        renderer.render(frame, self.stretch, node, parent_world_position);
+        self.registry.extend(renderer.take_registry());
+    }
+}
+
+/// Sends an event down to the widget it's aimed at and back up again, stopping as soon as
+/// something consumes it. Returns `Propagation::Continue` if nothing did, leaving it for the
+/// app's own bindings. While a popup is open, it gets every event instead.
+pub fn dispatch(state: &mut AppState, event: &Event) -> Propagation {
+    match state.overlay {
+        Some(Overlay::TaskAttributes { .. }) => {
+            return TaskAttributes.handle_event(state, event, TASK_ATTRIBUTES_ID, Phase::Target)
+        }
+        Some(Overlay::ColumnSettings { .. }) => {
+            return ColumnSettings.handle_event(state, event, COLUMN_SETTINGS_ID, Phase::Target)
+        }
+        Some(Overlay::PerformanceSettings { .. }) => {
+            return PerformanceSettings.handle_event(
+                state,
+                event,
+                PERFORMANCE_SETTINGS_ID,
+                Phase::Target,
+            )
+        }
+        Some(Overlay::CommandPalette { .. }) => {
+            return CommandPalette.handle_event(state, event, COMMAND_PALETTE_ID, Phase::Target)
+        }
+        Some(Overlay::Help { .. }) => {
+            return Help.handle_event(state, event, HELP_ID, Phase::Target)
+        }
+        Some(Overlay::Knobs { .. }) => {
+            return Knobs.handle_event(state, event, KNOBS_ID, Phase::Target)
+        }
+        Some(_) => {
+            return TaskActions.handle_event(state, event, TASK_ACTION_MENU_ID, Phase::Target)
+        }
+        None => {}
+    }
+    let target = match *event {
+        Event::Key(_) => state.focused,
+        Event::Mouse(MouseEvent { column, row, .. }) => state.widget_registry.hit_test(column, row),
+        Event::Resize(..) => None,
+    };
+    let target = match target {
+        Some(target) => target,
+        None => return Propagation::Continue,
+    };
+    for &phase in &[Phase::Capture, Phase::Target, Phase::Bubble] {
+        if offer(state, event, target, phase) == Propagation::Stop {
+            return Propagation::Stop;
+        }
+    }
+    Propagation::Continue
+}
+
+// The components are one level deep, so the path to a target is just the component that
+// contains it.
+fn offer(state: &mut AppState, event: &Event, target: WidgetId, phase: Phase) -> Propagation {
+    if TitleBar.contains(target) {
+        TitleBar.handle_event(state, event, target, phase)
+    } else if PerformancePane.contains(target) {
+        PerformancePane.handle_event(state, event, target, phase)
+    } else if PerformanceScreen.contains(target) {
+        PerformanceScreen.handle_event(state, event, target, phase)
+    } else if TasksPane.contains(target) {
+        TasksPane.handle_event(state, event, target, phase)
+    } else if NotificationsPane.contains(target) {
+        NotificationsPane.handle_event(state, event, target, phase)
+    } else if TaskActions.contains(target) {
+        TaskActions.handle_event(state, event, target, phase)
+    } else if TaskAttributes.contains(target) {
+        TaskAttributes.handle_event(state, event, target, phase)
+    } else if ColumnSettings.contains(target) {
+        ColumnSettings.handle_event(state, event, target, phase)
+    } else if PerformanceSettings.contains(target) {
+        PerformanceSettings.handle_event(state, event, target, phase)
+    } else if CommandPalette.contains(target) {
+        CommandPalette.handle_event(state, event, target, phase)
+    } else if Help.contains(target) {
+        Help.handle_event(state, event, target, phase)
+    } else if Knobs.contains(target) {
+        Knobs.handle_event(state, event, target, phase)
+    } else {
+        Propagation::Continue
+    }
+}
+
+/// Everything the components' layouts depend on besides the size of the screen, so that the
+/// layout is only built again when one of these changes. Widths stand in for labels and
+/// controls, since their widths are all the layout takes from them.
+#[derive(Clone, Copy, PartialEq)]
+pub struct LayoutKey {
+    language: &'static str,
+    menu_powerline_width: u16,
+    unread_notification_count: usize,
+    notifications_open: bool,
+    performance_screen_open: bool,
+    performance_expanded: bool,
+    performance_runtime_width: Option<u16>,
+    performance_time_window_width: u16,
+    performance_metrics_shown: u64,
+    tasks_view_mode_width: u16,
+    tasks_table_columns_shown: u64,
+}
+
+impl LayoutKey {
+    pub fn new(state: &AppState, glyph_set: &GlyphSet) -> LayoutKey {
+        let menu_powerline_labels = [glyph_set.menu_button, state.time_label.label()];
+        let performance_runtime_width = if state.snapshot.runtimes.is_empty() {
+            None
+        } else {
+            let runtime_labels = performance_runtime_labels(state);
+            Some(
+                runtime_control(&runtime_labels, state.performance_runtime, state.theme)
+                    .preferred_width(),
+            )
+        };
+        LayoutKey {
+            language: state.strings.language,
+            menu_powerline_width: menu_powerline(&menu_powerline_labels, glyph_set, state.theme)
+                .preferred_width(),
+            unread_notification_count: state.unread_notification_count,
+            notifications_open: state.notifications_open,
+            performance_screen_open: state.performance_screen_open,
+            performance_expanded: state.performance_expanded,
+            performance_runtime_width,
+            performance_time_window_width: time_window_control(
+                state.performance_history_view.time_window,
+                state.strings,
+                state.theme,
+            )
+            .preferred_width(),
+            performance_metrics_shown: shown_mask(&state.performance_metrics_shown),
+            tasks_view_mode_width: view_mode_control(state.view_mode, glyph_set, state.theme)
+                .preferred_width(),
+            tasks_table_columns_shown: shown_mask(&state.tasks_table_columns_shown),
+        }
+    }
+}
+
+// Which of a list of things are shown, one bit each.
+fn shown_mask(shown: &[bool]) -> u64 {
+    shown
+        .iter()
+        .enumerate()
+        .filter(|&(_, &shown)| shown)
+        .fold(0, |mask, (index, _)| mask | 1 << index)
+}
+
+// Title bar
+
+pub struct TitleBar;
+
+pub enum TitleBarMessage {
+    /// The clock moved on.
+    Tick(DateTime<Local>),
+}
+
+pub struct TitleBarLayout {
+    main_powerline_node: Node,
+    /// Only there while notifications have come in with the notification center hidden.
+    notifications_button_node: Option<Node>,
+    menu_powerline_node: Node,
+}
+
+impl Component for TitleBar {
+    type Message = TitleBarMessage;
+    type Layout = TitleBarLayout;
+
+    fn update(&self, state: &mut AppState, message: TitleBarMessage) {
+        match message {
+            TitleBarMessage::Tick(now) => {
+                state.now = Some(now);
+                state.time_label.update(Some(now.timestamp()), |label| {
+                    write!(label, "{}", now.format(TIME_FORMAT)).unwrap()
+                });
+            }
+        }
+    }
+
+    fn layout(
+        &self,
+        state: &AppState,
+        glyph_set: &GlyphSet,
+        stretch: &mut LayoutTree,
+        parent: Node,
+    ) -> TitleBarLayout {
+        let menu_powerline_labels = [glyph_set.menu_button, state.time_label.label()];
+        let menu_powerline = menu_powerline(&menu_powerline_labels, glyph_set, state.theme);
+        ui!(stretch; parent => [
+            title_bar_node { size: Size::fixed_height(1) } [
+                main_powerline_node { size: AUTO_SIZE, flex_grow: 1.0 },
+            ],
+        ]);
+        let notifications_button_node = notifications_button_label(state, glyph_set).map(|label| {
+            ui!(stretch; title_bar_node => [
+                notifications_button_node {
+                    size: Size::fixed_width(display_width(&label) as u16),
+                },
+            ]);
+            notifications_button_node
+        });
+        ui!(stretch; title_bar_node => [
+            menu_powerline_node {
+                size: Size::fixed_width(menu_powerline.preferred_width()),
+            },
+        ]);
+
+        TitleBarLayout {
+            main_powerline_node,
+            notifications_button_node,
+            menu_powerline_node,
+        }
+    }
+
+    fn view<B>(
+        &self,
+        state: &mut AppState,
+        frame: &mut Frame<B>,
+        context: &mut ViewContext,
+        layout: &TitleBarLayout,
+    ) where
+        B: Backend,
+    {
+        let mut renderer = Renderer::new();
+        let snapshot = &state.snapshot;
+        let runtime_count_label = format::count(
+            snapshot.runtime_count as usize,
+            &TITLE_BAR_RUNTIME_COUNT_LABELS,
+        );
+        let thread_count_label = format::count(
+            snapshot.thread_count as usize,
+            &TITLE_BAR_THREAD_COUNT_LABELS,
+        );
+        let mut main_powerline_labels = vec![
+            context.glyph_set.title,
+            &snapshot.target[..],
+            &runtime_count_label[..],
+            &thread_count_label[..],
+        ];
+        // Which workspace this is only matters once there's more than one.
+        let workspace_label;
+        if state.workspaces.len() > 1 {
+            workspace_label = format!(
+                "{} {}/{}",
+                context.glyph_set.workspace,
+                state.active_workspace + 1,
+                state.workspaces.len()
+            );
+            main_powerline_labels.push(&workspace_label);
+        }
+        let main_powerline =
+            title_bar_powerline(&main_powerline_labels, context.glyph_set, context.theme);
+        let main_powerline_width = main_powerline.preferred_width();
+        renderer.build_node(layout.main_powerline_node, main_powerline);
+        let menu_powerline_labels = [context.glyph_set.menu_button, state.time_label.label()];
+        renderer.build_node(
+            layout.menu_powerline_node,
+            menu_powerline(&menu_powerline_labels, context.glyph_set, context.theme),
+        );
+        context.identify(&mut renderer, layout.menu_powerline_node, MENU_BUTTON_ID);
+        if let (Some(node), Some(label)) = (
+            layout.notifications_button_node,
+            notifications_button_label(state, context.glyph_set),
+        ) {
+            renderer.build_node(
+                node,
+                Paragraph::new(label).style(
+                    TuiStyle::default()
+                        .fg(context.theme.title_main_color)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            );
+            context.identify(&mut renderer, node, NOTIFICATIONS_BUTTON_ID);
+        }
+
+        context.render(frame, &mut renderer, layout.main_powerline_node);
+        // The utilization ring goes at the end of the space the powerline leaves, if it leaves
+        // enough, with a space on either side.
+        let utilization_ring = utilization_ring(state, context.glyph_set);
+        let ring_width = utilization_ring.preferred_width() + 1;
+        let main_rect =
+            world_rect(context.stretch, context.root, layout.main_powerline_node).unwrap();
+        if main_rect.width > main_powerline_width + ring_width {
+            frame.render_widget(
+                utilization_ring,
+                TuiRect::new(
+                    main_rect.right() - ring_width,
+                    main_rect.y,
+                    ring_width - 1,
+                    1,
+                ),
+            );
+        }
+        if let Some(node) = layout.notifications_button_node {
+            context.render(frame, &mut renderer, node);
+        }
+        context.render(frame, &mut renderer, layout.menu_powerline_node);
+    }
+
+    fn contains(&self, id: WidgetId) -> bool {
+        id == MENU_BUTTON_ID || id == NOTIFICATIONS_BUTTON_ID
+    }
+
+    // The notifications button opens the notification center.
+    fn handle_event(
+        &self,
+        state: &mut AppState,
+        event: &Event,
+        target: WidgetId,
+        phase: Phase,
+    ) -> Propagation {
+        if phase != Phase::Target || target != NOTIFICATIONS_BUTTON_ID {
+            return Propagation::Continue;
+        }
+        match *event {
+            Event::Key(KeyEvent {
+                code: KeyCode::Enter,
+                ..
+            })
+            | Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                ..
+            }) => {
+                NotificationsPane.update(state, NotificationsPaneMessage::Toggle);
+                Propagation::Stop
+            }
+            _ => Propagation::Continue,
+        }
+    }
+}
+
+// Counts the notifications that came in while the notification center was hidden, if any.
+fn notifications_button_label(state: &AppState, glyph_set: &GlyphSet) -> Option<String> {
+    match state.unread_notification_count {
+        0 => None,
+        count => Some(format!(" {} {} ", glyph_set.notifications_button, count)),
+    }
+}
+
+// How busy the runtimes are, as the share of the time they spent polling tasks.
+fn utilization_ring(state: &AppState, glyph_set: &GlyphSet) -> Ring {
+    let theme = state.theme;
+    let run_percent = state.snapshot.performance.current.run_percent;
+    let color = theme.tasks_table_run_percent_thresholds.color(
+        run_percent,
+        theme.performance_graph_color,
+        theme.performance_graph_warning_color,
+        theme.performance_graph_critical_color,
+    );
+    Ring::new(run_percent / 100.0, color)
+        .track_color(theme.performance_axis_color)
+        .symbols(glyph_set.ring)
+}
+
+fn menu_powerline<'a>(labels: &'a [&'a str], glyph_set: &GlyphSet, theme: &Theme) -> Powerline<'a> {
+    title_bar_powerline(labels, glyph_set, theme)
+        .direction(PowerlineDirection::RightToLeft)
+        .main_visibility(MainVisibility::Invisible)
+}
+
+// Performance pane
+
+pub struct PerformancePane;
+
+pub enum PerformancePaneMessage {
+    /// Switches from one-line graphs to taller ones with axes, and from those to the
+    /// performance screen.
+    ToggleExpanded,
+    /// Moves the expanded graphs this many bars back in time, or toward the present for
+    /// negative counts.
+    PanHistory(isize),
+    /// Shows fewer samples to a bar in the expanded graphs if true, or more if false.
+    ZoomHistory(bool),
+    /// Brings the expanded graphs back to the newest samples, one to a bar.
+    ResetHistoryView,
+    /// Has the expanded graphs cover a time window, or go back to a sample per bar.
+    SelectTimeWindow(Option<TimeWindow>),
+    /// Shows one runtime's performance, or the whole program's.
+    SelectRuntime(Option<usize>),
+}
+
+pub struct PerformancePaneLayout {
+    performance_node: Node,
+    /// Only programs with more than one runtime have a runtime selector.
+    performance_runtime_node: Option<Node>,
+    performance_graphs_container_node: Node,
+    performance_expand_button_node: Node,
+}
+
+impl Component for PerformancePane {
+    type Message = PerformancePaneMessage;
+    type Layout = PerformancePaneLayout;
+
+    fn update(&self, state: &mut AppState, message: PerformancePaneMessage) {
+        match message {
+            PerformancePaneMessage::ToggleExpanded if state.performance_expanded => {
+                PerformanceScreen.update(state, PerformanceScreenMessage::Open)
+            }
+            PerformancePaneMessage::ToggleExpanded => state.performance_expanded = true,
+            PerformancePaneMessage::SelectRuntime(runtime) => state.performance_runtime = runtime,
+            // Panning and zooming only apply to the expanded graphs and the performance screen.
+            _ if !state.performance_expanded && !state.performance_screen_open => {}
+            PerformancePaneMessage::PanHistory(bars) => state.pan_performance_history(bars),
+            PerformancePaneMessage::ZoomHistory(zoom_in) => state.zoom_performance_history(zoom_in),
+            PerformancePaneMessage::ResetHistoryView => {
+                state.performance_history_view = HistoryView::default()
+            }
+            PerformancePaneMessage::SelectTimeWindow(time_window) => {
+                state.select_performance_time_window(time_window)
+            }
+        }
+    }
+
+    fn layout(
+        &self,
+        state: &AppState,
+        _: &GlyphSet,
+        stretch: &mut LayoutTree,
+        parent: Node,
+    ) -> PerformancePaneLayout {
+        let graph_height = if state.performance_expanded {
+            PERFORMANCE_EXPANDED_GRAPH_HEIGHT
+        } else {
+            1
+        };
+        ui!(stretch; parent => [
+            performance_node { size: Size::fixed_height(graph_height + 2) } [
+                // Grown from a zero width, rather than sized to its contents, so that the
+                // segments can't hold it open past the pane's edge. Likewise the graphs'
+                // container.
+                performance_inner_container_node {
+                    size: Size::fixed_width(0),
+                    padding: Rect::new(1, 1, 1, 1),
+                    flex_grow: 1.0,
+                },
+            ],
+        ]);
+        let performance_runtime_node = if state.snapshot.runtimes.is_empty() {
+            None
+        } else {
+            let runtime_labels = performance_runtime_labels(state);
+            let runtime_control =
+                runtime_control(&runtime_labels, state.performance_runtime, state.theme);
+            ui!(stretch; performance_inner_container_node => [
+                performance_runtime_node {
+                    size: Size::fixed(runtime_control.preferred_width(), 1),
+                    margin: Rect::new(0, 1, 0, 1),
+                },
+            ]);
+            Some(performance_runtime_node)
+        };
+        ui!(stretch; performance_inner_container_node => [
+            performance_graphs_container_node {
+                size: Size::fixed(0, graph_height),
+                flex_grow: 1.0,
+            },
+            performance_expand_button_node {
+                size: Size::fixed(2, 1),
+                flex_shrink: 0.0,
+            },
+        ]);
+        for metric in state.shown_performance_metrics() {
+            let performance_label_width = display_width(state.strings.performance_metrics[metric]);
+            // Expanded, there's room for percentiles under the current value.
+            let (performance_value_width, performance_value_height) =
+                if state.performance_expanded && PERFORMANCE_METRICS[metric].percentiles {
+                    (PERFORMANCE_SEGMENT_PERCENTILE_WIDTH, graph_height)
+                } else {
+                    (PERFORMANCE_SEGMENT_VALUE_WIDTH, 1)
+                };
+            // The label, the value, their margins and the padding.
+            let performance_segment_width =
+                performance_label_width + 1 + performance_value_width + 1 + 2;
+            // A segment starts out as wide as its label and value, but its zero width lets
+            // stretch shrink it below that, cutting the label, when the pane is too narrow for
+            // every segment and the expand button.
+            ui!(stretch; performance_graphs_container_node => [
+                performance_segment_node {
+                    size: Size::fixed(0, graph_height),
+                    flex_basis: Dimension::Points(performance_segment_width as f32),
+                    padding: Rect::new(0, 1, 0, 1),
+                    flex_grow: 1.0,
+                } [
+                    _performance_segment_label_node {
+                        size: Size::fixed(performance_label_width, 1),
+                        margin: Rect::new(0, 1, 0, 0),
+                    },
+                    _performance_segment_time_node {
+                        size: Size::fixed(performance_value_width, performance_value_height),
+                        margin: Rect::new(0, 1, 0, 0),
+                        flex_shrink: 0.0,
+                    },
+                    _performance_segment_graph_node {
+                        size: Size::fixed_height(graph_height),
+                        flex_grow: 1.0,
+                    },
+                ],
+            ]);
+        }
+
+        PerformancePaneLayout {
+            performance_node,
+            performance_runtime_node,
+            performance_graphs_container_node,
+            performance_expand_button_node,
+        }
+    }
+
+    fn view<B>(
+        &self,
+        state: &mut AppState,
+        frame: &mut Frame<B>,
+        context: &mut ViewContext,
+        layout: &PerformancePaneLayout,
+    ) where
+        B: Backend,
+    {
+        let theme = context.theme;
+        let performance = state.performance();
+        let shown_performance_metrics = state.shown_performance_metrics();
+        let runtime_labels = performance_runtime_labels(state);
+        let history_view = state.visible_performance_history();
+        let performance_node_children = context
+            .stretch
+            .children(layout.performance_graphs_container_node);
+        // Each graph fits the time window, if there is one, into as many bars as it has room for.
+        let performance_graphs: Vec<(usize, HistoryView, Vec<f32>)> = shown_performance_metrics
+            .iter()
+            .zip(performance_node_children)
+            .map(|(&metric, &performance_segment_node)| {
+                let performance_segment_graph_node =
+                    context.stretch.children(performance_segment_node)[2];
+                let bar_count = context
+                    .stretch
+                    .layout(performance_segment_graph_node)
+                    .size
+                    .width as usize
+                    * context.glyph_set.chart.samples_per_cell();
+                let graph_view = history_view.fitted(performance.history.len(), bar_count);
+                (
+                    bar_count,
+                    graph_view,
+                    performance.graph_values(metric, graph_view),
+                )
+            })
+            .collect();
+        let performance_latency_graph_thresholds = [
+            (
+                PERFORMANCE_LATENCY_WARNING_MS,
+                theme.performance_graph_warning_color,
+            ),
+            (
+                PERFORMANCE_LATENCY_CRITICAL_MS,
+                theme.performance_graph_critical_color,
+            ),
+        ];
+        let performance_aux_label = performance_aux_label(state, context.glyph_set, history_view);
+        let mut renderer = Renderer::new();
+
+        // Render performance values.
+        let performance_unit_style = UnitStyle {
+            number: TuiStyle::default().fg(theme.performance_numeric_color),
+            unit: TuiStyle::default().fg(theme.performance_minor_color),
+            microseconds: context.glyph_set.microseconds,
+        };
+        let current_performance_values = performance.current.values();
+
+        // Build performance pane.
+        renderer.build_node(
+            layout.performance_node,
+            pane_box_frame(
+                state.strings.performance,
+                theme.performance_box_fg,
+                context.glyph_set,
+                theme,
+            )
+            .aux_label(&performance_aux_label),
+        );
+        renderer.build_node(
+            layout.performance_expand_button_node,
+            Paragraph::new(context.glyph_set.performance_expand),
+        );
+        context.identify(
+            &mut renderer,
+            layout.performance_expand_button_node,
+            PERFORMANCE_EXPAND_BUTTON_ID,
+        );
+        if let Some(performance_runtime_node) = layout.performance_runtime_node {
+            renderer.build_node(
+                performance_runtime_node,
+                runtime_control(&runtime_labels, state.performance_runtime, theme)
+                    .symbols(context.glyph_set.segmented_control),
+            );
+            context.identify(
+                &mut renderer,
+                performance_runtime_node,
+                PERFORMANCE_RUNTIME_ID,
+            );
+        }
+        for (performance_segment_index, &metric) in shown_performance_metrics.iter().enumerate() {
+            let performance_metric = &PERFORMANCE_METRICS[metric];
+            let (bar_count, graph_view, ref graph_values) =
+                performance_graphs[performance_segment_index];
+            let performance_segment_node = performance_node_children[performance_segment_index];
+            let performance_segment_children = context.stretch.children(performance_segment_node);
+            let performance_segment_label_node = performance_segment_children[0];
+            let performance_segment_value_node = performance_segment_children[1];
+            let performance_segment_graph_node = performance_segment_children[2];
+            renderer.build_node(
+                performance_segment_label_node,
+                Paragraph::new(truncate_spans(
+                    Spans::from(state.strings.performance_metrics[metric]),
+                    context
+                        .stretch
+                        .layout(performance_segment_label_node)
+                        .size
+                        .width as u16,
+                    context.glyph_set.ellipsis,
+                ))
+                .style(performance_label_style(state, theme, metric)),
+            );
+            let mut performance_value_lines = vec![performance_metric
+                .quantity
+                .spans(current_performance_values[metric], performance_unit_style)];
+            if state.performance_expanded && performance_metric.percentiles {
+                // Over the samples the graph has room for.
+                let sample_count = bar_count * graph_view.samples_per_bar;
+                let window = performance.window_values(metric, graph_view, sample_count);
+                performance_value_lines.extend(percentile_lines(
+                    &window,
+                    performance_metric.quantity,
+                    performance_unit_style,
+                ));
+            }
+            renderer.build_node(
+                performance_segment_value_node,
+                Paragraph::new(performance_value_lines),
+            );
+            let mut performance_segment_graph = BarChart::auto_scaled(
+                graph_values,
+                PERFORMANCE_GRAPH_AUTO_SCALE,
+                theme.performance_graph_color,
+            )
+            .symbols(context.glyph_set.chart);
+            if performance_metric.latency {
+                performance_segment_graph =
+                    performance_segment_graph.thresholds(&performance_latency_graph_thresholds);
+            }
+            if state.performance_expanded {
+                performance_segment_graph =
+                    performance_segment_graph.axis(theme.performance_axis_color);
+            }
+            let graph_cache = &state.performance_graph_caches[metric];
+            let graph_window = (
+                performance.history.generation(),
+                state.performance_runtime,
+                graph_view,
+            );
+            performance_segment_graph = performance_segment_graph
+                .cached(&graph_cache.chart, graph_cache.generation(graph_window));
+            renderer.build_node(performance_segment_graph_node, performance_segment_graph);
+        }
+
+        context.render(frame, &mut renderer, layout.performance_node);
+    }
+
+    fn contains(&self, id: WidgetId) -> bool {
+        id == PERFORMANCE_EXPAND_BUTTON_ID || id == PERFORMANCE_RUNTIME_ID
+    }
+
+    // Left and Right step through the runtimes, after the whole program, and clicking one
+    // selects it.
+    fn handle_event(
+        &self,
+        state: &mut AppState,
+        event: &Event,
+        target: WidgetId,
+        phase: Phase,
+    ) -> Propagation {
+        if target == PERFORMANCE_RUNTIME_ID {
+            if phase != Phase::Target {
+                return Propagation::Continue;
+            }
+            let runtime_labels = performance_runtime_labels(state);
+            let index = state
+                .performance_runtime
+                .map_or(0, |runtime| runtime as u32 + 1);
+            let index = match *event {
+                Event::Key(KeyEvent { code, .. }) => step_index(index, runtime_labels.len(), code),
+                Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    column,
+                    ..
+                }) => state
+                    .widget_registry
+                    .rect(PERFORMANCE_RUNTIME_ID)
+                    .and_then(|rect| {
+                        runtime_control(&runtime_labels, state.performance_runtime, state.theme)
+                            .index_at(column.saturating_sub(rect.x), rect.width)
+                    })
+                    .map(|index| index as u32),
+                _ => None,
+            };
+            return match index {
+                Some(index) => {
+                    let runtime = (index as usize).checked_sub(1);
+                    self.update(state, PerformancePaneMessage::SelectRuntime(runtime));
+                    Propagation::Stop
+                }
+                None => Propagation::Continue,
+            };
+        }
+        match *event {
+            Event::Key(KeyEvent {
+                code: KeyCode::Enter,
+                ..
+            })
+            | Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                ..
+            }) if phase == Phase::Target => {
+                self.update(state, PerformancePaneMessage::ToggleExpanded);
+                Propagation::Stop
+            }
+            _ => Propagation::Continue,
+        }
+    }
+}
+
+// Says where the performance history is panned and zoomed to, if anywhere, and how old the
+// snapshot is, and whether that's stale.
+fn performance_aux_label(
+    state: &AppState,
+    glyph_set: &GlyphSet,
+    history_view: HistoryView,
+) -> String {
+    let mut performance_aux_labels = vec![];
+    if let Some(runtime) = state
+        .performance_runtime
+        .and_then(|runtime| state.snapshot.runtimes.get(runtime))
+    {
+        performance_aux_labels.push(runtime.name.clone());
+    }
+    let strings = state.strings;
+    if history_view.offset > 0 {
+        performance_aux_labels.push(strings::fill(
+            strings.performance_history_offset,
+            &[&format::duration_text(
+                PERFORMANCE_SAMPLE_INTERVAL * history_view.offset as u32,
+            )],
+        ));
+    }
+    if let Some(time_window) = history_view.time_window {
+        performance_aux_labels.push(strings::fill(
+            strings.performance_time_window,
+            &[strings.performance_time_windows[time_window.index() as usize]],
+        ));
+    } else if history_view.samples_per_bar > 1 {
+        performance_aux_labels.push(strings::fill(
+            strings.performance_history_zoom,
+            &[&format::duration_text(
+                PERFORMANCE_SAMPLE_INTERVAL * history_view.samples_per_bar as u32,
+            )],
+        ));
+    }
+    if state.snapshot.age >= PERFORMANCE_STALE_AGE {
+        performance_aux_labels.push(strings.performance_stale.to_owned());
+    }
+    performance_aux_labels.push(strings::fill(
+        strings.performance_updated,
+        &[&format::duration_text(state.snapshot.age)],
+    ));
+    performance_aux_labels.join(&format!(" {} ", glyph_set.separator))
+}
+
+// The runtime selector's labels: one for the whole program, then one for each runtime.
+fn performance_runtime_labels(state: &AppState) -> Vec<&str> {
+    let mut labels = vec![state.strings.performance_all_runtimes];
+    labels.extend(
+        state
+            .snapshot
+            .runtimes
+            .iter()
+            .map(|runtime| &runtime.name[..]),
+    );
+    labels
+}
+
+fn runtime_control<'a>(
+    labels: &'a [&'a str],
+    runtime: Option<usize>,
+    theme: &Theme,
+) -> SegmentedControl<'a> {
+    SegmentedControl::new(
+        labels,
+        runtime.map_or(0, |runtime| runtime as u32 + 1),
+        theme.tasks_filter_bg,
+        theme.tasks_filter_fg,
+    )
+}
+
+// Measurements whose alerts are raised have their labels called out.
+fn performance_label_style(state: &AppState, theme: &Theme, metric: usize) -> TuiStyle {
+    if state.performance_alerts_raised[metric] {
+        TuiStyle::default()
+            .fg(theme.performance_alert_label_color)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        TuiStyle::default().fg(theme.performance_label)
+    }
+}
+
+// A line for each of the percentiles of `values`, such as `p99 1.57ms`, or none if there are no
+// values.
+fn percentile_lines(values: &[f32], quantity: Quantity, style: UnitStyle) -> Vec<Spans<'static>> {
+    let percentiles = match history::percentiles(values, &PERFORMANCE_PERCENTILES) {
+        Some(percentiles) => percentiles,
+        None => return vec![],
+    };
+    PERFORMANCE_PERCENTILE_LABELS
+        .iter()
+        .zip(percentiles)
+        .map(|(&label, value)| {
+            let mut line = vec![Span::styled(format!("{} ", label), style.unit)];
+            line.extend(quantity.spans(value, style).0);
+            Spans::from(line)
+        })
+        .collect()
+}
+
+// Performance screen
+
+pub struct PerformanceScreen;
+
+pub enum PerformanceScreenMessage {
+    Open,
+    /// Compares the samples on screen with the ones before them, or stops comparing.
+    ToggleComparison,
+    /// Shows the newest values in big digits instead of charts, or goes back to the charts.
+    TogglePresentation,
+    /// Goes back to the panes, with the performance pane's one-line graphs.
+    Close,
+}
+
+pub struct PerformanceScreenLayout {
+    performance_screen_node: Node,
+    performance_time_window_node: Node,
+    performance_charts_container_node: Node,
+    performance_collapse_button_node: Node,
+}
+
+impl Component for PerformanceScreen {
+    type Message = PerformanceScreenMessage;
+    type Layout = PerformanceScreenLayout;
+
+    fn update(&self, state: &mut AppState, message: PerformanceScreenMessage) {
+        match message {
+            PerformanceScreenMessage::Open => state.performance_screen_open = true,
+            PerformanceScreenMessage::ToggleComparison => {
+                state.performance_comparison = !state.performance_comparison
+            }
+            PerformanceScreenMessage::TogglePresentation => {
+                state.performance_presentation = !state.performance_presentation
+            }
+            PerformanceScreenMessage::Close => {
+                state.performance_screen_open = false;
+                state.performance_expanded = false;
+            }
+        }
+    }
+
+    fn layout(
+        &self,
+        state: &AppState,
+        _: &GlyphSet,
+        stretch: &mut LayoutTree,
+        parent: Node,
+    ) -> PerformanceScreenLayout {
+        ui!(stretch; parent => [
+            performance_screen_node {
+                size: AUTO_SIZE,
+                flex_grow: 1.0,
+                flex_direction: FlexDirection::Column,
+            } [
+                performance_screen_inner_container_node {
+                    size: AUTO_SIZE,
+                    padding: Rect::new(1, 1, 1, 1),
+                    flex_grow: 1.0,
+                } [
+                    performance_charts_column_node {
+                        size: AUTO_SIZE,
+                        padding: Rect::new(0, 1, 0, 1),
+                        flex_grow: 1.0,
+                        flex_direction: FlexDirection::Column,
+                    } [
+                        performance_time_window_node {
+                            size: Size::fixed(
+                                time_window_control(
+                                    state.performance_history_view.time_window,
+                                    state.strings,
+                                    state.theme,
+                                )
+                                .preferred_width(),
+                                1,
+                            ),
+                            align_self: AlignSelf::FlexEnd,
+                        },
+                        performance_charts_container_node {
+                            size: AUTO_SIZE,
+                            flex_grow: 1.0,
+                            flex_direction: FlexDirection::Column,
+                        },
+                    ],
+                    performance_collapse_button_node { size: Size::fixed(2, 1) },
+                ],
+            ],
+        ]);
+        for _ in state.shown_performance_metrics() {
+            ui!(stretch; performance_charts_container_node => [
+                performance_chart_section_node {
+                    size: AUTO_SIZE,
+                    flex_grow: 1.0,
+                    flex_direction: FlexDirection::Column,
+                } [
+                    _performance_readout_node { size: Size::fixed_height(1) },
+                    _performance_chart_node { size: AUTO_SIZE, flex_grow: 1.0 },
+                ],
+            ]);
+        }
+
+        PerformanceScreenLayout {
+            performance_screen_node,
+            performance_time_window_node,
+            performance_charts_container_node,
+            performance_collapse_button_node,
+        }
+    }
+
+    fn view<B>(
+        &self,
+        state: &mut AppState,
+        frame: &mut Frame<B>,
+        context: &mut ViewContext,
+        layout: &PerformanceScreenLayout,
+    ) where
+        B: Backend,
+    {
+        let theme = context.theme;
+        let performance = state.performance();
+        let history_view = state.visible_performance_history();
+        let marker = match context.glyph_set.chart {
+            ChartSymbols::Braille => Marker::Braille,
+            ChartSymbols::Bars(_) => Marker::Dot,
+        };
+        let points_per_cell = context.glyph_set.chart.samples_per_cell();
+        let unit_style = UnitStyle {
+            number: TuiStyle::default().fg(theme.performance_numeric_color),
+            unit: TuiStyle::default().fg(theme.performance_minor_color),
+            microseconds: context.glyph_set.microseconds,
+        };
+        let minor_style = TuiStyle::default().fg(theme.performance_minor_color);
+        let axis_style = TuiStyle::default().fg(theme.performance_axis_color);
+        let aux_label = performance_aux_label(state, context.glyph_set, history_view);
+        let section_nodes = context
+            .stretch
+            .children(layout.performance_charts_container_node);
+        // Presenting, the sections show big digits rather than charts.
+        let chart_section_nodes: &[Node] = if state.performance_presentation {
+            &[]
+        } else {
+            section_nodes
+        };
+
+        // Work out what each chart shows before drawing any, since the charts borrow their points.
+        let charts: Vec<_> = state
+            .shown_performance_metrics()
+            .into_iter()
+            .zip(chart_section_nodes.iter())
+            .map(|(metric, &section_node)| {
+                let performance_metric = &PERFORMANCE_METRICS[metric];
+                let section_children = context.stretch.children(section_node);
+                let chart_width = context.stretch.layout(section_children[1]).size.width;
+                let history_len = performance.history.len();
+                let chart_view =
+                    history_view.fitted(history_len, chart_width as usize * points_per_cell);
+                let values = performance.graph_values(metric, chart_view);
+                let value_label =
+                    |value| text_of(performance_metric.quantity.spans(value, unit_style));
+                // The Y axis takes up its widest label and a line. Which values fit depends on
+                // the axis's width, so it's as wide as the largest value of all needs.
+                let all_max_value = values.iter().copied().fold(0.0, f32::max);
+                let y_axis_width = display_width(&value_label(all_max_value)) as usize + 1;
+                let point_count =
+                    (chart_width as usize).saturating_sub(y_axis_width).max(1) * points_per_cell;
+                // A time window has to fit in what's left beside the axis.
+                let chart_view = history_view.fitted(history_len, point_count);
+                let values = performance.graph_values(metric, chart_view);
+                // The newest value is at the right edge, however few values there are.
+                let visible_values = &values[values.len().saturating_sub(point_count)..];
+                let max_value = visible_values.iter().copied().fold(0.0, f32::max);
+                let y_labels: Vec<_> = [0.0, max_value]
+                    .iter()
+                    .map(|&value| Span::styled(value_label(value), minor_style))
+                    .collect();
+                let first_x = point_count - visible_values.len();
+                let points = visible_values
+                    .iter()
+                    .enumerate()
+                    .map(|(index, &value)| ((first_x + index) as f64, value as f64))
+                    .collect();
+
+                let newest_value = if history_view.offset == 0 {
+                    performance.current.values()[metric]
+                } else {
+                    visible_values.last().copied().unwrap_or_default()
+                };
+                let readout_values = [
+                    newest_value,
+                    visible_values.iter().copied().fold(f32::INFINITY, f32::min),
+                    visible_values
+                        .iter()
+                        .copied()
+                        .fold(f32::NEG_INFINITY, f32::max),
+                    visible_values.iter().sum::<f32>() / visible_values.len() as f32,
+                ];
+                let mut readout = vec![Span::styled(
+                    state.strings.performance_metrics[metric],
+                    performance_label_style(state, theme, metric),
+                )];
+                for (&label, &value) in state
+                    .strings
+                    .performance_readouts
+                    .iter()
+                    .zip(&readout_values)
+                {
+                    // With no samples, there's no minimum, maximum, or average.
+                    if value.is_finite() {
+                        readout.push(Span::styled(format!("  {} ", label), minor_style));
+                        readout.extend(performance_metric.quantity.spans(value, unit_style).0);
+                    }
+                }
+                if performance_metric.percentiles {
+                    let window = performance.window_values(
+                        metric,
+                        chart_view,
+                        point_count * chart_view.samples_per_bar,
+                    );
+                    for line in percentile_lines(&window, performance_metric.quantity, unit_style) {
+                        readout.push(Span::raw("  "));
+                        readout.extend(line.0);
+                    }
+                }
+
+                if state.performance_comparison {
+                    let comparison = performance.window_averages(
+                        metric,
+                        chart_view,
+                        point_count * chart_view.samples_per_bar,
+                    );
+                    if let Some((current, previous)) = comparison {
+                        readout.push(Span::styled(
+                            format!("  {} ", state.strings.performance_comparison),
+                            minor_style,
+                        ));
+                        readout.extend(performance_change_spans(
+                            performance_metric.quantity,
+                            current,
+                            previous,
+                            theme,
+                        ));
+                    }
+                }
+
+                // The time axis's labels go on a ruler under the graph, which starts past the Y
+                // axis's labels and line. The graph stretches its points to fill its width, and
+                // the ruler's columns have to span as much time as the graph's do.
+                let ruler_x = y_labels
+                    .iter()
+                    .map(|label| label.width() as u16)
+                    .max()
+                    .unwrap_or_default()
+                    + 1;
+                let ruler_width = chart_width as u16 - ruler_x.min(chart_width as u16);
+                let graph_x_max = (ruler_width as usize * points_per_cell).max(2) - 1;
+                let x_max = (point_count - 1).max(1);
+                let sample_span =
+                    PERFORMANCE_SAMPLE_INTERVAL.as_secs_f64() * chart_view.samples_per_bar as f64;
+                PerformanceChart {
+                    readout_node: section_children[0],
+                    chart_node: section_children[1],
+                    readout: Spans::from(readout),
+                    points,
+                    x_max: x_max as f64,
+                    y_max: if max_value > 0.0 {
+                        max_value as f64
+                    } else {
+                        1.0
+                    },
+                    y_labels,
+                    ruler_x,
+                    ruler_column_span: Duration::from_secs_f64(
+                        sample_span * (x_max * points_per_cell) as f64 / graph_x_max as f64,
+                    ),
+                    ruler_offset: PERFORMANCE_SAMPLE_INTERVAL * chart_view.offset as u32,
+                }
+            })
+            .collect();
+
+        let mut renderer = Renderer::new();
+        renderer.build_node(
+            layout.performance_screen_node,
+            pane_box_frame(
+                state.strings.performance,
+                theme.performance_box_fg,
+                context.glyph_set,
+                theme,
+            )
+            .aux_label(&aux_label)
+            .footer_label(state.strings.performance_screen_keys),
+        );
+        renderer.build_node(
+            layout.performance_time_window_node,
+            time_window_control(history_view.time_window, state.strings, theme)
+                .symbols(context.glyph_set.segmented_control),
+        );
+        context.identify(
+            &mut renderer,
+            layout.performance_time_window_node,
+            PERFORMANCE_TIME_WINDOW_ID,
+        );
+        renderer.build_node(
+            layout.performance_collapse_button_node,
+            Paragraph::new(context.glyph_set.performance_collapse),
+        );
+        context.identify(
+            &mut renderer,
+            layout.performance_collapse_button_node,
+            PERFORMANCE_COLLAPSE_BUTTON_ID,
+        );
+        let big_value_labels: Vec<_> = if state.performance_presentation {
+            state
+                .shown_performance_metrics()
+                .into_iter()
+                .map(|metric| {
+                    let value = performance.current.values()[metric];
+                    (metric, PERFORMANCE_METRICS[metric].quantity.text(value))
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+        for ((metric, value_label), &section_node) in big_value_labels.iter().zip(section_nodes) {
+            let section_children = context.stretch.children(section_node);
+            renderer.build_node(
+                section_children[0],
+                Paragraph::new(Span::styled(
+                    state.strings.performance_metrics[*metric],
+                    performance_label_style(state, theme, *metric),
+                ))
+                .alignment(Alignment::Center),
+            );
+            renderer.build_node(
+                section_children[1],
+                BigText::new(value_label)
+                    .style(TuiStyle::default().fg(theme.performance_numeric_color))
+                    .alignment(Alignment::Center),
+            );
+        }
+        for chart in &charts {
+            renderer.build_node(chart.readout_node, Paragraph::new(chart.readout.clone()));
+            let dataset = Dataset::default()
+                .data(&chart.points)
+                .marker(marker)
+                .graph_type(GraphType::Line)
+                .style(TuiStyle::default().fg(theme.performance_graph_color));
+            renderer.build_node(
+                chart.chart_node,
+                Chart::new(vec![dataset])
+                    .x_axis(
+                        Axis::default()
+                            .bounds([0.0, chart.x_max])
+                            .labels(vec![])
+                            .style(axis_style),
+                    )
+                    .y_axis(
+                        Axis::default()
+                            .bounds([0.0, chart.y_max])
+                            .labels(chart.y_labels.clone())
+                            .style(axis_style),
+                    ),
+            );
+        }
+
+        context.render(frame, &mut renderer, layout.performance_screen_node);
+        // The rulers go on the charts' label rows, under their X axes.
+        let strings = state.strings;
+        let time_ago_label = |ago| time_ago_label(strings, ago);
+        for chart in &charts {
+            let chart_rect = world_rect(context.stretch, context.root, chart.chart_node).unwrap();
+            if chart_rect.height < 2 || chart_rect.width <= chart.ruler_x {
+                continue;
+            }
+            frame.render_widget(
+                TimeRuler::new(chart.ruler_column_span, &time_ago_label)
+                    .offset(chart.ruler_offset)
+                    .style(minor_style),
+                TuiRect::new(
+                    chart_rect.x + chart.ruler_x,
+                    chart_rect.bottom() - 1,
+                    chart_rect.width - chart.ruler_x,
+                    1,
+                ),
+            );
+        }
+    }
+
+    fn contains(&self, id: WidgetId) -> bool {
+        id == PERFORMANCE_COLLAPSE_BUTTON_ID || id == PERFORMANCE_TIME_WINDOW_ID
+    }
+
+    // Left and Right step through the time windows, starting from a sample per bar, and clicking
+    // one selects it.
+    fn handle_event(
+        &self,
+        state: &mut AppState,
+        event: &Event,
+        target: WidgetId,
+        phase: Phase,
+    ) -> Propagation {
+        if target == PERFORMANCE_TIME_WINDOW_ID {
+            if phase != Phase::Target {
+                return Propagation::Continue;
+            }
+            let time_window = state.performance_history_view.time_window;
+            let index = match *event {
+                Event::Key(KeyEvent { code, .. }) => match time_window {
+                    Some(time_window) => {
+                        step_index(time_window.index(), TimeWindow::ALL.len(), code)
+                    }
+                    None if code == KeyCode::Right => Some(0),
+                    None => None,
+                },
+                Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    column,
+                    ..
+                }) => state
+                    .widget_registry
+                    .rect(PERFORMANCE_TIME_WINDOW_ID)
+                    .and_then(|rect| {
+                        time_window_control(time_window, state.strings, state.theme)
+                            .index_at(column.saturating_sub(rect.x), rect.width)
+                    })
+                    .map(|index| index as u32),
+                _ => None,
+            };
+            return match index.and_then(TimeWindow::from_index) {
+                Some(time_window) => {
+                    PerformancePane.update(
+                        state,
+                        PerformancePaneMessage::SelectTimeWindow(Some(time_window)),
+                    );
+                    Propagation::Stop
+                }
+                None => Propagation::Continue,
+            };
+        }
+        match *event {
+            Event::Key(KeyEvent {
+                code: KeyCode::Enter,
+                ..
+            })
+            | Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                ..
+            }) if phase == Phase::Target => {
+                self.update(state, PerformanceScreenMessage::Close);
+                Propagation::Stop
+            }
+            _ => Propagation::Continue,
+        }
+    }
+}
+
+// Says how much a measurement went up or down, such as `+105µs +11.2%`, in a color for which
+// way it went. There's no percentage for changes from zero.
+fn performance_change_spans(
+    quantity: Quantity,
+    current: f32,
+    previous: f32,
+    theme: &Theme,
+) -> Vec<Span<'static>> {
+    let change = current - previous;
+    let (sign, color) = if change > 0.0 {
+        ("+", theme.performance_increase_color)
+    } else if change < 0.0 {
+        ("-", theme.performance_decrease_color)
+    } else {
+        ("±", theme.performance_minor_color)
+    };
+    let style = TuiStyle::default().fg(color);
+    let mut text = format!("{}{}", sign, quantity.text(change.abs()));
+    if previous != 0.0 {
+        text.push_str(&format!(
+            " {}{:.1}%",
+            sign,
+            (change / previous * 100.0).abs()
+        ));
+    }
+    vec![Span::styled(text, style)]
+}
+
+// With no time window, none of the segments is selected.
+fn time_window_control(
+    time_window: Option<TimeWindow>,
+    strings: &'static Strings,
+    theme: &Theme,
+) -> SegmentedControl<'static> {
+    SegmentedControl::new(
+        &strings.performance_time_windows,
+        time_window.map_or(
+            strings.performance_time_windows.len() as u32,
+            TimeWindow::index,
+        ),
+        theme.tasks_filter_bg,
+        theme.tasks_filter_fg,
+    )
+}
+
+// One measurement's chart on the performance screen, and the readout above it.
+struct PerformanceChart {
+    readout_node: Node,
+    chart_node: Node,
+    readout: Spans<'static>,
+    /// Sample positions from the left edge, paired with values.
+    points: Vec<(f64, f64)>,
+    x_max: f64,
+    y_max: f64,
+    y_labels: Vec<Span<'static>>,
+    /// Where the time axis's ruler starts, from the chart's left edge.
+    ruler_x: u16,
+    ruler_column_span: Duration,
+    ruler_offset: Duration,
+}
+
+// Labels a point on a time axis, such as the performance screen's, this long before now.
+fn time_ago_label(strings: &Strings, ago: Duration) -> String {
+    if ago.is_zero() {
+        return strings.performance_chart_newest.to_owned();
+    }
+    strings::fill(
+        strings.performance_chart_ago,
+        &[&format::duration_text(ago)],
+    )
+}
+
+// The text of some spans, without their styles.
+fn text_of(spans: Spans) -> String {
+    spans.0.iter().map(|span| &span.content[..]).collect()
+}
+
+// Tasks pane
+
+pub struct TasksPane;
+
+pub enum TasksPaneMessage {
+    /// Scrolls the table body by this many rows.
+    ScrollRows(isize),
+    /// Scrolls the columns after the frozen ones by this many columns, or pans the timeline by
+    /// as many steps, toward the snapshot for positive counts.
+    ScrollColumns(isize),
+    /// Has each column of the timeline cover less time if true, or more if false.
+    ZoomTimeline(bool),
+    /// A left click anywhere on screen.
+    Click {
+        column: u16,
+        row: u16,
+    },
+    SelectTab(TasksTab),
+    SelectViewMode(ViewMode),
+    SelectTask(u64),
+    /// Sorts by a column, or reverses the sort if it's already sorted by that column.
+    SortBy(TaskSortKey),
+    /// Switches between listing every task and collapsing tasks that share a name.
+    ToggleGrouping,
+    /// Expands or collapses the group of tasks with this name.
+    ToggleGroup(String),
+    /// Starts or stops keeping the newest task in view.
+    ToggleFollow,
+    /// Lists only the tasks named like the selected one, or stops filtering by name.
+    ToggleNameFilter,
+    /// Bookmarks a task, or removes its bookmark.
+    ToggleBookmark(u64),
+}
+
+pub struct TasksPaneLayout {
+    tasks_node: Node,
+    tasks_tabs_node: Node,
+    tasks_view_mode_node: Node,
+    tasks_table_node: Node,
+    tasks_scrollbar_node: Node,
+}
+
+impl Component for TasksPane {
+    type Message = TasksPaneMessage;
+    type Layout = TasksPaneLayout;
+
+    fn update(&self, state: &mut AppState, message: TasksPaneMessage) {
+        match message {
+            TasksPaneMessage::ScrollRows(rows) => {
+                state.tasks_scroll_state.scroll_by(rows);
+                state.follow_tasks = false;
+            }
+            TasksPaneMessage::ScrollColumns(steps) if state.view_mode == ViewMode::Timeline => {
+                state.pan_timeline(-steps * TASKS_TIMELINE_PAN_STEP)
+            }
+            TasksPaneMessage::ScrollColumns(columns) => {
+                state.tasks_column_scroll_state.scroll_by(columns)
+            }
+            TasksPaneMessage::ZoomTimeline(zoom_in) => state.zoom_timeline(zoom_in),
+            TasksPaneMessage::Click { column, row } => {
+                if state.tasks_scroll_state.click(column, row) {
+                    state.follow_tasks = false;
+                }
+            }
+            TasksPaneMessage::SelectTab(tasks_tab) => state.select_tasks_tab(tasks_tab),
+            TasksPaneMessage::SelectViewMode(view_mode) => state.view_mode = view_mode,
+            TasksPaneMessage::SelectTask(task_id) => state.selected_task_id = Some(task_id),
+            TasksPaneMessage::SortBy(key) => state.sort_tasks_by(key),
+            TasksPaneMessage::ToggleGrouping => {
+                state.group_tasks_by_name = !state.group_tasks_by_name
+            }
+            TasksPaneMessage::ToggleGroup(name) => state.toggle_task_group(name),
+            TasksPaneMessage::ToggleFollow => state.follow_tasks = !state.follow_tasks,
+            TasksPaneMessage::ToggleNameFilter => state.toggle_task_name_filter(),
+            TasksPaneMessage::ToggleBookmark(task_id) => {
+                let task_label = match state.task(task_id) {
+                    Some(task) => format!("task {} ({})", task.id, task.name),
+                    None => return,
+                };
+                let bookmarked = state.toggle_bookmark(task_id);
+                let message = format!(
+                    "{} {}",
+                    BOOKMARK_TOGGLED_LABELS[!bookmarked as usize], task_label
+                );
+                state.show_toast(message);
+            }
+        }
+    }
+
+    fn layout(
+        &self,
+        state: &AppState,
+        glyph_set: &GlyphSet,
+        stretch: &mut LayoutTree,
+        parent: Node,
+    ) -> TasksPaneLayout {
+        // Lay out tasks pane.
+        ui!(stretch; parent => [
+            tasks_node {
+                size: AUTO_SIZE,
+                flex_grow: 1.0,
+                flex_direction: FlexDirection::Column,
+            } [
+                tasks_inner_container_node {
+                    size: AUTO_SIZE,
+                    padding: Rect::new(1, 1, 1, 1),
+                    flex_grow: 1.0,
+                    flex_direction: FlexDirection::Column,
+                } [
+                    tasks_tab_strip_node { size: Size::fixed_height(1) } [
+                        tasks_tabs_node {
+                            size: AUTO_SIZE,
+                            margin: Rect::new(0, 0, 0, 1),
+                            flex_grow: 1.0,
+                        },
+                        tasks_view_mode_node {
+                            size: Size::fixed_width(
+                                view_mode_control(state.view_mode, glyph_set, state.theme)
+                                    .preferred_width(),
+                            ),
+                            // The tabs give way instead, cutting their labels short.
+                            flex_shrink: 0.0,
+                        },
+                    ],
+                    tasks_table_container_node {
+                        size: AUTO_SIZE,
+                        margin: Rect::new(0, 0, 0, 1),
+                        flex_grow: 1.0,
+                    } [
+                        tasks_table_node { size: AUTO_SIZE, flex_grow: 1.0 },
+                        tasks_scrollbar_node { size: Size::fixed_width(1) },
+                    ],
+                ],
+            ],
+        ]);
+
+        // Lay out tasks table.
+        for table_column in state.shown_tasks_table_columns() {
+            match TASKS_TABLE_COLUMNS[table_column].width {
+                Some(width) => {
+                    ui!(stretch; tasks_table_node => [
+                        _tasks_table_column_node { size: Size::fixed_width(width) },
+                    ]);
+                }
+                None => {
+                    ui!(stretch; tasks_table_node => [
+                        _tasks_table_last_column_node { size: AUTO_SIZE, flex_grow: 1.0 },
+                    ]);
+                }
+            }
+        }
+
+        TasksPaneLayout {
+            tasks_node,
+            tasks_view_mode_node,
+            tasks_tabs_node,
+            tasks_table_node,
+            tasks_scrollbar_node,
+        }
+    }
+
+    fn view<B>(
+        &self,
+        state: &mut AppState,
+        frame: &mut Frame<B>,
+        context: &mut ViewContext,
+        layout: &TasksPaneLayout,
+    ) where
+        B: Backend,
+    {
+        let (theme, glyph_set) = (context.theme, context.glyph_set);
+        let strings = state.strings;
+        // Outlives the renderer, which the timeline's ruler borrows it through.
+        let time_ago_label = |ago| time_ago_label(strings, ago);
+        let mut renderer = Renderer::new();
+        let task_count = state.snapshot.tasks.len();
+        let tab_task_counts = TasksTab::ALL.map(|tasks_tab| state.task_count(tasks_tab));
+        let filtered_count = tab_task_counts[state.tasks_tab.index() as usize];
+        let footer_key = (
+            task_count,
+            filtered_count,
+            strings.language,
+            glyph_set.separator,
+        );
+        state.tasks_footer_label.update(footer_key, |label| {
+            write!(
+                label,
+                "{} {} {} {}",
+                format::count(task_count, &strings.tasks_footer_task_count),
+                glyph_set.separator,
+                strings.tasks_footer_filtered_count,
+                format::grouped_digits(filtered_count)
+            )
+            .unwrap()
+        });
+        // When the tabs don't all fit with their counts, they go without, and the footer is left
+        // to say how many tasks the selected one has. Any that still don't fit are cut short.
+        let tasks_tabs_width = context.stretch.layout(layout.tasks_tabs_node).size.width as u16;
+        let counted_tab_labels: Vec<_> = tab_task_counts
+            .iter()
+            .enumerate()
+            .map(|(tab_index, &tab_task_count)| {
+                format!(
+                    "{}{} ({})",
+                    glyph_set.tasks_tab_icons[tab_index],
+                    strings.tasks_tabs[tab_index],
+                    tab_task_count
+                )
+            })
+            .collect();
+        let counted_tab_labels: Vec<_> = counted_tab_labels.iter().map(String::as_str).collect();
+        let tab_counts_shown = SegmentedControl::new(
+            &counted_tab_labels,
+            state.tasks_tab.index(),
+            theme.tasks_filter_bg,
+            theme.tasks_filter_fg,
+        )
+        .preferred_width()
+            <= tasks_tabs_width;
+        for (tab_index, &tab_task_count) in tab_task_counts.iter().enumerate() {
+            let icon = glyph_set.tasks_tab_icons[tab_index];
+            let key = (tab_task_count, strings.language, icon, tab_counts_shown);
+            state.tasks_tab_labels[tab_index].update(key, |label| {
+                write!(label, "{}{}", icon, strings.tasks_tabs[tab_index]).unwrap();
+                if tab_counts_shown {
+                    write!(label, " ({})", tab_task_count).unwrap();
+                }
+            });
+        }
+        let mut tasks_box_frame =
+            pane_box_frame(strings.tasks, theme.tasks_box_fg, context.glyph_set, theme)
+                .footer_label(state.tasks_footer_label.label());
+        let name_filter_label = state
+            .task_name_filter
+            .as_ref()
+            .map(|name| strings::fill(strings.tasks_name_filter, &[name]));
+        let tasks_aux_labels: Vec<_> = name_filter_label
+            .as_deref()
+            .into_iter()
+            .chain(
+                [
+                    (state.group_tasks_by_name, strings.tasks_grouped),
+                    (state.follow_tasks, strings.tasks_following),
+                ]
+                .iter()
+                .filter(|&&(shown, _)| shown)
+                .map(|&(_, label)| label),
+            )
+            .collect();
+        let tasks_aux_label = tasks_aux_labels.join(&format!(" {} ", glyph_set.separator));
+        if !tasks_aux_label.is_empty() {
+            tasks_box_frame = tasks_box_frame.aux_label(&tasks_aux_label);
+        }
+        renderer.build_node(layout.tasks_node, tasks_box_frame);
+
+        let tab_labels = state.tasks_tab_labels.each_ref().map(CachedLabel::label);
+        renderer.build_node(
+            layout.tasks_tabs_node,
+            SegmentedControl::new(
+                &tab_labels[..],
+                state.tasks_tab.index(),
+                theme.tasks_filter_bg,
+                theme.tasks_filter_fg,
+            )
+            .symbols(glyph_set.segmented_control),
+        );
+
+        context.identify(&mut renderer, layout.tasks_tabs_node, TASKS_TABS_ID);
+        renderer.build_node(
+            layout.tasks_view_mode_node,
+            view_mode_control(state.view_mode, glyph_set, theme),
+        );
+        context.identify(
+            &mut renderer,
+            layout.tasks_view_mode_node,
+            TASKS_VIEW_MODE_ID,
+        );
+        let tasks_table_rect =
+            world_rect(context.stretch, context.root, layout.tasks_table_node).unwrap();
+        // The rows borrow the tasks while the scroll state below changes, so this goes to the
+        // tasks field directly rather than through `AppState::visible_tasks`.
+        let selected_task_id = state.selected_task_id;
+        let tasks_table_rows = tasks_table_rows(
+            visible_tasks(
+                &state.snapshot.tasks,
+                state.tasks_tab,
+                &state.bookmarked_task_ids,
+                state.task_name_filter.as_deref(),
+                state.tasks_sort,
+            ),
+            state.group_tasks_by_name,
+            &state.expanded_task_groups,
+        );
+        let followed_row_index = match state.newest_task_id() {
+            Some(task_id) if state.follow_tasks => tasks_table_rows
+                .iter()
+                .position(|tasks_table_row| tasks_table_row.contains_task(task_id)),
+            _ => None,
+        };
+        // The header row isn't part of the scrollable content, so it stays put while the body
+        // scrolls.
+        let tasks_viewport_length = tasks_table_rect.height.saturating_sub(1) as usize;
+        state
+            .tasks_scroll_state
+            .set_lengths(tasks_table_rows.len(), tasks_viewport_length);
+        if let Some(followed_row_index) = followed_row_index {
+            state.tasks_scroll_state.reveal(followed_row_index);
+        }
+        let tasks_table_widths: Vec<_>;
+        if state.view_mode == ViewMode::Timeline {
+            // The timeline has rows to click on, but no columns.
+            state.tasks_table_columns.clear();
+            let oldest = state.oldest_task_lifetime();
+            let bar_width = tasks_table_rect
+                .width
+                .saturating_sub(TASKS_TIMELINE_LABEL_WIDTH + 1);
+            let column_span = state
+                .timeline_view
+                .fitted_column_span(oldest, bar_width as usize);
+            let timeline_rows: Vec<_> = tasks_table_rows
+                .into_iter()
+                .enumerate()
+                .skip(state.tasks_scroll_state.offset())
+                .take(tasks_viewport_length)
+                .map(|(row_index, tasks_table_row)| {
+                    let selected = matches!(
+                        tasks_table_row,
+                        TasksTableRow::Task { task, .. } if Some(task.id) == selected_task_id
+                    );
+                    let row = tasks_timeline_row(
+                        tasks_table_row,
+                        &state.bookmarked_task_ids,
+                        glyph_set,
+                        theme,
+                    );
+                    // Stripes and the selection look the way they do in the table.
+                    match theme.tasks_table_stripe_bg {
+                        _ if selected => {
+                            row.style(TuiStyle::default().bg(theme.tasks_table_selected_bg))
+                        }
+                        Some(stripe_bg) if row_index % 2 == 1 => {
+                            row.style(TuiStyle::default().bg(stripe_bg))
+                        }
+                        _ => row,
+                    }
+                })
+                .collect();
+            renderer.build_node(
+                layout.tasks_table_node,
+                Timeline::new(timeline_rows, column_span)
+                    .offset(state.timeline_view.offset)
+                    .header(tasks_timeline_header(strings, theme))
+                    .ruler(
+                        &time_ago_label,
+                        TuiStyle::default().fg(theme.tasks_table_minor_cell_color),
+                    )
+                    .label_width(TASKS_TIMELINE_LABEL_WIDTH)
+                    .styles(
+                        TuiStyle::default().fg(theme.tasks_table_minor_cell_color),
+                        TuiStyle::default().fg(theme.tasks_table_status_running_color),
+                    ),
+            );
+        } else if state.view_mode == ViewMode::Graph {
+            // Like the timeline, the graph has no columns.
+            state.tasks_table_columns.clear();
+            let minor_style = TuiStyle::default().fg(theme.tasks_table_minor_cell_color);
+            let (wait_for_nodes, edges) = wait_for_graph(
+                &visible_tasks(
+                    &state.snapshot.tasks,
+                    state.tasks_tab,
+                    &state.bookmarked_task_ids,
+                    state.task_name_filter.as_deref(),
+                    state.tasks_sort,
+                ),
+                &state.snapshot.resources,
+            );
+            if wait_for_nodes.is_empty() {
+                renderer.build_node(
+                    layout.tasks_table_node,
+                    Paragraph::new(Span::styled(strings.tasks_graph_empty, minor_style)),
+                );
+            } else {
+                let graph_nodes = wait_for_nodes
+                    .into_iter()
+                    .map(|wait_for_node| match wait_for_node {
+                        WaitForNode::Task(task) => {
+                            let bookmarked = state.bookmarked_task_ids.contains(&task.id);
+                            let graph_node = GraphNode::new(tasks_graph_task_label(
+                                task, bookmarked, glyph_set, theme,
+                            ));
+                            if Some(task.id) == selected_task_id {
+                                graph_node
+                                    .style(TuiStyle::default().bg(theme.tasks_table_selected_bg))
+                            } else {
+                                graph_node
+                            }
+                        }
+                        WaitForNode::Resource(resource) => GraphNode::new(Spans::from(vec![
+                            Span::styled(&resource.kind[..], minor_style),
+                            Span::raw(" "),
+                            Span::styled(
+                                &resource.name[..],
+                                TuiStyle::default().fg(theme.tasks_table_name_cell_color),
+                            ),
+                        ])),
+                    })
+                    .collect();
+                renderer.build_node(
+                    layout.tasks_table_node,
+                    GraphView::new(graph_nodes, edges).edge_style(minor_style),
+                );
+            }
+        } else {
+            // Columns after the frozen ones scroll horizontally. Whatever width the columns
+            // scrolled out of view had goes to the last visible one so the table still fills the
+            // pane. There's a node for each shown column, and none for the hidden ones.
+            let shown_tasks_table_columns = state.shown_tasks_table_columns();
+            state.tasks_column_scroll_state.set_lengths(
+                shown_tasks_table_columns.len() - TASKS_TABLE_FROZEN_COLUMN_COUNT,
+                1,
+            );
+            let first_scrolled_column =
+                TASKS_TABLE_FROZEN_COLUMN_COUNT + state.tasks_column_scroll_state.offset();
+            let visible_shown_tasks_table_columns: Vec<_> = (0..TASKS_TABLE_FROZEN_COLUMN_COUNT)
+                .chain(first_scrolled_column..shown_tasks_table_columns.len())
+                .collect();
+            let visible_tasks_table_columns: Vec<_> = visible_shown_tasks_table_columns
+                .iter()
+                .map(|&shown_column| shown_tasks_table_columns[shown_column])
+                .collect();
+            let tasks_table_column_widths: Vec<_> = context
+                .stretch
+                .children(layout.tasks_table_node)
+                .iter()
+                .map(|&tasks_table_column_node| {
+                    context
+                        .stretch
+                        .layout(tasks_table_column_node)
+                        .to_rect()
+                        .width
+                })
+                .collect();
+            let tasks_table_total_width = context
+                .stretch
+                .layout(layout.tasks_table_node)
+                .to_rect()
+                .width;
+            let mut visible_tasks_table_column_widths = select_table_columns(
+                tasks_table_column_widths,
+                &visible_shown_tasks_table_columns,
+            );
+            let visible_tasks_table_width: u16 = visible_tasks_table_column_widths.iter().sum();
+            if let Some(last_column_width) = visible_tasks_table_column_widths.last_mut() {
+                *last_column_width += tasks_table_total_width - visible_tasks_table_width;
+            }
+            // On small terminals the layout can overflow the frame, and the table only gets the
+            // part that remains visible.
+            fit_table_column_widths(
+                &mut visible_tasks_table_column_widths,
+                tasks_table_rect
+                    .width
+                    .min(frame.size().width.saturating_sub(tasks_table_rect.x)),
+            );
+            tasks_table_widths = visible_tasks_table_column_widths
+                .iter()
+                .cloned()
+                .map(Constraint::Length)
+                .collect();
+            state.tasks_table_columns = visible_tasks_table_columns
+                .iter()
+                .cloned()
+                .zip(visible_tasks_table_column_widths.iter().cloned())
+                .collect();
+            // Only the rows in view are built, so that a frame takes as long with thousands of
+            // tasks as it does with a screenful.
+            let task_rows: Vec<_> = tasks_table_rows
+                .into_iter()
+                .enumerate()
+                .skip(state.tasks_scroll_state.offset())
+                .take(tasks_viewport_length)
+                .map(|(row_index, tasks_table_row)| {
+                    let (cells, task_id) = match tasks_table_row {
+                        TasksTableRow::Task { task, grouped } => {
+                            let bookmarked = state.bookmarked_task_ids.contains(&task.id);
+                            let cells = create_task_table_cells(
+                                task, grouped, bookmarked, glyph_set, theme,
+                            );
+                            (cells, Some(task.id))
+                        }
+                        TasksTableRow::Group(group) => (
+                            create_task_group_table_cells(&group, glyph_set, theme),
+                            None,
+                        ),
+                    };
+                    let row = Row::new(fit_task_table_cells(
+                        cells,
+                        &visible_tasks_table_columns,
+                        &visible_tasks_table_column_widths,
+                        glyph_set,
+                    ));
+                    if task_id.is_some() && task_id == selected_task_id {
+                        row.style(TuiStyle::default().bg(theme.tasks_table_selected_bg))
+                    } else {
+                        stripe_task_table_row(row, row_index, theme)
+                    }
+                })
+                .collect();
+            renderer.build_node(
+                layout.tasks_table_node,
+                Table::new(task_rows)
+                    .header(
+                        Row::new(fit_task_table_cells(
+                            tasks_table_header_cells(
+                                state.tasks_sort,
+                                strings,
+                                context.glyph_set,
+                                theme,
+                            ),
+                            &visible_tasks_table_columns,
+                            &visible_tasks_table_column_widths,
+                            glyph_set,
+                        ))
+                        .style(
+                            TuiStyle::default()
+                                .add_modifier(Modifier::BOLD)
+                                .fg(theme.tasks_table_header_fg),
+                        ),
+                    )
+                    .widths(&tasks_table_widths),
+            );
+        }
+        context.identify(&mut renderer, layout.tasks_table_node, TASKS_TABLE_ID);
+        renderer.build_node(
+            layout.tasks_scrollbar_node,
+            Scrollbar::new(
+                &mut state.tasks_scroll_state,
+                context.glyph_set.scrollbar,
+                theme.scrollbar_color,
+            ),
+        );
+        context.identify(
+            &mut renderer,
+            layout.tasks_scrollbar_node,
+            TASKS_SCROLLBAR_ID,
+        );
+
+        context.render(frame, &mut renderer, layout.tasks_node);
+    }
+
+    fn contains(&self, id: WidgetId) -> bool {
+        [
+            TASKS_TABS_ID,
+            TASKS_VIEW_MODE_ID,
+            TASKS_TABLE_ID,
+            TASKS_SCROLLBAR_ID,
+        ]
+        .contains(&id)
+    }
+
+    // Left and Right move the selection in whichever segmented control has the focus, rather
+    // than scrolling the table's columns. Clicking a row in the table selects its task, or
+    // expands or collapses its group, and clicking a header sorts by its column. The graph of who
+    // waits on what has neither rows nor headers.
+    fn handle_event(
+        &self,
+        state: &mut AppState,
+        event: &Event,
+        target: WidgetId,
+        phase: Phase,
+    ) -> Propagation {
+        if phase != Phase::Target {
+            return Propagation::Continue;
+        }
+        let message =
+            match *event {
+                Event::Key(KeyEvent { code, .. }) if target == TASKS_TABS_ID => {
+                    step_index(state.tasks_tab.index(), TasksTab::ALL.len(), code)
+                        .and_then(TasksTab::from_index)
+                        .map(TasksPaneMessage::SelectTab)
+                }
+                Event::Key(KeyEvent { code, .. }) if target == TASKS_VIEW_MODE_ID => {
+                    step_index(state.view_mode.index(), ViewMode::ALL.len(), code)
+                        .and_then(ViewMode::from_index)
+                        .map(TasksPaneMessage::SelectViewMode)
+                }
+                Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    column,
+                    row,
+                    ..
+                }) if target == TASKS_SCROLLBAR_ID => Some(TasksPaneMessage::Click { column, row }),
+                Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    column,
+                    row,
+                    ..
+                }) if target == TASKS_TABLE_ID && state.view_mode != ViewMode::Graph => {
+                    match state.widget_registry.rect(TASKS_TABLE_ID) {
+                        Some(table_rect) if row == table_rect.y => state
+                            .tasks_table_column_at(column)
+                            .and_then(|table_column| TASKS_TABLE_COLUMNS[table_column].sort_key)
+                            .map(TasksPaneMessage::SortBy),
+                        _ => state.tasks_table_row_at(row).map(|tasks_table_row| {
+                            match tasks_table_row {
+                                TasksTableRow::Task { task, .. } => {
+                                    TasksPaneMessage::SelectTask(task.id)
+                                }
+                                TasksTableRow::Group(group) => {
+                                    TasksPaneMessage::ToggleGroup(group.name.to_owned())
+                                }
+                            }
+                        }),
+                    }
+                }
+                _ => None,
+            };
+        match message {
+            Some(message) => {
+                self.update(state, message);
+                Propagation::Stop
+            }
+            None => Propagation::Continue,
+        }
+    }
+}
+
+// Notifications pane
+
+pub struct NotificationsPane;
+
+pub enum NotificationsPaneMessage {
+    /// Shows the notification center with the focus on the newest notification, or hides it.
+    Toggle,
+    /// Moves the highlight this many notifications older, or newer for negative counts,
+    /// stopping at the ends.
+    MoveSelection(isize),
+    /// Highlights a notification, counting from the newest.
+    Select(usize),
+    Dismiss,
+    Clear,
+    /// A left click anywhere on screen.
+    Click {
+        column: u16,
+        row: u16,
+    },
+}
+
+pub struct NotificationsPaneLayout {
+    notifications_node: Node,
+    notifications_list_node: Node,
+    notifications_scrollbar_node: Node,
+}
+
+impl Component for NotificationsPane {
+    type Message = NotificationsPaneMessage;
+    type Layout = NotificationsPaneLayout;
+
+    fn update(&self, state: &mut AppState, message: NotificationsPaneMessage) {
+        match message {
+            NotificationsPaneMessage::Toggle if state.notifications_open => {
+                state.notifications_open = false;
+                if state.focused.is_some_and(|id| self.contains(id)) {
+                    state.focused = None;
+                }
+            }
+            NotificationsPaneMessage::Toggle => {
+                state.notifications_open = true;
+                state.unread_notification_count = 0;
+                state.selected_notification = 0;
+                state.notifications_scroll_state.reveal(0);
+                state.focused = Some(NOTIFICATIONS_LIST_ID);
+            }
+            NotificationsPaneMessage::MoveSelection(notifications) => {
+                let last_index = state.notifications.len().saturating_sub(1);
+                state.selected_notification = (state.selected_notification as isize)
+                    .saturating_add(notifications)
+                    .clamp(0, last_index as isize)
+                    as usize;
+                state
+                    .notifications_scroll_state
+                    .reveal(state.selected_notification);
+            }
+            NotificationsPaneMessage::Select(index) => {
+                if index < state.notifications.len() {
+                    state.selected_notification = index;
+                }
+            }
+            NotificationsPaneMessage::Dismiss => state.dismiss_notification(),
+            NotificationsPaneMessage::Clear => state.clear_notifications(),
+            NotificationsPaneMessage::Click { column, row } => {
+                state.notifications_scroll_state.click(column, row);
+            }
+        }
+    }
+
+    fn layout(
+        &self,
+        _: &AppState,
+        _: &GlyphSet,
+        stretch: &mut LayoutTree,
+        parent: Node,
+    ) -> NotificationsPaneLayout {
+        ui!(stretch; parent => [
+            notifications_node { size: Size::fixed_height(NOTIFICATIONS_PANE_HEIGHT) } [
+                notifications_inner_container_node {
+                    size: AUTO_SIZE,
+                    padding: Rect::new(1, 1, 1, 1),
+                    flex_grow: 1.0,
+                } [
+                    notifications_list_node {
+                        size: AUTO_SIZE,
+                        margin: Rect::new(1, 1, 0, 0),
+                        flex_grow: 1.0,
+                    },
+                    notifications_scrollbar_node { size: Size::fixed_width(1) },
+                ],
+            ],
+        ]);
+
+        NotificationsPaneLayout {
+            notifications_node,
+            notifications_list_node,
+            notifications_scrollbar_node,
+        }
+    }
+
+    fn view<B>(
+        &self,
+        state: &mut AppState,
+        frame: &mut Frame<B>,
+        context: &mut ViewContext,
+        layout: &NotificationsPaneLayout,
+    ) where
+        B: Backend,
+    {
+        let theme = context.theme;
+        let mut renderer = Renderer::new();
+        let count_label = format::count(state.notifications.len(), &NOTIFICATION_COUNT_LABELS);
+        renderer.build_node(
+            layout.notifications_node,
+            pane_box_frame(
+                NOTIFICATIONS_LABEL,
+                theme.notifications_box_fg,
+                context.glyph_set,
+                theme,
+            )
+            .aux_label(&count_label)
+            .footer_label(NOTIFICATIONS_KEYS_LABEL),
+        );
+
+        // Newest first, with the kinds lined up.
+        let list_rect = world_rect(
+            context.stretch,
+            context.root,
+            layout.notifications_list_node,
+        )
+        .unwrap();
+        state
+            .notifications_scroll_state
+            .set_lengths(state.notifications.len(), list_rect.height as usize);
+        let kind_width = NOTIFICATION_KIND_LABELS
+            .iter()
+            .map(|label| display_width(label))
+            .max()
+            .unwrap_or(0) as usize;
+        let mut lines: Vec<_> = state
+            .notifications
+            .iter()
+            .rev()
+            .enumerate()
+            .skip(state.notifications_scroll_state.offset())
+            .take(list_rect.height as usize)
+            .map(|(index, notification)| {
+                let time_label = notification
+                    .raised_at
+                    .map(|raised_at| raised_at.format(NOTIFICATION_TIME_FORMAT).to_string())
+                    .unwrap_or_default();
+                let kind_color = match notification.kind {
+                    NotificationKind::Alert => theme.notification_alert_color,
+                    NotificationKind::Export => theme.notification_export_color,
+                    NotificationKind::Connection => theme.notification_connection_color,
+                };
+                let mut line = Spans::from(vec![
+                    Span::styled(
+                        format!("{} ", time_label),
+                        TuiStyle::default().fg(theme.notification_time_color),
+                    ),
+                    Span::styled(
+                        format!(
+                            "{:width$} ",
+                            NOTIFICATION_KIND_LABELS[notification.kind as usize],
+                            width = kind_width
+                        ),
+                        TuiStyle::default().fg(kind_color),
+                    ),
+                    Span::styled(
+                        notification.message.clone(),
+                        TuiStyle::default().fg(theme.notification_text_color),
+                    ),
+                ]);
+                if notification.kind == NotificationKind::Alert {
+                    line.0.insert(
+                        2,
+                        Span::styled(
+                            format!("{} ", context.glyph_set.performance_alert),
+                            TuiStyle::default().fg(kind_color),
+                        ),
+                    );
+                }
+                if index == state.selected_notification {
+                    let padding = (list_rect.width as usize).saturating_sub(line.width());
+                    line.0.push(Span::raw(" ".repeat(padding)));
+                    for span in &mut line.0 {
+                        span.style = span.style.bg(theme.tasks_table_selected_bg);
+                    }
+                }
+                line
+            })
+            .collect();
+        if lines.is_empty() {
+            lines.push(Spans::from(Span::styled(
+                NOTIFICATIONS_EMPTY_LABEL,
+                TuiStyle::default().fg(theme.notification_time_color),
+            )));
+        }
+        renderer.build_node(layout.notifications_list_node, Paragraph::new(lines));
+        context.identify(
+            &mut renderer,
+            layout.notifications_list_node,
+            NOTIFICATIONS_LIST_ID,
+        );
+        renderer.build_node(
+            layout.notifications_scrollbar_node,
+            Scrollbar::new(
+                &mut state.notifications_scroll_state,
+                context.glyph_set.scrollbar,
+                theme.scrollbar_color,
+            ),
+        );
+        context.identify(
+            &mut renderer,
+            layout.notifications_scrollbar_node,
+            NOTIFICATIONS_SCROLLBAR_ID,
+        );
+
+        context.render(frame, &mut renderer, layout.notifications_node);
+    }
+
+    fn contains(&self, id: WidgetId) -> bool {
+        id == NOTIFICATIONS_LIST_ID || id == NOTIFICATIONS_SCROLLBAR_ID
+    }
+
+    // With the list focused, Up and Down move the highlight and the pane's footer keys dismiss
+    // notifications. Clicking a notification highlights it.
+    fn handle_event(
+        &self,
+        state: &mut AppState,
+        event: &Event,
+        target: WidgetId,
+        phase: Phase,
+    ) -> Propagation {
+        if phase != Phase::Target {
+            return Propagation::Continue;
+        }
+        let message = match *event {
+            Event::Key(KeyEvent { code, .. }) if target == NOTIFICATIONS_LIST_ID => match code {
+                KeyCode::Up => Some(NotificationsPaneMessage::MoveSelection(-1)),
+                KeyCode::Down => Some(NotificationsPaneMessage::MoveSelection(1)),
+                KeyCode::Char('x') | KeyCode::Delete => Some(NotificationsPaneMessage::Dismiss),
+                KeyCode::Char('X') => Some(NotificationsPaneMessage::Clear),
+                _ => None,
+            },
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                ..
+            }) if target == NOTIFICATIONS_SCROLLBAR_ID => {
+                Some(NotificationsPaneMessage::Click { column, row })
+            }
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                row,
+                ..
+            }) => state
+                .widget_registry
+                .rect(NOTIFICATIONS_LIST_ID)
+                .map(|list_rect| {
+                    let index = state.notifications_scroll_state.offset()
+                        + row.saturating_sub(list_rect.y) as usize;
+                    NotificationsPaneMessage::Select(index)
+                }),
+            _ => None,
+        };
+        match message {
+            Some(message) => {
+                self.update(state, message);
+                Propagation::Stop
+            }
+            None => Propagation::Continue,
+        }
+    }
+}
+
+// Moves a segmented control's selection one segment left or right, stopping at the ends.
+fn step_index(index: u32, count: usize, code: KeyCode) -> Option<u32> {
+    match code {
+        KeyCode::Left => Some(index.saturating_sub(1)),
+        KeyCode::Right => Some((index + 1).min(count as u32 - 1)),
+        _ => None,
+    }
+}
+
+// Labels each column's header. While the table is sorted, the sorted column's header gets an
+// arrow, on the side away from where its values are aligned so that truncation trims the label
+// first, and the other headers are dimmed.
+fn tasks_table_header_cells(
+    sort: Option<TasksSort>,
+    strings: &'static Strings,
+    glyph_set: &GlyphSet,
+    theme: &Theme,
+) -> Vec<Spans<'static>> {
+    TASKS_TABLE_COLUMNS
+        .iter()
+        .zip(strings.tasks_table_columns.iter().copied())
+        .map(|(table_column, label)| match sort {
+            Some(sort) if table_column.sort_key == Some(sort.key) => {
+                let indicator = if sort.descending {
+                    glyph_set.sort_descending
+                } else {
+                    glyph_set.sort_ascending
+                };
+                let label = match table_column.alignment {
+                    Alignment::Right => format!("{} {}", indicator, label),
+                    _ => format!("{} {}", label, indicator),
+                };
+                Spans::from(label)
+            }
+            Some(_) => Spans::from(Span::styled(
+                label,
+                TuiStyle::default().fg(theme.tasks_table_unsorted_header_fg),
+            )),
+            None => Spans::from(label),
+        })
+        .collect()
+}
+
+fn view_mode_control<'a>(
+    view_mode: ViewMode,
+    glyph_set: &'a GlyphSet,
+    theme: &Theme,
+) -> SegmentedControl<'a> {
+    SegmentedControl::new(
+        &glyph_set.tasks_view_modes,
+        view_mode.index(),
+        theme.tasks_filter_bg,
+        theme.tasks_filter_fg,
+    )
+    .symbols(glyph_set.segmented_control)
+}
+
+// A row of the tasks timeline, labelled with the table's frozen columns after the button. A
+// group's bar starts when its oldest task was spawned, and is busy whenever any of its tasks
+// was.
+fn tasks_timeline_row<'a>(
+    tasks_table_row: TasksTableRow<'a>,
+    bookmarked_task_ids: &BTreeSet<u64>,
+    glyph_set: &GlyphSet,
+    theme: &Theme,
+) -> TimelineRow<'a> {
+    let (cells, started_ago, busy_periods) = match tasks_table_row {
+        TasksTableRow::Task { task, grouped } => {
+            let bookmarked = bookmarked_task_ids.contains(&task.id);
+            let cells = create_task_table_cells(task, grouped, bookmarked, glyph_set, theme);
+            (cells, task.lifetime(), task.busy_periods.iter().collect())
+        }
+        TasksTableRow::Group(group) => {
+            let busy_periods: Vec<_> = group
+                .tasks
+                .iter()
+                .flat_map(|task| &task.busy_periods)
+                .collect();
+            let cells = create_task_group_table_cells(&group, glyph_set, theme);
+            (cells, group.longest_lifetime(), busy_periods)
+        }
+    };
+    let mut label = vec![];
+    for cell in cells
+        .into_iter()
+        .skip(1)
+        .take(TASKS_TABLE_FROZEN_COLUMN_COUNT - 1)
+    {
+        if !label.is_empty() && !cell.0.is_empty() {
+            label.push(Span::raw(" "));
+        }
+        label.extend(cell.0);
+    }
+    TimelineRow::new(Spans::from(label), started_ago).busy_periods(
+        busy_periods
+            .into_iter()
+            .map(|busy_period: &BusyPeriod| (busy_period.started_ago, busy_period.ended_ago))
+            .collect(),
+    )
+}
+
+// A task's node in the graph of who waits on what: its status, then its ID and name, as the
+// table shows them.
+fn tasks_graph_task_label<'a>(
+    task: &'a Task,
+    bookmarked: bool,
+    glyph_set: &GlyphSet,
+    theme: &Theme,
+) -> Spans<'a> {
+    let mut cells = create_task_table_cells(task, false, bookmarked, glyph_set, theme)
+        .into_iter()
+        .skip(1);
+    let id_cell = cells.next().unwrap_or_default();
+    let name_cell = cells.next().unwrap_or_default();
+    let mut label = cells.next().unwrap_or_default().0;
+    label.push(Span::raw(" "));
+    label.extend(id_cell.0);
+    label.push(Span::raw(" "));
+    label.extend(name_cell.0);
+    Spans::from(label)
+}
+
+// The column titles over the labels.
+fn tasks_timeline_header(strings: &Strings, theme: &Theme) -> Spans<'static> {
+    Spans::from(Span::styled(
+        format!(
+            "{} {}",
+            strings.tasks_table_columns[1], strings.tasks_table_columns[2]
+        ),
+        TuiStyle::default()
+            .add_modifier(Modifier::BOLD)
+            .fg(theme.tasks_table_header_fg),
+    ))
+}
+
+// Task actions
+
+pub struct TaskActions;
+
+pub enum TaskActionsMessage {
+    /// Opens the action menu for a task.
+    OpenMenu(u64),
+    /// Moves the menu's highlight by this many items, stopping at the ends.
+    MoveSelection(isize),
+    /// Takes an action on the menu's task, or asks first if it's destructive.
+    Choose(TaskAction),
+    /// Takes the action the confirmation dialog asked about.
+    Confirm,
+    /// Closes the open popup without doing anything.
+    Close,
+    /// Takes back the action the toast is about, while the toast is showing.
+    Undo,
+}
+
+impl Component for TaskActions {
+    type Message = TaskActionsMessage;
+    type Layout = ();
+
+    fn update(&self, state: &mut AppState, message: TaskActionsMessage) {
+        match message {
+            TaskActionsMessage::OpenMenu(task_id) if state.task(task_id).is_some() => {
+                state.overlay = Some(Overlay::TaskActionMenu {
+                    task_id,
+                    selected_index: 0,
+                });
+            }
+            TaskActionsMessage::OpenMenu(_) => {}
+            TaskActionsMessage::MoveSelection(delta) => {
+                if let Some(Overlay::TaskActionMenu {
+                    ref mut selected_index,
+                    ..
+                }) = state.overlay
+                {
+                    *selected_index = (*selected_index as isize + delta).clamp(0, 2) as usize;
+                }
+            }
+            TaskActionsMessage::Choose(action) => {
+                let task_id = match state.overlay {
+                    Some(Overlay::TaskActionMenu { task_id, .. }) => task_id,
+                    _ => return,
+                };
+                if action.is_destructive() {
+                    state.overlay = Some(Overlay::ConfirmAbort { task_id });
+                } else {
+                    state.overlay = None;
+                    perform_task_action(state, task_id, action);
+                }
+            }
+            TaskActionsMessage::Confirm => {
+                if let Some(Overlay::ConfirmAbort { task_id }) = state.overlay {
+                    state.overlay = None;
+                    perform_task_action(state, task_id, TaskAction::Abort);
+                }
+            }
+            TaskActionsMessage::Close => state.overlay = None,
+            TaskActionsMessage::Undo => {
+                let undo = state.visible_toast().and_then(|toast| toast.undo.clone());
+                if let Some(undo) = undo {
+                    state.undo(undo);
+                    state.toast = None;
+                }
+            }
+        }
+    }
+
+    // Popups and toasts float over the panes, so they don't take part in the layout.
+    fn layout(&self, _: &AppState, _: &GlyphSet, _: &mut LayoutTree, _: Node) {}
+
+    fn view<B>(&self, state: &mut AppState, frame: &mut Frame<B>, context: &mut ViewContext, _: &())
+    where
+        B: Backend,
+    {
+        let theme = context.theme;
+        let bounds = frame.size();
+
+        // The toast goes in the row under the panes.
+        if let Some(toast) = state.visible_toast() {
+            let toast_style = TuiStyle::default().bg(theme.toast_bg).fg(theme.toast_fg);
+            let mut spans = vec![Span::raw(" ")];
+            if toast.kind == Some(NotificationKind::Alert) {
+                spans.push(Span::raw(context.glyph_set.performance_alert));
+                spans.push(Span::raw(" "));
+            }
+            spans.push(Span::raw(&toast.message[..]));
+            if toast.undo.is_some() {
+                spans.push(Span::raw(format!(" {} ", context.glyph_set.separator)));
+                spans.push(Span::styled(
+                    TOAST_UNDO_LABEL,
+                    TuiStyle::default().fg(theme.toast_key_color),
+                ));
+            }
+            frame.render_widget(
+                Paragraph::new(Spans::from(spans)).style(toast_style),
+                TuiRect::new(bounds.x, bounds.bottom() - 1, bounds.width, 1),
+            );
+        }
+
+        match state.overlay {
+            Some(Overlay::TaskActionMenu {
+                task_id,
+                selected_index,
+            }) => {
+                let actions = match state.task(task_id) {
+                    Some(task) => TaskAction::available(task),
+                    None => return,
+                };
+                let item_width = actions
+                    .iter()
+                    .map(|&action| display_width(TASK_ACTION_LABELS[action as usize]))
+                    .max()
+                    .unwrap_or(0);
+                // Each item has a cell of space either side, inside the popup's own padding.
+                let menu_width = (item_width + 6).max(display_width(TASK_ACTION_MENU_LABEL) + 6);
+                let anchor =
+                    tasks_table_row_rect(state, &context.registry, task_id).unwrap_or(bounds);
+                let menu_rect = place_popup(anchor, menu_width, actions.len() as u16 + 2, bounds);
+                let lines: Vec<_> = actions
+                    .iter()
+                    .enumerate()
+                    .map(|(index, &action)| {
+                        let mut style = TuiStyle::default().fg(theme.popup_text_color);
+                        if index == selected_index {
+                            style = style.bg(theme.popup_selected_bg);
+                        }
+                        let label = TASK_ACTION_LABELS[action as usize];
+                        Spans::from(Span::styled(
+                            format!(" {:width$} ", label, width = item_width as usize),
+                            style,
+                        ))
+                    })
+                    .collect();
+                draw_popup(
+                    frame,
+                    pane_box_frame(
+                        TASK_ACTION_MENU_LABEL,
+                        theme.popup_box_fg,
+                        context.glyph_set,
+                        theme,
+                    ),
+                    Paragraph::new(lines),
+                    menu_rect,
+                );
+                context.registry.insert(TASK_ACTION_MENU_ID, menu_rect);
+            }
+            Some(Overlay::ConfirmAbort { task_id }) => {
+                let task = match state.task(task_id) {
+                    Some(task) => task,
+                    None => return,
+                };
+                let message_style = TuiStyle::default().fg(theme.popup_text_color);
+                let lines = vec![
+                    Spans::from(vec![
+                        Span::styled(task.id.to_string(), message_style),
+                        Span::raw(" "),
+                        Span::styled(
+                            &task.name[..],
+                            TuiStyle::default().fg(theme.tasks_table_name_cell_color),
+                        ),
+                    ]),
+                    Spans::default(),
+                    Spans::from(Span::styled(CONFIRM_ABORT_MESSAGE_LABEL, message_style)),
+                ];
+                let dialog_width = CONFIRM_DIALOG_WIDTH.min(bounds.width);
+                let text_width = dialog_width.saturating_sub(4).max(1);
+                let text_height: u16 = lines
+                    .iter()
+                    .map(|line| wrapped_height(line, text_width))
+                    .sum();
+                let dialog_height = (text_height + 2).min(bounds.height);
+                let dialog_rect = TuiRect::new(
+                    bounds.x + (bounds.width - dialog_width) / 2,
+                    bounds.y + (bounds.height - dialog_height) / 2,
+                    dialog_width,
+                    dialog_height,
+                );
+                draw_popup(
+                    frame,
+                    pane_box_frame(
+                        CONFIRM_ABORT_LABEL,
+                        theme.error_box_fg,
+                        context.glyph_set,
+                        theme,
+                    )
+                    .footer_label(CONFIRM_ABORT_KEYS_LABEL),
+                    Paragraph::new(lines).wrap(Wrap { trim: true }),
+                    dialog_rect,
+                );
+                context.registry.insert(CONFIRM_DIALOG_ID, dialog_rect);
+            }
+            _ => {}
+        }
+    }
+
+    fn contains(&self, id: WidgetId) -> bool {
+        id == TASK_ACTION_MENU_ID || id == CONFIRM_DIALOG_ID
+    }
+
+    // Popups are modal, so this consumes every key and click while one is open. Clicking
+    // outside a popup closes it.
+    fn handle_event(
+        &self,
+        state: &mut AppState,
+        event: &Event,
+        _: WidgetId,
+        _: Phase,
+    ) -> Propagation {
+        let overlay = match state.overlay {
+            Some(
+                overlay @ Overlay::TaskActionMenu { .. } | overlay @ Overlay::ConfirmAbort { .. },
+            ) => overlay,
+            _ => return Propagation::Continue,
+        };
+        let message = match (overlay, *event) {
+            (_, Event::Resize(..)) => return Propagation::Continue,
+            (
+                Overlay::TaskActionMenu {
+                    task_id,
+                    selected_index,
+                },
+                Event::Key(KeyEvent { code, .. }),
+            ) => match code {
+                KeyCode::Up => Some(TaskActionsMessage::MoveSelection(-1)),
+                KeyCode::Down => Some(TaskActionsMessage::MoveSelection(1)),
+                KeyCode::Enter => state.task(task_id).map(|task| {
+                    TaskActionsMessage::Choose(TaskAction::available(task)[selected_index])
+                }),
+                KeyCode::Esc => Some(TaskActionsMessage::Close),
+                _ => None,
+            },
+            (Overlay::ConfirmAbort { .. }, Event::Key(KeyEvent { code, .. })) => match code {
+                KeyCode::Char('y') | KeyCode::Enter => Some(TaskActionsMessage::Confirm),
+                KeyCode::Char('n') | KeyCode::Esc => Some(TaskActionsMessage::Close),
+                _ => None,
+            },
+            (
+                overlay,
+                Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    column,
+                    row,
+                    ..
+                }),
+            ) => match (overlay, state.widget_registry.hit_test(column, row)) {
+                (Overlay::TaskActionMenu { task_id, .. }, Some(id))
+                    if id == TASK_ACTION_MENU_ID =>
+                {
+                    // Items start under the menu's top border.
+                    let menu_rect = state.widget_registry.rect(TASK_ACTION_MENU_ID).unwrap();
+                    let item_index = row.checked_sub(menu_rect.y + 1).map(usize::from);
+                    match (state.task(task_id), item_index) {
+                        (Some(task), Some(item_index)) => TaskAction::available(task)
+                            .get(item_index)
+                            .map(|&action| TaskActionsMessage::Choose(action)),
+                        _ => None,
+                    }
+                }
+                (_, Some(id)) if self.contains(id) => None,
+                _ => Some(TaskActionsMessage::Close),
+            },
+            _ => None,
+        };
+        if let Some(message) = message {
+            self.update(state, message);
+        }
+        Propagation::Stop
+    }
+}
+
+// Asks the main loop to put `text` on the clipboard, with a toast saying what was copied.
+fn copy_to_clipboard(state: &mut AppState, message: String, text: String) {
+    state.show_toast(message);
+    state.clipboard_request = Some(text);
+}
+
+// Does a task action and puts up a toast saying so, with a way to take it back.
+fn perform_task_action(state: &mut AppState, task_id: u64, action: TaskAction) {
+    let task_label = match state.task(task_id) {
+        Some(task) => format!("task {} ({})", task.id, task.name),
+        None => return,
+    };
+    let undo = state.perform_task_action(task_id, action);
+    state.toast = Some(Toast {
+        message: format!(
+            "{} {}",
+            TASK_ACTION_DONE_LABELS[action as usize], task_label
+        ),
+        kind: None,
+        undo,
+        expires_at: state
+            .now
+            .map(|now| now + chrono::Duration::seconds(TOAST_DURATION_SECONDS)),
+    });
+}
+
+// Where a task's row was drawn in this frame. For a task that's scrolled out of view or hidden
+// in a collapsed group, that's the whole table.
+fn tasks_table_row_rect(
+    state: &AppState,
+    registry: &WidgetRegistry,
+    task_id: u64,
+) -> Option<TuiRect> {
+    let table_rect = registry.rect(TASKS_TABLE_ID)?;
+    let row_index = state.tasks_table_rows().iter().position(|row| match *row {
+        TasksTableRow::Task { task, .. } => task.id == task_id,
+        TasksTableRow::Group(_) => false,
+    });
+    let viewport_length = table_rect.height.saturating_sub(1) as usize;
+    let offset = state.tasks_scroll_state.offset();
+    match row_index {
+        Some(row_index) if row_index >= offset && row_index - offset < viewport_length => {
+            Some(TuiRect::new(
+                table_rect.x,
+                table_rect.y + 1 + (row_index - offset) as u16,
+                table_rect.width,
+                1,
+            ))
+        }
+        _ => Some(table_rect),
+    }
+}
+
+// Clears the popup's area, so that nothing underneath shows through, and draws `content` inside
+// `box_frame`, with a cell of padding on either side.
+// Where `spawn_site` is, then the source around it, scrolled to keep the spawn in the middle.
+// Clears `rect` first, since it's drawn over the bottom of a popup.
+fn draw_spawn_site<B>(frame: &mut Frame<B>, spawn_site: &SpawnSite, rect: TuiRect, theme: &Theme)
+where
+    B: Backend,
+{
+    let minor_style = TuiStyle::default().fg(theme.popup_minor_color);
+    frame.render_widget(Clear, rect);
+    frame.render_widget(
+        Paragraph::new(Spans::from(vec![
+            Span::styled(format!("{} ", TASK_SPAWN_SITE_LABEL), minor_style),
+            Span::styled(
+                format!("{}:{}", spawn_site.file, spawn_site.line),
+                TuiStyle::default().fg(theme.popup_text_color),
+            ),
+        ])),
+        TuiRect::new(rect.x, rect.y, rect.width, 1),
+    );
+    let code_height = rect.height.saturating_sub(1);
+    let line_count = spawn_site.snippet.lines().count();
+    let marked_index = spawn_site.line.saturating_sub(spawn_site.first_line) as usize;
+    let first_line = marked_index
+        .saturating_sub(code_height as usize / 2)
+        .min(line_count.saturating_sub(code_height as usize));
+    let styles = CodeStyles {
+        text: TuiStyle::default().fg(theme.popup_text_color),
+        keyword: TuiStyle::default().fg(theme.code_keyword_color),
+        type_name: TuiStyle::default().fg(theme.code_type_color),
+        literal: TuiStyle::default().fg(theme.code_literal_color),
+        comment: TuiStyle::default().fg(theme.code_comment_color),
+        macro_name: TuiStyle::default().fg(theme.code_macro_color),
+        line_number: minor_style,
+        marked_line: TuiStyle::default().bg(theme.popup_selected_bg),
+    };
+    frame.render_widget(
+        CodeView::new(&spawn_site.snippet, spawn_site.first_line, styles)
+            .mark_line(spawn_site.line)
+            .scroll(first_line),
+        TuiRect::new(rect.x, rect.y + 1, rect.width, code_height),
+    );
+}
+
+fn draw_popup<B, W>(frame: &mut Frame<B>, box_frame: BoxFrame, content: W, rect: TuiRect)
+where
+    B: Backend,
+    W: Widget,
+{
+    frame.render_widget(Clear, rect);
+    frame.render_widget(box_frame, rect);
+    let content_rect = TuiRect::new(
+        rect.x + 2,
+        rect.y + 1,
+        rect.width.saturating_sub(4),
+        rect.height.saturating_sub(2),
+    );
+    frame.render_widget(content, content_rect);
+}
+
+// Task attributes
+
+pub struct TaskAttributes;
+
+pub enum TaskAttributesMessage {
+    /// Shows all of a task's attributes.
+    Open(u64),
+    /// Moves the highlight by this many attributes, stopping at the ends.
+    MoveSelection(isize),
+    /// Highlights an attribute by its index.
+    Select(usize),
+    /// Copies the highlighted attribute to the clipboard as `key=value`.
+    Copy,
+    /// Copies all of the task's attributes to the clipboard, a `key=value` line for each.
+    CopyAll,
+    Close,
+}
+
+impl Component for TaskAttributes {
+    type Message = TaskAttributesMessage;
+    type Layout = ();
+
+    fn update(&self, state: &mut AppState, message: TaskAttributesMessage) {
+        if let TaskAttributesMessage::Open(task_id) = message {
+            if state.task(task_id).is_some() {
+                state.overlay = Some(Overlay::TaskAttributes {
+                    task_id,
+                    selected_index: 0,
+                    first_index: 0,
+                });
+            }
+            return;
+        }
+        let (task_id, selected_index, first_index) = match state.overlay {
+            Some(Overlay::TaskAttributes {
+                task_id,
+                selected_index,
+                first_index,
+            }) => (task_id, selected_index, first_index),
+            _ => return,
+        };
+        let attribute_count = match state.task(task_id) {
+            Some(task) => task.attributes.len(),
+            None => return,
+        };
+        let selected_index = match message {
+            TaskAttributesMessage::Open(_) => unreachable!(),
+            TaskAttributesMessage::MoveSelection(delta) => (selected_index as isize + delta)
+                .clamp(0, attribute_count.saturating_sub(1) as isize)
+                as usize,
+            TaskAttributesMessage::Select(index) if index < attribute_count => index,
+            TaskAttributesMessage::Select(_) => return,
+            TaskAttributesMessage::Copy => {
+                let (key, value) = match state.task(task_id) {
+                    Some(task) if selected_index < attribute_count => {
+                        task.attributes[selected_index].clone()
+                    }
+                    _ => return,
+                };
+                let text = format!("{}={}", key, value);
+                copy_to_clipboard(state, format!("{} {}", COPIED_LABEL, text), text);
+                return;
+            }
+            TaskAttributesMessage::CopyAll => {
+                let text = match state.task(task_id) {
+                    Some(task) if attribute_count > 0 => task
+                        .attributes
+                        .iter()
+                        .map(|(key, value)| format!("{}={}\n", key, value))
+                        .collect(),
+                    _ => return,
+                };
+                let message = format!("{} {}", COPIED_ATTRIBUTES_LABEL, task_id);
+                copy_to_clipboard(state, message, text);
+                return;
+            }
+            TaskAttributesMessage::Close => {
+                state.overlay = None;
+                return;
+            }
+        };
+        // The view scrolls the highlight into sight when it next draws.
+        state.overlay = Some(Overlay::TaskAttributes {
+            task_id,
+            selected_index,
+            first_index,
+        });
+    }
+
+    // Like the other popups, this floats over the panes.
+    fn layout(&self, _: &AppState, _: &GlyphSet, _: &mut LayoutTree, _: Node) {}
+
+    fn view<B>(&self, state: &mut AppState, frame: &mut Frame<B>, context: &mut ViewContext, _: &())
+    where
+        B: Backend,
+    {
+        let (task_id, selected_index, first_index) = match state.overlay {
+            Some(Overlay::TaskAttributes {
+                task_id,
+                selected_index,
+                first_index,
+            }) => (task_id, selected_index, first_index),
+            _ => return,
+        };
+        let task = match state.task(task_id) {
+            Some(task) => task,
+            None => return,
+        };
+        let theme = context.theme;
+        let bounds = frame.size();
+
+        // Keep a row free above and below, so it's clear the popup is on top of something.
+        let popup_width = TASK_ATTRIBUTES_POPUP_WIDTH.min(bounds.width);
+        let text_width = popup_width.saturating_sub(4).max(1);
+        let lines = task_attribute_lines(task, selected_index, theme);
+        let heights: Vec<u16> = lines
+            .iter()
+            .map(|line| wrapped_height(line, text_width))
+            .collect();
+        let available_height = bounds.height.saturating_sub(4).max(1);
+        // The spawn site goes under the attributes, with a row for where it is and the rest for
+        // the source around it, in up to half of the room.
+        let spawn_site = task.spawn_site.as_ref();
+        let spawn_site_height = spawn_site.map_or(0, |spawn_site| {
+            (spawn_site.snippet.lines().count() as u16 + 1).min(available_height / 2)
+        });
+        let text_height = heights
+            .iter()
+            .sum::<u16>()
+            .max(1)
+            .min(available_height - spawn_site_height);
+        let first_index = scroll_into_view(&heights, first_index, selected_index, text_height);
+        let popup_height = (text_height + spawn_site_height + 2).min(bounds.height);
+        let popup_rect = TuiRect::new(
+            bounds.x + (bounds.width - popup_width) / 2,
+            bounds.y + (bounds.height - popup_height) / 2,
+            popup_width,
+            popup_height,
+        );
+
+        // Long task names would run into the popup's corner, so cut them short.
+        let separator = context.glyph_set.separator;
+        let label = format!(
+            "{} {} {} {}",
+            TASK_ATTRIBUTES_LABEL, separator, task.id, task.name
+        );
+        let label: String = truncate_spans(
+            Spans::from(label),
+            popup_width.saturating_sub(6),
+            context.glyph_set.ellipsis,
+        )
+        .0
+        .iter()
+        .map(|span| &span.content[..])
+        .collect();
+        let footer_label = format!(
+            "{}/{} {} {}",
+            (selected_index + 1).min(lines.len()),
+            lines.len(),
+            separator,
+            TASK_ATTRIBUTES_KEYS_LABEL
+        );
+        let content = if lines.is_empty() {
+            Paragraph::new(Span::styled(
+                TASK_ATTRIBUTES_EMPTY_LABEL,
+                TuiStyle::default().fg(theme.tasks_table_minor_cell_color),
+            ))
+        } else {
+            Paragraph::new(lines[first_index..].to_vec()).wrap(Wrap { trim: true })
+        };
+        draw_popup(
+            frame,
+            pane_box_frame(&label, theme.popup_box_fg, context.glyph_set, theme)
+                .footer_label(&footer_label),
+            content,
+            popup_rect,
+        );
+        context.registry.insert(TASK_ATTRIBUTES_ID, popup_rect);
+        if let Some(spawn_site) = spawn_site.filter(|_| spawn_site_height > 0) {
+            let spawn_site_rect = TuiRect::new(
+                popup_rect.x + 2,
+                popup_rect.y + 1 + text_height,
+                text_width,
+                spawn_site_height,
+            );
+            draw_spawn_site(frame, spawn_site, spawn_site_rect, theme);
+        }
+
+        // Attributes that name something elsewhere link to it, across every row they wrap onto.
+        let mut row = popup_rect.y + 1;
+        let text_bottom = popup_rect.y + 1 + text_height;
+        for (index, height) in heights.iter().enumerate().skip(first_index) {
+            if row >= text_bottom {
+                break;
+            }
+            let (key, value) = &task.attributes[index];
+            let url = attribute_link(key, value, state.request_link_template.as_deref());
+            if let Some(url) = url {
+                let area = TuiRect::new(
+                    popup_rect.x + 2,
+                    row,
+                    text_width,
+                    (*height).min(text_bottom - row),
+                );
+                context.hyperlinks.push(Hyperlink { area, url });
+            }
+            row += height;
+        }
+
+        state.overlay = Some(Overlay::TaskAttributes {
+            task_id,
+            selected_index,
+            first_index,
+        });
+    }
+
+    fn contains(&self, id: WidgetId) -> bool {
+        id == TASK_ATTRIBUTES_ID
+    }
+
+    // Modal, like the action menu. The wheel moves the highlight, clicking an attribute
+    // highlights it, and clicking outside the popup closes it.
+    fn handle_event(
+        &self,
+        state: &mut AppState,
+        event: &Event,
+        _: WidgetId,
+        _: Phase,
+    ) -> Propagation {
+        let message = match *event {
+            Event::Resize(..) => return Propagation::Continue,
+            Event::Key(KeyEvent { code, .. }) => match code {
+                KeyCode::Up => Some(TaskAttributesMessage::MoveSelection(-1)),
+                KeyCode::Down => Some(TaskAttributesMessage::MoveSelection(1)),
+                KeyCode::PageUp => Some(TaskAttributesMessage::MoveSelection(-10)),
+                KeyCode::PageDown => Some(TaskAttributesMessage::MoveSelection(10)),
+                KeyCode::Home => Some(TaskAttributesMessage::MoveSelection(isize::MIN / 2)),
+                KeyCode::End => Some(TaskAttributesMessage::MoveSelection(isize::MAX / 2)),
+                KeyCode::Char('c') | KeyCode::Enter => Some(TaskAttributesMessage::Copy),
+                KeyCode::Char('y') => Some(TaskAttributesMessage::CopyAll),
+                KeyCode::Char(' ') | KeyCode::Esc => Some(TaskAttributesMessage::Close),
+                _ => None,
+            },
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::ScrollUp,
+                ..
+            }) => Some(TaskAttributesMessage::MoveSelection(-1)),
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::ScrollDown,
+                ..
+            }) => Some(TaskAttributesMessage::MoveSelection(1)),
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                ..
+            }) => match state.widget_registry.hit_test(column, row) {
+                Some(id) if id == TASK_ATTRIBUTES_ID => {
+                    task_attribute_at(state, row).map(TaskAttributesMessage::Select)
+                }
+                _ => Some(TaskAttributesMessage::Close),
+            },
+            Event::Mouse(_) => None,
+        };
+        if let Some(message) = message {
+            self.update(state, message);
+        }
+        Propagation::Stop
+    }
+}
+
+// One line per attribute, which the popup wraps, with the highlighted one picked out.
+fn task_attribute_lines<'a>(
+    task: &'a Task,
+    selected_index: usize,
+    theme: &Theme,
+) -> Vec<Spans<'a>> {
+    task.attributes
+        .iter()
+        .enumerate()
+        .map(|(index, (key, value))| {
+            let mut key_style = TuiStyle::default().fg(theme.tasks_table_attribute_key_cell_color);
+            let mut minor_style = TuiStyle::default().fg(theme.tasks_table_minor_cell_color);
+            let mut value_style = TuiStyle::default().fg(theme.popup_text_color);
+            if index == selected_index {
+                key_style = key_style.bg(theme.popup_selected_bg);
+                minor_style = minor_style.bg(theme.popup_selected_bg);
+                value_style = value_style.bg(theme.popup_selected_bg);
+            }
+            Spans::from(vec![
+                Span::styled(&key[..], key_style),
+                Span::styled("=", minor_style),
+                Span::styled(&value[..], value_style),
+            ])
+        })
+        .collect()
+}
+
+// The first entry to show so that the selected one fits in `height` rows, moving no further
+// from `first_index` than needed. Entries are `heights` rows tall.
+fn scroll_into_view(
+    heights: &[u16],
+    first_index: usize,
+    selected_index: usize,
+    height: u16,
+) -> usize {
+    if selected_index >= heights.len() {
+        return 0;
+    }
+    let mut first_index = first_index.min(selected_index);
+    while first_index < selected_index
+        && heights[first_index..=selected_index].iter().sum::<u16>() > height
+    {
+        first_index += 1;
+    }
+    first_index
+}
+
+// The attribute drawn in a row of the popup, as of the last frame.
+fn task_attribute_at(state: &AppState, row: u16) -> Option<usize> {
+    let (task_id, first_index) = match state.overlay {
+        Some(Overlay::TaskAttributes {
+            task_id,
+            first_index,
+            ..
+        }) => (task_id, first_index),
+        _ => return None,
+    };
+    let task = state.task(task_id)?;
+    let popup_rect = state.widget_registry.rect(TASK_ATTRIBUTES_ID)?;
+    // Attributes start under the popup's top border, inside its padding.
+    let mut row = row.checked_sub(popup_rect.y + 1)?;
+    let text_width = popup_rect.width.saturating_sub(4).max(1);
+    let lines = task_attribute_lines(task, usize::MAX, state.theme);
+    for (index, line) in lines.iter().enumerate().skip(first_index) {
+        let height = wrapped_height(line, text_width);
+        if row < height {
+            return Some(index);
+        }
+        row -= height;
+    }
+    None
+}
+
+// Column settings
+
+pub struct ColumnSettings;
+
+pub enum ColumnSettingsMessage {
+    Open,
+    /// Moves the highlight by this many columns, stopping at the ends.
+    MoveSelection(isize),
+    /// Hides or shows the column at this index in the popup's list.
+    Toggle(usize),
+    Close,
+}
+
+impl Component for ColumnSettings {
+    type Message = ColumnSettingsMessage;
+    type Layout = ();
+
+    fn update(&self, state: &mut AppState, message: ColumnSettingsMessage) {
+        let item_count = TASKS_TABLE_COLUMNS.len() - TASKS_TABLE_FROZEN_COLUMN_COUNT;
+        match message {
+            ColumnSettingsMessage::Open => {
+                state.overlay = Some(Overlay::ColumnSettings { selected_index: 0 })
+            }
+            ColumnSettingsMessage::MoveSelection(delta) => {
+                if let Some(Overlay::ColumnSettings {
+                    ref mut selected_index,
+                }) = state.overlay
+                {
+                    *selected_index = (*selected_index as isize + delta)
+                        .clamp(0, item_count as isize - 1)
+                        as usize;
+                }
+            }
+            ColumnSettingsMessage::Toggle(index) if index < item_count => {
+                state.overlay = Some(Overlay::ColumnSettings {
+                    selected_index: index,
+                });
+                state.toggle_tasks_table_column(TASKS_TABLE_FROZEN_COLUMN_COUNT + index);
+            }
+            ColumnSettingsMessage::Toggle(_) => {}
+            ColumnSettingsMessage::Close => state.overlay = None,
+        }
+    }
+
+    // Like the other popups, this floats over the panes.
+    fn layout(&self, _: &AppState, _: &GlyphSet, _: &mut LayoutTree, _: Node) {}
+
+    fn view<B>(&self, state: &mut AppState, frame: &mut Frame<B>, context: &mut ViewContext, _: &())
+    where
+        B: Backend,
+    {
+        let selected_index = match state.overlay {
+            Some(Overlay::ColumnSettings { selected_index }) => selected_index,
+            _ => return,
+        };
+        let bounds = frame.size();
+        let items: Vec<_> = (TASKS_TABLE_FROZEN_COLUMN_COUNT..TASKS_TABLE_COLUMNS.len())
+            .map(|table_column| {
+                (
+                    state.strings.tasks_table_columns[table_column],
+                    state.tasks_table_columns_shown[table_column],
+                )
+            })
+            .collect();
+        // Opens under the table's header, where the columns are.
+        let anchor = match context.registry.rect(TASKS_TABLE_ID) {
+            Some(table_rect) => TuiRect::new(table_rect.x, table_rect.y, table_rect.width, 1),
+            None => bounds,
+        };
+        draw_checklist_popup(
+            frame,
+            context,
+            COLUMN_SETTINGS_ID,
+            [COLUMN_SETTINGS_LABEL, COLUMN_SETTINGS_KEYS_LABEL],
+            &items,
+            selected_index,
+            anchor,
+        );
+    }
+
+    fn contains(&self, id: WidgetId) -> bool {
+        id == COLUMN_SETTINGS_ID
+    }
+
+    // Modal, like the other popups. Clicking a column hides or shows it, and clicking outside
+    // the popup closes it.
+    fn handle_event(
+        &self,
+        state: &mut AppState,
+        event: &Event,
+        _: WidgetId,
+        _: Phase,
+    ) -> Propagation {
+        let selected_index = match state.overlay {
+            Some(Overlay::ColumnSettings { selected_index }) => selected_index,
+            _ => return Propagation::Continue,
+        };
+        let message = match *event {
+            Event::Resize(..) => return Propagation::Continue,
+            Event::Key(KeyEvent { code, .. }) => match code {
+                KeyCode::Up => Some(ColumnSettingsMessage::MoveSelection(-1)),
+                KeyCode::Down => Some(ColumnSettingsMessage::MoveSelection(1)),
+                KeyCode::Char(' ') | KeyCode::Enter => {
+                    Some(ColumnSettingsMessage::Toggle(selected_index))
+                }
+                KeyCode::Char('c') | KeyCode::Esc => Some(ColumnSettingsMessage::Close),
+                _ => None,
+            },
+            Event::Mouse(mouse_event) => checklist_click(state, &mouse_event, COLUMN_SETTINGS_ID)
+                .map(|click| match click {
+                    Some(index) => ColumnSettingsMessage::Toggle(index),
+                    None => ColumnSettingsMessage::Close,
+                }),
+        };
+        if let Some(message) = message {
+            self.update(state, message);
+        }
+        Propagation::Stop
+    }
+}
+
+// Performance settings
+
+pub struct PerformanceSettings;
+
+pub enum PerformanceSettingsMessage {
+    Open,
+    /// Moves the highlight by this many metrics, stopping at the ends.
+    MoveSelection(isize),
+    /// Hides or shows the metric at this index in the popup's list.
+    Toggle(usize),
+    Close,
+}
+
+impl Component for PerformanceSettings {
+    type Message = PerformanceSettingsMessage;
+    type Layout = ();
+
+    fn update(&self, state: &mut AppState, message: PerformanceSettingsMessage) {
+        match message {
+            PerformanceSettingsMessage::Open => {
+                state.overlay = Some(Overlay::PerformanceSettings { selected_index: 0 })
+            }
+            PerformanceSettingsMessage::MoveSelection(delta) => {
+                if let Some(Overlay::PerformanceSettings {
+                    ref mut selected_index,
+                }) = state.overlay
+                {
+                    *selected_index = (*selected_index as isize + delta)
+                        .clamp(0, PERFORMANCE_METRICS.len() as isize - 1)
+                        as usize;
+                }
+            }
+            PerformanceSettingsMessage::Toggle(index) if index < PERFORMANCE_METRICS.len() => {
+                state.overlay = Some(Overlay::PerformanceSettings {
+                    selected_index: index,
+                });
+                state.toggle_performance_metric(index);
+            }
+            PerformanceSettingsMessage::Toggle(_) => {}
+            PerformanceSettingsMessage::Close => state.overlay = None,
+        }
+    }
+
+    // Like the other popups, this floats over the panes.
+    fn layout(&self, _: &AppState, _: &GlyphSet, _: &mut LayoutTree, _: Node) {}
+
+    fn view<B>(&self, state: &mut AppState, frame: &mut Frame<B>, context: &mut ViewContext, _: &())
+    where
+        B: Backend,
+    {
+        let selected_index = match state.overlay {
+            Some(Overlay::PerformanceSettings { selected_index }) => selected_index,
+            _ => return,
+        };
+        let bounds = frame.size();
+        let items: Vec<_> = state
+            .strings
+            .performance_metrics
+            .iter()
+            .zip(state.performance_metrics_shown.iter())
+            .map(|(&label, &shown)| (label, shown))
+            .collect();
+        // Opens under the graphs, which share a row with the expand button.
+        let anchor = match context.registry.rect(PERFORMANCE_EXPAND_BUTTON_ID) {
+            Some(button_rect) => TuiRect::new(
+                bounds.x + 1,
+                button_rect.y,
+                button_rect.right().saturating_sub(bounds.x + 1),
+                1,
+            ),
+            None => bounds,
+        };
+        draw_checklist_popup(
+            frame,
+            context,
+            PERFORMANCE_SETTINGS_ID,
+            [
+                state.strings.performance_settings,
+                state.strings.performance_settings_keys,
+            ],
+            &items,
+            selected_index,
+            anchor,
+        );
+    }
+
+    fn contains(&self, id: WidgetId) -> bool {
+        id == PERFORMANCE_SETTINGS_ID
+    }
+
+    // Modal, like the other popups. Clicking a metric hides or shows it, and clicking outside
+    // the popup closes it.
+    fn handle_event(
+        &self,
+        state: &mut AppState,
+        event: &Event,
+        _: WidgetId,
+        _: Phase,
+    ) -> Propagation {
+        let selected_index = match state.overlay {
+            Some(Overlay::PerformanceSettings { selected_index }) => selected_index,
+            _ => return Propagation::Continue,
+        };
+        let message = match *event {
+            Event::Resize(..) => return Propagation::Continue,
+            Event::Key(KeyEvent { code, .. }) => match code {
+                KeyCode::Up => Some(PerformanceSettingsMessage::MoveSelection(-1)),
+                KeyCode::Down => Some(PerformanceSettingsMessage::MoveSelection(1)),
+                KeyCode::Char(' ') | KeyCode::Enter => {
+                    Some(PerformanceSettingsMessage::Toggle(selected_index))
+                }
+                KeyCode::Char('m') | KeyCode::Esc => Some(PerformanceSettingsMessage::Close),
+                _ => None,
+            },
+            Event::Mouse(mouse_event) => {
+                checklist_click(state, &mouse_event, PERFORMANCE_SETTINGS_ID).map(|click| {
+                    match click {
+                        Some(index) => PerformanceSettingsMessage::Toggle(index),
+                        None => PerformanceSettingsMessage::Close,
+                    }
+                })
+            }
+        };
+        if let Some(message) = message {
+            self.update(state, message);
+        }
+        Propagation::Stop
+    }
+}
+
+// Draws a popup listing things that can be shown or hidden, each with a checkbox, with the item
+// at `selected_index` highlighted. `labels` are the popup's title and the keys along its bottom.
+fn draw_checklist_popup<B>(
+    frame: &mut Frame<B>,
+    context: &mut ViewContext,
+    id: WidgetId,
+    labels: [&str; 2],
+    items: &[(&str, bool)],
+    selected_index: usize,
+    anchor: TuiRect,
+) where
+    B: Backend,
+{
+    let theme = context.theme;
+    let glyph_set = context.glyph_set;
+    let lines: Vec<_> = items
+        .iter()
+        .enumerate()
+        .map(|(index, &(label, checked))| {
+            let checkbox = if checked {
+                glyph_set.checkbox_checked
+            } else {
+                glyph_set.checkbox_unchecked
+            };
+            let mut style = TuiStyle::default().fg(theme.popup_text_color);
+            if index == selected_index {
+                style = style.bg(theme.popup_selected_bg);
+            }
+            Spans::from(Span::styled(format!(" {} {} ", checkbox, label), style))
+        })
+        .collect();
+    // The popup is wide enough for its labels, and for every item to highlight the same width.
+    let item_width = lines
+        .iter()
+        .map(|line| line.width() as u16)
+        .max()
+        .unwrap_or(0);
+    let lines: Vec<_> = lines
+        .into_iter()
+        .map(|line| align_spans(line, item_width, Alignment::Left))
+        .collect();
+    let popup_width = [
+        item_width,
+        display_width(labels[0]),
+        display_width(labels[1]),
+    ]
+    .iter()
+    .max()
+    .unwrap()
+        + 6;
+    let popup_rect = place_popup(anchor, popup_width, lines.len() as u16 + 2, frame.size());
+    draw_popup(
+        frame,
+        pane_box_frame(labels[0], theme.popup_box_fg, glyph_set, theme).footer_label(labels[1]),
+        Paragraph::new(lines),
+        popup_rect,
+    );
+    context.registry.insert(id, popup_rect);
+}
+
+// What a mouse event does to a checklist popup: `Some(Some(index))` for a click on an item,
+// `Some(None)` for a click outside the popup, and `None` for anything else.
+fn checklist_click(state: &AppState, event: &MouseEvent, id: WidgetId) -> Option<Option<usize>> {
+    match *event {
+        MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            ..
+        } => match state.widget_registry.hit_test(column, row) {
+            Some(hit_id) if hit_id == id => {
+                // Items start under the popup's top border.
+                let popup_rect = state.widget_registry.rect(id).unwrap();
+                row.checked_sub(popup_rect.y + 1)
+                    .map(|index| Some(index as usize))
+            }
+            _ => Some(None),
+        },
+        _ => None,
+    }
+}
+
+// Command palette
+
+pub struct CommandPalette;
+
+pub enum CommandPaletteMessage {
+    /// Opens the palette with an empty search.
+    Open,
+    /// Types a character into the search, and highlights the best match.
+    Type(char),
+    /// Deletes the character before the search's cursor.
+    DeleteBackward,
+    /// Deletes the character after the search's cursor.
+    DeleteForward,
+    /// Moves the search's cursor by this many characters, stopping at the ends.
+    MoveCursor(isize),
+    /// Moves the highlight by this many matches, stopping at the ends.
+    MoveSelection(isize),
+    /// Closes the palette and takes the action of the match at this index.
+    Choose(usize),
+    Close,
+}
+
+// An action the palette lists, with the characters of its label that matched the search.
+struct CommandPaletteMatch {
+    action: Action,
+    label: String,
+    positions: Vec<usize>,
+}
+
+impl Component for CommandPalette {
+    type Message = CommandPaletteMessage;
+    type Layout = ();
+
+    fn update(&self, state: &mut AppState, message: CommandPaletteMessage) {
+        if let CommandPaletteMessage::Open = message {
+            state.command_palette_input.clear();
+            state.overlay = Some(Overlay::CommandPalette { selected_index: 0 });
+            return;
+        }
+        let selected_index = match state.overlay {
+            Some(Overlay::CommandPalette { selected_index }) => selected_index,
+            _ => return,
+        };
+        let input = &mut state.command_palette_input;
+        let selected_index = match message {
+            CommandPaletteMessage::Open => unreachable!(),
+            CommandPaletteMessage::Type(character) => {
+                input.insert(character);
+                0
+            }
+            CommandPaletteMessage::DeleteBackward => {
+                input.delete_backward();
+                0
+            }
+            CommandPaletteMessage::DeleteForward => {
+                input.delete_forward();
+                0
+            }
+            CommandPaletteMessage::MoveCursor(characters) => {
+                input.move_cursor(characters);
+                selected_index
+            }
+            CommandPaletteMessage::MoveSelection(delta) => {
+                let match_count = command_palette_matches(state).len();
+                (selected_index as isize + delta).clamp(0, match_count.saturating_sub(1) as isize)
+                    as usize
+            }
+            CommandPaletteMessage::Choose(index) => {
+                let action = command_palette_matches(state)
+                    .get(index)
+                    .map(|palette_match| palette_match.action);
+                state.overlay = None;
+                if let Some(action) = action {
+                    perform_action(state, action);
+                }
+                return;
+            }
+            CommandPaletteMessage::Close => {
+                state.overlay = None;
+                return;
+            }
+        };
+        state.overlay = Some(Overlay::CommandPalette { selected_index });
+    }
+
+    // Like the other popups, this floats over the panes.
+    fn layout(&self, _: &AppState, _: &GlyphSet, _: &mut LayoutTree, _: Node) {}
+
+    fn view<B>(&self, state: &mut AppState, frame: &mut Frame<B>, context: &mut ViewContext, _: &())
+    where
+        B: Backend,
+    {
+        let selected_index = match state.overlay {
+            Some(Overlay::CommandPalette { selected_index }) => selected_index,
+            _ => return,
+        };
+        let theme = context.theme;
+        let bounds = frame.size();
+
+        // The search, a blank row, and then the matches, scrolled to keep the highlighted one
+        // in view.
+        let palette_width = COMMAND_PALETTE_WIDTH.min(bounds.width);
+        let palette_height = (COMMAND_PALETTE_ROW_COUNT + 4).min(bounds.height);
+        let palette_rect = TuiRect::new(
+            bounds.x + (bounds.width - palette_width) / 2,
+            bounds.y + (bounds.height - palette_height) / 4,
+            palette_width,
+            palette_height,
+        );
+        let row_count = palette_height.saturating_sub(4) as usize;
+        let item_width = palette_width.saturating_sub(4);
+        let first_index = (selected_index + 1).saturating_sub(row_count);
+        let text_style = TuiStyle::default().fg(theme.popup_text_color);
+        let match_style = TuiStyle::default()
+            .fg(theme.popup_match_color)
+            .add_modifier(Modifier::BOLD);
+        let matches = command_palette_matches(state);
+        let mut lines: Vec<_> = matches
+            .iter()
+            .enumerate()
+            .skip(first_index)
+            .take(row_count)
+            .map(|(index, palette_match)| {
+                let mut spans = vec![Span::raw(" ")];
+                spans.extend(palette_match.label.chars().enumerate().map(
+                    |(position, character)| {
+                        let style = if palette_match.positions.contains(&position) {
+                            match_style
+                        } else {
+                            text_style
+                        };
+                        Span::styled(character.to_string(), style)
+                    },
+                ));
+                let mut line = align_spans(
+                    truncate_spans(Spans::from(spans), item_width, context.glyph_set.ellipsis),
+                    item_width,
+                    Alignment::Left,
+                );
+                if index == selected_index {
+                    for span in &mut line.0 {
+                        span.style = span.style.bg(theme.popup_selected_bg);
+                    }
+                }
+                line
+            })
+            .collect();
+        if matches.is_empty() {
+            lines.push(Spans::from(Span::styled(
+                format!(" {}", COMMAND_PALETTE_EMPTY_LABEL),
+                TuiStyle::default().fg(theme.popup_minor_color),
+            )));
+        }
+        lines.insert(0, Spans::default());
+
+        draw_popup(
+            frame,
+            pane_box_frame(
+                COMMAND_PALETTE_LABEL,
+                theme.popup_box_fg,
+                context.glyph_set,
+                theme,
+            )
+            .footer_label(COMMAND_PALETTE_KEYS_LABEL),
+            Paragraph::new(lines),
+            palette_rect,
+        );
+        frame.render_widget(
+            TextInput::new(
+                &state.command_palette_input,
+                COMMAND_PALETTE_PLACEHOLDER_LABEL,
+                theme.popup_text_color,
+                theme.popup_minor_color,
+            ),
+            TuiRect::new(palette_rect.x + 3, palette_rect.y + 1, item_width - 1, 1),
+        );
+        context.registry.insert(COMMAND_PALETTE_ID, palette_rect);
+    }
+
+    fn contains(&self, id: WidgetId) -> bool {
+        id == COMMAND_PALETTE_ID
+    }
+
+    // Modal, like the other popups. Keys that type go into the search, clicking a match takes
+    // its action, and clicking outside the palette closes it.
+    fn handle_event(
+        &self,
+        state: &mut AppState,
+        event: &Event,
+        _: WidgetId,
+        _: Phase,
+    ) -> Propagation {
+        let selected_index = match state.overlay {
+            Some(Overlay::CommandPalette { selected_index }) => selected_index,
+            _ => return Propagation::Continue,
+        };
+        let page_length = COMMAND_PALETTE_ROW_COUNT as isize;
+        let message = match *event {
+            Event::Resize(..) => return Propagation::Continue,
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('p'),
+                modifiers: KeyModifiers::CONTROL,
+            }) => Some(CommandPaletteMessage::Close),
+            Event::Key(KeyEvent {
+                code: KeyCode::Char(character),
+                modifiers,
+            }) if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+                Some(CommandPaletteMessage::Type(character))
+            }
+            Event::Key(KeyEvent { code, .. }) => match code {
+                KeyCode::Backspace => Some(CommandPaletteMessage::DeleteBackward),
+                KeyCode::Delete => Some(CommandPaletteMessage::DeleteForward),
+                KeyCode::Left => Some(CommandPaletteMessage::MoveCursor(-1)),
+                KeyCode::Right => Some(CommandPaletteMessage::MoveCursor(1)),
+                KeyCode::Home => Some(CommandPaletteMessage::MoveCursor(isize::MIN)),
+                KeyCode::End => Some(CommandPaletteMessage::MoveCursor(isize::MAX)),
+                KeyCode::Up => Some(CommandPaletteMessage::MoveSelection(-1)),
+                KeyCode::Down => Some(CommandPaletteMessage::MoveSelection(1)),
+                KeyCode::PageUp => Some(CommandPaletteMessage::MoveSelection(-page_length)),
+                KeyCode::PageDown => Some(CommandPaletteMessage::MoveSelection(page_length)),
+                KeyCode::Enter => Some(CommandPaletteMessage::Choose(selected_index)),
+                KeyCode::Esc => Some(CommandPaletteMessage::Close),
+                _ => None,
+            },
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                ..
+            }) => match state.widget_registry.hit_test(column, row) {
+                Some(id) if id == COMMAND_PALETTE_ID => {
+                    // Matches start under the search and the blank row after it, scrolled
+                    // as they were drawn.
+                    let palette_rect = state.widget_registry.rect(COMMAND_PALETTE_ID).unwrap();
+                    let row_count = palette_rect.height.saturating_sub(4) as usize;
+                    let first_index = (selected_index + 1).saturating_sub(row_count);
+                    row.checked_sub(palette_rect.y + 3)
+                        .map(usize::from)
+                        .filter(|&index| index < row_count)
+                        .map(|index| CommandPaletteMessage::Choose(first_index + index))
+                }
+                _ => Some(CommandPaletteMessage::Close),
+            },
+            Event::Mouse(_) => None,
+        };
+        if let Some(message) = message {
+            self.update(state, message);
+        }
+        Propagation::Stop
+    }
+}
+
+// The actions whose labels match the palette's search, best first. Actions that match equally
+// well stay in the order `Action::available` lists them.
+fn command_palette_matches(state: &AppState) -> Vec<CommandPaletteMatch> {
+    let pattern = state.command_palette_input.text();
+    let mut matches: Vec<_> = Action::available(state)
+        .into_iter()
+        .filter_map(|action| {
+            let label = action_label(state, action);
+            fuzzy_match(pattern, &label).map(|fuzzy_match| {
+                (
+                    fuzzy_match.score,
+                    CommandPaletteMatch {
+                        action,
+                        label,
+                        positions: fuzzy_match.positions,
+                    },
+                )
+            })
+        })
+        .collect();
+    matches.sort_by_key(|&(score, _)| Reverse(score));
+    matches
+        .into_iter()
+        .map(|(_, palette_match)| palette_match)
+        .collect()
+}
+
+// Help
+
+pub struct Help;
+
+pub enum HelpMessage {
+    Open,
+    /// Scrolls the list of keys by this many lines, stopping at the ends.
+    Scroll(isize),
+    Close,
+}
+
+impl Component for Help {
+    type Message = HelpMessage;
+    type Layout = ();
+
+    fn update(&self, state: &mut AppState, message: HelpMessage) {
+        match message {
+            HelpMessage::Open => state.overlay = Some(Overlay::Help { first_index: 0 }),
+            HelpMessage::Scroll(lines) => {
+                if let Some(Overlay::Help { first_index }) = state.overlay {
+                    // Scroll no further than the last frame's popup had room for.
+                    let popup_rect = state.widget_registry.rect(HELP_ID);
+                    let viewport_length =
+                        popup_rect.map_or(0, |rect| rect.height.saturating_sub(2) as usize);
+                    let popup_width = popup_rect.map_or(HELP_POPUP_WIDTH, |rect| rect.width);
+                    let max_first_index =
+                        help_row_count(state, popup_width).saturating_sub(viewport_length);
+                    let first_index = (first_index as isize)
+                        .saturating_add(lines)
+                        .clamp(0, max_first_index as isize)
+                        as usize;
+                    state.overlay = Some(Overlay::Help { first_index });
+                }
+            }
+            HelpMessage::Close => state.overlay = None,
+        }
+    }
+
+    // Like the other popups, this floats over the panes.
+    fn layout(&self, _: &AppState, _: &GlyphSet, _: &mut LayoutTree, _: Node) {}
+
+    fn view<B>(&self, state: &mut AppState, frame: &mut Frame<B>, context: &mut ViewContext, _: &())
+    where
+        B: Backend,
+    {
+        let first_index = match state.overlay {
+            Some(Overlay::Help { first_index }) => first_index,
+            _ => return,
+        };
+        let bounds = frame.size();
+        let popup_width = HELP_POPUP_WIDTH.min(bounds.width);
+        let row_count = help_row_count(state, popup_width);
+        let popup_height = (row_count as u16 + 2).min(bounds.height);
+        let popup_rect = TuiRect::new(
+            bounds.x + (bounds.width - popup_width) / 2,
+            bounds.y + (bounds.height - popup_height) / 2,
+            popup_width,
+            popup_height,
+        );
+        let viewport_length = popup_height.saturating_sub(2) as usize;
+        let first_index = first_index.min(row_count.saturating_sub(viewport_length));
+        let markdown = help_markdown(state);
+        draw_popup(
+            frame,
+            pane_box_frame(
+                HELP_LABEL,
+                context.theme.popup_box_fg,
+                context.glyph_set,
+                context.theme,
+            )
+            .footer_label(HELP_KEYS_LABEL),
+            Markdown::new(&markdown, help_markdown_styles(context.theme)).scroll(first_index),
+            popup_rect,
+        );
+        context.registry.insert(HELP_ID, popup_rect);
+    }
+
+    fn contains(&self, id: WidgetId) -> bool {
+        id == HELP_ID
+    }
+
+    // Modal, like the other popups. Clicking outside the popup closes it.
+    fn handle_event(
+        &self,
+        state: &mut AppState,
+        event: &Event,
+        _: WidgetId,
+        _: Phase,
+    ) -> Propagation {
+        if !matches!(state.overlay, Some(Overlay::Help { .. })) {
+            return Propagation::Continue;
+        }
+        let page_length = state
+            .widget_registry
+            .rect(HELP_ID)
+            .map_or(1, |rect| rect.height.saturating_sub(2).max(1) as isize);
+        let message = match *event {
+            Event::Resize(..) => return Propagation::Continue,
+            Event::Key(KeyEvent { code, .. }) => match code {
+                KeyCode::Up => Some(HelpMessage::Scroll(-1)),
+                KeyCode::Down => Some(HelpMessage::Scroll(1)),
+                KeyCode::PageUp => Some(HelpMessage::Scroll(-page_length)),
+                KeyCode::PageDown | KeyCode::Char(' ') => Some(HelpMessage::Scroll(page_length)),
+                KeyCode::Home => Some(HelpMessage::Scroll(isize::MIN)),
+                KeyCode::End => Some(HelpMessage::Scroll(isize::MAX)),
+                KeyCode::Char('?') | KeyCode::Char('q') | KeyCode::Esc => Some(HelpMessage::Close),
+                _ => None,
+            },
+            Event::Mouse(MouseEvent {
+                kind, column, row, ..
+            }) => match kind {
+                MouseEventKind::ScrollUp => Some(HelpMessage::Scroll(-1)),
+                MouseEventKind::ScrollDown => Some(HelpMessage::Scroll(1)),
+                MouseEventKind::Down(MouseButton::Left)
+                    if state.widget_registry.hit_test(column, row) != Some(HELP_ID) =>
+                {
+                    Some(HelpMessage::Close)
+                }
+                _ => None,
+            },
+        };
+        if let Some(message) = message {
+            self.update(state, message);
+        }
+        Propagation::Stop
+    }
+}
+
+// The keys that do something on the screen showing, as Markdown: a heading for each category,
+// then a list item for each action. Keys bound to the same action one after the other share an
+// item.
+fn help_markdown(state: &AppState) -> String {
+    let mut entries: Vec<(KeyCategory, String, Action)> = vec![];
+    for category in &KeyCategory::ALL {
+        for binding in KEY_BINDINGS.iter().filter(|binding| {
+            binding.category == *category && binding.scope.applies(state.performance_screen_open)
+        }) {
+            match entries.last_mut() {
+                Some((last_category, keys, action))
+                    if *last_category == *category && *action == binding.action =>
+                {
+                    keys.push(' ');
+                    keys.push_str(&binding.key_label());
+                }
+                _ => entries.push((*category, binding.key_label(), binding.action)),
+            }
+        }
+    }
+    let key_width = entries
+        .iter()
+        .map(|(_, keys, _)| display_width(keys))
+        .max()
+        .unwrap_or(0) as usize;
+
+    let mut markdown = String::new();
+    for category in &KeyCategory::ALL {
+        let mut category_entries = entries
+            .iter()
+            .filter(|(entry_category, _, _)| entry_category == category)
+            .peekable();
+        if category_entries.peek().is_none() {
+            continue;
+        }
+        if !markdown.is_empty() {
+            markdown.push('\n');
+        }
+        writeln!(markdown, "# {}", HELP_CATEGORY_LABELS[*category as usize]).unwrap();
+        for (_, keys, action) in category_entries {
+            writeln!(
+                markdown,
+                "- `{:width$}`  {}",
+                keys,
+                action_label(state, *action),
+                width = key_width
+            )
+            .unwrap();
+        }
+    }
+    markdown
+}
+
+fn help_markdown_styles(theme: &Theme) -> MarkdownStyles {
+    MarkdownStyles {
+        text: TuiStyle::default().fg(theme.popup_text_color),
+        heading: TuiStyle::default()
+            .fg(theme.popup_match_color)
+            .add_modifier(Modifier::BOLD),
+        bold: TuiStyle::default().add_modifier(Modifier::BOLD),
+        code: TuiStyle::default().fg(theme.toast_key_color),
+        bullet: TuiStyle::default().fg(theme.popup_text_color),
+    }
+}
+
+// How many rows the keys take up in a help popup this wide, once they're wrapped.
+fn help_row_count(state: &AppState, popup_width: u16) -> usize {
+    let markdown = help_markdown(state);
+    Markdown::new(&markdown, MarkdownStyles::default())
+        .rows(popup_width.saturating_sub(4))
+        .len()
+}
+
+// Knobs
+
+pub struct Knobs;
+
+pub enum KnobsMessage {
+    /// Opens the panel, or closes it if it's open.
+    Toggle,
+    /// Moves the highlight by this many knobs, stopping at the ends.
+    MoveSelection(isize),
+    /// Highlights a knob by its index.
+    Select(usize),
+    /// Turns the highlighted knob by this many steps.
+    Turn(isize),
+    Close,
+}
+
+impl Component for Knobs {
+    type Message = KnobsMessage;
+    type Layout = ();
+
+    fn update(&self, state: &mut AppState, message: KnobsMessage) {
+        match message {
+            KnobsMessage::Toggle => {
+                state.overlay = match state.overlay {
+                    Some(Overlay::Knobs { .. }) => None,
+                    _ => Some(Overlay::Knobs { selected_index: 0 }),
+                }
+            }
+            KnobsMessage::MoveSelection(delta) => {
+                if let Some(Overlay::Knobs {
+                    ref mut selected_index,
+                }) = state.overlay
+                {
+                    *selected_index = (*selected_index as isize + delta)
+                        .clamp(0, Knob::ALL.len() as isize - 1)
+                        as usize;
+                }
+            }
+            KnobsMessage::Select(index) if index < Knob::ALL.len() => {
+                state.overlay = Some(Overlay::Knobs {
+                    selected_index: index,
+                })
+            }
+            KnobsMessage::Select(_) => {}
+            KnobsMessage::Turn(steps) => {
+                if let Some(Overlay::Knobs { selected_index }) = state.overlay {
+                    Knob::ALL[selected_index].turn(state, steps);
+                }
+            }
+            KnobsMessage::Close => state.overlay = None,
+        }
+    }
+
+    // Like the other popups, this floats over the panes.
+    fn layout(&self, _: &AppState, _: &GlyphSet, _: &mut LayoutTree, _: Node) {}
+
+    fn view<B>(&self, state: &mut AppState, frame: &mut Frame<B>, context: &mut ViewContext, _: &())
+    where
+        B: Backend,
+    {
+        let selected_index = match state.overlay {
+            Some(Overlay::Knobs { selected_index }) => selected_index,
+            _ => return,
+        };
+        let theme = context.theme;
+        let bounds = frame.size();
+        let values: Vec<_> = Knob::ALL
+            .iter()
+            .map(|&knob| knob_value(state, knob))
+            .collect();
+        let label_width = KNOB_LABELS.iter().map(|&label| display_width(label)).max();
+        let value_width = values.iter().map(|value| display_width(value)).max();
+        let lines: Vec<_> = KNOB_LABELS
+            .iter()
+            .zip(values.iter())
+            .enumerate()
+            .map(|(index, (&label, value))| {
+                let mut style = TuiStyle::default().fg(theme.popup_text_color);
+                if index == selected_index {
+                    style = style.bg(theme.popup_selected_bg);
+                }
+                Spans::from(Span::styled(
+                    format!(
+                        " {:label_width$}  {} {:>value_width$} {} ",
+                        label,
+                        KNOB_TURN_LABELS[0],
+                        value,
+                        KNOB_TURN_LABELS[1],
+                        label_width = label_width.unwrap_or(0) as usize,
+                        value_width = value_width.unwrap_or(0) as usize,
+                    ),
+                    style,
+                ))
+            })
+            .collect();
+        let item_width = lines
+            .iter()
+            .map(|line| line.width() as u16)
+            .max()
+            .unwrap_or(0);
+        let popup_width = item_width
+            .max(display_width(KNOBS_LABEL))
+            .max(display_width(KNOBS_KEYS_LABEL))
+            + 6;
+        // Out of the way in the top right corner, under the title bar, so that what the knobs
+        // change stays in view.
+        let anchor = TuiRect::new(bounds.right(), bounds.y, 0, 1);
+        let popup_rect = place_popup(anchor, popup_width, lines.len() as u16 + 2, bounds);
+        draw_popup(
+            frame,
+            pane_box_frame(KNOBS_LABEL, theme.popup_box_fg, context.glyph_set, theme)
+                .footer_label(KNOBS_KEYS_LABEL),
+            Paragraph::new(lines),
+            popup_rect,
+        );
+        context.registry.insert(KNOBS_ID, popup_rect);
+    }
+
+    fn contains(&self, id: WidgetId) -> bool {
+        id == KNOBS_ID
+    }
+
+    // Modal, like the other popups. Clicking a knob highlights it, and clicking outside the
+    // panel closes it. Shift turns knobs in large steps.
+    fn handle_event(
+        &self,
+        state: &mut AppState,
+        event: &Event,
+        _: WidgetId,
+        _: Phase,
+    ) -> Propagation {
+        if !matches!(state.overlay, Some(Overlay::Knobs { .. })) {
+            return Propagation::Continue;
+        }
+        let message = match *event {
+            Event::Resize(..) => return Propagation::Continue,
+            Event::Key(KeyEvent { code, modifiers }) => {
+                let step = if modifiers.contains(KeyModifiers::SHIFT) {
+                    KNOB_LARGE_STEP
+                } else {
+                    1
+                };
+                match code {
+                    KeyCode::Up => Some(KnobsMessage::MoveSelection(-1)),
+                    KeyCode::Down => Some(KnobsMessage::MoveSelection(1)),
+                    KeyCode::Left => Some(KnobsMessage::Turn(-step)),
+                    KeyCode::Right => Some(KnobsMessage::Turn(step)),
+                    KeyCode::Esc => Some(KnobsMessage::Close),
+                    _ => None,
+                }
+            }
+            Event::Mouse(mouse_event) => {
+                checklist_click(state, &mouse_event, KNOBS_ID).map(|click| match click {
+                    Some(index) => KnobsMessage::Select(index),
+                    None => KnobsMessage::Close,
+                })
+            }
+        };
+        if let Some(message) = message {
+            self.update(state, message);
+        }
+        Propagation::Stop
+    }
+}
+
+// What a knob is set to, such as `405` tasks or a mean poll time of `1.27ms`.
+fn knob_value(state: &AppState, knob: Knob) -> String {
+    let tasks = &state.snapshot.tasks;
+    match knob {
+        Knob::TaskCount => format::grouped_digits(tasks.len()),
+        Knob::PollTime => {
+            let poll_times: Vec<f32> = tasks
+                .iter()
+                .map(|task| task.poll_ms)
+                .filter(|poll_ms| poll_ms.is_finite())
+                .collect();
+            let mean = poll_times.iter().sum::<f32>() / poll_times.len().max(1) as f32;
+            Quantity::Milliseconds.text(mean)
+        }
+        Knob::Deadlock => KNOB_SWITCH_LABELS[knob.is_on(state) as usize].to_owned(),
+    }
+}
+
+// Actions
+
+// What an action does, as the command palette and the help overlay describe it. Actions that
+// toggle something say what they'd do now.
+fn action_label(state: &AppState, action: Action) -> String {
+    let label = match action {
+        Action::SelectTasksTab(tasks_tab) => ACTION_SELECT_TASKS_TAB_LABELS[tasks_tab as usize],
+        Action::SelectViewMode(view_mode) => ACTION_SELECT_VIEW_MODE_LABELS[view_mode as usize],
+        Action::ToggleGrouping => ACTION_TOGGLE_GROUPING_LABELS[state.group_tasks_by_name as usize],
+        Action::ToggleFollow => ACTION_TOGGLE_FOLLOW_LABELS[state.follow_tasks as usize],
+        Action::ToggleNotifications => {
+            ACTION_TOGGLE_NOTIFICATIONS_LABELS[state.notifications_open as usize]
+        }
+        Action::ToggleBookmark => {
+            let bookmarked = state
+                .selected_task_id
+                .is_some_and(|task_id| state.bookmarked_task_ids.contains(&task_id));
+            ACTION_TOGGLE_BOOKMARK_LABELS[bookmarked as usize]
+        }
+        Action::ToggleNameFilter => {
+            ACTION_TOGGLE_NAME_FILTER_LABELS[state.task_name_filter.is_some() as usize]
+        }
+        Action::SelectWorkspace(workspace) => {
+            let opened = workspace < state.workspaces.len().max(1);
+            let label = ACTION_SELECT_WORKSPACE_LABELS[!opened as usize];
+            return format!("{} {}", label, workspace + 1);
+        }
+        Action::OpenColumnSettings => ACTION_OPEN_COLUMN_SETTINGS_LABEL,
+        Action::TogglePerformanceScreen => {
+            ACTION_TOGGLE_PERFORMANCE_SCREEN_LABELS[state.performance_screen_open as usize]
+        }
+        Action::OpenPerformanceSettings => ACTION_OPEN_PERFORMANCE_SETTINGS_LABEL,
+        Action::Export(format) => ACTION_EXPORT_LABELS[format as usize],
+        Action::SelectTheme(theme) => {
+            return format!("{} {}", ACTION_SELECT_THEME_LABEL, THEMES[theme].name)
+        }
+        Action::OpenTask(task_id) => {
+            return match state.task(task_id) {
+                Some(task) => format!("{} {} {}", ACTION_OPEN_TASK_LABEL, task.id, task.name),
+                None => format!("{} {}", ACTION_OPEN_TASK_LABEL, task_id),
+            }
+        }
+        Action::Quit => ACTION_QUIT_LABEL,
+        Action::Suspend => ACTION_SUSPEND_LABEL,
+        Action::Back if state.focused.is_some() => ACTION_BACK_LABELS[0],
+        Action::Back if state.performance_screen_open => ACTION_BACK_LABELS[1],
+        Action::Back => ACTION_BACK_LABELS[2],
+        Action::StepFocus(forward) => ACTION_STEP_FOCUS_LABELS[forward as usize],
+        Action::ScrollRows(rows) => ACTION_SCROLL_ROWS_LABELS[(rows > 0) as usize],
+        Action::ScrollColumns(columns) => ACTION_SCROLL_COLUMNS_LABELS[(columns > 0) as usize],
+        Action::ZoomTimeline(zoom_in) => ACTION_ZOOM_TIMELINE_LABELS[zoom_in as usize],
+        Action::OpenSelectedTaskAttributes => ACTION_OPEN_SELECTED_TASK_ATTRIBUTES_LABEL,
+        Action::OpenSelectedTaskActions => ACTION_OPEN_SELECTED_TASK_ACTIONS_LABEL,
+        Action::PanHistory(bars) => ACTION_PAN_HISTORY_LABELS[(bars > 0) as usize],
+        Action::ZoomHistory(zoom_in) => ACTION_ZOOM_HISTORY_LABELS[zoom_in as usize],
+        Action::ResetHistoryView => ACTION_RESET_HISTORY_VIEW_LABEL,
+        Action::CycleTimeWindow => ACTION_CYCLE_TIME_WINDOW_LABEL,
+        Action::ToggleComparison => {
+            ACTION_TOGGLE_COMPARISON_LABELS[state.performance_comparison as usize]
+        }
+        Action::TogglePresentation => {
+            let presenting = state.performance_screen_open && state.performance_presentation;
+            ACTION_TOGGLE_PRESENTATION_LABELS[presenting as usize]
+        }
+        Action::OpenCommandPalette => ACTION_OPEN_COMMAND_PALETTE_LABEL,
+        Action::OpenHelp => ACTION_OPEN_HELP_LABEL,
+        Action::Undo => ACTION_UNDO_LABEL,
+        Action::CopySelectedTask => ACTION_COPY_SELECTED_TASK_LABEL,
+    };
+    label.to_owned()
+}
+
+/// Takes an action through the component it concerns. Actions on the tasks pane bring it back
+/// in place of the performance screen, so that their effect can be seen. Quitting, suspending,
+/// and going back are up to the app, which runs the main loop they affect.
+pub fn perform_action(state: &mut AppState, action: Action) {
+    let concerns_tasks = matches!(
+        action,
+        Action::SelectTasksTab(_)
+            | Action::SelectViewMode(_)
+            | Action::ToggleGrouping
+            | Action::ToggleFollow
+            | Action::ToggleNameFilter
+            | Action::ToggleBookmark
+            | Action::OpenColumnSettings
+            | Action::OpenTask(_)
+    );
+    if concerns_tasks && state.performance_screen_open {
+        PerformanceScreen.update(state, PerformanceScreenMessage::Close);
+    }
+    match action {
+        Action::SelectTasksTab(tasks_tab) => {
+            TasksPane.update(state, TasksPaneMessage::SelectTab(tasks_tab))
+        }
+        Action::SelectViewMode(view_mode) => {
+            TasksPane.update(state, TasksPaneMessage::SelectViewMode(view_mode))
+        }
+        Action::ToggleGrouping => TasksPane.update(state, TasksPaneMessage::ToggleGrouping),
+        Action::ToggleFollow => TasksPane.update(state, TasksPaneMessage::ToggleFollow),
+        Action::ToggleBookmark => {
+            if let Some(task_id) = state.selected_task_id {
+                TasksPane.update(state, TasksPaneMessage::ToggleBookmark(task_id));
+            }
+        }
+        Action::ToggleNameFilter => TasksPane.update(state, TasksPaneMessage::ToggleNameFilter),
+        Action::SelectWorkspace(workspace) => state.select_workspace(workspace),
+        Action::OpenColumnSettings => ColumnSettings.update(state, ColumnSettingsMessage::Open),
+        Action::TogglePerformanceScreen => {
+            let message = if state.performance_screen_open {
+                PerformanceScreenMessage::Close
+            } else {
+                PerformanceScreenMessage::Open
+            };
+            PerformanceScreen.update(state, message);
+        }
+        Action::OpenPerformanceSettings => {
+            PerformanceSettings.update(state, PerformanceSettingsMessage::Open)
+        }
+        Action::Export(format) => state.export_request = Some(format),
+        Action::SelectTheme(theme) => state.theme = THEMES[theme],
+        Action::OpenTask(task_id) => {
+            // The task may be on another tab.
+            if !state.visible_tasks().iter().any(|task| task.id == task_id) {
+                TasksPane.update(state, TasksPaneMessage::SelectTab(TasksTab::All));
+            }
+            TasksPane.update(state, TasksPaneMessage::SelectTask(task_id));
+            TaskAttributes.update(state, TaskAttributesMessage::Open(task_id));
+        }
+        Action::Quit | Action::Suspend | Action::Back => {}
+        Action::StepFocus(forward) => state.step_focus(forward),
+        Action::ScrollRows(rows) => TasksPane.update(state, TasksPaneMessage::ScrollRows(rows)),
+        Action::ScrollColumns(columns) => {
+            TasksPane.update(state, TasksPaneMessage::ScrollColumns(columns))
+        }
+        Action::ZoomTimeline(zoom_in) => {
+            TasksPane.update(state, TasksPaneMessage::ZoomTimeline(zoom_in))
+        }
+        Action::OpenSelectedTaskAttributes => {
+            if let Some(task_id) = state.selected_task_id {
+                TaskAttributes.update(state, TaskAttributesMessage::Open(task_id));
+            }
+        }
+        Action::OpenSelectedTaskActions => {
+            if let Some(task_id) = state.selected_task_id {
+                TaskActions.update(state, TaskActionsMessage::OpenMenu(task_id));
+            }
+        }
+        Action::PanHistory(bars) => {
+            PerformancePane.update(state, PerformancePaneMessage::PanHistory(bars))
+        }
+        Action::ZoomHistory(zoom_in) => {
+            PerformancePane.update(state, PerformancePaneMessage::ZoomHistory(zoom_in))
+        }
+        Action::ResetHistoryView => {
+            PerformancePane.update(state, PerformancePaneMessage::ResetHistoryView)
+        }
+        Action::CycleTimeWindow => {
+            let time_window = match state.performance_history_view.time_window {
+                Some(time_window) => TimeWindow::from_index(time_window.index() + 1),
+                None => Some(TimeWindow::OneMinute),
+            };
+            PerformancePane.update(state, PerformancePaneMessage::SelectTimeWindow(time_window));
+        }
+        Action::ToggleComparison => {
+            PerformanceScreen.update(state, PerformanceScreenMessage::ToggleComparison)
+        }
+        Action::TogglePresentation => {
+            // From the panes, this always opens the screen presenting.
+            let presenting = state.performance_screen_open && state.performance_presentation;
+            PerformanceScreen.update(state, PerformanceScreenMessage::Open);
+            if state.performance_presentation == presenting {
+                PerformanceScreen.update(state, PerformanceScreenMessage::TogglePresentation);
+            }
+        }
+        Action::OpenCommandPalette => CommandPalette.update(state, CommandPaletteMessage::Open),
+        Action::OpenHelp => Help.update(state, HelpMessage::Open),
+        Action::ToggleNotifications => {
+            NotificationsPane.update(state, NotificationsPaneMessage::Toggle)
+        }
+        Action::Undo => TaskActions.update(state, TaskActionsMessage::Undo),
+        Action::CopySelectedTask => {
+            let task = match state
+                .selected_task_id
+                .and_then(|task_id| state.task(task_id))
+            {
+                Some(task) => task,
+                None => return,
+            };
+            let text = task_table_row_text(
+                task,
+                &state.shown_tasks_table_columns(),
+                state.strings,
+                state.theme,
+            );
+            let message = format!("{} {}", COPIED_ROW_LABEL, task.id);
+            copy_to_clipboard(state, message, text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw_frame;
+    use crate::export::ExportFormat;
+    use crate::fixtures;
+    use crate::glyphs::GLYPH_SET_NERD_FONT;
+    use crate::state::PerformanceSample;
+    use std::time::Duration;
+    use stretch::number::Number;
+    use stretch::style::Style;
+    use tui::backend::TestBackend;
+    use tui::Terminal;
+    use turbowish_widgets::layout::TABLE_COLUMN_SPACING;
+
+    fn compute_layout<C>(
+        component: &C,
+        state: &AppState,
+        width: u16,
+        height: u16,
+    ) -> (LayoutTree, Node, C::Layout)
+    where
+        C: Component,
+    {
+        let mut stretch = LayoutTree::new();
+        let root = stretch.new_node(Style {
+            size: Size::fixed(width, height),
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        });
+        let layout = component.layout(state, &GLYPH_SET_NERD_FONT, &mut stretch, root);
+        stretch.compute_layout(
+            root,
+            Size {
+                width: Number::Undefined,
+                height: Number::Undefined,
+            },
+        );
+        (stretch, root, layout)
+    }
+
+    #[test]
+    fn expanding_the_performance_pane_makes_it_taller() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        let (stretch, _, layout) = compute_layout(&PerformancePane, &state, 80, 24);
+        let collapsed_height = stretch.layout(layout.performance_node).size.height;
+        PerformancePane.update(&mut state, PerformancePaneMessage::ToggleExpanded);
+        let (stretch, _, layout) = compute_layout(&PerformancePane, &state, 80, 24);
+        let expanded_height = stretch.layout(layout.performance_node).size.height;
+        assert_eq!(
+            expanded_height - collapsed_height,
+            (PERFORMANCE_EXPANDED_GRAPH_HEIGHT - 1) as f32
+        );
+    }
+
+    #[test]
+    fn expanding_twice_opens_the_performance_screen() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        let mut click = |state: &mut AppState, id: WidgetId| {
+            terminal
+                .draw(|frame| {
+                    draw_frame(frame, &fixtures::fixed_clock(), &GLYPH_SET_NERD_FONT, state)
+                })
+                .unwrap();
+            let rect = state.widget_registry.rect(id).unwrap();
+            dispatch(
+                state,
+                &Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    column: rect.x,
+                    row: rect.y,
+                    modifiers: KeyModifiers::NONE,
+                }),
+            );
+            terminal.backend().buffer().clone()
+        };
+        click(&mut state, PERFORMANCE_EXPAND_BUTTON_ID);
+        assert!(state.performance_expanded && !state.performance_screen_open);
+        click(&mut state, PERFORMANCE_EXPAND_BUTTON_ID);
+        assert!(state.performance_screen_open);
+
+        // The screen has a chart for each metric, under readouts of the values it shows.
+        let buffer = click(&mut state, PERFORMANCE_COLLAPSE_BUTTON_ID);
+        let text: String = buffer.content.iter().map(|cell| &cell.symbol[..]).collect();
+        assert!(text.contains("Runtime  now 23.3%  min 1.0%  max 8.0%  avg 4.6%"));
+        assert!(text.contains("Wake time  now 750µs"));
+        assert!(!text.contains("Tasks"));
+        assert!(!state.performance_screen_open && !state.performance_expanded);
+    }
+
+    #[test]
+    fn clicking_a_time_window_selects_it() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        state.performance_screen_open = true;
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal
+            .draw(|frame| {
+                draw_frame(
+                    frame,
+                    &fixtures::fixed_clock(),
+                    &GLYPH_SET_NERD_FONT,
+                    &mut state,
+                )
+            })
+            .unwrap();
+        let rect = state
+            .widget_registry
+            .rect(PERFORMANCE_TIME_WINDOW_ID)
+            .unwrap();
+        let click = |state: &mut AppState, column| {
+            dispatch(
+                state,
+                &Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    column,
+                    row: rect.y,
+                    modifiers: KeyModifiers::NONE,
+                }),
+            )
+        };
+        // Ten columns in is on `15m`, past `1m` and `5m` and the space or edge either side of each.
+        assert_eq!(click(&mut state, rect.x + 10), Propagation::Stop);
+        assert_eq!(
+            state.performance_history_view.time_window,
+            Some(TimeWindow::FifteenMinutes)
+        );
+        click(&mut state, rect.x);
+        assert_eq!(
+            state.performance_history_view.time_window,
+            Some(TimeWindow::OneMinute)
+        );
+    }
+
+    #[test]
+    fn comparison_shows_the_change_from_the_previous_window() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        state.performance_screen_open = true;
+        state.select_performance_time_window(Some(TimeWindow::OneMinute));
+        PerformanceScreen.update(&mut state, PerformanceScreenMessage::ToggleComparison);
+        // Runtime doubles from one minute to the next.
+        for run_percent in [10.0, 20.0].iter() {
+            for _ in 0..60 {
+                state.record_performance(PerformanceSample {
+                    run_percent: *run_percent,
+                    ..PerformanceSample::default()
+                });
+            }
+        }
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal
+            .draw(|frame| {
+                draw_frame(
+                    frame,
+                    &fixtures::fixed_clock(),
+                    &GLYPH_SET_NERD_FONT,
+                    &mut state,
+                )
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer.content.iter().map(|cell| &cell.symbol[..]).collect();
+        assert!(text.contains("avg 20.0%  vs prev +10.0% +100.0%"));
+        assert!(text.contains("vs prev ±0ns"));
+    }
+
+    #[test]
+    fn runtime_selector_shows_one_runtimes_performance() {
+        let mut state = AppState::new(fixtures::Dataset::Pathological.snapshot());
+        let mut terminal = Terminal::new(TestBackend::new(160, 30)).unwrap();
+        let mut draw = |state: &mut AppState| {
+            terminal
+                .draw(|frame| {
+                    draw_frame(frame, &fixtures::fixed_clock(), &GLYPH_SET_NERD_FONT, state)
+                })
+                .unwrap();
+            let buffer = terminal.backend().buffer();
+            buffer
+                .content
+                .iter()
+                .map(|cell| &cell.symbol[..])
+                .collect::<String>()
+        };
+        assert!(draw(&mut state).contains("Runtime 23.3%"));
+
+        // `blocking-io` is past `all` and `main`, each with a space or edge either side.
+        let rect = state.widget_registry.rect(PERFORMANCE_RUNTIME_ID).unwrap();
+        dispatch(
+            &mut state,
+            &Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: rect.x + 11,
+                row: rect.y,
+                modifiers: KeyModifiers::NONE,
+            }),
+        );
+        assert_eq!(state.performance_runtime, Some(1));
+        let text = draw(&mut state);
+        let current = state.snapshot.runtimes[1].performance.current;
+        assert!(text.contains(&format!("Runtime {:.1}%", current.run_percent)));
+        assert!(text.contains("blocking-io · updated"));
+
+        // Left goes back toward the whole program.
+        state.focused = Some(PERFORMANCE_RUNTIME_ID);
+        for _ in 0..3 {
+            dispatch(&mut state, &Event::Key(KeyEvent::from(KeyCode::Left)));
+        }
+        assert_eq!(state.performance_runtime, None);
+    }
+
+    #[test]
+    fn expanded_performance_pane_shows_latency_percentiles() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        let mut draw = |state: &mut AppState| {
+            terminal
+                .draw(|frame| {
+                    draw_frame(frame, &fixtures::fixed_clock(), &GLYPH_SET_NERD_FONT, state)
+                })
+                .unwrap();
+            let buffer = terminal.backend().buffer();
+            buffer
+                .content
+                .iter()
+                .map(|cell| &cell.symbol[..])
+                .collect::<String>()
+        };
+        // There's no room for them in a single line.
+        assert!(!draw(&mut state).contains("p99"));
+        // Expanded, they're under the current value, over the seven samples that fit.
+        state.performance_expanded = true;
+        let text = draw(&mut state);
+        assert!(text.contains("Poll time 1.05ms"));
+        assert!(text.contains("p50 3.00ms"));
+        assert!(text.contains("p99 8.00ms"));
+        // The performance screen's charts are wide enough for all seven samples.
+        state.performance_screen_open = true;
+        let text = draw(&mut state);
+        assert!(text.contains("avg 4.57ms  p50 4.00ms  p95 8.00ms  p99 8.00ms"));
+    }
+
+    #[test]
+    fn tasks_pane_scrolls_no_further_than_its_last_row() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::typical()));
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let (stretch, root, layout) = compute_layout(&TasksPane, &state, 80, 24);
+        let mut context = ViewContext::new(&stretch, root, &GLYPH_SET_NERD_FONT, state.theme, None);
+        // Scroll lengths are only known once the pane has been drawn.
+        for _ in 0..2 {
+            terminal
+                .draw(|frame| TasksPane.view(&mut state, frame, &mut context, &layout))
+                .unwrap();
+            TasksPane.update(&mut state, TasksPaneMessage::ScrollRows(1_000));
+            TasksPane.update(&mut state, TasksPaneMessage::ScrollColumns(1_000));
+        }
+
+        let viewport_length = stretch.layout(layout.tasks_table_node).size.height - 1.0;
+        assert_eq!(
+            state.tasks_scroll_state.offset(),
+            state.snapshot.tasks.len() - viewport_length as usize
+        );
+        assert_eq!(
+            state.tasks_column_scroll_state.offset(),
+            state.shown_tasks_table_columns().len() - TASKS_TABLE_FROZEN_COLUMN_COUNT - 1
+        );
+    }
+
+    #[test]
+    fn tabs_filter_the_table_and_clicks_select_from_what_is_shown() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::typical()));
+        assert_eq!(state.task_count(TasksTab::All), 405);
+        assert_eq!(state.task_count(TasksTab::Running), 3);
+        assert_eq!(state.task_count(TasksTab::Sleeping), 402);
+        assert_eq!(state.task_count(TasksTab::Deadlocked), 0);
+
+        TasksPane.update(&mut state, TasksPaneMessage::SelectTab(TasksTab::Running));
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let (stretch, root, layout) = compute_layout(&TasksPane, &state, 80, 24);
+        let mut context = ViewContext::new(&stretch, root, &GLYPH_SET_NERD_FONT, state.theme, None);
+        terminal
+            .draw(|frame| TasksPane.view(&mut state, frame, &mut context, &layout))
+            .unwrap();
+        state.widget_registry = context.registry;
+        let table_rect = state.widget_registry.rect(TASKS_TABLE_ID).unwrap();
+
+        let left_click = |row| {
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: table_rect.x,
+                row,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+
+        // The header row selects nothing, and the first column's header doesn't sort.
+        assert_eq!(
+            dispatch(&mut state, &left_click(table_rect.y)),
+            Propagation::Continue
+        );
+        let second_running_task_id = state.visible_tasks()[1].id;
+        let row_click = left_click(table_rect.y + 2);
+        assert_eq!(dispatch(&mut state, &row_click), Propagation::Stop);
+        assert_eq!(state.selected_task_id, Some(second_running_task_id));
+
+        // The selected task isn't sleeping, so switching to that tab drops the selection.
+        TasksPane.update(&mut state, TasksPaneMessage::SelectTab(TasksTab::Sleeping));
+        assert_eq!(state.selected_task_id, None);
+    }
+
+    #[test]
+    fn clicking_a_header_sorts_by_its_column_and_marks_it() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let (stretch, root, layout) = compute_layout(&TasksPane, &state, 80, 24);
+        let mut draw = |state: &mut AppState| {
+            let mut context =
+                ViewContext::new(&stretch, root, &GLYPH_SET_NERD_FONT, state.theme, None);
+            terminal
+                .draw(|frame| TasksPane.view(state, frame, &mut context, &layout))
+                .unwrap();
+            state.widget_registry = context.registry;
+            let table_rect = state.widget_registry.rect(TASKS_TABLE_ID).unwrap();
+            let buffer = terminal.backend().buffer();
+            let header: String = (table_rect.x..table_rect.right())
+                .map(|x| buffer.get(x, table_rect.y).symbol.clone())
+                .collect();
+            (table_rect, header)
+        };
+        let (table_rect, header) = draw(&mut state);
+        assert!(!header.contains('▲'));
+
+        // Poll is the sixth column, and nothing has scrolled the columns.
+        let poll_header_column = table_rect.x
+            + TASKS_TABLE_COLUMNS[..5]
+                .iter()
+                .map(|table_column| table_column.width.unwrap() + TABLE_COLUMN_SPACING)
+                .sum::<u16>();
+        let header_click = Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: poll_header_column,
+            row: table_rect.y,
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(dispatch(&mut state, &header_click), Propagation::Stop);
+        let poll_times: Vec<_> = state
+            .visible_tasks()
+            .iter()
+            .map(|task| task.poll_ms)
+            .collect();
+        assert!(poll_times.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(draw(&mut state).1.contains("▲ Poll"));
+
+        dispatch(&mut state, &header_click);
+        assert_eq!(
+            state.tasks_sort,
+            Some(TasksSort {
+                key: TaskSortKey::PollMs,
+                descending: true,
+            })
+        );
+        assert!(draw(&mut state).1.contains("▼ Poll"));
+    }
+
+    #[test]
+    fn aborting_a_task_asks_first_and_can_be_undone() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        let task_id = state.snapshot.tasks[1].id;
+        let key = |code| {
+            Event::Key(KeyEvent {
+                code,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+
+        // The first item pauses the task, which can be taken back.
+        TaskActions.update(&mut state, TaskActionsMessage::OpenMenu(task_id));
+        assert_eq!(
+            dispatch(&mut state, &key(KeyCode::Enter)),
+            Propagation::Stop
+        );
+        assert!(state.overlay.is_none());
+        assert!(state.task(task_id).unwrap().paused);
+        TaskActions.update(&mut state, TaskActionsMessage::Undo);
+        assert!(!state.task(task_id).unwrap().paused);
+        assert!(state.visible_toast().is_none());
+
+        // Abort is the last item, and saying no leaves the task alone.
+        TaskActions.update(&mut state, TaskActionsMessage::OpenMenu(task_id));
+        for _ in 0..3 {
+            dispatch(&mut state, &key(KeyCode::Down));
+        }
+        dispatch(&mut state, &key(KeyCode::Enter));
+        assert_eq!(state.overlay, Some(Overlay::ConfirmAbort { task_id }));
+        dispatch(&mut state, &key(KeyCode::Char('n')));
+        assert!(state.overlay.is_none());
+        assert!(state.task(task_id).is_some());
+
+        let tasks = state.snapshot.tasks.clone();
+        TaskActions.update(&mut state, TaskActionsMessage::OpenMenu(task_id));
+        TaskActions.update(&mut state, TaskActionsMessage::Choose(TaskAction::Abort));
+        dispatch(&mut state, &key(KeyCode::Char('y')));
+        assert!(state.task(task_id).is_none());
+        assert!(state.visible_toast().unwrap().undo.is_some());
+        TaskActions.update(&mut state, TaskActionsMessage::Undo);
+        assert_eq!(state.snapshot.tasks, tasks);
+    }
+
+    #[test]
+    fn attributes_popup_scrolls_to_the_highlight() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::pathological()));
+        let task = state
+            .snapshot
+            .tasks
+            .iter()
+            .find(|task| task.attributes.len() == 20)
+            .unwrap()
+            .clone();
+        let mut terminal = Terminal::new(TestBackend::new(40, 12)).unwrap();
+        let mut draw = |state: &mut AppState| {
+            terminal
+                .draw(|frame| {
+                    draw_frame(frame, &fixtures::fixed_clock(), &GLYPH_SET_NERD_FONT, state)
+                })
+                .unwrap();
+        };
+
+        TaskAttributes.update(&mut state, TaskAttributesMessage::Open(task.id));
+        draw(&mut state);
+        for _ in 0..19 {
+            dispatch(&mut state, &Event::Key(KeyEvent::from(KeyCode::Down)));
+        }
+        draw(&mut state);
+        // Eight rows fit in the popup, leaving a row free above and below it.
+        assert_eq!(
+            state.overlay,
+            Some(Overlay::TaskAttributes {
+                task_id: task.id,
+                selected_index: 19,
+                first_index: 12,
+            })
+        );
+
+        let popup_rect = state.widget_registry.rect(TASK_ATTRIBUTES_ID).unwrap();
+        let click = Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: popup_rect.x + 2,
+            row: popup_rect.y + 2,
+            modifiers: KeyModifiers::NONE,
+        });
+        dispatch(&mut state, &click);
+        TaskAttributes.update(&mut state, TaskAttributesMessage::Copy);
+        assert_eq!(state.clipboard_request.as_deref(), Some("key-13=value-13"));
+    }
+
+    #[test]
+    fn attributes_popup_links_urls_and_request_ids() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::pathological()));
+        state.request_link_template = Some("https://tracing.example.com/{}".to_owned());
+        let task = state
+            .snapshot
+            .tasks
+            .iter_mut()
+            .find(|task| task.attributes.len() == 20)
+            .unwrap();
+        task.attributes[1].1 = "https://example.com/docs".to_owned();
+        task.attributes[3] = ("request-id".to_owned(), "r/42".to_owned());
+        let task_id = task.id;
+        let mut terminal = Terminal::new(TestBackend::new(40, 12)).unwrap();
+
+        TaskAttributes.update(&mut state, TaskAttributesMessage::Open(task_id));
+        terminal
+            .draw(|frame| {
+                draw_frame(
+                    frame,
+                    &fixtures::fixed_clock(),
+                    &GLYPH_SET_NERD_FONT,
+                    &mut state,
+                )
+            })
+            .unwrap();
+        let popup_rect = state.widget_registry.rect(TASK_ATTRIBUTES_ID).unwrap();
+        let text_row = |index| TuiRect::new(popup_rect.x + 2, popup_rect.y + 1 + index, 36, 1);
+        let buffer = terminal.backend().buffer();
+        assert_eq!(buffer.get(text_row(1).x, text_row(1).y).symbol, "k");
+        assert_eq!(buffer.get(text_row(1).x - 1, text_row(1).y).symbol, " ");
+        assert_eq!(
+            state.hyperlinks,
+            vec![
+                Hyperlink {
+                    area: text_row(1),
+                    url: "https://example.com/docs".to_owned(),
+                },
+                Hyperlink {
+                    area: text_row(3),
+                    url: "https://tracing.example.com/r%2F42".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn copying_a_task_takes_its_row_or_all_of_its_attributes() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        perform_action(&mut state, Action::CopySelectedTask);
+        assert_eq!(state.clipboard_request, None);
+
+        state.selected_task_id = Some(285);
+        perform_action(&mut state, Action::CopySelectedTask);
+        let text = state.clipboard_request.take().unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        assert!(lines[0].starts_with("ID\tName\t"));
+        assert!(lines[1].starts_with("285\tconnection-handler\t"));
+        assert!(lines[1].ends_with("request-id=dbabfa1a-f722-41c0-82dc-a02e88e55d2a"));
+
+        perform_action(&mut state, Action::OpenSelectedTaskAttributes);
+        dispatch(&mut state, &Event::Key(KeyEvent::from(KeyCode::Char('y'))));
+        assert_eq!(
+            state.clipboard_request.as_deref(),
+            Some(
+                "remote-address=127.0.0.1:56723\n\
+                 request-id=dbabfa1a-f722-41c0-82dc-a02e88e55d2a\n"
+            )
+        );
+    }
+
+    #[test]
+    fn following_keeps_the_newest_task_in_view_until_scrolled_by_hand() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::typical()));
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let mut draw = |state: &mut AppState| {
+            terminal
+                .draw(|frame| {
+                    draw_frame(frame, &fixtures::fixed_clock(), &GLYPH_SET_NERD_FONT, state)
+                })
+                .unwrap();
+        };
+        draw(&mut state);
+        TasksPane.update(&mut state, TasksPaneMessage::ToggleFollow);
+        for index in 0..3 {
+            state.spawn_task(fixtures::spawned_task(index));
+            draw(&mut state);
+            let newest_task_id = state.newest_task_id().unwrap();
+            let table_rect = state.widget_registry.rect(TASKS_TABLE_ID).unwrap();
+            let last_row = state.tasks_table_row_at(table_rect.bottom() - 1);
+            assert!(last_row.is_some_and(|row| row.contains_task(newest_task_id)));
+        }
+
+        TasksPane.update(&mut state, TasksPaneMessage::ScrollRows(-1));
+        assert!(!state.follow_tasks);
+        let offset = state.tasks_scroll_state.offset();
+        state.spawn_task(fixtures::spawned_task(3));
+        draw(&mut state);
+        assert_eq!(state.tasks_scroll_state.offset(), offset);
+    }
+
+    #[test]
+    fn column_settings_show_the_duration_columns() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        let main_task = state
+            .snapshot
+            .tasks
+            .iter_mut()
+            .find(|task| task.name == "main")
+            .unwrap();
+        main_task.busy_time = Duration::from_millis(350);
+        main_task.idle_time = Duration::from_secs(4 * 60 + 5);
+        let mut terminal = Terminal::new(TestBackend::new(120, 24)).unwrap();
+        let mut draw = |state: &mut AppState| {
+            terminal
+                .draw(|frame| {
+                    draw_frame(frame, &fixtures::fixed_clock(), &GLYPH_SET_NERD_FONT, state)
+                })
+                .unwrap();
+            let buffer = terminal.backend().buffer().clone();
+            let table_rect = state.widget_registry.rect(TASKS_TABLE_ID).unwrap();
+            (table_rect.y..table_rect.bottom())
+                .map(|y| {
+                    (table_rect.x..table_rect.right())
+                        .map(|x| buffer.get(x, y).symbol.clone())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert!(!draw(&mut state)[0].contains("Lifetime"));
+
+        // Busy, Idle, and Lifetime follow State, Run %, Poll, and Wake in the list.
+        ColumnSettings.update(&mut state, ColumnSettingsMessage::Open);
+        for _ in 0..4 {
+            dispatch(&mut state, &Event::Key(KeyEvent::from(KeyCode::Down)));
+        }
+        for _ in 0..3 {
+            dispatch(&mut state, &Event::Key(KeyEvent::from(KeyCode::Char(' '))));
+            dispatch(&mut state, &Event::Key(KeyEvent::from(KeyCode::Down)));
+        }
+        dispatch(&mut state, &Event::Key(KeyEvent::from(KeyCode::Esc)));
+        assert!(state.overlay.is_none());
+
+        let rows = draw(&mut state);
+        assert!(rows[0].contains("Busy    Idle Lifetime"));
+        let main_row = rows.iter().find(|row| row.contains(" main ")).unwrap();
+        assert!(main_row.contains("  350ms   4m05s    4m05s"));
+    }
+
+    #[test]
+    fn metric_settings_swap_the_performance_panes_graphs() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        let mut terminal = Terminal::new(TestBackend::new(120, 24)).unwrap();
+        let mut draw = |state: &mut AppState| {
+            terminal
+                .draw(|frame| {
+                    draw_frame(frame, &fixtures::fixed_clock(), &GLYPH_SET_NERD_FONT, state)
+                })
+                .unwrap();
+            let buffer = terminal.backend().buffer().clone();
+            let button_rect = state
+                .widget_registry
+                .rect(PERFORMANCE_EXPAND_BUTTON_ID)
+                .unwrap();
+            (0..buffer.area.width)
+                .map(|x| buffer.get(x, button_rect.y).symbol.clone())
+                .collect::<String>()
+        };
+        assert!(draw(&mut state).contains("Runtime 23.3%"));
+
+        // Hide Runtime, then show Steal rate, which is the fifth metric.
+        PerformanceSettings.update(&mut state, PerformanceSettingsMessage::Open);
+        dispatch(&mut state, &Event::Key(KeyEvent::from(KeyCode::Char(' '))));
+        for _ in 0..4 {
+            dispatch(&mut state, &Event::Key(KeyEvent::from(KeyCode::Down)));
+        }
+        dispatch(&mut state, &Event::Key(KeyEvent::from(KeyCode::Char(' '))));
+        dispatch(&mut state, &Event::Key(KeyEvent::from(KeyCode::Esc)));
+        assert!(state.overlay.is_none());
+        assert_eq!(state.shown_performance_metrics(), vec![1, 2, 3, 4]);
+
+        let graphs_row = draw(&mut state);
+        assert!(!graphs_row.contains("Runtime"));
+        assert!(graphs_row.contains("Steal rate 12.4/s"));
+
+        // The last metric shown can't be hidden.
+        for metric in 1..4 {
+            state.toggle_performance_metric(metric);
+        }
+        state.toggle_performance_metric(4);
+        assert_eq!(state.shown_performance_metrics(), vec![4]);
+    }
+
+    #[test]
+    fn command_palette_runs_the_best_match_for_what_is_typed() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        state.performance_screen_open = true;
+        let run = |state: &mut AppState, search: &str| {
+            CommandPalette.update(state, CommandPaletteMessage::Open);
+            for character in search.chars() {
+                dispatch(state, &Event::Key(KeyEvent::from(KeyCode::Char(character))));
+            }
+            dispatch(state, &Event::Key(KeyEvent::from(KeyCode::Enter)));
+        };
+
+        // Filtering brings the tasks pane back so that the filter can be seen.
+        run(&mut state, "fil run");
+        assert_eq!(state.tasks_tab, TasksTab::Running);
+        assert!(state.overlay.is_none());
+        assert!(!state.performance_screen_open);
+
+        run(&mut state, "theme basic");
+        assert_eq!(state.theme.name, "basic");
+        run(&mut state, "json");
+        assert_eq!(state.export_request, Some(ExportFormat::Json));
+
+        // A sleeping task, which isn't on the running tab.
+        let task = state.snapshot.tasks[3].clone();
+        run(&mut state, &format!("open {}", task.id));
+        assert_eq!(state.tasks_tab, TasksTab::All);
+        assert_eq!(state.selected_task_id, Some(task.id));
+        assert!(matches!(
+            state.overlay,
+            Some(Overlay::TaskAttributes { task_id, .. }) if task_id == task.id
+        ));
+
+        // Searches that match nothing run nothing.
+        state.overlay = None;
+        run(&mut state, "zzz");
+        assert_eq!(state.tasks_tab, TasksTab::All);
+    }
+
+    #[test]
+    fn help_lists_the_keys_for_the_screen_showing() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        let help_text = |state: &AppState| -> Vec<String> {
+            help_markdown(state).lines().map(str::to_owned).collect()
+        };
+
+        perform_action(&mut state, Action::OpenHelp);
+        assert!(matches!(
+            state.overlay,
+            Some(Overlay::Help { first_index: 0 })
+        ));
+        let lines = help_text(&state);
+        assert!(lines.contains(&"# Filtering".to_owned()));
+        assert!(lines.iter().any(|line| line.starts_with("- `↑ ")));
+        assert!(lines.iter().any(|line| line.contains("+ =")));
+        assert!(lines.iter().any(|line| line.contains("ctrl-c")));
+
+        // The tasks pane's keys don't apply while the performance screen hides it.
+        state.performance_screen_open = true;
+        let lines = help_text(&state);
+        assert!(!lines.iter().any(|line| line.starts_with("- `↑ ")));
+        assert!(lines.iter().any(|line| line.starts_with("- `d ")));
+
+        dispatch(&mut state, &Event::Key(KeyEvent::from(KeyCode::Esc)));
+        assert!(state.overlay.is_none());
+    }
+
+    #[test]
+    fn notification_center_takes_the_focus_and_dismisses_what_is_highlighted() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        for index in 0..3 {
+            state.notify(NotificationKind::Alert, index.to_string());
+        }
+        let key = |state: &mut AppState, code| dispatch(state, &Event::Key(KeyEvent::from(code)));
+
+        perform_action(&mut state, Action::ToggleNotifications);
+        assert!(state.notifications_open);
+        assert_eq!(state.focused, Some(NOTIFICATIONS_LIST_ID));
+        assert_eq!(state.unread_notification_count, 0);
+        key(&mut state, KeyCode::Down);
+        key(&mut state, KeyCode::Down);
+        key(&mut state, KeyCode::Down);
+        assert_eq!(state.selected_notification, 2);
+        key(&mut state, KeyCode::Char('x'));
+        assert_eq!(state.notifications.len(), 2);
+        assert_eq!(state.notifications[0].message, "1");
+
+        // Notifications that come in while it's open are already read.
+        state.notify(NotificationKind::Export, "3".to_owned());
+        assert_eq!(state.unread_notification_count, 0);
+        key(&mut state, KeyCode::Char('X'));
+        assert!(state.notifications.is_empty());
+
+        perform_action(&mut state, Action::ToggleNotifications);
+        assert!(!state.notifications_open);
+        assert_eq!(state.focused, None);
+    }
+}
@@ -0,0 +1,143 @@
+//! What the console keeps between sessions, in files per target in the config directory.
+//!
+//! Targets are named by their address, which is made safe for a file name, so that each
+//! program the console watches keeps its own bookmarks and session.
+
+use crate::session;
+use crate::state::AppState;
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+static CONFIG_DIR_NAME: &'static str = "tokio-console";
+static BOOKMARKS_EXTENSION: &'static str = "bookmarks";
+static SESSION_EXTENSION: &'static str = "session";
+#[cfg_attr(windows, allow(dead_code))]
+static XDG_CONFIG_HOME_VAR: &'static str = "XDG_CONFIG_HOME";
+#[cfg_attr(windows, allow(dead_code))]
+static HOME_VAR: &'static str = "HOME";
+#[cfg_attr(not(windows), allow(dead_code))]
+static APPDATA_VAR: &'static str = "APPDATA";
+
+/// The directory the console keeps its files in, if the environment says where that is.
+pub fn config_dir() -> Option<PathBuf> {
+    config_dir_from(|name| env::var_os(name).map(PathBuf::from))
+}
+
+// `%APPDATA%` on Windows. Elsewhere, `$XDG_CONFIG_HOME`, or `~/.config` where that isn't set,
+// which is where command-line tools tend to look on macOS too. Relative paths are ignored, as
+// the XDG spec asks.
+fn config_dir_from<F>(var: F) -> Option<PathBuf>
+where
+    F: Fn(&str) -> Option<PathBuf>,
+{
+    let base = if cfg!(windows) {
+        var(APPDATA_VAR)
+    } else {
+        var(XDG_CONFIG_HOME_VAR)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| var(HOME_VAR).map(|home| home.join(".config")))
+    };
+    base.filter(|dir| dir.is_absolute())
+        .map(|dir| dir.join(CONFIG_DIR_NAME))
+}
+
+// The file in `dir` that keeps something about `target`, such as its bookmarks.
+fn target_file(dir: &Path, target: &str, extension: &str) -> PathBuf {
+    let file_stem: String = target
+        .chars()
+        .map(|character| {
+            if character.is_ascii_alphanumeric() || character == '-' || character == '.' {
+                character
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    dir.join(format!("{}.{}", file_stem, extension))
+}
+
+// What's kept in the file, or an empty string if nothing has been kept yet.
+fn read_target_file(dir: &Path, target: &str, extension: &str) -> io::Result<String> {
+    match fs::read_to_string(target_file(dir, target, extension)) {
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(String::new()),
+        result => result,
+    }
+}
+
+fn write_target_file(dir: &Path, target: &str, extension: &str, contents: &str) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    fs::write(target_file(dir, target, extension), contents)
+}
+
+/// The IDs of the tasks bookmarked in `target`, which is none if it has never had any.
+/// Lines that aren't task IDs are skipped.
+pub fn load_bookmarks(dir: &Path, target: &str) -> io::Result<BTreeSet<u64>> {
+    Ok(read_target_file(dir, target, BOOKMARKS_EXTENSION)?
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .collect())
+}
+
+/// Keeps the IDs of the tasks bookmarked in `target`, one to a line, creating `dir` if need be.
+pub fn save_bookmarks(dir: &Path, target: &str, task_ids: &BTreeSet<u64>) -> io::Result<()> {
+    let contents: String = task_ids
+        .iter()
+        .map(|task_id| format!("{}\n", task_id))
+        .collect();
+    write_target_file(dir, target, BOOKMARKS_EXTENSION, &contents)
+}
+
+/// Puts `state` back the way the last session with `target` left it, if there was one.
+pub fn load_session(dir: &Path, target: &str, state: &mut AppState) -> io::Result<()> {
+    let contents = read_target_file(dir, target, SESSION_EXTENSION)?;
+    session::apply(state, &contents);
+    Ok(())
+}
+
+/// Keeps what `session` restores of `state` for the next session with `target`.
+pub fn save_session(dir: &Path, target: &str, state: &AppState) -> io::Result<()> {
+    write_target_file(dir, target, SESSION_EXTENSION, &session::write(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process;
+
+    #[cfg(not(windows))]
+    #[test]
+    fn config_dir_follows_xdg_then_home() {
+        let config_dir = |vars: &[(&str, &str)]| {
+            let vars: Vec<_> = vars.to_vec();
+            config_dir_from(move |name| {
+                vars.iter()
+                    .find(|&&(var_name, _)| var_name == name)
+                    .map(|&(_, value)| PathBuf::from(value))
+            })
+        };
+        assert_eq!(
+            config_dir(&[("XDG_CONFIG_HOME", "/xdg"), ("HOME", "/home/ferris")]),
+            Some(PathBuf::from("/xdg/tokio-console"))
+        );
+        assert_eq!(
+            config_dir(&[("XDG_CONFIG_HOME", "xdg"), ("HOME", "/home/ferris")]),
+            Some(PathBuf::from("/home/ferris/.config/tokio-console"))
+        );
+        assert_eq!(config_dir(&[]), None);
+    }
+
+    #[test]
+    fn bookmarks_are_kept_per_target() {
+        let dir = env::temp_dir().join(format!("tokio-console-mocks-test-{}", process::id()));
+        let task_ids: BTreeSet<u64> = [3, 1, 4].iter().cloned().collect();
+        save_bookmarks(&dir, "localhost:6669", &task_ids).unwrap();
+
+        assert_eq!(load_bookmarks(&dir, "localhost:6669").unwrap(), task_ids);
+        assert!(load_bookmarks(&dir, "localhost:6670").unwrap().is_empty());
+        assert!(dir.join("localhost_6669.bookmarks").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
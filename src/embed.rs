@@ -0,0 +1,239 @@
+//! The console's panes as components that other apps can draw in their own UIs, such as an
+//! admin TUI that wants a view of its Tokio runtime next to everything else. Built with the
+//! `lib` feature.
+//!
+//! The host keeps an `AppState`, brings its snapshot up to date however it gets the data, and
+//! draws the panes it wants into rects of its own frames, calling `begin_frame` before the
+//! first of them each time. None of the binary's terminal setup
+//! comes along: raw mode, the alternate screen and the event loop stay the host's.
+
+use crate::components::{
+    self, Component, NotificationsPane, NotificationsPaneLayout, PerformancePane,
+    PerformancePaneLayout, Propagation, TasksPane, TasksPaneLayout, ViewContext,
+};
+use crate::glyphs::GlyphSet;
+use crate::state::AppState;
+use crossterm::event::Event;
+use std::mem;
+use stretch::geometry::{Rect, Size};
+use stretch::number::Number;
+use stretch::style::{AlignItems, FlexDirection, Style};
+use tui::backend::Backend;
+use tui::layout::Rect as TuiRect;
+use tui::Frame;
+use turbowish_widgets::layout::{LayoutTree, RectExt, SizeExt};
+use turbowish_widgets::renderer::WidgetRegistry;
+
+/// A pane of the console that can be drawn on its own.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Pane {
+    Performance,
+    Tasks,
+    Notifications,
+}
+
+/// Draws a pane into part of the host's frame. The pane is laid out again for every frame, in
+/// a stretch tree that's kept to reuse its storage.
+pub struct EmbeddedPane {
+    pane: Pane,
+    stretch: LayoutTree,
+}
+
+enum PaneLayout {
+    Performance(PerformancePaneLayout),
+    Tasks(TasksPaneLayout),
+    Notifications(NotificationsPaneLayout),
+}
+
+impl EmbeddedPane {
+    pub fn new(pane: Pane) -> EmbeddedPane {
+        EmbeddedPane {
+            pane,
+            stretch: LayoutTree::new(),
+        }
+    }
+
+    pub fn pane(&self) -> Pane {
+        self.pane
+    }
+
+    /// Draws the pane into `area`, which it fills as it would the console's screen. Its widgets
+    /// are added to the ones the other panes drew since `begin_frame`, so that `handle_event`
+    /// can find them.
+    pub fn draw<B>(
+        &mut self,
+        frame: &mut Frame<B>,
+        area: TuiRect,
+        glyph_set: &GlyphSet,
+        state: &mut AppState,
+    ) where
+        B: Backend,
+    {
+        // The root node covers the frame down to the area's corner, with padding up to the
+        // area, so that nodes land where the host wants them.
+        let stretch = &mut self.stretch;
+        stretch.clear();
+        let root = stretch.new_node(Style {
+            size: Size::fixed(area.right(), area.bottom()),
+            padding: Rect::new(area.y as i32, 0, 0, area.x as i32),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Stretch,
+            ..Default::default()
+        });
+        let layout = match self.pane {
+            Pane::Performance => {
+                PaneLayout::Performance(PerformancePane.layout(state, glyph_set, stretch, root))
+            }
+            Pane::Tasks => PaneLayout::Tasks(TasksPane.layout(state, glyph_set, stretch, root)),
+            Pane::Notifications => {
+                PaneLayout::Notifications(NotificationsPane.layout(state, glyph_set, stretch, root))
+            }
+        };
+        stretch.compute_layout(
+            root,
+            Size {
+                width: Number::Undefined,
+                height: Number::Undefined,
+            },
+        );
+
+        let mut context = ViewContext::new(stretch, root, glyph_set, state.theme, state.focused);
+        context.registry = mem::take(&mut state.widget_registry);
+        match layout {
+            PaneLayout::Performance(ref layout) => {
+                PerformancePane.view(state, frame, &mut context, layout)
+            }
+            PaneLayout::Tasks(ref layout) => TasksPane.view(state, frame, &mut context, layout),
+            PaneLayout::Notifications(ref layout) => {
+                NotificationsPane.view(state, frame, &mut context, layout)
+            }
+        }
+        state.widget_registry = context.registry;
+    }
+}
+
+/// Starts a frame of embedded panes, forgetting where the last frame's were drawn. Call it
+/// before drawing the first of them, so that a pane the host stops drawing stops taking clicks.
+pub fn begin_frame(state: &mut AppState) {
+    state.widget_registry = WidgetRegistry::new();
+}
+
+/// Offers an event to the embedded pane it's aimed at: a click on one of their widgets, or a
+/// key for the one with the focus. Returns `Propagation::Continue` if no pane took it, leaving
+/// it for the host.
+///
+/// Popups aren't drawn in embedded panes, so any that an event opens, such as the task actions
+/// menu, are closed again rather than left to take every event after it.
+pub fn handle_event(state: &mut AppState, event: &Event) -> Propagation {
+    let propagation = components::dispatch(state, event);
+    state.overlay = None;
+    propagation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use crate::glyphs::GLYPH_SET_NERD_FONT;
+    use crate::snapshot_tests::buffer_to_string;
+    use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+    use tui::backend::TestBackend;
+    use tui::widgets::{Block, Borders};
+    use tui::Terminal;
+
+    // Draws the tasks pane inside a host's box, with the performance pane above it.
+    fn draw_host(state: &mut AppState, panes: &mut [EmbeddedPane; 2]) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(60, 16)).unwrap();
+        terminal
+            .draw(|frame| {
+                begin_frame(state);
+                let host_block = Block::default().title("Admin").borders(Borders::ALL);
+                let inner = host_block.inner(frame.size());
+                frame.render_widget(host_block, frame.size());
+                let (performance_area, tasks_area) = (
+                    TuiRect::new(inner.x, inner.y, inner.width, 3),
+                    TuiRect::new(inner.x, inner.y + 3, inner.width, inner.height - 3),
+                );
+                panes[0].draw(frame, performance_area, &GLYPH_SET_NERD_FONT, state);
+                panes[1].draw(frame, tasks_area, &GLYPH_SET_NERD_FONT, state);
+            })
+            .unwrap();
+        buffer_to_string(terminal.backend().buffer())
+    }
+
+    #[test]
+    fn panes_draw_into_the_hosts_rects_and_take_events_aimed_at_them() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        let mut panes = [
+            EmbeddedPane::new(Pane::Performance),
+            EmbeddedPane::new(Pane::Tasks),
+        ];
+        let screen = draw_host(&mut state, &mut panes);
+        let rows: Vec<&str> = screen.lines().collect();
+        assert!(rows[0].starts_with("┌Admin"));
+        assert!(rows[3].starts_with("│╰"));
+        assert!(rows[1].starts_with("│╭─ Performance"), "{}", screen);
+        assert!(rows[4].starts_with("│╭─ Tasks"), "{}", screen);
+        assert!(rows[14].starts_with("│╰") && rows[15].starts_with("└"));
+
+        // Clicking a task's row selects it, at the row the host put it on.
+        let table_rect = state
+            .widget_registry
+            .rect(components::TASKS_TABLE_ID)
+            .unwrap();
+        assert!(table_rect.y > 4);
+        let click = Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: table_rect.x + 1,
+            row: table_rect.y + 1,
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(handle_event(&mut state, &click), Propagation::Stop);
+        assert!(state.selected_task_id.is_some());
+
+        // Nothing of the console's is outside of the panes.
+        let outside = Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 0,
+            row: 0,
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(handle_event(&mut state, &outside), Propagation::Continue);
+    }
+
+    #[test]
+    fn a_pane_the_host_stops_drawing_stops_taking_clicks() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        let mut panes = [
+            EmbeddedPane::new(Pane::Performance),
+            EmbeddedPane::new(Pane::Tasks),
+        ];
+        draw_host(&mut state, &mut panes);
+        let table_rect = state
+            .widget_registry
+            .rect(components::TASKS_TABLE_ID)
+            .unwrap();
+
+        // The host drops the tasks pane and draws only the performance pane.
+        let mut terminal = Terminal::new(TestBackend::new(60, 16)).unwrap();
+        terminal
+            .draw(|frame| {
+                begin_frame(&mut state);
+                panes[0].draw(
+                    frame,
+                    TuiRect::new(1, 1, 58, 3),
+                    &GLYPH_SET_NERD_FONT,
+                    &mut state,
+                );
+            })
+            .unwrap();
+        let click = Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: table_rect.x + 1,
+            row: table_rect.y + 1,
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(handle_event(&mut state, &click), Propagation::Continue);
+        assert!(state.selected_task_id.is_none());
+    }
+}
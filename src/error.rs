@@ -0,0 +1,69 @@
+use std::fmt::{self, Display, Formatter};
+
+static CONNECTION_REFUSED_TITLE: &'static str = "Connection refused";
+static CONNECTION_REFUSED_SUGGESTIONS: [&'static str; 3] = [
+    "Check that the instrumented application is running.",
+    "Check that the application was built with the console subscriber enabled.",
+    "Check the target address and port.",
+];
+static CONNECTION_LOST_TITLE: &'static str = "Connection lost";
+static CONNECTION_LOST_SUGGESTIONS: [&'static str; 2] = [
+    "Check that the instrumented application is still running.",
+    "Check the network between the console and the application.",
+];
+static INVALID_SCENARIO_TITLE: &'static str = "Invalid scenario";
+static INVALID_SCENARIO_SUGGESTIONS: [&'static str; 2] = [
+    "Fix the scenario file and retry.",
+    "Run without a scenario to use the built-in data.",
+];
+
+/// An error the console can recover from. These are shown on an in-app error screen that lets
+/// the user retry or quit; bugs still go through the panic hook.
+pub enum ConsoleError {
+    ConnectionRefused {
+        target: String,
+    },
+    /// The target stopped sending updates after the console connected to it.
+    ConnectionLost {
+        target: String,
+    },
+    InvalidScenario {
+        path: String,
+        reason: String,
+    },
+}
+
+impl ConsoleError {
+    pub fn title(&self) -> &'static str {
+        match *self {
+            ConsoleError::ConnectionRefused { .. } => CONNECTION_REFUSED_TITLE,
+            ConsoleError::ConnectionLost { .. } => CONNECTION_LOST_TITLE,
+            ConsoleError::InvalidScenario { .. } => INVALID_SCENARIO_TITLE,
+        }
+    }
+
+    pub fn suggestions(&self) -> &'static [&'static str] {
+        match *self {
+            ConsoleError::ConnectionRefused { .. } => &CONNECTION_REFUSED_SUGGESTIONS,
+            ConsoleError::ConnectionLost { .. } => &CONNECTION_LOST_SUGGESTIONS,
+            ConsoleError::InvalidScenario { .. } => &INVALID_SCENARIO_SUGGESTIONS,
+        }
+    }
+}
+
+impl Display for ConsoleError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match *self {
+            ConsoleError::ConnectionRefused { ref target } => {
+                write!(formatter, "Couldn't connect to {}.", target)
+            }
+            ConsoleError::ConnectionLost { ref target } => {
+                write!(formatter, "Lost the connection to {}.", target)
+            }
+            ConsoleError::InvalidScenario {
+                ref path,
+                ref reason,
+            } => write!(formatter, "Couldn't load {}: {}.", path, reason),
+        }
+    }
+}
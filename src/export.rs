@@ -0,0 +1,191 @@
+//! Writing the performance history out, for analysis in spreadsheets or notebooks.
+//!
+//! Both formats have a record per sample, oldest first: the time the sample was taken, then
+//! every metric's value in the metric's own unit, whether or not the performance pane shows it.
+//! Column names are the metrics' command-line keys with their units, such as `wake_ms`.
+
+use crate::format::Quantity;
+use crate::state::{Performance, PERFORMANCE_SAMPLE_INTERVAL};
+use crate::PERFORMANCE_METRICS;
+use chrono::{DateTime, Local};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+static TIMESTAMP_COLUMN: &'static str = "timestamp";
+static FILE_NAME_PREFIX: &'static str = "performance";
+static FILE_NAME_TIME_FORMAT: &'static str = "%Y%m%d-%H%M%S";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ExportFormat {
+    Csv,
+    /// An array with an object per sample.
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// Writes the history to a new file in `dir`, named for the format and the time of the newest
+/// sample, and returns its path. `runtime` names the runtime the history is of, if it's only
+/// one of the program's.
+pub fn export(
+    dir: &Path,
+    format: ExportFormat,
+    performance: &Performance,
+    runtime: Option<&str>,
+    newest_at: DateTime<Local>,
+) -> io::Result<PathBuf> {
+    let mut file_name = FILE_NAME_PREFIX.to_owned();
+    if let Some(runtime) = runtime {
+        file_name.push('-');
+        file_name.push_str(runtime);
+    }
+    let path = dir.join(format!(
+        "{}-{}.{}",
+        file_name,
+        newest_at.format(FILE_NAME_TIME_FORMAT),
+        format.extension()
+    ));
+    let mut out = BufWriter::new(File::create(&path)?);
+    write(&mut out, format, performance, newest_at)?;
+    out.flush()?;
+    Ok(path)
+}
+
+/// Writes the history to `out`, with the newest sample taken at `newest_at` and the others a
+/// sample interval apart before it. Older samples the history has averaged together are written
+/// as that average at each of their times.
+pub fn write<W>(
+    out: &mut W,
+    format: ExportFormat,
+    performance: &Performance,
+    newest_at: DateTime<Local>,
+) -> io::Result<()>
+where
+    W: Write,
+{
+    let columns: Vec<String> = (0..PERFORMANCE_METRICS.len()).map(column_name).collect();
+    let interval = chrono::Duration::from_std(PERFORMANCE_SAMPLE_INTERVAL).unwrap();
+    let sample_count = performance.history.len();
+    let records = performance
+        .history
+        .iter()
+        .enumerate()
+        .map(|(index, sample)| {
+            let taken_at = newest_at - interval * (sample_count - 1 - index) as i32;
+            (taken_at.to_rfc3339(), sample.values())
+        });
+
+    match format {
+        ExportFormat::Csv => {
+            writeln!(out, "{},{}", TIMESTAMP_COLUMN, columns.join(","))?;
+            for (timestamp, values) in records {
+                write!(out, "{}", timestamp)?;
+                for value in &values {
+                    // Spreadsheets take an empty cell better than `NaN`.
+                    if value.is_finite() {
+                        write!(out, ",{}", value)?;
+                    } else {
+                        write!(out, ",")?;
+                    }
+                }
+                writeln!(out)?;
+            }
+        }
+        ExportFormat::Json => {
+            write!(out, "[")?;
+            for (index, (timestamp, values)) in records.enumerate() {
+                if index > 0 {
+                    write!(out, ",")?;
+                }
+                write!(out, "\n  {{\"{}\": \"{}\"", TIMESTAMP_COLUMN, timestamp)?;
+                for (column, value) in columns.iter().zip(&values) {
+                    // JSON has no NaN or infinity.
+                    if value.is_finite() {
+                        write!(out, ", \"{}\": {}", column, value)?;
+                    } else {
+                        write!(out, ", \"{}\": null", column)?;
+                    }
+                }
+                write!(out, "}}")?;
+            }
+            writeln!(out, "\n]")?;
+        }
+    }
+    Ok(())
+}
+
+// Names a metric's column by its key and unit, such as `wake_ms`.
+fn column_name(metric: usize) -> String {
+    let performance_metric = &PERFORMANCE_METRICS[metric];
+    let unit = match performance_metric.quantity {
+        Quantity::Percent => "_percent",
+        Quantity::Number { .. } => "",
+        Quantity::Milliseconds => "_ms",
+        Quantity::PerSecond => "_per_s",
+    };
+    format!("{}{}", performance_metric.key, unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::PerformanceSample;
+    use chrono::TimeZone;
+
+    #[test]
+    fn exports_have_a_timestamped_record_per_sample() {
+        let performance = Performance {
+            current: PerformanceSample::default(),
+            history: vec![
+                PerformanceSample {
+                    run_percent: 12.5,
+                    ..PerformanceSample::default()
+                },
+                PerformanceSample {
+                    wake_ms: f32::NAN,
+                    ..PerformanceSample::default()
+                },
+            ]
+            .into_iter()
+            .collect(),
+        };
+        let newest_at = Local.ymd(2021, 3, 4).and_hms(13, 37, 0);
+        let export = |format| {
+            let mut out = vec![];
+            write(&mut out, format, &performance, newest_at).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        let offset = newest_at.format("%:z");
+
+        let csv = export(ExportFormat::Csv);
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "timestamp,runtime_percent,depth,poll_ms,wake_ms,steal_per_s,park_ms"
+        );
+        assert_eq!(
+            lines[1],
+            format!("2021-03-04T13:36:59{},12.5,0,0,0,0,0", offset)
+        );
+        assert_eq!(
+            lines[2],
+            format!("2021-03-04T13:37:00{},0,0,0,,0,0", offset)
+        );
+
+        let json = export(ExportFormat::Json);
+        assert!(json.starts_with(&format!(
+            "[\n  {{\"timestamp\": \"2021-03-04T13:36:59{}\", \"runtime_percent\": 12.5, ",
+            offset
+        )));
+        assert!(json.contains("\"wake_ms\": null"));
+        assert!(json.ends_with("}\n]\n"));
+    }
+}
@@ -0,0 +1,180 @@
+//! Drawing at a fixed size whatever the terminal's size, so that mocks are reviewed at exactly
+//! the sizes the team targets.
+//!
+//! The app is laid out for the fixed size. A bigger terminal shows it in the middle, with the
+//! rest left blank, and a smaller one shows its top left corner and cuts off the rest.
+
+use std::io::{self, Write};
+use tui::backend::Backend;
+use tui::buffer::Cell;
+use tui::layout::Rect;
+
+/// Where the top left corner of a screen of `size` goes in a terminal of `terminal_size`: in
+/// the middle of it, or in its corner along any dimension the screen doesn't fit in.
+pub fn offset(terminal_size: (u16, u16), size: (u16, u16)) -> (u16, u16) {
+    (
+        terminal_size.0.saturating_sub(size.0) / 2,
+        terminal_size.1.saturating_sub(size.1) / 2,
+    )
+}
+
+/// Where a cell of the terminal is on a screen of `size`, or `None` if it's outside of it.
+pub fn to_screen(
+    terminal_size: (u16, u16),
+    size: (u16, u16),
+    (column, row): (u16, u16),
+) -> Option<(u16, u16)> {
+    let (offset_x, offset_y) = offset(terminal_size, size);
+    let (x, y) = (column.checked_sub(offset_x)?, row.checked_sub(offset_y)?);
+    if x < size.0 && y < size.1 {
+        Some((x, y))
+    } else {
+        None
+    }
+}
+
+/// A backend that reports a fixed size rather than the terminal's, and draws the screen
+/// where `offset` puts it. With no fixed size, everything passes through as-is.
+///
+/// Moving the screen takes redrawing all of it, so when the terminal is resized, clear the
+/// `Terminal` this is in.
+pub struct FixedSizeBackend<B> {
+    backend: B,
+    size: Option<(u16, u16)>,
+}
+
+impl<B> FixedSizeBackend<B>
+where
+    B: Backend,
+{
+    pub fn new(backend: B, size: Option<(u16, u16)>) -> FixedSizeBackend<B> {
+        FixedSizeBackend { backend, size }
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    fn terminal_size(&self) -> io::Result<(u16, u16)> {
+        let area = self.backend.size()?;
+        Ok((area.width, area.height))
+    }
+}
+
+impl<B> Backend for FixedSizeBackend<B>
+where
+    B: Backend,
+{
+    fn draw<'a, I>(&mut self, content: I) -> Result<(), io::Error>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        let size = match self.size {
+            None => return self.backend.draw(content),
+            Some(size) => size,
+        };
+        let terminal_size = self.terminal_size()?;
+        let (offset_x, offset_y) = offset(terminal_size, size);
+        let cells = content
+            .map(|(x, y, cell)| (x + offset_x, y + offset_y, cell))
+            .filter(|&(x, y, _)| x < terminal_size.0 && y < terminal_size.1);
+        self.backend.draw(cells)
+    }
+
+    fn hide_cursor(&mut self) -> Result<(), io::Error> {
+        self.backend.hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> Result<(), io::Error> {
+        self.backend.show_cursor()
+    }
+
+    fn get_cursor(&mut self) -> Result<(u16, u16), io::Error> {
+        let (x, y) = self.backend.get_cursor()?;
+        let size = match self.size {
+            None => return Ok((x, y)),
+            Some(size) => size,
+        };
+        let (offset_x, offset_y) = offset(self.terminal_size()?, size);
+        Ok((x.saturating_sub(offset_x), y.saturating_sub(offset_y)))
+    }
+
+    fn set_cursor(&mut self, x: u16, y: u16) -> Result<(), io::Error> {
+        let size = match self.size {
+            None => return self.backend.set_cursor(x, y),
+            Some(size) => size,
+        };
+        let (offset_x, offset_y) = offset(self.terminal_size()?, size);
+        self.backend.set_cursor(x + offset_x, y + offset_y)
+    }
+
+    fn clear(&mut self) -> Result<(), io::Error> {
+        self.backend.clear()
+    }
+
+    fn size(&self) -> Result<Rect, io::Error> {
+        match self.size {
+            Some((width, height)) => Ok(Rect::new(0, 0, width, height)),
+            None => self.backend.size(),
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), io::Error> {
+        self.backend.flush()
+    }
+}
+
+/// Passes writes through, so that backends that write escape sequences of their own, such as
+/// `HyperlinkBackend`, can go around this one.
+impl<B> Write for FixedSizeBackend<B>
+where
+    B: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.backend.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Write::flush(&mut self.backend)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot_tests::buffer_to_string;
+    use tui::backend::TestBackend;
+    use tui::widgets::{Block, Borders};
+    use tui::Terminal;
+
+    fn draw_box(terminal_size: (u16, u16), size: (u16, u16)) -> String {
+        let backend = TestBackend::new(terminal_size.0, terminal_size.1);
+        let mut terminal = Terminal::new(FixedSizeBackend::new(backend, Some(size))).unwrap();
+        terminal
+            .draw(|frame| {
+                assert_eq!(frame.size(), Rect::new(0, 0, size.0, size.1));
+                frame.render_widget(Block::default().borders(Borders::ALL), frame.size());
+            })
+            .unwrap();
+        buffer_to_string(terminal.backend().backend().buffer())
+    }
+
+    #[test]
+    fn screens_are_centered_in_bigger_terminals_and_cut_off_in_smaller_ones() {
+        assert_eq!(
+            draw_box((8, 5), (4, 3)),
+            "        \n  ┌──┐  \n  │  │  \n  └──┘  \n        \n"
+        );
+        assert_eq!(draw_box((3, 2), (4, 3)), "┌──\n│  \n");
+        assert_eq!(draw_box((6, 2), (4, 3)), " ┌──┐ \n │  │ \n");
+    }
+
+    #[test]
+    fn terminal_cells_map_onto_the_screen() {
+        assert_eq!(to_screen((100, 30), (80, 24), (10, 3)), Some((0, 0)));
+        assert_eq!(to_screen((100, 30), (80, 24), (89, 26)), Some((79, 23)));
+        assert_eq!(to_screen((100, 30), (80, 24), (9, 3)), None);
+        assert_eq!(to_screen((100, 30), (80, 24), (90, 3)), None);
+        assert_eq!(to_screen((60, 20), (80, 24), (59, 19)), Some((59, 19)));
+    }
+}
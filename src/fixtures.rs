@@ -0,0 +1,1089 @@
+//! Example task data, shared by the binary, the tests, and the benchmarks so that they all show
+//! the same thing.
+//!
+//! The data is made up but deterministic: the same dataset always has the same tasks in the
+//! same order.
+
+use crate::clock::FixedClock;
+use crate::history::DEFAULT_HISTORY_CAPACITY;
+use crate::state::{
+    AppState, Performance, PerformanceSample, Runtime, Snapshot, PERFORMANCE_SAMPLE_INTERVAL,
+};
+use crate::{BusyPeriod, Resource, SpawnSite, Task, TaskStatus};
+use chrono::{Local, TimeZone};
+use std::time::Duration;
+
+static TARGET_LABEL: &'static str = "my_app (412)";
+static DATASETS: [(&'static str, Dataset); 5] = [
+    ("tiny", Dataset::Tiny),
+    ("typical", Dataset::Typical),
+    ("pathological", Dataset::Pathological),
+    ("deadlock", Dataset::Deadlock),
+    ("stress", Dataset::Stress),
+];
+static TYPICAL_TASK_NAMES: [&'static str; 5] = [
+    "connection-handler",
+    "connection-handler",
+    "db-pool-worker",
+    "timer",
+    "log-flusher",
+];
+/// The task counts the mockups were drawn with: 405 tasks, 3 of them running.
+const TYPICAL_TASK_COUNT: usize = 405;
+const TYPICAL_RUNNING_TASK_COUNT: usize = 3;
+const TYPICAL_FIRST_GENERATED_ID: u64 = 287;
+const STRESS_TASK_COUNT: usize = 50_000;
+/// Clear of the IDs the other datasets' resources name, so that none of those show up.
+const STRESS_FIRST_ID: u64 = 100_000;
+const STRESS_SEED: u32 = 0x57e5;
+/// The odds, one in this many, of a stress task running.
+const STRESS_RUNNING_ODDS: u32 = 40;
+/// Stress tasks are named a subject, then a role, such as `grpc-poller`.
+static STRESS_NAME_SUBJECTS: [&'static str; 8] = [
+    "http", "grpc", "db", "cache", "queue", "auth", "metrics", "upload",
+];
+static STRESS_NAME_ROLES: [&'static str; 6] = [
+    "handler", "worker", "poller", "flusher", "reaper", "listener",
+];
+/// Stress tasks have a run of these attributes, of varying length.
+static STRESS_ATTRIBUTE_KEYS: [&'static str; 5] =
+    ["request-id", "trace-id", "user-id", "shard", "topic"];
+// Keeps spawned tasks from repeating the generated typical ones.
+const SPAWNED_TASK_SEED: u32 = 0x5eed;
+const PERFORMANCE_SAMPLE_SEED: u32 = 0x5a3e;
+const RUNTIME_PERFORMANCE_SAMPLE_SEED: u32 = 0x2a7e;
+const RUNTIME_COUNT: u32 = 1;
+const THREAD_COUNT: u32 = 8;
+const MAX_LIFETIME_MILLIS: u64 = 2 * 60 * 60 * 1000;
+/// How many stretches each task's busy time is split into, spread evenly over its life.
+const BUSY_PERIOD_COUNT: u32 = 24;
+const SNAPSHOT_AGE: Duration = Duration::from_millis(200);
+const DEMO_SEED: u32 = 0xde70;
+const DEMO_DEADLOCK_AFTER: Duration = Duration::from_secs(30);
+/// The most a measurement moves in a sample interval in the demo, as a factor either way.
+const DEMO_WALK_FACTOR: f32 = 1.1;
+/// How far the program's measurements wander in the demo, as a factor either way of where they
+/// started.
+const DEMO_WALK_RANGE: f32 = 3.0;
+/// The odds, one in this many, of a running task going to sleep in a sample interval, and of a
+/// sleeping one waking up. Most tasks sleep, as on a server that isn't too busy.
+const DEMO_SLEEP_ODDS: u32 = 5;
+const DEMO_WAKE_ODDS: u32 = 400;
+/// The odds, one in this many, of a connection handler finishing in a sample interval, which
+/// about keeps up with the ones being spawned.
+const DEMO_COMPLETION_ODDS: u32 = 2;
+/// The most busy periods a task keeps in the demo, where a running task adds one every sample
+/// interval. The oldest go first, which is further back than the timeline usually looks.
+const DEMO_BUSY_PERIOD_LIMIT: usize = 600;
+static DEMO_COMPLETING_TASK_NAME: &'static str = "connection-handler";
+/// The demo's deadlock is between the first two tasks with this name, each holding one of
+/// these resources and waiting on the other.
+static DEMO_DEADLOCK_TASK_NAME: &'static str = "db-pool-worker";
+static DEMO_DEADLOCK_RESOURCES: [(&'static str, &'static str); 2] =
+    [("Mutex", "pool"), ("RwLock", "schema")];
+static CURRENT_PERFORMANCE: PerformanceSample = PerformanceSample {
+    run_percent: 23.3,
+    scheduler_depth: 2.19,
+    poll_ms: 1.05,
+    wake_ms: 0.75,
+    steal_rate: 12.4,
+    park_ms: 3.2,
+};
+/// Every measurement follows the same shape, so that the graphs look alike.
+static PERFORMANCE_HISTORY_SHAPE: [f32; 7] = [4.0, 2.0, 7.0, 1.0, 7.0, 8.0, 3.0];
+/// Task names, with the file and line each is spawned on, then the first line of the snippet
+/// around it and the snippet itself.
+static SPAWN_SITES: [(&'static str, &'static str, u32, u32, &'static str); 6] = [
+    (
+        "connection-handler",
+        "src/server.rs",
+        42,
+        36,
+        r#"    loop {
+        let (socket, remote_address) = listener.accept().await?;
+        let request_id = Uuid::new_v4();
+        // Each client gets its own task, so one slow client can't hold up the rest.
+        tokio::task::Builder::new()
+            .name("connection-handler")
+            .spawn(handle_connection(socket, remote_address, request_id));
+    }"#,
+    ),
+    (
+        "public-accept",
+        "src/main.rs",
+        23,
+        18,
+        r#"#[tokio::main]
+async fn main() -> io::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:8080").await?;
+    tokio::task::Builder::new()
+        .name("public-accept")
+        .spawn(server::accept_loop(listener));"#,
+    ),
+    (
+        "db-pool-worker",
+        "src/db.rs",
+        16,
+        12,
+        r#"impl Pool {
+    pub fn new(size: usize) -> Pool {
+        let (sender, receiver) = async_channel::unbounded();
+        for _ in 0..size {
+            tokio::spawn(worker(receiver.clone()));
+        }
+        Pool { sender }
+    }"#,
+    ),
+    (
+        "timer",
+        "src/metrics.rs",
+        9,
+        8,
+        r#"pub fn start_reporting(registry: Arc<Registry>) {
+    tokio::spawn(async move {
+        let mut interval = time::interval(Duration::from_secs(1));
+        loop {
+            interval.tick().await;
+            registry.report();
+        }
+    });
+}"#,
+    ),
+    (
+        "log-flusher",
+        "src/logging.rs",
+        30,
+        27,
+        r#"    let (writer, mut lines) = mpsc::channel::<String>(1024);
+    /* Flushing on a task of its own keeps
+       slow disks off the request path. */
+    tokio::spawn(async move {
+        while let Some(line) = lines.recv().await {
+            file.write_all(line.as_bytes()).await.ok();
+        }
+    });"#,
+    ),
+    (
+        "cache-refresher",
+        "src/cache.rs",
+        18,
+        17,
+        r#"pub fn start_refreshing(cache: Arc<Cache>, every: Duration) {
+    tokio::spawn(async move {
+        let mut interval = time::interval(every);
+        loop {
+            interval.tick().await;
+            let mut profiles = cache.profiles.lock().await;
+            // Takes the session index while holding the profiles,
+            // the other way around from the session reaper.
+            let sessions = cache.session_index.read().await;
+            profiles.refresh(&sessions);
+        }
+    });
+}"#,
+    ),
+];
+/// What the tasks hold and wait on. Two of the pathological tasks are deadlocked, each holding
+/// the lock the other waits on, with a third stuck behind them. In the deadlock dataset, three
+/// tasks each hold the lock the next one waits on, with a connection handler stuck behind them.
+static RESOURCES: [ResourceFixture; 7] = [
+    (1, "Semaphore", "connection_limit", &[285, 286], &[1]),
+    (2, "Mutex", "sessions", &[285], &[286]),
+    (3, "Mutex", "left", &[2], &[6, 3]),
+    (4, "RwLock", "right", &[6], &[2]),
+    (5, "Mutex", "profile_cache", &[712], &[714, 715]),
+    (6, "RwLock", "session_index", &[713], &[712]),
+    (7, "Mutex", "audit_log", &[714], &[713]),
+];
+/// The pathological program splits its work across runtimes, for showing them side by side.
+static PATHOLOGICAL_RUNTIME_NAMES: [&'static str; 3] = ["main", "blocking-io", "compute"];
+
+/// A named set of tasks.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Dataset {
+    /// The four hand-written tasks. Snapshot tests use these, so they stay small enough to read.
+    Tiny,
+    /// A few hundred tasks, as a busy server might have.
+    Typical,
+    /// Tasks with extreme values and awkward text, for checking that nothing breaks.
+    Pathological,
+    /// The tiny tasks, plus tasks that wait on each other in a cycle and one stuck behind them,
+    /// for showing off how deadlocks look.
+    Deadlock,
+    /// Tens of thousands of tasks and a full hour of performance history, for checking that the
+    /// console keeps up with a very large program.
+    Stress,
+}
+
+impl Dataset {
+    pub fn from_name(name: &str) -> Option<Dataset> {
+        DATASETS
+            .iter()
+            .find(|&&(dataset_name, _)| dataset_name == name)
+            .map(|&(_, dataset)| dataset)
+    }
+
+    pub fn tasks(self) -> Vec<Task> {
+        match self {
+            Dataset::Tiny => tiny(),
+            Dataset::Typical => typical(),
+            Dataset::Pathological => pathological(),
+            Dataset::Deadlock => deadlock(),
+            Dataset::Stress => stress(),
+        }
+    }
+
+    pub fn snapshot(self) -> Snapshot {
+        match self {
+            Dataset::Pathological => {
+                multi_runtime_snapshot(self.tasks(), &PATHOLOGICAL_RUNTIME_NAMES)
+            }
+            Dataset::Stress => {
+                let mut snapshot = snapshot(self.tasks());
+                snapshot.performance.history = (0..DEFAULT_HISTORY_CAPACITY as u32)
+                    .map(performance_sample)
+                    .collect();
+                snapshot
+            }
+            _ => snapshot(self.tasks()),
+        }
+    }
+}
+
+/// A clock stopped at the moment the example data was taken, so that the title bar shows the same
+/// time on every run.
+pub fn fixed_clock() -> FixedClock {
+    FixedClock::new(Local.ymd(2021, 3, 4).and_hms(13, 37, 0))
+}
+
+/// Wraps `tasks` in a snapshot of a single-runtime program with made-up performance figures.
+pub fn snapshot(tasks: Vec<Task>) -> Snapshot {
+    let history = PERFORMANCE_HISTORY_SHAPE
+        .iter()
+        .map(|&value| PerformanceSample {
+            run_percent: value,
+            scheduler_depth: value,
+            poll_ms: value,
+            wake_ms: value,
+            steal_rate: value,
+            park_ms: value,
+        })
+        .collect();
+    Snapshot {
+        target: TARGET_LABEL.to_owned(),
+        runtime_count: RUNTIME_COUNT,
+        thread_count: THREAD_COUNT,
+        resources: resources(&tasks),
+        tasks,
+        performance: Performance {
+            current: CURRENT_PERFORMANCE,
+            history,
+        },
+        runtimes: vec![],
+        age: SNAPSHOT_AGE,
+        generation: 0,
+    }
+}
+
+/// Like `snapshot`, but for a program with a runtime of each name, each with made-up
+/// performance figures of its own.
+pub fn multi_runtime_snapshot(tasks: Vec<Task>, runtime_names: &[&str]) -> Snapshot {
+    let runtimes = runtime_names
+        .iter()
+        .enumerate()
+        .map(|(runtime, name)| Runtime {
+            name: (*name).to_owned(),
+            performance: Performance {
+                current: runtime_performance_sample(runtime, 0),
+                history: (0..PERFORMANCE_HISTORY_SHAPE.len() as u32)
+                    .map(|index| runtime_performance_sample(runtime, index))
+                    .collect(),
+            },
+        })
+        .collect();
+    Snapshot {
+        runtime_count: runtime_names.len() as u32,
+        runtimes,
+        ..snapshot(tasks)
+    }
+}
+
+pub fn tiny() -> Vec<Task> {
+    vec![
+        task(
+            285,
+            "connection-handler",
+            TaskStatus::Running,
+            (24.5, 1.41, 0.713),
+            &[
+                ("remote-address", "127.0.0.1:56723"),
+                ("request-id", "dbabfa1a-f722-41c0-82dc-a02e88e55d2a"),
+            ],
+        ),
+        task(
+            286,
+            "connection-handler",
+            TaskStatus::Sleeping,
+            (1.9, 1.14, 0.692),
+            &[
+                ("remote-address", "127.0.0.1:34135"),
+                ("request-id", "2087d5f8-7275-4179-a0b4-5ed285b0d988"),
+            ],
+        ),
+        task(
+            1,
+            "public-accept",
+            TaskStatus::Sleeping,
+            (0.6, 0.13, 0.501),
+            &[("local-address", "127.0.0.1:8080")],
+        ),
+        task(0, "main", TaskStatus::Sleeping, (0.0, 0.09, 0.106), &[]),
+    ]
+}
+
+/// The tiny tasks followed by generated ones, up to the task counts the mockups were drawn with.
+pub fn typical() -> Vec<Task> {
+    let mut tasks = tiny();
+    let running_task_count = tasks
+        .iter()
+        .filter(|task| task.status == TaskStatus::Running)
+        .count();
+    for index in 0..(TYPICAL_TASK_COUNT - tasks.len()) {
+        let noise = mix(index as u32);
+        let name = TYPICAL_TASK_NAMES[index % TYPICAL_TASK_NAMES.len()];
+        let (status, run_percent) = if index < TYPICAL_RUNNING_TASK_COUNT - running_task_count {
+            (TaskStatus::Running, 5.0 + (noise % 150) as f32 / 10.0)
+        } else {
+            (TaskStatus::Sleeping, (noise % 50) as f32 / 100.0)
+        };
+        let poll_ms = 0.05 + (noise / 7 % 300) as f32 / 100.0;
+        let wake_ms = 0.01 + (noise / 11 % 1000) as f32 / 1000.0;
+        let remote_address = format!("127.0.0.1:{}", 32768 + noise % 28232);
+        let attributes: &[(&str, &str)] = match name {
+            "connection-handler" => &[("remote-address", &remote_address)],
+            "timer" => &[("interval", "1s")],
+            _ => &[],
+        };
+        tasks.push(task(
+            TYPICAL_FIRST_GENERATED_ID + index as u64,
+            name,
+            status,
+            (run_percent, poll_ms, wake_ms),
+            attributes,
+        ));
+    }
+    tasks
+}
+
+/// Text that's empty, very long, double width, or combining, and numbers that are huge or not
+/// numbers at all.
+pub fn pathological() -> Vec<Task> {
+    let long_name = "very-".repeat(40) + "long-name";
+    let long_value = "x".repeat(300);
+    let many_attributes: Vec<(String, String)> = (0..20)
+        .map(|index| (format!("key-{}", index), format!("value-{}", index)))
+        .collect();
+    let many_attributes: Vec<(&str, &str)> = many_attributes
+        .iter()
+        .map(|(key, value)| (&key[..], &value[..]))
+        .collect();
+    vec![
+        task(u64::MAX, "", TaskStatus::Running, (100.0, 0.0, 0.0), &[]),
+        task(
+            2,
+            &long_name,
+            TaskStatus::Deadlocked,
+            (1.0e9, 123_456_789.0, 987_654.3),
+            &[("request-id", &long_value)],
+        ),
+        task(
+            3,
+            "接続ハンドラー",
+            TaskStatus::Sleeping,
+            (f32::NAN, f32::INFINITY, -1.0),
+            &[("アドレス", "127.0.0.1:8080")],
+        ),
+        task(
+            4,
+            "🦀 crab-task",
+            TaskStatus::Running,
+            (0.05, 0.005, 0.0005),
+            &[("emoji", "🦀🦀🦀"), ("odd", "a=b, c=d")],
+        ),
+        task(
+            5,
+            "cafe\u{301}-combining",
+            TaskStatus::Sleeping,
+            (0.0, 0.0, 0.0),
+            &many_attributes,
+        ),
+        task(
+            6,
+            "\t\u{200b}",
+            TaskStatus::Deadlocked,
+            (-0.0, 0.0, 0.0),
+            &[("", "")],
+        ),
+    ]
+}
+
+/// The tiny tasks, plus a cache refresher, a session reaper, and an audit writer that each hold
+/// the lock the next one waits on, and a connection handler waiting behind them.
+pub fn deadlock() -> Vec<Task> {
+    let mut tasks = tiny();
+    tasks.extend(vec![
+        task(
+            712,
+            "cache-refresher",
+            TaskStatus::Deadlocked,
+            (0.0, 3.82, 0.418),
+            &[("cache", "user-profiles"), ("interval", "30s")],
+        ),
+        task(
+            713,
+            "session-reaper",
+            TaskStatus::Deadlocked,
+            (0.0, 0.97, 0.207),
+            &[("idle-timeout", "15m")],
+        ),
+        task(
+            714,
+            "audit-writer",
+            TaskStatus::Deadlocked,
+            (0.0, 2.25, 0.654),
+            &[("path", "/var/log/my_app/audit.log")],
+        ),
+        task(
+            715,
+            "connection-handler",
+            TaskStatus::Sleeping,
+            (0.0, 1.27, 0.731),
+            &[
+                ("remote-address", "127.0.0.1:51877"),
+                ("request-id", "6f0c3b8e-1d2a-4f57-9b6e-3c8a7d19e402"),
+            ],
+        ),
+    ]);
+    tasks
+}
+
+/// Tens of thousands of tasks with a few dozen names between them, mostly sleeping, each with
+/// none or some of a handful of attributes.
+pub fn stress() -> Vec<Task> {
+    (0..STRESS_TASK_COUNT)
+        .map(|index| {
+            let noise = mix(index as u32 ^ STRESS_SEED);
+            let name = format!(
+                "{}-{}",
+                STRESS_NAME_SUBJECTS[noise as usize % STRESS_NAME_SUBJECTS.len()],
+                STRESS_NAME_ROLES[(noise >> 4) as usize % STRESS_NAME_ROLES.len()]
+            );
+            let (status, run_percent) = if (noise >> 8).is_multiple_of(STRESS_RUNNING_ODDS) {
+                (
+                    TaskStatus::Running,
+                    1.0 + ((noise >> 12) % 200) as f32 / 10.0,
+                )
+            } else {
+                (TaskStatus::Sleeping, ((noise >> 12) % 50) as f32 / 100.0)
+            };
+            let poll_ms = 0.02 + (noise / 7 % 500) as f32 / 100.0;
+            let wake_ms = 0.01 + (noise / 11 % 1000) as f32 / 1000.0;
+            let first_key = (noise >> 20) as usize % STRESS_ATTRIBUTE_KEYS.len();
+            let attributes: Vec<(&str, String)> = STRESS_ATTRIBUTE_KEYS[first_key..]
+                .iter()
+                .enumerate()
+                .map(|(key_index, &key)| (key, format!("{:08x}", mix(noise ^ key_index as u32))))
+                .collect();
+            let attributes: Vec<(&str, &str)> = attributes
+                .iter()
+                .map(|(key, value)| (*key, &value[..]))
+                .collect();
+            task(
+                STRESS_FIRST_ID + index as u64,
+                &name,
+                status,
+                (run_percent, poll_ms, wake_ms),
+                &attributes,
+            )
+        })
+        .collect()
+}
+
+/// `count` tasks made by repeating the typical dataset with fresh IDs, for measuring how
+/// things scale.
+pub fn repeated(count: usize) -> Vec<Task> {
+    let typical_tasks = typical();
+    (0..count)
+        .map(|index| Task {
+            id: index as u64,
+            ..typical_tasks[index % typical_tasks.len()].clone()
+        })
+        .collect()
+}
+
+/// A connection handler for a client that just connected, as a server keeps spawning. Each
+/// `index` gives a different client. The ID is left for whoever adds the task to choose.
+pub fn spawned_task(index: u32) -> Task {
+    let noise = mix(index ^ SPAWNED_TASK_SEED);
+    let remote_address = format!("127.0.0.1:{}", 32768 + noise % 28232);
+    let task = task(
+        0,
+        "connection-handler",
+        TaskStatus::Sleeping,
+        (
+            (noise % 50) as f32 / 100.0,
+            0.05 + (noise / 7 % 300) as f32 / 100.0,
+            0.01 + (noise / 11 % 1000) as f32 / 1000.0,
+        ),
+        &[("remote-address", &remote_address)],
+    );
+    Task {
+        busy_time: Duration::ZERO,
+        idle_time: Duration::ZERO,
+        ..task
+    }
+}
+
+fn task(
+    id: u64,
+    name: &str,
+    status: TaskStatus,
+    (run_percent, poll_ms, wake_ms): (f32, f32, f32),
+    attributes: &[(&str, &str)],
+) -> Task {
+    // Made-up lifetimes of up to a couple of hours, split between busy and idle time by how much
+    // of the runtime the task is using.
+    let lifetime = Duration::from_millis(u64::from(mix(id as u32)) % MAX_LIFETIME_MILLIS);
+    let busy_fraction = if run_percent.is_finite() {
+        (run_percent / 100.0).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let busy_time = lifetime.mul_f32(busy_fraction);
+    Task {
+        id,
+        name: name.to_owned(),
+        status,
+        run_percent,
+        poll_ms,
+        wake_ms,
+        attributes: attributes
+            .iter()
+            .map(|&(key, value)| (key.to_owned(), value.to_owned()))
+            .collect(),
+        busy_time,
+        idle_time: lifetime.saturating_sub(busy_time),
+        paused: false,
+        boosted: false,
+        spawn_site: spawn_site(name),
+        busy_periods: busy_periods(id, lifetime, busy_time),
+    }
+}
+
+// A resource's ID, kind, and name, then the IDs of the tasks holding it and of those waiting on
+// it.
+type ResourceFixture = (
+    u64,
+    &'static str,
+    &'static str,
+    &'static [u64],
+    &'static [u64],
+);
+
+// The resources that any of `tasks` hold or wait on, with only those tasks.
+fn resources(tasks: &[Task]) -> Vec<Resource> {
+    let task_ids = |ids: &[u64]| -> Vec<u64> {
+        ids.iter()
+            .copied()
+            .filter(|&id| tasks.iter().any(|task| task.id == id))
+            .collect()
+    };
+    RESOURCES
+        .iter()
+        .map(
+            |&(id, kind, name, holder_task_ids, waiting_task_ids)| Resource {
+                id,
+                kind: kind.to_owned(),
+                name: name.to_owned(),
+                holder_task_ids: task_ids(holder_task_ids),
+                waiting_task_ids: task_ids(waiting_task_ids),
+            },
+        )
+        .filter(|resource| {
+            !resource.holder_task_ids.is_empty() || !resource.waiting_task_ids.is_empty()
+        })
+        .collect()
+}
+
+// `busy_time` split into equal stretches, each somewhere in its own equal part of the task's
+// life.
+fn busy_periods(id: u64, lifetime: Duration, busy_time: Duration) -> Vec<BusyPeriod> {
+    let part_length = lifetime / BUSY_PERIOD_COUNT;
+    let period_length = busy_time / BUSY_PERIOD_COUNT;
+    if period_length.is_zero() {
+        return vec![];
+    }
+    (0..BUSY_PERIOD_COUNT)
+        .map(|part| {
+            let slack = part_length.saturating_sub(period_length);
+            let position = mix(id as u32 ^ mix(part)) % 1000;
+            let started_ago = lifetime - part_length * part - slack * position / 1000;
+            BusyPeriod {
+                started_ago,
+                ended_ago: started_ago.saturating_sub(period_length),
+            }
+        })
+        .collect()
+}
+
+// Where the tasks named `name` are spawned in the program the mockups watch, if it's a task the
+// mockups have source for.
+fn spawn_site(name: &str) -> Option<SpawnSite> {
+    SPAWN_SITES
+        .iter()
+        .find(|&&(task_name, ..)| task_name == name)
+        .map(|&(_, file, line, first_line, snippet)| SpawnSite {
+            file: file.to_owned(),
+            line,
+            snippet: snippet.to_owned(),
+            first_line,
+        })
+}
+
+/// A made-up performance sample, the `index`th taken since the snapshot. Each measurement
+/// wanders between half and one and a half times the snapshot's figure.
+pub fn performance_sample(index: u32) -> PerformanceSample {
+    let noise = mix(index ^ PERFORMANCE_SAMPLE_SEED);
+    let scale = |shift: u32| 0.5 + (noise >> shift & 0xff) as f32 / 255.0;
+    PerformanceSample {
+        run_percent: CURRENT_PERFORMANCE.run_percent * scale(0),
+        scheduler_depth: CURRENT_PERFORMANCE.scheduler_depth * scale(4),
+        poll_ms: CURRENT_PERFORMANCE.poll_ms * scale(8),
+        wake_ms: CURRENT_PERFORMANCE.wake_ms * scale(12),
+        steal_rate: CURRENT_PERFORMANCE.steal_rate * scale(16),
+        park_ms: CURRENT_PERFORMANCE.park_ms * scale(20),
+    }
+}
+
+/// A made-up performance sample for one of the runtimes of a multi-runtime snapshot, wandering
+/// the way `performance_sample` does but differently for each runtime.
+pub fn runtime_performance_sample(runtime: usize, index: u32) -> PerformanceSample {
+    let seed = mix(runtime as u32 ^ RUNTIME_PERFORMANCE_SAMPLE_SEED);
+    performance_sample(index.wrapping_add(seed))
+}
+
+/// A target that keeps changing, for `--demo`. Every sample interval, tasks go to sleep and
+/// wake up, connection handlers finish, and the measurements each take a random step. Half a
+/// minute in, two tasks deadlock. Like the datasets, it does the same thing every time.
+pub struct Demo {
+    sample_count: u32,
+    /// The program's performance when the demo started, which its measurements wander around.
+    start: PerformanceSample,
+    current: PerformanceSample,
+}
+
+impl Demo {
+    pub fn new(performance: PerformanceSample) -> Demo {
+        Demo {
+            sample_count: 0,
+            start: performance,
+            current: performance,
+        }
+    }
+
+    /// Moves the target in `state` on by a sample interval, and returns the program's
+    /// performance over that interval.
+    pub fn advance(&mut self, state: &mut AppState) -> PerformanceSample {
+        self.sample_count += 1;
+        let noise = mix(self.sample_count ^ DEMO_SEED);
+        for task in &mut state.snapshot.tasks {
+            advance_task(task, mix(task.id as u32 ^ noise));
+        }
+        complete_connection(state, noise);
+        if PERFORMANCE_SAMPLE_INTERVAL * self.sample_count == DEMO_DEADLOCK_AFTER {
+            deadlock_demo_tasks(state);
+        }
+
+        let (start, current) = (self.start, self.current);
+        let walk = |value: f32, start: f32, shift: u32| {
+            walk(value, noise >> shift).clamp(start / DEMO_WALK_RANGE, start * DEMO_WALK_RANGE)
+        };
+        self.current = PerformanceSample {
+            run_percent: walk(current.run_percent, start.run_percent, 0).min(100.0),
+            scheduler_depth: walk(current.scheduler_depth, start.scheduler_depth, 4),
+            poll_ms: walk(current.poll_ms, start.poll_ms, 8),
+            wake_ms: walk(current.wake_ms, start.wake_ms, 12),
+            steal_rate: walk(current.steal_rate, start.steal_rate, 16),
+            park_ms: walk(current.park_ms, start.park_ms, 20),
+        };
+        self.current
+    }
+}
+
+// Ages the task by a sample interval, which it spent busy for its share of the runtime if it's
+// running. Unless it's stuck, it may then go to sleep or wake up, and its measurements take a
+// step.
+fn advance_task(task: &mut Task, noise: u32) {
+    let interval = PERFORMANCE_SAMPLE_INTERVAL;
+    let running = task.status == TaskStatus::Running && !task.paused;
+    let busy_fraction = if running && task.run_percent.is_finite() {
+        (task.run_percent / 100.0).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let busy_time = interval.mul_f32(busy_fraction);
+    task.busy_time = task.busy_time.saturating_add(busy_time);
+    task.idle_time = task.idle_time.saturating_add(interval - busy_time);
+    for busy_period in &mut task.busy_periods {
+        busy_period.started_ago = busy_period.started_ago.saturating_add(interval);
+        busy_period.ended_ago = busy_period.ended_ago.saturating_add(interval);
+    }
+    if !busy_time.is_zero() {
+        task.busy_periods.push(BusyPeriod {
+            started_ago: busy_time,
+            ended_ago: Duration::ZERO,
+        });
+        let excess = task
+            .busy_periods
+            .len()
+            .saturating_sub(DEMO_BUSY_PERIOD_LIMIT);
+        task.busy_periods.drain(..excess);
+    }
+
+    if task.status == TaskStatus::Deadlocked || task.paused {
+        return;
+    }
+    task.status = match task.status {
+        TaskStatus::Running if noise.is_multiple_of(DEMO_SLEEP_ODDS) => TaskStatus::Sleeping,
+        TaskStatus::Sleeping if noise.is_multiple_of(DEMO_WAKE_ODDS) => TaskStatus::Running,
+        status => status,
+    };
+    task.run_percent = walk(task.run_percent, noise >> 8).min(100.0);
+    task.poll_ms = walk(task.poll_ms, noise >> 16);
+    task.wake_ms = walk(task.wake_ms, noise >> 24);
+}
+
+// Now and then, lets one of the connection handlers that don't hold or wait on anything finish.
+fn complete_connection(state: &mut AppState, noise: u32) {
+    if !noise.is_multiple_of(DEMO_COMPLETION_ODDS) {
+        return;
+    }
+    let snapshot = &state.snapshot;
+    let task_ids: Vec<u64> = snapshot
+        .tasks
+        .iter()
+        .filter(|task| task.name == DEMO_COMPLETING_TASK_NAME)
+        .filter(|task| !holds_or_waits(snapshot, task.id))
+        .map(|task| task.id)
+        .collect();
+    if !task_ids.is_empty() {
+        let task_id = task_ids[(noise / DEMO_COMPLETION_ODDS) as usize % task_ids.len()];
+        state.complete_task(task_id);
+    }
+}
+
+// Has the first two tasks named `DEMO_DEADLOCK_TASK_NAME` deadlock, if there are two.
+fn deadlock_demo_tasks(state: &mut AppState) {
+    let task_ids: Vec<u64> = state
+        .snapshot
+        .tasks
+        .iter()
+        .filter(|task| task.name == DEMO_DEADLOCK_TASK_NAME)
+        .filter(|task| task.status != TaskStatus::Deadlocked)
+        .map(|task| task.id)
+        .take(DEMO_DEADLOCK_RESOURCES.len())
+        .collect();
+    deadlock_tasks(&mut state.snapshot, &task_ids);
+}
+
+// Has each of the tasks take one of the demo's resources and wait on the next one's, if there
+// are as many tasks as resources.
+fn deadlock_tasks(snapshot: &mut Snapshot, task_ids: &[u64]) {
+    if task_ids.len() < DEMO_DEADLOCK_RESOURCES.len() {
+        return;
+    }
+    for task in &mut snapshot.tasks {
+        if task_ids.contains(&task.id) {
+            task.status = TaskStatus::Deadlocked;
+            task.run_percent = 0.0;
+        }
+    }
+    let first_id = snapshot
+        .resources
+        .iter()
+        .map(|resource| resource.id + 1)
+        .max()
+        .unwrap_or(0);
+    for (index, &(kind, name)) in DEMO_DEADLOCK_RESOURCES.iter().enumerate() {
+        snapshot.resources.push(Resource {
+            id: first_id + index as u64,
+            kind: kind.to_owned(),
+            name: name.to_owned(),
+            holder_task_ids: vec![task_ids[index]],
+            waiting_task_ids: vec![task_ids[(index + 1) % task_ids.len()]],
+        });
+    }
+}
+
+// Whether the task holds or waits on any resource.
+fn holds_or_waits(snapshot: &Snapshot, task_id: u64) -> bool {
+    snapshot.resources.iter().any(|resource| {
+        resource.holder_task_ids.contains(&task_id) || resource.waiting_task_ids.contains(&task_id)
+    })
+}
+
+/// A mock value the knobs panel can turn while the console runs, so that edge cases can be
+/// explored without a dataset for each.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Knob {
+    /// How many tasks there are. Turning it up spawns connection handlers, and turning it down
+    /// finishes the newest tasks that don't hold or wait on anything.
+    TaskCount,
+    /// How long tasks take to poll. Each step doubles or halves every task's poll time.
+    PollTime,
+    /// Whether any tasks are deadlocked. Turning it on deadlocks two tasks that are free, and
+    /// turning it off has every deadlocked task let go of what it holds and go back to sleep.
+    Deadlock,
+}
+
+impl Knob {
+    /// In the order the knobs panel lists them.
+    pub const ALL: [Knob; 3] = [Knob::TaskCount, Knob::PollTime, Knob::Deadlock];
+
+    /// Turns the knob by `steps`, up for positive steps and down for negative ones.
+    pub fn turn(self, state: &mut AppState, steps: isize) {
+        state.snapshot.changed();
+        match self {
+            Knob::TaskCount if steps > 0 => {
+                for _ in 0..steps {
+                    let index = state.snapshot.tasks.len() as u32;
+                    state.spawn_task(spawned_task(index));
+                }
+            }
+            Knob::TaskCount => {
+                for _ in steps..0 {
+                    let snapshot = &state.snapshot;
+                    match snapshot
+                        .tasks
+                        .iter()
+                        .rev()
+                        .find(|task| !holds_or_waits(snapshot, task.id))
+                    {
+                        Some(task) => state.complete_task(task.id),
+                        None => break,
+                    }
+                }
+            }
+            Knob::PollTime => {
+                let factor = 2.0_f32.powi(steps as i32);
+                for task in &mut state.snapshot.tasks {
+                    task.poll_ms *= factor;
+                }
+            }
+            Knob::Deadlock if steps > 0 => {
+                if self.is_on(state) {
+                    return;
+                }
+                let snapshot = &state.snapshot;
+                let task_ids: Vec<u64> = snapshot
+                    .tasks
+                    .iter()
+                    .filter(|task| !holds_or_waits(snapshot, task.id))
+                    .map(|task| task.id)
+                    .take(DEMO_DEADLOCK_RESOURCES.len())
+                    .collect();
+                deadlock_tasks(&mut state.snapshot, &task_ids);
+            }
+            Knob::Deadlock if steps < 0 => {
+                let snapshot = &mut state.snapshot;
+                let task_ids: Vec<u64> = snapshot
+                    .tasks
+                    .iter()
+                    .filter(|task| task.status == TaskStatus::Deadlocked)
+                    .map(|task| task.id)
+                    .collect();
+                for task in &mut snapshot.tasks {
+                    if task_ids.contains(&task.id) {
+                        task.status = TaskStatus::Sleeping;
+                    }
+                }
+                for resource in &mut snapshot.resources {
+                    resource
+                        .holder_task_ids
+                        .retain(|task_id| !task_ids.contains(task_id));
+                    resource
+                        .waiting_task_ids
+                        .retain(|task_id| !task_ids.contains(task_id));
+                }
+                snapshot.resources.retain(|resource| {
+                    !resource.holder_task_ids.is_empty() || !resource.waiting_task_ids.is_empty()
+                });
+            }
+            Knob::Deadlock => {}
+        }
+    }
+
+    /// Whether a switch like `Deadlock` is on. Knobs that aren't switches never are.
+    pub fn is_on(self, state: &AppState) -> bool {
+        match self {
+            Knob::Deadlock => state
+                .snapshot
+                .tasks
+                .iter()
+                .any(|task| task.status == TaskStatus::Deadlocked),
+            _ => false,
+        }
+    }
+}
+
+// Takes `value` a random step up or down, by up to `DEMO_WALK_FACTOR`. Steps are as likely to
+// multiply as to divide by any factor, so values don't drift toward zero.
+fn walk(value: f32, noise: u32) -> f32 {
+    value * DEMO_WALK_FACTOR.powf((noise & 0xff) as f32 / 127.5 - 1.0)
+}
+
+/// Scrambles an index into a pseudo-random but repeatable number.
+pub fn mix(index: u32) -> u32 {
+    let mut x = index.wrapping_mul(0x9e37_79b9) ^ 0x85eb_ca6b;
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7feb_352d);
+    x ^= x >> 15;
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::TasksTab;
+    use std::collections::HashSet;
+
+    #[test]
+    fn typical_matches_the_reported_task_counts() {
+        let tasks = typical();
+        assert_eq!(tasks.len(), TYPICAL_TASK_COUNT);
+        let running_count = tasks
+            .iter()
+            .filter(|task| task.status == TaskStatus::Running)
+            .count();
+        assert_eq!(running_count, TYPICAL_RUNNING_TASK_COUNT);
+        let mut ids: Vec<_> = tasks.iter().map(|task| task.id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), tasks.len());
+    }
+
+    #[test]
+    fn the_deadlocked_tasks_wait_on_each_other_in_a_cycle() {
+        let mut state = AppState::new(Dataset::Deadlock.snapshot());
+        state.select_tasks_tab(TasksTab::Deadlocked);
+        let (nodes, edges) = state.wait_for_graph();
+        assert_eq!(nodes.len(), 6);
+        // Following the edges from any node leads back to it, through every other node.
+        let mut node = 0;
+        for _ in 0..nodes.len() {
+            let next: Vec<_> = edges.iter().filter(|&&(from, _)| from == node).collect();
+            assert_eq!(next.len(), 1);
+            node = next[0].1;
+        }
+        assert_eq!(node, 0);
+    }
+
+    #[test]
+    fn the_demo_deadlocks_two_tasks_half_a_minute_in() {
+        let mut state = AppState::new(Dataset::Typical.snapshot());
+        let mut demo = Demo::new(state.snapshot.performance.current);
+        let task_count = state.snapshot.tasks.len();
+        let deadlocked_count = |state: &AppState| {
+            state
+                .snapshot
+                .tasks
+                .iter()
+                .filter(|task| task.status == TaskStatus::Deadlocked)
+                .count()
+        };
+        let samples: Vec<_> = (1..30).map(|_| demo.advance(&mut state)).collect();
+        assert_eq!(deadlocked_count(&state), 0);
+        assert!(samples.windows(2).all(|pair| pair[0] != pair[1]));
+        // Nothing's spawning new connection handlers, so the finished ones aren't replaced.
+        assert!(state.snapshot.tasks.len() < task_count);
+
+        demo.advance(&mut state);
+        assert_eq!(deadlocked_count(&state), 2);
+        state.select_tasks_tab(TasksTab::Deadlocked);
+        let (nodes, edges) = state.wait_for_graph();
+        assert_eq!((nodes.len(), edges.len()), (4, 4));
+    }
+
+    #[test]
+    fn knobs_change_the_mock_target_and_turn_back() {
+        let mut state = AppState::new(Dataset::Deadlock.snapshot());
+        let task_count = state.snapshot.tasks.len();
+        Knob::TaskCount.turn(&mut state, 10);
+        assert_eq!(state.snapshot.tasks.len(), task_count + 10);
+        // Tasks that hold or wait on something, such as the deadlocked ones, are kept.
+        Knob::TaskCount.turn(&mut state, -100);
+        let snapshot = &state.snapshot;
+        assert!(snapshot.tasks.len() < task_count);
+        assert!(snapshot
+            .tasks
+            .iter()
+            .all(|task| holds_or_waits(snapshot, task.id)));
+
+        let poll_times = |state: &AppState| -> Vec<f32> {
+            state
+                .snapshot
+                .tasks
+                .iter()
+                .map(|task| task.poll_ms)
+                .collect()
+        };
+        let original_poll_times = poll_times(&state);
+        Knob::PollTime.turn(&mut state, 1);
+        assert_eq!(poll_times(&state)[0], original_poll_times[0] * 2.0);
+        Knob::PollTime.turn(&mut state, -1);
+        assert_eq!(poll_times(&state), original_poll_times);
+
+        assert!(Knob::Deadlock.is_on(&state));
+        Knob::Deadlock.turn(&mut state, -1);
+        assert!(!Knob::Deadlock.is_on(&state));
+        state.select_tasks_tab(TasksTab::Deadlocked);
+        assert!(state.visible_tasks().is_empty());
+        Knob::TaskCount.turn(&mut state, 2);
+        Knob::Deadlock.turn(&mut state, 1);
+        assert_eq!(state.visible_tasks().len(), 2);
+        let (nodes, edges) = state.wait_for_graph();
+        assert_eq!((nodes.len(), edges.len()), (4, 4));
+    }
+
+    #[test]
+    fn the_stress_dataset_has_tens_of_thousands_of_varied_tasks() {
+        let snapshot = Dataset::Stress.snapshot();
+        assert_eq!(snapshot.tasks.len(), STRESS_TASK_COUNT);
+        assert_eq!(snapshot.performance.history.len(), DEFAULT_HISTORY_CAPACITY);
+        assert!(snapshot.resources.is_empty());
+        let names: HashSet<_> = snapshot.tasks.iter().map(|task| &task.name).collect();
+        assert_eq!(
+            names.len(),
+            STRESS_NAME_SUBJECTS.len() * STRESS_NAME_ROLES.len()
+        );
+        let attribute_counts: HashSet<_> = snapshot
+            .tasks
+            .iter()
+            .map(|task| task.attributes.len())
+            .collect();
+        assert_eq!(attribute_counts.len(), STRESS_ATTRIBUTE_KEYS.len());
+        assert!(snapshot
+            .tasks
+            .iter()
+            .any(|task| task.status == TaskStatus::Running));
+    }
+
+    #[test]
+    fn datasets_are_found_by_name() {
+        for &(name, dataset) in &DATASETS {
+            assert_eq!(Dataset::from_name(name), Some(dataset));
+            assert!(!dataset.tasks().is_empty());
+        }
+        assert_eq!(Dataset::from_name("huge"), None);
+    }
+}
@@ -0,0 +1,253 @@
+//! Writing measurements as text.
+//!
+//! Numbers get a precision that depends on their size rather than on where they're shown, so
+//! that the same value reads the same everywhere: three significant digits for durations, one
+//! decimal place for percentages. Units follow the number in a quieter style of their own.
+
+use std::time::Duration;
+use tui::style::Style as TuiStyle;
+use tui::text::{Span, Spans};
+
+/// Duration units smaller than a minute, largest first, and how many seconds each is. Durations
+/// in microseconds are written with the `UnitStyle`'s unit instead.
+static SUBMINUTE_UNITS: [(&'static str, f64); 4] = [
+    ("s", 1.0),
+    ("ms", 1e-3),
+    (MICROSECONDS_UNIT, 1e-6),
+    ("ns", 1e-9),
+];
+static THOUSANDS_SEPARATOR: char = ',';
+const MICROSECONDS_UNIT: &str = "µs";
+const SECONDS_PER_MINUTE: u64 = 60;
+const SECONDS_PER_HOUR: u64 = 60 * SECONDS_PER_MINUTE;
+const SECONDS_PER_DAY: u64 = 24 * SECONDS_PER_HOUR;
+
+/// How a number and its unit are styled.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct UnitStyle {
+    pub number: TuiStyle,
+    pub unit: TuiStyle,
+    /// How microseconds are written, for fonts without a micro sign.
+    pub microseconds: &'static str,
+}
+
+impl Default for UnitStyle {
+    fn default() -> UnitStyle {
+        UnitStyle {
+            number: TuiStyle::default(),
+            unit: TuiStyle::default(),
+            microseconds: MICROSECONDS_UNIT,
+        }
+    }
+}
+
+/// What kind of quantity a number measures, which decides how it's written.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Quantity {
+    /// A share out of 100, written with one decimal place and a `%`.
+    Percent,
+    /// A number with no unit, written with this many decimal places.
+    Number { precision: usize },
+    /// A duration given in milliseconds, written as `duration` would write it.
+    Milliseconds,
+    /// How many times something happens each second.
+    PerSecond,
+}
+
+impl Quantity {
+    pub fn spans(self, value: f32, style: UnitStyle) -> Spans<'static> {
+        match self {
+            Quantity::Percent => percent(value, style),
+            Quantity::Number { precision } => Spans::from(Span::styled(
+                format!("{:.*}", precision, value),
+                style.number,
+            )),
+            Quantity::Milliseconds => milliseconds(value, style),
+            Quantity::PerSecond => per_second(value, style),
+        }
+    }
+
+    /// Writes a value as plain text, for labels that aren't styled piece by piece.
+    pub fn text(self, value: f32) -> String {
+        let spans = self.spans(value, UnitStyle::default());
+        spans.0.iter().map(|span| &span.content[..]).collect()
+    }
+}
+
+/// Writes a percentage, such as `23.3%`.
+pub fn percent(value: f32, style: UnitStyle) -> Spans<'static> {
+    number_and_unit(format!("{:.1}", value), "%", style)
+}
+
+/// Writes a rate, such as `12.4/s`.
+pub fn per_second(value: f32, style: UnitStyle) -> Spans<'static> {
+    number_and_unit(format!("{:.1}", value), "/s", style)
+}
+
+/// Writes a duration given in milliseconds, as measurements arrive. Values that aren't a
+/// duration at all, such as NaN or negative numbers, are written as they are, in milliseconds.
+pub fn milliseconds(milliseconds: f32, style: UnitStyle) -> Spans<'static> {
+    if !milliseconds.is_finite() || milliseconds < 0.0 {
+        return number_and_unit(format!("{:.2}", milliseconds), "ms", style);
+    }
+    duration(Duration::from_secs_f64(milliseconds as f64 / 1000.0), style)
+}
+
+/// Writes a duration. Under a minute, that's three significant digits in the largest unit that
+/// keeps the number at least 1, such as `713µs` or `1.41ms`. Longer durations are written in
+/// their two largest units, such as `4m05s` or `2h13m`, so that they fit in a few cells too.
+pub fn duration(duration: Duration, style: UnitStyle) -> Spans<'static> {
+    let seconds = duration.as_secs();
+    let (major, major_unit, minor, minor_unit) = if seconds < SECONDS_PER_MINUTE {
+        let (number, unit) = subminute(duration.as_secs_f64());
+        let unit = if unit == MICROSECONDS_UNIT {
+            style.microseconds
+        } else {
+            unit
+        };
+        return number_and_unit(number, unit, style);
+    } else if seconds < SECONDS_PER_HOUR {
+        (
+            seconds / SECONDS_PER_MINUTE,
+            "m",
+            seconds % SECONDS_PER_MINUTE,
+            "s",
+        )
+    } else if seconds < SECONDS_PER_DAY {
+        (
+            seconds / SECONDS_PER_HOUR,
+            "h",
+            seconds % SECONDS_PER_HOUR / SECONDS_PER_MINUTE,
+            "m",
+        )
+    } else {
+        (
+            seconds / SECONDS_PER_DAY,
+            "d",
+            seconds % SECONDS_PER_DAY / SECONDS_PER_HOUR,
+            "h",
+        )
+    };
+    Spans::from(vec![
+        Span::styled(major.to_string(), style.number),
+        Span::styled(major_unit, style.unit),
+        Span::styled(format!("{:02}", minor), style.number),
+        Span::styled(minor_unit, style.unit),
+    ])
+}
+
+/// Writes a duration as plain text, for labels that aren't styled piece by piece.
+pub fn duration_text(duration: Duration) -> String {
+    let spans = self::duration(duration, UnitStyle::default());
+    spans.0.iter().map(|span| &span.content[..]).collect()
+}
+
+/// Writes a count followed by the singular or plural label for what's counted, such as
+/// `1 runtime` or `1,024 tasks`.
+pub fn count(count: usize, labels: &[&str; 2]) -> String {
+    let label = if count == 1 { labels[0] } else { labels[1] };
+    format!("{} {}", grouped_digits(count), label)
+}
+
+/// Writes a count with its digits grouped in threes.
+pub fn grouped_digits(count: usize) -> String {
+    let digits = count.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(THOUSANDS_SEPARATOR);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+// Picks the unit for a duration under a minute, and writes the number in it with three
+// significant digits. A number that rounds up to 1000 moves up to the next unit instead.
+fn subminute(seconds: f64) -> (String, &'static str) {
+    let unit_index = SUBMINUTE_UNITS
+        .iter()
+        .position(|&(_, unit_seconds)| seconds >= unit_seconds)
+        .unwrap_or(SUBMINUTE_UNITS.len() - 1);
+    let (unit, unit_seconds) = SUBMINUTE_UNITS[unit_index];
+    let value = seconds / unit_seconds;
+    let number = three_significant_digits(value);
+    match unit_index.checked_sub(1) {
+        Some(larger_unit_index) if number.parse::<f64>().unwrap_or(0.0) >= 1000.0 => {
+            let (larger_unit, larger_unit_seconds) = SUBMINUTE_UNITS[larger_unit_index];
+            (
+                three_significant_digits(seconds / larger_unit_seconds),
+                larger_unit,
+            )
+        }
+        _ => (number, unit),
+    }
+}
+
+fn three_significant_digits(value: f64) -> String {
+    let precision = if value >= 100.0 || value == 0.0 {
+        0
+    } else if value >= 10.0 {
+        1
+    } else {
+        2
+    };
+    format!("{:.*}", precision, value)
+}
+
+fn number_and_unit(number: String, unit: &'static str, style: UnitStyle) -> Spans<'static> {
+    Spans::from(vec![
+        Span::styled(number, style.number),
+        Span::styled(unit, style.unit),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(spans: Spans) -> String {
+        spans.0.iter().map(|span| &span.content[..]).collect()
+    }
+
+    #[test]
+    fn durations_keep_three_significant_digits_in_the_largest_unit() {
+        let style = UnitStyle::default();
+        assert_eq!(text(milliseconds(1.41, style)), "1.41ms");
+        assert_eq!(text(milliseconds(0.713, style)), "713µs");
+        assert_eq!(text(milliseconds(24.5, style)), "24.5ms");
+        assert_eq!(text(milliseconds(0.00005, style)), "50.0ns");
+        assert_eq!(text(milliseconds(0.0, style)), "0ns");
+        // Rounding up to 1000 moves to the next unit.
+        assert_eq!(text(milliseconds(0.9996, style)), "1.00ms");
+        assert_eq!(text(milliseconds(f32::NAN, style)), "NaNms");
+        assert_eq!(text(milliseconds(-1.0, style)), "-1.00ms");
+        let style = UnitStyle {
+            microseconds: "us",
+            ..style
+        };
+        assert_eq!(text(milliseconds(0.713, style)), "713us");
+
+        assert_eq!(duration_text(Duration::from_millis(12_300)), "12.3s");
+        assert_eq!(duration_text(Duration::from_secs(4 * 60 + 5)), "4m05s");
+        assert_eq!(
+            duration_text(Duration::from_secs(2 * 3600 + 13 * 60)),
+            "2h13m"
+        );
+        assert_eq!(
+            duration_text(Duration::from_secs(3 * 86400 + 4 * 3600)),
+            "3d04h"
+        );
+    }
+
+    #[test]
+    fn counts_are_grouped_and_labeled() {
+        let labels = ["task", "tasks"];
+        assert_eq!(count(1, &labels), "1 task");
+        assert_eq!(count(0, &labels), "0 tasks");
+        assert_eq!(count(405, &labels), "405 tasks");
+        assert_eq!(count(1_234_567, &labels), "1,234,567 tasks");
+        assert_eq!(text(percent(23.33, UnitStyle::default())), "23.3%");
+        assert_eq!(text(per_second(12.44, UnitStyle::default())), "12.4/s");
+    }
+}
@@ -0,0 +1,109 @@
+use std::time::{Duration, Instant};
+
+/// The shortest time between two frames. Bursts of input, such as mouse drags, are coalesced
+/// into one redraw per interval.
+const MIN_FRAME_INTERVAL: Duration = Duration::from_millis(16);
+/// How long to wait for input when nothing is due to change.
+const IDLE_POLL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Decides when to redraw and how long to wait for input in between.
+///
+/// Nothing is drawn until something marks the UI dirty, so when neither input nor data arrive
+/// the main loop blocks polling for events with a long timeout instead of redrawing.
+pub struct FrameLimiter {
+    dirty: bool,
+    last_frame_time: Option<Instant>,
+}
+
+impl Default for FrameLimiter {
+    fn default() -> FrameLimiter {
+        FrameLimiter::new()
+    }
+}
+
+impl FrameLimiter {
+    pub fn new() -> FrameLimiter {
+        FrameLimiter {
+            dirty: true,
+            last_frame_time: None,
+        }
+    }
+
+    /// Records that input arrived or data changed, so the next frame should be drawn.
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn should_draw(&self, now: Instant) -> bool {
+        self.dirty && self.time_until_next_frame(now) == Duration::from_secs(0)
+    }
+
+    pub fn did_draw(&mut self, now: Instant) {
+        self.dirty = false;
+        self.last_frame_time = Some(now);
+    }
+
+    /// How long the main loop may wait for input. A pending frame is drawn as soon as the
+    /// frame interval allows; otherwise the wait lasts until `next_data_change`, if the caller
+    /// knows when that is, or the idle timeout.
+    pub fn poll_timeout(&self, now: Instant, next_data_change: Option<Duration>) -> Duration {
+        if self.dirty {
+            return self.time_until_next_frame(now);
+        }
+        match next_data_change {
+            Some(next_data_change) => next_data_change.min(IDLE_POLL_TIMEOUT),
+            None => IDLE_POLL_TIMEOUT,
+        }
+    }
+
+    fn time_until_next_frame(&self, now: Instant) -> Duration {
+        match self.last_frame_time {
+            None => Duration::from_secs(0),
+            Some(last_frame_time) => {
+                MIN_FRAME_INTERVAL.saturating_sub(now.saturating_duration_since(last_frame_time))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draws_first_frame_immediately() {
+        let limiter = FrameLimiter::new();
+        let now = Instant::now();
+        assert!(limiter.should_draw(now));
+        assert_eq!(limiter.poll_timeout(now, None), Duration::from_secs(0));
+    }
+
+    #[test]
+    fn sleeps_when_idle() {
+        let mut limiter = FrameLimiter::new();
+        let now = Instant::now();
+        limiter.did_draw(now);
+        assert!(!limiter.should_draw(now + IDLE_POLL_TIMEOUT));
+        assert_eq!(limiter.poll_timeout(now, None), IDLE_POLL_TIMEOUT);
+        assert_eq!(
+            limiter.poll_timeout(now, Some(Duration::from_millis(300))),
+            Duration::from_millis(300)
+        );
+    }
+
+    #[test]
+    fn coalesces_input_within_frame_interval() {
+        let mut limiter = FrameLimiter::new();
+        let now = Instant::now();
+        limiter.did_draw(now);
+        limiter.mark_dirty();
+
+        let soon = now + Duration::from_millis(4);
+        assert!(!limiter.should_draw(soon));
+        assert_eq!(
+            limiter.poll_timeout(soon, None),
+            MIN_FRAME_INTERVAL - Duration::from_millis(4)
+        );
+        assert!(limiter.should_draw(now + MIN_FRAME_INTERVAL));
+    }
+}
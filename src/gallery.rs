@@ -0,0 +1,183 @@
+//! Curated mock screens, so that designers can flip through the whole planned UI with
+//! `--screen=NAME` instead of clicking their way to each part of it.
+//!
+//! Each screen starts from the typical dataset and is arranged by a function of its own. The
+//! clock is stopped, so that nothing spawns or gets sampled and the screen stays as arranged
+//! until it's interacted with.
+
+use crate::app::App;
+use crate::components::{
+    Component, NotificationsPane, NotificationsPaneMessage, TaskAttributes, TaskAttributesMessage,
+    TitleBar, TitleBarMessage,
+};
+use crate::error::ConsoleError;
+use crate::fixtures::{self, Dataset};
+use crate::state::{AlertThreshold, AppState, PerformanceSample, ViewMode};
+use crate::strings::Strings;
+use crate::{performance_metric_by_key, PERFORMANCE_LATENCY_WARNING_MS};
+
+/// Indexed like `MockScreen::ALL`.
+pub static SCREEN_NAMES: [&'static str; 6] = [
+    "dashboard",
+    "task-detail",
+    "warnings",
+    "resources",
+    "empty",
+    "error",
+];
+
+/// The task whose attributes the task detail screen shows. Its spawn site is known, so the
+/// popup shows some code too.
+const TASK_DETAIL_TASK_ID: u64 = 285;
+/// The measurements the warnings screen raises alerts on, by their keys on the command line.
+static WARNING_METRIC_KEYS: [&'static str; 2] = ["poll", "wake"];
+/// How far past the warning threshold the warnings screen's newest sample spikes.
+const WARNING_SPIKE_FACTOR: f32 = 1.6;
+static FAKE_CONNECTION_TARGET: &'static str = "localhost:6669";
+
+/// One of the screens in the gallery.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MockScreen {
+    /// The main screen, as the console opens.
+    Dashboard,
+    /// A task's attributes popup, over the main screen.
+    TaskDetail,
+    /// Latency alerts that just went off, with the notifications pane open.
+    Warnings,
+    /// The graph of which tasks hold and wait on which resources.
+    Resources,
+    /// A program that has no tasks.
+    Empty,
+    /// The error screen, as shown when the target can't be reached.
+    Error,
+}
+
+impl MockScreen {
+    /// In the order `SCREEN_NAMES` names them.
+    pub const ALL: [MockScreen; 6] = [
+        MockScreen::Dashboard,
+        MockScreen::TaskDetail,
+        MockScreen::Warnings,
+        MockScreen::Resources,
+        MockScreen::Empty,
+        MockScreen::Error,
+    ];
+
+    pub fn from_name(name: &str) -> Option<MockScreen> {
+        SCREEN_NAMES
+            .iter()
+            .position(|&screen_name| screen_name == name)
+            .map(|index| MockScreen::ALL[index])
+    }
+
+    pub fn name(self) -> &'static str {
+        SCREEN_NAMES[self as usize]
+    }
+
+    /// An app showing the screen, labelled with `strings`.
+    pub fn app(self, strings: &'static Strings) -> App {
+        let error = match self {
+            MockScreen::Error => Some(ConsoleError::ConnectionRefused {
+                target: FAKE_CONNECTION_TARGET.to_owned(),
+            }),
+            _ => None,
+        };
+        let mut app = App::new(
+            Box::new(fixtures::fixed_clock()),
+            Dataset::Typical.snapshot(),
+            error,
+        );
+        app.state.strings = strings;
+        // Notifications raised while arranging are stamped with the clock's time.
+        TitleBar.update(&mut app.state, TitleBarMessage::Tick(app.clock.now()));
+        let arrange = match self {
+            MockScreen::Dashboard | MockScreen::Error => dashboard,
+            MockScreen::TaskDetail => task_detail,
+            MockScreen::Warnings => warnings,
+            MockScreen::Resources => resources,
+            MockScreen::Empty => empty,
+        };
+        arrange(&mut app.state);
+        app
+    }
+}
+
+// Left as the console opens. The error screen has this behind it, for after retrying.
+fn dashboard(_: &mut AppState) {}
+
+fn task_detail(state: &mut AppState) {
+    state.selected_task_id = Some(TASK_DETAIL_TASK_ID);
+    TaskAttributes.update(state, TaskAttributesMessage::Open(TASK_DETAIL_TASK_ID));
+}
+
+// Sets alerts at the latency warning threshold, then takes a sample that spikes past it.
+fn warnings(state: &mut AppState) {
+    let threshold = AlertThreshold::new(PERFORMANCE_LATENCY_WARNING_MS);
+    let spike = PERFORMANCE_LATENCY_WARNING_MS * WARNING_SPIKE_FACTOR;
+    for key in &WARNING_METRIC_KEYS {
+        let metric = performance_metric_by_key(key).unwrap();
+        state.set_performance_alert_threshold(metric, Some(threshold));
+    }
+    state.record_performance(PerformanceSample {
+        poll_ms: spike,
+        wake_ms: spike,
+        ..state.snapshot.performance.current
+    });
+    NotificationsPane.update(state, NotificationsPaneMessage::Toggle);
+}
+
+fn resources(state: &mut AppState) {
+    state.view_mode = ViewMode::Graph;
+}
+
+fn empty(state: &mut AppState) {
+    state.snapshot.tasks.clear();
+    state.snapshot.resources.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glyphs::GLYPH_SET_NERD_FONT;
+    use crate::snapshot_tests::buffer_to_string;
+    use crate::strings::STRINGS_ENGLISH;
+    use tui::backend::TestBackend;
+    use tui::Terminal;
+
+    fn render(screen: MockScreen) -> String {
+        let mut app = screen.app(&STRINGS_ENGLISH);
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal
+            .draw(|frame| app.draw(frame, &GLYPH_SET_NERD_FONT))
+            .unwrap();
+        buffer_to_string(terminal.backend().buffer())
+    }
+
+    #[test]
+    fn screens_are_found_by_name() {
+        for &screen in &MockScreen::ALL {
+            assert_eq!(MockScreen::from_name(screen.name()), Some(screen));
+        }
+        assert_eq!(MockScreen::from_name("settings"), None);
+    }
+
+    #[test]
+    fn each_screen_shows_what_its_named_for() {
+        let screen_text = [
+            (MockScreen::Dashboard, "connection-handler"),
+            (
+                MockScreen::TaskDetail,
+                "dbabfa1a-f722-41c0-82dc-a02e88e55d2a",
+            ),
+            (MockScreen::Warnings, "Poll time above 5.00ms"),
+            (MockScreen::Resources, "Semaphore connection_limit"),
+            (MockScreen::Empty, "0 tasks"),
+            (MockScreen::Error, FAKE_CONNECTION_TARGET),
+        ];
+        for &(screen, text) in &screen_text {
+            let rendered = render(screen);
+            assert!(rendered.contains(text), "{}:\n{}", screen.name(), rendered);
+        }
+        assert!(!render(MockScreen::Empty).contains("connection-handler"));
+    }
+}
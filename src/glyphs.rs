@@ -0,0 +1,133 @@
+use turbowish_widgets::text::ELLIPSIS;
+use turbowish_widgets::{
+    BorderSet, ChartSymbols, PowerlineSymbols, RingSymbols, ScrollbarSymbols,
+    SegmentedControlSymbols, CHART_SYMBOLS_ASCII, CHART_SYMBOLS_BRAILLE, POWERLINE_SYMBOLS_ASCII,
+    POWERLINE_SYMBOLS_NERD_FONT, RING_SYMBOLS_ASCII, RING_SYMBOLS_UNICODE, SCROLLBAR_SYMBOLS_ASCII,
+    SCROLLBAR_SYMBOLS_NERD_FONT, SEGMENTED_CONTROL_SYMBOLS_ASCII,
+    SEGMENTED_CONTROL_SYMBOLS_NERD_FONT,
+};
+
+/// The symbols the UI draws with. The Nerd Font set is the intended look; the ASCII set is a
+/// fallback for terminals and fonts that can't display box-drawing or private-use glyphs.
+#[derive(Clone, Copy)]
+pub struct GlyphSet {
+    pub border_set: BorderSet,
+    pub scrollbar: &'static ScrollbarSymbols,
+    pub chart: ChartSymbols,
+    /// The separators of the title bar's powerlines.
+    pub powerline: &'static PowerlineSymbols,
+    /// The ends of the tabs, view modes, and other segmented controls.
+    pub segmented_control: &'static SegmentedControlSymbols,
+    /// The utilization gauge in the title bar.
+    pub ring: &'static RingSymbols,
+    /// Ends text that's been cut short to fit.
+    pub ellipsis: &'static str,
+    /// Goes between the parts of a label, such as the counts in the tasks pane's footer.
+    pub separator: &'static str,
+    /// The unit of durations under a millisecond.
+    pub microseconds: &'static str,
+    /// Marks the header of the column the tasks table is sorted by, smallest first.
+    pub sort_ascending: &'static str,
+    pub sort_descending: &'static str,
+    /// Marks the options that are on in lists of settings, and the ones that are off.
+    pub checkbox_checked: &'static str,
+    pub checkbox_unchecked: &'static str,
+    /// The first segment of the title bar.
+    pub title: &'static str,
+    /// In front of which workspace is shown, once there's more than one.
+    pub workspace: &'static str,
+    /// The label of the title bar's menu button.
+    pub menu_button: &'static str,
+    /// In front of the count of notifications that came in unseen.
+    pub notifications_button: &'static str,
+    /// The performance pane's buttons for taller graphs and for going back to one-line ones.
+    pub performance_expand: &'static str,
+    pub performance_collapse: &'static str,
+    /// Leads the messages of alerts.
+    pub performance_alert: &'static str,
+    /// Indexed by `TasksTab`, in front of the tabs' names. Each is empty or ends in a space.
+    pub tasks_tab_icons: [&'static str; 5],
+    /// Indexed by `ViewMode`, in the tasks pane's view mode control.
+    pub tasks_view_modes: [&'static str; 4],
+    /// The tasks table's state column.
+    pub task_running: &'static str,
+    pub task_sleeping: &'static str,
+    pub task_deadlocked: &'static str,
+    pub task_paused: &'static str,
+    /// In front of the names of boosted and bookmarked tasks. Each ends in a space.
+    pub task_boosted: &'static str,
+    pub task_bookmark: &'static str,
+    /// The buttons that open a collapsed group of tasks and close an open one.
+    pub task_group_open: &'static str,
+    pub task_group_close: &'static str,
+    /// In front of how many tasks are in a group.
+    pub task_group_count: &'static str,
+}
+
+pub static GLYPH_SET_NERD_FONT: GlyphSet = GlyphSet {
+    border_set: BorderSet::Rounded,
+    scrollbar: &SCROLLBAR_SYMBOLS_NERD_FONT,
+    chart: CHART_SYMBOLS_BRAILLE,
+    powerline: &POWERLINE_SYMBOLS_NERD_FONT,
+    segmented_control: &SEGMENTED_CONTROL_SYMBOLS_NERD_FONT,
+    ring: &RING_SYMBOLS_UNICODE,
+    ellipsis: ELLIPSIS,
+    separator: "·",
+    microseconds: "µs",
+    sort_ascending: "▲",
+    sort_descending: "▼",
+    checkbox_checked: "\u{f046}",
+    checkbox_unchecked: "\u{f096}",
+    title: "ﴱ Tokio",
+    workspace: "\u{f009}",
+    menu_button: "☰ Menu",
+    notifications_button: "\u{f0f3}",
+    performance_expand: "\u{fa4e}",
+    performance_collapse: "\u{f066}",
+    performance_alert: "\u{f071}",
+    tasks_tab_icons: ["", "\u{f04b} ", "\u{f04c} ", "\u{f071} ", "\u{f02e} "],
+    tasks_view_modes: ["\u{f03a}", "\u{fb44}", "\u{f0ae}", "\u{f0e8}"],
+    task_running: "\u{f04b}",
+    task_sleeping: "\u{f04c}",
+    task_deadlocked: "\u{f071}",
+    task_paused: "\u{f28b}",
+    task_boosted: "\u{f062} ",
+    task_bookmark: "\u{f02e} ",
+    task_group_open: "\u{f457}",
+    task_group_close: "\u{f458}",
+    task_group_count: "×",
+};
+
+pub static GLYPH_SET_ASCII: GlyphSet = GlyphSet {
+    border_set: BorderSet::Ascii,
+    scrollbar: &SCROLLBAR_SYMBOLS_ASCII,
+    chart: CHART_SYMBOLS_ASCII,
+    powerline: &POWERLINE_SYMBOLS_ASCII,
+    segmented_control: &SEGMENTED_CONTROL_SYMBOLS_ASCII,
+    ring: &RING_SYMBOLS_ASCII,
+    ellipsis: "~",
+    separator: "-",
+    microseconds: "us",
+    sort_ascending: "^",
+    sort_descending: "v",
+    checkbox_checked: "[x]",
+    checkbox_unchecked: "[ ]",
+    title: "Tokio",
+    workspace: "#",
+    menu_button: "Menu",
+    notifications_button: "!",
+    performance_expand: "+",
+    performance_collapse: "-",
+    performance_alert: "!",
+    tasks_tab_icons: ["", "", "", "", ""],
+    tasks_view_modes: ["F", "T", "L", "G"],
+    task_running: ">",
+    task_sleeping: "z",
+    task_deadlocked: "!",
+    task_paused: "=",
+    task_boosted: "^ ",
+    task_bookmark: "* ",
+    task_group_open: "+",
+    task_group_close: "-",
+    task_group_count: "x",
+};
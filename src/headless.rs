@@ -0,0 +1,644 @@
+//! Drives the app without a terminal, for end-to-end tests.
+//!
+//! A `HeadlessApp` feeds synthetic events to `App::handle_event` and redraws into a
+//! `TestBackend` after each one, the way the main loop would, so tests can check both the
+//! resulting state and what ended up on screen. Events can also be given as a script of
+//! whitespace-separated steps:
+//!
+//! - `Up`, `Down`, `Left`, `Right`, `Esc`, `Enter`, `Tab`, `BackTab`, `Space`: the named key.
+//! - `F1` through `F12`: that function key.
+//! - A single character, such as `q` or `r`: that key.
+//! - `Ctrl-c`, `Alt-2`, `Shift-Right`: the key with Control, Alt, or Shift held.
+//! - `click:12,3`: a left click at column 12, row 3.
+//! - `resize:100x30`: a terminal resize.
+
+use crate::app::{App, Command};
+use crate::error::ConsoleError;
+use crate::fixtures;
+use crate::glyphs::{GlyphSet, GLYPH_SET_NERD_FONT};
+use crate::snapshot_tests::buffer_to_string;
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use tui::backend::TestBackend;
+use tui::Terminal;
+
+pub struct HeadlessApp {
+    pub app: App,
+    terminal: Terminal<TestBackend>,
+    glyph_set: GlyphSet,
+    /// The commands the app returned, in order.
+    pub commands: Vec<Command>,
+}
+
+impl HeadlessApp {
+    pub fn new(width: u16, height: u16) -> HeadlessApp {
+        HeadlessApp::with_error(width, height, None)
+    }
+
+    pub fn with_error(width: u16, height: u16, error: Option<ConsoleError>) -> HeadlessApp {
+        let mut headless_app = HeadlessApp {
+            app: App::new(
+                Box::new(fixtures::fixed_clock()),
+                fixtures::snapshot(fixtures::tiny()),
+                error,
+            ),
+            terminal: Terminal::new(TestBackend::new(width, height)).unwrap(),
+            glyph_set: GLYPH_SET_NERD_FONT,
+            commands: vec![],
+        };
+        headless_app.draw();
+        headless_app
+    }
+
+    pub fn send(&mut self, event: Event) {
+        // `TestBackend` can't change size, so a resize starts over with a new terminal, much
+        // like the real one, which clears and redraws everything after a resize.
+        if let Event::Resize(width, height) = event {
+            self.terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        }
+        if let Some(command) = self.app.handle_event(event) {
+            self.commands.push(command);
+        }
+        self.draw();
+    }
+
+    pub fn run_script(&mut self, script: &str) {
+        for step in script.split_whitespace() {
+            self.send(parse_step(step));
+        }
+    }
+
+    pub fn screen(&self) -> String {
+        buffer_to_string(self.terminal.backend().buffer())
+    }
+
+    pub fn row(&self, y: u16) -> String {
+        self.screen().lines().nth(y as usize).unwrap().to_owned()
+    }
+
+    fn draw(&mut self) {
+        let (app, glyph_set) = (&mut self.app, &self.glyph_set);
+        self.terminal
+            .draw(|frame| app.draw(frame, glyph_set))
+            .unwrap();
+    }
+}
+
+fn parse_step(step: &str) -> Event {
+    if let Some(position) = step.strip_prefix("click:") {
+        let (column, row) = split_pair(position, ',');
+        return Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        });
+    }
+    if let Some(size) = step.strip_prefix("resize:") {
+        let (width, height) = split_pair(size, 'x');
+        return Event::Resize(width, height);
+    }
+
+    let (modifiers, key) = if let Some(key) = step.strip_prefix("Ctrl-") {
+        (KeyModifiers::CONTROL, key)
+    } else if let Some(key) = step.strip_prefix("Alt-") {
+        (KeyModifiers::ALT, key)
+    } else if let Some(key) = step.strip_prefix("Shift-") {
+        (KeyModifiers::SHIFT, key)
+    } else {
+        (KeyModifiers::NONE, step)
+    };
+    let code = match key {
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Esc" => KeyCode::Esc,
+        "Enter" => KeyCode::Enter,
+        "Tab" => KeyCode::Tab,
+        "BackTab" => KeyCode::BackTab,
+        "Space" => KeyCode::Char(' '),
+        _ if key.len() > 1 && key.starts_with('F') => match key[1..].parse() {
+            Ok(number) => KeyCode::F(number),
+            Err(_) => panic!("unknown script step `{}`", step),
+        },
+        _ if key.chars().count() == 1 => KeyCode::Char(key.chars().next().unwrap()),
+        _ => panic!("unknown script step `{}`", step),
+    };
+    Event::Key(KeyEvent { code, modifiers })
+}
+
+fn split_pair(string: &str, separator: char) -> (u16, u16) {
+    let mut parts = string
+        .splitn(2, separator)
+        .map(|part| part.parse().unwrap());
+    (parts.next().unwrap(), parts.next().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, FixedClock};
+    use crate::components::{TASKS_SCROLLBAR_ID, TASKS_TABS_ID};
+    use crate::fixtures::Dataset;
+    use crate::format;
+    use crate::link::{SimulatedLink, LINK_TIMEOUT_UPDATE_COUNT};
+    use crate::state::{TasksTab, TasksTableRow, ViewMode};
+    use crate::strings::{STRINGS_ENGLISH, STRINGS_SPANISH};
+    use crate::theme::THEMES;
+    use crate::TASK_ATTRIBUTES_LABEL;
+    use std::time::Duration;
+
+    #[test]
+    fn quit_keys_return_quit() {
+        for script in &["q", "Esc", "Ctrl-c"] {
+            let mut headless_app = HeadlessApp::new(80, 24);
+            headless_app.run_script(script);
+            assert_eq!(headless_app.commands, vec![Command::Quit], "{}", script);
+        }
+    }
+
+    #[test]
+    fn ctrl_z_requests_suspend() {
+        let mut headless_app = HeadlessApp::new(80, 24);
+        headless_app.run_script("z Ctrl-z");
+        assert_eq!(headless_app.commands, vec![Command::Suspend]);
+    }
+
+    // At this size the tasks table has room for two of the four tasks.
+    #[test]
+    fn arrow_keys_scroll_tasks() {
+        let mut headless_app = HeadlessApp::new(80, 11);
+        assert!(headless_app.row(7).contains("285"));
+
+        headless_app.run_script("Down");
+        assert_eq!(headless_app.app.state.tasks_scroll_state.offset(), 1);
+        assert!(headless_app.row(7).contains("286"));
+
+        // Scrolling stops at the last page.
+        headless_app.run_script("Down Down Down");
+        assert_eq!(headless_app.app.state.tasks_scroll_state.offset(), 2);
+        assert!(headless_app.row(7).contains("public-accept"));
+        assert!(headless_app.row(8).contains("main"));
+
+        headless_app.run_script("Up Up Up");
+        assert_eq!(headless_app.app.state.tasks_scroll_state.offset(), 0);
+        assert!(headless_app.row(7).contains("285"));
+    }
+
+    #[test]
+    fn left_and_right_scroll_columns() {
+        let mut headless_app = HeadlessApp::new(80, 24);
+        assert!(headless_app.row(6).contains("State"));
+
+        headless_app.run_script("Right");
+        assert_eq!(headless_app.app.state.tasks_column_scroll_state.offset(), 1);
+        assert!(!headless_app.row(6).contains("State"));
+        assert!(headless_app.row(6).contains("Run %"));
+
+        headless_app.run_script("Left Left");
+        assert_eq!(headless_app.app.state.tasks_column_scroll_state.offset(), 0);
+        assert!(headless_app.row(6).contains("State"));
+    }
+
+    #[test]
+    fn clicking_the_scrollbar_arrows_scrolls_tasks() {
+        let mut headless_app = HeadlessApp::new(80, 11);
+        headless_app.run_script("click:78,8 click:78,8");
+        assert_eq!(headless_app.app.state.tasks_scroll_state.offset(), 2);
+        assert!(headless_app.row(7).contains("public-accept"));
+        headless_app.run_script("click:78,6");
+        assert_eq!(headless_app.app.state.tasks_scroll_state.offset(), 1);
+
+        // Clicks elsewhere don't scroll.
+        headless_app.run_script("click:40,7");
+        assert_eq!(headless_app.app.state.tasks_scroll_state.offset(), 1);
+    }
+
+    #[test]
+    fn resizing_redraws_at_the_new_size() {
+        let mut headless_app = HeadlessApp::new(80, 24);
+        headless_app.run_script("resize:100x30");
+        let screen = headless_app.screen();
+        assert_eq!(screen.lines().count(), 30);
+        assert!(screen.lines().all(|line| line.chars().count() == 100));
+        assert!(headless_app.row(0).contains("Menu"));
+    }
+
+    #[test]
+    fn the_layout_is_kept_until_the_screen_or_the_panes_change() {
+        let mut headless_app = HeadlessApp::new(80, 24);
+        let main_node = |headless_app: &HeadlessApp| {
+            let layout = headless_app.app.state.frame_layout.as_ref().unwrap();
+            (
+                layout.main_node,
+                layout.stretch.children(layout.main_node).len(),
+            )
+        };
+        let (first_main_node, child_count) = main_node(&headless_app);
+
+        headless_app.run_script("Down resize:100x30");
+        assert_eq!(main_node(&headless_app), (first_main_node, child_count));
+        let layout = headless_app.app.state.frame_layout.as_ref().unwrap();
+        assert_eq!(layout.size, (100, 29));
+        assert_eq!(layout.stretch.layout(first_main_node).size.width, 100.0);
+
+        headless_app.app.state.notifications_open = true;
+        headless_app.run_script("Down");
+        assert_eq!(main_node(&headless_app).1, child_count + 1);
+    }
+
+    #[test]
+    fn the_frame_time_readout_shows_the_last_frames_phases() {
+        let mut headless_app = HeadlessApp::new(100, 24);
+        let state = &mut headless_app.app.state;
+        state.frame_times_shown = true;
+        state.frame_times.phases = [
+            Duration::from_micros(300),
+            Duration::from_micros(2_100),
+            Duration::from_micros(1_600),
+        ];
+        headless_app.run_script("Down");
+        assert!(headless_app
+            .row(23)
+            .ends_with(" frame 4.0 ms · layout 0.3 · build 2.1 · render 1.6 "));
+
+        // Tests don't count allocations, so this stands in for a binary that does.
+        headless_app.app.state.memory_allocated = 12_345_678;
+        headless_app.run_script("Down");
+        assert!(headless_app.row(23).ends_with(" · mem 12.3 MB "));
+    }
+
+    #[test]
+    fn retry_dismisses_the_error_screen() {
+        let error = ConsoleError::ConnectionRefused {
+            target: "localhost:6669".to_owned(),
+        };
+        let mut headless_app = HeadlessApp::with_error(80, 24, Some(error));
+        assert!(headless_app.screen().contains("Connection refused"));
+
+        // Navigation is ignored while the error is up.
+        headless_app.run_script("Down Right");
+        assert_eq!(headless_app.app.state.tasks_scroll_state.offset(), 0);
+        assert_eq!(headless_app.app.state.tasks_column_scroll_state.offset(), 0);
+
+        headless_app.run_script("r");
+        assert!(headless_app.app.error.is_none());
+        assert!(headless_app.screen().contains("Tasks"));
+    }
+
+    #[test]
+    fn focused_controls_take_keys_before_the_app_does() {
+        let mut headless_app = HeadlessApp::new(80, 24);
+        // Focus moves through the widgets in the order they're drawn: the menu, the
+        // performance pane's expand button, then the tasks tabs.
+        headless_app.run_script("Tab Tab Tab");
+        assert_eq!(headless_app.app.state.focused, Some(TASKS_TABS_ID));
+
+        headless_app.run_script("Right");
+        let state = &headless_app.app.state;
+        assert_eq!(state.tasks_tab, TasksTab::Running);
+        assert_eq!(state.tasks_column_scroll_state.offset(), 0);
+
+        // Escape gives up the focus instead of quitting, and then the arrows scroll again.
+        headless_app.run_script("Esc Right");
+        assert!(headless_app.commands.is_empty());
+        assert_eq!(headless_app.app.state.focused, None);
+        let state = &headless_app.app.state;
+        assert_eq!(state.tasks_tab, TasksTab::Running);
+        assert_eq!(state.tasks_column_scroll_state.offset(), 1);
+
+        headless_app.run_script("BackTab");
+        assert_eq!(headless_app.app.state.focused, Some(TASKS_SCROLLBAR_ID));
+    }
+
+    #[test]
+    fn space_shows_the_selected_tasks_attributes_for_copying() {
+        let mut headless_app = HeadlessApp::new(80, 24);
+        let task = headless_app.app.state.snapshot.tasks[0].clone();
+        headless_app.app.state.selected_task_id = Some(task.id);
+
+        headless_app.run_script("Space");
+        let screen = headless_app.screen();
+        assert!(screen.contains(TASK_ATTRIBUTES_LABEL));
+        // Under the attributes, where the task was spawned.
+        let spawn_site = task.spawn_site.as_ref().unwrap();
+        assert!(screen.contains(&format!("{}:{}", spawn_site.file, spawn_site.line)));
+        assert!(screen.contains(&format!("{}▶", spawn_site.line)));
+        // The popup keeps the keys, so Escape closes it rather than quitting.
+        headless_app.run_script("c Esc");
+        let (key, value) = &task.attributes[0];
+        assert_eq!(
+            headless_app.commands,
+            vec![Command::Copy(format!("{}={}", key, value))]
+        );
+        assert!(headless_app.app.state.overlay.is_none());
+    }
+
+    #[test]
+    fn y_copies_the_selected_task_straight_away() {
+        let mut headless_app = HeadlessApp::new(80, 24);
+        let task_id = headless_app.app.state.snapshot.tasks[0].id;
+        headless_app.app.state.selected_task_id = Some(task_id);
+
+        headless_app.run_script("y");
+        assert!(matches!(headless_app.commands[..], [Command::Copy(_)]));
+    }
+
+    #[test]
+    fn bookmarked_tasks_get_a_tab_of_their_own() {
+        let mut headless_app = HeadlessApp::new(120, 24);
+        let task = headless_app.app.state.snapshot.tasks[1].clone();
+        headless_app.app.state.selected_task_id = Some(task.id);
+
+        headless_app.run_script("b 5");
+        assert_eq!(headless_app.commands, vec![Command::SaveBookmarks]);
+        let state = &headless_app.app.state;
+        assert_eq!(state.tasks_tab, TasksTab::Bookmarked);
+        assert_eq!(state.visible_tasks(), vec![&task]);
+        assert!(headless_app.screen().contains("Bookmarked (1)"));
+
+        headless_app.run_script("b");
+        assert!(headless_app.app.state.visible_tasks().is_empty());
+    }
+
+    #[test]
+    fn panes_are_labelled_in_the_catalogs_language() {
+        let mut headless_app = HeadlessApp::new(120, 24);
+        headless_app.app.state.strings = &STRINGS_SPANISH;
+        headless_app.run_script("g");
+        let screen = headless_app.screen();
+        assert!(screen.contains("Tareas"));
+        assert!(screen.contains("agrupadas por nombre"));
+        assert!(screen.contains("Rendimiento"));
+        assert!(screen.contains("actualizado hace"));
+    }
+
+    #[test]
+    fn workspaces_keep_their_own_panes() {
+        let mut headless_app = HeadlessApp::new(120, 24);
+        let task = headless_app.app.state.snapshot.tasks[1].clone();
+        headless_app.app.state.selected_task_id = Some(task.id);
+
+        headless_app.run_script("Alt-2 F Alt-3 2 g");
+        let state = &headless_app.app.state;
+        assert_eq!((state.active_workspace, state.workspaces.len()), (2, 3));
+        assert_eq!(state.task_name_filter.as_deref(), Some(&task.name[..]));
+        assert!(headless_app.screen().contains("3/3"));
+
+        headless_app.run_script("Alt-1");
+        let state = &headless_app.app.state;
+        assert_eq!(state.tasks_tab, TasksTab::All);
+        assert_eq!(state.task_name_filter, None);
+        assert!(!state.group_tasks_by_name);
+        assert!(headless_app.screen().contains("1/3"));
+
+        headless_app.run_script("Alt-3");
+        let state = &headless_app.app.state;
+        assert_eq!(state.tasks_tab, TasksTab::Running);
+        assert!(state.group_tasks_by_name);
+        assert!(state
+            .visible_tasks()
+            .iter()
+            .all(|visible_task| visible_task.name.contains(&task.name[..])));
+
+        // Alt-9 skips past the workspaces that haven't been opened.
+        headless_app.run_script("Alt-9");
+        assert_eq!(headless_app.app.state.active_workspace, 2);
+    }
+
+    #[test]
+    fn the_timeline_draws_each_tasks_life_and_pans_with_the_arrows() {
+        let mut headless_app = HeadlessApp::new(100, 20);
+        // The view mode control comes after the tasks tabs.
+        headless_app.run_script("Tab Tab Tab Tab Right Right Esc");
+        assert_eq!(headless_app.app.state.view_mode, ViewMode::Timeline);
+        let screen = headless_app.screen();
+        // Now is the newest tick on the ruler over the bars.
+        assert!(screen.contains(&format!("{}╵", STRINGS_ENGLISH.performance_chart_newest)));
+        // The oldest task's bar starts at the left edge, with the others' after it.
+        assert!(screen.contains("0 main                    ├──"));
+
+        headless_app.run_script("> Left");
+        assert!(headless_app.app.state.timeline_view.offset > Duration::from_secs(0));
+        assert!(!headless_app
+            .screen()
+            .contains(STRINGS_ENGLISH.performance_chart_newest));
+    }
+
+    #[test]
+    fn the_graph_shows_who_each_task_waits_on() {
+        let mut headless_app = HeadlessApp::new(100, 24);
+        headless_app.run_script("Tab Tab Tab Tab Right Right Right Esc");
+        assert_eq!(headless_app.app.state.view_mode, ViewMode::Graph);
+        let screen = headless_app.screen();
+        assert!(screen.contains("Semaphore connection_limit"));
+        assert!(screen.contains("Mutex sessions"));
+
+        // None of these tasks are deadlocked.
+        headless_app.run_script("4");
+        assert!(headless_app
+            .screen()
+            .contains(STRINGS_ENGLISH.tasks_graph_empty));
+    }
+
+    #[test]
+    fn tens_of_thousands_of_tasks_scroll_to_the_last_one() {
+        let mut headless_app = HeadlessApp::new(100, 30);
+        headless_app.app.state.snapshot = Dataset::Stress.snapshot();
+        headless_app.run_script("Down");
+        headless_app
+            .app
+            .state
+            .tasks_scroll_state
+            .scroll_by(1_000_000);
+        headless_app.run_script("Down");
+        let state = &headless_app.app.state;
+        let task_ids: Vec<_> = state
+            .tasks_table_rows()
+            .iter()
+            .map(|row| match *row {
+                TasksTableRow::Task { task, .. } => task.id,
+                TasksTableRow::Group(_) => panic!("tasks aren't grouped"),
+            })
+            .collect();
+        let screen = headless_app.screen();
+        assert!(screen.contains(&format!(" {} ", task_ids[task_ids.len() - 1])));
+        assert!(!screen.contains(&format!(" {} ", task_ids[0])));
+    }
+
+    #[test]
+    fn tens_of_thousands_of_tasks_list_and_filter_by_name() {
+        let mut headless_app = HeadlessApp::new(100, 30);
+        let state = &mut headless_app.app.state;
+        state.snapshot = Dataset::Stress.snapshot();
+        let task = state.snapshot.tasks[0].clone();
+        state.selected_task_id = Some(task.id);
+        headless_app.run_script("F");
+        let state = &headless_app.app.state;
+        assert_eq!(state.task_name_filter.as_ref(), Some(&task.name));
+        let same_name_count = state
+            .snapshot
+            .tasks
+            .iter()
+            .filter(|other_task| other_task.name == task.name)
+            .count();
+        let footer = format!(
+            "{} {}",
+            STRINGS_ENGLISH.tasks_footer_filtered_count,
+            format::grouped_digits(same_name_count)
+        );
+        assert!(headless_app.screen().contains(&footer));
+        assert!(headless_app.screen().contains(&task.id.to_string()));
+    }
+
+    #[test]
+    fn a_target_that_stops_answering_goes_stale_then_drops() {
+        let mut headless_app = HeadlessApp::new(100, 24);
+        let start = fixtures::fixed_clock().now();
+        headless_app.app.link = Some(SimulatedLink::new(Duration::ZERO, Duration::ZERO, 1.0));
+        let tick_at = |headless_app: &mut HeadlessApp, seconds: u32| {
+            headless_app.app.clock = Box::new(FixedClock::new(
+                start + chrono::Duration::seconds(seconds.into()),
+            ));
+            headless_app.app.tick();
+            headless_app.draw();
+        };
+        tick_at(&mut headless_app, 0);
+        assert!(!headless_app
+            .screen()
+            .contains(STRINGS_ENGLISH.performance_stale));
+
+        // Every update is lost, so the data only gets older until the link times out.
+        for seconds in 1..LINK_TIMEOUT_UPDATE_COUNT {
+            tick_at(&mut headless_app, seconds);
+        }
+        assert!(headless_app.app.error.is_none());
+        assert!(headless_app
+            .screen()
+            .contains(STRINGS_ENGLISH.performance_stale));
+        tick_at(&mut headless_app, LINK_TIMEOUT_UPDATE_COUNT);
+        assert!(headless_app.screen().contains("Lost the connection"));
+
+        headless_app.run_script("r");
+        assert!(headless_app.app.error.is_none());
+    }
+
+    #[test]
+    fn the_theme_preview_switches_themes_on_a_timer_and_on_t() {
+        let mut headless_app = HeadlessApp::new(100, 24);
+        let start = fixtures::fixed_clock().now();
+        headless_app.app.theme_preview_interval = Some(Duration::from_secs(5));
+        let tick_at = |headless_app: &mut HeadlessApp, seconds: i64| {
+            headless_app.app.clock =
+                Box::new(FixedClock::new(start + chrono::Duration::seconds(seconds)));
+            headless_app.app.tick();
+            headless_app.draw();
+        };
+        tick_at(&mut headless_app, 0);
+        tick_at(&mut headless_app, 4);
+        assert_eq!(headless_app.app.state.theme.name, THEMES[0].name);
+        tick_at(&mut headless_app, 5);
+        assert_eq!(headless_app.app.state.theme.name, THEMES[1].name);
+        assert!(headless_app.screen().contains("Theme: basic"));
+
+        // Pressing `t` switches right away, and the new theme gets a whole interval.
+        headless_app.run_script("t");
+        assert_eq!(headless_app.app.state.theme.name, THEMES[0].name);
+        tick_at(&mut headless_app, 6);
+        tick_at(&mut headless_app, 10);
+        assert_eq!(headless_app.app.state.theme.name, THEMES[0].name);
+        tick_at(&mut headless_app, 11);
+        assert_eq!(headless_app.app.state.theme.name, THEMES[1].name);
+    }
+
+    #[test]
+    fn the_generation_only_moves_on_when_the_next_frame_would_look_different() {
+        let mut headless_app = HeadlessApp::new(100, 24);
+        let start = fixtures::fixed_clock().now();
+        let tick_at = |headless_app: &mut HeadlessApp, millis: i64| {
+            headless_app.app.clock = Box::new(FixedClock::new(
+                start + chrono::Duration::milliseconds(millis),
+            ));
+            headless_app.app.tick();
+            headless_app.app.generation()
+        };
+        let generation = tick_at(&mut headless_app, 0);
+        assert_eq!(tick_at(&mut headless_app, 500), generation);
+        headless_app.run_script("F7");
+        assert_eq!(headless_app.app.generation(), generation);
+
+        headless_app.run_script("Down");
+        assert_ne!(headless_app.app.generation(), generation);
+        // A new sample comes in, in the next second.
+        let generation = headless_app.app.generation();
+        assert_ne!(tick_at(&mut headless_app, 1_000), generation);
+    }
+
+    #[test]
+    fn a_slow_link_moves_the_generation_on_only_when_the_age_shown_changes() {
+        let mut headless_app = HeadlessApp::new(100, 24);
+        let start = fixtures::fixed_clock().now();
+        headless_app.app.link = Some(SimulatedLink::new(
+            Duration::from_millis(200),
+            Duration::ZERO,
+            0.0,
+        ));
+        let tick_at = |headless_app: &mut HeadlessApp, millis: i64| {
+            headless_app.app.clock = Box::new(FixedClock::new(
+                start + chrono::Duration::milliseconds(millis),
+            ));
+            headless_app.app.tick();
+            headless_app.app.generation()
+        };
+        tick_at(&mut headless_app, 0);
+        let generation = tick_at(&mut headless_app, 20);
+        assert_eq!(tick_at(&mut headless_app, 70), generation);
+        assert_ne!(tick_at(&mut headless_app, 120), generation);
+    }
+
+    #[test]
+    fn the_knobs_panel_changes_the_mock_values_on_screen() {
+        let mut headless_app = HeadlessApp::new(100, 24);
+        let task_count = headless_app.app.state.snapshot.tasks.len();
+        headless_app.run_script("F12");
+        assert!(headless_app.screen().contains("Knobs"));
+
+        headless_app.run_script("Right Shift-Right");
+        assert_eq!(
+            headless_app.app.state.snapshot.tasks.len(),
+            task_count + 1 + 10
+        );
+        assert!(headless_app
+            .screen()
+            .contains(&format!(" {} >", task_count + 11)));
+
+        headless_app.run_script("Down Down Right");
+        assert!(headless_app.screen().contains(" on >"));
+        headless_app.run_script("Left");
+        assert!(headless_app.screen().contains(" off >"));
+
+        headless_app.run_script("F12");
+        assert!(!headless_app.screen().contains("Knobs"));
+        assert_eq!(headless_app.app.state.overlay, None);
+    }
+
+    #[test]
+    fn presentation_mode_shows_the_newest_values_in_big_digits() {
+        let mut headless_app = HeadlessApp::new(120, 40);
+        headless_app.run_script("p P");
+        let state = &headless_app.app.state;
+        assert!(state.performance_screen_open && state.performance_presentation);
+        let screen = headless_app.screen();
+        assert!(screen.contains("█▄█"));
+        assert!(screen.contains(STRINGS_ENGLISH.performance_metrics[0]));
+        assert!(!screen.contains(STRINGS_ENGLISH.performance_readouts[1]));
+
+        headless_app.run_script("P");
+        assert!(!headless_app.app.state.performance_presentation);
+        assert!(headless_app
+            .screen()
+            .contains(STRINGS_ENGLISH.performance_readouts[1]));
+    }
+}
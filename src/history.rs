@@ -0,0 +1,300 @@
+//! Measurements kept over time.
+//!
+//! A `History` holds a fixed number of samples, oldest first, so that a session left running
+//! doesn't take more and more memory. Once it's full, it can either forget the oldest samples
+//! as new ones arrive or average them into older points of a second, coarser ring buffer, which
+//! keeps a long window at a lower resolution for the cost of a short one.
+
+use std::collections::VecDeque;
+use std::iter::{self, FromIterator};
+use std::ops::Range;
+
+/// An hour of samples taken a second apart.
+pub const DEFAULT_HISTORY_CAPACITY: usize = 3600;
+/// A minute of samples taken a second apart.
+pub const DEFAULT_SAMPLES_PER_OLDER_POINT: usize = 60;
+/// The rest of a day, a minute to a point.
+pub const DEFAULT_OLDER_CAPACITY: usize = 23 * 60;
+
+/// Samples that can be averaged into one, for keeping older history at a lower resolution.
+pub trait Average: Copy {
+    /// The average of `samples`, of which there's at least one.
+    fn average(samples: &[Self]) -> Self;
+}
+
+impl Average for f32 {
+    fn average(samples: &[f32]) -> f32 {
+        samples.iter().sum::<f32>() / samples.len() as f32
+    }
+}
+
+/// A ring buffer of samples, oldest first, behind an optional ring buffer of older points that
+/// each average a run of the samples that came before.
+///
+/// However it's stored, the history reads as evenly spaced samples: each older point stands in
+/// for all of the samples it averages.
+#[derive(Clone, Debug)]
+pub struct History<T> {
+    samples: VecDeque<T>,
+    capacity: usize,
+    older_points: VecDeque<T>,
+    older_capacity: usize,
+    samples_per_older_point: usize,
+    /// Samples that left the ring buffer, until there are enough to average into a point.
+    pending: Vec<T>,
+    average: Option<fn(&[T]) -> T>,
+    /// Moves on whenever the samples change, so that what's drawn from them can be kept until
+    /// they do.
+    generation: u64,
+}
+
+impl<T> History<T> {
+    /// An empty history that keeps the newest `capacity` samples, and at least one, and forgets
+    /// the rest.
+    pub fn with_capacity(capacity: usize) -> History<T> {
+        let capacity = capacity.max(1);
+        History {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+            older_points: VecDeque::new(),
+            older_capacity: 0,
+            samples_per_older_point: 1,
+            pending: vec![],
+            average: None,
+            generation: 0,
+        }
+    }
+
+    /// Adds the newest sample. If the history is full, the oldest sample is forgotten, or
+    /// averaged into an older point along with the ones after it, forgetting the oldest point.
+    pub fn push(&mut self, sample: T) {
+        if self.samples.len() == self.capacity {
+            let oldest = self.samples.pop_front().unwrap();
+            if let Some(average) = self.average {
+                self.pending.push(oldest);
+                if self.pending.len() == self.samples_per_older_point {
+                    if self.older_points.len() == self.older_capacity {
+                        self.older_points.pop_front();
+                    }
+                    self.older_points.push_back(average(&self.pending));
+                    self.pending.clear();
+                }
+            }
+        }
+        self.samples.push_back(sample);
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Where the samples are up to. This moves on with each sample, which may also have
+    /// averaged older samples into a point, and only then.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// How many samples the history covers, counting each older point as the samples it
+    /// averages.
+    pub fn len(&self) -> usize {
+        self.older_points.len() * self.samples_per_older_point
+            + self.pending.len()
+            + self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// How many samples the history keeps at full resolution.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The samples, oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        let samples_per_older_point = self.samples_per_older_point;
+        self.older_points
+            .iter()
+            .flat_map(move |point| iter::repeat_n(point, samples_per_older_point))
+            .chain(&self.pending)
+            .chain(&self.samples)
+    }
+
+    /// The samples in `range`, oldest first, without going through the ones before it.
+    pub fn range(&self, range: Range<usize>) -> impl Iterator<Item = &T> + '_ {
+        let samples_per_older_point = self.samples_per_older_point;
+        let end = range.end.min(self.len());
+        let start = range.start.min(end);
+        let first_point = (start / samples_per_older_point).min(self.older_points.len());
+        self.older_points
+            .range(first_point..)
+            .flat_map(move |point| iter::repeat_n(point, samples_per_older_point))
+            .chain(&self.pending)
+            .chain(&self.samples)
+            .skip(start - first_point * samples_per_older_point)
+            .take(end - start)
+    }
+}
+
+impl<T> History<T>
+where
+    T: Average,
+{
+    /// An empty history that keeps the newest `capacity` samples, and at least one, and
+    /// averages each run of `samples_per_older_point` of the samples before them into one of up
+    /// to `older_capacity` older points.
+    pub fn with_older_points(
+        capacity: usize,
+        older_capacity: usize,
+        samples_per_older_point: usize,
+    ) -> History<T> {
+        History {
+            older_points: VecDeque::with_capacity(older_capacity),
+            older_capacity,
+            samples_per_older_point: samples_per_older_point.max(1),
+            average: if older_capacity > 0 {
+                Some(T::average)
+            } else {
+                None
+            },
+            ..History::with_capacity(capacity)
+        }
+    }
+}
+
+/// An hour of samples and the rest of a day of older points, at a sample a second.
+impl<T> Default for History<T>
+where
+    T: Average,
+{
+    fn default() -> History<T> {
+        History::with_older_points(
+            DEFAULT_HISTORY_CAPACITY,
+            DEFAULT_OLDER_CAPACITY,
+            DEFAULT_SAMPLES_PER_OLDER_POINT,
+        )
+    }
+}
+
+/// Keeps the samples, taken oldest first, as the default history does.
+impl<T> FromIterator<T> for History<T>
+where
+    T: Average,
+{
+    fn from_iter<I>(samples: I) -> History<T>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut history = History::default();
+        for sample in samples {
+            history.push(sample);
+        }
+        history
+    }
+}
+
+/// Averages each run of `samples_per_point` values into one, for showing more values than there
+/// are points to show them in. The runs are counted back from the newest value, so that the
+/// newest point is always a full one; the oldest may average fewer values.
+pub fn downsample(values: &[f32], samples_per_point: usize) -> Vec<f32> {
+    let samples_per_point = samples_per_point.max(1);
+    let mut points: Vec<f32> = values
+        .rchunks(samples_per_point)
+        .map(|chunk| chunk.iter().sum::<f32>() / chunk.len() as f32)
+        .collect();
+    points.reverse();
+    points
+}
+
+/// The values below which the given percentages of `values` fall, by the nearest-rank method, so
+/// that each is one of the values. `None` if there are no values.
+pub fn percentiles(values: &[f32], percentages: &[f32]) -> Option<Vec<f32>> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    Some(
+        percentages
+            .iter()
+            .map(|&percentage| {
+                let rank = (percentage / 100.0 * sorted.len() as f32).ceil() as usize;
+                sorted[rank.clamp(1, sorted.len()) - 1]
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_full_history_forgets_its_oldest_samples() {
+        let mut history = History::with_capacity(3);
+        for sample in 0..5 {
+            history.push(sample);
+        }
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn samples_that_leave_a_full_history_are_averaged_into_older_points() {
+        let mut history = History::with_older_points(3, 2, 2);
+        for sample in 0..8 {
+            history.push(sample as f32);
+        }
+        // 0 and 1 were averaged, then 2 and 3; 4 is waiting for 5.
+        assert_eq!(history.len(), 8);
+        assert_eq!(
+            history.iter().copied().collect::<Vec<_>>(),
+            vec![0.5, 0.5, 2.5, 2.5, 4.0, 5.0, 6.0, 7.0]
+        );
+        assert_eq!(
+            history.range(1..5).copied().collect::<Vec<_>>(),
+            vec![0.5, 2.5, 2.5, 4.0]
+        );
+        assert_eq!(
+            history.range(6..20).copied().collect::<Vec<_>>(),
+            vec![6.0, 7.0]
+        );
+
+        // The oldest point is forgotten to make room.
+        history.push(8.0);
+        assert_eq!(history.len(), 7);
+        assert_eq!(history.iter().next(), Some(&2.5));
+    }
+
+    #[test]
+    fn the_generation_moves_on_with_each_sample() {
+        let mut history = History::with_older_points(2, 1, 2);
+        let generation = history.generation();
+        assert_eq!(history.clone().generation(), generation);
+        let mut generations = vec![generation];
+        for sample in 0..5 {
+            history.push(sample as f32);
+            generations.push(history.generation());
+        }
+        generations.dedup();
+        assert_eq!(generations.len(), 6);
+    }
+
+    #[test]
+    fn downsampling_averages_runs_counted_from_the_newest_value() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(downsample(&values, 1), values.to_vec());
+        assert_eq!(downsample(&values, 2), vec![1.0, 2.5, 4.5]);
+        assert_eq!(downsample(&values, 8), vec![3.0]);
+        assert!(downsample(&[], 2).is_empty());
+    }
+
+    #[test]
+    fn percentiles_are_values_from_the_samples() {
+        let values: Vec<f32> = (1..=100).rev().map(|value| value as f32).collect();
+        assert_eq!(
+            percentiles(&values, &[50.0, 95.0, 99.0, 100.0]),
+            Some(vec![50.0, 95.0, 99.0, 100.0])
+        );
+        assert_eq!(percentiles(&[7.0], &[0.0, 99.0]), Some(vec![7.0, 7.0]));
+        assert_eq!(percentiles(&[], &[50.0]), None);
+    }
+}
@@ -0,0 +1,246 @@
+//! Making attribute values that name something elsewhere, such as URLs and request IDs, into
+//! links the terminal can open.
+//!
+//! Links are OSC 8 escape sequences around the linked text. They can't go in the cells tui
+//! diffs, since it would count the URL's characters towards the cells' widths, so components
+//! note where they drew links and `HyperlinkBackend` writes the sequences around those cells
+//! as it draws them. Cells only carry their link while they're being redrawn, so text that
+//! stays put as it gains or loses a link keeps whatever the terminal last drew it with.
+
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+use tui::backend::Backend;
+use tui::buffer::Cell;
+use tui::layout::Rect;
+
+static HTTP_PREFIXES: [&'static str; 2] = ["http://", "https://"];
+// Attribute keys whose values are request IDs, linked with the template given on the command
+// line.
+static REQUEST_ID_KEYS: [&'static str; 4] = ["request-id", "request_id", "trace-id", "trace_id"];
+/// Where a request ID goes in a link template.
+pub static LINK_TEMPLATE_PLACEHOLDER: &'static str = "{}";
+static OSC_8_START: &'static str = "\x1b]8;;";
+static OSC_8_END: &'static str = "\x1b\\";
+
+/// Text drawn in `area` that links to `url`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Hyperlink {
+    pub area: Rect,
+    pub url: String,
+}
+
+/// The links drawn in the last frame, which the app fills in and `HyperlinkBackend` reads.
+pub type SharedHyperlinks = Rc<RefCell<Vec<Hyperlink>>>;
+
+/// Where an attribute value links to, if it's a URL, or a request ID and there's a template to
+/// put it in, such as `https://tracing.example.com/requests/{}`.
+pub fn attribute_link(
+    key: &str,
+    value: &str,
+    request_link_template: Option<&str>,
+) -> Option<String> {
+    if let Some(prefix) = HTTP_PREFIXES
+        .iter()
+        .find(|prefix| value.starts_with(*prefix))
+    {
+        // Terminals take only printable ASCII in links, and anything else is likely not a URL.
+        return if value.len() > prefix.len() && value.bytes().all(|byte| byte.is_ascii_graphic()) {
+            Some(value.to_owned())
+        } else {
+            None
+        };
+    }
+    let template = request_link_template?;
+    if value.is_empty()
+        || !REQUEST_ID_KEYS.contains(&&key.to_ascii_lowercase()[..])
+        || !template.contains(LINK_TEMPLATE_PLACEHOLDER)
+    {
+        return None;
+    }
+    Some(template.replace(LINK_TEMPLATE_PLACEHOLDER, &percent_encode(value)))
+}
+
+// Escapes everything but the characters URLs leave unreserved.
+fn percent_encode(text: &str) -> String {
+    let mut encoded = String::new();
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// A backend that writes the links noted in `hyperlinks` around the cells drawn inside them,
+/// leaving out blank cells so that only text is clickable. With no links, cells pass through
+/// as-is, so terminals without link support can be given an empty list.
+pub struct HyperlinkBackend<B> {
+    backend: B,
+    hyperlinks: SharedHyperlinks,
+}
+
+impl<B> HyperlinkBackend<B>
+where
+    B: Backend + Write,
+{
+    pub fn new(backend: B, hyperlinks: SharedHyperlinks) -> HyperlinkBackend<B> {
+        HyperlinkBackend {
+            backend,
+            hyperlinks,
+        }
+    }
+
+    // Draws a run of cells that all link to `url`, or to nothing.
+    fn draw_run(&mut self, cells: &[(u16, u16, &Cell)], url: Option<&str>) -> io::Result<()> {
+        if cells.is_empty() {
+            return Ok(());
+        }
+        if let Some(url) = url {
+            write!(self.backend, "{}{}{}", OSC_8_START, url, OSC_8_END)?;
+        }
+        self.backend.draw(cells.iter().copied())?;
+        if url.is_some() {
+            write!(self.backend, "{}{}", OSC_8_START, OSC_8_END)?;
+        }
+        Ok(())
+    }
+}
+
+impl<B> Backend for HyperlinkBackend<B>
+where
+    B: Backend + Write,
+{
+    fn draw<'a, I>(&mut self, content: I) -> Result<(), io::Error>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        let hyperlinks = Rc::clone(&self.hyperlinks);
+        let hyperlinks = hyperlinks.borrow();
+        if hyperlinks.is_empty() {
+            return self.backend.draw(content);
+        }
+
+        let mut run = vec![];
+        let mut run_link = None;
+        for (x, y, cell) in content {
+            let link = if cell.symbol.trim().is_empty() {
+                None
+            } else {
+                hyperlinks.iter().position(|hyperlink| {
+                    let area = hyperlink.area;
+                    x >= area.x && x < area.right() && y >= area.y && y < area.bottom()
+                })
+            };
+            if link != run_link {
+                self.draw_run(&run, run_link.map(|index| &hyperlinks[index].url[..]))?;
+                run.clear();
+                run_link = link;
+            }
+            run.push((x, y, cell));
+        }
+        self.draw_run(&run, run_link.map(|index| &hyperlinks[index].url[..]))
+    }
+
+    fn hide_cursor(&mut self) -> Result<(), io::Error> {
+        self.backend.hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> Result<(), io::Error> {
+        self.backend.show_cursor()
+    }
+
+    fn get_cursor(&mut self) -> Result<(u16, u16), io::Error> {
+        self.backend.get_cursor()
+    }
+
+    fn set_cursor(&mut self, x: u16, y: u16) -> Result<(), io::Error> {
+        self.backend.set_cursor(x, y)
+    }
+
+    fn clear(&mut self) -> Result<(), io::Error> {
+        self.backend.clear()
+    }
+
+    fn size(&self) -> Result<Rect, io::Error> {
+        self.backend.size()
+    }
+
+    fn flush(&mut self) -> Result<(), io::Error> {
+        Backend::flush(&mut self.backend)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tui::backend::CrosstermBackend;
+
+    // Output the test can still read after handing it to the backend.
+    #[derive(Clone, Default)]
+    struct SharedOutput(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn urls_and_request_ids_get_links() {
+        let template = Some("https://tracing.example.com/requests/{}");
+        assert_eq!(
+            attribute_link("endpoint", "https://example.com/health", None).as_deref(),
+            Some("https://example.com/health")
+        );
+        assert_eq!(attribute_link("endpoint", "https://", None), None);
+        assert_eq!(
+            attribute_link("endpoint", "https://ex ample.com", None),
+            None
+        );
+        assert_eq!(attribute_link("request-id", "abc-123", None), None);
+        assert_eq!(
+            attribute_link("Request-ID", "abc 123", template).as_deref(),
+            Some("https://tracing.example.com/requests/abc%20123")
+        );
+        assert_eq!(attribute_link("interval", "1s", template), None);
+        assert_eq!(attribute_link("request-id", "", template), None);
+    }
+
+    #[test]
+    fn linked_text_is_wrapped_in_escape_sequences() {
+        let hyperlinks: SharedHyperlinks = Rc::new(RefCell::new(vec![Hyperlink {
+            area: Rect::new(1, 0, 3, 1),
+            url: "https://example.com".to_owned(),
+        }]));
+        let output = SharedOutput::default();
+        let mut backend = HyperlinkBackend::new(CrosstermBackend::new(output.clone()), hyperlinks);
+        let cell = |symbol: &str| {
+            let mut cell = Cell::default();
+            cell.set_symbol(symbol);
+            cell
+        };
+        let cells = [cell("a"), cell("b"), cell("c"), cell(" "), cell("d")];
+        backend
+            .draw(
+                cells
+                    .iter()
+                    .enumerate()
+                    .map(|(x, cell)| (x as u16, 0, cell)),
+            )
+            .unwrap();
+        let output = String::from_utf8(output.0.borrow().clone()).unwrap();
+        let link_start = output.find("\x1b]8;;https://example.com\x1b\\").unwrap();
+        let link_end = output.rfind("\x1b]8;;\x1b\\").unwrap();
+        assert!(output[..link_start].contains('a'));
+        assert!(output[link_start..link_end].contains("bc"));
+        assert!(!output[link_start..link_end].contains('d'));
+        assert!(output[link_end..].contains('d'));
+    }
+}
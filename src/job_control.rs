@@ -0,0 +1,70 @@
+//! Suspending to the shell with Ctrl-Z and resuming with `fg`.
+//!
+//! Raw mode turns Ctrl-Z into a key event, so the main loop asks for a suspend itself. A
+//! `SIGTSTP` sent from elsewhere is caught too, since its default action would stop the process
+//! with the terminal still in raw mode. Either way the main loop restores the terminal and then
+//! stops for real with `SIGSTOP`, which can't be caught. When `SIGCONT` arrives the terminal has
+//! to be set up again, since the shell will have reset it in the meantime.
+
+#[cfg(unix)]
+use signal_hook::consts::{SIGCONT, SIGSTOP, SIGTSTP};
+#[cfg(unix)]
+use signal_hook::{flag, low_level};
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+pub struct JobControl {
+    suspend_requested: Arc<AtomicBool>,
+    continued: Arc<AtomicBool>,
+}
+
+impl JobControl {
+    #[cfg(unix)]
+    pub fn install() -> io::Result<JobControl> {
+        let job_control = JobControl::new();
+        flag::register(SIGTSTP, job_control.suspend_requested.clone())?;
+        flag::register(SIGCONT, job_control.continued.clone())?;
+        Ok(job_control)
+    }
+
+    // There's no job control on Windows, so suspending is never requested and does nothing.
+    #[cfg(not(unix))]
+    pub fn install() -> io::Result<JobControl> {
+        Ok(JobControl::new())
+    }
+
+    fn new() -> JobControl {
+        JobControl {
+            suspend_requested: Arc::new(AtomicBool::new(false)),
+            continued: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn request_suspend(&self) {
+        if cfg!(unix) {
+            self.suspend_requested.store(true, Ordering::SeqCst);
+        }
+    }
+
+    pub fn take_suspend_request(&self) -> bool {
+        self.suspend_requested.swap(false, Ordering::SeqCst)
+    }
+
+    /// Returns true once after each time the process is continued.
+    pub fn take_continued(&self) -> bool {
+        self.continued.swap(false, Ordering::SeqCst)
+    }
+
+    /// Stops the process, returning once it has been continued. The caller is expected to have
+    /// restored the terminal first.
+    #[cfg(unix)]
+    pub fn stop(&self) -> io::Result<()> {
+        low_level::raise(SIGSTOP)
+    }
+
+    #[cfg(not(unix))]
+    pub fn stop(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
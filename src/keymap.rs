@@ -0,0 +1,467 @@
+//! The keys the app responds to once popups and focused widgets have had their turn.
+//!
+//! `App::handle_event` looks keys up here rather than matching them itself, and the help overlay
+//! lists the same table, so what `?` shows is always what the keys do. Popups take their own
+//! keys, which their footers list.
+
+use crate::export::ExportFormat;
+use crate::state::{Action, TasksTab};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// How many bars the performance history pans by per key press.
+const PERFORMANCE_HISTORY_PAN_STEP: isize = 8;
+
+/// What a key binding is for, which the help overlay groups bindings by.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum KeyCategory {
+    Navigation,
+    Filtering,
+    Views,
+    Export,
+    General,
+}
+
+impl KeyCategory {
+    /// In the order the help overlay lists them.
+    pub const ALL: [KeyCategory; 5] = [
+        KeyCategory::Navigation,
+        KeyCategory::Filtering,
+        KeyCategory::Views,
+        KeyCategory::Export,
+        KeyCategory::General,
+    ];
+}
+
+/// When a key binding applies.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum KeyScope {
+    /// Before popups and focused widgets see the key, so that it works even over them.
+    Always,
+    /// Whenever nothing else took the key.
+    Main,
+    /// Only while the performance screen is open.
+    PerformanceScreen,
+    /// Only while the tasks pane is showing, rather than the performance screen.
+    TasksPane,
+}
+
+impl KeyScope {
+    /// Whether bindings in this scope apply while the performance screen is open or not, as
+    /// the help overlay lists them.
+    pub fn applies(self, performance_screen_open: bool) -> bool {
+        match self {
+            KeyScope::Always => true,
+            KeyScope::Main => true,
+            KeyScope::PerformanceScreen => performance_screen_open,
+            KeyScope::TasksPane => !performance_screen_open,
+        }
+    }
+}
+
+/// A key and what it does. Keys bound without Control or Alt match whatever Shift does, so that
+/// `E` and `?` match however the terminal reports them.
+#[derive(Clone, Copy, Debug)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+    pub action: Action,
+    pub category: KeyCategory,
+    pub scope: KeyScope,
+}
+
+impl KeyBinding {
+    /// Whether `key` presses this binding's key.
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let chord_modifiers = KeyModifiers::CONTROL | KeyModifiers::ALT;
+        key.code == self.code && key.modifiers & chord_modifiers == self.modifiers & chord_modifiers
+    }
+
+    /// How the help overlay writes the key, such as `ctrl-p` or `space`.
+    pub fn key_label(&self) -> String {
+        let key = match self.code {
+            KeyCode::Char(' ') => "space".to_owned(),
+            KeyCode::Char(character) => character.to_string(),
+            KeyCode::Up => "↑".to_owned(),
+            KeyCode::Down => "↓".to_owned(),
+            KeyCode::Left => "←".to_owned(),
+            KeyCode::Right => "→".to_owned(),
+            KeyCode::Tab => "tab".to_owned(),
+            KeyCode::BackTab => "shift-tab".to_owned(),
+            KeyCode::Esc => "esc".to_owned(),
+            KeyCode::Enter => "enter".to_owned(),
+            KeyCode::F(number) => format!("f{}", number),
+            code => format!("{:?}", code).to_lowercase(),
+        };
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("ctrl-");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("alt-");
+        }
+        label + &key
+    }
+}
+
+const fn bind(code: KeyCode, action: Action, category: KeyCategory, scope: KeyScope) -> KeyBinding {
+    KeyBinding {
+        code,
+        modifiers: KeyModifiers::NONE,
+        action,
+        category,
+        scope,
+    }
+}
+
+const fn bind_control(character: char, action: Action, scope: KeyScope) -> KeyBinding {
+    KeyBinding {
+        code: KeyCode::Char(character),
+        modifiers: KeyModifiers::CONTROL,
+        action,
+        category: KeyCategory::General,
+        scope,
+    }
+}
+
+const fn bind_alt(character: char, action: Action) -> KeyBinding {
+    KeyBinding {
+        code: KeyCode::Char(character),
+        modifiers: KeyModifiers::ALT,
+        action,
+        category: KeyCategory::Views,
+        scope: KeyScope::Main,
+    }
+}
+
+/// Every binding, in the order the help overlay lists them within each category. Where two
+/// bindings share a key, the first that applies wins.
+pub static KEY_BINDINGS: [KeyBinding; 51] = [
+    bind_control('c', Action::Quit, KeyScope::Always),
+    bind_control('z', Action::Suspend, KeyScope::Always),
+    bind(
+        KeyCode::Tab,
+        Action::StepFocus(true),
+        KeyCategory::Navigation,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::BackTab,
+        Action::StepFocus(false),
+        KeyCategory::Navigation,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Up,
+        Action::ScrollRows(-1),
+        KeyCategory::Navigation,
+        KeyScope::TasksPane,
+    ),
+    bind(
+        KeyCode::Down,
+        Action::ScrollRows(1),
+        KeyCategory::Navigation,
+        KeyScope::TasksPane,
+    ),
+    bind(
+        KeyCode::Left,
+        Action::ScrollColumns(-1),
+        KeyCategory::Navigation,
+        KeyScope::TasksPane,
+    ),
+    bind(
+        KeyCode::Right,
+        Action::ScrollColumns(1),
+        KeyCategory::Navigation,
+        KeyScope::TasksPane,
+    ),
+    bind(
+        KeyCode::Char(' '),
+        Action::OpenSelectedTaskAttributes,
+        KeyCategory::Navigation,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Char('a'),
+        Action::OpenSelectedTaskActions,
+        KeyCategory::Navigation,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Char('['),
+        Action::PanHistory(PERFORMANCE_HISTORY_PAN_STEP),
+        KeyCategory::Navigation,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Char(']'),
+        Action::PanHistory(-PERFORMANCE_HISTORY_PAN_STEP),
+        KeyCategory::Navigation,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Char('0'),
+        Action::ResetHistoryView,
+        KeyCategory::Navigation,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Char('1'),
+        Action::SelectTasksTab(TasksTab::All),
+        KeyCategory::Filtering,
+        KeyScope::TasksPane,
+    ),
+    bind(
+        KeyCode::Char('2'),
+        Action::SelectTasksTab(TasksTab::Running),
+        KeyCategory::Filtering,
+        KeyScope::TasksPane,
+    ),
+    bind(
+        KeyCode::Char('3'),
+        Action::SelectTasksTab(TasksTab::Sleeping),
+        KeyCategory::Filtering,
+        KeyScope::TasksPane,
+    ),
+    bind(
+        KeyCode::Char('4'),
+        Action::SelectTasksTab(TasksTab::Deadlocked),
+        KeyCategory::Filtering,
+        KeyScope::TasksPane,
+    ),
+    bind(
+        KeyCode::Char('5'),
+        Action::SelectTasksTab(TasksTab::Bookmarked),
+        KeyCategory::Filtering,
+        KeyScope::TasksPane,
+    ),
+    bind(
+        KeyCode::Char('b'),
+        Action::ToggleBookmark,
+        KeyCategory::Filtering,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Char('g'),
+        Action::ToggleGrouping,
+        KeyCategory::Filtering,
+        KeyScope::TasksPane,
+    ),
+    bind(
+        KeyCode::Char('F'),
+        Action::ToggleNameFilter,
+        KeyCategory::Filtering,
+        KeyScope::TasksPane,
+    ),
+    bind(
+        KeyCode::Char('c'),
+        Action::OpenColumnSettings,
+        KeyCategory::Filtering,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Char('m'),
+        Action::OpenPerformanceSettings,
+        KeyCategory::Filtering,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Char('p'),
+        Action::TogglePerformanceScreen,
+        KeyCategory::Views,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Char('-'),
+        Action::ZoomHistory(false),
+        KeyCategory::Views,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Char('+'),
+        Action::ZoomHistory(true),
+        KeyCategory::Views,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Char('='),
+        Action::ZoomHistory(true),
+        KeyCategory::Views,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Char('w'),
+        Action::CycleTimeWindow,
+        KeyCategory::Views,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Char('d'),
+        Action::ToggleComparison,
+        KeyCategory::Views,
+        KeyScope::PerformanceScreen,
+    ),
+    bind(
+        KeyCode::Char('P'),
+        Action::TogglePresentation,
+        KeyCategory::Views,
+        KeyScope::PerformanceScreen,
+    ),
+    bind(
+        KeyCode::Char('<'),
+        Action::ZoomTimeline(false),
+        KeyCategory::Views,
+        KeyScope::TasksPane,
+    ),
+    bind(
+        KeyCode::Char('>'),
+        Action::ZoomTimeline(true),
+        KeyCategory::Views,
+        KeyScope::TasksPane,
+    ),
+    bind(
+        KeyCode::Char('f'),
+        Action::ToggleFollow,
+        KeyCategory::Views,
+        KeyScope::TasksPane,
+    ),
+    bind(
+        KeyCode::Char('n'),
+        Action::ToggleNotifications,
+        KeyCategory::Views,
+        KeyScope::Main,
+    ),
+    bind_alt('1', Action::SelectWorkspace(0)),
+    bind_alt('2', Action::SelectWorkspace(1)),
+    bind_alt('3', Action::SelectWorkspace(2)),
+    bind_alt('4', Action::SelectWorkspace(3)),
+    bind_alt('5', Action::SelectWorkspace(4)),
+    bind_alt('6', Action::SelectWorkspace(5)),
+    bind_alt('7', Action::SelectWorkspace(6)),
+    bind_alt('8', Action::SelectWorkspace(7)),
+    bind_alt('9', Action::SelectWorkspace(8)),
+    bind(
+        KeyCode::Char('e'),
+        Action::Export(ExportFormat::Csv),
+        KeyCategory::Export,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Char('E'),
+        Action::Export(ExportFormat::Json),
+        KeyCategory::Export,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Char('y'),
+        Action::CopySelectedTask,
+        KeyCategory::Export,
+        KeyScope::Main,
+    ),
+    bind_control('p', Action::OpenCommandPalette, KeyScope::Main),
+    bind(
+        KeyCode::Char('?'),
+        Action::OpenHelp,
+        KeyCategory::General,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Char('u'),
+        Action::Undo,
+        KeyCategory::General,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Esc,
+        Action::Back,
+        KeyCategory::General,
+        KeyScope::Main,
+    ),
+    bind(
+        KeyCode::Char('q'),
+        Action::Quit,
+        KeyCategory::General,
+        KeyScope::Main,
+    ),
+];
+
+/// The action bound to `key` in `scope`, which is `KeyScope::Always` for keys that haven't been
+/// offered to popups and focused widgets yet.
+pub fn action_for(
+    key: &KeyEvent,
+    scope: KeyScope,
+    performance_screen_open: bool,
+) -> Option<Action> {
+    KEY_BINDINGS
+        .iter()
+        .filter(|binding| match scope {
+            KeyScope::Always => binding.scope == KeyScope::Always,
+            _ => {
+                binding.scope != KeyScope::Always && binding.scope.applies(performance_screen_open)
+            }
+        })
+        .find(|binding| binding.matches(key))
+        .map(|binding| binding.action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_find_their_bindings_in_scope() {
+        let key = |code, modifiers| KeyEvent { code, modifiers };
+        let main = |code, modifiers, performance_screen_open| {
+            action_for(
+                &key(code, modifiers),
+                KeyScope::Main,
+                performance_screen_open,
+            )
+        };
+        assert_eq!(
+            main(KeyCode::Char('p'), KeyModifiers::NONE, false),
+            Some(Action::TogglePerformanceScreen)
+        );
+        assert_eq!(
+            main(KeyCode::Char('p'), KeyModifiers::CONTROL, false),
+            Some(Action::OpenCommandPalette)
+        );
+        assert_eq!(
+            main(KeyCode::Char('E'), KeyModifiers::SHIFT, false),
+            Some(Action::Export(ExportFormat::Json))
+        );
+        assert_eq!(main(KeyCode::Char('d'), KeyModifiers::NONE, false), None);
+        assert_eq!(
+            main(KeyCode::Char('d'), KeyModifiers::NONE, true),
+            Some(Action::ToggleComparison)
+        );
+        assert_eq!(
+            main(KeyCode::Char('P'), KeyModifiers::SHIFT, true),
+            Some(Action::TogglePresentation)
+        );
+        assert_eq!(main(KeyCode::Up, KeyModifiers::NONE, true), None);
+        assert_eq!(
+            main(KeyCode::Char('>'), KeyModifiers::SHIFT, false),
+            Some(Action::ZoomTimeline(true))
+        );
+        assert_eq!(
+            main(KeyCode::Char('2'), KeyModifiers::ALT, true),
+            Some(Action::SelectWorkspace(1))
+        );
+        assert_eq!(
+            action_for(
+                &key(KeyCode::Char('c'), KeyModifiers::CONTROL),
+                KeyScope::Always,
+                false
+            ),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            action_for(
+                &key(KeyCode::Char('q'), KeyModifiers::NONE),
+                KeyScope::Always,
+                false
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,1086 @@
+#![allow(clippy::redundant_static_lifetimes)]
+
+//! Mockups of the Tokio console UI, drawn with tui. The binary shows them in a terminal; the
+//! library exposes the drawing code so that tests and benchmarks can render frames headlessly.
+//! With the `lib` feature, `embed` lets other apps draw the panes in UIs of their own; turning
+//! off the default `cli` feature leaves out the binary's terminal setup.
+
+use crate::clock::Clock;
+use crate::components::{
+    ColumnSettings, CommandPalette, Component, Help, Knobs, LayoutKey, NotificationsPane,
+    NotificationsPaneLayout, PerformancePane, PerformancePaneLayout, PerformanceScreen,
+    PerformanceScreenLayout, PerformanceSettings, TaskActions, TaskAttributes, TasksPane,
+    TasksPaneLayout, TitleBar, TitleBarLayout, TitleBarMessage, ViewContext,
+};
+use crate::error::ConsoleError;
+use crate::format::{Quantity, UnitStyle};
+use crate::glyphs::{GlyphSet, GLYPH_SET_NERD_FONT};
+use crate::profiling::Phase;
+use crate::state::{AppState, TaskGroup, TaskSortKey};
+use crate::strings::Strings;
+use crate::theme::{Theme, Thresholds};
+use std::mem;
+use std::time::Duration;
+use stretch::geometry::{Point, Rect, Size};
+use stretch::number::Number;
+use stretch::style::{AlignItems, Dimension, FlexDirection, JustifyContent, Style};
+use tui::backend::Backend;
+use tui::layout::Alignment;
+use tui::style::{Color, Modifier, Style as TuiStyle};
+use tui::text::{Span, Spans};
+use tui::widgets::{Cell, Paragraph, Row, Wrap};
+use tui::Frame;
+use turbowish_widgets::layout::{LayoutTree, Node, RectExt, SizeExt};
+use turbowish_widgets::renderer::Renderer;
+use turbowish_widgets::text::{align_spans, display_width, truncate_spans, wrapped_height};
+use turbowish_widgets::{ui, AutoScale, BoxFrame, Powerline};
+
+pub mod app;
+pub mod backend;
+pub mod clipboard;
+pub mod clock;
+pub mod components;
+pub mod config;
+#[cfg(feature = "lib")]
+pub mod embed;
+pub mod error;
+pub mod export;
+pub mod fixed_size;
+pub mod fixtures;
+pub mod format;
+pub mod frame_limiter;
+pub mod gallery;
+pub mod glyphs;
+#[cfg(test)]
+mod headless;
+pub mod history;
+pub mod hyperlink;
+#[cfg(feature = "cli")]
+pub mod job_control;
+pub mod keymap;
+pub mod link;
+#[cfg(feature = "cli")]
+pub mod platform;
+pub mod profiling;
+pub mod screen_diff;
+pub mod screenshot;
+pub mod session;
+#[cfg(test)]
+mod snapshot_tests;
+pub mod state;
+pub mod storybook;
+pub mod strings;
+pub mod synchronized;
+#[cfg(feature = "cli")]
+pub mod terminal;
+#[cfg(feature = "cli")]
+pub mod terminal_guard;
+pub mod theme;
+
+static TITLE_BAR_RUNTIME_COUNT_LABELS: [&'static str; 2] = ["runtime", "runtimes"];
+static TITLE_BAR_THREAD_COUNT_LABELS: [&'static str; 2] = ["thread", "threads"];
+static TIME_FORMAT: &'static str = "%x %r";
+static PERFORMANCE_PERCENTILES: [f32; 3] = [50.0, 95.0, 99.0];
+static PERFORMANCE_PERCENTILE_LABELS: [&'static str; 3] = ["p50", "p95", "p99"];
+/// One of the measurements the performance pane can show.
+/// Its label is the one at the same index in `Strings::performance_metrics`.
+struct PerformanceMetric {
+    /// What the metric is called on the command line, as in `--alert=wake=5`.
+    key: &'static str,
+    quantity: Quantity,
+    /// Whether the graph turns the warning and critical colors past the latency thresholds.
+    latency: bool,
+    /// Whether the percentiles of the samples on screen are shown along with the current value,
+    /// for measurements whose spread matters more than their average.
+    percentiles: bool,
+    /// Whether the metric is shown until the user hides it in the metric settings.
+    shown_by_default: bool,
+    /// The value, in the metric's own unit, above which the metric raises an alert unless the
+    /// user sets a threshold of their own.
+    default_alert_above: Option<f32>,
+}
+
+// Indexed like `PerformanceSample::values`.
+static PERFORMANCE_METRICS: [PerformanceMetric; 6] = [
+    PerformanceMetric {
+        key: "runtime",
+        quantity: Quantity::Percent,
+        latency: false,
+        percentiles: false,
+        shown_by_default: true,
+        default_alert_above: None,
+    },
+    PerformanceMetric {
+        key: "depth",
+        quantity: Quantity::Number { precision: 2 },
+        latency: false,
+        percentiles: false,
+        shown_by_default: true,
+        default_alert_above: None,
+    },
+    PerformanceMetric {
+        key: "poll",
+        quantity: Quantity::Milliseconds,
+        latency: true,
+        percentiles: true,
+        shown_by_default: true,
+        default_alert_above: Some(10.0),
+    },
+    PerformanceMetric {
+        key: "wake",
+        quantity: Quantity::Milliseconds,
+        latency: true,
+        percentiles: true,
+        shown_by_default: true,
+        default_alert_above: Some(5.0),
+    },
+    PerformanceMetric {
+        key: "steal",
+        quantity: Quantity::PerSecond,
+        latency: false,
+        percentiles: false,
+        shown_by_default: false,
+        default_alert_above: None,
+    },
+    PerformanceMetric {
+        key: "park",
+        quantity: Quantity::Milliseconds,
+        latency: false,
+        percentiles: false,
+        shown_by_default: false,
+        default_alert_above: None,
+    },
+];
+static TASKS_TABLE_GROUP_MEMBER_INDENT: &'static str = "  ";
+/// How one column of the tasks table is laid out and sorted. Its header is the one at the same
+/// index in `Strings::tasks_table_columns`.
+struct TasksTableColumn {
+    /// What the column is called in saved sessions.
+    key: &'static str,
+    alignment: Alignment,
+    /// The column's width in cells. The last column has none, and takes whatever is left.
+    width: Option<u16>,
+    /// What clicking the header sorts by, for the columns that can be sorted.
+    sort_key: Option<TaskSortKey>,
+    /// Whether the column is shown until the user hides it in the column settings.
+    shown_by_default: bool,
+}
+
+static TASKS_TABLE_COLUMNS: [TasksTableColumn; 11] = [
+    TasksTableColumn {
+        key: "open",
+        alignment: Alignment::Left,
+        width: Some(3),
+        sort_key: None,
+        shown_by_default: true,
+    },
+    TasksTableColumn {
+        key: "id",
+        alignment: Alignment::Right,
+        width: Some(10),
+        sort_key: Some(TaskSortKey::Id),
+        shown_by_default: true,
+    },
+    TasksTableColumn {
+        key: "name",
+        alignment: Alignment::Left,
+        width: Some(24),
+        sort_key: Some(TaskSortKey::Name),
+        shown_by_default: true,
+    },
+    TasksTableColumn {
+        key: "state",
+        alignment: Alignment::Left,
+        width: Some(5),
+        sort_key: Some(TaskSortKey::Status),
+        shown_by_default: true,
+    },
+    TasksTableColumn {
+        key: "run_percent",
+        alignment: Alignment::Right,
+        width: Some(5),
+        sort_key: Some(TaskSortKey::RunPercent),
+        shown_by_default: true,
+    },
+    TasksTableColumn {
+        key: "poll",
+        alignment: Alignment::Right,
+        width: Some(7),
+        sort_key: Some(TaskSortKey::PollMs),
+        shown_by_default: true,
+    },
+    TasksTableColumn {
+        key: "wake",
+        alignment: Alignment::Right,
+        width: Some(7),
+        sort_key: Some(TaskSortKey::WakeMs),
+        shown_by_default: true,
+    },
+    TasksTableColumn {
+        key: "busy",
+        alignment: Alignment::Right,
+        width: Some(7),
+        sort_key: Some(TaskSortKey::BusyTime),
+        shown_by_default: false,
+    },
+    TasksTableColumn {
+        key: "idle",
+        alignment: Alignment::Right,
+        width: Some(7),
+        sort_key: Some(TaskSortKey::IdleTime),
+        shown_by_default: false,
+    },
+    TasksTableColumn {
+        key: "lifetime",
+        alignment: Alignment::Right,
+        width: Some(8),
+        sort_key: Some(TaskSortKey::Lifetime),
+        shown_by_default: false,
+    },
+    TasksTableColumn {
+        key: "attributes",
+        alignment: Alignment::Left,
+        width: None,
+        sort_key: None,
+        shown_by_default: true,
+    },
+];
+const TASKS_TABLE_FROZEN_COLUMN_COUNT: usize = 3;
+/// How wide the labels in front of the tasks timeline's bars are.
+const TASKS_TIMELINE_LABEL_WIDTH: u16 = 24;
+/// How many columns the tasks timeline pans by per key press.
+const TASKS_TIMELINE_PAN_STEP: isize = 8;
+
+static TASK_ACTION_MENU_LABEL: &'static str = "Actions";
+// Indexed by `TaskAction`.
+static TASK_ACTION_LABELS: [&'static str; 5] = [
+    "Pause",
+    "Resume",
+    "Boost priority",
+    "Drop priority boost",
+    "Abort…",
+];
+static TASK_ACTION_DONE_LABELS: [&'static str; 5] = [
+    "Paused",
+    "Resumed",
+    "Boosted",
+    "Dropped the boost of",
+    "Aborted",
+];
+static CONFIRM_ABORT_LABEL: &'static str = "Abort task?";
+static CONFIRM_ABORT_MESSAGE_LABEL: &'static str =
+    "The task will be dropped at its next await, and won't run again.";
+static CONFIRM_ABORT_KEYS_LABEL: &'static str = "y abort · n cancel";
+static TOAST_UNDO_LABEL: &'static str = "u undo";
+static TASK_ATTRIBUTES_LABEL: &'static str = "Attributes";
+static COLUMN_SETTINGS_LABEL: &'static str = "Columns";
+static COLUMN_SETTINGS_KEYS_LABEL: &'static str = "space show/hide · esc close";
+static TASK_ATTRIBUTES_EMPTY_LABEL: &'static str = "No attributes";
+static TASK_ATTRIBUTES_KEYS_LABEL: &'static str = "c copy · y copy all · esc close";
+static TASK_SPAWN_SITE_LABEL: &'static str = "Spawned at";
+static COPIED_LABEL: &'static str = "Copied";
+static COPIED_ROW_LABEL: &'static str = "Copied the row of task";
+static COPIED_ATTRIBUTES_LABEL: &'static str = "Copied the attributes of task";
+// Each of these pairs says what happened when a bookmark was added, then when it was removed.
+static BOOKMARK_TOGGLED_LABELS: [&'static str; 2] = ["Bookmarked", "Removed the bookmark from"];
+static BOOKMARKS_LOAD_FAILED_LABEL: &'static str = "Couldn't load bookmarks:";
+static BOOKMARKS_SAVE_FAILED_LABEL: &'static str = "Couldn't save bookmarks:";
+static SESSION_LOAD_FAILED_LABEL: &'static str = "Couldn't restore the last session:";
+static EXPORTED_LABEL: &'static str = "Exported";
+static EXPORTED_TO_LABEL: &'static str = "to";
+static EXPORT_FAILED_LABEL: &'static str = "Couldn't export:";
+static EXPORT_SAMPLE_COUNT_LABELS: [&'static str; 2] = ["sample", "samples"];
+static CONNECTED_TO_LABEL: &'static str = "Connected to";
+static THEME_PREVIEW_LABEL: &'static str = "Theme:";
+static THEME_PREVIEW_KEYS_LABEL: &'static str = "· t next";
+static NOTIFICATIONS_LABEL: &'static str = "Notifications";
+static NOTIFICATIONS_KEYS_LABEL: &'static str = "↑↓ select · x dismiss · X clear all";
+static NOTIFICATIONS_EMPTY_LABEL: &'static str = "Nothing has happened yet";
+static NOTIFICATION_COUNT_LABELS: [&'static str; 2] = ["notification", "notifications"];
+// Indexed by `NotificationKind`.
+static NOTIFICATION_KIND_LABELS: [&'static str; 3] = ["alert", "export", "connection"];
+static NOTIFICATION_TIME_FORMAT: &'static str = "%r";
+static COMMAND_PALETTE_LABEL: &'static str = "Commands";
+static COMMAND_PALETTE_PLACEHOLDER_LABEL: &'static str = "Type to search";
+static COMMAND_PALETTE_EMPTY_LABEL: &'static str = "No matching commands";
+static COMMAND_PALETTE_KEYS_LABEL: &'static str = "↑↓ select · enter run · esc close";
+// Indexed by `TasksTab`.
+static ACTION_SELECT_TASKS_TAB_LABELS: [&'static str; 5] = [
+    "Show all tasks",
+    "Filter to running tasks",
+    "Filter to sleeping tasks",
+    "Filter to deadlocked tasks",
+    "Filter to bookmarked tasks",
+];
+// Indexed by `ViewMode`.
+static ACTION_SELECT_VIEW_MODE_LABELS: [&'static str; 4] = [
+    "List tasks flat",
+    "List tasks as a tree",
+    "Show tasks on a timeline",
+    "Show who tasks wait on",
+];
+// Each of these pairs says what the action does while the setting is off, then while it's on.
+static ACTION_TOGGLE_GROUPING_LABELS: [&'static str; 2] =
+    ["Group tasks by name", "Stop grouping tasks by name"];
+static ACTION_TOGGLE_FOLLOW_LABELS: [&'static str; 2] =
+    ["Follow new tasks", "Stop following new tasks"];
+static ACTION_TOGGLE_NOTIFICATIONS_LABELS: [&'static str; 2] =
+    ["Show notifications", "Hide notifications"];
+static ACTION_TOGGLE_NAME_FILTER_LABELS: [&'static str; 2] = [
+    "Filter to the selected task's name",
+    "Stop filtering by name",
+];
+static ACTION_SELECT_WORKSPACE_LABELS: [&'static str; 2] =
+    ["Switch to workspace", "Open workspace"];
+static ACTION_TOGGLE_BOOKMARK_LABELS: [&'static str; 2] = [
+    "Bookmark the selected task",
+    "Remove the selected task's bookmark",
+];
+static ACTION_TOGGLE_PERFORMANCE_SCREEN_LABELS: [&'static str; 2] = [
+    "Open the performance screen",
+    "Close the performance screen",
+];
+static ACTION_OPEN_COLUMN_SETTINGS_LABEL: &'static str = "Choose columns…";
+static ACTION_OPEN_PERFORMANCE_SETTINGS_LABEL: &'static str = "Choose performance metrics…";
+// Indexed by `ExportFormat`.
+static ACTION_EXPORT_LABELS: [&'static str; 2] = [
+    "Export performance history as CSV",
+    "Export performance history as JSON",
+];
+static ACTION_SELECT_THEME_LABEL: &'static str = "Switch theme to";
+static ACTION_OPEN_TASK_LABEL: &'static str = "Open task";
+static ACTION_QUIT_LABEL: &'static str = "Quit";
+static ACTION_SUSPEND_LABEL: &'static str = "Suspend to the shell";
+// What going back does with a control focused, with the performance screen open, and otherwise.
+static ACTION_BACK_LABELS: [&'static str; 3] = [
+    "Leave the focused control",
+    "Close the performance screen",
+    "Quit",
+];
+// Each of these pairs says what the action does backward, then forward.
+static ACTION_STEP_FOCUS_LABELS: [&'static str; 2] =
+    ["Focus the previous control", "Focus the next control"];
+static ACTION_SCROLL_ROWS_LABELS: [&'static str; 2] = ["Scroll tasks up", "Scroll tasks down"];
+static ACTION_SCROLL_COLUMNS_LABELS: [&'static str; 2] =
+    ["Scroll columns left", "Scroll columns right"];
+static ACTION_PAN_HISTORY_LABELS: [&'static str; 2] =
+    ["Pan the graphs toward now", "Pan the graphs back in time"];
+static ACTION_ZOOM_HISTORY_LABELS: [&'static str; 2] =
+    ["Zoom the graphs out", "Zoom the graphs in"];
+static ACTION_ZOOM_TIMELINE_LABELS: [&'static str; 2] =
+    ["Zoom the timeline out", "Zoom the timeline in"];
+static ACTION_OPEN_SELECTED_TASK_ATTRIBUTES_LABEL: &'static str =
+    "Show the selected task's attributes";
+static ACTION_OPEN_SELECTED_TASK_ACTIONS_LABEL: &'static str = "Act on the selected task…";
+static ACTION_RESET_HISTORY_VIEW_LABEL: &'static str = "Jump to the newest samples";
+static ACTION_CYCLE_TIME_WINDOW_LABEL: &'static str = "Cycle the graphs' time window";
+static ACTION_TOGGLE_COMPARISON_LABELS: [&'static str; 2] = [
+    "Compare with the previous window",
+    "Stop comparing with the previous window",
+];
+static ACTION_TOGGLE_PRESENTATION_LABELS: [&'static str; 2] =
+    ["Present the newest values in big digits", "Show the charts"];
+static ACTION_OPEN_COMMAND_PALETTE_LABEL: &'static str = "Search commands…";
+static ACTION_OPEN_HELP_LABEL: &'static str = "Show keys";
+static ACTION_UNDO_LABEL: &'static str = "Undo the last task action";
+static ACTION_COPY_SELECTED_TASK_LABEL: &'static str = "Copy the selected task's row";
+static HELP_LABEL: &'static str = "Keys";
+static HELP_KEYS_LABEL: &'static str = "↑↓ scroll · esc close";
+// Indexed by `KeyCategory`.
+static HELP_CATEGORY_LABELS: [&'static str; 5] =
+    ["Navigation", "Filtering", "Views", "Export", "General"];
+static KNOBS_LABEL: &'static str = "Knobs";
+static KNOBS_KEYS_LABEL: &'static str = "↑↓ select · ←→ turn · f12 close";
+// Indexed by `Knob`.
+static KNOB_LABELS: [&'static str; 3] = ["Tasks", "Poll time", "Deadlock"];
+static KNOB_SWITCH_LABELS: [&'static str; 2] = ["off", "on"];
+static KNOB_TURN_LABELS: [&'static str; 2] = ["<", ">"];
+static FRAME_TIMES_LABEL: &'static str = "frame";
+static MEMORY_ALLOCATED_LABEL: &'static str = "mem";
+/// How many steps a knob turns by per key press with Shift held.
+const KNOB_LARGE_STEP: isize = 10;
+const CONFIRM_DIALOG_WIDTH: u16 = 48;
+const COMMAND_PALETTE_WIDTH: u16 = 60;
+const HELP_POPUP_WIDTH: u16 = 56;
+/// Including its borders.
+const NOTIFICATIONS_PANE_HEIGHT: u16 = 8;
+/// How many matches the command palette lists at once.
+const COMMAND_PALETTE_ROW_COUNT: u16 = 12;
+const TASK_ATTRIBUTES_POPUP_WIDTH: u16 = 64;
+const TOAST_DURATION_SECONDS: i64 = 5;
+
+static ERROR_SCREEN_SUGGESTIONS_LABEL: &'static str = "Suggested actions:";
+static ERROR_SCREEN_SUGGESTION_BULLET: &'static str = "• ";
+static ERROR_SCREEN_KEYS_LABEL: &'static str = "r retry · q quit";
+const ERROR_SCREEN_WIDTH: u16 = 64;
+
+static AUTO_SIZE: Size<Dimension> = Size {
+    width: Dimension::Auto,
+    height: Dimension::Auto,
+};
+
+const PERFORMANCE_SEGMENT_VALUE_WIDTH: u16 = 6;
+/// Wide enough for a percentile's label and value, such as `p99 1.57ms`.
+const PERFORMANCE_SEGMENT_PERCENTILE_WIDTH: u16 = 10;
+const PERFORMANCE_EXPANDED_GRAPH_HEIGHT: u16 = 5;
+const PERFORMANCE_GRAPH_AUTO_SCALE: AutoScale = AutoScale {
+    padding: 0.1,
+    sticky_max: None,
+};
+
+/// How old the snapshot gets before the performance pane calls it stale: a couple of the
+/// updates due every second gone missing.
+const PERFORMANCE_STALE_AGE: Duration = Duration::from_secs(3);
+
+const PERFORMANCE_LATENCY_WARNING_MS: f32 = 5.0;
+const PERFORMANCE_LATENCY_CRITICAL_MS: f32 = 7.0;
+
+/// The index of the performance metric called `key` on the command line, such as `wake`.
+pub fn performance_metric_by_key(key: &str) -> Option<usize> {
+    PERFORMANCE_METRICS
+        .iter()
+        .position(|performance_metric| performance_metric.key == key)
+}
+
+/// The stretch tree of the last frame drawn, with its layout computed. The next frame reuses it
+/// as it is unless the screen was resized, which only changes the main node's style, or
+/// something in its `LayoutKey` changed, which builds the tree again.
+pub struct FrameLayout {
+    stretch: LayoutTree,
+    main_node: Node,
+    /// The size of the screen, less the status line.
+    size: (u16, u16),
+    key: LayoutKey,
+    title_bar_layout: TitleBarLayout,
+    /// The performance screen takes the place of the panes.
+    performance_screen_layout: Option<PerformanceScreenLayout>,
+    pane_layouts: Option<(PerformancePaneLayout, TasksPaneLayout)>,
+    notifications_pane_layout: Option<NotificationsPaneLayout>,
+}
+
+impl FrameLayout {
+    // Lays out the UI in `stretch`, reusing its storage.
+    fn build(
+        mut stretch: LayoutTree,
+        state: &AppState,
+        glyph_set: &GlyphSet,
+        size: (u16, u16),
+        key: LayoutKey,
+    ) -> FrameLayout {
+        stretch.clear();
+        let main_node = stretch.new_node(main_node_style(size));
+
+        let title_bar_layout = TitleBar.layout(state, glyph_set, &mut stretch, main_node);
+        let (performance_screen_layout, pane_layouts) = if state.performance_screen_open {
+            (
+                Some(PerformanceScreen.layout(state, glyph_set, &mut stretch, main_node)),
+                None,
+            )
+        } else {
+            (
+                None,
+                Some((
+                    PerformancePane.layout(state, glyph_set, &mut stretch, main_node),
+                    TasksPane.layout(state, glyph_set, &mut stretch, main_node),
+                )),
+            )
+        };
+        let notifications_pane_layout = if state.notifications_open {
+            Some(NotificationsPane.layout(state, glyph_set, &mut stretch, main_node))
+        } else {
+            None
+        };
+        TaskActions.layout(state, glyph_set, &mut stretch, main_node);
+        TaskAttributes.layout(state, glyph_set, &mut stretch, main_node);
+        ColumnSettings.layout(state, glyph_set, &mut stretch, main_node);
+        PerformanceSettings.layout(state, glyph_set, &mut stretch, main_node);
+        CommandPalette.layout(state, glyph_set, &mut stretch, main_node);
+        Help.layout(state, glyph_set, &mut stretch, main_node);
+        Knobs.layout(state, glyph_set, &mut stretch, main_node);
+
+        FrameLayout {
+            stretch,
+            main_node,
+            size,
+            key,
+            title_bar_layout,
+            performance_screen_layout,
+            pane_layouts,
+            notifications_pane_layout,
+        }
+    }
+
+    // Fits the layout to a screen of `size`, for `key`, and computes it if anything changed.
+    fn update(
+        mut self,
+        state: &AppState,
+        glyph_set: &GlyphSet,
+        size: (u16, u16),
+        key: LayoutKey,
+    ) -> FrameLayout {
+        if key != self.key {
+            self = FrameLayout::build(self.stretch, state, glyph_set, size, key);
+        } else if size != self.size {
+            self.stretch
+                .set_style(self.main_node, main_node_style(size));
+            self.size = size;
+        }
+        if self.stretch.dirty(self.main_node) {
+            self.stretch.compute_layout(
+                self.main_node,
+                Size {
+                    width: Number::Undefined,
+                    height: Number::Undefined,
+                },
+            );
+        }
+        self
+    }
+}
+
+fn main_node_style((width, height): (u16, u16)) -> Style {
+    Style {
+        size: Size::fixed(width, height),
+        flex_direction: FlexDirection::Column,
+        align_items: AlignItems::Stretch,
+        ..Default::default()
+    }
+}
+
+pub fn draw_frame<B>(
+    frame: &mut Frame<B>,
+    clock: &dyn Clock,
+    glyph_set: &GlyphSet,
+    state: &mut AppState,
+) where
+    B: Backend,
+{
+    TitleBar.update(state, TitleBarMessage::Tick(clock.now()));
+
+    // Lay out UI, or reuse the last frame's layout.
+    let layout_scope = profiling::scope(Phase::Layout);
+    let size = (frame.size().width, frame.size().height - 1);
+    let key = LayoutKey::new(state, glyph_set);
+    let layout = match state.frame_layout.take() {
+        Some(layout) => layout,
+        None => FrameLayout::build(LayoutTree::new(), state, glyph_set, size, key),
+    }
+    .update(state, glyph_set, size, key);
+    layout_scope.end();
+
+    let build_scope = profiling::scope(Phase::Build);
+    let mut context = ViewContext::new(
+        &layout.stretch,
+        layout.main_node,
+        glyph_set,
+        state.theme,
+        state.focused,
+    );
+    TitleBar.view(state, frame, &mut context, &layout.title_bar_layout);
+    if let Some(ref performance_screen_layout) = layout.performance_screen_layout {
+        PerformanceScreen.view(state, frame, &mut context, performance_screen_layout);
+    }
+    if let Some((ref performance_pane_layout, ref tasks_pane_layout)) = layout.pane_layouts {
+        PerformancePane.view(state, frame, &mut context, performance_pane_layout);
+        TasksPane.view(state, frame, &mut context, tasks_pane_layout);
+    }
+    if let Some(ref notifications_pane_layout) = layout.notifications_pane_layout {
+        NotificationsPane.view(state, frame, &mut context, notifications_pane_layout);
+    }
+    TaskActions.view(state, frame, &mut context, &());
+    TaskAttributes.view(state, frame, &mut context, &());
+    ColumnSettings.view(state, frame, &mut context, &());
+    PerformanceSettings.view(state, frame, &mut context, &());
+    CommandPalette.view(state, frame, &mut context, &());
+    Help.view(state, frame, &mut context, &());
+    Knobs.view(state, frame, &mut context, &());
+    build_scope.end();
+    state.widget_registry = context.registry;
+    state.hyperlinks = context.hyperlinks;
+    state.frame_layout = Some(layout);
+
+    // This frame's times aren't in until it's drawn, so the readout shows the last frame's.
+    if state.frame_times_shown {
+        draw_frame_times(frame, glyph_set, state);
+    }
+    state.frame_times = profiling::finish_frame();
+    state.memory_allocated = profiling::allocated();
+}
+
+// Puts how long the last frame took, and each phase of it, at the right end of the row under
+// the panes, followed by the memory allocated if it's counted.
+fn draw_frame_times<B>(frame: &mut Frame<B>, glyph_set: &GlyphSet, state: &AppState)
+where
+    B: Backend,
+{
+    let milliseconds = |duration: Duration| duration.as_secs_f64() * 1000.0;
+    let mut label = format!(
+        " {} {:.1} ms",
+        FRAME_TIMES_LABEL,
+        milliseconds(state.frame_times.total())
+    );
+    for &phase in &Phase::ALL {
+        label += &format!(
+            " {} {} {:.1}",
+            glyph_set.separator,
+            phase.name(),
+            milliseconds(state.frame_times.phase(phase))
+        );
+    }
+    if state.memory_allocated > 0 {
+        label += &format!(
+            " {} {} {:.1} MB",
+            glyph_set.separator,
+            MEMORY_ALLOCATED_LABEL,
+            state.memory_allocated as f64 / 1_000_000.0
+        );
+    }
+    label.push(' ');
+    let bounds = frame.size();
+    let width = display_width(&label).min(bounds.width);
+    let style = TuiStyle::default()
+        .bg(state.theme.toast_bg)
+        .fg(state.theme.toast_fg);
+    frame.render_widget(
+        Paragraph::new(label).style(style),
+        tui::layout::Rect::new(bounds.right() - width, bounds.bottom() - 1, width, 1),
+    );
+}
+
+pub fn draw_error_screen<B>(
+    frame: &mut Frame<B>,
+    glyph_set: &GlyphSet,
+    theme: &Theme,
+    error: &ConsoleError,
+) where
+    B: Backend,
+{
+    let message_style = TuiStyle::default().fg(theme.error_message_color);
+    let suggestion_style = TuiStyle::default().fg(theme.error_suggestion_color);
+    let message = error.to_string();
+    let mut lines = vec![
+        Spans::from(Span::styled(&message[..], message_style)),
+        Spans::default(),
+        Spans::from(Span::styled(
+            ERROR_SCREEN_SUGGESTIONS_LABEL,
+            message_style.add_modifier(Modifier::BOLD),
+        )),
+    ];
+    for &suggestion in error.suggestions() {
+        lines.push(Spans::from(vec![
+            Span::styled(ERROR_SCREEN_SUGGESTION_BULLET, suggestion_style),
+            Span::styled(suggestion, suggestion_style),
+        ]));
+    }
+
+    // Size the box to the wrapped text: two cells of border and two of padding on each side.
+    let box_width = ERROR_SCREEN_WIDTH.min(frame.size().width);
+    let text_width = box_width.saturating_sub(4).max(1);
+    let text_height: u16 = lines
+        .iter()
+        .map(|line| wrapped_height(line, text_width))
+        .sum();
+
+    let mut stretch = LayoutTree::new();
+    let mut renderer = Renderer::new();
+    let main_node = stretch.new_node(Style {
+        size: Size::fixed(frame.size().width, frame.size().height),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..Default::default()
+    });
+    ui!(stretch, renderer; main_node => [
+        error_node { size: Size::fixed(box_width, text_height + 2) } (
+            pane_box_frame(error.title(), theme.error_box_fg, glyph_set, theme)
+                .footer_label(ERROR_SCREEN_KEYS_LABEL)
+        ) [
+            _error_inner_container_node {
+                size: AUTO_SIZE,
+                padding: Rect::new(1, 2, 1, 2),
+                flex_grow: 1.0,
+            } [
+                _error_text_node { size: AUTO_SIZE, flex_grow: 1.0 } (
+                    Paragraph::new(lines).wrap(Wrap { trim: true })
+                ),
+            ],
+        ],
+    ]);
+    stretch.compute_layout(
+        main_node,
+        Size {
+            width: Number::Undefined,
+            height: Number::Undefined,
+        },
+    );
+
+    renderer.render(frame, &stretch, main_node, Point { x: 0, y: 0 });
+}
+
+fn pane_box_frame<'a>(
+    label: &'a str,
+    border_color: Color,
+    glyph_set: &GlyphSet,
+    theme: &Theme,
+) -> BoxFrame<'a> {
+    BoxFrame::new(label)
+        .border_color(border_color)
+        .text_color(theme.box_text_color)
+        .border_set(glyph_set.border_set)
+}
+
+fn title_bar_powerline<'a>(
+    labels: &'a [&'a str],
+    glyph_set: &GlyphSet,
+    theme: &Theme,
+) -> Powerline<'a> {
+    Powerline::new(labels)
+        .symbols(glyph_set.powerline)
+        .main_color(theme.title_main_color)
+        .sub_color(theme.title_sub_color)
+        .sub_sub_colors(theme.title_sub_sub_bg, theme.title_sub_sub_fg)
+        .sub_separator_color(theme.title_sub_separator_color)
+}
+
+/// A task as shown in the tasks table.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Task {
+    pub id: u64,
+    pub name: String,
+    pub status: TaskStatus,
+    /// The share of the runtime's busy time spent polling this task.
+    pub run_percent: f32,
+    pub poll_ms: f32,
+    pub wake_ms: f32,
+    pub attributes: Vec<(String, String)>,
+    /// How long the task has spent being polled, over its whole life.
+    pub busy_time: Duration,
+    /// How long the task has spent waiting to be woken, over its whole life.
+    pub idle_time: Duration,
+    /// Whether the user paused the task from the console.
+    pub paused: bool,
+    /// Whether the user raised the task's scheduling priority from the console.
+    pub boosted: bool,
+    /// Where in the program's source the task was spawned, if the runtime says.
+    pub spawn_site: Option<SpawnSite>,
+    /// The stretches of its life the task spent being polled, oldest first, as far back as the
+    /// runtime remembers them.
+    pub busy_periods: Vec<BusyPeriod>,
+}
+
+impl Task {
+    /// How long ago the task was spawned. A task is always either busy or idle, so this is the
+    /// two added together.
+    pub fn lifetime(&self) -> Duration {
+        self.busy_time + self.idle_time
+    }
+}
+
+/// A stretch of time a task spent being polled, as how long before the snapshot it started and
+/// ended.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BusyPeriod {
+    pub started_ago: Duration,
+    pub ended_ago: Duration,
+}
+
+/// The line of source a task was spawned on, with the lines around it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SpawnSite {
+    /// Relative to the program's crate, such as `src/server.rs`.
+    pub file: String,
+    pub line: u32,
+    /// The lines around `line`, starting at `first_line`.
+    pub snippet: String,
+    pub first_line: u32,
+}
+
+/// Something tasks hold and wait on, such as a mutex or a semaphore.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Resource {
+    pub id: u64,
+    /// What sort of thing it is, such as `Mutex`.
+    pub kind: String,
+    /// What the program calls it, such as the field it's kept in.
+    pub name: String,
+    /// The tasks holding it, whom the tasks waiting on it wait for.
+    pub holder_task_ids: Vec<u64>,
+    pub waiting_task_ids: Vec<u64>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TaskStatus {
+    Running,
+    Sleeping,
+    Deadlocked,
+}
+
+// Grouped tasks are indented under their group's row.
+/// A task's row as the tasks table shows it, for pasting elsewhere: a line of the headers of
+/// `table_columns`, then a line of the task's cells in those columns, untruncated. Cells are
+/// separated by tabs so that spreadsheets split them into columns.
+fn task_table_row_text(
+    task: &Task,
+    table_columns: &[usize],
+    strings: &Strings,
+    theme: &Theme,
+) -> String {
+    // The first column is only a button.
+    let table_columns: Vec<_> = table_columns
+        .iter()
+        .cloned()
+        .filter(|&table_column| !strings.tasks_table_columns[table_column].is_empty())
+        .collect();
+    let headers: Vec<_> = table_columns
+        .iter()
+        .map(|&table_column| strings.tasks_table_columns[table_column])
+        .collect();
+    let cells: Vec<String> = select_table_columns(
+        create_task_table_cells(task, false, false, &GLYPH_SET_NERD_FONT, theme),
+        &table_columns,
+    )
+    .into_iter()
+    .map(|cell| cell.0.iter().map(|span| &span.content[..]).collect())
+    .collect();
+    format!("{}\n{}", headers.join("\t"), cells.join("\t"))
+}
+
+// Bookmarked tasks are marked before their names.
+fn create_task_table_cells<'a>(
+    task: &'a Task,
+    grouped: bool,
+    bookmarked: bool,
+    glyph_set: &GlyphSet,
+    theme: &Theme,
+) -> Vec<Spans<'a>> {
+    let open_cell_style = TuiStyle::default().fg(theme.tasks_table_open_cell_color);
+    let minor_cell_style = TuiStyle::default().fg(theme.tasks_table_minor_cell_color);
+    let name_cell_style = TuiStyle::default().fg(theme.tasks_table_name_cell_color);
+    let key_cell_style = TuiStyle::default().fg(theme.tasks_table_attribute_key_cell_color);
+    let value_cell_style = TuiStyle::default().fg(theme.tasks_table_attribute_value_cell_color);
+
+    let mut attribute_spans = Vec::with_capacity(task.attributes.len() * 4);
+    for (index, (key, value)) in task.attributes.iter().enumerate() {
+        if index > 0 {
+            attribute_spans.push(Span::styled(", ", minor_cell_style));
+        }
+        attribute_spans.push(Span::styled(&key[..], key_cell_style));
+        attribute_spans.push(Span::styled("=", minor_cell_style));
+        attribute_spans.push(Span::styled(&value[..], value_cell_style));
+    }
+
+    let mut name_spans = Vec::with_capacity(4);
+    if grouped {
+        name_spans.push(Span::raw(TASKS_TABLE_GROUP_MEMBER_INDENT));
+    }
+    if bookmarked {
+        name_spans.push(Span::styled(
+            glyph_set.task_bookmark,
+            TuiStyle::default().fg(theme.tasks_table_bookmark_color),
+        ));
+    }
+    if task.boosted {
+        name_spans.push(Span::styled(
+            glyph_set.task_boosted,
+            TuiStyle::default().fg(theme.tasks_table_boosted_color),
+        ));
+    }
+    name_spans.push(Span::styled(&task.name[..], name_cell_style));
+    let status_cell = if task.paused {
+        Spans::from(Span::styled(
+            glyph_set.task_paused,
+            TuiStyle::default().fg(theme.tasks_table_status_paused_color),
+        ))
+    } else {
+        task_status_cell(task.status, glyph_set, theme)
+    };
+
+    // Built up in one go, since there's a row of these for every task in every frame.
+    let mut cells = Vec::with_capacity(TASKS_TABLE_COLUMNS.len());
+    cells.push(Spans::from(Span::styled(
+        glyph_set.task_group_open,
+        open_cell_style,
+    )));
+    cells.push(Spans::from(task.id.to_string()));
+    cells.push(Spans::from(name_spans));
+    cells.push(status_cell);
+    push_task_measurement_cells(
+        &mut cells,
+        task.run_percent,
+        task.poll_ms,
+        task.wake_ms,
+        glyph_set,
+        theme,
+    );
+    push_task_duration_cells(
+        &mut cells,
+        task.busy_time,
+        task.idle_time,
+        task.lifetime(),
+        glyph_set,
+        theme,
+    );
+    cells.push(Spans::from(attribute_spans));
+    cells
+}
+
+// A group's row has no ID or attributes of its own, and shows how many tasks it has after their
+// shared name.
+fn create_task_group_table_cells<'a>(
+    group: &TaskGroup<'a>,
+    glyph_set: &GlyphSet,
+    theme: &Theme,
+) -> Vec<Spans<'a>> {
+    let open_cell_style = TuiStyle::default().fg(theme.tasks_table_open_cell_color);
+    let minor_cell_style = TuiStyle::default().fg(theme.tasks_table_minor_cell_color);
+    let name_cell_style = TuiStyle::default().fg(theme.tasks_table_name_cell_color);
+    let button_label = if group.expanded {
+        glyph_set.task_group_close
+    } else {
+        glyph_set.task_group_open
+    };
+
+    let mut cells = Vec::with_capacity(TASKS_TABLE_COLUMNS.len());
+    cells.push(Spans::from(Span::styled(button_label, open_cell_style)));
+    cells.push(Spans::default());
+    cells.push(Spans::from(vec![
+        Span::styled(group.name, name_cell_style),
+        Span::styled(
+            format!(" {}{}", glyph_set.task_group_count, group.tasks.len()),
+            minor_cell_style,
+        ),
+    ]));
+    cells.push(task_status_cell(group.status(), glyph_set, theme));
+    push_task_measurement_cells(
+        &mut cells,
+        group.run_percent(),
+        group.mean_poll_ms(),
+        group.mean_wake_ms(),
+        glyph_set,
+        theme,
+    );
+    push_task_duration_cells(
+        &mut cells,
+        group.total_busy_time(),
+        group.total_idle_time(),
+        group.longest_lifetime(),
+        glyph_set,
+        theme,
+    );
+    cells.push(Spans::default());
+    cells
+}
+
+fn task_status_cell(status: TaskStatus, glyph_set: &GlyphSet, theme: &Theme) -> Spans<'static> {
+    let (status_label, status_color);
+    match status {
+        TaskStatus::Running => {
+            status_label = glyph_set.task_running;
+            status_color = theme.tasks_table_status_running_color;
+        }
+        TaskStatus::Sleeping => {
+            status_label = glyph_set.task_sleeping;
+            status_color = theme.tasks_table_status_sleeping_color;
+        }
+        TaskStatus::Deadlocked => {
+            status_label = glyph_set.task_deadlocked;
+            status_color = theme.tasks_table_status_deadlocked_color;
+        }
+    };
+    Spans::from(Span::styled(
+        status_label,
+        TuiStyle::default().fg(status_color),
+    ))
+}
+
+// Adds the Run %, Poll, and Wake cells, each in the color its thresholds call for.
+fn push_task_measurement_cells(
+    cells: &mut Vec<Spans>,
+    run_percent: f32,
+    poll_ms: f32,
+    wake_ms: f32,
+    glyph_set: &GlyphSet,
+    theme: &Theme,
+) {
+    let style = |value: f32, thresholds: &Thresholds| UnitStyle {
+        number: TuiStyle::default().fg(thresholds.color(
+            value,
+            theme.tasks_table_numeric_cell_color,
+            theme.tasks_table_warning_cell_color,
+            theme.tasks_table_critical_cell_color,
+        )),
+        ..task_table_unit_style(glyph_set, theme)
+    };
+    cells.push(format::percent(
+        run_percent,
+        style(run_percent, &theme.tasks_table_run_percent_thresholds),
+    ));
+    cells.push(format::milliseconds(
+        poll_ms,
+        style(poll_ms, &theme.tasks_table_poll_thresholds),
+    ));
+    cells.push(format::milliseconds(
+        wake_ms,
+        style(wake_ms, &theme.tasks_table_wake_thresholds),
+    ));
+}
+
+// Adds the Busy, Idle, and Lifetime cells.
+fn push_task_duration_cells(
+    cells: &mut Vec<Spans>,
+    busy_time: Duration,
+    idle_time: Duration,
+    lifetime: Duration,
+    glyph_set: &GlyphSet,
+    theme: &Theme,
+) {
+    let style = task_table_unit_style(glyph_set, theme);
+    for &duration in &[busy_time, idle_time, lifetime] {
+        cells.push(format::duration(duration, style));
+    }
+}
+
+fn task_table_unit_style(glyph_set: &GlyphSet, theme: &Theme) -> UnitStyle {
+    UnitStyle {
+        number: TuiStyle::default().fg(theme.tasks_table_numeric_cell_color),
+        unit: TuiStyle::default().fg(theme.tasks_table_minor_cell_color),
+        microseconds: glyph_set.microseconds,
+    }
+}
+
+// Picks out the visible columns of a row and fits each cell to its column's width and
+// alignment. The cells of the visible columns are moved out, leaving empty ones behind.
+fn fit_task_table_cells<'a>(
+    mut cells: Vec<Spans<'a>>,
+    visible_columns: &[usize],
+    visible_column_widths: &[u16],
+    glyph_set: &GlyphSet,
+) -> Vec<Cell<'a>> {
+    visible_columns
+        .iter()
+        .zip(visible_column_widths.iter())
+        .map(|(&column, &width)| {
+            let cell = mem::take(&mut cells[column]);
+            let alignment = TASKS_TABLE_COLUMNS[column].alignment;
+            let cell = truncate_spans(cell, width, glyph_set.ellipsis);
+            Cell::from(align_spans(cell, width, alignment))
+        })
+        .collect()
+}
+
+fn select_table_columns<T>(cells: Vec<T>, visible_columns: &[usize]) -> Vec<T> {
+    cells
+        .into_iter()
+        .enumerate()
+        .filter(|(column, _)| visible_columns.contains(column))
+        .map(|(_, cell)| cell)
+        .collect()
+}
+
+// Shades every other row when the theme provides a stripe color. Stripes follow the row's
+// position in the tab's task list, so they stay attached to the same tasks while scrolling.
+fn stripe_task_table_row<'a>(row: Row<'a>, row_index: usize, theme: &Theme) -> Row<'a> {
+    match theme.tasks_table_stripe_bg {
+        Some(stripe_bg) if row_index % 2 == 1 => row.style(TuiStyle::default().bg(stripe_bg)),
+        _ => row,
+    }
+}
@@ -0,0 +1,93 @@
+//! A stand-in for the connection to the target, which can be made slow and unreliable, so that
+//! the reconnect screen, stale readouts, and gaps in the graphs can be designed without a real
+//! transport.
+//!
+//! Like the mock data, the link is made up but deterministic: the same settings lose the same
+//! updates on every run.
+
+use crate::fixtures;
+use std::time::Duration;
+
+const LINK_SEED: u32 = 0x11e4;
+/// How many updates in a row can go missing before the connection counts as lost.
+pub const LINK_TIMEOUT_UPDATE_COUNT: u32 = 5;
+
+/// How late the target's updates arrive, and how many never do.
+#[derive(Clone, Debug)]
+pub struct SimulatedLink {
+    /// How long every update takes to arrive.
+    pub latency: Duration,
+    /// Up to how much longer than `latency` each update takes, at random.
+    pub jitter: Duration,
+    /// The fraction of updates lost on the way, from 0 to 1.
+    pub loss: f32,
+    sent_count: u32,
+    /// How many updates in a row have been lost.
+    lost_count: u32,
+}
+
+impl SimulatedLink {
+    pub fn new(latency: Duration, jitter: Duration, loss: f32) -> SimulatedLink {
+        SimulatedLink {
+            latency,
+            jitter,
+            loss,
+            sent_count: 0,
+            lost_count: 0,
+        }
+    }
+
+    /// Sends an update, returning how long it took to arrive, or `None` if it was lost.
+    pub fn send(&mut self) -> Option<Duration> {
+        self.sent_count = self.sent_count.wrapping_add(1);
+        let noise = fixtures::mix(self.sent_count ^ LINK_SEED);
+        if ((noise & 0xffff) as f32) < self.loss * 65536.0 {
+            self.lost_count += 1;
+            return None;
+        }
+        self.lost_count = 0;
+        Some(self.latency + self.jitter.mul_f32((noise >> 16) as f32 / 65535.0))
+    }
+
+    /// Whether enough updates in a row were lost for the connection to count as dropped. The
+    /// count starts over once it has.
+    pub fn take_timeout(&mut self) -> bool {
+        if self.lost_count < LINK_TIMEOUT_UPDATE_COUNT {
+            return false;
+        }
+        self.lost_count = 0;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn updates_arrive_late_or_not_at_all() {
+        let latency = Duration::from_millis(300);
+        let jitter = Duration::from_millis(100);
+        let mut link = SimulatedLink::new(latency, jitter, 0.0);
+        for _ in 0..100 {
+            let delay = link.send().unwrap();
+            assert!(delay >= latency && delay <= latency + jitter);
+        }
+
+        let mut link = SimulatedLink::new(latency, jitter, 0.25);
+        let lost_count = (0..1000).filter(|_| link.send().is_none()).count();
+        assert!((200..300).contains(&lost_count), "{}", lost_count);
+    }
+
+    #[test]
+    fn the_connection_drops_after_enough_updates_in_a_row_are_lost() {
+        let mut link = SimulatedLink::new(Duration::ZERO, Duration::ZERO, 1.0);
+        for _ in 1..LINK_TIMEOUT_UPDATE_COUNT {
+            assert_eq!(link.send(), None);
+            assert!(!link.take_timeout());
+        }
+        link.send();
+        assert!(link.take_timeout());
+        assert!(!link.take_timeout());
+    }
+}
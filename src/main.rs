@@ -1,873 +1,651 @@
-use crate::widgets::{
-    BarChart, BoxFrame, MainVisibility, Powerline, PowerlineDirection, Scrollbar, SegmentedControl,
-};
+#![allow(clippy::redundant_static_lifetimes)]
+
 use better_panic::Settings;
-use chrono::Local;
-use crossterm::{cursor, execute, terminal};
-use std::collections::HashMap;
-use std::io::{self, Stdout};
+use crossterm::event::{Event, MouseEvent, MouseEventKind};
+use std::cell::RefCell;
+use std::env;
+use std::fs;
+use std::io::{self, BufWriter};
 use std::panic;
-use stretch::geometry::{Point, Rect, Size};
-use stretch::node::Node;
-use stretch::number::Number;
-use stretch::result::Layout;
-use stretch::style::{AlignItems, Dimension, FlexDirection, Style};
-use stretch::Stretch;
-use tui::backend::CrosstermBackend;
-use tui::layout::{Constraint, Rect as TuiRect};
-use tui::style::{Color, Modifier, Style as TuiStyle};
-use tui::text::{Span, Spans};
-use tui::widgets::{Cell, Paragraph, Row, Table};
-use tui::{Frame, Terminal};
-use widgets::AnyWidget;
-
-mod widgets;
-
-fn main() -> Result<(), io::Error> {
-    let backend = CrosstermBackend::new(io::stdout());
-    let mut terminal = Terminal::new(backend)?;
-    terminal.clear()?;
+use std::path::Path;
+use std::process;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use tokio_console_mocks::app::{App, Command};
+use tokio_console_mocks::backend::{ColorDepth, ColorDepthBackend};
+use tokio_console_mocks::clipboard;
+use tokio_console_mocks::clock::SystemClock;
+use tokio_console_mocks::config;
+use tokio_console_mocks::error::ConsoleError;
+use tokio_console_mocks::fixed_size::{self, FixedSizeBackend};
+use tokio_console_mocks::fixtures;
+use tokio_console_mocks::fixtures::{Dataset, Demo};
+use tokio_console_mocks::frame_limiter::FrameLimiter;
+use tokio_console_mocks::gallery::MockScreen;
+use tokio_console_mocks::glyphs::{GLYPH_SET_ASCII, GLYPH_SET_NERD_FONT};
+use tokio_console_mocks::hyperlink::HyperlinkBackend;
+use tokio_console_mocks::job_control::JobControl;
+use tokio_console_mocks::link::SimulatedLink;
+use tokio_console_mocks::performance_metric_by_key;
+use tokio_console_mocks::platform::{self, TerminalSupport};
+use tokio_console_mocks::screen_diff::{self, Screen};
+use tokio_console_mocks::screenshot;
+use tokio_console_mocks::state::AlertThreshold;
+use tokio_console_mocks::storybook::Storybook;
+use tokio_console_mocks::strings::{self, Strings};
+use tokio_console_mocks::synchronized::SynchronizedBackend;
+use tokio_console_mocks::terminal::{DefaultLibrary, TerminalLibrary};
+use tokio_console_mocks::terminal_guard::TerminalGuard;
+use tokio_console_mocks::theme::THEMES;
+use tui::Terminal;
+use turbowish_widgets::{CHART_SYMBOLS_ASCII, CHART_SYMBOLS_BLOCKS, CHART_SYMBOLS_BRAILLE};
+
+// Counts allocations for the memory in the frame time readout.
+#[cfg(feature = "profiling")]
+#[global_allocator]
+static ALLOCATOR: tokio_console_mocks::profiling::CountingAllocator =
+    tokio_console_mocks::profiling::CountingAllocator;
+
+fn main() -> crossterm::Result<()> {
+    match operands().first().map(|operand| &operand[..]) {
+        Some(subcommand) if subcommand == DIFF_SUBCOMMAND => process::exit(run_diff()),
+        Some(subcommand) if subcommand == DUMP_SUBCOMMAND => process::exit(run_dump()),
+        Some(subcommand) if subcommand == SCREENSHOTS_SUBCOMMAND => {
+            process::exit(run_screenshots())
+        }
+        _ => {}
+    }
 
+    let terminal_support = terminal_support_with_overrides(platform::detect_terminal_support());
+    let fixed_size = match fixed_size() {
+        Ok(fixed_size) => fixed_size,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(DIFF_TROUBLE_EXIT_CODE);
+        }
+    };
     panic::set_hook(Box::new(move |panic_info| {
-        let mut stdout = io::stdout();
-        execute!(stdout, cursor::MoveTo(0, 0)).unwrap();
-        execute!(stdout, terminal::Clear(terminal::ClearType::All)).unwrap();
-
-        execute!(stdout, terminal::LeaveAlternateScreen).unwrap();
-        execute!(stdout, cursor::Show).unwrap();
-
-        terminal::disable_raw_mode().unwrap();
+        DefaultLibrary::restore_terminal();
         Settings::auto().create_panic_handler()(panic_info);
     }));
 
-    terminal.draw(|frame| draw_frame(frame)).unwrap();
-    Ok(())
-}
-
-static TITLE_LABEL: &'static str = "ﴱ Tokio";
-static TITLE_BAR_RUNTIME_COUNT_LABELS: [&'static str; 2] = ["runtime", "runtimes"];
-static TITLE_BAR_THREAD_COUNT_LABELS: [&'static str; 2] = ["thread", "threads"];
-static MENU_BUTTON_LABEL: &'static str = "☰ Menu";
-static TIME_FORMAT: &'static str = "%x %r";
-static PERFORMANCE_LABEL: &'static str = "Performance";
-static PERFORMANCE_RUN_PERCENT_TIME_LABEL: &'static str = "Runtime";
-static PERFORMANCE_DEPTH_LABEL: &'static str = "Sched. depth";
-static PERFORMANCE_POLL_TIME_LABEL: &'static str = "Poll time";
-static PERFORMANCE_WAKE_TIME_LABEL: &'static str = "Wake time";
-static PERFORMANCE_LABELS: [&'static str; 4] = [
-    PERFORMANCE_RUN_PERCENT_TIME_LABEL,
-    PERFORMANCE_DEPTH_LABEL,
-    PERFORMANCE_POLL_TIME_LABEL,
-    PERFORMANCE_WAKE_TIME_LABEL,
-];
-static PERFORMANCE_EXPAND_LABEL: &'static str = "\u{fa4e}";
-static TASKS_LABEL: &'static str = "Tasks";
-static TASKS_TAB_LABEL_ALL: &'static str = "All";
-static TASKS_TAB_LABEL_RUNNING: &'static str = "\u{f04b} Running";
-static TASKS_TAB_LABEL_SLEEPING: &'static str = "\u{f04c} Sleeping";
-static TASKS_TAB_LABEL_DEADLOCKED: &'static str = "\u{f071} Deadlocked";
-static TASKS_TAB_LABELS: [&'static str; 4] = [
-    TASKS_TAB_LABEL_ALL,
-    TASKS_TAB_LABEL_RUNNING,
-    TASKS_TAB_LABEL_SLEEPING,
-    TASKS_TAB_LABEL_DEADLOCKED,
-];
-static TASKS_VIEW_MODE_LABEL_FLAT: &'static str = "\u{f03a}";
-static TASKS_VIEW_MODE_LABEL_TREE: &'static str = "\u{fb44}";
-static TASKS_VIEW_MODE_LABELS: [&'static str; 2] =
-    [TASKS_VIEW_MODE_LABEL_FLAT, TASKS_VIEW_MODE_LABEL_TREE];
-static TASKS_TABLE_STATUS_RUNNING: &'static str = "\u{f04b}";
-static TASKS_TABLE_STATUS_SLEEPING: &'static str = "\u{f04c}";
-static TASKS_TABLE_STATUS_DEADLOCKED: &'static str = "\u{f071}";
-static TASKS_TABLE_BUTTON_OPEN: &'static str = "\u{f457}";
-static _TASKS_TABLE_BUTTON_CLOSE: &'static str = "\u{f458}";
-static TASKS_TABLE_COLUMN_LABEL_ID: &'static str = "ID";
-static TASKS_TABLE_COLUMN_LABEL_NAME: &'static str = "Name";
-static TASKS_TABLE_COLUMN_LABEL_STATE: &'static str = "State";
-static TASKS_TABLE_COLUMN_LABEL_RUN_PERCENT: &'static str = "Run %";
-static TASKS_TABLE_COLUMN_LABEL_POLL_MS: &'static str = "Poll";
-static TASKS_TABLE_COLUMN_LABEL_WAKE_MS: &'static str = "Wake";
-static TASKS_TABLE_COLUMN_LABEL_ATTRIBUTES: &'static str = "Attributes";
-static TASKS_TABLE_COLUMN_LABELS: [&'static str; 8] = [
-    "",
-    TASKS_TABLE_COLUMN_LABEL_ID,
-    TASKS_TABLE_COLUMN_LABEL_NAME,
-    TASKS_TABLE_COLUMN_LABEL_STATE,
-    TASKS_TABLE_COLUMN_LABEL_RUN_PERCENT,
-    TASKS_TABLE_COLUMN_LABEL_POLL_MS,
-    TASKS_TABLE_COLUMN_LABEL_WAKE_MS,
-    TASKS_TABLE_COLUMN_LABEL_ATTRIBUTES,
-];
-static TASKS_TABLE_COLUMN_WIDTHS: [u16; 7] = [
-    3,  // Widgets
-    10, // ID
-    24, // Name
-    5,  // State
-    5,  // Run %
-    7,  // Poll ms
-    7,  // Wake ms
-];
-
-static AUTO_SIZE: Size<Dimension> = Size {
-    width: Dimension::Auto,
-    height: Dimension::Auto,
-};
-
-static FAKE_TARGET_LABEL: &'static str = "my_app (412)";
-static FAKE_TASK_COUNTS: [u32; 4] = [405, 3, 402, 0];
-const FAKE_RUNTIME_COUNT: u32 = 1;
-const FAKE_THREAD_COUNT: u32 = 8;
-
-const PERFORMANCE_SEGMENT_VALUE_WIDTH: u16 = 6;
-
-const THEME_COLOR_TITLE_MAIN_COLOR: Color = Color::Rgb(0x88, 0xc0, 0xd0);
-const THEME_COLOR_TITLE_SUB_COLOR: Color = Color::Rgb(0x81, 0xa1, 0xc1);
-const THEME_COLOR_TITLE_SUB_SUB_BG: Color = Color::Rgb(0x3b, 0x42, 0x52);
-const THEME_COLOR_TITLE_SUB_SUB_FG: Color = Color::Rgb(0xe5, 0xe9, 0xf0);
-const THEME_COLOR_TITLE_SUB_SEPARATOR_COLOR: Color = Color::DarkGray;
-const THEME_COLOR_PERFORMANCE_BOX_FG: Color = Color::Green;
-const THEME_COLOR_PERFORMANCE_LABEL: Color = Color::Gray;
-const THEME_COLOR_PERFORMANCE_NUMERIC_COLOR: Color = Color::Green;
-const THEME_COLOR_PERFORMANCE_MINOR_COLOR: Color = Color::DarkGray;
-const THEME_COLOR_PERFORMANCE_GRAPH_COLOR: Color = Color::Green;
-const THEME_COLOR_TASKS_BOX_FG: Color = Color::Red;
-const THEME_COLOR_TASKS_FILTER_BG: Color = Color::Black; // Color::Rgb(32, 0, 0);
-const THEME_COLOR_TASKS_FILTER_FG: Color = Color::Gray; // Color::Red;
-const THEME_COLOR_TASKS_TABLE_HEADER_FG: Color = Color::White;
-const THEME_COLOR_TASKS_TABLE_OPEN_CELL_COLOR: Color = Color::DarkGray;
-const THEME_COLOR_TASKS_TABLE_MINOR_CELL_COLOR: Color = Color::DarkGray;
-const THEME_COLOR_TASKS_TABLE_NAME_CELL_COLOR: Color = Color::Yellow;
-const THEME_COLOR_TASKS_TABLE_NUMERIC_CELL_COLOR: Color = Color::Green;
-const THEME_COLOR_TASKS_TABLE_ATTRIBUTE_KEY_CELL_COLOR: Color = Color::Blue;
-const THEME_COLOR_TASKS_TABLE_ATTRIBUTE_VALUE_CELL_COLOR: Color = Color::Yellow;
-const THEME_COLOR_TASKS_TABLE_STATUS_RUNNING_COLOR: Color = Color::Green;
-const THEME_COLOR_TASKS_TABLE_STATUS_SLEEPING_COLOR: Color = Color::Gray;
-const THEME_COLOR_TASKS_TABLE_STATUS_DEADLOCKED_COLOR: Color = Color::Red;
-const THEME_COLOR_SCROLLBAR_COLOR: Color = Color::Gray;
-
-type AppFrame<'a> = Frame<'a, CrosstermBackend<Stdout>>;
-
-fn draw_frame(frame: &mut AppFrame) {
-    // Initialize the DOM.
-    let mut stretch = Stretch::new();
-    let mut renderer = Renderer::new();
-    let main_node = stretch
-        .new_node(
-            Style {
-                size: Size::fixed(frame.size().width, frame.size().height - 1),
-                flex_direction: FlexDirection::Column,
-                align_items: AlignItems::Stretch,
-                ..Default::default()
-            },
-            vec![],
-        )
-        .unwrap();
-
-    // Lay out UI.
-    let title_bar_layout = TitleBarLayout::layout(&mut stretch, main_node);
-    let performance_pane_layout = PerformancePaneLayout::layout(&mut stretch, main_node);
-    let tasks_pane_layout = TasksPaneLayout::layout(&mut stretch, main_node);
-    stretch
-        .compute_layout(
-            main_node,
-            Size {
-                width: Number::Undefined,
-                height: Number::Undefined,
-            },
-        )
-        .unwrap();
-
-    // Build title bar.
-    let runtime_count_label = format!(
-        "{} {}",
-        FAKE_RUNTIME_COUNT, TITLE_BAR_RUNTIME_COUNT_LABELS[0]
-    );
-    let thread_count_label = format!("{} {}", FAKE_THREAD_COUNT, TITLE_BAR_THREAD_COUNT_LABELS[1]);
-    let main_powerline_labels = [
-        TITLE_LABEL,
-        FAKE_TARGET_LABEL,
-        &runtime_count_label[..],
-        &thread_count_label[..],
-    ];
-    renderer.build_node(
-        title_bar_layout.main_powerline_node,
-        Powerline {
-            labels: &main_powerline_labels,
-            direction: PowerlineDirection::LeftToRight,
-            main_visibility: MainVisibility::Visible,
-            main_color: THEME_COLOR_TITLE_MAIN_COLOR,
-            sub_color: THEME_COLOR_TITLE_SUB_COLOR,
-            sub_sub_bg_color: THEME_COLOR_TITLE_SUB_SUB_BG,
-            sub_sub_fg_color: THEME_COLOR_TITLE_SUB_SUB_FG,
-            sub_separator_color: THEME_COLOR_TITLE_SUB_SEPARATOR_COLOR,
-        },
-    );
-    let time_label = Local::now().format(TIME_FORMAT).to_string();
-    let menu_powerline_labels = [MENU_BUTTON_LABEL, &time_label[..]];
-    renderer.build_node(
-        title_bar_layout.menu_powerline_node,
-        Powerline {
-            labels: &menu_powerline_labels,
-            direction: PowerlineDirection::RightToLeft,
-            main_visibility: MainVisibility::Invisible,
-            main_color: THEME_COLOR_TITLE_MAIN_COLOR,
-            sub_color: THEME_COLOR_TITLE_SUB_COLOR,
-            sub_sub_bg_color: THEME_COLOR_TITLE_SUB_SUB_BG,
-            sub_sub_fg_color: THEME_COLOR_TITLE_SUB_SUB_FG,
-            sub_separator_color: THEME_COLOR_TITLE_SUB_SEPARATOR_COLOR,
-        },
-    );
-
-    // Render performance values.
-    let performance_numeric_style = TuiStyle::default().fg(THEME_COLOR_PERFORMANCE_NUMERIC_COLOR);
-    let performance_minor_style = TuiStyle::default().fg(THEME_COLOR_PERFORMANCE_MINOR_COLOR);
-    let rendered_performance_values = vec![
-        Spans::from(vec![
-            Span::styled("23.3", performance_numeric_style),
-            Span::styled("%", performance_minor_style),
-        ]),
-        Spans::from(vec![Span::styled("2.19", performance_numeric_style)]),
-        Spans::from(vec![
-            Span::styled("1.05", performance_numeric_style),
-            Span::styled("ms", performance_minor_style),
-        ]),
-        Spans::from(vec![
-            Span::styled("0.75", performance_numeric_style),
-            Span::styled("ms", performance_minor_style),
-        ]),
-    ];
-
-    // Build performance pane.
-    renderer.build_node(
-        performance_pane_layout.performance_node,
-        BoxFrame {
-            label: PERFORMANCE_LABEL,
-            border_color: THEME_COLOR_PERFORMANCE_BOX_FG,
-            text_color: Color::White,
-        },
-    );
-    renderer.build_node(
-        performance_pane_layout.performance_expand_button_node,
-        Paragraph::new(PERFORMANCE_EXPAND_LABEL),
-    );
-    let performance_node_children = stretch
-        .children(performance_pane_layout.performance_graphs_container_node)
-        .unwrap();
-    for performance_segment_index in 0..PERFORMANCE_LABELS.len() {
-        let performance_segment_node = performance_node_children[performance_segment_index];
-        let performance_segment_children = stretch.children(performance_segment_node).unwrap();
-        let performance_segment_label_node = performance_segment_children[0];
-        let performance_segment_value_node = performance_segment_children[1];
-        let performance_segment_graph_node = performance_segment_children[2];
-        renderer.build_node(
-            performance_segment_label_node,
-            Paragraph::new(PERFORMANCE_LABELS[performance_segment_index])
-                .style(TuiStyle::default().fg(THEME_COLOR_PERFORMANCE_LABEL)),
-        );
-        renderer.build_node(
-            performance_segment_value_node,
-            Paragraph::new(rendered_performance_values[performance_segment_index].clone()),
-        );
-        renderer.build_node(
-            performance_segment_graph_node,
-            BarChart::new(
-                &[4.0, 2.0, 7.0, 1.0, 7.0, 8.0, 3.0],
-                0.0,
-                7.0,
-                THEME_COLOR_PERFORMANCE_GRAPH_COLOR,
+    // Raw mode turns Ctrl-C into a key event, which the main loop handles, so this mostly
+    // catches SIGTERM and SIGHUP, or closing the console window on Windows. The handler runs on
+    // its own thread rather than in signal context, so it's free to write to the terminal.
+    ctrlc::set_handler(|| {
+        DefaultLibrary::restore_terminal();
+        process::exit(SIGNAL_EXIT_CODE);
+    })
+    .expect("Failed to install the signal handler");
+
+    let job_control = JobControl::install()?;
+    let terminal_guard = TerminalGuard::<DefaultLibrary>::enter()?;
+    let hyperlinks = Rc::new(RefCell::new(vec![]));
+    // A frame is written out in one go when it's flushed, rather than as stdout's line buffer
+    // fills up, and the terminal shows it all at once where it can.
+    let stdout = BufWriter::with_capacity(OUTPUT_BUFFER_CAPACITY, io::stdout());
+    let backend = ColorDepthBackend::new(
+        HyperlinkBackend::new(
+            FixedSizeBackend::new(
+                SynchronizedBackend::new(
+                    DefaultLibrary::backend(stdout),
+                    terminal_support.synchronized_output,
+                ),
+                fixed_size,
             ),
-        );
-    }
-
-    // Build tasks pane.
-    renderer.build_node(
-        tasks_pane_layout.tasks_node,
-        BoxFrame {
-            label: TASKS_LABEL,
-            border_color: THEME_COLOR_TASKS_BOX_FG,
-            text_color: Color::White,
-        },
-    );
-
-    let mut tab_labels = vec![];
-    for label_index in 0..TASKS_TAB_LABELS.len() {
-        tab_labels.push(format!(
-            "{} ({})",
-            TASKS_TAB_LABELS[label_index], FAKE_TASK_COUNTS[label_index]
-        ));
-    }
-    let tab_label_refs: Vec<_> = tab_labels.iter().map(|label| &**label).collect();
-    renderer.build_node(
-        tasks_pane_layout.tasks_tabs_node,
-        SegmentedControl::new(
-            &tab_label_refs[..],
-            0,
-            THEME_COLOR_TASKS_FILTER_BG,
-            THEME_COLOR_TASKS_FILTER_FG,
-        ),
-    );
-
-    renderer.build_node(
-        tasks_pane_layout.tasks_view_mode_node,
-        SegmentedControl::new(
-            &TASKS_VIEW_MODE_LABELS,
-            0,
-            THEME_COLOR_TASKS_FILTER_BG,
-            THEME_COLOR_TASKS_FILTER_FG,
+            Rc::clone(&hyperlinks),
         ),
+        terminal_support.color_depth,
     );
-    renderer.build_node(
-        tasks_pane_layout.tasks_scrollbar_node,
-        Scrollbar::new(0.0, 1.0, 0.0, 1.0, THEME_COLOR_SCROLLBAR_COLOR),
-    );
-    let tasks_table_widths: Vec<_> = stretch
-        .children(tasks_pane_layout.tasks_table_node)
-        .unwrap()
-        .iter()
-        .map(|&tasks_table_column_node| {
-            Constraint::Length(
-                stretch
-                    .layout(tasks_table_column_node)
-                    .unwrap()
-                    .to_rect()
-                    .width as u16,
-            )
-        })
-        .collect();
-    renderer.build_node(
-        tasks_pane_layout.tasks_table_node,
-        Table::new(vec![
-            create_task_table_row(
-                "285",
-                "connection-handler",
-                TaskStatus::Running,
-                "24.5",
-                "1.41",
-                "0.713",
-                &[
-                    ("remote-address", "127.0.0.1:56723"),
-                    ("request-id", "dbabfa1a-f722-41c0-82dc-a02e88e55d2a"),
-                ],
-            ),
-            create_task_table_row(
-                "286",
-                "connection-handler",
-                TaskStatus::Sleeping,
-                "1.9",
-                "1.14",
-                "0.692",
-                &[
-                    ("remote-address", "127.0.0.1:34135"),
-                    ("request-id", "2087d5f8-7275-4179-a0b4-5ed285b0d988"),
-                ],
-            ),
-            create_task_table_row(
-                "1",
-                "public-accept",
-                TaskStatus::Sleeping,
-                "0.6",
-                "0.13",
-                "0.501",
-                &[("local-address", "127.0.0.1:8080")],
-            ),
-            create_task_table_row(
-                "0",
-                "main",
-                TaskStatus::Sleeping,
-                "0.0",
-                "0.09",
-                "0.106",
-                &[],
-            ),
-        ])
-        .header(
-            Row::new(TASKS_TABLE_COLUMN_LABELS.to_vec()).style(
-                TuiStyle::default()
-                    .add_modifier(Modifier::BOLD)
-                    .fg(THEME_COLOR_TASKS_TABLE_HEADER_FG),
-            ),
-        )
-        .widths(&tasks_table_widths),
-    );
+    let mut terminal = Terminal::new(backend)?;
+    terminal.clear()?;
 
-    renderer.render(frame, &stretch, main_node, Point { x: 0, y: 0 });
-}
+    if operands().first().map(|operand| &operand[..]) == Some(STORYBOOK_SUBCOMMAND) {
+        return run_storybook(&mut terminal);
+    }
 
-struct TitleBarLayout {
-    main_powerline_node: Node,
-    menu_powerline_node: Node,
-}
+    let glyph_set = &terminal_support.glyph_set;
 
-impl TitleBarLayout {
-    fn layout(stretch: &mut Stretch, main_node: Node) -> TitleBarLayout {
-        let title_bar_node = stretch.add_new_child(
-            main_node,
-            Style {
-                size: Size::fixed_height(1),
-                ..Default::default()
-            },
-        );
-        let main_powerline_node = stretch.add_new_child(
-            title_bar_node,
-            Style {
-                size: AUTO_SIZE,
-                flex_grow: 1.0,
-                ..Default::default()
-            },
-        );
-        let menu_powerline_node = stretch.add_new_child(
-            title_bar_node,
-            Style {
-                size: Size::fixed_width(MENU_BUTTON_LABEL.chars().count() as u16 + 3),
-                ..Default::default()
-            },
-        );
+    let screen = flag_value(SCREEN_FLAG_PREFIX).and_then(|name| MockScreen::from_name(&name));
+    let mut app = match screen {
+        Some(screen) => screen.app(strings()),
+        None => App::new(
+            Box::new(SystemClock),
+            dataset().snapshot(),
+            fake_startup_error(),
+        ),
+    };
+    if env::args().any(|arg| arg == DEMO_FLAG) {
+        app.demo = Some(Demo::new(app.state.snapshot.performance.current));
+    }
+    app.link = simulated_link();
+    app.theme_preview_interval = theme_preview_interval();
+    for (metric, threshold) in alert_thresholds() {
+        app.state.set_performance_alert_threshold(metric, threshold);
+    }
+    app.state.request_link_template = flag_value(LINK_TEMPLATE_FLAG_PREFIX);
+    app.state.strings = strings();
+    // Gallery screens stay as they were arranged, and they and the theme preview leave the
+    // target's session alone.
+    let config_dir = match (screen, app.theme_preview_interval) {
+        (None, None) => config::config_dir(),
+        _ => None,
+    };
+    if let Some(ref config_dir) = config_dir {
+        app.load_bookmarks(config_dir);
+        app.load_session(config_dir);
+    }
+    let mut frame_limiter = FrameLimiter::new();
+    let mut drawn_generation = None;
+    loop {
+        if job_control.take_suspend_request() {
+            DefaultLibrary::restore_terminal();
+            job_control.stop()?;
+            job_control.take_continued();
+            resume_terminal(&mut terminal)?;
+            frame_limiter.mark_dirty();
+        }
+        if job_control.take_continued() {
+            resume_terminal(&mut terminal)?;
+            frame_limiter.mark_dirty();
+        }
 
-        TitleBarLayout {
-            main_powerline_node,
-            menu_powerline_node,
+        app.tick();
+        // Frames are only drawn when they'd look different from the last one.
+        let generation = app.generation();
+        if drawn_generation != Some(generation) {
+            frame_limiter.mark_dirty();
+        }
+        if frame_limiter.should_draw(Instant::now()) {
+            terminal.draw(|frame| {
+                app.draw(frame, glyph_set);
+                // The backend writes the links as tui flushes the frame, once this returns.
+                if terminal_support.hyperlinks {
+                    *hyperlinks.borrow_mut() = app.state.hyperlinks.clone();
+                }
+            })?;
+            frame_limiter.did_draw(Instant::now());
+            drawn_generation = Some(generation);
         }
-    }
-}
 
-struct PerformancePaneLayout {
-    performance_node: Node,
-    performance_graphs_container_node: Node,
-    performance_expand_button_node: Node,
-}
+        // The data changes on its own only when the clock ticks, so wake up for the next one.
+        let poll_timeout =
+            frame_limiter.poll_timeout(Instant::now(), Some(app.clock.time_until_next_second()));
+        if !DefaultLibrary::poll_event(poll_timeout)? {
+            continue;
+        }
 
-impl PerformancePaneLayout {
-    fn layout(stretch: &mut Stretch, main_node: Node) -> PerformancePaneLayout {
-        let performance_node = stretch.add_new_child(
-            main_node,
-            Style {
-                size: Size::fixed_height(3),
-                ..Default::default()
-            },
-        );
-        let performance_inner_container_node = stretch.add_new_child(
-            performance_node,
-            Style {
-                size: AUTO_SIZE,
-                padding: Rect::new(1, 1, 1, 1),
-                flex_grow: 1.0,
-                ..Default::default()
+        let event = match fixed_size {
+            None => DefaultLibrary::read_event()?,
+            Some(size) => match fit_event_to_size(DefaultLibrary::read_event()?, size)? {
+                None => continue,
+                Some(event) => event,
             },
-        );
-        let performance_graphs_container_node = stretch.add_new_child(
-            performance_inner_container_node,
-            Style {
-                size: Size::fixed_height(1),
-                flex_grow: 1.0,
-                ..Default::default()
-            },
-        );
-        let performance_expand_button_node = stretch.add_new_child(
-            performance_inner_container_node,
-            Style {
-                size: Size::fixed(2, 1),
-                ..Default::default()
-            },
-        );
-        for &performance_label in &PERFORMANCE_LABELS {
-            let performance_segment_node = stretch.add_new_child(
-                performance_graphs_container_node,
-                Style {
-                    size: Size::fixed_height(1),
-                    padding: Rect::new(0, 1, 0, 1),
-                    flex_grow: 1.0,
-                    ..Default::default()
-                },
-            );
-            let _performance_segment_label_node = stretch.add_new_child(
-                performance_segment_node,
-                Style {
-                    size: Size::fixed(performance_label.chars().count() as u16, 1),
-                    margin: Rect::new(0, 1, 0, 0),
-                    ..Default::default()
-                },
-            );
-            let _performance_segment_time_node = stretch.add_new_child(
-                performance_segment_node,
-                Style {
-                    size: Size::fixed(PERFORMANCE_SEGMENT_VALUE_WIDTH, 1),
-                    margin: Rect::new(0, 1, 0, 0),
-                    ..Default::default()
-                },
-            );
-            let _performance_segment_graph_node = stretch.add_new_child(
-                performance_segment_node,
-                Style {
-                    size: Size::fixed_height(1),
-                    flex_grow: 1.0,
-                    ..Default::default()
-                },
-            );
+        };
+        if let Event::Resize(..) = event {
+            if fixed_size.is_some() {
+                // The screen stays the same size but moves to stay centered.
+                terminal.clear()?;
+            }
+        }
+        if let Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Moved,
+            ..
+        }) = event
+        {
+            continue;
         }
 
-        PerformancePaneLayout {
-            performance_node,
-            performance_graphs_container_node,
-            performance_expand_button_node,
+        match app.handle_event(event) {
+            Some(Command::Quit) => break,
+            Some(Command::Suspend) => job_control.request_suspend(),
+            Some(Command::Copy(text)) => {
+                clipboard::copy(&mut io::stdout(), &text)?;
+                clipboard::copy_locally(&text);
+            }
+            Some(Command::Export(format)) => app.export(format, Path::new(EXPORT_DIR)),
+            Some(Command::SaveBookmarks) => {
+                if let Some(ref config_dir) = config_dir {
+                    app.save_bookmarks(config_dir);
+                }
+            }
+            None => {}
         }
     }
-}
 
-struct TasksPaneLayout {
-    tasks_node: Node,
-    tasks_tabs_node: Node,
-    tasks_view_mode_node: Node,
-    tasks_table_node: Node,
-    tasks_scrollbar_node: Node,
+    // Reported once the terminal is back to normal, where the message stays on screen.
+    let session_result = config_dir
+        .as_ref()
+        .map(|config_dir| app.save_session(config_dir));
+    drop(terminal_guard);
+    if let Some(Err(error)) = session_result {
+        eprintln!("{} {}", SESSION_SAVE_FAILED_LABEL, error);
+    }
+    Ok(())
 }
 
-impl TasksPaneLayout {
-    fn layout(stretch: &mut Stretch, main_node: Node) -> TasksPaneLayout {
-        // Lay out tasks pane.
-        let tasks_node = stretch.add_new_child(
-            main_node,
-            Style {
-                size: AUTO_SIZE,
-                flex_grow: 1.0,
-                flex_direction: FlexDirection::Column,
-                ..Default::default()
-            },
-        );
-        let tasks_inner_container_node = stretch.add_new_child(
-            tasks_node,
-            Style {
-                size: AUTO_SIZE,
-                padding: Rect::new(1, 1, 1, 1),
-                flex_grow: 1.0,
-                flex_direction: FlexDirection::Column,
-                ..Default::default()
-            },
-        );
-        let tasks_tab_strip_node = stretch.add_new_child(
-            tasks_inner_container_node,
-            Style {
-                size: Size::fixed_height(1),
-                ..Default::default()
-            },
-        );
-        let tasks_tabs_node = stretch.add_new_child(
-            tasks_tab_strip_node,
-            Style {
-                size: AUTO_SIZE,
-                margin: Rect::new(0, 0, 0, 1),
-                flex_grow: 1.0,
-                ..Default::default()
-            },
-        );
-        let tasks_view_mode_node = stretch.add_new_child(
-            tasks_tab_strip_node,
-            Style {
-                size: Size::fixed_width(
-                    (TASKS_VIEW_MODE_LABEL_FLAT.chars().count()
-                        + TASKS_VIEW_MODE_LABEL_TREE.chars().count()
-                        + 4) as u16,
-                ),
-                ..Default::default()
-            },
-        );
-        let tasks_table_container_node = stretch.add_new_child(
-            tasks_inner_container_node,
-            Style {
-                size: AUTO_SIZE,
-                margin: Rect::new(0, 0, 0, 1),
-                flex_grow: 1.0,
-                ..Default::default()
-            },
-        );
-        let tasks_table_node = stretch.add_new_child(
-            tasks_table_container_node,
-            Style {
-                size: AUTO_SIZE,
-                flex_grow: 1.0,
-                ..Default::default()
-            },
-        );
-        let tasks_scrollbar_node = stretch.add_new_child(
-            tasks_table_container_node,
-            Style {
-                size: Size::fixed_width(1),
-                ..Default::default()
-            },
-        );
-
-        // Lay out tasks table.
-        for &table_column_width in
-            &TASKS_TABLE_COLUMN_WIDTHS[0..TASKS_TABLE_COLUMN_LABELS.len() - 1]
-        {
-            let _tasks_table_column_node = stretch.add_new_child(
-                tasks_table_node,
-                Style {
-                    size: Size::fixed_width(table_column_width),
-                    ..Default::default()
-                },
-            );
-        }
-        let _tasks_table_last_column_node = stretch.add_new_child(
-            tasks_table_node,
-            Style {
-                size: AUTO_SIZE,
-                flex_grow: 1.0,
-                ..Default::default()
-            },
-        );
-
-        TasksPaneLayout {
-            tasks_node,
-            tasks_view_mode_node,
-            tasks_tabs_node,
-            tasks_table_node,
-            tasks_scrollbar_node,
+// What shells report for a process killed by SIGINT.
+const SIGNAL_EXIT_CODE: i32 = 130;
+// `diff` exits the way diff(1) does: 0 if the screens match, 1 if they don't, 2 on trouble.
+const DIFF_SAME_EXIT_CODE: i32 = 0;
+const DIFF_DIFFERENT_EXIT_CODE: i32 = 1;
+const DIFF_TROUBLE_EXIT_CODE: i32 = 2;
+const DEFAULT_RENDER_SIZE: (u16, u16) = (120, 40);
+const WIDE_SIZE: (u16, u16) = (200, 50);
+const DEFAULT_THEME_PREVIEW_INTERVAL: Duration = Duration::from_secs(5);
+// Exports go in the directory the console was started in.
+static EXPORT_DIR: &'static str = ".";
+
+static DIFF_SUBCOMMAND: &'static str = "diff";
+static DUMP_SUBCOMMAND: &'static str = "dump";
+static SCREENSHOTS_SUBCOMMAND: &'static str = "screenshots";
+static STORYBOOK_SUBCOMMAND: &'static str = "storybook";
+static DIFF_USAGE: &'static str = "usage: tokio-console-mocks diff <old> <new> [--size=WxH]";
+static DUMP_USAGE: &'static str = "usage: tokio-console-mocks dump <dataset> [--size=WxH]";
+static SCREENSHOTS_USAGE: &'static str = "usage: tokio-console-mocks screenshots <dir>";
+static SIZE_FLAG_PREFIX: &'static str = "--size=";
+static SIZE_FLAG_WIDE: &'static str = "wide";
+static ASCII_FLAG: &'static str = "--ascii";
+static DEMO_FLAG: &'static str = "--demo";
+static COLOR_FLAG_PREFIX: &'static str = "--color=";
+static COLOR_FLAG_TRUECOLOR: &'static str = "truecolor";
+static COLOR_FLAG_256: &'static str = "256";
+static COLOR_FLAG_16: &'static str = "16";
+static GLYPHS_FLAG_PREFIX: &'static str = "--glyphs=";
+static GLYPHS_FLAG_NERD_FONT: &'static str = "nerd-font";
+static GLYPHS_FLAG_ASCII: &'static str = "ascii";
+static CHART_FLAG_PREFIX: &'static str = "--chart=";
+static CHART_FLAG_BRAILLE: &'static str = "braille";
+static CHART_FLAG_BLOCKS: &'static str = "blocks";
+static CHART_FLAG_ASCII: &'static str = "ascii";
+static HYPERLINKS_FLAG_PREFIX: &'static str = "--hyperlinks=";
+static HYPERLINKS_FLAG_ON: &'static str = "on";
+static HYPERLINKS_FLAG_OFF: &'static str = "off";
+static SYNCHRONIZED_OUTPUT_FLAG_PREFIX: &'static str = "--synchronized-output=";
+static SYNCHRONIZED_OUTPUT_FLAG_ON: &'static str = "on";
+static SYNCHRONIZED_OUTPUT_FLAG_OFF: &'static str = "off";
+static LINK_TEMPLATE_FLAG_PREFIX: &'static str = "--link-template=";
+static LOCALE_FLAG_PREFIX: &'static str = "--locale=";
+static DATASET_FLAG_PREFIX: &'static str = "--dataset=";
+static ERROR_FLAG_PREFIX: &'static str = "--error=";
+static SCREEN_FLAG_PREFIX: &'static str = "--screen=";
+static ALERT_FLAG_PREFIX: &'static str = "--alert=";
+static LATENCY_FLAG_PREFIX: &'static str = "--latency=";
+static JITTER_FLAG_PREFIX: &'static str = "--jitter=";
+static LOSS_FLAG_PREFIX: &'static str = "--loss=";
+static THEME_PREVIEW_FLAG: &'static str = "--theme-preview";
+static THEME_PREVIEW_FLAG_PREFIX: &'static str = "--theme-preview=";
+static ALERT_FLAG_OFF: &'static str = "off";
+static ERROR_FLAG_CONNECTION_REFUSED: &'static str = "connection-refused";
+static ERROR_FLAG_INVALID_SCENARIO: &'static str = "invalid-scenario";
+
+static SESSION_SAVE_FAILED_LABEL: &'static str = "Couldn't save the session:";
+
+static FAKE_CONNECTION_TARGET: &'static str = "localhost:6669";
+static FAKE_SCENARIO_PATH: &'static str = "scenarios/deadlock.toml";
+static FAKE_SCENARIO_ERROR_REASON: &'static str = "expected a table at line 12, column 1";
+
+// Enough for a full redraw of a big terminal in truecolor.
+const OUTPUT_BUFFER_CAPACITY: usize = 256 * 1024;
+
+type AppBackend = ColorDepthBackend<
+    HyperlinkBackend<
+        FixedSizeBackend<SynchronizedBackend<<DefaultLibrary as TerminalLibrary>::Backend>>,
+    >,
+>;
+
+// Shows the widgets one at a time until quit. Nothing changes unless a key is pressed, so this
+// only draws after events.
+fn run_storybook(terminal: &mut Terminal<AppBackend>) -> crossterm::Result<()> {
+    let mut storybook = Storybook::new();
+    loop {
+        terminal.draw(|frame| storybook.draw(frame, THEMES[0]))?;
+        if let Event::Key(key) = DefaultLibrary::read_event()? {
+            if storybook.handle_key(key) {
+                return Ok(());
+            }
         }
     }
 }
 
-trait StretchExt {
-    fn add_new_child(&mut self, parent: Node, style: Style) -> Node;
-    fn add_single_line_text(&mut self, parent: Node, string: &str) -> Node;
+// Enters the full-screen state again after the process was stopped, and clears tui's copy of
+// the screen so that the next frame is drawn in full over whatever the shell left behind.
+fn resume_terminal(terminal: &mut Terminal<AppBackend>) -> crossterm::Result<()> {
+    DefaultLibrary::enter_full_screen()?;
+    terminal.clear()?;
+    Ok(())
 }
 
-impl StretchExt for Stretch {
-    fn add_new_child(&mut self, parent: Node, style: Style) -> Node {
-        let node = self.new_node(style, vec![]).unwrap();
-        self.add_child(parent, node).unwrap();
-        node
-    }
-
-    fn add_single_line_text(&mut self, parent: Node, string: &str) -> Node {
-        self.add_new_child(
-            parent,
-            Style {
-                size: Size::fixed(string.chars().count() as u16, 1),
-                ..Default::default()
-            },
-        )
-    }
+// Returns the value of the last `--name=value` style flag with the given prefix.
+fn flag_value(prefix: &str) -> Option<String> {
+    env::args()
+        .rev()
+        .find_map(|arg| arg.strip_prefix(prefix).map(|value| value.to_owned()))
 }
 
-#[allow(dead_code)]
-enum TaskStatus {
-    Running,
-    Sleeping,
-    Deadlocked,
+// Returns the values of every `--name=value` style flag with the given prefix, in order.
+fn flag_values(prefix: &str) -> Vec<String> {
+    env::args()
+        .filter_map(|arg| arg.strip_prefix(prefix).map(|value| value.to_owned()))
+        .collect()
 }
 
-fn create_task_table_row<'a>(
-    id: &'a str,
-    name: &'a str,
-    status: TaskStatus,
-    run_percent: &'a str,
-    poll_ms: &'a str,
-    wake_ms: &'a str,
-    attributes: &'a [(&'a str, &'a str)],
-) -> Row<'a> {
-    let open_cell_style = TuiStyle::default().fg(THEME_COLOR_TASKS_TABLE_OPEN_CELL_COLOR);
-    let minor_cell_style = TuiStyle::default().fg(THEME_COLOR_TASKS_TABLE_MINOR_CELL_COLOR);
-    let name_cell_style = TuiStyle::default().fg(THEME_COLOR_TASKS_TABLE_NAME_CELL_COLOR);
-    let numeric_cell_style = TuiStyle::default().fg(THEME_COLOR_TASKS_TABLE_NUMERIC_CELL_COLOR);
-    let key_cell_style = TuiStyle::default().fg(THEME_COLOR_TASKS_TABLE_ATTRIBUTE_KEY_CELL_COLOR);
-    let value_cell_style =
-        TuiStyle::default().fg(THEME_COLOR_TASKS_TABLE_ATTRIBUTE_VALUE_CELL_COLOR);
-
-    let mut attribute_spans = vec![];
-    for (index, &(key, value)) in attributes.iter().enumerate() {
-        if index > 0 {
-            attribute_spans.push(Span::styled(", ", minor_cell_style));
+// Detection can guess wrong, so the color depth and glyph set can be forced with `--color` and
+// `--glyphs`. `--ascii` is shorthand for `--glyphs=ascii`. `--chart` picks the chart symbols on
+// their own, for fonts that have everything but decent braille. `--hyperlinks=on` or `off`
+// decides whether attribute values are drawn as links, and `--synchronized-output=on` or `off`
+// whether frames are drawn as synchronized updates.
+fn terminal_support_with_overrides(mut terminal_support: TerminalSupport) -> TerminalSupport {
+    match flag_value(COLOR_FLAG_PREFIX) {
+        Some(ref depth) if depth == COLOR_FLAG_TRUECOLOR => {
+            terminal_support.color_depth = ColorDepth::TrueColor
         }
-        attribute_spans.push(Span::styled(key, key_cell_style));
-        attribute_spans.push(Span::styled("=", minor_cell_style));
-        attribute_spans.push(Span::styled(value, value_cell_style));
+        Some(ref depth) if depth == COLOR_FLAG_256 => {
+            terminal_support.color_depth = ColorDepth::Ansi256
+        }
+        Some(ref depth) if depth == COLOR_FLAG_16 => {
+            terminal_support.color_depth = ColorDepth::Ansi16
+        }
+        _ => {}
     }
-
-    let (status_label, status_color);
-    match status {
-        TaskStatus::Running => {
-            status_label = TASKS_TABLE_STATUS_RUNNING;
-            status_color = THEME_COLOR_TASKS_TABLE_STATUS_RUNNING_COLOR;
+    match flag_value(GLYPHS_FLAG_PREFIX) {
+        Some(ref glyphs) if glyphs == GLYPHS_FLAG_NERD_FONT => {
+            terminal_support.glyph_set = GLYPH_SET_NERD_FONT
         }
-        TaskStatus::Sleeping => {
-            status_label = TASKS_TABLE_STATUS_SLEEPING;
-            status_color = THEME_COLOR_TASKS_TABLE_STATUS_SLEEPING_COLOR;
+        Some(ref glyphs) if glyphs == GLYPHS_FLAG_ASCII => {
+            terminal_support.glyph_set = GLYPH_SET_ASCII
         }
-        TaskStatus::Deadlocked => {
-            status_label = TASKS_TABLE_STATUS_DEADLOCKED;
-            status_color = THEME_COLOR_TASKS_TABLE_STATUS_DEADLOCKED_COLOR;
+        _ => {}
+    }
+    if env::args().any(|arg| arg == ASCII_FLAG) {
+        terminal_support.glyph_set = GLYPH_SET_ASCII;
+    }
+    match flag_value(CHART_FLAG_PREFIX) {
+        Some(ref chart) if chart == CHART_FLAG_BRAILLE => {
+            terminal_support.glyph_set.chart = CHART_SYMBOLS_BRAILLE
         }
-    };
-    let status_style = TuiStyle::default().fg(status_color);
-
-    Row::new(vec![
-        Cell::from(TASKS_TABLE_BUTTON_OPEN).style(open_cell_style),
-        Cell::from(id),
-        Cell::from(name).style(name_cell_style),
-        Cell::from(status_label).style(status_style),
-        Cell::from(Spans::from(vec![
-            Span::styled(run_percent, numeric_cell_style),
-            Span::styled("%", minor_cell_style),
-        ])),
-        Cell::from(Spans::from(vec![
-            Span::styled(poll_ms, numeric_cell_style),
-            Span::styled("ms", minor_cell_style),
-        ])),
-        Cell::from(Spans::from(vec![
-            Span::styled(wake_ms, numeric_cell_style),
-            Span::styled("ms", minor_cell_style),
-        ])),
-        Cell::from(Spans::from(attribute_spans)),
-    ])
+        Some(ref chart) if chart == CHART_FLAG_BLOCKS => {
+            terminal_support.glyph_set.chart = CHART_SYMBOLS_BLOCKS
+        }
+        Some(ref chart) if chart == CHART_FLAG_ASCII => {
+            terminal_support.glyph_set.chart = CHART_SYMBOLS_ASCII
+        }
+        _ => {}
+    }
+    match flag_value(HYPERLINKS_FLAG_PREFIX) {
+        Some(ref hyperlinks) if hyperlinks == HYPERLINKS_FLAG_ON => {
+            terminal_support.hyperlinks = true
+        }
+        Some(ref hyperlinks) if hyperlinks == HYPERLINKS_FLAG_OFF => {
+            terminal_support.hyperlinks = false
+        }
+        _ => {}
+    }
+    match flag_value(SYNCHRONIZED_OUTPUT_FLAG_PREFIX) {
+        Some(ref synchronized) if synchronized == SYNCHRONIZED_OUTPUT_FLAG_ON => {
+            terminal_support.synchronized_output = true
+        }
+        Some(ref synchronized) if synchronized == SYNCHRONIZED_OUTPUT_FLAG_OFF => {
+            terminal_support.synchronized_output = false
+        }
+        _ => {}
+    }
+    terminal_support
 }
 
-struct Renderer<'a> {
-    stretch_node_to_widget: HashMap<Node, AnyWidget<'a>>,
-    stretch_node_to_bg_color: HashMap<Node, Color>,
+// Picks the example tasks to show with `--dataset=tiny`, `typical`, `pathological`, `deadlock`,
+// or `stress`. A gallery screen picked with `--screen=NAME` shows the typical tasks whatever this
+// says.
+fn dataset() -> Dataset {
+    flag_value(DATASET_FLAG_PREFIX)
+        .and_then(|name| Dataset::from_name(&name))
+        .unwrap_or(Dataset::Typical)
 }
 
-impl<'a> Renderer<'a> {
-    fn new() -> Renderer<'a> {
-        Renderer {
-            stretch_node_to_widget: HashMap::new(),
-            stretch_node_to_bg_color: HashMap::new(),
-        }
-    }
-
-    fn build_node<W>(&mut self, node: Node, widget: W)
-    where
-        W: Into<AnyWidget<'a>>,
-    {
-        self.stretch_node_to_widget.insert(node, widget.into());
+// Picks the labels' language from `--locale=es`, say, or else from the environment's locale.
+fn strings() -> &'static Strings {
+    match flag_value(LOCALE_FLAG_PREFIX).or_else(strings::env_locale) {
+        Some(locale) => strings::catalog(&locale),
+        None => strings::CATALOGS[0],
     }
+}
 
-    fn render(
-        &mut self,
-        frame: &mut AppFrame,
-        stretch: &Stretch,
-        node: Node,
-        world_position: Point<u16>,
-    ) {
-        let local_rect = stretch.layout(node).unwrap().to_rect();
-        let local_style = stretch.style(node).unwrap();
-
-        let mut padding_rect = local_rect.clone();
-        let local_padding = resolve_padding(local_style.padding);
-        padding_rect.x += world_position.x;
-        padding_rect.y += world_position.y;
-
-        if let Some(bg_color) = self.stretch_node_to_bg_color.remove(&node) {
-            let mut row = String::new();
-            for _ in padding_rect.x..padding_rect.right() {
-                row.push(' ');
-            }
-            for y in padding_rect.y..padding_rect.bottom() {
-                frame.render_widget(
-                    Paragraph::new(&row[..]).style(TuiStyle::default().bg(bg_color)),
-                    TuiRect::new(padding_rect.x, y, padding_rect.width, 1),
-                );
+// Sets when measurements raise alerts with `--alert=METRIC=VALUE`, in the metric's own unit,
+// such as `--alert=wake=5` for wake times over 5ms, or turns a metric's alert off with
+// `--alert=METRIC=off`. Flags that don't name a metric or a number are ignored.
+fn alert_thresholds() -> Vec<(usize, Option<AlertThreshold>)> {
+    flag_values(ALERT_FLAG_PREFIX)
+        .iter()
+        .filter_map(|alert| {
+            let mut parts = alert.splitn(2, '=');
+            let metric = performance_metric_by_key(parts.next()?)?;
+            let value = parts.next()?;
+            if value == ALERT_FLAG_OFF {
+                return Some((metric, None));
             }
-        }
+            let raise_above = value.parse::<f32>().ok()?;
+            Some((metric, Some(AlertThreshold::new(raise_above))))
+        })
+        .collect()
+}
 
-        if let Some(widget) = self.stretch_node_to_widget.remove(&node) {
-            // Determine content rect.
-            let mut content_rect = padding_rect.clone();
-            content_rect.x += local_padding.start;
-            content_rect.y += local_padding.top;
-            content_rect.width -= local_padding.start + local_padding.end;
-            content_rect.height -= local_padding.top + local_padding.bottom;
+// Makes updates from the mock target arrive `--latency=MS` late, up to `--jitter=MS` later still,
+// with `--loss=PERCENT` of them lost on the way. There's no simulated link unless one of these is
+// given.
+fn simulated_link() -> Option<SimulatedLink> {
+    let millis = |prefix| flag_value(prefix).and_then(|value| value.parse::<u64>().ok());
+    let latency = millis(LATENCY_FLAG_PREFIX);
+    let jitter = millis(JITTER_FLAG_PREFIX);
+    let loss = flag_value(LOSS_FLAG_PREFIX)
+        .and_then(|value| value.parse::<f32>().ok())
+        .filter(|loss| (0.0..=100.0).contains(loss));
+    if latency.is_none() && jitter.is_none() && loss.is_none() {
+        return None;
+    }
+    Some(SimulatedLink::new(
+        Duration::from_millis(latency.unwrap_or(0)),
+        Duration::from_millis(jitter.unwrap_or(0)),
+        loss.unwrap_or(0.0) / 100.0,
+    ))
+}
 
-            frame.render_widget(widget, content_rect);
-        }
+// Switches to the next theme every `--theme-preview=SECONDS`, or every five seconds with just
+// `--theme-preview`, as well as whenever `t` is pressed. Intervals that aren't a whole number
+// of seconds above zero are taken as the default.
+fn theme_preview_interval() -> Option<Duration> {
+    if let Some(seconds) = flag_value(THEME_PREVIEW_FLAG_PREFIX) {
+        let interval = seconds
+            .parse::<u64>()
+            .ok()
+            .filter(|&seconds| seconds > 0)
+            .map(Duration::from_secs);
+        return Some(interval.unwrap_or(DEFAULT_THEME_PREVIEW_INTERVAL));
+    }
+    if env::args().any(|arg| arg == THEME_PREVIEW_FLAG) {
+        return Some(DEFAULT_THEME_PREVIEW_INTERVAL);
+    }
+    None
+}
 
-        // Recur.
-        if let Ok(kids) = stretch.children(node) {
-            for kid in kids {
-                self.render(
-                    frame,
-                    stretch,
-                    kid,
-                    Point {
-                        x: padding_rect.x,
-                        y: padding_rect.y,
-                    },
-                )
-            }
-        }
+// Stands in for errors from connecting to a real target or loading a scenario file, so the
+// error screen can be shown with `--error=connection-refused` or `--error=invalid-scenario`.
+fn fake_startup_error() -> Option<ConsoleError> {
+    let kind = flag_value(ERROR_FLAG_PREFIX)?;
+    if kind == ERROR_FLAG_CONNECTION_REFUSED {
+        Some(ConsoleError::ConnectionRefused {
+            target: FAKE_CONNECTION_TARGET.to_owned(),
+        })
+    } else if kind == ERROR_FLAG_INVALID_SCENARIO {
+        Some(ConsoleError::InvalidScenario {
+            path: FAKE_SCENARIO_PATH.to_owned(),
+            reason: FAKE_SCENARIO_ERROR_REASON.to_owned(),
+        })
+    } else {
+        None
     }
 }
 
-trait ToRect {
-    fn to_rect(&self) -> TuiRect;
+// The arguments that aren't flags, starting with the subcommand, if any.
+fn operands() -> Vec<String> {
+    env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("--"))
+        .collect()
 }
 
-impl ToRect for Layout {
-    fn to_rect(&self) -> TuiRect {
-        TuiRect {
-            x: self.location.x.round() as u16,
-            y: self.location.y.round() as u16,
-            width: self.size.width.round() as u16,
-            height: self.size.height.round() as u16,
+// Prints a dataset's main screen, rendered headlessly, to save for comparing with `diff` later.
+fn run_dump() -> i32 {
+    let operands = operands();
+    if operands.len() != 2 {
+        eprintln!("{}", DUMP_USAGE);
+        return DIFF_TROUBLE_EXIT_CODE;
+    }
+    match load_screen(&operands[1]) {
+        Ok(screen) => {
+            print!("{}", screen);
+            DIFF_SAME_EXIT_CODE
+        }
+        Err(message) => {
+            eprintln!("{}", message);
+            DIFF_TROUBLE_EXIT_CODE
         }
     }
 }
 
-// Geometry extensions
-
-trait SizeExt {
-    fn fixed(x: u16, y: u16) -> Self;
-    fn fixed_width(x: u16) -> Self;
-    fn fixed_height(y: u16) -> Self;
+// Compares two screens, each either a dataset rendered now or a dump saved earlier, and prints
+// the cells that differ.
+fn run_diff() -> i32 {
+    let operands = operands();
+    if operands.len() != 3 {
+        eprintln!("{}", DIFF_USAGE);
+        return DIFF_TROUBLE_EXIT_CODE;
+    }
+    let (old, new) = match (load_screen(&operands[1]), load_screen(&operands[2])) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(message), _) | (_, Err(message)) => {
+            eprintln!("{}", message);
+            return DIFF_TROUBLE_EXIT_CODE;
+        }
+    };
+    match screen_diff::write_diff(
+        &mut io::stdout(),
+        (&operands[1], &old),
+        (&operands[2], &new),
+    ) {
+        Ok(0) => DIFF_SAME_EXIT_CODE,
+        Ok(_) => DIFF_DIFFERENT_EXIT_CODE,
+        Err(error) => {
+            eprintln!("{}", error);
+            DIFF_TROUBLE_EXIT_CODE
+        }
+    }
 }
 
-impl SizeExt for Size<Dimension> {
-    fn fixed(x: u16, y: u16) -> Self {
-        Size {
-            width: Dimension::Points(x as f32),
-            height: Dimension::Points(y as f32),
-        }
+// Writes pictures of every gallery screen at several sizes and in every theme to a directory,
+// for the documentation. Like dumps, they use Nerd Font glyphs unless `--glyphs` says otherwise,
+// and English unless `--locale` does.
+fn run_screenshots() -> i32 {
+    let operands = operands();
+    if operands.len() != 2 {
+        eprintln!("{}", SCREENSHOTS_USAGE);
+        return DIFF_TROUBLE_EXIT_CODE;
     }
-    fn fixed_width(x: u16) -> Self {
-        Size {
-            width: Dimension::Points(x as f32),
-            height: Dimension::Auto,
+    let terminal_support = terminal_support_with_overrides(TerminalSupport {
+        color_depth: ColorDepth::TrueColor,
+        glyph_set: GLYPH_SET_NERD_FONT,
+        hyperlinks: false,
+        synchronized_output: false,
+    });
+    let strings = match flag_value(LOCALE_FLAG_PREFIX) {
+        Some(locale) => strings::catalog(&locale),
+        None => strings::CATALOGS[0],
+    };
+    let dir = Path::new(&operands[1]);
+    match screenshot::write_gallery(dir, strings, &terminal_support.glyph_set) {
+        Ok(paths) => {
+            for path in paths {
+                println!("{}", path.display());
+            }
+            DIFF_SAME_EXIT_CODE
         }
-    }
-    fn fixed_height(y: u16) -> Self {
-        Size {
-            width: Dimension::Auto,
-            height: Dimension::Points(y as f32),
+        Err(error) => {
+            eprintln!("Couldn't write screenshots to {}: {}", dir.display(), error);
+            DIFF_TROUBLE_EXIT_CODE
         }
     }
 }
 
-trait RectExt {
-    fn new(top: i32, end: i32, bottom: i32, start: i32) -> Self;
+// Renders the named dataset with the time pinned, or reads a dump from the file at that path.
+// Renders use Nerd Font glyphs unless overridden, rather than guessing from the environment, so
+// that dumps made on different machines compare equal.
+fn load_screen(name: &str) -> Result<Screen, String> {
+    if let Some(dataset) = Dataset::from_name(name) {
+        let terminal_support = terminal_support_with_overrides(TerminalSupport {
+            color_depth: ColorDepth::TrueColor,
+            glyph_set: GLYPH_SET_NERD_FONT,
+            hyperlinks: false,
+            synchronized_output: false,
+        });
+        let mut app = App::new(
+            Box::new(fixtures::fixed_clock()),
+            dataset.snapshot(),
+            fake_startup_error(),
+        );
+        let (width, height) = render_size()?;
+        return Ok(screen_diff::render_app(
+            &mut app,
+            &terminal_support.glyph_set,
+            width,
+            height,
+        ));
+    }
+    fs::read_to_string(name)
+        .map(|dump| Screen::parse(&dump))
+        .map_err(|error| format!("Couldn't read {}: {}", name, error))
 }
 
-impl RectExt for Rect<Dimension> {
-    fn new(top: i32, end: i32, bottom: i32, start: i32) -> Self {
-        Rect {
-            start: Dimension::Points(start as f32),
-            end: Dimension::Points(end as f32),
-            top: Dimension::Points(top as f32),
-            bottom: Dimension::Points(bottom as f32),
-        }
-    }
+// The size that headless renders are made at: `--size`'s, or 120x40 without it.
+fn render_size() -> Result<(u16, u16), String> {
+    Ok(fixed_size()?.unwrap_or(DEFAULT_RENDER_SIZE))
 }
 
-fn resolve_padding(padding: Rect<Dimension>) -> Rect<u16> {
-    return Rect {
-        start: resolve_padding_dimension(padding.start),
-        end: resolve_padding_dimension(padding.end),
-        top: resolve_padding_dimension(padding.top),
-        bottom: resolve_padding_dimension(padding.bottom),
+// Parses `--size=WxH`, or `--size=wide` for 200x50. In the terminal, the app is laid out at this
+// size rather than the terminal's, so that mocks are reviewed at the sizes the team targets.
+fn fixed_size() -> Result<Option<(u16, u16)>, String> {
+    let size = match flag_value(SIZE_FLAG_PREFIX) {
+        None => return Ok(None),
+        Some(size) => size,
     };
-
-    fn resolve_padding_dimension(length: Dimension) -> u16 {
-        match length {
-            Dimension::Auto | Dimension::Undefined | Dimension::Percent(_) => 0,
-            Dimension::Points(length) => length as u16,
-        }
+    if size == SIZE_FLAG_WIDE {
+        return Ok(Some(WIDE_SIZE));
+    }
+    let mut dimensions = size
+        .splitn(2, 'x')
+        .map(|dimension| dimension.parse::<u16>());
+    match (dimensions.next(), dimensions.next()) {
+        (Some(Ok(width)), Some(Ok(height))) if width > 0 && height > 0 => Ok(Some((width, height))),
+        _ => Err(format!(
+            "Invalid size `{}`; expected WIDTHxHEIGHT or {}",
+            size, SIZE_FLAG_WIDE
+        )),
     }
 }
+
+// Moves mouse events from where they are in the terminal to where they are on a screen of a fixed
+// size, dropping those outside of it.
+fn fit_event_to_size(event: Event, size: (u16, u16)) -> crossterm::Result<Option<Event>> {
+    let mut mouse_event = match event {
+        Event::Mouse(mouse_event) => mouse_event,
+        event => return Ok(Some(event)),
+    };
+    let terminal_size = DefaultLibrary::size()?;
+    Ok(
+        fixed_size::to_screen(terminal_size, size, (mouse_event.column, mouse_event.row)).map(
+            |(column, row)| {
+                mouse_event.column = column;
+                mouse_event.row = row;
+                Event::Mouse(mouse_event)
+            },
+        ),
+    )
+}
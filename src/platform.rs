@@ -0,0 +1,234 @@
+use crate::backend::ColorDepth;
+use crate::glyphs::{GlyphSet, GLYPH_SET_ASCII, GLYPH_SET_NERD_FONT};
+#[cfg(not(windows))]
+use crossterm::terminal;
+use std::env;
+
+#[cfg_attr(windows, allow(dead_code))]
+static TERM_VAR: &'static str = "TERM";
+#[cfg_attr(windows, allow(dead_code))]
+static COLORTERM_VAR: &'static str = "COLORTERM";
+// Checked in the order the C library resolves them.
+#[cfg_attr(windows, allow(dead_code))]
+static LOCALE_VARS: [&'static str; 3] = ["LC_ALL", "LC_CTYPE", "LANG"];
+#[cfg_attr(windows, allow(dead_code))]
+static TERM_PROGRAM_VAR: &'static str = "TERM_PROGRAM";
+// Terminals that set `TERM_PROGRAM` and open OSC 8 links.
+#[cfg_attr(windows, allow(dead_code))]
+static HYPERLINK_TERM_PROGRAMS: [&'static str; 4] = ["iTerm.app", "WezTerm", "vscode", "ghostty"];
+// Set by GNOME Terminal and the other VTE-based terminals, which open links from 0.50 on.
+#[cfg_attr(windows, allow(dead_code))]
+static VTE_VERSION_VAR: &'static str = "VTE_VERSION";
+#[cfg_attr(windows, allow(dead_code))]
+const VTE_FIRST_HYPERLINK_VERSION: u32 = 5000;
+#[cfg_attr(windows, allow(dead_code))]
+static KITTY_WINDOW_ID_VAR: &'static str = "KITTY_WINDOW_ID";
+// Set by Windows Terminal in every session it starts; absent under the legacy console host.
+#[cfg(windows)]
+static WINDOWS_TERMINAL_SESSION_VAR: &'static str = "WT_SESSION";
+
+/// What the terminal can display, as far as can be told without querying it.
+#[derive(Clone, Copy)]
+pub struct TerminalSupport {
+    pub color_depth: ColorDepth,
+    pub glyph_set: GlyphSet,
+    /// Whether the terminal opens OSC 8 links, rather than printing them or ignoring them.
+    pub hyperlinks: bool,
+    /// Whether frames can be drawn as synchronized updates, which terminals that don't support
+    /// them ignore as long as they understand escape sequences at all.
+    pub synchronized_output: bool,
+}
+
+/// Gets the terminal ready to draw and reports what it supports.
+///
+/// On Windows this turns on virtual terminal processing so escape sequences are interpreted
+/// rather than printed. Where that isn't available, crossterm falls back to the console API.
+#[cfg(windows)]
+pub fn detect_terminal_support() -> TerminalSupport {
+    crossterm::ansi_support::supports_ansi();
+    windows_terminal_support(env::var_os(WINDOWS_TERMINAL_SESSION_VAR).is_some())
+}
+
+/// Reports what the terminal supports, judging by the environment and the terminal's size.
+#[cfg(not(windows))]
+pub fn detect_terminal_support() -> TerminalSupport {
+    let size = terminal::size().unwrap_or((0, 0));
+    unix_terminal_support(|name| env::var(name).ok(), size)
+}
+
+// Windows Terminal handles truecolor, links, and whatever font the user has set up. The legacy
+// console host only has the 16 console colors, and its default fonts have no box-drawing or
+// Nerd Font glyphs. This is kept separate from the detection so it can be tested on any
+// platform.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn windows_terminal_support(is_windows_terminal: bool) -> TerminalSupport {
+    if is_windows_terminal {
+        TerminalSupport {
+            color_depth: ColorDepth::TrueColor,
+            glyph_set: GLYPH_SET_NERD_FONT,
+            hyperlinks: true,
+            synchronized_output: true,
+        }
+    } else {
+        TerminalSupport {
+            color_depth: ColorDepth::Ansi16,
+            glyph_set: GLYPH_SET_ASCII,
+            hyperlinks: false,
+            synchronized_output: false,
+        }
+    }
+}
+
+// Truecolor support is advertised through `COLORTERM` and 256 colors through `TERM`. Whether the
+// font has Nerd Font glyphs can't be detected, so they are assumed unless the terminal is the
+// Linux console or the locale is explicitly something other than UTF-8. Terminals that don't
+// understand links can print their escape sequences as garbage, so links are only drawn for
+// terminals known to open them. A size of zero means the terminal couldn't be queried at all,
+// so nothing beyond the basics is assumed, not even escape sequences beyond those crossterm
+// needs.
+#[cfg_attr(windows, allow(dead_code))]
+fn unix_terminal_support<F>(var: F, (width, height): (u16, u16)) -> TerminalSupport
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let term = var(TERM_VAR).unwrap_or_default();
+    if width == 0 || height == 0 || term == "dumb" {
+        return TerminalSupport {
+            color_depth: ColorDepth::Ansi16,
+            glyph_set: GLYPH_SET_ASCII,
+            hyperlinks: false,
+            synchronized_output: false,
+        };
+    }
+
+    let color_depth = match var(COLORTERM_VAR).as_deref() {
+        Some("truecolor") | Some("24bit") => ColorDepth::TrueColor,
+        _ if term.contains("256color") => ColorDepth::Ansi256,
+        _ => ColorDepth::Ansi16,
+    };
+
+    let locale = LOCALE_VARS
+        .iter()
+        .filter_map(|&name| var(name))
+        .find(|value| !value.is_empty());
+    let non_utf8_locale = match locale {
+        Some(locale) => {
+            let encoding = locale.to_ascii_lowercase().replace('-', "");
+            !encoding.contains("utf8")
+        }
+        None => false,
+    };
+    let glyph_set = if term == "linux" || non_utf8_locale {
+        GLYPH_SET_ASCII
+    } else {
+        GLYPH_SET_NERD_FONT
+    };
+
+    let hyperlinks = var(TERM_PROGRAM_VAR)
+        .is_some_and(|program| HYPERLINK_TERM_PROGRAMS.contains(&&program[..]))
+        || var(VTE_VERSION_VAR)
+            .and_then(|version| version.parse::<u32>().ok())
+            .is_some_and(|version| version >= VTE_FIRST_HYPERLINK_VERSION)
+        || var(KITTY_WINDOW_ID_VAR).is_some();
+
+    TerminalSupport {
+        color_depth,
+        glyph_set,
+        hyperlinks,
+        synchronized_output: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use turbowish_widgets::BorderSet;
+
+    fn env_from<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|&&(var_name, _)| var_name == name)
+                .map(|&(_, value)| value.to_owned())
+        }
+    }
+
+    #[test]
+    fn windows_terminal_keeps_full_support() {
+        let support = windows_terminal_support(true);
+        assert_eq!(support.color_depth, ColorDepth::TrueColor);
+        assert!(support.glyph_set.border_set == BorderSet::Rounded);
+        assert!(support.synchronized_output);
+    }
+
+    #[test]
+    fn legacy_console_falls_back() {
+        let support = windows_terminal_support(false);
+        assert_eq!(support.color_depth, ColorDepth::Ansi16);
+        assert!(support.glyph_set.border_set == BorderSet::Ascii);
+        assert!(!support.synchronized_output);
+    }
+
+    #[test]
+    fn color_depth_follows_colorterm_and_term() {
+        let vars = [("TERM", "xterm-256color"), ("COLORTERM", "truecolor")];
+        let support = unix_terminal_support(env_from(&vars), (80, 24));
+        assert_eq!(support.color_depth, ColorDepth::TrueColor);
+
+        let vars = [("TERM", "xterm-256color")];
+        let support = unix_terminal_support(env_from(&vars), (80, 24));
+        assert_eq!(support.color_depth, ColorDepth::Ansi256);
+
+        let vars = [("TERM", "xterm")];
+        let support = unix_terminal_support(env_from(&vars), (80, 24));
+        assert_eq!(support.color_depth, ColorDepth::Ansi16);
+    }
+
+    #[test]
+    fn glyphs_fall_back_for_linux_console_and_non_utf8_locales() {
+        let vars = [("TERM", "xterm-256color"), ("LANG", "en_US.UTF-8")];
+        let support = unix_terminal_support(env_from(&vars), (80, 24));
+        assert!(support.glyph_set.border_set == BorderSet::Rounded);
+
+        let vars = [("TERM", "linux"), ("LANG", "en_US.UTF-8")];
+        let support = unix_terminal_support(env_from(&vars), (80, 24));
+        assert!(support.glyph_set.border_set == BorderSet::Ascii);
+
+        let vars = [
+            ("TERM", "xterm-256color"),
+            ("LC_ALL", "C"),
+            ("LANG", "en_US.UTF-8"),
+        ];
+        let support = unix_terminal_support(env_from(&vars), (80, 24));
+        assert!(support.glyph_set.border_set == BorderSet::Ascii);
+    }
+
+    #[test]
+    fn hyperlinks_are_only_drawn_for_terminals_known_to_open_them() {
+        let vars = [("TERM", "xterm-256color"), ("TERM_PROGRAM", "iTerm.app")];
+        assert!(unix_terminal_support(env_from(&vars), (80, 24)).hyperlinks);
+
+        let vars = [("TERM", "xterm-256color"), ("VTE_VERSION", "6003")];
+        assert!(unix_terminal_support(env_from(&vars), (80, 24)).hyperlinks);
+
+        let vars = [("TERM", "xterm-256color"), ("VTE_VERSION", "4205")];
+        assert!(!unix_terminal_support(env_from(&vars), (80, 24)).hyperlinks);
+
+        let vars = [
+            ("TERM", "xterm-256color"),
+            ("TERM_PROGRAM", "Apple_Terminal"),
+        ];
+        assert!(!unix_terminal_support(env_from(&vars), (80, 24)).hyperlinks);
+
+        let vars = [("TERM", "xterm-kitty"), ("KITTY_WINDOW_ID", "1")];
+        assert!(!unix_terminal_support(env_from(&vars), (0, 0)).hyperlinks);
+    }
+
+    #[test]
+    fn unknown_size_assumes_the_basics() {
+        let vars = [("TERM", "xterm-256color"), ("COLORTERM", "truecolor")];
+        let support = unix_terminal_support(env_from(&vars), (0, 0));
+        assert_eq!(support.color_depth, ColorDepth::Ansi16);
+        assert!(support.glyph_set.border_set == BorderSet::Ascii);
+        assert!(!support.synchronized_output);
+    }
+}
@@ -0,0 +1,207 @@
+//! Timing of the phases of drawing a frame, so that performance work on the renderer can be
+//! measured.
+//!
+//! Built with the `profiling` feature, each phase is a `tracing` span, for whichever subscriber
+//! is installed to record, and the times of each frame are kept for the readout that F9 shows.
+//! Without it, scopes do nothing and every frame takes no time.
+//!
+//! The readout also shows how much memory is allocated, for catching whatever keeps growing in
+//! a long session. That takes the binary to install `CountingAllocator` as its global
+//! allocator; otherwise nothing is counted.
+
+use std::time::Duration;
+
+#[cfg(feature = "profiling")]
+use std::alloc::{GlobalAlloc, Layout, System};
+#[cfg(feature = "profiling")]
+use std::cell::Cell;
+#[cfg(feature = "profiling")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "profiling")]
+use std::time::Instant;
+
+/// A part of drawing a frame.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Phase {
+    /// Building the stretch tree and computing its layout, when it changed.
+    Layout,
+    /// Running the components' views, which build their widgets.
+    Build,
+    /// Drawing widgets into the frame. This happens during `Build`, as each component finishes.
+    Render,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 3] = [Phase::Layout, Phase::Build, Phase::Render];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Layout => "layout",
+            Phase::Build => "build",
+            Phase::Render => "render",
+        }
+    }
+}
+
+/// How long each phase of a frame took. `Build` leaves out the rendering it did.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct FrameTimes {
+    pub phases: [Duration; 3],
+}
+
+impl FrameTimes {
+    pub fn phase(&self, phase: Phase) -> Duration {
+        self.phases[phase as usize]
+    }
+
+    pub fn total(&self) -> Duration {
+        self.phases.iter().sum()
+    }
+}
+
+/// Times a phase until it's dropped.
+#[cfg(feature = "profiling")]
+pub struct Scope {
+    phase: Phase,
+    start: Instant,
+    _span: tracing::span::EnteredSpan,
+}
+
+impl Scope {
+    /// Stops timing the phase, before the end of the block the scope is in.
+    pub fn end(self) {}
+}
+
+#[cfg(feature = "profiling")]
+impl Drop for Scope {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        PHASE_TIMES.with(|phase_times| {
+            let phase_time = &phase_times[self.phase as usize];
+            phase_time.set(phase_time.get() + elapsed);
+        });
+    }
+}
+
+/// Times a phase until it's dropped.
+#[cfg(not(feature = "profiling"))]
+pub struct Scope;
+
+#[cfg(feature = "profiling")]
+thread_local! {
+    // The time spent in each phase since the last frame was finished.
+    static PHASE_TIMES: [Cell<Duration>; 3] = Default::default();
+}
+
+/// Starts timing `phase`, on this thread, until the returned scope is dropped.
+#[cfg(feature = "profiling")]
+pub fn scope(phase: Phase) -> Scope {
+    Scope {
+        phase,
+        start: Instant::now(),
+        _span: tracing::trace_span!("draw", phase = phase.name()).entered(),
+    }
+}
+
+/// Starts timing `phase`, on this thread, until the returned scope is dropped.
+#[cfg(not(feature = "profiling"))]
+pub fn scope(_: Phase) -> Scope {
+    Scope
+}
+
+/// Takes the times of the scopes dropped on this thread since the last call, as one frame's.
+#[cfg(feature = "profiling")]
+pub fn finish_frame() -> FrameTimes {
+    let mut frame_times = FrameTimes::default();
+    PHASE_TIMES.with(|phase_times| {
+        for (time, phase_time) in frame_times.phases.iter_mut().zip(phase_times.iter()) {
+            *time = phase_time.take();
+        }
+    });
+    let render_time = frame_times.phase(Phase::Render);
+    let build_time = &mut frame_times.phases[Phase::Build as usize];
+    *build_time = build_time.saturating_sub(render_time);
+    frame_times
+}
+
+/// Takes the times of the scopes dropped on this thread since the last call, as one frame's.
+#[cfg(not(feature = "profiling"))]
+pub fn finish_frame() -> FrameTimes {
+    FrameTimes::default()
+}
+
+/// The system allocator, counting the bytes allocated through it for `allocated`.
+#[cfg(feature = "profiling")]
+pub struct CountingAllocator;
+
+#[cfg(feature = "profiling")]
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(feature = "profiling")]
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = unsafe { System.alloc(layout) };
+        if !pointer.is_null() {
+            ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        }
+        pointer
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let pointer = unsafe { System.alloc_zeroed(layout) };
+        if !pointer.is_null() {
+            ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(pointer, layout) };
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_pointer = unsafe { System.realloc(pointer, layout, new_size) };
+        if !new_pointer.is_null() {
+            ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+            ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+        }
+        new_pointer
+    }
+}
+
+/// How many bytes are allocated through `CountingAllocator` right now, or 0 if it isn't the
+/// global allocator.
+#[cfg(feature = "profiling")]
+pub fn allocated() -> usize {
+    ALLOCATED.load(Ordering::Relaxed)
+}
+
+/// How many bytes are allocated through `CountingAllocator` right now, or 0 if it isn't the
+/// global allocator.
+#[cfg(not(feature = "profiling"))]
+pub fn allocated() -> usize {
+    0
+}
+
+#[cfg(all(test, feature = "profiling"))]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn build_times_leave_out_the_rendering_they_did() {
+        finish_frame();
+        {
+            let _build_scope = scope(Phase::Build);
+            thread::sleep(Duration::from_millis(2));
+            let _render_scope = scope(Phase::Render);
+            thread::sleep(Duration::from_millis(4));
+        }
+        let frame_times = finish_frame();
+        assert_eq!(frame_times.phase(Phase::Layout), Duration::from_secs(0));
+        assert!(frame_times.phase(Phase::Build) >= Duration::from_millis(2));
+        assert!(frame_times.phase(Phase::Render) >= Duration::from_millis(4));
+        assert_eq!(finish_frame(), FrameTimes::default());
+    }
+}
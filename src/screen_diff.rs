@@ -0,0 +1,298 @@
+//! Cell-by-cell comparison of two rendered screens, for reviewing what a UI change altered.
+//!
+//! Screens come either from rendering the app headlessly or from a dump saved earlier. A dump is
+//! the screen's text, one row per line, in the same form as the snapshot files, so dumps saved
+//! before and after a change can be compared with `tokio-console-mocks diff`.
+
+use crate::app::App;
+use crate::glyphs::GlyphSet;
+use crossterm::queue;
+use crossterm::style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor};
+use std::fmt::{self, Display, Formatter};
+use std::io::Write;
+use tui::backend::TestBackend;
+use tui::buffer::Buffer;
+use tui::Terminal;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+static OLD_HEADER_PREFIX: &'static str = "---";
+static NEW_HEADER_PREFIX: &'static str = "+++";
+static OLD_ROW_PREFIX: &'static str = "-";
+static NEW_ROW_PREFIX: &'static str = "+";
+static MARKER_ROW_PREFIX: &'static str = " ";
+static CHANGED_CELL_MARKER: &'static str = "^";
+
+static THEME_COLOR_DIFF_HEADER: Color = Color::Cyan;
+static THEME_COLOR_DIFF_OLD_CELL_BG: Color = Color::DarkRed;
+static THEME_COLOR_DIFF_NEW_CELL_BG: Color = Color::DarkGreen;
+static THEME_COLOR_DIFF_CELL_FG: Color = Color::White;
+
+/// The text of a screen, one string per cell.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Screen {
+    rows: Vec<Vec<String>>,
+}
+
+impl Screen {
+    pub fn from_buffer(buffer: &Buffer) -> Screen {
+        let rows = (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer.get(x, y).symbol.clone())
+                    .collect()
+            })
+            .collect();
+        Screen { rows }
+    }
+
+    /// Reads a dump back in. Zero-width characters, such as combining accents, join the cell
+    /// before them, as they do when tui draws them.
+    pub fn parse(dump: &str) -> Screen {
+        let rows = dump
+            .lines()
+            .map(|line| {
+                let mut cells: Vec<String> = vec![];
+                for character in line.chars() {
+                    match cells.last_mut() {
+                        Some(cell) if character.width().unwrap_or(0) == 0 => cell.push(character),
+                        _ => cells.push(character.to_string()),
+                    }
+                }
+                cells
+            })
+            .collect();
+        Screen { rows }
+    }
+
+    pub fn width(&self) -> usize {
+        self.rows.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn cell(&self, x: usize, y: usize) -> Option<&str> {
+        self.rows.get(y)?.get(x).map(|cell| &cell[..])
+    }
+}
+
+/// Writes the screen as a dump.
+impl Display for Screen {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        for row in &self.rows {
+            for cell in row {
+                formatter.write_str(cell)?;
+            }
+            formatter.write_str("\n")?;
+        }
+        Ok(())
+    }
+}
+
+/// A row that differs between two screens.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RowDiff {
+    pub y: usize,
+    /// Whether each cell changed, over the wider of the two rows.
+    pub changed_cells: Vec<bool>,
+}
+
+impl RowDiff {
+    pub fn changed_cell_count(&self) -> usize {
+        self.changed_cells
+            .iter()
+            .filter(|&&changed| changed)
+            .count()
+    }
+}
+
+/// Compares two screens cell by cell. Cells that exist in only one screen, because the screens
+/// are different sizes, count as changed.
+pub fn diff_screens(old: &Screen, new: &Screen) -> Vec<RowDiff> {
+    let width = old.width().max(new.width());
+    (0..old.height().max(new.height()))
+        .filter_map(|y| {
+            let changed_cells: Vec<bool> = (0..width)
+                .map(|x| old.cell(x, y) != new.cell(x, y))
+                .collect();
+            if changed_cells.contains(&true) {
+                Some(RowDiff { y, changed_cells })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Draws the app into an off-screen buffer of the given size.
+pub fn render_app(app: &mut App, glyph_set: &GlyphSet, width: u16, height: u16) -> Screen {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|frame| app.draw(frame, glyph_set)).unwrap();
+    Screen::from_buffer(terminal.backend().buffer())
+}
+
+/// Prints each changed row twice, old then new, with the changed cells highlighted, followed by
+/// a line of markers under the changed cells for when the colors don't survive. Returns the
+/// number of changed cells.
+pub fn write_diff<W>(
+    writer: &mut W,
+    (old_name, old): (&str, &Screen),
+    (new_name, new): (&str, &Screen),
+) -> crossterm::Result<usize>
+where
+    W: Write,
+{
+    write_header(writer, OLD_HEADER_PREFIX, old_name, old)?;
+    write_header(writer, NEW_HEADER_PREFIX, new_name, new)?;
+
+    let row_diffs = diff_screens(old, new);
+    for row_diff in &row_diffs {
+        queue!(
+            writer,
+            SetForegroundColor(THEME_COLOR_DIFF_HEADER),
+            Print(format!("@@ row {} @@\n", row_diff.y)),
+            ResetColor
+        )?;
+        write_row(
+            writer,
+            OLD_ROW_PREFIX,
+            old,
+            row_diff,
+            THEME_COLOR_DIFF_OLD_CELL_BG,
+        )?;
+        write_row(
+            writer,
+            NEW_ROW_PREFIX,
+            new,
+            row_diff,
+            THEME_COLOR_DIFF_NEW_CELL_BG,
+        )?;
+
+        queue!(writer, Print(MARKER_ROW_PREFIX))?;
+        for (x, &changed) in row_diff.changed_cells.iter().enumerate() {
+            let marker = if changed { CHANGED_CELL_MARKER } else { " " };
+            queue!(
+                writer,
+                Print(marker.repeat(marker_width(old, new, x, row_diff.y)))
+            )?;
+        }
+        queue!(writer, Print("\n"))?;
+    }
+
+    let changed_cell_count = row_diffs.iter().map(RowDiff::changed_cell_count).sum();
+    queue!(
+        writer,
+        Print(format!(
+            "{} cells differ in {} rows\n",
+            changed_cell_count,
+            row_diffs.len()
+        ))
+    )?;
+    writer.flush()?;
+    Ok(changed_cell_count)
+}
+
+fn write_header<W>(
+    writer: &mut W,
+    prefix: &str,
+    name: &str,
+    screen: &Screen,
+) -> crossterm::Result<()>
+where
+    W: Write,
+{
+    queue!(
+        writer,
+        SetForegroundColor(THEME_COLOR_DIFF_HEADER),
+        Print(format!(
+            "{} {} ({}x{})\n",
+            prefix,
+            name,
+            screen.width(),
+            screen.height()
+        )),
+        ResetColor
+    )
+}
+
+fn write_row<W>(
+    writer: &mut W,
+    prefix: &str,
+    screen: &Screen,
+    row_diff: &RowDiff,
+    changed_cell_bg: Color,
+) -> crossterm::Result<()>
+where
+    W: Write,
+{
+    queue!(writer, Print(prefix))?;
+    for (x, &changed) in row_diff.changed_cells.iter().enumerate() {
+        // Cells past the end of this screen are shown as blanks so that the rows line up.
+        let cell = screen.cell(x, row_diff.y).unwrap_or(" ");
+        if changed {
+            queue!(
+                writer,
+                SetBackgroundColor(changed_cell_bg),
+                SetForegroundColor(THEME_COLOR_DIFF_CELL_FG),
+                Print(cell),
+                ResetColor
+            )?;
+        } else {
+            queue!(writer, Print(cell))?;
+        }
+    }
+    queue!(writer, Print("\n"))
+}
+
+// Double-width characters take two columns, so their markers do too. The old and new rows are
+// printed one above the other, so the wider of the two cells sets the width.
+fn marker_width(old: &Screen, new: &Screen, x: usize, y: usize) -> usize {
+    let cell_width = |screen: &Screen| screen.cell(x, y).map_or(1, |cell| cell.width().max(1));
+    cell_width(old).max(cell_width(new))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tui::layout::Rect;
+    use tui::style::Style;
+
+    #[test]
+    fn dumps_parse_back_into_the_same_cells() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 8, 2));
+        buffer.set_string(0, 0, "漢字 ok", Style::default());
+        buffer.set_string(0, 1, "cafe\u{301}", Style::default());
+        let screen = Screen::from_buffer(&buffer);
+        assert_eq!(Screen::parse(&screen.to_string()), screen);
+    }
+
+    #[test]
+    fn diff_marks_only_the_changed_cells() {
+        let old = Screen::parse("abc\ndef\nghi\n");
+        let new = Screen::parse("abc\ndXf\nghi\njk\n");
+        let row_diffs = diff_screens(&old, &new);
+        assert_eq!(
+            row_diffs,
+            vec![
+                RowDiff {
+                    y: 1,
+                    changed_cells: vec![false, true, false],
+                },
+                RowDiff {
+                    y: 3,
+                    changed_cells: vec![true, true, false],
+                },
+            ]
+        );
+        assert!(diff_screens(&old, &old).is_empty());
+
+        let mut output = vec![];
+        let changed_cell_count = write_diff(&mut output, ("old", &old), ("new", &new)).unwrap();
+        assert_eq!(changed_cell_count, 3);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("@@ row 1 @@"));
+        assert!(output.contains("\n  ^ \n"));
+        assert!(output.ends_with("3 cells differ in 2 rows\n"));
+    }
+}
@@ -0,0 +1,379 @@
+//! Pictures of rendered screens, for the documentation and the README.
+//!
+//! Screens are rendered headlessly and written out as HTML, a `<pre>` of colored spans, or as
+//! SVG, a rectangle per run of background color under a line of text per run of style. Either
+//! way each run is laid out on the cell grid, so the columns line up whatever the font.
+//! Colors the theme leaves to the terminal are taken from the Campbell scheme, like the other
+//! named colors.
+
+use crate::backend;
+use crate::gallery::MockScreen;
+use crate::glyphs::GlyphSet;
+use crate::strings::Strings;
+use crate::theme::THEMES;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tui::backend::TestBackend;
+use tui::buffer::Buffer;
+use tui::style::{Color, Modifier};
+use tui::Terminal;
+use unicode_width::UnicodeWidthStr;
+
+/// The sizes every gallery screen is pictured at: a default terminal, the size the mockups were
+/// designed at, and a roomy one.
+pub static SCREENSHOT_SIZES: [(u16, u16); 3] = [(80, 24), (120, 40), (160, 50)];
+
+static FONT_FAMILY: &'static str =
+    "'JetBrainsMono Nerd Font', 'DejaVu Sans Mono', Menlo, Consolas, monospace";
+const FONT_SIZE_PX: u32 = 15;
+// How big a cell is in SVG pictures, for `FONT_SIZE_PX`.
+const CELL_WIDTH_PX: u32 = 9;
+const CELL_HEIGHT_PX: u32 = 18;
+// How far below the top of its cell the text sits.
+const BASELINE_PX: u32 = 14;
+const HTML_PADDING_PX: u32 = 8;
+const DIM_OPACITY: f32 = 0.5;
+/// Black and gray, as in the Campbell scheme.
+const DEFAULT_BG: (u8, u8, u8) = (0x0c, 0x0c, 0x0c);
+const DEFAULT_FG: (u8, u8, u8) = (0xcc, 0xcc, 0xcc);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ScreenshotFormat {
+    Html,
+    Svg,
+}
+
+impl ScreenshotFormat {
+    pub const ALL: [ScreenshotFormat; 2] = [ScreenshotFormat::Html, ScreenshotFormat::Svg];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ScreenshotFormat::Html => "html",
+            ScreenshotFormat::Svg => "svg",
+        }
+    }
+}
+
+/// Cells in a row that are drawn alike.
+#[derive(Clone, PartialEq, Debug)]
+struct Run {
+    x: u16,
+    /// In cells, which is more than the text's length when it has wide characters.
+    width: u16,
+    text: String,
+    fg: (u8, u8, u8),
+    bg: (u8, u8, u8),
+    modifier: Modifier,
+}
+
+/// Renders every gallery screen at every size in `SCREENSHOT_SIZES`, in every theme, and
+/// writes each in every format to `dir`, which is created if need be. Files are named for the
+/// screen, the theme, and the size, such as `dashboard-basic-120x40.svg`. Returns their paths.
+pub fn write_gallery(
+    dir: &Path,
+    strings: &'static Strings,
+    glyph_set: &GlyphSet,
+) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+    let mut paths = vec![];
+    for &screen in &MockScreen::ALL {
+        for &(width, height) in &SCREENSHOT_SIZES {
+            for &theme in &THEMES {
+                let mut app = screen.app(strings);
+                app.state.theme = theme;
+                let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+                terminal.draw(|frame| app.draw(frame, glyph_set))?;
+                let name = format!("{}-{}-{}x{}", screen.name(), theme.name, width, height);
+                for &format in &ScreenshotFormat::ALL {
+                    let path = dir.join(format!("{}.{}", name, format.extension()));
+                    let mut out = BufWriter::new(File::create(&path)?);
+                    write(&mut out, format, terminal.backend().buffer(), &name)?;
+                    out.flush()?;
+                    paths.push(path);
+                }
+            }
+        }
+    }
+    Ok(paths)
+}
+
+/// Writes a picture of `buffer` to `out`. HTML pictures are titled `title`.
+pub fn write<W>(
+    out: &mut W,
+    format: ScreenshotFormat,
+    buffer: &Buffer,
+    title: &str,
+) -> io::Result<()>
+where
+    W: Write,
+{
+    match format {
+        ScreenshotFormat::Html => write_html(out, buffer, title),
+        ScreenshotFormat::Svg => write_svg(out, buffer),
+    }
+}
+
+fn write_html<W>(out: &mut W, buffer: &Buffer, title: &str) -> io::Result<()>
+where
+    W: Write,
+{
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html>")?;
+    writeln!(out, "<head>")?;
+    writeln!(out, "<meta charset=\"utf-8\">")?;
+    writeln!(out, "<title>{}</title>", escape(title))?;
+    writeln!(out, "</head>")?;
+    writeln!(
+        out,
+        "<body style=\"margin: 0; background: {}\">",
+        hex(DEFAULT_BG)
+    )?;
+    write!(
+        out,
+        "<pre style=\"margin: 0; padding: {}px; font-family: {}; font-size: {}px; \
+         line-height: {}px; color: {}\">",
+        HTML_PADDING_PX,
+        FONT_FAMILY,
+        FONT_SIZE_PX,
+        CELL_HEIGHT_PX,
+        hex(DEFAULT_FG)
+    )?;
+    for y in 0..buffer.area.height {
+        for run in row_runs(buffer, y) {
+            let mut style = vec![];
+            if run.fg != DEFAULT_FG {
+                style.push(format!("color: {}", hex(run.fg)));
+            }
+            if run.bg != DEFAULT_BG {
+                style.push(format!("background: {}", hex(run.bg)));
+            }
+            style.extend(modifier_styles(run.modifier));
+            if style.is_empty() {
+                write!(out, "{}", escape(&run.text))?;
+            } else {
+                write!(
+                    out,
+                    "<span style=\"{}\">{}</span>",
+                    style.join("; "),
+                    escape(&run.text)
+                )?;
+            }
+        }
+        writeln!(out)?;
+    }
+    writeln!(out, "</pre>")?;
+    writeln!(out, "</body>")?;
+    writeln!(out, "</html>")
+}
+
+fn write_svg<W>(out: &mut W, buffer: &Buffer) -> io::Result<()>
+where
+    W: Write,
+{
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+         font-family=\"{}\" font-size=\"{}\">",
+        buffer.area.width as u32 * CELL_WIDTH_PX,
+        buffer.area.height as u32 * CELL_HEIGHT_PX,
+        FONT_FAMILY,
+        FONT_SIZE_PX
+    )?;
+    writeln!(
+        out,
+        "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+        hex(DEFAULT_BG)
+    )?;
+    for y in 0..buffer.area.height {
+        let runs = row_runs(buffer, y);
+        // Backgrounds go first, so that they're under the text of the runs next to them.
+        for run in runs.iter().filter(|run| run.bg != DEFAULT_BG) {
+            writeln!(
+                out,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                run.x as u32 * CELL_WIDTH_PX,
+                y as u32 * CELL_HEIGHT_PX,
+                run.width as u32 * CELL_WIDTH_PX,
+                CELL_HEIGHT_PX,
+                hex(run.bg)
+            )?;
+        }
+        for run in runs.iter().filter(|run| !run.text.trim().is_empty()) {
+            let style = modifier_styles(run.modifier);
+            let style = if style.is_empty() {
+                String::new()
+            } else {
+                format!(" style=\"{}\"", style.join("; "))
+            };
+            writeln!(
+                out,
+                "<text x=\"{}\" y=\"{}\" textLength=\"{}\" lengthAdjust=\"spacingAndGlyphs\" \
+                 xml:space=\"preserve\" fill=\"{}\"{}>{}</text>",
+                run.x as u32 * CELL_WIDTH_PX,
+                y as u32 * CELL_HEIGHT_PX + BASELINE_PX,
+                run.width as u32 * CELL_WIDTH_PX,
+                hex(run.fg),
+                style,
+                escape(&run.text)
+            )?;
+        }
+    }
+    writeln!(out, "</svg>")
+}
+
+// Splits a row into runs of cells with the same colors and modifiers. Reversed cells have their
+// colors swapped here, so the reverse modifier is dropped. The cells after a wide character are
+// hidden by it, and left out.
+fn row_runs(buffer: &Buffer, y: u16) -> Vec<Run> {
+    let mut runs: Vec<Run> = vec![];
+    let mut x = 0;
+    while x < buffer.area.width {
+        let cell = buffer.get(x, y);
+        let width = (cell.symbol.width() as u16).max(1);
+        let mut fg = rgb(cell.fg, DEFAULT_FG);
+        let mut bg = rgb(cell.bg, DEFAULT_BG);
+        if cell.modifier.contains(Modifier::REVERSED) {
+            std::mem::swap(&mut fg, &mut bg);
+        }
+        let modifier = cell.modifier - Modifier::REVERSED;
+        match runs.last_mut() {
+            Some(run) if (run.fg, run.bg, run.modifier) == (fg, bg, modifier) => {
+                run.width += width;
+                run.text.push_str(&cell.symbol);
+            }
+            _ => runs.push(Run {
+                x,
+                width,
+                text: cell.symbol.clone(),
+                fg,
+                bg,
+                modifier,
+            }),
+        }
+        x += width;
+    }
+    runs
+}
+
+fn rgb(color: Color, default: (u8, u8, u8)) -> (u8, u8, u8) {
+    backend::color_rgb(color).unwrap_or(default)
+}
+
+// The CSS for the modifiers both formats can show.
+fn modifier_styles(modifier: Modifier) -> Vec<String> {
+    let mut styles = vec![];
+    if modifier.contains(Modifier::BOLD) {
+        styles.push("font-weight: bold".to_owned());
+    }
+    if modifier.contains(Modifier::ITALIC) {
+        styles.push("font-style: italic".to_owned());
+    }
+    if modifier.contains(Modifier::UNDERLINED) {
+        styles.push("text-decoration: underline".to_owned());
+    }
+    if modifier.contains(Modifier::DIM) {
+        styles.push(format!("opacity: {}", DIM_OPACITY));
+    }
+    styles
+}
+
+fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glyphs::GLYPH_SET_ASCII;
+    use crate::strings::STRINGS_ENGLISH;
+    use std::env;
+    use std::process;
+    use tui::style::Style;
+    use tui::text::{Span, Spans};
+    use tui::widgets::Paragraph;
+
+    fn picture(format: ScreenshotFormat) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(12, 2)).unwrap();
+        terminal
+            .draw(|frame| {
+                let style = Style::default()
+                    .fg(Color::Rgb(0x88, 0xc0, 0xd0))
+                    .bg(Color::Red)
+                    .add_modifier(Modifier::BOLD);
+                let text = Spans::from(vec![Span::raw("a<b "), Span::styled("&c", style)]);
+                frame.render_widget(Paragraph::new(text), frame.size());
+            })
+            .unwrap();
+        let mut out = vec![];
+        write(&mut out, format, terminal.backend().buffer(), "test").unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn html_pictures_have_a_span_per_styled_run() {
+        let html = picture(ScreenshotFormat::Html);
+        assert!(html.contains("<title>test</title>"), "{}", html);
+        assert!(
+            html.contains(
+                "a&lt;b <span style=\"color: #88c0d0; background: #c50f1f; \
+                 font-weight: bold\">&amp;c</span>      \n"
+            ),
+            "{}",
+            html
+        );
+    }
+
+    #[test]
+    fn svg_pictures_lay_runs_out_on_the_cell_grid() {
+        let svg = picture(ScreenshotFormat::Svg);
+        assert!(svg.contains("width=\"108\" height=\"36\""), "{}", svg);
+        assert!(
+            svg.contains("<rect x=\"36\" y=\"0\" width=\"18\" height=\"18\" fill=\"#c50f1f\"/>"),
+            "{}",
+            svg
+        );
+        assert!(svg.contains(">a&lt;b </text>"), "{}", svg);
+        assert!(
+            svg.contains(
+                "<text x=\"36\" y=\"14\" textLength=\"18\" lengthAdjust=\"spacingAndGlyphs\" \
+                 xml:space=\"preserve\" fill=\"#88c0d0\" style=\"font-weight: bold\">&amp;c</text>"
+            ),
+            "{}",
+            svg
+        );
+        // The second row is blank, so it has no text.
+        assert_eq!(svg.matches("<text").count(), 2);
+    }
+
+    #[test]
+    fn the_gallery_is_pictured_at_every_size_in_every_theme() {
+        let dir =
+            env::temp_dir().join(format!("tokio-console-mocks-screenshots-{}", process::id()));
+        let paths = write_gallery(&dir, &STRINGS_ENGLISH, &GLYPH_SET_ASCII).unwrap();
+        assert_eq!(
+            paths.len(),
+            MockScreen::ALL.len()
+                * SCREENSHOT_SIZES.len()
+                * THEMES.len()
+                * ScreenshotFormat::ALL.len()
+        );
+        let dashboard = fs::read_to_string(dir.join("dashboard-basic-120x40.html")).unwrap();
+        assert!(dashboard.contains("connection-handler"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
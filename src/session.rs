@@ -0,0 +1,220 @@
+//! Putting the console back the way the user left it: the theme, the tasks table's tab, sort,
+//! and columns, and how the panes were laid out.
+//!
+//! Sessions are kept as `key=value` lines. Lines that don't make sense, such as ones naming a
+//! column that's since been removed, are skipped, so that an old session file still restores
+//! whatever it can.
+
+use crate::state::{AppState, TaskSortKey, TasksSort, TasksTab, TimeWindow, ViewMode};
+use crate::theme::THEMES;
+use crate::{PERFORMANCE_METRICS, TASKS_TABLE_COLUMNS, TASKS_TABLE_FROZEN_COLUMN_COUNT};
+use std::fmt::Write;
+
+static THEME_KEY: &'static str = "theme";
+static TASKS_TAB_KEY: &'static str = "tasks_tab";
+static VIEW_MODE_KEY: &'static str = "view_mode";
+static SORT_KEY: &'static str = "sort";
+static GROUPED_KEY: &'static str = "grouped";
+static FOLLOW_KEY: &'static str = "follow";
+static COLUMNS_KEY: &'static str = "columns";
+static PERFORMANCE_EXPANDED_KEY: &'static str = "performance_expanded";
+static METRICS_KEY: &'static str = "metrics";
+static TIME_WINDOW_KEY: &'static str = "time_window";
+static SAMPLES_PER_BAR_KEY: &'static str = "samples_per_bar";
+static NOTIFICATIONS_OPEN_KEY: &'static str = "notifications_open";
+static DESCENDING_SUFFIX: &'static str = " descending";
+
+/// The parts of `state` a session keeps, one `key=value` line each.
+pub fn write(state: &AppState) -> String {
+    let sort = match state.tasks_sort {
+        Some(sort) => {
+            let key = TASKS_TABLE_COLUMNS
+                .iter()
+                .find(|table_column| table_column.sort_key == Some(sort.key))
+                .map_or("", |table_column| table_column.key);
+            let suffix = if sort.descending {
+                DESCENDING_SUFFIX
+            } else {
+                ""
+            };
+            format!("{}{}", key, suffix)
+        }
+        None => String::new(),
+    };
+    let columns: Vec<_> = TASKS_TABLE_COLUMNS
+        .iter()
+        .zip(&state.tasks_table_columns_shown)
+        .filter(|&(_, &shown)| shown)
+        .map(|(table_column, _)| table_column.key)
+        .collect();
+    let metrics: Vec<_> = PERFORMANCE_METRICS
+        .iter()
+        .zip(&state.performance_metrics_shown)
+        .filter(|&(_, &shown)| shown)
+        .map(|(metric, _)| metric.key)
+        .collect();
+    let time_window = match state.performance_history_view.time_window {
+        Some(time_window) => time_window.index().to_string(),
+        None => String::new(),
+    };
+
+    let mut session = String::new();
+    let mut line = |key: &str, value: &dyn std::fmt::Display| {
+        writeln!(session, "{}={}", key, value).unwrap();
+    };
+    line(THEME_KEY, &state.theme.name);
+    line(TASKS_TAB_KEY, &state.tasks_tab.index());
+    line(VIEW_MODE_KEY, &state.view_mode.index());
+    line(SORT_KEY, &sort);
+    line(GROUPED_KEY, &state.group_tasks_by_name);
+    line(FOLLOW_KEY, &state.follow_tasks);
+    line(COLUMNS_KEY, &columns.join(","));
+    line(PERFORMANCE_EXPANDED_KEY, &state.performance_expanded);
+    line(METRICS_KEY, &metrics.join(","));
+    line(TIME_WINDOW_KEY, &time_window);
+    line(
+        SAMPLES_PER_BAR_KEY,
+        &state.performance_history_view.samples_per_bar,
+    );
+    line(NOTIFICATIONS_OPEN_KEY, &state.notifications_open);
+    session
+}
+
+/// Restores what `session`, as `write` wrote it, kept of a state.
+pub fn apply(state: &mut AppState, session: &str) {
+    for line in session.lines() {
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => continue,
+        };
+        if key == THEME_KEY {
+            if let Some(theme) = THEMES.iter().find(|theme| theme.name == value) {
+                state.theme = theme;
+            }
+        } else if key == TASKS_TAB_KEY {
+            if let Some(tasks_tab) = value.parse().ok().and_then(TasksTab::from_index) {
+                state.select_tasks_tab(tasks_tab);
+            }
+        } else if key == VIEW_MODE_KEY {
+            if let Some(view_mode) = value.parse().ok().and_then(ViewMode::from_index) {
+                state.view_mode = view_mode;
+            }
+        } else if key == SORT_KEY {
+            state.tasks_sort = parse_sort(value);
+        } else if key == GROUPED_KEY {
+            if let Ok(grouped) = value.parse() {
+                state.group_tasks_by_name = grouped;
+            }
+        } else if key == FOLLOW_KEY {
+            if let Ok(follow) = value.parse() {
+                state.follow_tasks = follow;
+            }
+        } else if key == COLUMNS_KEY {
+            let keys: Vec<_> = value.split(',').collect();
+            for (table_column, shown) in state.tasks_table_columns_shown.iter_mut().enumerate() {
+                // The frozen columns can't be hidden.
+                if table_column >= TASKS_TABLE_FROZEN_COLUMN_COUNT {
+                    *shown = keys.contains(&TASKS_TABLE_COLUMNS[table_column].key);
+                }
+            }
+        } else if key == PERFORMANCE_EXPANDED_KEY {
+            if let Ok(expanded) = value.parse() {
+                state.performance_expanded = expanded;
+            }
+        } else if key == METRICS_KEY {
+            let keys: Vec<_> = value.split(',').collect();
+            // The pane always shows at least one measurement.
+            if PERFORMANCE_METRICS
+                .iter()
+                .any(|metric| keys.contains(&metric.key))
+            {
+                for (metric, shown) in state.performance_metrics_shown.iter_mut().enumerate() {
+                    *shown = keys.contains(&PERFORMANCE_METRICS[metric].key);
+                }
+            }
+        } else if key == TIME_WINDOW_KEY {
+            state.performance_history_view.time_window =
+                value.parse().ok().and_then(TimeWindow::from_index);
+        } else if key == SAMPLES_PER_BAR_KEY {
+            if let Some(samples_per_bar) = value.parse().ok().filter(|&samples| samples > 0) {
+                state.performance_history_view.samples_per_bar = samples_per_bar;
+            }
+        } else if key == NOTIFICATIONS_OPEN_KEY {
+            if let Ok(open) = value.parse() {
+                state.notifications_open = open;
+            }
+        }
+    }
+}
+
+// Parses a sort as `write` writes it: a column's key, then ` descending` if it's reversed.
+fn parse_sort(value: &str) -> Option<TasksSort> {
+    let (column_key, descending) = match value.strip_suffix(DESCENDING_SUFFIX) {
+        Some(column_key) => (column_key, true),
+        None => (value, false),
+    };
+    let key: TaskSortKey = TASKS_TABLE_COLUMNS
+        .iter()
+        .find(|table_column| table_column.key == column_key)?
+        .sort_key?;
+    Some(TasksSort { key, descending })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use crate::theme::THEME_BASIC;
+
+    #[test]
+    fn sessions_restore_what_they_kept() {
+        let new_state = || AppState::new(fixtures::snapshot(fixtures::tiny()));
+        let mut state = new_state();
+        state.theme = &THEME_BASIC;
+        state.select_tasks_tab(TasksTab::Sleeping);
+        state.sort_tasks_by(TaskSortKey::WakeMs);
+        state.sort_tasks_by(TaskSortKey::WakeMs);
+        state.group_tasks_by_name = true;
+        state.toggle_tasks_table_column(TASKS_TABLE_COLUMNS.len() - 1);
+        state.toggle_performance_metric(0);
+        state.performance_expanded = true;
+        state.performance_history_view.time_window = Some(TimeWindow::FiveMinutes);
+
+        let session = write(&state);
+        assert!(session.contains("sort=wake descending\n"));
+        let mut restored = new_state();
+        apply(&mut restored, &session);
+        assert_eq!(restored.theme.name, THEME_BASIC.name);
+        assert_eq!(restored.tasks_tab, TasksTab::Sleeping);
+        assert_eq!(restored.tasks_sort, state.tasks_sort);
+        assert!(restored.group_tasks_by_name);
+        assert_eq!(
+            restored.tasks_table_columns_shown,
+            state.tasks_table_columns_shown
+        );
+        assert_eq!(
+            restored.performance_metrics_shown,
+            state.performance_metrics_shown
+        );
+        assert!(restored.performance_expanded);
+        assert_eq!(
+            restored.performance_history_view,
+            state.performance_history_view
+        );
+    }
+
+    #[test]
+    fn sessions_skip_what_they_dont_understand() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        let shown = state.tasks_table_columns_shown.clone();
+        apply(
+            &mut state,
+            "theme=neon\ntasks_tab=9\nsort=attributes\nmetrics=bogus\nsamples_per_bar=0\nnonsense",
+        );
+        assert_eq!(state.tasks_tab, TasksTab::All);
+        assert_eq!(state.tasks_sort, None);
+        assert_eq!(state.tasks_table_columns_shown, shown);
+        assert!(state.performance_metrics_shown.iter().any(|&shown| shown));
+        assert_eq!(state.performance_history_view.samples_per_bar, 1);
+    }
+}
@@ -0,0 +1,177 @@
+//! Renders whole frames into a `TestBackend` at fixed sizes and compares the text against insta
+//! snapshots in `src/snapshots`. After an intended visual change, review and accept the new
+//! snapshots with `cargo insta review`.
+
+use crate::error::ConsoleError;
+use crate::fixtures::{self, Dataset};
+use crate::glyphs::{GlyphSet, GLYPH_SET_ASCII, GLYPH_SET_NERD_FONT};
+use crate::screen_diff::Screen;
+use crate::state::AppState;
+use crate::strings::STRINGS_ENGLISH;
+use crate::theme::THEME_DEFAULT;
+use crate::{draw_error_screen, draw_frame, Task};
+use insta::assert_snapshot;
+use tui::backend::TestBackend;
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::Terminal;
+
+static SNAPSHOT_SIZES: [(u16, u16); 3] = [(80, 24), (120, 40), (40, 12)];
+static MATRIX_SIZES: [(u16, u16); 4] = [(80, 24), (100, 30), (200, 60), (40, 10)];
+static MATRIX_DATASETS: [Dataset; 4] = [
+    Dataset::Tiny,
+    Dataset::Typical,
+    Dataset::Pathological,
+    Dataset::Deadlock,
+];
+
+// Renders the buffer's symbols one row per line, so snapshots can be read and diffed as text.
+pub fn buffer_to_string(buffer: &Buffer) -> String {
+    Screen::from_buffer(buffer).to_string()
+}
+
+fn render_main_screen(width: u16, height: u16, glyph_set: &GlyphSet) -> String {
+    buffer_to_string(&render_main_screen_buffer(
+        width,
+        height,
+        glyph_set,
+        &fixtures::tiny(),
+    ))
+}
+
+fn render_main_screen_buffer(
+    width: u16,
+    height: u16,
+    glyph_set: &GlyphSet,
+    tasks: &[Task],
+) -> Buffer {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    let mut state = AppState::new(fixtures::snapshot(tasks.to_vec()));
+    terminal
+        .draw(|frame| draw_frame(frame, &fixtures::fixed_clock(), glyph_set, &mut state))
+        .unwrap();
+    terminal.backend().buffer().clone()
+}
+
+#[test]
+fn main_screen() {
+    for &(width, height) in &SNAPSHOT_SIZES {
+        assert_snapshot!(
+            format!("main_screen_{}x{}", width, height),
+            render_main_screen(width, height, &GLYPH_SET_NERD_FONT)
+        );
+    }
+}
+
+#[test]
+fn main_screen_ascii() {
+    for &(width, height) in &SNAPSHOT_SIZES {
+        assert_snapshot!(
+            format!("main_screen_ascii_{}x{}", width, height),
+            render_main_screen(width, height, &GLYPH_SET_ASCII)
+        );
+    }
+}
+
+// Not a snapshot: checks that nothing the ASCII glyph set draws needs more than ASCII, at every
+// size. The pathological dataset is left out, since its tasks' names aren't ASCII themselves.
+#[test]
+fn main_screen_ascii_draws_only_ascii() {
+    for &(width, height) in &MATRIX_SIZES {
+        for &dataset in &[Dataset::Tiny, Dataset::Typical, Dataset::Deadlock] {
+            let buffer =
+                render_main_screen_buffer(width, height, &GLYPH_SET_ASCII, &dataset.tasks());
+            for y in 0..height {
+                for x in 0..width {
+                    let symbol = &buffer.get(x, y).symbol;
+                    assert!(
+                        symbol.is_ascii(),
+                        "{}x{} {:?}: {:?} at {}, {}",
+                        width,
+                        height,
+                        dataset,
+                        symbol,
+                        x,
+                        y
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn error_screen() {
+    let error = ConsoleError::ConnectionRefused {
+        target: "localhost:6669".to_owned(),
+    };
+    for &(width, height) in &SNAPSHOT_SIZES {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|frame| draw_error_screen(frame, &GLYPH_SET_NERD_FONT, &THEME_DEFAULT, &error))
+            .unwrap();
+        assert_snapshot!(
+            format!("error_screen_{}x{}", width, height),
+            buffer_to_string(terminal.backend().buffer())
+        );
+    }
+}
+
+// Not a snapshot: checks that every size and dataset draws without panicking and that the
+// title bar and both panes still fit on screen, with their borders closed and intact down both
+// sides.
+#[test]
+fn main_screen_fits_every_size() {
+    for &(width, height) in &MATRIX_SIZES {
+        for &dataset in &MATRIX_DATASETS {
+            let tasks = dataset.tasks();
+            // The pieces of each glyph set's pane borders: top left, bottom left and bottom
+            // right corners, and the sides.
+            for (glyph_set, border) in &[
+                (GLYPH_SET_NERD_FONT, ["╭", "╰", "╯", "│"]),
+                (GLYPH_SET_ASCII, ["+", "+", "+", "|"]),
+            ] {
+                let [top_left, bottom_left, bottom_right, side] = *border;
+                let buffer = render_main_screen_buffer(width, height, glyph_set, &tasks);
+                let context = format!("{}x{} {:?}", width, height, dataset);
+                assert_eq!(buffer.area, Rect::new(0, 0, width, height), "{}", context);
+                let rows: Vec<String> = buffer_to_string(&buffer)
+                    .lines()
+                    .map(str::to_owned)
+                    .collect();
+                assert!(rows[0].contains(glyph_set.menu_button), "{}", context);
+
+                // Each pane's top border starts a box whose bottom border is also on screen.
+                let corner = |x: u16, y: u16| buffer.get(x, y).symbol.clone();
+                for label in &[STRINGS_ENGLISH.performance, STRINGS_ENGLISH.tasks] {
+                    let top = rows
+                        .iter()
+                        .position(|row| row.starts_with(top_left) && row.contains(label))
+                        .unwrap_or_else(|| panic!("{}: no {} pane", context, label));
+                    let bottom = (top + 1..rows.len())
+                        .find(|&y| corner(0, y as u16) == bottom_left)
+                        .unwrap_or_else(|| panic!("{}: {} pane isn't closed", context, label));
+                    assert_eq!(
+                        corner(width - 1, bottom as u16),
+                        bottom_right,
+                        "{}",
+                        context
+                    );
+                    for y in top + 1..bottom {
+                        for &x in &[0, width - 1] {
+                            assert_eq!(
+                                corner(x, y as u16),
+                                side,
+                                "{}: {} pane's side at {}, {}",
+                                context,
+                                label,
+                                x,
+                                y
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,1959 @@
+//! Everything the main screen shows, and everything the user has changed about how it's shown,
+//! in one place.
+//!
+//! `draw_frame` draws an `AppState` and input changes what's on screen only by changing the
+//! state, so that features such as filtering and selection can't disagree about what the user is
+//! looking at.
+
+use crate::components::TASKS_TABLE_ID;
+use crate::export::ExportFormat;
+use crate::history::{self, Average, History};
+use crate::hyperlink::Hyperlink;
+use crate::profiling::FrameTimes;
+use crate::strings::{self, Strings, STRINGS_ENGLISH};
+use crate::theme::{Theme, THEMES, THEME_DEFAULT};
+use crate::{
+    FrameLayout, Resource, Task, TaskStatus, PERFORMANCE_METRICS, TASKS_TABLE_COLUMNS,
+    TASKS_TABLE_FROZEN_COLUMN_COUNT, TOAST_DURATION_SECONDS,
+};
+use chrono::{DateTime, Local};
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ops::Range;
+use std::time::Duration;
+use turbowish_widgets::layout::TABLE_COLUMN_SPACING;
+use turbowish_widgets::renderer::{WidgetId, WidgetRegistry};
+use turbowish_widgets::{ChartCache, ScrollState, TextInputState};
+
+/// The data collected from the program being watched, as of one moment.
+#[derive(Clone)]
+pub struct Snapshot {
+    /// The name and process ID of the program being watched.
+    pub target: String,
+    pub runtime_count: u32,
+    pub thread_count: u32,
+    pub tasks: Vec<Task>,
+    /// What the tasks hold and wait on.
+    pub resources: Vec<Resource>,
+    /// The whole program's performance, across all of its runtimes.
+    pub performance: Performance,
+    /// Each runtime's own performance, in the order they were started, for programs with more
+    /// than one. Empty for programs with a single runtime, where `performance` says it all.
+    pub runtimes: Vec<Runtime>,
+    /// How long ago the data was collected.
+    pub age: Duration,
+    /// Counts the changes made to the snapshot since it was taken, so that whatever's drawn
+    /// from it can tell whether it's still current.
+    pub generation: u64,
+}
+
+impl Snapshot {
+    /// Records that the snapshot changed.
+    pub fn changed(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+}
+
+/// One of several runtimes in the program being watched.
+#[derive(Clone)]
+pub struct Runtime {
+    pub name: String,
+    pub performance: Performance,
+}
+
+/// The runtime-wide measurements in the performance pane.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct PerformanceSample {
+    /// The share of the time the runtime spent polling tasks.
+    pub run_percent: f32,
+    /// How many tasks were waiting to be polled, on average.
+    pub scheduler_depth: f32,
+    pub poll_ms: f32,
+    pub wake_ms: f32,
+    /// How many tasks worker threads took from each other's queues each second.
+    pub steal_rate: f32,
+    /// How long worker threads slept each time they ran out of work, on average.
+    pub park_ms: f32,
+}
+
+impl PerformanceSample {
+    /// The measurements in the order the performance pane can show them.
+    pub fn values(&self) -> [f32; 6] {
+        [
+            self.run_percent,
+            self.scheduler_depth,
+            self.poll_ms,
+            self.wake_ms,
+            self.steal_rate,
+            self.park_ms,
+        ]
+    }
+}
+
+/// Averages each measurement on its own.
+impl Average for PerformanceSample {
+    fn average(samples: &[PerformanceSample]) -> PerformanceSample {
+        let average = |value: fn(&PerformanceSample) -> f32| {
+            samples.iter().map(value).sum::<f32>() / samples.len() as f32
+        };
+        PerformanceSample {
+            run_percent: average(|sample| sample.run_percent),
+            scheduler_depth: average(|sample| sample.scheduler_depth),
+            poll_ms: average(|sample| sample.poll_ms),
+            wake_ms: average(|sample| sample.wake_ms),
+            steal_rate: average(|sample| sample.steal_rate),
+            park_ms: average(|sample| sample.park_ms),
+        }
+    }
+}
+
+/// How far apart performance samples are taken.
+pub const PERFORMANCE_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+/// The most samples one bar of the expanded graphs can average.
+const PERFORMANCE_HISTORY_MAX_SAMPLES_PER_BAR: usize = 64;
+/// About how many bars wide the graphs are, for panning through a time window a bar at a time
+/// without knowing which graph is being looked at.
+const PERFORMANCE_HISTORY_NOMINAL_BAR_COUNT: usize = 60;
+/// How far below its threshold a measurement has to fall before its alert clears.
+const PERFORMANCE_ALERT_CLEAR_FRACTION: f32 = 0.8;
+/// How many notifications the notification center keeps before dropping the oldest.
+const NOTIFICATION_LIMIT: usize = 100;
+/// How long a column of the tasks timeline can cover, closest first.
+const TIMELINE_COLUMN_SPANS: [Duration; 8] = [
+    Duration::from_millis(100),
+    Duration::from_secs(1),
+    Duration::from_secs(5),
+    Duration::from_secs(15),
+    Duration::from_secs(30),
+    Duration::from_secs(60),
+    Duration::from_secs(5 * 60),
+    Duration::from_secs(15 * 60),
+];
+/// About how many columns wide the tasks timeline is, for panning and zooming it without
+/// knowing how wide it's drawn.
+const TIMELINE_NOMINAL_COLUMN_COUNT: usize = 60;
+/// How many workspaces there can be, one for each of Alt-1 to Alt-9.
+pub const WORKSPACE_LIMIT: usize = 9;
+
+#[derive(Clone, Default)]
+pub struct Performance {
+    pub current: PerformanceSample,
+    /// Earlier samples for the graphs, oldest first.
+    pub history: History<PerformanceSample>,
+}
+
+impl Performance {
+    /// One measurement's values as the graphs show them through `view`, oldest first.
+    pub fn graph_values(&self, metric: usize, view: HistoryView) -> Vec<f32> {
+        let range = self.view_range(view);
+        let values: Vec<f32> = self
+            .history
+            .range(range)
+            .map(|sample| sample.values()[metric])
+            .collect();
+        history::downsample(&values, view.samples_per_bar)
+    }
+
+    /// One measurement's samples, as many as `sample_count`, ending where `view` has panned
+    /// to. Unlike `graph_values`, these aren't averaged, so that they keep their spread.
+    pub fn window_values(&self, metric: usize, view: HistoryView, sample_count: usize) -> Vec<f32> {
+        let range = self.view_range(view);
+        let start = range.end.saturating_sub(sample_count).max(range.start);
+        self.history
+            .range(start..range.end)
+            .map(|sample| sample.values()[metric])
+            .collect()
+    }
+
+    /// The averages of one measurement over the window `window_values` gives, and over as many
+    /// samples just before it, for comparing the two. `None` if there are no samples before the
+    /// window.
+    pub fn window_averages(
+        &self,
+        metric: usize,
+        view: HistoryView,
+        sample_count: usize,
+    ) -> Option<(f32, f32)> {
+        let current = self.window_values(metric, view, sample_count);
+        let previous_view = HistoryView {
+            offset: view.offset + current.len(),
+            ..view
+        };
+        let previous = self.window_values(metric, previous_view, current.len());
+        if current.is_empty() || previous.is_empty() {
+            return None;
+        }
+        let average = |values: &[f32]| values.iter().sum::<f32>() / values.len() as f32;
+        Some((average(&current), average(&previous)))
+    }
+
+    // The samples `view` covers, however many of them fit on screen.
+    fn view_range(&self, view: HistoryView) -> Range<usize> {
+        let end = self.history.len().saturating_sub(view.offset);
+        let start = match view.time_window {
+            Some(time_window) => end.saturating_sub(time_window.sample_count(end)),
+            None => 0,
+        };
+        start..end
+    }
+}
+
+/// Which part of the performance history the expanded graphs show, and how closely.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct HistoryView {
+    /// How many samples before the newest the graphs end. At 0, they follow new samples in.
+    pub offset: usize,
+    /// How many samples each bar averages. At 1, every sample gets a bar of its own.
+    pub samples_per_bar: usize,
+    /// How much history the graphs cover, however wide they are. While there's a time window,
+    /// `fitted` works out how many samples each bar averages.
+    pub time_window: Option<TimeWindow>,
+}
+
+impl HistoryView {
+    /// The view for a graph with room for `bar_count` bars, out of a history this long. With a
+    /// time window, each bar averages as many samples as it takes for the window to fit.
+    pub fn fitted(self, history_len: usize, bar_count: usize) -> HistoryView {
+        match self.time_window {
+            Some(time_window) => {
+                let sample_count =
+                    time_window.sample_count(history_len.saturating_sub(self.offset));
+                HistoryView {
+                    samples_per_bar: sample_count.div_ceil(bar_count.max(1)).max(1),
+                    ..self
+                }
+            }
+            None => self,
+        }
+    }
+}
+
+impl Default for HistoryView {
+    fn default() -> HistoryView {
+        HistoryView {
+            offset: 0,
+            samples_per_bar: 1,
+            time_window: None,
+        }
+    }
+}
+
+/// Which part of the tasks' lives the timeline shows, and how closely.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct TimelineView {
+    /// How long before the snapshot the timeline ends. At zero, it ends at the snapshot.
+    pub offset: Duration,
+    /// How long each column covers, or `None` to fit the oldest task's whole life in.
+    pub column_span: Option<Duration>,
+}
+
+impl TimelineView {
+    /// How long each column covers for a timeline with room for `column_count` columns, where
+    /// the oldest task was spawned `oldest` before the snapshot.
+    pub fn fitted_column_span(self, oldest: Duration, column_count: usize) -> Duration {
+        self.column_span.unwrap_or_else(|| {
+            let millis = (oldest.as_millis() as u64).div_ceil(column_count.max(1) as u64);
+            Duration::from_millis(millis.max(1))
+        })
+    }
+}
+
+/// How much of the performance history the expanded graphs and the performance screen's charts
+/// cover.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TimeWindow {
+    OneMinute,
+    FiveMinutes,
+    FifteenMinutes,
+    /// Everything in the history.
+    All,
+}
+
+impl TimeWindow {
+    /// In the order the time window selector shows them, shortest first.
+    pub const ALL: [TimeWindow; 4] = [
+        TimeWindow::OneMinute,
+        TimeWindow::FiveMinutes,
+        TimeWindow::FifteenMinutes,
+        TimeWindow::All,
+    ];
+
+    pub fn index(self) -> u32 {
+        self as u32
+    }
+
+    pub fn from_index(index: u32) -> Option<TimeWindow> {
+        TimeWindow::ALL.get(index as usize).cloned()
+    }
+
+    /// How many samples the window covers, out of a history this long.
+    pub fn sample_count(self, history_len: usize) -> usize {
+        let duration = match self {
+            TimeWindow::OneMinute => Duration::from_secs(60),
+            TimeWindow::FiveMinutes => Duration::from_secs(5 * 60),
+            TimeWindow::FifteenMinutes => Duration::from_secs(15 * 60),
+            TimeWindow::All => return history_len,
+        };
+        (duration.as_secs() / PERFORMANCE_SAMPLE_INTERVAL.as_secs()) as usize
+    }
+}
+
+/// The tabs above the tasks table, each showing the tasks with one status, the bookmarked
+/// ones, or all of them.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TasksTab {
+    All,
+    Running,
+    Sleeping,
+    Deadlocked,
+    Bookmarked,
+}
+
+impl TasksTab {
+    /// In the order the tabs appear.
+    pub const ALL: [TasksTab; 5] = [
+        TasksTab::All,
+        TasksTab::Running,
+        TasksTab::Sleeping,
+        TasksTab::Deadlocked,
+        TasksTab::Bookmarked,
+    ];
+
+    pub fn index(self) -> u32 {
+        self as u32
+    }
+
+    pub fn from_index(index: u32) -> Option<TasksTab> {
+        TasksTab::ALL.get(index as usize).cloned()
+    }
+
+    /// Whether the tab lists `task`, given the IDs of the bookmarked tasks.
+    pub fn shows(self, task: &Task, bookmarked_task_ids: &BTreeSet<u64>) -> bool {
+        match self {
+            TasksTab::All => true,
+            TasksTab::Running => task.status == TaskStatus::Running,
+            TasksTab::Sleeping => task.status == TaskStatus::Sleeping,
+            TasksTab::Deadlocked => task.status == TaskStatus::Deadlocked,
+            TasksTab::Bookmarked => bookmarked_task_ids.contains(&task.id),
+        }
+    }
+}
+
+/// A task property the tasks table can be sorted by.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TaskSortKey {
+    Id,
+    Name,
+    Status,
+    RunPercent,
+    PollMs,
+    WakeMs,
+    BusyTime,
+    IdleTime,
+    Lifetime,
+}
+
+impl TaskSortKey {
+    /// Orders two tasks by this key, smallest first. Numbers that aren't numbers sort last.
+    pub fn compare(self, a: &Task, b: &Task) -> Ordering {
+        match self {
+            TaskSortKey::Id => a.id.cmp(&b.id),
+            TaskSortKey::Name => a.name.cmp(&b.name),
+            TaskSortKey::Status => status_rank(a.status).cmp(&status_rank(b.status)),
+            TaskSortKey::RunPercent => a.run_percent.total_cmp(&b.run_percent),
+            TaskSortKey::PollMs => a.poll_ms.total_cmp(&b.poll_ms),
+            TaskSortKey::WakeMs => a.wake_ms.total_cmp(&b.wake_ms),
+            TaskSortKey::BusyTime => a.busy_time.cmp(&b.busy_time),
+            TaskSortKey::IdleTime => a.idle_time.cmp(&b.idle_time),
+            TaskSortKey::Lifetime => a.lifetime().cmp(&b.lifetime()),
+        }
+    }
+}
+
+fn status_rank(status: TaskStatus) -> u8 {
+    match status {
+        TaskStatus::Running => 0,
+        TaskStatus::Sleeping => 1,
+        TaskStatus::Deadlocked => 2,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TasksSort {
+    pub key: TaskSortKey,
+    pub descending: bool,
+}
+
+/// The tasks `tasks_tab` shows whose names contain `name_filter`, if there is one, in the order
+/// `sort` puts them in. Ties, and everything when there's no sort, stay in snapshot order.
+pub fn visible_tasks<'a>(
+    tasks: &'a [Task],
+    tasks_tab: TasksTab,
+    bookmarked_task_ids: &BTreeSet<u64>,
+    name_filter: Option<&str>,
+    sort: Option<TasksSort>,
+) -> Vec<&'a Task> {
+    let mut visible_tasks: Vec<_> = tasks
+        .iter()
+        .filter(|task| is_visible(task, tasks_tab, bookmarked_task_ids, name_filter))
+        .collect();
+    if let Some(sort) = sort {
+        visible_tasks.sort_by(|a, b| {
+            let ordering = sort.key.compare(a, b);
+            if sort.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+    visible_tasks
+}
+
+// Whether `visible_tasks` lists the task.
+fn is_visible(
+    task: &Task,
+    tasks_tab: TasksTab,
+    bookmarked_task_ids: &BTreeSet<u64>,
+    name_filter: Option<&str>,
+) -> bool {
+    tasks_tab.shows(task, bookmarked_task_ids)
+        && name_filter.is_none_or(|name| task.name.contains(name))
+}
+
+/// Tasks that share a name, shown as one row with their measurements combined.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TaskGroup<'a> {
+    pub name: &'a str,
+    /// In table order.
+    pub tasks: Vec<&'a Task>,
+    /// Whether the group's tasks are listed under its row.
+    pub expanded: bool,
+}
+
+impl<'a> TaskGroup<'a> {
+    /// The status that most needs attention: deadlocked if any task is, then running, then
+    /// sleeping.
+    pub fn status(&self) -> TaskStatus {
+        self.tasks
+            .iter()
+            .map(|task| task.status)
+            .max_by_key(|&status| match status {
+                TaskStatus::Sleeping => 0,
+                TaskStatus::Running => 1,
+                TaskStatus::Deadlocked => 2,
+            })
+            .unwrap_or(TaskStatus::Sleeping)
+    }
+
+    /// The group's share of the runtime's busy time, which is the sum of its tasks' shares.
+    pub fn run_percent(&self) -> f32 {
+        self.tasks.iter().map(|task| task.run_percent).sum()
+    }
+
+    pub fn mean_poll_ms(&self) -> f32 {
+        self.mean(|task| task.poll_ms)
+    }
+
+    pub fn mean_wake_ms(&self) -> f32 {
+        self.mean(|task| task.wake_ms)
+    }
+
+    pub fn total_busy_time(&self) -> Duration {
+        self.tasks.iter().map(|task| task.busy_time).sum()
+    }
+
+    pub fn total_idle_time(&self) -> Duration {
+        self.tasks.iter().map(|task| task.idle_time).sum()
+    }
+
+    /// How long ago the group's oldest task was spawned.
+    pub fn longest_lifetime(&self) -> Duration {
+        self.tasks
+            .iter()
+            .map(|task| task.lifetime())
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn mean(&self, value: impl Fn(&Task) -> f32) -> f32 {
+        let sum: f32 = self.tasks.iter().map(|task| value(task)).sum();
+        sum / self.tasks.len() as f32
+    }
+}
+
+/// One row of the tasks table's body.
+#[derive(Clone, PartialEq, Debug)]
+pub enum TasksTableRow<'a> {
+    Task {
+        task: &'a Task,
+        /// Whether the task is listed under its expanded group's row.
+        grouped: bool,
+    },
+    Group(TaskGroup<'a>),
+}
+
+impl<'a> TasksTableRow<'a> {
+    /// Whether the row is the task's own, or that of the group it's in.
+    pub fn contains_task(&self, task_id: u64) -> bool {
+        match *self {
+            TasksTableRow::Task { task, .. } => task.id == task_id,
+            TasksTableRow::Group(ref group) => group.tasks.iter().any(|task| task.id == task_id),
+        }
+    }
+}
+
+/// A node of the graph of who waits on what.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WaitForNode<'a> {
+    Task(&'a Task),
+    Resource(&'a Resource),
+}
+
+/// The tasks in `visible_tasks` that hold or wait on something, and what they hold and wait on,
+/// with edges from each task to what it waits on and from each resource to the tasks holding
+/// it, as indices of nodes. A deadlock is a cycle.
+pub fn wait_for_graph<'a>(
+    visible_tasks: &[&'a Task],
+    resources: &'a [Resource],
+) -> (Vec<WaitForNode<'a>>, Vec<(usize, usize)>) {
+    let visible_task = |task_id: &u64| {
+        visible_tasks
+            .iter()
+            .find(|task| task.id == *task_id)
+            .copied()
+    };
+    let mut nodes = vec![];
+    let mut edges = vec![];
+    let mut task_nodes = HashMap::new();
+    for resource in resources {
+        let waiting_tasks = resource
+            .waiting_task_ids
+            .iter()
+            .filter_map(visible_task)
+            .map(|task| (task, true));
+        let holder_tasks = resource
+            .holder_task_ids
+            .iter()
+            .filter_map(visible_task)
+            .map(|task| (task, false));
+        let tasks: Vec<_> = waiting_tasks.chain(holder_tasks).collect();
+        if tasks.is_empty() {
+            continue;
+        }
+        let resource_node = nodes.len();
+        nodes.push(WaitForNode::Resource(resource));
+        for (task, waiting) in tasks {
+            let task_node = *task_nodes.entry(task.id).or_insert_with(|| {
+                nodes.push(WaitForNode::Task(task));
+                nodes.len() - 1
+            });
+            edges.push(if waiting {
+                (task_node, resource_node)
+            } else {
+                (resource_node, task_node)
+            });
+        }
+    }
+    (nodes, edges)
+}
+
+/// Arranges the visible tasks into the table's rows. When grouping by name, tasks that share a
+/// name collapse into one row where the first of them would have been, and the tasks of the
+/// groups in `expanded_groups` are listed under their group's row. Names only one task has stay
+/// as plain task rows.
+pub fn tasks_table_rows<'a>(
+    visible_tasks: Vec<&'a Task>,
+    group_by_name: bool,
+    expanded_groups: &HashSet<String>,
+) -> Vec<TasksTableRow<'a>> {
+    if !group_by_name {
+        return visible_tasks
+            .into_iter()
+            .map(|task| TasksTableRow::Task {
+                task,
+                grouped: false,
+            })
+            .collect();
+    }
+
+    let mut groups: Vec<TaskGroup> = vec![];
+    for task in visible_tasks {
+        match groups.iter_mut().find(|group| group.name == task.name) {
+            Some(group) => group.tasks.push(task),
+            None => groups.push(TaskGroup {
+                name: &task.name,
+                tasks: vec![task],
+                expanded: expanded_groups.contains(&task.name),
+            }),
+        }
+    }
+
+    let mut rows = vec![];
+    for group in groups {
+        if group.tasks.len() == 1 {
+            rows.push(TasksTableRow::Task {
+                task: group.tasks[0],
+                grouped: false,
+            });
+            continue;
+        }
+        let members: Vec<_> = if group.expanded {
+            group.tasks.clone()
+        } else {
+            vec![]
+        };
+        rows.push(TasksTableRow::Group(group));
+        rows.extend(members.into_iter().map(|task| TasksTableRow::Task {
+            task,
+            grouped: true,
+        }));
+    }
+    rows
+}
+
+/// Something the user can do to a task from the console.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TaskAction {
+    Pause,
+    Resume,
+    Boost,
+    Unboost,
+    Abort,
+}
+
+impl TaskAction {
+    /// The actions the menu offers for `task`, in menu order.
+    pub fn available(task: &Task) -> [TaskAction; 3] {
+        [
+            if task.paused {
+                TaskAction::Resume
+            } else {
+                TaskAction::Pause
+            },
+            if task.boosted {
+                TaskAction::Unboost
+            } else {
+                TaskAction::Boost
+            },
+            TaskAction::Abort,
+        ]
+    }
+
+    /// Whether the action is hard enough to take back that the user should be asked first.
+    pub fn is_destructive(self) -> bool {
+        self == TaskAction::Abort
+    }
+}
+
+/// Something the user can do from the command palette, to the app rather than to a task.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Action {
+    SelectTasksTab(TasksTab),
+    SelectViewMode(ViewMode),
+    ToggleGrouping,
+    ToggleFollow,
+    OpenColumnSettings,
+    TogglePerformanceScreen,
+    OpenPerformanceSettings,
+    Export(ExportFormat),
+    /// Switches to a built-in theme, by its index in `THEMES`.
+    SelectTheme(usize),
+    /// Selects a task and shows its attributes.
+    OpenTask(u64),
+    Quit,
+    /// Stops the app and returns to the shell, like Ctrl-Z does elsewhere.
+    Suspend,
+    /// Takes the focus off the focused control, closes the performance screen, or quits,
+    /// whichever comes first.
+    Back,
+    /// Moves the focus to the next control if true, or the previous one if false.
+    StepFocus(bool),
+    /// Scrolls the tasks table's body by this many rows.
+    ScrollRows(isize),
+    /// Scrolls the tasks table's columns after the frozen ones by this many columns, or pans
+    /// the tasks timeline by as many steps, toward the snapshot for positive counts.
+    ScrollColumns(isize),
+    /// Has each column of the tasks timeline cover less time if true, or more if false.
+    ZoomTimeline(bool),
+    OpenSelectedTaskAttributes,
+    OpenSelectedTaskActions,
+    /// Moves the performance history this many bars back in time, or toward the present for
+    /// negative counts.
+    PanHistory(isize),
+    /// Shows fewer samples to a bar if true, or more if false.
+    ZoomHistory(bool),
+    /// Brings the performance history back to the newest samples, one to a bar.
+    ResetHistoryView,
+    /// Steps through the time windows, then back to a sample per bar.
+    CycleTimeWindow,
+    ToggleComparison,
+    /// Shows the newest values in big digits on the performance screen, opening it if need be,
+    /// or goes back to its charts.
+    TogglePresentation,
+    OpenCommandPalette,
+    OpenHelp,
+    ToggleNotifications,
+    /// Lists only the tasks named like the selected one, or stops filtering by name.
+    ToggleNameFilter,
+    /// Switches to a workspace, by index, opening it if it's the next one.
+    SelectWorkspace(usize),
+    /// Bookmarks the selected task, or removes its bookmark.
+    ToggleBookmark,
+    /// Copies the selected task's row to the clipboard.
+    CopySelectedTask,
+    /// Takes back the task action the toast is about.
+    Undo,
+}
+
+impl Action {
+    /// The actions the command palette offers in `state`, in the order it lists them before
+    /// anything's been typed: the app's own, then one to open each task.
+    pub fn available(state: &AppState) -> Vec<Action> {
+        let mut actions: Vec<Action> = TasksTab::ALL
+            .iter()
+            .map(|&tasks_tab| Action::SelectTasksTab(tasks_tab))
+            .collect();
+        actions.extend(
+            ViewMode::ALL
+                .iter()
+                .map(|&view_mode| Action::SelectViewMode(view_mode)),
+        );
+        actions.extend(&[
+            Action::ToggleGrouping,
+            Action::ToggleFollow,
+            Action::ToggleNotifications,
+            Action::ToggleNameFilter,
+            Action::ToggleBookmark,
+            Action::OpenColumnSettings,
+            Action::TogglePerformanceScreen,
+            Action::TogglePresentation,
+            Action::OpenPerformanceSettings,
+            Action::Export(ExportFormat::Csv),
+            Action::Export(ExportFormat::Json),
+            Action::CopySelectedTask,
+            Action::OpenHelp,
+        ]);
+        actions.extend((0..THEMES.len()).map(Action::SelectTheme));
+        let workspace_count = (state.workspaces.len().max(1) + 1).min(WORKSPACE_LIMIT);
+        actions.extend((0..workspace_count).map(Action::SelectWorkspace));
+        actions.extend(
+            state
+                .snapshot
+                .tasks
+                .iter()
+                .map(|task| Action::OpenTask(task.id)),
+        );
+        actions
+    }
+}
+
+/// How to take back a task action.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Undo {
+    /// Puts an aborted task back where it was in the snapshot.
+    Restore { index: usize, task: Task },
+    /// Applies the opposite action to the task.
+    Apply { task_id: u64, action: TaskAction },
+}
+
+/// A popup that takes all input until it's closed.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Overlay {
+    /// The actions for a task, with one of them highlighted.
+    TaskActionMenu { task_id: u64, selected_index: usize },
+    /// Asks before aborting a task.
+    ConfirmAbort { task_id: u64 },
+    /// Which of the tasks table's columns are shown, with one of the columns after the frozen
+    /// ones highlighted.
+    ColumnSettings { selected_index: usize },
+    /// Which measurements the performance pane shows, with one of them highlighted.
+    PerformanceSettings { selected_index: usize },
+    /// Every action there is, narrowed down by what's been typed into the palette's search,
+    /// with the match at `selected_index` highlighted.
+    CommandPalette { selected_index: usize },
+    /// The keys the app responds to, scrolled so that `first_index` is at the top.
+    Help { first_index: usize },
+    /// The mock values that can be changed while the console runs, with one of them
+    /// highlighted.
+    Knobs { selected_index: usize },
+    /// All of a task's attributes, scrolled so that `first_index` is at the top.
+    TaskAttributes {
+        task_id: u64,
+        selected_index: usize,
+        first_index: usize,
+    },
+}
+
+/// When a measurement raises an alert. The alert is raised once the measurement goes above
+/// `raise_above`, and cleared once it falls below `clear_below`, so that a measurement hovering
+/// around the threshold doesn't raise it over and over.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AlertThreshold {
+    pub raise_above: f32,
+    pub clear_below: f32,
+}
+
+impl AlertThreshold {
+    /// A threshold that clears a little below where it's raised.
+    pub fn new(raise_above: f32) -> AlertThreshold {
+        AlertThreshold {
+            raise_above,
+            clear_below: raise_above * PERFORMANCE_ALERT_CLEAR_FRACTION,
+        }
+    }
+}
+
+/// A short-lived message along the bottom of the screen.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Toast {
+    pub message: String,
+    /// What the message is about, for a toast that's also in the notification center.
+    pub kind: Option<NotificationKind>,
+    /// How to take back what the message is about, if it can be.
+    pub undo: Option<Undo>,
+    /// When the toast goes away. Toasts made before the first frame, when there's no clock
+    /// reading yet, stay until they're replaced.
+    pub expires_at: Option<DateTime<Local>>,
+}
+
+/// What a notification is about.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum NotificationKind {
+    /// A measurement crossed its alert threshold.
+    Alert,
+    /// The performance history was written to a file, or couldn't be.
+    Export,
+    /// Connecting to the program being watched failed or succeeded.
+    Connection,
+}
+
+/// Something that happened that the user may want to look back on once its toast is gone.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub message: String,
+    /// As of the last frame drawn, if there was one.
+    pub raised_at: Option<DateTime<Local>>,
+}
+
+/// How the tasks table arranges its rows.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ViewMode {
+    Flat,
+    /// Tasks under the tasks that spawned them.
+    Tree,
+    /// Each task's life as a bar across time, with the stretches it was busy highlighted.
+    Timeline,
+    /// Who waits on what, as arrows from tasks to the resources they wait on and from resources
+    /// to the tasks holding them.
+    Graph,
+}
+
+impl ViewMode {
+    /// In the order the view mode control shows them.
+    pub const ALL: [ViewMode; 4] = [
+        ViewMode::Flat,
+        ViewMode::Tree,
+        ViewMode::Timeline,
+        ViewMode::Graph,
+    ];
+
+    pub fn index(self) -> u32 {
+        self as u32
+    }
+
+    pub fn from_index(index: u32) -> Option<ViewMode> {
+        ViewMode::ALL.get(index as usize).cloned()
+    }
+}
+
+/// How a workspace left the panes: what the tasks table lists and how, and how the performance
+/// pane was set up. Everything else, such as the theme and the notifications, is shared.
+#[derive(Clone)]
+pub struct Workspace {
+    tasks_tab: TasksTab,
+    view_mode: ViewMode,
+    timeline_view: TimelineView,
+    tasks_sort: Option<TasksSort>,
+    group_tasks_by_name: bool,
+    task_name_filter: Option<String>,
+    expanded_task_groups: HashSet<String>,
+    follow_tasks: bool,
+    selected_task_id: Option<u64>,
+    tasks_scroll_state: ScrollState,
+    tasks_column_scroll_state: ScrollState,
+    tasks_table_columns_shown: Vec<bool>,
+    performance_expanded: bool,
+    performance_screen_open: bool,
+    performance_history_view: HistoryView,
+    performance_metrics_shown: Vec<bool>,
+    performance_runtime: Option<usize>,
+    performance_comparison: bool,
+    performance_presentation: bool,
+}
+
+impl Workspace {
+    fn save(state: &AppState) -> Workspace {
+        Workspace {
+            tasks_tab: state.tasks_tab,
+            view_mode: state.view_mode,
+            timeline_view: state.timeline_view,
+            tasks_sort: state.tasks_sort,
+            group_tasks_by_name: state.group_tasks_by_name,
+            task_name_filter: state.task_name_filter.clone(),
+            expanded_task_groups: state.expanded_task_groups.clone(),
+            follow_tasks: state.follow_tasks,
+            selected_task_id: state.selected_task_id,
+            tasks_scroll_state: state.tasks_scroll_state,
+            tasks_column_scroll_state: state.tasks_column_scroll_state,
+            tasks_table_columns_shown: state.tasks_table_columns_shown.clone(),
+            performance_expanded: state.performance_expanded,
+            performance_screen_open: state.performance_screen_open,
+            performance_history_view: state.performance_history_view,
+            performance_metrics_shown: state.performance_metrics_shown.clone(),
+            performance_runtime: state.performance_runtime,
+            performance_comparison: state.performance_comparison,
+            performance_presentation: state.performance_presentation,
+        }
+    }
+
+    fn restore(self, state: &mut AppState) {
+        state.tasks_tab = self.tasks_tab;
+        state.view_mode = self.view_mode;
+        state.timeline_view = self.timeline_view;
+        state.tasks_sort = self.tasks_sort;
+        state.group_tasks_by_name = self.group_tasks_by_name;
+        state.task_name_filter = self.task_name_filter;
+        state.expanded_task_groups = self.expanded_task_groups;
+        state.follow_tasks = self.follow_tasks;
+        state.selected_task_id = self.selected_task_id;
+        state.tasks_scroll_state = self.tasks_scroll_state;
+        state.tasks_column_scroll_state = self.tasks_column_scroll_state;
+        state.tasks_table_columns_shown = self.tasks_table_columns_shown;
+        state.performance_expanded = self.performance_expanded;
+        state.performance_screen_open = self.performance_screen_open;
+        state.performance_history_view = self.performance_history_view;
+        state.performance_metrics_shown = self.performance_metrics_shown;
+        state.performance_runtime = self.performance_runtime;
+        state.performance_comparison = self.performance_comparison;
+        state.performance_presentation = self.performance_presentation;
+    }
+}
+
+/// A label that's drawn in every frame but changes only now and then, kept from one frame to the
+/// next so that it's only written again when what it says changes. The key is whatever the label
+/// is written from.
+#[derive(Default)]
+pub struct CachedLabel<K> {
+    key: Option<K>,
+    label: String,
+}
+
+impl<K> CachedLabel<K>
+where
+    K: PartialEq,
+{
+    /// Makes this the label for `key`, writing it into the label's buffer with `write` unless it
+    /// already is.
+    pub fn update<F>(&mut self, key: K, write: F)
+    where
+        F: FnOnce(&mut String),
+    {
+        if self.key.as_ref() == Some(&key) {
+            return;
+        }
+        self.label.clear();
+        write(&mut self.label);
+        self.key = Some(key);
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+/// A performance graph's chart, kept from one frame to the next along with the data window it
+/// showed: the history's generation, the runtime, and the part of the history in view. The
+/// window's generation only moves on when the window does, and only then is the chart encoded
+/// again.
+///
+/// The graphs are built while the rest of the state is borrowed, so the window is in cells.
+#[derive(Default)]
+pub struct GraphCache {
+    window: Cell<Option<(u64, Option<usize>, HistoryView)>>,
+    generation: Cell<u64>,
+    pub chart: ChartCache,
+}
+
+impl GraphCache {
+    /// The generation of `window`, which is the last window's unless the window moved.
+    pub fn generation(&self, window: (u64, Option<usize>, HistoryView)) -> u64 {
+        if self.window.get() != Some(window) {
+            self.window.set(Some(window));
+            self.generation.set(self.generation.get().wrapping_add(1));
+        }
+        self.generation.get()
+    }
+}
+
+pub struct AppState {
+    pub snapshot: Snapshot,
+    /// The time shown in the title bar, as of the last frame drawn.
+    pub now: Option<DateTime<Local>>,
+    /// `now` as the title bar shows it, keyed by the second.
+    pub time_label: CachedLabel<Option<i64>>,
+    /// Each tab's icon, name and task count, keyed by the count, the language, the icon and
+    /// whether there was room for the count, by tab.
+    pub tasks_tab_labels: [CachedLabel<(usize, &'static str, &'static str, bool)>; 5],
+    /// How many tasks there are and how many the tab shows, keyed by the two counts, the
+    /// language and the separator between them.
+    pub tasks_footer_label: CachedLabel<(usize, usize, &'static str, &'static str)>,
+    /// The last frame's layout, for the next frame to reuse.
+    pub frame_layout: Option<FrameLayout>,
+    /// Whether the readout of how long frames take to draw is shown. Only builds with the
+    /// `profiling` feature time them.
+    pub frame_times_shown: bool,
+    pub frame_times: FrameTimes,
+    /// The bytes allocated as of the last frame, for the frame time readout. 0 if uncounted.
+    pub memory_allocated: usize,
+    /// Counts the changes made to the UI state, such as by the events the app handled, so that
+    /// the main loop can skip frames that would look just like the last.
+    pub generation: u64,
+    pub theme: &'static Theme,
+    /// The labels, in the language the locale asks for.
+    pub strings: &'static Strings,
+    pub tasks_tab: TasksTab,
+    pub view_mode: ViewMode,
+    /// Where the tasks timeline is panned and zoomed to.
+    pub timeline_view: TimelineView,
+    /// With no sort, tasks are listed in the order the snapshot has them.
+    pub tasks_sort: Option<TasksSort>,
+    /// Whether tasks that share a name are collapsed into one row.
+    pub group_tasks_by_name: bool,
+    /// Lists only the tasks whose names contain this.
+    pub task_name_filter: Option<String>,
+    /// The names of the groups whose tasks are listed under them.
+    pub expanded_task_groups: HashSet<String>,
+    /// Whether the table keeps the newest task in view as tasks are spawned. Scrolling by hand
+    /// turns this off.
+    pub follow_tasks: bool,
+    /// The ID of the task the user last clicked on, if it's still on the current tab.
+    pub selected_task_id: Option<u64>,
+    /// The IDs of the tasks the user bookmarked, which are kept for the target between
+    /// sessions.
+    pub bookmarked_task_ids: BTreeSet<u64>,
+    /// Whether the bookmarks changed, which the app tells the main loop to save like a
+    /// clipboard request.
+    pub bookmarks_changed: bool,
+    pub tasks_scroll_state: ScrollState,
+    /// Scrolls the tasks table's columns after the frozen ones.
+    pub tasks_column_scroll_state: ScrollState,
+    /// Whether each of the tasks table's columns is shown, by column. Hidden columns are left out
+    /// of the table entirely, rather than scrolled out of view.
+    pub tasks_table_columns_shown: Vec<bool>,
+    /// The tasks table's visible columns and their widths in the last frame drawn, left to
+    /// right, for finding which header was clicked.
+    pub tasks_table_columns: Vec<(usize, u16)>,
+    /// The popup that has taken over input, if any.
+    pub overlay: Option<Overlay>,
+    /// What's been typed into the command palette's search.
+    pub command_palette_input: TextInputState,
+    pub toast: Option<Toast>,
+    /// Alerts, exports, and connection events, oldest first, for the notification center.
+    pub notifications: Vec<Notification>,
+    /// How many notifications came in while the notification center was hidden.
+    pub unread_notification_count: usize,
+    /// Whether the notification center is showing under the panes.
+    pub notifications_open: bool,
+    /// The highlighted notification, counting from the newest.
+    pub selected_notification: usize,
+    pub notifications_scroll_state: ScrollState,
+    /// Text to put on the system clipboard, which the app hands to the main loop once the
+    /// event that asked for it has been handled.
+    pub clipboard_request: Option<String>,
+    /// The format to export the performance history in, which the app hands to the main loop
+    /// like a clipboard request.
+    pub export_request: Option<ExportFormat>,
+    /// Whether the performance pane shows taller graphs with axes.
+    pub performance_expanded: bool,
+    /// Whether the performance screen, with a full-size chart for each measurement, has taken
+    /// the place of the panes.
+    pub performance_screen_open: bool,
+    /// Where the expanded graphs and the performance screen's charts are panned and zoomed to.
+    /// The one-line graphs always show the newest samples, one to a bar.
+    pub performance_history_view: HistoryView,
+    /// Whether each measurement the performance pane can show is shown, by metric.
+    pub performance_metrics_shown: Vec<bool>,
+    /// Which runtime the performance pane and screen show, by index into the snapshot's
+    /// runtimes, or `None` for the whole program.
+    pub performance_runtime: Option<usize>,
+    /// Whether the performance screen's readouts compare the samples on screen with as many
+    /// before them.
+    pub performance_comparison: bool,
+    /// Whether the performance screen shows the newest values in big digits, for reading from
+    /// across a room, instead of charts.
+    pub performance_presentation: bool,
+    /// When each measurement raises an alert, by metric. `None` never does.
+    pub performance_alert_thresholds: Vec<Option<AlertThreshold>>,
+    /// Whether each measurement's alert is raised, by metric.
+    pub performance_alerts_raised: Vec<bool>,
+    /// Each measurement's graph, as last drawn, by metric.
+    pub performance_graph_caches: Vec<GraphCache>,
+    /// How each workspace opened so far left the panes, by index. The active workspace's entry
+    /// is out of date until another workspace takes over.
+    pub workspaces: Vec<Workspace>,
+    pub active_workspace: usize,
+    /// The widget keys are aimed at. With nothing focused, keys go straight to the app.
+    pub focused: Option<WidgetId>,
+    /// Where the interactive widgets were in the last frame drawn, for routing mouse events.
+    pub widget_registry: WidgetRegistry,
+    /// The links drawn in the last frame, for the main loop to hand to the backend on terminals
+    /// that can open them.
+    pub hyperlinks: Vec<Hyperlink>,
+    /// Where request ID attributes link to, with `{}` standing for the ID.
+    pub request_link_template: Option<String>,
+}
+
+impl AppState {
+    pub fn new(snapshot: Snapshot) -> AppState {
+        AppState {
+            snapshot,
+            now: None,
+            time_label: CachedLabel::default(),
+            tasks_tab_labels: Default::default(),
+            tasks_footer_label: CachedLabel::default(),
+            frame_layout: None,
+            frame_times_shown: false,
+            frame_times: FrameTimes::default(),
+            memory_allocated: 0,
+            generation: 0,
+            theme: &THEME_DEFAULT,
+            strings: &STRINGS_ENGLISH,
+            tasks_tab: TasksTab::All,
+            view_mode: ViewMode::Flat,
+            timeline_view: TimelineView::default(),
+            tasks_sort: None,
+            group_tasks_by_name: false,
+            task_name_filter: None,
+            expanded_task_groups: HashSet::new(),
+            follow_tasks: false,
+            selected_task_id: None,
+            bookmarked_task_ids: BTreeSet::new(),
+            bookmarks_changed: false,
+            tasks_scroll_state: ScrollState::default(),
+            tasks_column_scroll_state: ScrollState::default(),
+            tasks_table_columns_shown: TASKS_TABLE_COLUMNS
+                .iter()
+                .map(|table_column| table_column.shown_by_default)
+                .collect(),
+            tasks_table_columns: vec![],
+            overlay: None,
+            command_palette_input: TextInputState::default(),
+            toast: None,
+            notifications: vec![],
+            unread_notification_count: 0,
+            notifications_open: false,
+            selected_notification: 0,
+            notifications_scroll_state: ScrollState::default(),
+            clipboard_request: None,
+            export_request: None,
+            performance_expanded: false,
+            performance_screen_open: false,
+            performance_history_view: HistoryView::default(),
+            performance_metrics_shown: PERFORMANCE_METRICS
+                .iter()
+                .map(|metric| metric.shown_by_default)
+                .collect(),
+            performance_runtime: None,
+            performance_comparison: false,
+            performance_presentation: false,
+            performance_alert_thresholds: PERFORMANCE_METRICS
+                .iter()
+                .map(|metric| metric.default_alert_above.map(AlertThreshold::new))
+                .collect(),
+            performance_alerts_raised: vec![false; PERFORMANCE_METRICS.len()],
+            performance_graph_caches: PERFORMANCE_METRICS
+                .iter()
+                .map(|_| GraphCache::default())
+                .collect(),
+            workspaces: vec![],
+            active_workspace: 0,
+            focused: None,
+            widget_registry: WidgetRegistry::new(),
+            hyperlinks: vec![],
+            request_link_template: None,
+        }
+    }
+
+    /// The tasks on the current tab, in table order.
+    pub fn visible_tasks(&self) -> Vec<&Task> {
+        visible_tasks(
+            &self.snapshot.tasks,
+            self.tasks_tab,
+            &self.bookmarked_task_ids,
+            self.task_name_filter.as_deref(),
+            self.tasks_sort,
+        )
+    }
+
+    /// How many tasks `tasks_tab` would show. Every tab's count is drawn in every frame, so
+    /// this counts them without listing them.
+    pub fn task_count(&self, tasks_tab: TasksTab) -> usize {
+        let name_filter = self.task_name_filter.as_deref();
+        self.snapshot
+            .tasks
+            .iter()
+            .filter(|task| is_visible(task, tasks_tab, &self.bookmarked_task_ids, name_filter))
+            .count()
+    }
+
+    /// Lists only the tasks named like the selected task, or all of them again if they're
+    /// filtered by name already.
+    pub fn toggle_task_name_filter(&mut self) {
+        if self.task_name_filter.take().is_some() {
+            return;
+        }
+        let selected_task = self.selected_task_id.and_then(|task_id| self.task(task_id));
+        self.task_name_filter = selected_task.map(|task| task.name.clone());
+    }
+
+    /// Puts the panes the way the workspace at `index` left them, keeping how the current
+    /// workspace has them to come back to. Workspaces past the last one opened start out as
+    /// copies of the current one.
+    pub fn select_workspace(&mut self, index: usize) {
+        if index == self.active_workspace
+            || index > self.workspaces.len().max(1)
+            || index >= WORKSPACE_LIMIT
+        {
+            return;
+        }
+        let current = Workspace::save(self);
+        if self.workspaces.is_empty() {
+            self.workspaces.push(current.clone());
+        } else {
+            self.workspaces[self.active_workspace] = current.clone();
+        }
+        if index == self.workspaces.len() {
+            self.workspaces.push(current);
+        }
+        self.workspaces[index].clone().restore(self);
+        self.active_workspace = index;
+        self.focused = None;
+    }
+
+    /// Bookmarks a task, or removes its bookmark, and returns whether it's bookmarked now.
+    pub fn toggle_bookmark(&mut self, task_id: u64) -> bool {
+        let bookmarked = if self.bookmarked_task_ids.remove(&task_id) {
+            false
+        } else {
+            self.bookmarked_task_ids.insert(task_id)
+        };
+        self.bookmarks_changed = true;
+        bookmarked
+    }
+
+    /// Switches tabs, dropping the selection if the selected task isn't on the new tab.
+    pub fn select_tasks_tab(&mut self, tasks_tab: TasksTab) {
+        self.tasks_tab = tasks_tab;
+        let selected_task_id = self.selected_task_id;
+        if !self
+            .visible_tasks()
+            .iter()
+            .any(|task| Some(task.id) == selected_task_id)
+        {
+            self.selected_task_id = None;
+        }
+    }
+
+    /// The rows of the tasks table's body, in order.
+    pub fn tasks_table_rows(&self) -> Vec<TasksTableRow<'_>> {
+        tasks_table_rows(
+            self.visible_tasks(),
+            self.group_tasks_by_name,
+            &self.expanded_task_groups,
+        )
+    }
+
+    /// The graph of who waits on what among the tasks on the current tab.
+    pub fn wait_for_graph(&self) -> (Vec<WaitForNode<'_>>, Vec<(usize, usize)>) {
+        wait_for_graph(&self.visible_tasks(), &self.snapshot.resources)
+    }
+
+    /// The row drawn in the given screen row of the tasks table's body in the last frame, if
+    /// any.
+    pub fn tasks_table_row_at(&self, row: u16) -> Option<TasksTableRow<'_>> {
+        let table_rect = self.widget_registry.rect(TASKS_TABLE_ID)?;
+        // The first row is the header.
+        let body_row = row.checked_sub(table_rect.y + 1)? as usize;
+        self.tasks_table_rows()
+            .into_iter()
+            .nth(self.tasks_scroll_state.offset() + body_row)
+    }
+
+    /// Expands the group of tasks named `name`, or collapses it if it's expanded.
+    pub fn toggle_task_group(&mut self, name: String) {
+        if !self.expanded_task_groups.remove(&name) {
+            self.expanded_task_groups.insert(name);
+        }
+    }
+
+    /// The column of the tasks table under the given screen column in the last frame, if any.
+    pub fn tasks_table_column_at(&self, column: u16) -> Option<usize> {
+        let table_rect = self.widget_registry.rect(TASKS_TABLE_ID)?;
+        let mut x = table_rect.x;
+        for &(table_column, width) in &self.tasks_table_columns {
+            if column >= x && column < x + width {
+                return Some(table_column);
+            }
+            x += width + TABLE_COLUMN_SPACING;
+        }
+        None
+    }
+
+    /// The columns of the tasks table that aren't hidden, left to right.
+    pub fn shown_tasks_table_columns(&self) -> Vec<usize> {
+        (0..TASKS_TABLE_COLUMNS.len())
+            .filter(|&table_column| self.tasks_table_columns_shown[table_column])
+            .collect()
+    }
+
+    /// Hides a column of the tasks table, or shows it again. The frozen columns, which identify
+    /// the tasks, can't be hidden. Hiding the column the table is sorted by leaves the sort
+    /// alone, so that the order doesn't change underneath the user.
+    pub fn toggle_tasks_table_column(&mut self, table_column: usize) {
+        if (TASKS_TABLE_FROZEN_COLUMN_COUNT..TASKS_TABLE_COLUMNS.len()).contains(&table_column) {
+            let shown = &mut self.tasks_table_columns_shown[table_column];
+            *shown = !*shown;
+        }
+    }
+
+    /// The measurements the performance pane shows, left to right.
+    pub fn shown_performance_metrics(&self) -> Vec<usize> {
+        (0..PERFORMANCE_METRICS.len())
+            .filter(|&metric| self.performance_metrics_shown[metric])
+            .collect()
+    }
+
+    /// Hides a measurement in the performance pane, or shows it again. The last one shown stays,
+    /// so that the pane always has something in it.
+    pub fn toggle_performance_metric(&mut self, metric: usize) {
+        let shown_count = self.shown_performance_metrics().len();
+        if let Some(shown) = self.performance_metrics_shown.get_mut(metric) {
+            if !*shown || shown_count > 1 {
+                *shown = !*shown;
+            }
+        }
+    }
+
+    /// Sorts the tasks table by `key`, or reverses the sort if it's already sorted by `key`.
+    pub fn sort_tasks_by(&mut self, key: TaskSortKey) {
+        self.tasks_sort = Some(match self.tasks_sort {
+            Some(sort) if sort.key == key => TasksSort {
+                key,
+                descending: !sort.descending,
+            },
+            _ => TasksSort {
+                key,
+                descending: false,
+            },
+        });
+    }
+
+    pub fn task(&self, task_id: u64) -> Option<&Task> {
+        self.snapshot.tasks.iter().find(|task| task.id == task_id)
+    }
+
+    /// Does `action` to the task, if it still exists, and returns how to undo it.
+    pub fn perform_task_action(&mut self, task_id: u64, action: TaskAction) -> Option<Undo> {
+        let index = self
+            .snapshot
+            .tasks
+            .iter()
+            .position(|task| task.id == task_id)?;
+        self.snapshot.changed();
+        let task = &mut self.snapshot.tasks[index];
+        let opposite_action = match action {
+            TaskAction::Pause => {
+                task.paused = true;
+                TaskAction::Resume
+            }
+            TaskAction::Resume => {
+                task.paused = false;
+                TaskAction::Pause
+            }
+            TaskAction::Boost => {
+                task.boosted = true;
+                TaskAction::Unboost
+            }
+            TaskAction::Unboost => {
+                task.boosted = false;
+                TaskAction::Boost
+            }
+            TaskAction::Abort => {
+                let task = self.snapshot.tasks.remove(index);
+                if self.selected_task_id == Some(task_id) {
+                    self.selected_task_id = None;
+                }
+                return Some(Undo::Restore { index, task });
+            }
+        };
+        Some(Undo::Apply {
+            task_id,
+            action: opposite_action,
+        })
+    }
+
+    /// Takes back an action.
+    pub fn undo(&mut self, undo: Undo) {
+        match undo {
+            Undo::Restore { index, task } => {
+                let index = index.min(self.snapshot.tasks.len());
+                self.snapshot.tasks.insert(index, task);
+            }
+            Undo::Apply { task_id, action } => {
+                self.perform_task_action(task_id, action);
+            }
+        }
+    }
+
+    /// The toast, unless it has expired as of the last frame drawn.
+    pub fn visible_toast(&self) -> Option<&Toast> {
+        self.toast
+            .as_ref()
+            .filter(|toast| match (toast.expires_at, self.now) {
+                (Some(expires_at), Some(now)) => now < expires_at,
+                _ => true,
+            })
+    }
+
+    /// Puts up a toast with nothing to undo, for the usual time.
+    pub fn show_toast(&mut self, message: String) {
+        self.toast = Some(Toast {
+            message,
+            kind: None,
+            undo: None,
+            expires_at: self
+                .now
+                .map(|now| now + chrono::Duration::seconds(TOAST_DURATION_SECONDS)),
+        });
+    }
+
+    /// Puts up a toast for something that happened, and keeps it in the notification center.
+    /// A highlighted notification stays highlighted as newer ones come in above it.
+    pub fn notify(&mut self, kind: NotificationKind, message: String) {
+        if !self.notifications.is_empty() {
+            self.selected_notification += 1;
+        }
+        self.notifications.push(Notification {
+            kind,
+            message: message.clone(),
+            raised_at: self.now,
+        });
+        if self.notifications.len() > NOTIFICATION_LIMIT {
+            self.notifications.remove(0);
+        }
+        self.selected_notification = self.selected_notification.min(self.notifications.len() - 1);
+        if !self.notifications_open {
+            self.unread_notification_count =
+                (self.unread_notification_count + 1).min(self.notifications.len());
+        }
+        self.show_toast(message);
+        if let Some(ref mut toast) = self.toast {
+            toast.kind = Some(kind);
+        }
+    }
+
+    /// Removes the highlighted notification, highlighting the next older one, or the newest if
+    /// there's nothing older.
+    pub fn dismiss_notification(&mut self) {
+        let count = self.notifications.len();
+        if self.selected_notification < count {
+            self.notifications
+                .remove(count - 1 - self.selected_notification);
+        }
+        self.selected_notification = self
+            .selected_notification
+            .min(self.notifications.len().saturating_sub(1));
+        self.unread_notification_count =
+            self.unread_notification_count.min(self.notifications.len());
+    }
+
+    pub fn clear_notifications(&mut self) {
+        self.notifications.clear();
+        self.selected_notification = 0;
+        self.unread_notification_count = 0;
+    }
+
+    /// Records that the UI state changed.
+    pub fn changed(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Adds a task that was just spawned, with the next task ID.
+    pub fn spawn_task(&mut self, task: Task) {
+        let id = self.newest_task_id().map_or(0, |id| id.saturating_add(1));
+        self.snapshot.tasks.push(Task { id, ..task });
+        self.snapshot.changed();
+    }
+
+    /// Removes a task that finished, if it's still there, unselecting it if it was selected.
+    pub fn complete_task(&mut self, task_id: u64) {
+        self.snapshot.tasks.retain(|task| task.id != task_id);
+        self.snapshot.changed();
+        if self.selected_task_id == Some(task_id) {
+            self.selected_task_id = None;
+        }
+    }
+
+    /// Adds the newest performance sample. Graphs panned back through the history stay on the
+    /// samples they were showing. Measurements that cross their alert thresholds raise or clear
+    /// their alerts, with a notification for each alert raised.
+    pub fn record_performance(&mut self, sample: PerformanceSample) {
+        self.snapshot.changed();
+        let performance = &mut self.snapshot.performance;
+        performance.current = sample;
+        performance.history.push(sample);
+        let view = &mut self.performance_history_view;
+        if view.offset > 0 {
+            view.offset = (view.offset + 1).min(performance.history.len().saturating_sub(1));
+        }
+
+        for (metric, &value) in sample.values().iter().enumerate() {
+            let threshold = match self.performance_alert_thresholds[metric] {
+                Some(threshold) => threshold,
+                None => continue,
+            };
+            let raised = &mut self.performance_alerts_raised[metric];
+            if !*raised && value > threshold.raise_above {
+                *raised = true;
+                let performance_metric = &PERFORMANCE_METRICS[metric];
+                let message = strings::fill(
+                    self.strings.performance_alert_above,
+                    &[
+                        self.strings.performance_metrics[metric],
+                        &performance_metric.quantity.text(threshold.raise_above),
+                    ],
+                );
+                self.notify(NotificationKind::Alert, message);
+            } else if *raised && value < threshold.clear_below {
+                *raised = false;
+            }
+        }
+    }
+
+    /// Adds the newest performance sample of one of the program's runtimes.
+    pub fn record_runtime_performance(&mut self, runtime: usize, sample: PerformanceSample) {
+        if let Some(runtime) = self.snapshot.runtimes.get_mut(runtime) {
+            runtime.performance.current = sample;
+            runtime.performance.history.push(sample);
+            self.snapshot.changed();
+        }
+    }
+
+    /// The performance the performance pane and screen show: the selected runtime's, or the
+    /// whole program's.
+    pub fn performance(&self) -> &Performance {
+        self.performance_runtime
+            .and_then(|runtime| self.snapshot.runtimes.get(runtime))
+            .map_or(&self.snapshot.performance, |runtime| &runtime.performance)
+    }
+
+    /// Sets when a measurement raises an alert, or stops it from raising any, clearing the
+    /// alert if it's raised.
+    pub fn set_performance_alert_threshold(
+        &mut self,
+        metric: usize,
+        threshold: Option<AlertThreshold>,
+    ) {
+        if metric < PERFORMANCE_METRICS.len() {
+            self.performance_alert_thresholds[metric] = threshold;
+            self.performance_alerts_raised[metric] = false;
+        }
+    }
+
+    /// Moves the expanded graphs this many bars back in time, or toward the present for negative
+    /// counts, stopping at the newest sample and with at least one sample left to show.
+    pub fn pan_performance_history(&mut self, bars: isize) {
+        let history_len = self.performance().history.len();
+        let view = &mut self.performance_history_view;
+        let max_offset = history_len.saturating_sub(1);
+        let samples_per_bar = view
+            .fitted(history_len, PERFORMANCE_HISTORY_NOMINAL_BAR_COUNT)
+            .samples_per_bar;
+        let offset = view.offset as isize + bars * samples_per_bar as isize;
+        view.offset = offset.clamp(0, max_offset as isize) as usize;
+    }
+
+    /// Halves or doubles how many samples each bar of the expanded graphs averages. With a time
+    /// window, moves to the next shorter or longer one instead, and zooming in past the shortest
+    /// goes back to a sample per bar.
+    pub fn zoom_performance_history(&mut self, zoom_in: bool) {
+        let view = &mut self.performance_history_view;
+        if let Some(time_window) = view.time_window {
+            view.time_window = match (zoom_in, time_window.index()) {
+                (true, 0) => None,
+                (true, index) => TimeWindow::from_index(index - 1),
+                (false, index) => TimeWindow::from_index(index + 1).or(Some(time_window)),
+            };
+            return;
+        }
+        view.samples_per_bar = if zoom_in {
+            (view.samples_per_bar / 2).max(1)
+        } else {
+            (view.samples_per_bar * 2).min(PERFORMANCE_HISTORY_MAX_SAMPLES_PER_BAR)
+        };
+    }
+
+    /// Has the expanded graphs and the performance screen's charts cover a time window, however
+    /// wide they are, or go back to a sample per bar.
+    pub fn select_performance_time_window(&mut self, time_window: Option<TimeWindow>) {
+        let view = &mut self.performance_history_view;
+        view.time_window = time_window;
+        view.samples_per_bar = 1;
+    }
+
+    /// The part of the performance history the graphs show: where they're panned and zoomed to
+    /// when expanded or on the performance screen, and the newest samples otherwise.
+    pub fn visible_performance_history(&self) -> HistoryView {
+        if self.performance_expanded || self.performance_screen_open {
+            self.performance_history_view
+        } else {
+            HistoryView::default()
+        }
+    }
+
+    /// How long before the snapshot the oldest task was spawned, which is as far back as the
+    /// tasks timeline goes.
+    pub fn oldest_task_lifetime(&self) -> Duration {
+        self.snapshot
+            .tasks
+            .iter()
+            .map(Task::lifetime)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Moves the tasks timeline this many columns back in time, or toward the snapshot for
+    /// negative counts, stopping at the snapshot and at the oldest task's spawn.
+    pub fn pan_timeline(&mut self, columns: isize) {
+        let oldest = self.oldest_task_lifetime();
+        let view = &mut self.timeline_view;
+        let span = view
+            .fitted_column_span(oldest, TIMELINE_NOMINAL_COLUMN_COUNT)
+            .saturating_mul(columns.unsigned_abs() as u32);
+        view.offset = if columns >= 0 {
+            view.offset.saturating_add(span).min(oldest)
+        } else {
+            view.offset.saturating_sub(span)
+        };
+    }
+
+    /// Has each column of the tasks timeline cover the next shorter or longer span. Zooming out
+    /// past the span that fits the oldest task's whole life in goes back to fitting it.
+    pub fn zoom_timeline(&mut self, zoom_in: bool) {
+        let oldest = self.oldest_task_lifetime();
+        let fitted_span =
+            TimelineView::default().fitted_column_span(oldest, TIMELINE_NOMINAL_COLUMN_COUNT);
+        let view = &mut self.timeline_view;
+        let span = view.fitted_column_span(oldest, TIMELINE_NOMINAL_COLUMN_COUNT);
+        view.column_span = if zoom_in {
+            TIMELINE_COLUMN_SPANS
+                .iter()
+                .rev()
+                .find(|&&closer_span| closer_span < span)
+                .or(TIMELINE_COLUMN_SPANS.first())
+                .copied()
+        } else {
+            TIMELINE_COLUMN_SPANS
+                .iter()
+                .find(|&&farther_span| farther_span > span && farther_span < fitted_span)
+                .copied()
+        };
+    }
+
+    /// Task IDs are handed out in spawn order, so the newest task has the highest.
+    pub fn newest_task_id(&self) -> Option<u64> {
+        self.snapshot.tasks.iter().map(|task| task.id).max()
+    }
+
+    /// Moves the focus to the next widget on screen, or the previous one, wrapping around.
+    pub fn step_focus(&mut self, forward: bool) {
+        self.focused = if forward {
+            self.widget_registry.next_focus(self.focused)
+        } else {
+            self.widget_registry.previous_focus(self.focused)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn grouping_collapses_tasks_that_share_a_name_until_expanded() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        state.group_tasks_by_name = true;
+        let rows = state.tasks_table_rows();
+        assert_eq!(rows.len(), 3);
+        let group = match rows[0] {
+            TasksTableRow::Group(ref group) => group.clone(),
+            _ => panic!("expected the connection handlers to be grouped"),
+        };
+        assert_eq!(group.name, "connection-handler");
+        assert_eq!(group.tasks.len(), 2);
+        assert_eq!(group.status(), TaskStatus::Running);
+        assert!((group.run_percent() - 26.4).abs() < 1e-4);
+        assert!((group.mean_poll_ms() - 1.275).abs() < 1e-4);
+        assert!(matches!(
+            rows[1],
+            TasksTableRow::Task { task, grouped: false } if task.name == "public-accept"
+        ));
+
+        state.toggle_task_group("connection-handler".to_owned());
+        let ids: Vec<_> = state
+            .tasks_table_rows()
+            .iter()
+            .map(|row| match *row {
+                TasksTableRow::Task { task, grouped } => Some((task.id, grouped)),
+                TasksTableRow::Group(_) => None,
+            })
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                None,
+                Some((285, true)),
+                Some((286, true)),
+                Some((1, false)),
+                Some((0, false)),
+            ]
+        );
+    }
+
+    #[test]
+    fn panned_performance_graphs_stay_on_the_samples_they_show() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        let run_percents = |state: &AppState| {
+            state
+                .snapshot
+                .performance
+                .graph_values(0, state.visible_performance_history())
+        };
+        assert_eq!(
+            run_percents(&state),
+            vec![4.0, 2.0, 7.0, 1.0, 7.0, 8.0, 3.0]
+        );
+
+        // Panning and zooming only show while the graphs are expanded.
+        state.pan_performance_history(2);
+        state.zoom_performance_history(false);
+        assert_eq!(run_percents(&state).len(), 7);
+        state.performance_expanded = true;
+        assert_eq!(run_percents(&state), vec![4.0, 4.5, 4.0]);
+
+        // New samples come in after the ones on screen.
+        state.record_performance(PerformanceSample {
+            run_percent: 100.0,
+            ..PerformanceSample::default()
+        });
+        assert_eq!(state.performance_history_view.offset, 3);
+        assert_eq!(run_percents(&state), vec![4.0, 4.5, 4.0]);
+
+        // Panning stops with one sample left to show, and back at the newest.
+        state.pan_performance_history(100);
+        assert_eq!(run_percents(&state), vec![4.0]);
+        state.pan_performance_history(-100);
+        state.zoom_performance_history(true);
+        assert_eq!(run_percents(&state).last(), Some(&100.0));
+    }
+
+    #[test]
+    fn the_timeline_zooms_in_steps_and_pans_within_the_tasks_lives() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        let oldest = state.oldest_task_lifetime();
+        let fitted_span = state
+            .timeline_view
+            .fitted_column_span(oldest, TIMELINE_NOMINAL_COLUMN_COUNT);
+        assert!(fitted_span * TIMELINE_NOMINAL_COLUMN_COUNT as u32 >= oldest);
+
+        // Zooming in picks the next shorter span, and zooming back out fits the tasks again.
+        state.zoom_timeline(true);
+        let span = state.timeline_view.column_span.unwrap();
+        assert!(span < fitted_span);
+        assert!(TIMELINE_COLUMN_SPANS.contains(&span));
+        for _ in 0..TIMELINE_COLUMN_SPANS.len() {
+            state.zoom_timeline(true);
+        }
+        assert_eq!(
+            state.timeline_view.column_span,
+            Some(TIMELINE_COLUMN_SPANS[0])
+        );
+        for _ in 0..TIMELINE_COLUMN_SPANS.len() {
+            state.zoom_timeline(false);
+        }
+        assert_eq!(state.timeline_view.column_span, None);
+
+        // Panning stops at the oldest task's spawn, and back at the snapshot.
+        state.zoom_timeline(true);
+        state.pan_timeline(2);
+        assert_eq!(state.timeline_view.offset, span * 2);
+        state.pan_timeline(10_000);
+        assert_eq!(state.timeline_view.offset, oldest);
+        state.pan_timeline(-10_000);
+        assert_eq!(state.timeline_view.offset, Duration::from_secs(0));
+    }
+
+    #[test]
+    fn deadlocked_tasks_wait_on_each_other_in_a_cycle() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::pathological()));
+        state.select_tasks_tab(TasksTab::Deadlocked);
+        let (nodes, edges) = state.wait_for_graph();
+        let node_ids: Vec<_> = nodes
+            .iter()
+            .map(|node| match *node {
+                WaitForNode::Task(task) => ("task", task.id),
+                WaitForNode::Resource(resource) => ("resource", resource.id),
+            })
+            .collect();
+        // The task waiting on the first lock isn't deadlocked, so it isn't shown.
+        assert_eq!(
+            node_ids,
+            [("resource", 3), ("task", 6), ("task", 2), ("resource", 4)]
+        );
+        assert_eq!(edges, [(1, 0), (0, 2), (2, 3), (3, 1)]);
+
+        state.select_tasks_tab(TasksTab::Running);
+        assert!(state.wait_for_graph().0.is_empty());
+    }
+
+    #[test]
+    fn time_windows_fit_the_graphs_however_wide() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        for index in 0..600 {
+            state.record_performance(fixtures::performance_sample(index));
+        }
+        state.performance_expanded = true;
+        state.select_performance_time_window(Some(TimeWindow::FiveMinutes));
+        let view = state.visible_performance_history();
+        let graph_values = |bar_count| {
+            let view = view.fitted(state.snapshot.performance.history.len(), bar_count);
+            state.snapshot.performance.graph_values(0, view).len()
+        };
+        assert_eq!(graph_values(300), 300);
+        assert_eq!(graph_values(100), 100);
+        assert_eq!(graph_values(80), 75);
+        assert_eq!(graph_values(1000), 300);
+
+        // Zooming moves between time windows, and past the shortest, back to a bar per sample.
+        state.zoom_performance_history(false);
+        assert_eq!(
+            state.performance_history_view.time_window,
+            Some(TimeWindow::FifteenMinutes)
+        );
+        for _ in 0..3 {
+            state.zoom_performance_history(true);
+        }
+        assert_eq!(state.performance_history_view, HistoryView::default());
+    }
+
+    #[test]
+    fn alerts_clear_only_well_below_their_thresholds() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        let wake = 3;
+        state.set_performance_alert_threshold(wake, Some(AlertThreshold::new(5.0)));
+        let record = |state: &mut AppState, wake_ms| {
+            state.toast = None;
+            state.record_performance(PerformanceSample {
+                wake_ms,
+                ..PerformanceSample::default()
+            });
+            (state.performance_alerts_raised[wake], state.toast.is_some())
+        };
+        assert_eq!(record(&mut state, 5.0), (false, false));
+        assert_eq!(record(&mut state, 5.5), (true, true));
+        // Dipping just under the threshold and back doesn't raise the alert again.
+        assert_eq!(record(&mut state, 4.5), (true, false));
+        assert_eq!(record(&mut state, 6.0), (true, false));
+        assert_eq!(record(&mut state, 3.0), (false, false));
+        assert_eq!(record(&mut state, 5.5), (true, true));
+        assert!(state
+            .toast
+            .unwrap()
+            .message
+            .ends_with("Wake time above 5.00ms"));
+    }
+
+    #[test]
+    fn notifications_keep_their_highlight_as_they_come_and_go() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::tiny()));
+        let messages = |state: &AppState| -> Vec<String> {
+            state
+                .notifications
+                .iter()
+                .map(|notification| notification.message.clone())
+                .collect()
+        };
+        for index in 0..3 {
+            state.notify(NotificationKind::Export, index.to_string());
+        }
+        assert_eq!(state.unread_notification_count, 3);
+        assert_eq!(state.toast.as_ref().unwrap().message, "2");
+
+        // The newest is highlighted until another comes in above it.
+        state.notify(NotificationKind::Alert, "3".to_owned());
+        assert_eq!(state.selected_notification, 3);
+        state.selected_notification = 1;
+        state.dismiss_notification();
+        assert_eq!(messages(&state), ["0", "1", "3"]);
+        assert_eq!(state.selected_notification, 1);
+        state.selected_notification = 2;
+        state.dismiss_notification();
+        assert_eq!(messages(&state), ["1", "3"]);
+        assert_eq!(state.selected_notification, 1);
+
+        // The oldest go first once there are too many.
+        for index in 0..NOTIFICATION_LIMIT {
+            state.notify(NotificationKind::Connection, index.to_string());
+        }
+        assert_eq!(state.notifications.len(), NOTIFICATION_LIMIT);
+        assert_eq!(state.notifications[0].message, "0");
+        assert_eq!(state.unread_notification_count, NOTIFICATION_LIMIT);
+        state.clear_notifications();
+        assert!(state.notifications.is_empty());
+        assert_eq!(state.unread_notification_count, 0);
+    }
+
+    #[test]
+    fn cached_labels_are_only_written_again_when_their_key_changes() {
+        let mut label = CachedLabel::default();
+        let mut writes = 0;
+        for &count in &[3, 3, 4, 4] {
+            label.update(count, |label| {
+                writes += 1;
+                label.push_str(&count.to_string())
+            });
+            assert_eq!(label.label(), count.to_string());
+        }
+        assert_eq!(writes, 2);
+    }
+
+    #[test]
+    fn task_counts_match_the_tasks_each_tab_shows() {
+        let mut state = AppState::new(fixtures::snapshot(fixtures::typical()));
+        state.task_name_filter = Some("handler".to_owned());
+        for &tasks_tab in &TasksTab::ALL {
+            let shown = visible_tasks(
+                &state.snapshot.tasks,
+                tasks_tab,
+                &state.bookmarked_task_ids,
+                state.task_name_filter.as_deref(),
+                None,
+            );
+            assert_eq!(state.task_count(tasks_tab), shown.len());
+        }
+    }
+}
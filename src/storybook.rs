@@ -0,0 +1,768 @@
+//! Each widget on its own, in a few combinations of its props, for developing and reviewing
+//! widgets without arranging the whole app around them. `tokio-console-mocks storybook` shows
+//! one story at a time, at the size the story asks for, and the arrow keys flip through them.
+
+use crate::format;
+use crate::theme::Theme;
+use crossterm::event::{KeyCode, KeyEvent};
+use std::time::Duration;
+use tui::backend::Backend;
+use tui::buffer::Buffer;
+use tui::layout::{Alignment, Rect};
+use tui::style::{Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph, Widget};
+use tui::Frame;
+use turbowish_widgets::{
+    BarChart, BigText, BorderSet, BoxFrame, Canvas, CanvasSymbols, CodeStyles, CodeView, Graph,
+    GraphNode, GraphSeries, GraphView, MainVisibility, Markdown, MarkdownStyles, Powerline,
+    PowerlineDirection, Ring, ScrollState, Scrollbar, SegmentedControl, TextInput, TextInputState,
+    TimeRuler, Timeline, TimelineRow, CHART_SYMBOLS_ASCII, CHART_SYMBOLS_BLOCKS,
+    CHART_SYMBOLS_BRAILLE, SCROLLBAR_SYMBOLS_ASCII, SCROLLBAR_SYMBOLS_NERD_FONT,
+};
+
+static STORYBOOK_TITLE_LABEL: &'static str = "Storybook";
+static STORYBOOK_KEYS_LABEL: &'static str = "← → story · ↑ ↓ widget · q quit";
+static SEGMENT_LABELS: [&'static str; 4] = ["All", "Running", "Sleeping", "Deadlocked"];
+static POWERLINE_LABELS: [&'static str; 4] = ["Tokio", "my_app (412)", "1 runtime", "4 workers"];
+static TEXT_INPUT_PLACEHOLDER: &'static str = "Filter by name";
+static TEXT_INPUT_LONG_TEXT: &'static str = "connection-handler-for-the-upstream-pool";
+static CHART_SAMPLES: [f32; 40] = [
+    2.0, 2.5, 3.1, 2.8, 3.6, 4.2, 3.9, 4.8, 5.5, 5.1, 4.4, 3.8, 3.2, 3.5, 4.1, 4.9, 6.2, 7.4, 8.1,
+    7.2, 6.0, 5.2, 4.6, 4.0, 3.4, 2.9, 2.6, 3.0, 3.7, 4.5, 5.3, 5.9, 5.4, 4.7, 4.1, 3.6, 3.3, 3.9,
+    4.6, 5.0,
+];
+static GRAPH_SAMPLES: [f32; 40] = [
+    1.0, 1.2, 1.1, 1.4, 1.8, 2.2, 2.0, 1.7, 1.5, 1.6, 2.1, 2.7, 3.2, 3.0, 2.6, 2.2, 1.9, 1.8, 2.0,
+    2.4, 2.9, 3.5, 3.9, 3.6, 3.1, 2.7, 2.5, 2.3, 2.2, 2.5, 2.8, 3.0, 2.9, 2.6, 2.4, 2.3, 2.5, 2.8,
+    3.1, 3.3,
+];
+static TIME_RULER_NOW_LABEL: &'static str = "now";
+static BIG_TEXT: &'static str = "12.5ms";
+static CODE_SNIPPET: &'static str = "// Accept connections until shutdown.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let listener = TcpListener::bind(\"0.0.0.0:6669\").await?;
+    loop {
+        let (socket, _) = listener.accept().await?;
+        tokio::spawn(handle_connection(socket));
+    }
+}";
+const CODE_SNIPPET_FIRST_LINE: u32 = 11;
+const CODE_SNIPPET_SPAWN_LINE: u32 = 17;
+static MARKDOWN_SOURCE: &'static str = "# Deadlock
+Tasks **712**, **713**, and **714** wait on each other:
+- `cache-refresher` holds `profile_cache`
+- `session-reaper` holds `session_index`
+1. Release the locks in a fixed order.";
+static GRAPH_VIEW_LABELS: [&'static str; 6] = [
+    "cache-refresher",
+    "Mutex profile_cache",
+    "audit-writer",
+    "Mutex audit_log",
+    "session-reaper",
+    "RwLock session_index",
+];
+const TIMELINE_COLUMN_SPAN: Duration = Duration::from_secs(1);
+
+type DrawStory = fn(&Theme, Rect, &mut Buffer);
+
+/// A widget with one combination of its props.
+pub struct Story {
+    /// The widget's type.
+    pub widget: &'static str,
+    /// What's particular about its props in this story.
+    pub variant: &'static str,
+    /// The columns and rows the story is drawn in.
+    pub size: (u16, u16),
+    draw: DrawStory,
+}
+
+impl Story {
+    /// Draws the widget into `area` of `buffer`, in `theme`'s colors.
+    pub fn draw(&self, theme: &Theme, area: Rect, buffer: &mut Buffer) {
+        (self.draw)(theme, area, buffer)
+    }
+}
+
+/// Every story, grouped by widget.
+pub static STORIES: [Story; 38] = [
+    Story {
+        widget: "SegmentedControl",
+        variant: "first selected",
+        size: (40, 1),
+        draw: |theme, area, buffer| segmented_control(theme, 0).render(area, buffer),
+    },
+    Story {
+        widget: "SegmentedControl",
+        variant: "middle selected",
+        size: (40, 1),
+        draw: |theme, area, buffer| segmented_control(theme, 2).render(area, buffer),
+    },
+    Story {
+        widget: "SegmentedControl",
+        variant: "last selected, cut off",
+        size: (24, 1),
+        draw: |theme, area, buffer| segmented_control(theme, 3).render(area, buffer),
+    },
+    Story {
+        widget: "Powerline",
+        variant: "left to right",
+        size: (60, 1),
+        draw: |theme, area, buffer| powerline(theme).render(area, buffer),
+    },
+    Story {
+        widget: "Powerline",
+        variant: "right to left",
+        size: (60, 1),
+        draw: |theme, area, buffer| {
+            powerline(theme)
+                .direction(PowerlineDirection::RightToLeft)
+                .render(area, buffer)
+        },
+    },
+    Story {
+        widget: "Powerline",
+        variant: "no main segment",
+        size: (60, 1),
+        draw: |theme, area, buffer| {
+            powerline(theme)
+                .main_visibility(MainVisibility::Invisible)
+                .render(area, buffer)
+        },
+    },
+    Story {
+        widget: "Powerline",
+        variant: "left to right, cut off",
+        size: (24, 1),
+        draw: |theme, area, buffer| powerline(theme).render(area, buffer),
+    },
+    Story {
+        widget: "Powerline",
+        variant: "right to left, cut off",
+        size: (24, 1),
+        draw: |theme, area, buffer| {
+            powerline(theme)
+                .direction(PowerlineDirection::RightToLeft)
+                .render(area, buffer)
+        },
+    },
+    Story {
+        widget: "BoxFrame",
+        variant: "rounded, every label",
+        size: (40, 8),
+        draw: |theme, area, buffer| {
+            box_frame(theme, BorderSet::Rounded)
+                .aux_label("updated 1s ago")
+                .footer_label("esc close")
+                .render(area, buffer)
+        },
+    },
+    Story {
+        widget: "BoxFrame",
+        variant: "square, centered label",
+        size: (40, 8),
+        draw: |theme, area, buffer| {
+            box_frame(theme, BorderSet::Square)
+                .label_alignment(Alignment::Center)
+                .render(area, buffer)
+        },
+    },
+    Story {
+        widget: "BoxFrame",
+        variant: "double",
+        size: (40, 8),
+        draw: |theme, area, buffer| box_frame(theme, BorderSet::Double).render(area, buffer),
+    },
+    Story {
+        widget: "BoxFrame",
+        variant: "thick",
+        size: (40, 8),
+        draw: |theme, area, buffer| box_frame(theme, BorderSet::Thick).render(area, buffer),
+    },
+    Story {
+        widget: "BoxFrame",
+        variant: "ASCII, labels cut off",
+        size: (16, 4),
+        draw: |theme, area, buffer| {
+            box_frame(theme, BorderSet::Ascii)
+                .aux_label("updated 1s ago")
+                .render(area, buffer)
+        },
+    },
+    Story {
+        widget: "Scrollbar",
+        variant: "at the top",
+        size: (1, 12),
+        draw: |theme, area, buffer| scrollbar(theme, area, buffer, 0, false),
+    },
+    Story {
+        widget: "Scrollbar",
+        variant: "halfway",
+        size: (1, 12),
+        draw: |theme, area, buffer| scrollbar(theme, area, buffer, 45, false),
+    },
+    Story {
+        widget: "Scrollbar",
+        variant: "at the bottom, ASCII",
+        size: (1, 12),
+        draw: |theme, area, buffer| scrollbar(theme, area, buffer, 90, true),
+    },
+    Story {
+        widget: "TextInput",
+        variant: "empty",
+        size: (30, 1),
+        draw: |theme, area, buffer| text_input(theme, area, buffer, "", 0),
+    },
+    Story {
+        widget: "TextInput",
+        variant: "cursor in the middle",
+        size: (30, 1),
+        draw: |theme, area, buffer| text_input(theme, area, buffer, "conn", 2),
+    },
+    Story {
+        widget: "TextInput",
+        variant: "scrolled",
+        size: (30, 1),
+        draw: |theme, area, buffer| text_input(theme, area, buffer, TEXT_INPUT_LONG_TEXT, 0),
+    },
+    Story {
+        widget: "BarChart",
+        variant: "braille, with an axis",
+        size: (30, 8),
+        draw: |theme, area, buffer| {
+            bar_chart(theme)
+                .symbols(CHART_SYMBOLS_BRAILLE)
+                .axis(theme.performance_axis_color)
+                .render(area, buffer)
+        },
+    },
+    Story {
+        widget: "BarChart",
+        variant: "blocks, with thresholds",
+        size: (40, 8),
+        draw: |theme, area, buffer| {
+            let thresholds = [
+                (5.0, theme.performance_graph_warning_color),
+                (7.0, theme.performance_graph_critical_color),
+            ];
+            bar_chart(theme)
+                .symbols(CHART_SYMBOLS_BLOCKS)
+                .thresholds(&thresholds)
+                .render(area, buffer)
+        },
+    },
+    Story {
+        widget: "BarChart",
+        variant: "ASCII, with a baseline",
+        size: (40, 6),
+        draw: |theme, area, buffer| {
+            bar_chart(theme)
+                .symbols(CHART_SYMBOLS_ASCII)
+                .baseline(4.0)
+                .axis(theme.performance_axis_color)
+                .render(area, buffer)
+        },
+    },
+    Story {
+        widget: "Graph",
+        variant: "one series",
+        size: (30, 8),
+        draw: |theme, area, buffer| {
+            let series = [GraphSeries::new(
+                "poll",
+                &GRAPH_SAMPLES,
+                theme.performance_graph_color,
+            )];
+            Graph::new(&series, 0.0, 4.0).render(area, buffer)
+        },
+    },
+    Story {
+        widget: "Graph",
+        variant: "two series, axis and legend",
+        size: (40, 10),
+        draw: |theme, area, buffer| {
+            let series = [
+                GraphSeries::new("poll", &GRAPH_SAMPLES, theme.performance_graph_color),
+                GraphSeries::new(
+                    "wake",
+                    &CHART_SAMPLES,
+                    theme.performance_graph_warning_color,
+                ),
+            ];
+            Graph::new(&series, 0.0, 9.0)
+                .axis(theme.performance_axis_color)
+                .legend(theme.performance_label)
+                .render(area, buffer)
+        },
+    },
+    Story {
+        widget: "Canvas",
+        variant: "braille",
+        size: (20, 8),
+        draw: |theme, area, buffer| canvas(theme, CanvasSymbols::Braille).render(area, buffer),
+    },
+    Story {
+        widget: "Canvas",
+        variant: "half blocks",
+        size: (20, 8),
+        draw: |theme, area, buffer| canvas(theme, CanvasSymbols::HalfBlocks).render(area, buffer),
+    },
+    Story {
+        widget: "Ring",
+        variant: "empty",
+        size: (9, 5),
+        draw: |theme, area, buffer| ring(theme, 0.0).render(area, buffer),
+    },
+    Story {
+        widget: "Ring",
+        variant: "two thirds",
+        size: (9, 5),
+        draw: |theme, area, buffer| ring(theme, 0.67).render(area, buffer),
+    },
+    Story {
+        widget: "Ring",
+        variant: "one row",
+        size: (9, 1),
+        draw: |theme, area, buffer| ring(theme, 0.4).render(area, buffer),
+    },
+    Story {
+        widget: "BigText",
+        variant: "left aligned",
+        size: (30, 3),
+        draw: |theme, area, buffer| big_text(theme, Alignment::Left).render(area, buffer),
+    },
+    Story {
+        widget: "BigText",
+        variant: "centered",
+        size: (30, 3),
+        draw: |theme, area, buffer| big_text(theme, Alignment::Center).render(area, buffer),
+    },
+    Story {
+        widget: "BigText",
+        variant: "too short to be big",
+        size: (30, 2),
+        draw: |theme, area, buffer| big_text(theme, Alignment::Right).render(area, buffer),
+    },
+    Story {
+        widget: "Timeline",
+        variant: "with a ruler",
+        size: (50, 6),
+        draw: |theme, area, buffer| timeline(theme, area, buffer, Duration::ZERO),
+    },
+    Story {
+        widget: "Timeline",
+        variant: "panned back",
+        size: (50, 6),
+        draw: |theme, area, buffer| timeline(theme, area, buffer, Duration::from_secs(20)),
+    },
+    Story {
+        widget: "TimeRuler",
+        variant: "one second per column",
+        size: (50, 1),
+        draw: |theme, area, buffer| {
+            TimeRuler::new(TIMELINE_COLUMN_SPAN, &time_ago_label)
+                .style(Style::default().fg(theme.performance_axis_color))
+                .render(area, buffer)
+        },
+    },
+    Story {
+        widget: "GraphView",
+        variant: "a cycle",
+        size: (40, 18),
+        draw: |theme, area, buffer| graph_view(theme).render(area, buffer),
+    },
+    Story {
+        widget: "CodeView",
+        variant: "marked line",
+        size: (64, 9),
+        draw: |theme, area, buffer| {
+            code_view(theme)
+                .mark_line(CODE_SNIPPET_SPAWN_LINE)
+                .render(area, buffer)
+        },
+    },
+    Story {
+        widget: "Markdown",
+        variant: "every kind of block",
+        size: (40, 8),
+        draw: |theme, area, buffer| {
+            let styles = MarkdownStyles {
+                text: Style::default().fg(theme.popup_text_color),
+                heading: Style::default()
+                    .fg(theme.title_main_color)
+                    .add_modifier(Modifier::BOLD),
+                bold: Style::default().add_modifier(Modifier::BOLD),
+                code: Style::default().fg(theme.code_literal_color),
+                bullet: Style::default().fg(theme.popup_minor_color),
+            };
+            Markdown::new(MARKDOWN_SOURCE, styles).render(area, buffer)
+        },
+    },
+];
+
+/// Which story is showing.
+pub struct Storybook {
+    index: usize,
+}
+
+impl Storybook {
+    pub fn new() -> Storybook {
+        Storybook { index: 0 }
+    }
+
+    pub fn story(&self) -> &'static Story {
+        &STORIES[self.index]
+    }
+
+    /// Left and right go to the previous and next story, and up and down to the first story of
+    /// the previous and next widget, wrapping around at the ends. Returns whether the key asks
+    /// to quit.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let count = STORIES.len();
+        match key.code {
+            KeyCode::Right => self.index = (self.index + 1) % count,
+            KeyCode::Left => self.index = (self.index + count - 1) % count,
+            KeyCode::Down => {
+                let widget = self.story().widget;
+                self.index = (1..=count)
+                    .map(|offset| (self.index + offset) % count)
+                    .find(|&index| STORIES[index].widget != widget)
+                    .unwrap_or(self.index);
+            }
+            KeyCode::Up => {
+                let first = first_story_of_widget(self.index);
+                self.index = first_story_of_widget((first + count - 1) % count);
+            }
+            KeyCode::Char('q') | KeyCode::Esc => return true,
+            _ => {}
+        }
+        false
+    }
+
+    /// Draws the story in the middle of the screen, outlined so that its bounds show, under a
+    /// header naming it.
+    pub fn draw<B>(&self, frame: &mut Frame<B>, theme: &Theme)
+    where
+        B: Backend,
+    {
+        let story = self.story();
+        let screen = frame.size();
+        let header = Spans::from(vec![
+            Span::styled(
+                format!(" {} ", STORYBOOK_TITLE_LABEL),
+                Style::default()
+                    .fg(theme.title_sub_sub_fg)
+                    .bg(theme.title_main_color)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(" {} ", story.widget),
+                Style::default()
+                    .fg(theme.title_main_color)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(
+                    "{} · {}×{} · {}/{}",
+                    story.variant,
+                    story.size.0,
+                    story.size.1,
+                    self.index + 1,
+                    STORIES.len()
+                ),
+                Style::default().fg(theme.box_text_color),
+            ),
+        ]);
+        frame.render_widget(Paragraph::new(header), Rect::new(0, 0, screen.width, 1));
+        if screen.height > 1 {
+            frame.render_widget(
+                Paragraph::new(STORYBOOK_KEYS_LABEL)
+                    .style(Style::default().fg(theme.popup_minor_color)),
+                Rect::new(0, screen.height - 1, screen.width, 1),
+            );
+        }
+
+        // Room for the outline, between the header and the keys.
+        let stage = Rect::new(0, 1, screen.width, screen.height.saturating_sub(2));
+        let width = story.size.0.min(stage.width.saturating_sub(2));
+        let height = story.size.1.min(stage.height.saturating_sub(2));
+        let area = Rect::new(
+            stage.x + (stage.width - width) / 2,
+            stage.y + (stage.height - height) / 2,
+            width,
+            height,
+        );
+        if area.x > 0 && area.y > 0 {
+            frame.render_widget(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.performance_axis_color)),
+                Rect::new(area.x - 1, area.y - 1, width + 2, height + 2),
+            );
+        }
+        frame.render_widget(StoryWidget { story, theme }, area);
+    }
+}
+
+// Frames only hand out their buffers to widgets.
+struct StoryWidget<'a> {
+    story: &'a Story,
+    theme: &'a Theme,
+}
+
+impl<'a> Widget for StoryWidget<'a> {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        self.story.draw(self.theme, area, buffer)
+    }
+}
+
+impl Default for Storybook {
+    fn default() -> Storybook {
+        Storybook::new()
+    }
+}
+
+fn first_story_of_widget(index: usize) -> usize {
+    let widget = STORIES[index].widget;
+    (0..=index)
+        .rev()
+        .take_while(|&index| STORIES[index].widget == widget)
+        .last()
+        .unwrap_or(index)
+}
+
+fn segmented_control(theme: &Theme, selected_index: u32) -> SegmentedControl<'static> {
+    SegmentedControl::new(
+        &SEGMENT_LABELS,
+        selected_index,
+        theme.focus_bg,
+        theme.tasks_box_fg,
+    )
+}
+
+fn powerline(theme: &Theme) -> Powerline<'static> {
+    Powerline::new(&POWERLINE_LABELS)
+        .main_color(theme.title_main_color)
+        .sub_color(theme.title_sub_color)
+        .sub_sub_colors(theme.title_sub_sub_bg, theme.title_sub_sub_fg)
+        .sub_separator_color(theme.title_sub_separator_color)
+}
+
+fn box_frame(theme: &Theme, border_set: BorderSet) -> BoxFrame<'static> {
+    BoxFrame::new("Performance")
+        .border_color(theme.performance_box_fg)
+        .text_color(theme.box_text_color)
+        .border_set(border_set)
+}
+
+// A hundred lines in a view as tall as the scrollbar, scrolled down `offset` of them.
+fn scrollbar(theme: &Theme, area: Rect, buffer: &mut Buffer, offset: isize, ascii: bool) {
+    let mut state = ScrollState::default();
+    state.set_lengths(100, area.height as usize);
+    state.scroll_by(offset);
+    let symbols = if ascii {
+        &SCROLLBAR_SYMBOLS_ASCII
+    } else {
+        &SCROLLBAR_SYMBOLS_NERD_FONT
+    };
+    Scrollbar::new(&mut state, symbols, theme.scrollbar_color).render(area, buffer)
+}
+
+// `text` typed in, with the cursor moved back `cursor_back` characters from the end.
+fn text_input(theme: &Theme, area: Rect, buffer: &mut Buffer, text: &str, cursor_back: isize) {
+    let mut state = TextInputState::default();
+    for character in text.chars() {
+        state.insert(character);
+    }
+    state.move_cursor(-cursor_back);
+    TextInput::new(
+        &state,
+        TEXT_INPUT_PLACEHOLDER,
+        theme.tasks_filter_fg,
+        theme.popup_minor_color,
+    )
+    .render(area, buffer)
+}
+
+fn bar_chart(theme: &Theme) -> BarChart<'static> {
+    BarChart::new(&CHART_SAMPLES, 0.0, 9.0, theme.performance_graph_color)
+}
+
+fn canvas(theme: &Theme, symbols: CanvasSymbols) -> Canvas {
+    let (dots_x, dots_y) = symbols.dots_per_cell();
+    let (width, height) = (20 * dots_x, 8 * dots_y);
+    Canvas::new(symbols)
+        .rect(Rect::new(0, 0, width, height), theme.performance_axis_color)
+        .line(
+            (2, height - 3),
+            (width - 3, 2),
+            theme.performance_graph_color,
+        )
+        .filled_rect(
+            Rect::new(width / 2, height / 2, width / 4, height / 4),
+            theme.performance_graph_warning_color,
+        )
+        .point(
+            (width / 4, height / 4),
+            theme.performance_graph_critical_color,
+        )
+}
+
+fn ring(theme: &Theme, ratio: f32) -> Ring {
+    Ring::new(ratio, theme.performance_graph_color).track_color(theme.performance_axis_color)
+}
+
+fn big_text(theme: &Theme, alignment: Alignment) -> BigText<'static> {
+    BigText::new(BIG_TEXT)
+        .style(Style::default().fg(theme.performance_numeric_color))
+        .alignment(alignment)
+}
+
+fn timeline(theme: &Theme, area: Rect, buffer: &mut Buffer, offset: Duration) {
+    let secs = Duration::from_secs;
+    let row = |name: &'static str, started_ago: Duration| {
+        TimelineRow::new(
+            Spans::from(Span::styled(
+                name,
+                Style::default().fg(theme.tasks_table_name_cell_color),
+            )),
+            started_ago,
+        )
+    };
+    let rows = vec![
+        row("main", secs(90)).busy_periods(vec![(secs(90), secs(85)), (secs(30), secs(28))]),
+        row("connection-handler", secs(40))
+            .busy_periods(vec![(secs(40), secs(36)), (secs(12), secs(4))]),
+        row("db-pool-worker", secs(25)).busy_periods(vec![(secs(20), secs(18))]),
+        row("cache-refresher", secs(8)),
+    ];
+    Timeline::new(rows, TIMELINE_COLUMN_SPAN)
+        .offset(offset)
+        .header(Spans::from("Task"))
+        .ruler(
+            &time_ago_label,
+            Style::default().fg(theme.performance_axis_color),
+        )
+        .label_width(20)
+        .styles(
+            Style::default().fg(theme.tasks_table_minor_cell_color),
+            Style::default().fg(theme.tasks_table_status_running_color),
+        )
+        .render(area, buffer)
+}
+
+fn time_ago_label(ago: Duration) -> String {
+    if ago.is_zero() {
+        return TIME_RULER_NOW_LABEL.to_owned();
+    }
+    format::duration_text(ago)
+}
+
+// Three tasks, each holding a resource the next one waits on.
+fn graph_view(theme: &Theme) -> GraphView<'static> {
+    let nodes = GRAPH_VIEW_LABELS
+        .iter()
+        .enumerate()
+        .map(|(index, &label)| {
+            let color = if index % 2 == 0 {
+                theme.tasks_table_status_deadlocked_color
+            } else {
+                theme.tasks_table_attribute_value_cell_color
+            };
+            GraphNode::new(Spans::from(label)).style(Style::default().fg(color))
+        })
+        .collect();
+    let edges = vec![(1, 0), (0, 3), (3, 2), (2, 5), (5, 4), (4, 1)];
+    GraphView::new(nodes, edges).edge_style(Style::default().fg(theme.performance_axis_color))
+}
+
+fn code_view(theme: &Theme) -> CodeView<'static> {
+    let styles = CodeStyles {
+        text: Style::default().fg(theme.popup_text_color),
+        keyword: Style::default().fg(theme.code_keyword_color),
+        type_name: Style::default().fg(theme.code_type_color),
+        literal: Style::default().fg(theme.code_literal_color),
+        comment: Style::default().fg(theme.code_comment_color),
+        macro_name: Style::default().fg(theme.code_macro_color),
+        line_number: Style::default().fg(theme.popup_minor_color),
+        marked_line: Style::default().bg(theme.popup_selected_bg),
+    };
+    CodeView::new(CODE_SNIPPET, CODE_SNIPPET_FIRST_LINE, styles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot_tests::buffer_to_string;
+    use crate::theme::THEMES;
+    use crossterm::event::KeyModifiers;
+    use tui::backend::TestBackend;
+    use tui::Terminal;
+
+    fn press(storybook: &mut Storybook, code: KeyCode) -> bool {
+        storybook.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn each_widgets_stories_are_together() {
+        let mut widgets: Vec<&str> = STORIES.iter().map(|story| story.widget).collect();
+        widgets.dedup();
+        for widget in &widgets {
+            assert_eq!(widgets.iter().filter(|&name| name == widget).count(), 1);
+        }
+    }
+
+    #[test]
+    fn arrows_flip_through_stories_and_widgets() {
+        let mut storybook = Storybook::new();
+        press(&mut storybook, KeyCode::Left);
+        assert_eq!(storybook.story().widget, "Markdown");
+        press(&mut storybook, KeyCode::Right);
+        press(&mut storybook, KeyCode::Right);
+        assert_eq!(storybook.story().variant, "middle selected");
+
+        press(&mut storybook, KeyCode::Down);
+        assert_eq!(storybook.story().widget, "Powerline");
+        assert_eq!(storybook.story().variant, "left to right");
+        press(&mut storybook, KeyCode::Down);
+        assert_eq!(storybook.story().widget, "BoxFrame");
+        press(&mut storybook, KeyCode::Right);
+        press(&mut storybook, KeyCode::Up);
+        assert_eq!(storybook.story().widget, "Powerline");
+        assert_eq!(storybook.story().variant, "left to right");
+        press(&mut storybook, KeyCode::Up);
+        press(&mut storybook, KeyCode::Up);
+        assert_eq!(storybook.story().widget, "Markdown");
+
+        assert!(!press(&mut storybook, KeyCode::Char('x')));
+        assert!(press(&mut storybook, KeyCode::Char('q')));
+    }
+
+    // Big enough for every story, then too small for most of them.
+    #[test]
+    fn every_story_draws_at_any_size() {
+        for &(width, height) in &[(80, 24), (20, 6), (1, 1)] {
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+            let mut storybook = Storybook::new();
+            for _ in 0..STORIES.len() {
+                for &theme in &THEMES {
+                    terminal.draw(|frame| storybook.draw(frame, theme)).unwrap();
+                }
+                press(&mut storybook, KeyCode::Right);
+            }
+        }
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal
+            .draw(|frame| Storybook::new().draw(frame, THEMES[0]))
+            .unwrap();
+        let screen = buffer_to_string(terminal.backend().buffer());
+        assert!(
+            screen.contains("SegmentedControl first selected"),
+            "{}",
+            screen
+        );
+        assert!(screen.contains("Deadlocked"), "{}", screen);
+    }
+}
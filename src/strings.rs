@@ -0,0 +1,272 @@
+//! The words the tasks and performance panes are labelled with, one catalog per language.
+//!
+//! Glyphs, such as the icons in front of the tabs' names, aren't words and stay in the crate
+//! root. Labels that have a value in them are templates, with `{}` where the value goes, since
+//! languages don't agree on where that is.
+
+use std::env;
+
+/// Where a value goes in a template.
+static PLACEHOLDER: &'static str = "{}";
+// Where the locale comes from, in the order POSIX asks for.
+static LOCALE_VARS: [&'static str; 3] = ["LC_ALL", "LC_MESSAGES", "LANG"];
+
+/// Every label in one language.
+pub struct Strings {
+    /// The language the catalog is in, as the first part of a locale such as `es_ES.UTF-8`.
+    pub language: &'static str,
+    pub tasks: &'static str,
+    /// Indexed by `TasksTab`.
+    pub tasks_tabs: [&'static str; 5],
+    /// For one task, and for any other number of them.
+    pub tasks_footer_task_count: [&'static str; 2],
+    pub tasks_footer_filtered_count: &'static str,
+    pub tasks_grouped: &'static str,
+    pub tasks_following: &'static str,
+    /// Takes the name the tasks are filtered to.
+    pub tasks_name_filter: &'static str,
+    /// In place of the graph of who waits on what, when none of the tasks shown hold or wait on
+    /// anything.
+    pub tasks_graph_empty: &'static str,
+    /// Indexed like `TASKS_TABLE_COLUMNS`. Columns with no header are only buttons.
+    pub tasks_table_columns: [&'static str; 11],
+    pub performance: &'static str,
+    /// Indexed like `PERFORMANCE_METRICS`.
+    pub performance_metrics: [&'static str; 6],
+    /// Takes how long ago the snapshot was taken.
+    pub performance_updated: &'static str,
+    /// In front of `performance_updated`, when the snapshot is old enough that updates have gone
+    /// missing.
+    pub performance_stale: &'static str,
+    /// Takes how far back the history is panned.
+    pub performance_history_offset: &'static str,
+    /// Takes how long each bar of the history covers.
+    pub performance_history_zoom: &'static str,
+    /// Takes the time window, from `performance_time_windows`.
+    pub performance_time_window: &'static str,
+    /// Indexed by `TimeWindow`.
+    pub performance_time_windows: [&'static str; 4],
+    pub performance_all_runtimes: &'static str,
+    pub performance_screen_keys: &'static str,
+    pub performance_comparison: &'static str,
+    /// The newest value, then the minimum, maximum, and average of the ones on screen.
+    pub performance_readouts: [&'static str; 4],
+    pub performance_chart_newest: &'static str,
+    /// Takes how long before the newest sample a point on the time axis is.
+    pub performance_chart_ago: &'static str,
+    pub performance_settings: &'static str,
+    pub performance_settings_keys: &'static str,
+    /// Takes the metric, then the value it went over.
+    pub performance_alert_above: &'static str,
+}
+
+pub static STRINGS_ENGLISH: Strings = Strings {
+    language: "en",
+    tasks: "Tasks",
+    tasks_tabs: ["All", "Running", "Sleeping", "Deadlocked", "Bookmarked"],
+    tasks_footer_task_count: ["task", "tasks"],
+    tasks_footer_filtered_count: "filtered:",
+    tasks_grouped: "grouped by name",
+    tasks_following: "following newest",
+    tasks_name_filter: "named {}",
+    tasks_graph_empty: "No task shown holds or waits on anything",
+    tasks_table_columns: [
+        "",
+        "ID",
+        "Name",
+        "State",
+        "Run %",
+        "Poll",
+        "Wake",
+        "Busy",
+        "Idle",
+        "Lifetime",
+        "Attributes",
+    ],
+    performance: "Performance",
+    performance_metrics: [
+        "Runtime",
+        "Sched. depth",
+        "Poll time",
+        "Wake time",
+        "Steal rate",
+        "Park time",
+    ],
+    performance_updated: "updated {} ago",
+    performance_stale: "stale",
+    performance_history_offset: "{} back",
+    performance_history_zoom: "{} per bar",
+    performance_time_window: "{} window",
+    performance_time_windows: ["1m", "5m", "15m", "all"],
+    performance_all_runtimes: "all",
+    performance_screen_keys:
+        "[ ] pan · - + zoom · w window · d compare · P present · 0 newest · m metrics · e export · esc close",
+    performance_comparison: "vs prev",
+    performance_readouts: ["now", "min", "max", "avg"],
+    performance_chart_newest: "now",
+    performance_chart_ago: "{} ago",
+    performance_settings: "Metrics",
+    performance_settings_keys: "space show/hide · esc close",
+    performance_alert_above: "{} above {}",
+};
+
+pub static STRINGS_SPANISH: Strings = Strings {
+    language: "es",
+    tasks: "Tareas",
+    tasks_tabs: ["Todas", "En curso", "Dormidas", "Bloqueadas", "Marcadas"],
+    tasks_footer_task_count: ["tarea", "tareas"],
+    tasks_footer_filtered_count: "filtradas:",
+    tasks_grouped: "agrupadas por nombre",
+    tasks_following: "siguiendo la más nueva",
+    tasks_name_filter: "llamadas {}",
+    tasks_graph_empty: "Ninguna tarea mostrada tiene ni espera nada",
+    tasks_table_columns: [
+        "",
+        "ID",
+        "Nombre",
+        "Estado",
+        "% ejec.",
+        "Sondeo",
+        "Aviso",
+        "Ocupada",
+        "Inactiva",
+        "Vida",
+        "Atributos",
+    ],
+    performance: "Rendimiento",
+    performance_metrics: [
+        "Ejecución",
+        "Prof. cola",
+        "T. sondeo",
+        "T. aviso",
+        "Robos",
+        "T. reposo",
+    ],
+    performance_updated: "actualizado hace {}",
+    performance_stale: "desactualizado",
+    performance_history_offset: "{} atrás",
+    performance_history_zoom: "{} por barra",
+    performance_time_window: "ventana de {}",
+    performance_time_windows: ["1m", "5m", "15m", "todo"],
+    performance_all_runtimes: "todos",
+    performance_screen_keys: "[ ] desplazar · - + zoom · w ventana · d comparar · P presentar · 0 reciente · m métricas · e exportar · esc cerrar",
+    performance_comparison: "vs ant.",
+    performance_readouts: ["ahora", "mín", "máx", "media"],
+    performance_chart_newest: "ahora",
+    performance_chart_ago: "hace {}",
+    performance_settings: "Métricas",
+    performance_settings_keys: "espacio mostrar/ocultar · esc cerrar",
+    performance_alert_above: "{} por encima de {}",
+};
+
+/// Every catalog. The first is the one used for languages that don't have their own.
+pub static CATALOGS: [&'static Strings; 2] = [&STRINGS_ENGLISH, &STRINGS_SPANISH];
+
+/// The catalog for `locale`, such as `es_ES.UTF-8` or `es`, or the English one if there isn't
+/// one in its language.
+pub fn catalog(locale: &str) -> &'static Strings {
+    let language = locale
+        .split(['_', '-', '.', '@'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    CATALOGS
+        .iter()
+        .find(|strings| strings.language == language)
+        .copied()
+        .unwrap_or(CATALOGS[0])
+}
+
+/// The locale the environment asks for, if it asks for one.
+pub fn env_locale() -> Option<String> {
+    locale_from(|name| env::var(name).ok())
+}
+
+fn locale_from<F>(var: F) -> Option<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    LOCALE_VARS
+        .iter()
+        .filter_map(|name| var(name))
+        .find(|locale| !locale.is_empty())
+}
+
+/// `template` with `values` in place of its placeholders, in order. Placeholders past the last
+/// value are left empty.
+pub fn fill(template: &str, values: &[&str]) -> String {
+    let mut filled = String::new();
+    let mut parts = template.split(PLACEHOLDER);
+    filled.push_str(parts.next().unwrap_or_default());
+    for (index, part) in parts.enumerate() {
+        filled.push_str(values.get(index).copied().unwrap_or_default());
+        filled.push_str(part);
+    }
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locales_pick_their_language_or_english() {
+        assert_eq!(catalog("es_ES.UTF-8").language, "es");
+        assert_eq!(catalog("ES").language, "es");
+        assert_eq!(catalog("en_GB").language, "en");
+        assert_eq!(catalog("C").language, "en");
+        assert_eq!(catalog("").language, "en");
+
+        let locale = |vars: &[(&str, &str)]| {
+            let vars: Vec<_> = vars.to_vec();
+            locale_from(move |name| {
+                vars.iter()
+                    .find(|&&(var_name, _)| var_name == name)
+                    .map(|&(_, value)| value.to_owned())
+            })
+        };
+        assert_eq!(
+            locale(&[("LANG", "en_US.UTF-8"), ("LC_MESSAGES", "es_MX")]).as_deref(),
+            Some("es_MX")
+        );
+        assert_eq!(
+            locale(&[("LC_ALL", ""), ("LANG", "es")]).as_deref(),
+            Some("es")
+        );
+        assert_eq!(locale(&[]), None);
+    }
+
+    #[test]
+    fn templates_take_their_values_in_order() {
+        assert_eq!(
+            fill("{} above {}", &["Poll time", "10ms"]),
+            "Poll time above 10ms"
+        );
+        assert_eq!(fill("actualizado hace {}", &["3s"]), "actualizado hace 3s");
+        assert_eq!(fill("{} back", &[]), " back");
+    }
+
+    #[test]
+    fn every_template_has_its_placeholders() {
+        for strings in &CATALOGS {
+            let templates = [
+                (strings.tasks_name_filter, 1),
+                (strings.performance_updated, 1),
+                (strings.performance_history_offset, 1),
+                (strings.performance_history_zoom, 1),
+                (strings.performance_time_window, 1),
+                (strings.performance_chart_ago, 1),
+                (strings.performance_alert_above, 2),
+            ];
+            for &(template, placeholders) in &templates {
+                assert_eq!(
+                    template.matches(PLACEHOLDER).count(),
+                    placeholders,
+                    "{}: {}",
+                    strings.language,
+                    template
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Drawing each frame as one synchronized update, so that the terminal shows it all at once
+//! rather than tearing halfway through a full-screen redraw.
+//!
+//! Terminals that support synchronized output hold off on showing what they're sent between the
+//! begin and end sequences, and the rest ignore the sequences as a mode they don't know. The
+//! legacy Windows console prints them instead, so they're only written where escape sequences
+//! are understood.
+
+use std::io::{self, Write};
+use tui::backend::Backend;
+use tui::buffer::Cell;
+use tui::layout::Rect;
+
+static BEGIN_SYNCHRONIZED_UPDATE: &'static str = "\x1b[?2026h";
+static END_SYNCHRONIZED_UPDATE: &'static str = "\x1b[?2026l";
+
+/// A backend that begins a synchronized update with the first thing a frame draws or writes
+/// and ends it when the frame is flushed. Unless it's enabled, everything passes through as-is.
+///
+/// Put this inside the backends that write escape sequences of their own, such as
+/// `HyperlinkBackend`, so that their sequences are part of the update too. For the frame to
+/// reach the terminal in as few writes as it can, give the backend this wraps a buffered writer.
+pub struct SynchronizedBackend<B> {
+    backend: B,
+    enabled: bool,
+    in_update: bool,
+}
+
+impl<B> SynchronizedBackend<B>
+where
+    B: Backend + Write,
+{
+    pub fn new(backend: B, enabled: bool) -> SynchronizedBackend<B> {
+        SynchronizedBackend {
+            backend,
+            enabled,
+            in_update: false,
+        }
+    }
+
+    fn begin_update(&mut self) -> io::Result<()> {
+        if self.enabled && !self.in_update {
+            self.backend
+                .write_all(BEGIN_SYNCHRONIZED_UPDATE.as_bytes())?;
+            self.in_update = true;
+        }
+        Ok(())
+    }
+}
+
+impl<B> Backend for SynchronizedBackend<B>
+where
+    B: Backend + Write,
+{
+    fn draw<'a, I>(&mut self, content: I) -> Result<(), io::Error>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        self.begin_update()?;
+        self.backend.draw(content)
+    }
+
+    fn hide_cursor(&mut self) -> Result<(), io::Error> {
+        self.backend.hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> Result<(), io::Error> {
+        self.backend.show_cursor()
+    }
+
+    fn get_cursor(&mut self) -> Result<(u16, u16), io::Error> {
+        self.backend.get_cursor()
+    }
+
+    fn set_cursor(&mut self, x: u16, y: u16) -> Result<(), io::Error> {
+        self.backend.set_cursor(x, y)
+    }
+
+    fn clear(&mut self) -> Result<(), io::Error> {
+        self.backend.clear()
+    }
+
+    fn size(&self) -> Result<Rect, io::Error> {
+        self.backend.size()
+    }
+
+    fn flush(&mut self) -> Result<(), io::Error> {
+        if self.in_update {
+            self.backend.write_all(END_SYNCHRONIZED_UPDATE.as_bytes())?;
+            self.in_update = false;
+        }
+        Backend::flush(&mut self.backend)
+    }
+}
+
+/// Passes writes through, inside the frame's update.
+impl<B> Write for SynchronizedBackend<B>
+where
+    B: Backend + Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.begin_update()?;
+        self.backend.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Write::flush(&mut self.backend)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use tui::backend::CrosstermBackend;
+    use tui::widgets::Paragraph;
+    use tui::Terminal;
+
+    // Output the test can still read after handing it to the backend.
+    #[derive(Clone, Default)]
+    struct SharedOutput(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn draw_frames(enabled: bool, frame_count: usize) -> String {
+        let output = SharedOutput::default();
+        let backend = CrosstermBackend::new(output.clone());
+        let mut terminal = Terminal::new(SynchronizedBackend::new(backend, enabled)).unwrap();
+        // `CrosstermBackend` can't tell the size of a terminal it isn't drawing to.
+        terminal.resize(Rect::new(0, 0, 8, 2)).unwrap();
+        for index in 0..frame_count {
+            terminal
+                .draw(|frame| frame.render_widget(Paragraph::new(index.to_string()), frame.size()))
+                .unwrap();
+        }
+        let output = String::from_utf8(output.0.borrow().clone()).unwrap();
+        output
+    }
+
+    #[test]
+    fn each_frame_is_one_synchronized_update() {
+        let output = draw_frames(true, 2);
+        let updates: Vec<_> = output.split(BEGIN_SYNCHRONIZED_UPDATE).skip(1).collect();
+        assert_eq!(updates.len(), 2);
+        for (index, update) in updates.iter().enumerate() {
+            let (frame, _) = update.split_once(END_SYNCHRONIZED_UPDATE).unwrap();
+            assert!(frame.contains(&index.to_string()));
+        }
+    }
+
+    #[test]
+    fn frames_pass_through_unless_enabled() {
+        let output = draw_frames(false, 2);
+        assert!(!output.contains(BEGIN_SYNCHRONIZED_UPDATE));
+        assert!(!output.contains(END_SYNCHRONIZED_UPDATE));
+        assert!(output.contains('1'));
+    }
+}
@@ -0,0 +1,395 @@
+//! The library the binary drives the terminal with: crossterm, or termion on unix with the
+//! `termion` feature, for terminals where crossterm misbehaves.
+//!
+//! Either way the app handles crossterm's events, so termion's are translated into them, and
+//! frames go out through the library's own tui backend, under the app's backends.
+
+use crossterm::event::Event;
+use crossterm::{cursor, event, execute, terminal};
+use std::io::{self, BufWriter, Stdout, Write};
+use std::time::Duration;
+use tui::backend::{Backend, CrosstermBackend};
+
+/// What the binary needs from a terminal library. Everything goes to and comes from the
+/// process's own terminal, so there's nothing to hold on to but the backend.
+pub trait TerminalLibrary {
+    /// The tui backend that draws with the library.
+    type Backend: Backend + Write;
+
+    fn backend(out: BufWriter<Stdout>) -> Self::Backend;
+
+    /// Enters raw mode and the alternate screen, with mouse capture on and the cursor hidden.
+    /// If any step fails, the ones that succeeded are undone before the error is returned.
+    fn enter_full_screen() -> crossterm::Result<()>;
+
+    /// Puts the terminal back the way the shell expects it. This is safe to call more than
+    /// once, and from any thread, and errors are ignored so that one failed step doesn't keep
+    /// the rest from running.
+    fn restore_terminal();
+
+    /// The terminal's size in cells.
+    fn size() -> crossterm::Result<(u16, u16)>;
+
+    /// Waits up to `timeout` for an event, returning whether one is ready to read.
+    fn poll_event(timeout: Duration) -> crossterm::Result<bool>;
+
+    /// Reads the next event, waiting for one if need be.
+    fn read_event() -> crossterm::Result<Event>;
+}
+
+/// The library the binary was built to use.
+#[cfg(not(all(unix, feature = "termion")))]
+pub type DefaultLibrary = Crossterm;
+/// The library the binary was built to use.
+#[cfg(all(unix, feature = "termion"))]
+pub type DefaultLibrary = Termion;
+
+pub struct Crossterm;
+
+impl TerminalLibrary for Crossterm {
+    type Backend = CrosstermBackend<BufWriter<Stdout>>;
+
+    fn backend(out: BufWriter<Stdout>) -> Self::Backend {
+        CrosstermBackend::new(out)
+    }
+
+    fn enter_full_screen() -> crossterm::Result<()> {
+        let result = terminal::enable_raw_mode().and_then(|_| {
+            execute!(
+                io::stdout(),
+                terminal::EnterAlternateScreen,
+                event::EnableMouseCapture,
+                cursor::Hide
+            )
+        });
+        if result.is_err() {
+            Crossterm::restore_terminal();
+        }
+        result
+    }
+
+    fn restore_terminal() {
+        let _ = execute!(
+            io::stdout(),
+            event::DisableMouseCapture,
+            terminal::LeaveAlternateScreen,
+            cursor::Show
+        );
+        let _ = terminal::disable_raw_mode();
+    }
+
+    fn size() -> crossterm::Result<(u16, u16)> {
+        terminal::size()
+    }
+
+    fn poll_event(timeout: Duration) -> crossterm::Result<bool> {
+        event::poll(timeout)
+    }
+
+    fn read_event() -> crossterm::Result<Event> {
+        event::read()
+    }
+}
+
+#[cfg(all(unix, feature = "termion"))]
+pub use self::termion_library::Termion;
+
+#[cfg(all(unix, feature = "termion"))]
+mod termion_library {
+    use super::TerminalLibrary;
+    use crossterm::event::{
+        Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    };
+    use signal_hook::consts::SIGWINCH;
+    use signal_hook::iterator::Signals;
+    use std::io::{self, BufWriter, Stdout, Write};
+    use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+    use std::sync::{Mutex, OnceLock, PoisonError};
+    use std::thread;
+    use std::time::Duration;
+    use termion::event::{
+        Event as TermionEvent, Key, MouseButton as TermionMouseButton,
+        MouseEvent as TermionMouseEvent,
+    };
+    use termion::input::TermRead;
+    use termion::raw::{IntoRawMode, RawTerminal};
+    use termion::{cursor, screen};
+    use tui::backend::TermionBackend;
+
+    // What termion's `MouseTerminal` writes to turn mouse reporting on and off: clicks, drags,
+    // and the extended coordinates that reach past column 223.
+    static ENABLE_MOUSE_CAPTURE: &'static str = "\x1b[?1000h\x1b[?1002h\x1b[?1015h\x1b[?1006h";
+    static DISABLE_MOUSE_CAPTURE: &'static str = "\x1b[?1006l\x1b[?1015l\x1b[?1002l\x1b[?1000l";
+
+    // Raw mode is undone by dropping the terminal that entered it, which remembers the mode the
+    // terminal was in before.
+    static RAW_TERMINAL: Mutex<Option<RawTerminal<Stdout>>> = Mutex::new(None);
+    static EVENTS: OnceLock<Mutex<Events>> = OnceLock::new();
+
+    pub struct Termion;
+
+    // termion only reads events by blocking, so threads read the keyboard and mouse and catch
+    // resizes, which termion doesn't report, and send them here for `poll_event` to wait on.
+    struct Events {
+        receiver: Receiver<io::Result<Event>>,
+        // An event `poll_event` received, for `read_event` to return.
+        pending: Option<io::Result<Event>>,
+    }
+
+    impl Events {
+        fn start() -> Mutex<Events> {
+            let (sender, receiver) = mpsc::channel();
+            let input_sender = sender.clone();
+            thread::spawn(move || {
+                for event in io::stdin().events() {
+                    let event = match event {
+                        Ok(event) => translate_event(event).map(Ok),
+                        Err(error) => Some(Err(error)),
+                    };
+                    if let Some(event) = event {
+                        if input_sender.send(event).is_err() {
+                            return;
+                        }
+                    }
+                }
+            });
+            if let Ok(mut signals) = Signals::new([SIGWINCH]) {
+                thread::spawn(move || {
+                    for _ in signals.forever() {
+                        let event = termion::terminal_size()
+                            .map(|(width, height)| Event::Resize(width, height));
+                        if sender.send(event).is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+            Mutex::new(Events {
+                receiver,
+                pending: None,
+            })
+        }
+    }
+
+    fn events() -> &'static Mutex<Events> {
+        EVENTS.get_or_init(Events::start)
+    }
+
+    impl TerminalLibrary for Termion {
+        type Backend = TermionBackend<BufWriter<Stdout>>;
+
+        fn backend(out: BufWriter<Stdout>) -> Self::Backend {
+            TermionBackend::new(out)
+        }
+
+        fn enter_full_screen() -> crossterm::Result<()> {
+            let result = io::stdout().into_raw_mode().and_then(|raw_terminal| {
+                *RAW_TERMINAL.lock().unwrap_or_else(PoisonError::into_inner) = Some(raw_terminal);
+                let mut stdout = io::stdout();
+                write!(
+                    stdout,
+                    "{}{}{}",
+                    screen::ToAlternateScreen,
+                    ENABLE_MOUSE_CAPTURE,
+                    cursor::Hide
+                )?;
+                stdout.flush()
+            });
+            if result.is_err() {
+                Termion::restore_terminal();
+            }
+            Ok(result?)
+        }
+
+        fn restore_terminal() {
+            let mut stdout = io::stdout();
+            let _ = write!(
+                stdout,
+                "{}{}{}",
+                DISABLE_MOUSE_CAPTURE,
+                screen::ToMainScreen,
+                cursor::Show
+            );
+            let _ = stdout.flush();
+            RAW_TERMINAL
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .take();
+        }
+
+        fn size() -> crossterm::Result<(u16, u16)> {
+            Ok(termion::terminal_size()?)
+        }
+
+        fn poll_event(timeout: Duration) -> crossterm::Result<bool> {
+            let mut events = events().lock().unwrap_or_else(PoisonError::into_inner);
+            if events.pending.is_some() {
+                return Ok(true);
+            }
+            match events.receiver.recv_timeout(timeout) {
+                Ok(event) => {
+                    events.pending = Some(event);
+                    Ok(true)
+                }
+                Err(RecvTimeoutError::Timeout) => Ok(false),
+                Err(RecvTimeoutError::Disconnected) => Err(input_closed().into()),
+            }
+        }
+
+        fn read_event() -> crossterm::Result<Event> {
+            let mut events = events().lock().unwrap_or_else(PoisonError::into_inner);
+            let event = match events.pending.take() {
+                Some(event) => event,
+                None => events.receiver.recv().map_err(|_| input_closed())?,
+            };
+            Ok(event?)
+        }
+    }
+
+    fn input_closed() -> io::Error {
+        io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "the terminal's input was closed",
+        )
+    }
+
+    // Turns a termion event into the crossterm event the app handles, if there is one. termion
+    // reports mouse positions from 1 and crossterm from 0. Keys termion can't tell apart, such
+    // as Shift with the arrows, come through without the modifier, and the rest are dropped.
+    pub fn translate_event(event: TermionEvent) -> Option<Event> {
+        match event {
+            TermionEvent::Key(key) => translate_key(key).map(Event::Key),
+            TermionEvent::Mouse(mouse_event) => {
+                let (kind, column, row) = match mouse_event {
+                    TermionMouseEvent::Press(TermionMouseButton::WheelUp, column, row) => {
+                        (MouseEventKind::ScrollUp, column, row)
+                    }
+                    TermionMouseEvent::Press(TermionMouseButton::WheelDown, column, row) => {
+                        (MouseEventKind::ScrollDown, column, row)
+                    }
+                    TermionMouseEvent::Press(button, column, row) => {
+                        (MouseEventKind::Down(translate_button(button)), column, row)
+                    }
+                    // termion doesn't say which button was released or is held.
+                    TermionMouseEvent::Release(column, row) => {
+                        (MouseEventKind::Up(MouseButton::Left), column, row)
+                    }
+                    TermionMouseEvent::Hold(column, row) => {
+                        (MouseEventKind::Drag(MouseButton::Left), column, row)
+                    }
+                };
+                Some(Event::Mouse(MouseEvent {
+                    kind,
+                    column: column.saturating_sub(1),
+                    row: row.saturating_sub(1),
+                    modifiers: KeyModifiers::NONE,
+                }))
+            }
+            TermionEvent::Unsupported(_) => None,
+        }
+    }
+
+    fn translate_key(key: Key) -> Option<KeyEvent> {
+        let (code, modifiers) = match key {
+            Key::Backspace => (KeyCode::Backspace, KeyModifiers::NONE),
+            Key::Left => (KeyCode::Left, KeyModifiers::NONE),
+            Key::Right => (KeyCode::Right, KeyModifiers::NONE),
+            Key::Up => (KeyCode::Up, KeyModifiers::NONE),
+            Key::Down => (KeyCode::Down, KeyModifiers::NONE),
+            Key::Home => (KeyCode::Home, KeyModifiers::NONE),
+            Key::End => (KeyCode::End, KeyModifiers::NONE),
+            Key::PageUp => (KeyCode::PageUp, KeyModifiers::NONE),
+            Key::PageDown => (KeyCode::PageDown, KeyModifiers::NONE),
+            Key::BackTab => (KeyCode::BackTab, KeyModifiers::SHIFT),
+            Key::Delete => (KeyCode::Delete, KeyModifiers::NONE),
+            Key::Insert => (KeyCode::Insert, KeyModifiers::NONE),
+            Key::F(number) => (KeyCode::F(number), KeyModifiers::NONE),
+            Key::Char('\n') => (KeyCode::Enter, KeyModifiers::NONE),
+            Key::Char('\t') => (KeyCode::Tab, KeyModifiers::NONE),
+            // crossterm reports capitals with Shift held.
+            Key::Char(character) if character.is_uppercase() => {
+                (KeyCode::Char(character), KeyModifiers::SHIFT)
+            }
+            Key::Char(character) => (KeyCode::Char(character), KeyModifiers::NONE),
+            Key::Alt(character) => (KeyCode::Char(character), KeyModifiers::ALT),
+            Key::Ctrl(character) => (KeyCode::Char(character), KeyModifiers::CONTROL),
+            Key::Esc => (KeyCode::Esc, KeyModifiers::NONE),
+            _ => return None,
+        };
+        Some(KeyEvent { code, modifiers })
+    }
+
+    fn translate_button(button: TermionMouseButton) -> MouseButton {
+        match button {
+            TermionMouseButton::Right => MouseButton::Right,
+            TermionMouseButton::Middle => MouseButton::Middle,
+            _ => MouseButton::Left,
+        }
+    }
+}
+
+#[cfg(all(test, unix, feature = "termion"))]
+mod tests {
+    use super::termion_library::translate_event;
+    use crossterm::event::{
+        Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    };
+    use termion::event::MouseEvent as TermionMouseEvent;
+    use termion::event::{Event as TermionEvent, Key, MouseButton as TermionMouseButton};
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> Option<Event> {
+        Some(Event::Key(KeyEvent { code, modifiers }))
+    }
+
+    #[test]
+    fn termion_events_are_translated_into_the_crossterm_events_the_app_handles() {
+        let translate_key = |key| translate_event(TermionEvent::Key(key));
+        assert_eq!(
+            translate_key(Key::Char('\n')),
+            key(KeyCode::Enter, KeyModifiers::NONE)
+        );
+        assert_eq!(
+            translate_key(Key::Char('E')),
+            key(KeyCode::Char('E'), KeyModifiers::SHIFT)
+        );
+        assert_eq!(
+            translate_key(Key::Ctrl('c')),
+            key(KeyCode::Char('c'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            translate_key(Key::BackTab),
+            key(KeyCode::BackTab, KeyModifiers::SHIFT)
+        );
+        assert_eq!(translate_key(Key::Null), None);
+
+        // Mouse positions count from 1 in termion and from 0 in crossterm.
+        let mouse = |kind, column, row| {
+            Some(Event::Mouse(MouseEvent {
+                kind,
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            }))
+        };
+        assert_eq!(
+            translate_event(TermionEvent::Mouse(TermionMouseEvent::Press(
+                TermionMouseButton::Left,
+                1,
+                1
+            ))),
+            mouse(MouseEventKind::Down(MouseButton::Left), 0, 0)
+        );
+        assert_eq!(
+            translate_event(TermionEvent::Mouse(TermionMouseEvent::Press(
+                TermionMouseButton::WheelDown,
+                10,
+                4
+            ))),
+            mouse(MouseEventKind::ScrollDown, 9, 3)
+        );
+        assert_eq!(
+            translate_event(TermionEvent::Unsupported(vec![0x1b, b'[', b'Z', b'?'])),
+            None
+        );
+    }
+}
@@ -0,0 +1,36 @@
+//! Switching the terminal into the state the UI draws in and back again.
+
+use crate::terminal::TerminalLibrary;
+use std::marker::PhantomData;
+
+/// Keeps the terminal in raw mode on the alternate screen, with mouse capture on, for as long as
+/// it lives. Dropping it puts the terminal back, so a normal exit and an early return through `?`
+/// restore it the same way. Panics and signals call the library's `restore_terminal` directly,
+/// since they don't necessarily unwind through `main`.
+pub struct TerminalGuard<L>
+where
+    L: TerminalLibrary,
+{
+    library: PhantomData<L>,
+}
+
+impl<L> TerminalGuard<L>
+where
+    L: TerminalLibrary,
+{
+    pub fn enter() -> crossterm::Result<TerminalGuard<L>> {
+        L::enter_full_screen()?;
+        Ok(TerminalGuard {
+            library: PhantomData,
+        })
+    }
+}
+
+impl<L> Drop for TerminalGuard<L>
+where
+    L: TerminalLibrary,
+{
+    fn drop(&mut self) {
+        L::restore_terminal();
+    }
+}
@@ -0,0 +1,304 @@
+//! The colors the UI is drawn in.
+//!
+//! Every color comes from a `Theme`, so that switching themes is a matter of pointing the app
+//! state at a different one.
+
+use tui::style::Color;
+
+/// The values at which a measurement goes from fine to worth a look to a problem. Values at or
+/// above `warning` are drawn in the theme's warning color, and at or above `critical` in its
+/// critical color.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Thresholds {
+    pub warning: f32,
+    pub critical: f32,
+}
+
+impl Thresholds {
+    /// Picks `normal`, `warning`, or `critical` for `value`.
+    pub fn color(&self, value: f32, normal: Color, warning: Color, critical: Color) -> Color {
+        if value >= self.critical {
+            critical
+        } else if value >= self.warning {
+            warning
+        } else {
+            normal
+        }
+    }
+}
+
+/// A complete set of colors for the main screen and the error screen.
+pub struct Theme {
+    pub name: &'static str,
+    pub title_main_color: Color,
+    pub title_sub_color: Color,
+    pub title_sub_sub_bg: Color,
+    pub title_sub_sub_fg: Color,
+    pub title_sub_separator_color: Color,
+    pub box_text_color: Color,
+    pub focus_bg: Color,
+    pub performance_box_fg: Color,
+    pub performance_label: Color,
+    pub performance_numeric_color: Color,
+    pub performance_minor_color: Color,
+    pub performance_graph_color: Color,
+    pub performance_axis_color: Color,
+    pub performance_graph_warning_color: Color,
+    pub performance_graph_critical_color: Color,
+    /// The labels of measurements past their alert thresholds, which are bold as well.
+    pub performance_alert_label_color: Color,
+    /// Measurements that went up since the previous window. Most of them are costs, so this is
+    /// the color of things getting worse.
+    pub performance_increase_color: Color,
+    pub performance_decrease_color: Color,
+    pub tasks_box_fg: Color,
+    pub tasks_filter_bg: Color,
+    pub tasks_filter_fg: Color,
+    pub tasks_table_header_fg: Color,
+    /// The headers of the columns the tasks table isn't sorted by, while it's sorted.
+    pub tasks_table_unsorted_header_fg: Color,
+    pub tasks_table_open_cell_color: Color,
+    pub tasks_table_minor_cell_color: Color,
+    pub tasks_table_name_cell_color: Color,
+    pub tasks_table_numeric_cell_color: Color,
+    /// Run %, Poll, and Wake values past their warning thresholds.
+    pub tasks_table_warning_cell_color: Color,
+    /// Run %, Poll, and Wake values past their critical thresholds.
+    pub tasks_table_critical_cell_color: Color,
+    /// When the Run % column turns to the warning and critical colors, in percent.
+    pub tasks_table_run_percent_thresholds: Thresholds,
+    /// When the Poll column turns to the warning and critical colors, in milliseconds.
+    pub tasks_table_poll_thresholds: Thresholds,
+    /// When the Wake column turns to the warning and critical colors, in milliseconds.
+    pub tasks_table_wake_thresholds: Thresholds,
+    /// The background of every other row in the tasks table. `None` turns striping off.
+    pub tasks_table_stripe_bg: Option<Color>,
+    /// The background of the selected task's row, over any stripe.
+    pub tasks_table_selected_bg: Color,
+    pub tasks_table_attribute_key_cell_color: Color,
+    pub tasks_table_attribute_value_cell_color: Color,
+    pub tasks_table_status_running_color: Color,
+    pub tasks_table_status_sleeping_color: Color,
+    pub tasks_table_status_deadlocked_color: Color,
+    pub tasks_table_status_paused_color: Color,
+    /// The mark before the names of tasks whose priority was boosted.
+    pub tasks_table_boosted_color: Color,
+    /// The mark before the names of bookmarked tasks.
+    pub tasks_table_bookmark_color: Color,
+    pub scrollbar_color: Color,
+    pub popup_box_fg: Color,
+    pub popup_text_color: Color,
+    pub popup_selected_bg: Color,
+    /// The characters of a search result that matched what was typed, which are bold as well.
+    pub popup_match_color: Color,
+    /// Placeholders and other text that's there to explain rather than to read.
+    pub popup_minor_color: Color,
+    pub code_keyword_color: Color,
+    pub code_type_color: Color,
+    /// Strings, characters, and numbers.
+    pub code_literal_color: Color,
+    pub code_comment_color: Color,
+    /// Macro names and attributes.
+    pub code_macro_color: Color,
+    pub toast_bg: Color,
+    pub toast_fg: Color,
+    pub toast_key_color: Color,
+    pub notifications_box_fg: Color,
+    pub notification_time_color: Color,
+    pub notification_alert_color: Color,
+    pub notification_export_color: Color,
+    pub notification_connection_color: Color,
+    pub notification_text_color: Color,
+    pub error_box_fg: Color,
+    pub error_message_color: Color,
+    pub error_suggestion_color: Color,
+}
+
+/// The Nord-inspired colors the mockups were designed in.
+pub static THEME_DEFAULT: Theme = Theme {
+    name: "default",
+    title_main_color: Color::Rgb(0x88, 0xc0, 0xd0),
+    title_sub_color: Color::Rgb(0x81, 0xa1, 0xc1),
+    title_sub_sub_bg: Color::Rgb(0x3b, 0x42, 0x52),
+    title_sub_sub_fg: Color::Rgb(0xe5, 0xe9, 0xf0),
+    title_sub_separator_color: Color::DarkGray,
+    box_text_color: Color::White,
+    focus_bg: Color::Rgb(0x43, 0x4c, 0x5e),
+    performance_box_fg: Color::Green,
+    performance_label: Color::Gray,
+    performance_numeric_color: Color::Green,
+    performance_minor_color: Color::DarkGray,
+    performance_graph_color: Color::Green,
+    performance_axis_color: Color::DarkGray,
+    performance_graph_warning_color: Color::Yellow,
+    performance_graph_critical_color: Color::Red,
+    performance_alert_label_color: Color::Red,
+    performance_increase_color: Color::Red,
+    performance_decrease_color: Color::Green,
+    tasks_box_fg: Color::Red,
+    tasks_filter_bg: Color::Black, // Color::Rgb(32, 0, 0);
+    tasks_filter_fg: Color::Gray,  // Color::Red;
+    tasks_table_header_fg: Color::White,
+    tasks_table_unsorted_header_fg: Color::DarkGray,
+    tasks_table_open_cell_color: Color::DarkGray,
+    tasks_table_minor_cell_color: Color::DarkGray,
+    tasks_table_name_cell_color: Color::Yellow,
+    tasks_table_numeric_cell_color: Color::Green,
+    tasks_table_warning_cell_color: Color::Yellow,
+    tasks_table_critical_cell_color: Color::Red,
+    tasks_table_run_percent_thresholds: Thresholds {
+        warning: 20.0,
+        critical: 50.0,
+    },
+    tasks_table_poll_thresholds: Thresholds {
+        warning: 1.0,
+        critical: 10.0,
+    },
+    tasks_table_wake_thresholds: Thresholds {
+        warning: 1.0,
+        critical: 10.0,
+    },
+    tasks_table_stripe_bg: Some(Color::Rgb(0x2e, 0x34, 0x40)),
+    tasks_table_selected_bg: Color::Rgb(0x4c, 0x56, 0x6a),
+    tasks_table_attribute_key_cell_color: Color::Blue,
+    tasks_table_attribute_value_cell_color: Color::Yellow,
+    tasks_table_status_running_color: Color::Green,
+    tasks_table_status_sleeping_color: Color::Gray,
+    tasks_table_status_deadlocked_color: Color::Red,
+    tasks_table_status_paused_color: Color::Yellow,
+    tasks_table_boosted_color: Color::Cyan,
+    tasks_table_bookmark_color: Color::Magenta,
+    scrollbar_color: Color::Gray,
+    popup_box_fg: Color::Cyan,
+    popup_text_color: Color::White,
+    popup_selected_bg: Color::Rgb(0x43, 0x4c, 0x5e),
+    popup_match_color: Color::Rgb(0x88, 0xc0, 0xd0),
+    popup_minor_color: Color::DarkGray,
+    code_keyword_color: Color::Rgb(0x81, 0xa1, 0xc1),
+    code_type_color: Color::Rgb(0x8f, 0xbc, 0xbb),
+    code_literal_color: Color::Rgb(0xa3, 0xbe, 0x8c),
+    code_comment_color: Color::Rgb(0x61, 0x6e, 0x88),
+    code_macro_color: Color::Rgb(0x88, 0xc0, 0xd0),
+    toast_bg: Color::Rgb(0x3b, 0x42, 0x52),
+    toast_fg: Color::Rgb(0xe5, 0xe9, 0xf0),
+    toast_key_color: Color::Rgb(0x88, 0xc0, 0xd0),
+    notifications_box_fg: Color::Blue,
+    notification_time_color: Color::DarkGray,
+    notification_alert_color: Color::Red,
+    notification_export_color: Color::Green,
+    notification_connection_color: Color::Rgb(0x81, 0xa1, 0xc1),
+    notification_text_color: Color::White,
+    error_box_fg: Color::Red,
+    error_message_color: Color::White,
+    error_suggestion_color: Color::Gray,
+};
+
+/// The default's colors, approximated with the 16 that every color terminal has, for terminals
+/// that can't show the others.
+pub static THEME_BASIC: Theme = Theme {
+    name: "basic",
+    title_main_color: Color::Cyan,
+    title_sub_color: Color::Blue,
+    title_sub_sub_bg: Color::DarkGray,
+    title_sub_sub_fg: Color::White,
+    title_sub_separator_color: Color::Gray,
+    box_text_color: Color::White,
+    focus_bg: Color::DarkGray,
+    performance_box_fg: Color::Green,
+    performance_label: Color::Gray,
+    performance_numeric_color: Color::Green,
+    performance_minor_color: Color::DarkGray,
+    performance_graph_color: Color::Green,
+    performance_axis_color: Color::DarkGray,
+    performance_graph_warning_color: Color::Yellow,
+    performance_graph_critical_color: Color::Red,
+    performance_alert_label_color: Color::LightRed,
+    performance_increase_color: Color::Red,
+    performance_decrease_color: Color::Green,
+    tasks_box_fg: Color::Red,
+    tasks_filter_bg: Color::Black,
+    tasks_filter_fg: Color::Gray,
+    tasks_table_header_fg: Color::White,
+    tasks_table_unsorted_header_fg: Color::DarkGray,
+    tasks_table_open_cell_color: Color::DarkGray,
+    tasks_table_minor_cell_color: Color::DarkGray,
+    tasks_table_name_cell_color: Color::Yellow,
+    tasks_table_numeric_cell_color: Color::Green,
+    tasks_table_warning_cell_color: Color::Yellow,
+    tasks_table_critical_cell_color: Color::Red,
+    tasks_table_run_percent_thresholds: THEME_DEFAULT.tasks_table_run_percent_thresholds,
+    tasks_table_poll_thresholds: THEME_DEFAULT.tasks_table_poll_thresholds,
+    tasks_table_wake_thresholds: THEME_DEFAULT.tasks_table_wake_thresholds,
+    // Without a color between black and dark gray, stripes would be as loud as the selection.
+    tasks_table_stripe_bg: None,
+    tasks_table_selected_bg: Color::DarkGray,
+    tasks_table_attribute_key_cell_color: Color::Blue,
+    tasks_table_attribute_value_cell_color: Color::Yellow,
+    tasks_table_status_running_color: Color::Green,
+    tasks_table_status_sleeping_color: Color::Gray,
+    tasks_table_status_deadlocked_color: Color::Red,
+    tasks_table_status_paused_color: Color::Yellow,
+    tasks_table_boosted_color: Color::Cyan,
+    tasks_table_bookmark_color: Color::Magenta,
+    scrollbar_color: Color::Gray,
+    popup_box_fg: Color::Cyan,
+    popup_text_color: Color::White,
+    popup_selected_bg: Color::DarkGray,
+    popup_match_color: Color::Cyan,
+    popup_minor_color: Color::Gray,
+    code_keyword_color: Color::Blue,
+    code_type_color: Color::LightCyan,
+    code_literal_color: Color::Green,
+    code_comment_color: Color::Gray,
+    code_macro_color: Color::Cyan,
+    toast_bg: Color::DarkGray,
+    toast_fg: Color::White,
+    toast_key_color: Color::Cyan,
+    notifications_box_fg: Color::Blue,
+    notification_time_color: Color::Gray,
+    notification_alert_color: Color::LightRed,
+    notification_export_color: Color::Green,
+    notification_connection_color: Color::Blue,
+    notification_text_color: Color::White,
+    error_box_fg: Color::Red,
+    error_message_color: Color::White,
+    error_suggestion_color: Color::Gray,
+};
+
+/// The built-in themes, in the order the command palette lists them.
+pub static THEMES: [&Theme; 2] = [&THEME_DEFAULT, &THEME_BASIC];
+
+/// The built-in theme after `theme`, going back to the first after the last. Themes that aren't
+/// built in are followed by the first.
+pub fn next_theme(theme: &Theme) -> &'static Theme {
+    let index = THEMES
+        .iter()
+        .position(|&built_in| built_in.name == theme.name)
+        .map_or(0, |index| index + 1);
+    THEMES[index % THEMES.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thresholds_pick_the_color_for_how_far_a_value_has_gone() {
+        let thresholds = Thresholds {
+            warning: 1.0,
+            critical: 10.0,
+        };
+        let color = |value| thresholds.color(value, Color::Green, Color::Yellow, Color::Red);
+        assert_eq!(color(0.5), Color::Green);
+        assert_eq!(color(1.0), Color::Yellow);
+        assert_eq!(color(9.9), Color::Yellow);
+        assert_eq!(color(10.0), Color::Red);
+        assert_eq!(color(f32::NAN), Color::Green);
+    }
+
+    #[test]
+    fn themes_go_round_in_order() {
+        assert_eq!(next_theme(&THEME_DEFAULT).name, THEME_BASIC.name);
+        assert_eq!(next_theme(&THEME_BASIC).name, THEME_DEFAULT.name);
+    }
+}
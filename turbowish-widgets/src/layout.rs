@@ -0,0 +1,345 @@
+//! Helpers for laying out a UI with stretch's flexbox and converting the results to tui's
+//! coordinates.
+
+use crate::text::display_width;
+use stretch::geometry::{Rect, Size};
+use stretch::node::Node as StretchNode;
+use stretch::number::Number;
+use stretch::result::Layout;
+use stretch::style::{Dimension, Style};
+use stretch::Stretch;
+use tui::layout::Rect as TuiRect;
+
+/// tui's default gap between table columns.
+pub const TABLE_COLUMN_SPACING: u16 = 1;
+
+/// A node of a `LayoutTree`: its index among the tree's nodes, in the order they were made.
+/// What goes with each node, such as the widget drawn in it, can be kept in a vector by this
+/// index rather than hashed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Node(u32);
+
+impl Node {
+    /// Where the node is among its tree's nodes, counting from 0.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A stretch tree that numbers its nodes as it makes them and keeps each node's children, style
+/// and last layout, so that the tree can be walked and drawn without looking anything up in
+/// stretch's maps. Clearing the tree keeps its storage, and numbers the next nodes from 0 again.
+pub struct LayoutTree {
+    stretch: Stretch,
+    stretch_nodes: Vec<StretchNode>,
+    children: Vec<Vec<Node>>,
+    styles: Vec<Style>,
+    layouts: Vec<Layout>,
+}
+
+impl LayoutTree {
+    /// Creates an empty tree.
+    pub fn new() -> LayoutTree {
+        LayoutTree {
+            stretch: Stretch::new(),
+            stretch_nodes: vec![],
+            children: vec![],
+            styles: vec![],
+            layouts: vec![],
+        }
+    }
+
+    /// Creates a node with `style` and no parent, such as the root.
+    pub fn new_node(&mut self, style: Style) -> Node {
+        let stretch_node = self.stretch.new_node(style, vec![]).unwrap();
+        let node = Node(self.stretch_nodes.len() as u32);
+        self.stretch_nodes.push(stretch_node);
+        self.children.push(vec![]);
+        self.styles.push(style);
+        self.layouts
+            .push(*self.stretch.layout(stretch_node).unwrap());
+        node
+    }
+
+    /// Creates a node with `style` and appends it to `parent`'s children.
+    pub fn add_new_child(&mut self, parent: Node, style: Style) -> Node {
+        let node = self.new_node(style);
+        self.stretch
+            .add_child(
+                self.stretch_nodes[parent.index()],
+                self.stretch_nodes[node.index()],
+            )
+            .unwrap();
+        self.children[parent.index()].push(node);
+        node
+    }
+
+    /// Creates a node exactly as big as `string` on one line and appends it to `parent`'s
+    /// children.
+    pub fn add_single_line_text(&mut self, parent: Node, string: &str) -> Node {
+        self.add_new_child(
+            parent,
+            Style {
+                size: Size::fixed(display_width(string), 1),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// `node`'s children, in the order they were added.
+    pub fn children(&self, node: Node) -> &[Node] {
+        &self.children[node.index()]
+    }
+
+    /// `node`'s style.
+    pub fn style(&self, node: Node) -> &Style {
+        &self.styles[node.index()]
+    }
+
+    /// Changes `node`'s style, which leaves it to be laid out again.
+    pub fn set_style(&mut self, node: Node, style: Style) {
+        self.stretch
+            .set_style(self.stretch_nodes[node.index()], style)
+            .unwrap();
+        self.styles[node.index()] = style;
+    }
+
+    /// Whether anything changed in `node`'s subtree since it was last laid out.
+    pub fn dirty(&self, node: Node) -> bool {
+        self.stretch
+            .dirty(self.stretch_nodes[node.index()])
+            .unwrap()
+    }
+
+    /// Lays out `node` and its descendants within `size`.
+    pub fn compute_layout(&mut self, node: Node, size: Size<Number>) {
+        self.stretch
+            .compute_layout(self.stretch_nodes[node.index()], size)
+            .unwrap();
+        for (layout, &stretch_node) in self.layouts.iter_mut().zip(&self.stretch_nodes) {
+            *layout = *self.stretch.layout(stretch_node).unwrap();
+        }
+    }
+
+    /// Where `node` went, relative to its parent, as of the last `compute_layout`.
+    pub fn layout(&self, node: Node) -> &Layout {
+        &self.layouts[node.index()]
+    }
+
+    /// How many nodes the tree has.
+    pub fn len(&self) -> usize {
+        self.stretch_nodes.len()
+    }
+
+    /// Whether the tree has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.stretch_nodes.is_empty()
+    }
+
+    /// Removes every node, keeping the storage for the next ones.
+    pub fn clear(&mut self) {
+        self.stretch.clear();
+        self.stretch_nodes.clear();
+        self.children.clear();
+        self.styles.clear();
+        self.layouts.clear();
+    }
+}
+
+impl Default for LayoutTree {
+    fn default() -> LayoutTree {
+        LayoutTree::new()
+    }
+}
+
+/// Converts a computed layout, which is relative to the node's parent, to a tui rect, rounding
+/// to whole cells.
+pub trait ToRect {
+    /// Returns the layout's position and size in cells.
+    fn to_rect(&self) -> TuiRect;
+}
+
+impl ToRect for Layout {
+    fn to_rect(&self) -> TuiRect {
+        TuiRect {
+            x: self.location.x.round() as u16,
+            y: self.location.y.round() as u16,
+            width: self.size.width.round() as u16,
+            height: self.size.height.round() as u16,
+        }
+    }
+}
+
+// Geometry extensions
+
+/// Constructors for sizes given in cells.
+pub trait SizeExt {
+    /// A size fixed in both directions.
+    fn fixed(x: u16, y: u16) -> Self;
+    /// A fixed width and an automatic height.
+    fn fixed_width(x: u16) -> Self;
+    /// An automatic width and a fixed height.
+    fn fixed_height(y: u16) -> Self;
+}
+
+impl SizeExt for Size<Dimension> {
+    fn fixed(x: u16, y: u16) -> Self {
+        Size {
+            width: Dimension::Points(x as f32),
+            height: Dimension::Points(y as f32),
+        }
+    }
+    fn fixed_width(x: u16) -> Self {
+        Size {
+            width: Dimension::Points(x as f32),
+            height: Dimension::Auto,
+        }
+    }
+    fn fixed_height(y: u16) -> Self {
+        Size {
+            width: Dimension::Auto,
+            height: Dimension::Points(y as f32),
+        }
+    }
+}
+
+/// Constructors for margins and padding given in cells.
+pub trait RectExt {
+    /// Sides in CSS order: top, end (right), bottom, start (left).
+    fn new(top: i32, end: i32, bottom: i32, start: i32) -> Self;
+}
+
+impl RectExt for Rect<Dimension> {
+    fn new(top: i32, end: i32, bottom: i32, start: i32) -> Self {
+        Rect {
+            start: Dimension::Points(start as f32),
+            end: Dimension::Points(end as f32),
+            top: Dimension::Points(top as f32),
+            bottom: Dimension::Points(bottom as f32),
+        }
+    }
+}
+
+/// Returns padding in cells. Only fixed padding is supported; anything else counts as none.
+pub fn resolve_padding(padding: Rect<Dimension>) -> Rect<u16> {
+    return Rect {
+        start: resolve_padding_dimension(padding.start),
+        end: resolve_padding_dimension(padding.end),
+        top: resolve_padding_dimension(padding.top),
+        bottom: resolve_padding_dimension(padding.bottom),
+    };
+
+    fn resolve_padding_dimension(length: Dimension) -> u16 {
+        match length {
+            Dimension::Auto | Dimension::Undefined | Dimension::Percent(_) => 0,
+            Dimension::Points(length) => length as u16,
+        }
+    }
+}
+
+/// Finds `node` among the descendants of `root` and returns its rect in the same coordinates as
+/// `root`'s.
+pub fn world_rect(tree: &LayoutTree, root: Node, node: Node) -> Option<TuiRect> {
+    let root_rect = tree.layout(root).to_rect();
+    if root == node {
+        return Some(root_rect);
+    }
+    tree.children(root)
+        .iter()
+        .find_map(|&child| world_rect(tree, child, node))
+        .map(|mut rect| {
+            rect.x += root_rect.x;
+            rect.y += root_rect.y;
+            rect
+        })
+}
+
+/// Trims fixed table column widths from left to right until they fit in `table_width`.
+///
+/// tui sizes table columns with a constraint solver, which settles on an arbitrary solution when
+/// fixed widths don't fit, so that narrow terminals would render differently from run to run.
+/// Trimming the widths first keeps the layout predictable.
+pub fn fit_table_column_widths(widths: &mut [u16], table_width: u16) {
+    let spacing_width = (widths.len() as u16).saturating_sub(1) * TABLE_COLUMN_SPACING;
+    let mut available_width = table_width.saturating_sub(spacing_width);
+    for width in widths {
+        *width = (*width).min(available_width);
+        available_width -= *width;
+    }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use stretch::style::Style;
+}
+
+/// Builds a tree of nodes in a `LayoutTree` in one go, binding each node to a variable of the same name.
+///
+/// Each node is written as its name, then its style fields in braces (fields left out get
+/// their defaults), then optionally a widget in parentheses for a `Renderer` to draw in it,
+/// then optionally its children in brackets. Nodes that are only there for spacing can be
+/// given names starting with an underscore.
+///
+/// ```
+/// use stretch::geometry::Size;
+/// use stretch::style::Style;
+/// use tui::widgets::Paragraph;
+/// use turbowish_widgets::layout::{LayoutTree, SizeExt};
+/// use turbowish_widgets::renderer::Renderer;
+/// use turbowish_widgets::ui;
+///
+/// let mut stretch = LayoutTree::new();
+/// let mut renderer = Renderer::new();
+/// let root = stretch.new_node(Style::default());
+/// ui!(stretch, renderer; root => [
+///     header { size: Size::fixed_height(1) } (Paragraph::new("Title")),
+///     body { flex_grow: 1.0 } [
+///         sidebar { size: Size::fixed_width(20) },
+///         _gutter { size: Size::fixed_width(1) },
+///         content { flex_grow: 1.0 },
+///     ],
+/// ]);
+/// assert_eq!(stretch.children(body), [sidebar, _gutter, content]);
+/// ```
+///
+/// Without a renderer, as in `ui!(stretch; root => [...])`, the macro only builds the tree.
+#[macro_export]
+macro_rules! ui {
+    ($stretch:ident, $renderer:ident; $parent:expr => [$($nodes:tt)*]) => {
+        $crate::ui!(@nodes ($stretch, $renderer) $parent; $($nodes)*);
+    };
+    ($stretch:ident; $parent:expr => [$($nodes:tt)*]) => {
+        $crate::ui!(@nodes ($stretch) $parent; $($nodes)*);
+    };
+
+    (@nodes $context:tt $parent:expr;) => {};
+    (
+        @nodes $context:tt $parent:expr;
+        $name:ident { $($field:ident : $value:expr),* $(,)? }
+        $(($widget:expr))?
+        $([$($children:tt)*])?
+        $(, $($rest:tt)*)?
+    ) => {
+        let $name = $crate::ui!(@node $context $parent; $($field: $value),*);
+        $($crate::ui!(@widget $context $name; $widget);)?
+        $($crate::ui!(@nodes $context $name; $($children)*);)?
+        $($crate::ui!(@nodes $context $parent; $($rest)*);)?
+    };
+
+    (@node ($stretch:ident $(, $renderer:ident)?) $parent:expr; $($field:ident : $value:expr),*) => {{
+        $stretch.add_new_child(
+            $parent,
+            $crate::layout::__private::Style {
+                $($field: $value,)*
+                ..::core::default::Default::default()
+            },
+        )
+    }};
+
+    (@widget ($stretch:ident, $renderer:ident) $name:ident; $widget:expr) => {
+        $renderer.build_node($name, $widget);
+    };
+    (@widget ($stretch:ident) $name:ident; $widget:expr) => {
+        compile_error!("attaching widgets needs a renderer: `ui!(stretch, renderer; ...)`");
+    };
+}
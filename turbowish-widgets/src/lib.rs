@@ -0,0 +1,19 @@
+#![allow(clippy::redundant_static_lifetimes)]
+#![warn(missing_docs)]
+
+//! The widgets from the Tokio console mockups, for any tui application: a powerline, a framed
+//! box with labels in its borders, a segmented control, a scrollbar, a text input, a bar chart
+//! that draws in braille, blocks, or ASCII, a braille line graph, a canvas to draw shapes on,
+//! a ring gauge, big text for numbers that have to be read from afar, Markdown text,
+//! highlighted Rust code, a timeline of bars such as tasks' lives, a ruler of times ago for
+//! time axes, and a graph of nodes joined by arrows, such as who waits on what.
+//!
+//! Widgets can be drawn directly with tui's `Frame::render_widget`, or laid out with stretch
+//! and drawn by a [`Renderer`](renderer::Renderer), using the helpers in [`layout`].
+
+pub mod layout;
+pub mod renderer;
+pub mod text;
+mod widgets;
+
+pub use crate::widgets::*;
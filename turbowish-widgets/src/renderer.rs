@@ -0,0 +1,660 @@
+//! Draws widgets into the rects that stretch laid out for them.
+
+use crate::layout::{resolve_padding, LayoutTree, Node, ToRect};
+use crate::widgets::AnyWidget;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::cell::{Cell, Ref, RefCell};
+use stretch::geometry::Point;
+use tui::backend::Backend;
+use tui::buffer::Buffer;
+use tui::layout::Rect as TuiRect;
+use tui::style::{Color, Style as TuiStyle};
+use tui::widgets::Widget;
+use tui::Frame;
+
+/// A stable name for an interactive widget, so that it can be found on screen after it's drawn
+/// no matter which layout node it ended up in.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct WidgetId(pub &'static str);
+
+/// Where each identified widget was drawn, in drawing order.
+///
+/// The renderer fills one in as it draws, and it stays valid until the next frame: mouse events
+/// are hit tested against it, focus moves through it in order, and popups anchor to the rects
+/// in it.
+#[derive(Clone, Default, Debug)]
+pub struct WidgetRegistry {
+    entries: Vec<(WidgetId, TuiRect)>,
+}
+
+impl WidgetRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> WidgetRegistry {
+        WidgetRegistry::default()
+    }
+
+    /// Records that `id` was drawn in `rect`, forgetting wherever it was drawn before.
+    pub fn insert(&mut self, id: WidgetId, rect: TuiRect) {
+        self.entries.retain(|&(other_id, _)| other_id != id);
+        self.entries.push((id, rect));
+    }
+
+    /// Adds everything in `other`, as though it had been drawn after what's here.
+    pub fn extend(&mut self, other: WidgetRegistry) {
+        for (id, rect) in other.entries {
+            self.insert(id, rect);
+        }
+    }
+
+    /// Where `id` was drawn, if it was.
+    pub fn rect(&self, id: WidgetId) -> Option<TuiRect> {
+        self.entries
+            .iter()
+            .find(|&&(other_id, _)| other_id == id)
+            .map(|&(_, rect)| rect)
+    }
+
+    /// The widget under a cell. Where widgets overlap, the one drawn last is on top.
+    pub fn hit_test(&self, column: u16, row: u16) -> Option<WidgetId> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(_, rect)| {
+                column >= rect.x && column < rect.right() && row >= rect.y && row < rect.bottom()
+            })
+            .map(|&(id, _)| id)
+    }
+
+    /// The identified widgets in drawing order, which is the order focus moves through them.
+    pub fn ids(&self) -> impl Iterator<Item = WidgetId> + '_ {
+        self.entries.iter().map(|&(id, _)| id)
+    }
+
+    /// The widget focus moves to from `current` when stepping forward, wrapping around at the
+    /// end. With nothing focused, or when `current` wasn't drawn, focus goes to the first one.
+    pub fn next_focus(&self, current: Option<WidgetId>) -> Option<WidgetId> {
+        self.step_focus(current, true)
+    }
+
+    /// Like `next_focus`, but stepping backward, so that focus starts from the last one.
+    pub fn previous_focus(&self, current: Option<WidgetId>) -> Option<WidgetId> {
+        self.step_focus(current, false)
+    }
+
+    /// Places a popup of the given size next to `id`'s rect, as `place_popup` does.
+    pub fn anchor_popup(
+        &self,
+        id: WidgetId,
+        width: u16,
+        height: u16,
+        bounds: TuiRect,
+    ) -> Option<TuiRect> {
+        Some(place_popup(self.rect(id)?, width, height, bounds))
+    }
+
+    fn step_focus(&self, current: Option<WidgetId>, forward: bool) -> Option<WidgetId> {
+        let count = self.entries.len();
+        let position = current.and_then(|current| self.ids().position(|id| id == current));
+        let index = match (position, forward) {
+            (Some(position), true) => (position + 1) % count,
+            (Some(position), false) => (position + count - 1) % count,
+            (None, true) => 0,
+            (None, false) => count.checked_sub(1)?,
+        };
+        self.entries.get(index).map(|&(id, _)| id)
+    }
+}
+
+/// Places a popup of the given size just below `anchor`, or just above it if there's no room
+/// below, shifted left as needed to stay inside `bounds`.
+pub fn place_popup(anchor: TuiRect, width: u16, height: u16, bounds: TuiRect) -> TuiRect {
+    let width = width.min(bounds.width);
+    let height = height.min(bounds.height);
+    let x = anchor.x.min(bounds.right() - width).max(bounds.x);
+    let y = if anchor.bottom() + height <= bounds.bottom() {
+        anchor.bottom()
+    } else {
+        anchor.y.saturating_sub(height).max(bounds.y)
+    };
+    TuiRect::new(x, y, width, height)
+}
+
+/// How many cells a node has to cover before its subtrees are painted on separate threads: a
+/// 200x50 terminal's worth. Below that, handing the work out costs more than it saves.
+pub const PARALLEL_MIN_AREA: u32 = 200 * 50;
+
+/// A map from the nodes of a `LayoutTree` to values, kept in a vector by each node's index so
+/// that finding a node's value is indexing the vector. Iteration goes in the order the nodes
+/// were made.
+///
+/// A map is for one tree at a time: clearing the tree numbers its next nodes from 0 again, so
+/// clear the map with it. Clearing the map keeps its storage for the next frame's nodes.
+pub struct NodeMap<T> {
+    values: Vec<Option<T>>,
+}
+
+impl<T> NodeMap<T> {
+    /// Creates an empty map.
+    pub fn new() -> NodeMap<T> {
+        NodeMap { values: vec![] }
+    }
+
+    /// Sets `node`'s value, returning the one it replaced.
+    pub fn insert(&mut self, node: Node, value: T) -> Option<T> {
+        if node.index() >= self.values.len() {
+            self.values.resize_with(node.index() + 1, || None);
+        }
+        self.values[node.index()].replace(value)
+    }
+
+    /// `node`'s value, if it has one.
+    pub fn get(&self, node: Node) -> Option<&T> {
+        self.values.get(node.index())?.as_ref()
+    }
+
+    /// `node`'s value, if it has one, to change.
+    pub fn get_mut(&mut self, node: Node) -> Option<&mut T> {
+        self.values.get_mut(node.index())?.as_mut()
+    }
+
+    /// `node`'s value, set to what `default` returns first if it has none.
+    pub fn get_or_insert_with<F>(&mut self, node: Node, default: F) -> &mut T
+    where
+        F: FnOnce() -> T,
+    {
+        if self.get(node).is_none() {
+            self.insert(node, default());
+        }
+        self.get_mut(node).unwrap()
+    }
+
+    /// Takes `node`'s value out of the map.
+    pub fn remove(&mut self, node: Node) -> Option<T> {
+        self.values.get_mut(node.index())?.take()
+    }
+
+    /// The values, in the order their nodes were made.
+    pub fn values(&self) -> impl Iterator<Item = &T> + '_ {
+        self.values.iter().flatten()
+    }
+
+    /// Whether no node has a value.
+    pub fn is_empty(&self) -> bool {
+        self.values().next().is_none()
+    }
+
+    /// Removes every value, keeping the storage for reuse.
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+impl<T> Default for NodeMap<T> {
+    fn default() -> NodeMap<T> {
+        NodeMap::new()
+    }
+}
+
+/// Maps layout nodes to the widgets drawn in them.
+///
+/// Lay out the tree first, attach a widget to each node that shows something with `build_node`,
+/// then call `render` on the root once the layout has been computed. Each widget is drawn in
+/// its node's content rect, inside the node's padding. Nodes given an ID with `set_id` have
+/// their rects recorded in the renderer's `WidgetRegistry` as they're drawn.
+pub struct Renderer<'a> {
+    nodes: NodeMap<NodeDrawing<'a>>,
+    registry: RefCell<WidgetRegistry>,
+    parallel_min_area: u32,
+}
+
+// What's drawn in a node.
+#[derive(Default)]
+struct NodeDrawing<'a> {
+    /// Taken when it's drawn, since widgets draw themselves by value.
+    widget: Cell<Option<AnyWidget<'a>>>,
+    bg_color: Option<Color>,
+    id: Option<WidgetId>,
+}
+
+impl<'a> Renderer<'a> {
+    /// Creates a renderer with no widgets.
+    pub fn new() -> Renderer<'a> {
+        Renderer {
+            nodes: NodeMap::new(),
+            registry: RefCell::new(WidgetRegistry::new()),
+            parallel_min_area: PARALLEL_MIN_AREA,
+        }
+    }
+
+    /// Draws `widget` in `node`, replacing any widget already there.
+    pub fn build_node<W>(&mut self, node: Node, widget: W)
+    where
+        W: Into<AnyWidget<'a>>,
+    {
+        self.nodes
+            .get_or_insert_with(node, NodeDrawing::default)
+            .widget = Cell::new(Some(widget.into()));
+    }
+
+    /// Fills `node`, padding included, with `bg_color` before drawing its widget.
+    pub fn set_bg_color(&mut self, node: Node, bg_color: Color) {
+        self.nodes
+            .get_or_insert_with(node, NodeDrawing::default)
+            .bg_color = Some(bg_color);
+    }
+
+    /// Names `node` so that where it's drawn is recorded in the registry.
+    pub fn set_id(&mut self, node: Node, id: WidgetId) {
+        self.nodes.get_or_insert_with(node, NodeDrawing::default).id = Some(id);
+    }
+
+    /// Where the identified nodes drawn so far ended up.
+    pub fn registry(&self) -> Ref<'_, WidgetRegistry> {
+        self.registry.borrow()
+    }
+
+    /// Takes the registry, leaving an empty one behind.
+    pub fn take_registry(&self) -> WidgetRegistry {
+        self.registry.take()
+    }
+
+    /// Paints subtrees side by side on separate threads once the node they're in covers at
+    /// least `area` cells, with the `parallel` feature. Small screens are faster to draw on one
+    /// thread.
+    pub fn set_parallel_min_area(&mut self, area: u32) {
+        self.parallel_min_area = area;
+    }
+
+    /// Draws `node` and its descendants. `world_position` is where `node`'s parent is on
+    /// screen; for the root, that's the origin. tui's widgets draw themselves by value, so each
+    /// widget is taken out of the renderer as it's drawn, while backgrounds and IDs stay for the
+    /// next time the nodes are drawn.
+    ///
+    /// With the `parallel` feature, where a node's children don't overlap and the node is big
+    /// enough, each child's subtree is painted into a buffer of its own on the thread pool, and
+    /// the buffers are copied onto the frame once they're done.
+    pub fn render<B>(
+        &self,
+        frame: &mut Frame<B>,
+        tree: &LayoutTree,
+        node: Node,
+        world_position: Point<u16>,
+    ) where
+        B: Backend,
+    {
+        let placed = self.place(tree, node, world_position);
+        frame.render_widget(
+            Painting {
+                placed,
+                parallel_min_area: self.parallel_min_area,
+            },
+            frame.size(),
+        );
+    }
+
+    // Finds where `node` and its descendants go on screen and takes their widgets, recording
+    // identified nodes in the registry on the way. Layout trees can't be shared between
+    // threads, so this is done before any painting.
+    fn place(&self, tree: &LayoutTree, node: Node, world_position: Point<u16>) -> Placed<'a> {
+        let (widget, bg_color, id) = match self.nodes.get(node) {
+            Some(drawing) => (drawing.widget.take(), drawing.bg_color, drawing.id),
+            None => (None, None, None),
+        };
+        let local_rect = tree.layout(node).to_rect();
+        let local_style = tree.style(node);
+
+        let mut padding_rect = local_rect;
+        let local_padding = resolve_padding(local_style.padding);
+        padding_rect.x += world_position.x;
+        padding_rect.y += world_position.y;
+
+        if let Some(id) = id {
+            self.registry.borrow_mut().insert(id, padding_rect);
+        }
+
+        let mut placed = Placed {
+            bounds: None,
+            fill: None,
+            widget: None,
+            children: vec![],
+        };
+        if let Some(bg_color) = bg_color {
+            placed.bounds = union(placed.bounds, Some(padding_rect));
+            placed.fill = Some((padding_rect, bg_color));
+        }
+
+        if let Some(widget) = widget {
+            // Determine content rect.
+            let mut content_rect = padding_rect;
+            content_rect.x += local_padding.start;
+            content_rect.y += local_padding.top;
+            content_rect.width -= local_padding.start + local_padding.end;
+            content_rect.height -= local_padding.top + local_padding.bottom;
+
+            placed.bounds = union(placed.bounds, Some(content_rect));
+            placed.widget = Some((content_rect, widget));
+        }
+
+        // Recur.
+        for &kid in tree.children(node) {
+            let child = self.place(
+                tree,
+                kid,
+                Point {
+                    x: padding_rect.x,
+                    y: padding_rect.y,
+                },
+            );
+            placed.bounds = union(placed.bounds, child.bounds);
+            placed.children.push(child);
+        }
+        placed
+    }
+}
+
+// What's drawn in a node and its descendants, and where on screen.
+struct Placed<'a> {
+    /// The smallest rect around everything drawn in the subtree, if anything is.
+    bounds: Option<TuiRect>,
+    /// The node's background, filling its padding rect.
+    fill: Option<(TuiRect, Color)>,
+    /// The node's widget, in its content rect.
+    widget: Option<(TuiRect, AnyWidget<'a>)>,
+    children: Vec<Placed<'a>>,
+}
+
+impl<'a> Placed<'a> {
+    fn paint(self, buffer: &mut Buffer, parallel_min_area: u32) {
+        if let Some((rect, bg_color)) = self.fill {
+            if let Some(rect) = clip(rect, buffer.area) {
+                for y in rect.top()..rect.bottom() {
+                    for x in rect.left()..rect.right() {
+                        buffer
+                            .get_mut(x, y)
+                            .set_symbol(" ")
+                            .set_style(TuiStyle::default().bg(bg_color));
+                    }
+                }
+            }
+        }
+        if let Some((rect, widget)) = self.widget {
+            widget.render(rect, buffer);
+        }
+
+        #[cfg(feature = "parallel")]
+        if self.children.len() > 1
+            && self
+                .bounds
+                .and_then(|bounds| clip(bounds, buffer.area))
+                .is_some_and(|bounds| u32::from(bounds.area()) >= parallel_min_area)
+            && disjoint(&self.children)
+        {
+            paint_in_parallel(self.children, buffer, parallel_min_area);
+            return;
+        }
+        paint_in_order(self.children, buffer, parallel_min_area);
+    }
+}
+
+// Paints each of the subtrees straight onto `buffer`, one after another.
+fn paint_in_order(children: Vec<Placed>, buffer: &mut Buffer, parallel_min_area: u32) {
+    for child in children {
+        child.paint(buffer, parallel_min_area);
+    }
+}
+
+// Paints each of the subtrees into a buffer of its own on the thread pool, then copies them
+// onto `buffer`. Each child starts from what's under it, since widgets can leave cells they
+// don't draw on alone or only change some of their style.
+#[cfg(feature = "parallel")]
+fn paint_in_parallel(children: Vec<Placed>, buffer: &mut Buffer, parallel_min_area: u32) {
+    let parts: Vec<_> = children
+        .into_iter()
+        .filter_map(|child| {
+            let area = clip(child.bounds?, buffer.area)?;
+            Some((child, copy_area(buffer, area)))
+        })
+        .collect();
+    let child_buffers: Vec<_> = parts
+        .into_par_iter()
+        .map(|(child, mut child_buffer)| {
+            child.paint(&mut child_buffer, parallel_min_area);
+            child_buffer
+        })
+        .collect();
+    for child_buffer in &child_buffers {
+        let area = child_buffer.area;
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                *buffer.get_mut(x, y) = child_buffer.get(x, y).clone();
+            }
+        }
+    }
+}
+
+// Draws placed widgets, wherever they were placed, no matter the area it's given.
+struct Painting<'a> {
+    placed: Placed<'a>,
+    parallel_min_area: u32,
+}
+
+impl<'a> Widget for Painting<'a> {
+    fn render(self, _: TuiRect, buffer: &mut Buffer) {
+        self.placed.paint(buffer, self.parallel_min_area);
+    }
+}
+
+// Whether none of the subtrees draw over each other.
+#[cfg(feature = "parallel")]
+fn disjoint(placed: &[Placed]) -> bool {
+    let bounds: Vec<_> = placed.iter().filter_map(|placed| placed.bounds).collect();
+    bounds.iter().enumerate().all(|(index, rect)| {
+        bounds[index + 1..]
+            .iter()
+            .all(|other_rect| !rect.intersects(*other_rect))
+    })
+}
+
+// The smallest rect around both, leaving out empty ones.
+fn union(rect: Option<TuiRect>, other_rect: Option<TuiRect>) -> Option<TuiRect> {
+    let rect = rect.filter(|rect| rect.area() > 0);
+    let other_rect = other_rect.filter(|rect| rect.area() > 0);
+    match (rect, other_rect) {
+        (Some(rect), Some(other_rect)) => Some(rect.union(other_rect)),
+        (rect, None) => rect,
+        (None, other_rect) => other_rect,
+    }
+}
+
+// The part of `rect` inside `bounds`, unless it's empty.
+fn clip(rect: TuiRect, bounds: TuiRect) -> Option<TuiRect> {
+    if rect.intersects(bounds) {
+        Some(rect.intersection(bounds))
+    } else {
+        None
+    }
+}
+
+// A buffer holding a copy of the cells in `area`.
+#[cfg(feature = "parallel")]
+fn copy_area(buffer: &Buffer, area: TuiRect) -> Buffer {
+    let mut copy = Buffer::empty(area);
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            *copy.get_mut(x, y) = buffer.get(x, y).clone();
+        }
+    }
+    copy
+}
+
+impl<'a> Default for Renderer<'a> {
+    fn default() -> Renderer<'a> {
+        Renderer::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::SizeExt;
+    use crate::ui;
+    use stretch::geometry::Size;
+    use stretch::number::Number;
+    use stretch::style::{FlexDirection, Style};
+    use tui::backend::TestBackend;
+    use tui::widgets::Paragraph;
+    use tui::Terminal;
+
+    static BUTTON_ID: WidgetId = WidgetId("button");
+    static LIST_ID: WidgetId = WidgetId("list");
+    static POPUP_ID: WidgetId = WidgetId("popup");
+
+    #[test]
+    fn rendering_records_where_identified_nodes_went() {
+        let mut stretch = LayoutTree::new();
+        let mut renderer = Renderer::new();
+        let root = stretch.new_node(Style {
+            size: Size::fixed(20, 10),
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        });
+        ui!(stretch, renderer; root => [
+            _toolbar { size: Size::fixed_height(1) } [
+                _spacer { size: Size::fixed_width(4) },
+                button { size: Size::fixed_width(6) } (Paragraph::new("Button")),
+            ],
+            list { flex_grow: 1.0 },
+        ]);
+        renderer.set_id(button, BUTTON_ID);
+        renderer.set_id(list, LIST_ID);
+        stretch.compute_layout(
+            root,
+            Size {
+                width: Number::Undefined,
+                height: Number::Undefined,
+            },
+        );
+        let mut terminal = Terminal::new(TestBackend::new(20, 10)).unwrap();
+        terminal
+            .draw(|frame| renderer.render(frame, &stretch, root, Point { x: 0, y: 0 }))
+            .unwrap();
+
+        let registry = renderer.take_registry();
+        assert_eq!(registry.rect(BUTTON_ID), Some(TuiRect::new(4, 0, 6, 1)));
+        assert_eq!(registry.rect(LIST_ID), Some(TuiRect::new(0, 1, 20, 9)));
+        assert_eq!(registry.hit_test(4, 0), Some(BUTTON_ID));
+        assert_eq!(registry.hit_test(3, 0), None);
+        assert_eq!(registry.hit_test(19, 9), Some(LIST_ID));
+        assert!(renderer.registry().ids().next().is_none());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn painting_subtrees_in_parallel_draws_what_painting_them_in_order_does() {
+        let draw = |parallel_min_area| {
+            let mut stretch = LayoutTree::new();
+            let mut renderer = Renderer::new();
+            renderer.set_parallel_min_area(parallel_min_area);
+            let root = stretch.new_node(Style {
+                size: Size::fixed(24, 4),
+                flex_direction: FlexDirection::Column,
+                ..Default::default()
+            });
+            ui!(stretch, renderer; root => [
+                row { size: Size::fixed_height(2) } [
+                    left { flex_grow: 1.0 } (Paragraph::new("left\nside")),
+                    right { flex_grow: 1.0 } [
+                        inner { size: Size::fixed_width(5) } (
+                            Paragraph::new("right").style(TuiStyle::default().fg(Color::Red))
+                        ),
+                    ],
+                ],
+                _bottom { flex_grow: 1.0 } (Paragraph::new("bottom")),
+            ]);
+            renderer.set_bg_color(row, Color::Blue);
+            renderer.set_bg_color(inner, Color::Green);
+            renderer.set_id(left, BUTTON_ID);
+            stretch.compute_layout(
+                root,
+                Size {
+                    width: Number::Undefined,
+                    height: Number::Undefined,
+                },
+            );
+            let mut terminal = Terminal::new(TestBackend::new(24, 4)).unwrap();
+            terminal
+                .draw(|frame| renderer.render(frame, &stretch, root, Point { x: 0, y: 0 }))
+                .unwrap();
+            (
+                terminal.backend().buffer().clone(),
+                renderer.take_registry().rect(BUTTON_ID),
+            )
+        };
+
+        let (buffer, button_rect) = draw(0);
+        assert_eq!((buffer.clone(), button_rect), draw(u32::MAX));
+        assert_eq!(buffer.get(0, 1).symbol, "s");
+        assert_eq!(buffer.get(0, 1).bg, Color::Blue);
+        assert_eq!(buffer.get(12, 0).fg, Color::Red);
+        assert_eq!(buffer.get(12, 0).bg, Color::Green);
+        assert_eq!(buffer.get(17, 0).bg, Color::Blue);
+        assert_eq!(button_rect, Some(TuiRect::new(0, 0, 10, 2)));
+    }
+
+    #[test]
+    fn node_maps_keep_values_in_the_order_their_nodes_were_made() {
+        let mut stretch = LayoutTree::new();
+        let nodes: Vec<Node> = (0..3).map(|_| stretch.new_node(Style::default())).collect();
+
+        let mut map = NodeMap::new();
+        map.insert(nodes[2], "c");
+        map.insert(nodes[0], "a");
+        assert_eq!(map.insert(nodes[0], "A"), Some("a"));
+        assert_eq!(map.get(nodes[1]), None);
+        assert_eq!(map.values().copied().collect::<Vec<_>>(), ["A", "c"]);
+        assert_eq!(map.remove(nodes[2]), Some("c"));
+        assert_eq!(map.remove(nodes[2]), None);
+        map.insert(nodes[1], "b");
+        assert_eq!(map.values().copied().collect::<Vec<_>>(), ["A", "b"]);
+
+        stretch.clear();
+        map.clear();
+        assert!(map.is_empty());
+        let node = stretch.new_node(Style::default());
+        assert_eq!(map.get(node), None);
+        assert_eq!(map.insert(node, "d"), None);
+        assert_eq!(map.get(node), Some(&"d"));
+    }
+
+    #[test]
+    fn focus_wraps_and_popups_stay_on_screen() {
+        let mut registry = WidgetRegistry::new();
+        registry.insert(BUTTON_ID, TuiRect::new(14, 0, 6, 1));
+        registry.insert(LIST_ID, TuiRect::new(0, 1, 20, 9));
+        // Drawn last, so on top of the list.
+        registry.insert(POPUP_ID, TuiRect::new(2, 2, 4, 4));
+        assert_eq!(registry.hit_test(3, 3), Some(POPUP_ID));
+
+        assert_eq!(registry.next_focus(None), Some(BUTTON_ID));
+        assert_eq!(registry.next_focus(Some(BUTTON_ID)), Some(LIST_ID));
+        assert_eq!(registry.next_focus(Some(POPUP_ID)), Some(BUTTON_ID));
+        assert_eq!(registry.previous_focus(Some(BUTTON_ID)), Some(POPUP_ID));
+        assert_eq!(registry.previous_focus(None), Some(POPUP_ID));
+        assert_eq!(WidgetRegistry::new().next_focus(None), None);
+
+        let bounds = TuiRect::new(0, 0, 20, 10);
+        assert_eq!(
+            registry.anchor_popup(BUTTON_ID, 8, 3, bounds),
+            Some(TuiRect::new(12, 1, 8, 3))
+        );
+        assert_eq!(
+            registry.anchor_popup(LIST_ID, 8, 3, bounds),
+            Some(TuiRect::new(0, 0, 8, 3))
+        );
+        assert_eq!(
+            registry.anchor_popup(WidgetId("missing"), 8, 3, bounds),
+            None
+        );
+    }
+}
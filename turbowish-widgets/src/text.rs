@@ -0,0 +1,252 @@
+//! Measuring, truncating, and aligning styled text by terminal columns, and fuzzy matching it
+//! for search.
+
+use tui::layout::Alignment;
+use tui::style::Style;
+use tui::text::{Span, Spans};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// The usual ellipsis for `truncate_spans`, for fonts that have it.
+pub static ELLIPSIS: &'static str = "…";
+// Padding is borrowed from here, rather than allocated, where this is long enough.
+static SPACES: &'static str =
+    "                                                                                ";
+
+/// Returns how many terminal columns `string` occupies. Use this rather than counting `chars()`
+/// when laying out text, since CJK characters and some icon glyphs are two columns wide.
+pub fn display_width(string: &str) -> u16 {
+    string.width() as u16
+}
+
+/// Cuts `spans` down to at most `width` terminal columns, ending with `ellipsis` if anything
+/// was removed. Widths are display widths, so double-width characters count as two columns.
+pub fn truncate_spans<'a>(spans: Spans<'a>, width: u16, ellipsis: &'a str) -> Spans<'a> {
+    let width = width as usize;
+    if spans.width() <= width {
+        return spans;
+    }
+    let ellipsis_width = ellipsis.width();
+    if width < ellipsis_width.max(1) {
+        return Spans::default();
+    }
+
+    // Leave room for the ellipsis, which takes the style of the text it replaces.
+    let mut remaining_width = width - ellipsis_width;
+    let mut truncated_spans = vec![];
+    let mut ellipsis_style = Style::default();
+    for span in spans.0 {
+        ellipsis_style = span.style;
+        let span_width = span.width();
+        if span_width <= remaining_width {
+            remaining_width -= span_width;
+            truncated_spans.push(span);
+            continue;
+        }
+
+        let mut prefix = String::new();
+        for character in span.content.chars() {
+            let character_width = character.width().unwrap_or(0);
+            if character_width > remaining_width {
+                break;
+            }
+            remaining_width -= character_width;
+            prefix.push(character);
+        }
+        if !prefix.is_empty() {
+            truncated_spans.push(Span::styled(prefix, span.style));
+        }
+        break;
+    }
+    truncated_spans.push(Span::styled(ellipsis, ellipsis_style));
+    Spans::from(truncated_spans)
+}
+
+/// Pads `spans` with spaces to fill `width` columns, placing the text according to `alignment`.
+/// Text that is already at least `width` columns wide is returned unchanged.
+pub fn align_spans(mut spans: Spans, width: u16, alignment: Alignment) -> Spans {
+    let padding = (width as usize).saturating_sub(spans.width());
+    let (leading_padding, trailing_padding) = match alignment {
+        Alignment::Left => (0, padding),
+        Alignment::Center => (padding / 2, padding - padding / 2),
+        Alignment::Right => (padding, 0),
+    };
+
+    if leading_padding > 0 {
+        spans.0.insert(0, padding_span(leading_padding));
+    }
+    if trailing_padding > 0 {
+        spans.0.push(padding_span(trailing_padding));
+    }
+    spans
+}
+
+// `width` spaces.
+fn padding_span(width: usize) -> Span<'static> {
+    match SPACES.get(..width) {
+        Some(spaces) => Span::raw(spaces),
+        None => Span::raw(" ".repeat(width)),
+    }
+}
+
+/// Estimates how many rows `spans` takes up when word-wrapped to `width` columns, as a
+/// `Paragraph` with trimming wrap would lay it out. Words wider than a row are split across
+/// rows. An empty line still takes up one row.
+pub fn wrapped_height(spans: &Spans, width: u16) -> u16 {
+    let width = width.max(1) as usize;
+    let text: String = spans.0.iter().map(|span| &span.content[..]).collect();
+    let (mut rows, mut row_width) = (1, 0);
+    for word in text.split_whitespace() {
+        let word_width = word.width();
+        let needed_width = if row_width == 0 {
+            word_width
+        } else {
+            row_width + 1 + word_width
+        };
+        if needed_width <= width {
+            row_width = needed_width;
+            continue;
+        }
+        if row_width > 0 {
+            rows += 1;
+        }
+        rows += (word_width.saturating_sub(1)) / width;
+        row_width = word_width - (word_width.saturating_sub(1)) / width * width;
+    }
+    rows as u16
+}
+
+/// How well a fuzzy search pattern matched some text.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FuzzyMatch {
+    /// Higher for better matches. Only comparable between matches of the same pattern.
+    pub score: i32,
+    /// The indices of the characters of the text that matched, in order, for highlighting.
+    pub positions: Vec<usize>,
+}
+
+const FUZZY_MATCH_SCORE: i32 = 1;
+const FUZZY_CONSECUTIVE_BONUS: i32 = 4;
+const FUZZY_WORD_START_BONUS: i32 = 3;
+
+/// Matches `pattern` against `text` the way fuzzy finders do: every character of the pattern
+/// has to appear in the text in order, ignoring case, but not necessarily together. Runs of
+/// consecutive characters and characters that start words score higher, so that `run` matches
+/// "Filter to running tasks" better than it matches "Rerun". Spaces in the pattern are ignored.
+/// Returns `None` if the pattern doesn't match at all.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|character| !character.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let text: Vec<char> = text.chars().collect();
+    if pattern.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: vec![],
+        });
+    }
+
+    // `scores[i][j]` is the best score for matching the first `i + 1` characters of the pattern
+    // with the last of them at `text[j]`, and `previous[i][j]` is where the character before it
+    // matched in that best match.
+    let (pattern_length, text_length) = (pattern.len(), text.len());
+    let mut scores = vec![vec![None; text_length]; pattern_length];
+    let mut previous = vec![vec![0; text_length]; pattern_length];
+    for (i, &pattern_character) in pattern.iter().enumerate() {
+        // The best score for the pattern so far ending anywhere before `j - 1`.
+        let mut best_before: Option<(i32, usize)> = None;
+        for j in 0..text_length {
+            if i > 0 && j >= 2 {
+                if let Some(score) = scores[i - 1][j - 2] {
+                    if best_before.is_none_or(|(best, _)| score > best) {
+                        best_before = Some((score, j - 2));
+                    }
+                }
+            }
+            if !text[j].to_lowercase().eq(Some(pattern_character)) {
+                continue;
+            }
+            let word_start = j == 0 || !text[j - 1].is_alphanumeric();
+            let bonus = FUZZY_MATCH_SCORE
+                + if word_start {
+                    FUZZY_WORD_START_BONUS
+                } else {
+                    0
+                };
+            if i == 0 {
+                scores[i][j] = Some(bonus);
+                continue;
+            }
+            let consecutive = if j > 0 {
+                scores[i - 1][j - 1].map(|score| (score + FUZZY_CONSECUTIVE_BONUS, j - 1))
+            } else {
+                None
+            };
+            let best = match (best_before, consecutive) {
+                (Some(before), Some(consecutive)) if before.0 > consecutive.0 => Some(before),
+                (_, Some(consecutive)) => Some(consecutive),
+                (before, None) => before,
+            };
+            if let Some((score, k)) = best {
+                scores[i][j] = Some(score + bonus);
+                previous[i][j] = k;
+            }
+        }
+    }
+
+    let (score, mut j) = scores[pattern_length - 1]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| score.map(|score| (score, j)))
+        .max_by_key(|&(score, j)| (score, std::cmp::Reverse(j)))?;
+    let mut positions = vec![j; pattern_length];
+    for i in (1..pattern_length).rev() {
+        j = previous[i][j];
+        positions[i - 1] = j;
+    }
+    Some(FuzzyMatch { score, positions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spans_are_padded_on_the_side_away_from_their_alignment() {
+        let text =
+            |spans: Spans| -> String { spans.0.iter().map(|span| &span.content[..]).collect() };
+        let spans = || Spans::from(vec![Span::raw("ab"), Span::raw("c")]);
+        assert_eq!(text(align_spans(spans(), 6, Alignment::Left)), "abc   ");
+        assert_eq!(text(align_spans(spans(), 6, Alignment::Center)), " abc  ");
+        assert_eq!(text(align_spans(spans(), 6, Alignment::Right)), "   abc");
+        assert_eq!(text(align_spans(spans(), 2, Alignment::Right)), "abc");
+        let wide = align_spans(spans(), 200, Alignment::Right);
+        assert_eq!(wide.width(), 200);
+        assert!(text(wide).ends_with(" abc"));
+    }
+
+    #[test]
+    fn fuzzy_matches_prefer_runs_and_word_starts() {
+        let positions = |pattern, text| fuzzy_match(pattern, text).map(|m| m.positions);
+        assert_eq!(
+            positions("run", "Filter to running tasks"),
+            Some(vec![10, 11, 12])
+        );
+        assert_eq!(
+            positions("FTR", "Filter to running tasks"),
+            Some(vec![0, 7, 10])
+        );
+        assert_eq!(
+            positions("open 42", "Open task 42"),
+            Some(vec![0, 1, 2, 3, 10, 11])
+        );
+        assert_eq!(positions("", "Anything"), Some(vec![]));
+        assert_eq!(positions("xyz", "Filter to running tasks"), None);
+        assert_eq!(positions("ksat", "tasks"), None);
+
+        let score = |pattern, text| fuzzy_match(pattern, text).unwrap().score;
+        assert!(score("run", "Filter to running tasks") > score("run", "Rerun"));
+        assert!(score("csv", "Export as CSV") > score("csv", "Choose sort values"));
+    }
+}
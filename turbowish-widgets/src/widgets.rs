@@ -0,0 +1,4604 @@
+use crate::text::{display_width, truncate_spans, ELLIPSIS};
+use derive_more::{Constructor, From};
+use std::mem;
+use std::sync::Mutex;
+use std::time::Duration;
+use tui::buffer::Buffer;
+use tui::layout::{Alignment, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Borders, Chart, Paragraph, Table, Widget};
+use unicode_width::UnicodeWidthChar;
+
+static FRAME_SYMBOLS_ROUNDED: FrameSymbols = FrameSymbols {
+    upper_left: "╭",
+    upper_right: "╮",
+    lower_right: "╯",
+    lower_left: "╰",
+    horizontal: "─",
+    vertical: "│",
+};
+static FRAME_SYMBOLS_SQUARE: FrameSymbols = FrameSymbols {
+    upper_left: "┌",
+    upper_right: "┐",
+    lower_right: "┘",
+    lower_left: "└",
+    horizontal: "─",
+    vertical: "│",
+};
+static FRAME_SYMBOLS_DOUBLE: FrameSymbols = FrameSymbols {
+    upper_left: "╔",
+    upper_right: "╗",
+    lower_right: "╝",
+    lower_left: "╚",
+    horizontal: "═",
+    vertical: "║",
+};
+static FRAME_SYMBOLS_THICK: FrameSymbols = FrameSymbols {
+    upper_left: "┏",
+    upper_right: "┓",
+    lower_right: "┛",
+    lower_left: "┗",
+    horizontal: "━",
+    vertical: "┃",
+};
+static FRAME_SYMBOLS_ASCII: FrameSymbols = FrameSymbols {
+    upper_left: "+",
+    upper_right: "+",
+    lower_right: "+",
+    lower_left: "+",
+    horizontal: "-",
+    vertical: "|",
+};
+/// Arrows from Nerd Fonts and shaded blocks.
+pub static SCROLLBAR_SYMBOLS_NERD_FONT: ScrollbarSymbols = ScrollbarSymbols {
+    up: "\u{f431}",
+    down: "\u{f433}",
+    track: "░",
+    thumb: "█",
+};
+/// Plain ASCII, for terminals without box-drawing glyphs.
+pub static SCROLLBAR_SYMBOLS_ASCII: ScrollbarSymbols = ScrollbarSymbols {
+    up: "^",
+    down: "v",
+    track: "|",
+    thumb: "#",
+};
+/// The default, and the most detailed.
+pub static CHART_SYMBOLS_BRAILLE: ChartSymbols = ChartSymbols::Braille;
+/// Eighth blocks, for fonts with poor braille.
+pub static CHART_SYMBOLS_BLOCKS: ChartSymbols =
+    ChartSymbols::Bars(&[" ", "▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"]);
+/// Plain ASCII, with three levels per cell.
+pub static CHART_SYMBOLS_ASCII: ChartSymbols = ChartSymbols::Bars(&[" ", "_", "=", "#"]);
+/// Arrows from Nerd Fonts and Powerline fonts.
+pub static POWERLINE_SYMBOLS_NERD_FONT: PowerlineSymbols = PowerlineSymbols {
+    main_ltr: "\u{e0b0}",
+    sub_ltr: "\u{e0b1}",
+    main_rtl: "\u{e0b2}",
+    sub_rtl: "\u{e0b3}",
+};
+/// Plain ASCII, for terminals without Powerline glyphs.
+pub static POWERLINE_SYMBOLS_ASCII: PowerlineSymbols = PowerlineSymbols {
+    main_ltr: ">",
+    sub_ltr: "|",
+    main_rtl: "<",
+    sub_rtl: "|",
+};
+/// Rounded ends from Nerd Fonts.
+pub static SEGMENTED_CONTROL_SYMBOLS_NERD_FONT: SegmentedControlSymbols = SegmentedControlSymbols {
+    left_end: "\u{e0b6}",
+    right_end: "\u{e0b4}",
+    ellipsis: ELLIPSIS,
+};
+/// Plain ASCII, with brackets for ends.
+pub static SEGMENTED_CONTROL_SYMBOLS_ASCII: SegmentedControlSymbols = SegmentedControlSymbols {
+    left_end: "[",
+    right_end: "]",
+    ellipsis: "~",
+};
+/// Pie glyphs, and a ring drawn heavy where it's filled and thin where it isn't.
+pub static RING_SYMBOLS_UNICODE: RingSymbols = RingSymbols {
+    pies: ["○", "◔", "◑", "◕", "●"],
+    filled_border_set: BorderSet::Thick,
+    track_border_set: BorderSet::Rounded,
+};
+/// Plain ASCII, telling the filled part of the ring from the rest by color alone.
+pub static RING_SYMBOLS_ASCII: RingSymbols = RingSymbols {
+    pies: [".", ":", "o", "O", "@"],
+    filled_border_set: BorderSet::Ascii,
+    track_border_set: BorderSet::Ascii,
+};
+static BAR_CHART_AXIS_VERTICAL_SYMBOL: &'static str = "│";
+static BAR_CHART_AXIS_TICK_SYMBOL: &'static str = "┤";
+static BAR_CHART_AXIS_ORIGIN_SYMBOL: &'static str = "└";
+static BAR_CHART_AXIS_HORIZONTAL_SYMBOL: &'static str = "─";
+static BAR_CHART_AXIS_BASELINE_TICK_SYMBOL: &'static str = "┴";
+static GRAPH_LEGEND_MARKER_SYMBOL: &'static str = "━";
+static CODE_VIEW_MARK_SYMBOL: &'static str = "▶";
+static RUST_KEYWORDS: [&'static str; 38] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+static MARKDOWN_BULLET_SYMBOL: &'static str = "•";
+static TIMELINE_IDLE_SYMBOL: &'static str = "─";
+static TIMELINE_BUSY_SYMBOL: &'static str = "━";
+static TIMELINE_SPAWN_SYMBOL: &'static str = "├";
+static TIME_RULER_TICK_SYMBOL: &'static str = "╵";
+static GRAPH_VIEW_ARROW_SYMBOL: &'static str = "▼";
+// Indexed by a mask of the directions a line leaves the cell in.
+static GRAPH_VIEW_LINE_SYMBOLS: [&'static str; 16] = [
+    " ", "│", "│", "│", "─", "┘", "┐", "┤", "─", "└", "┌", "├", "─", "┴", "┬", "┼",
+];
+static BIG_TEXT_GLYPHS: [(char, BigGlyph); 16] = [
+    ('0', ["█▀█", "█ █", "█▄█"]),
+    ('1', ["▀█ ", " █ ", "▄█▄"]),
+    ('2', ["▀▀█", "█▀▀", "█▄▄"]),
+    ('3', ["▀▀█", " ▀█", "▄▄█"]),
+    ('4', ["█ █", "▀▀█", "  █"]),
+    ('5', ["█▀▀", "▀▀█", "▄▄█"]),
+    ('6', ["█▀▀", "█▀█", "█▄█"]),
+    ('7', ["▀▀█", "  █", "  █"]),
+    ('8', ["█▀█", "█▀█", "█▄█"]),
+    ('9', ["█▀█", "▀▀█", "▄▄█"]),
+    ('.', [" ", " ", "▄"]),
+    (',', [" ", " ", "▄"]),
+    (':', [" ", "▀", "▀"]),
+    ('-', ["   ", "▀▀▀", "   "]),
+    ('+', ["   ", "▄█▄", " ▀ "]),
+    ('%', ["█ ▄", " ▄▀", "▀ █"]),
+];
+// Indexed by a mask of the lit halves, top half first.
+static HALF_BLOCKS: [&'static str; 4] = [" ", "▀", "▄", "█"];
+
+const BAR_CHART_AXIS_TICK_SPACING: u16 = 5;
+/// The round intervals a time ruler's ticks can be apart, shortest first.
+const TIME_RULER_INTERVALS: [Duration; 21] = [
+    Duration::from_millis(100),
+    Duration::from_millis(200),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_secs(2),
+    Duration::from_secs(5),
+    Duration::from_secs(10),
+    Duration::from_secs(15),
+    Duration::from_secs(30),
+    Duration::from_secs(60),
+    Duration::from_secs(2 * 60),
+    Duration::from_secs(5 * 60),
+    Duration::from_secs(10 * 60),
+    Duration::from_secs(15 * 60),
+    Duration::from_secs(30 * 60),
+    Duration::from_secs(60 * 60),
+    Duration::from_secs(2 * 60 * 60),
+    Duration::from_secs(3 * 60 * 60),
+    Duration::from_secs(6 * 60 * 60),
+    Duration::from_secs(12 * 60 * 60),
+    Duration::from_secs(24 * 60 * 60),
+];
+const GRAPH_VIEW_UP: u8 = 1;
+const GRAPH_VIEW_DOWN: u8 = 2;
+const GRAPH_VIEW_LEFT: u8 = 4;
+const GRAPH_VIEW_RIGHT: u8 = 8;
+/// Rows between layers, for the arrows to turn in and then point down from.
+const GRAPH_VIEW_LAYER_GAP: u16 = 2;
+/// Columns between the nodes in a layer.
+const GRAPH_VIEW_NODE_GAP: u16 = 3;
+const SCROLLBAR_MIN_THUMB_LENGTH: u16 = 1;
+const BIG_TEXT_HEIGHT: u16 = 3;
+const BIG_TEXT_GAP: u16 = 1;
+
+static DOTS: [char; 256] = [
+    '⠀', '⡀', '⠄', '⡄', '⠂', '⡂', '⠆', '⡆', '⠁', '⡁', '⠅', '⡅', '⠃', '⡃', '⠇', '⡇', '⢀', '⣀', '⢄',
+    '⣄', '⢂', '⣂', '⢆', '⣆', '⢁', '⣁', '⢅', '⣅', '⢃', '⣃', '⢇', '⣇', '⠠', '⡠', '⠤', '⡤', '⠢', '⡢',
+    '⠦', '⡦', '⠡', '⡡', '⠥', '⡥', '⠣', '⡣', '⠧', '⡧', '⢠', '⣠', '⢤', '⣤', '⢢', '⣢', '⢦', '⣦', '⢡',
+    '⣡', '⢥', '⣥', '⢣', '⣣', '⢧', '⣧', '⠐', '⡐', '⠔', '⡔', '⠒', '⡒', '⠖', '⡖', '⠑', '⡑', '⠕', '⡕',
+    '⠓', '⡓', '⠗', '⡗', '⢐', '⣐', '⢔', '⣔', '⢒', '⣒', '⢖', '⣖', '⢑', '⣑', '⢕', '⣕', '⢓', '⣓', '⢗',
+    '⣗', '⠰', '⡰', '⠴', '⡴', '⠲', '⡲', '⠶', '⡶', '⠱', '⡱', '⠵', '⡵', '⠳', '⡳', '⠷', '⡷', '⢰', '⣰',
+    '⢴', '⣴', '⢲', '⣲', '⢶', '⣶', '⢱', '⣱', '⢵', '⣵', '⢳', '⣳', '⢷', '⣷', '⠈', '⡈', '⠌', '⡌', '⠊',
+    '⡊', '⠎', '⡎', '⠉', '⡉', '⠍', '⡍', '⠋', '⡋', '⠏', '⡏', '⢈', '⣈', '⢌', '⣌', '⢊', '⣊', '⢎', '⣎',
+    '⢉', '⣉', '⢍', '⣍', '⢋', '⣋', '⢏', '⣏', '⠨', '⡨', '⠬', '⡬', '⠪', '⡪', '⠮', '⡮', '⠩', '⡩', '⠭',
+    '⡭', '⠫', '⡫', '⠯', '⡯', '⢨', '⣨', '⢬', '⣬', '⢪', '⣪', '⢮', '⣮', '⢩', '⣩', '⢭', '⣭', '⢫', '⣫',
+    '⢯', '⣯', '⠘', '⡘', '⠜', '⡜', '⠚', '⡚', '⠞', '⡞', '⠙', '⡙', '⠝', '⡝', '⠛', '⡛', '⠟', '⡟', '⢘',
+    '⣘', '⢜', '⣜', '⢚', '⣚', '⢞', '⣞', '⢙', '⣙', '⢝', '⣝', '⢛', '⣛', '⢟', '⣟', '⠸', '⡸', '⠼', '⡼',
+    '⠺', '⡺', '⠾', '⡾', '⠹', '⡹', '⠽', '⡽', '⠻', '⡻', '⠿', '⡿', '⢸', '⣸', '⢼', '⣼', '⢺', '⣺', '⢾',
+    '⣾', '⢹', '⣹', '⢽', '⣽', '⢻', '⣻', '⢿', '⣿',
+];
+
+/// Any of the widgets a `Renderer` can draw. Every variant converts from its widget with
+/// `into()`.
+///
+/// Unlike the widgets on their own, `AnyWidget` clips its area to the buffer first, so it's safe
+/// to draw even where the layout has overflowed the screen.
+#[derive(From)]
+pub enum AnyWidget<'a> {
+    /// A bar chart.
+    BarChart(BarChart<'a>),
+    /// Text drawn in large block letters.
+    BigText(BigText<'a>),
+    /// A frame drawn around an area.
+    BoxFrame(BoxFrame<'a>),
+    /// Shapes drawn as dots.
+    Canvas(Canvas),
+    /// A line chart with axes.
+    Chart(Chart<'a>),
+    /// Source code with syntax highlighting.
+    CodeView(CodeView<'a>),
+    /// Lines through series of samples.
+    Graph(Graph<'a>),
+    /// Nodes in layers, joined by arrows.
+    GraphView(GraphView<'a>),
+    /// Markdown text, laid out and styled.
+    Markdown(Markdown<'a>),
+    /// A paragraph of wrapped text.
+    Paragraph(Paragraph<'a>),
+    /// A row of powerline segments.
+    Powerline(Powerline<'a>),
+    /// A gauge drawn as a ring.
+    Ring(Ring),
+    /// A scrollbar.
+    Scrollbar(Scrollbar<'a>),
+    /// A row of segments, one of which is selected.
+    SegmentedControl(SegmentedControl<'a>),
+    /// A table of rows and columns.
+    Table(Table<'a>),
+    /// A single-line text field.
+    TextInput(TextInput<'a>),
+    /// A ruler marking times along a timeline.
+    TimeRuler(TimeRuler<'a>),
+    /// Rows of bars along a time axis.
+    Timeline(Timeline<'a>),
+}
+
+impl<'a> Widget for AnyWidget<'a> {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        // tui's own widgets assume their area lies inside the buffer, which stops being true
+        // once the layout overflows a small terminal.
+        let area = clip_to_buffer(area, buffer);
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        match self {
+            AnyWidget::BarChart(widget) => widget.render(area, buffer),
+            AnyWidget::BigText(widget) => widget.render(area, buffer),
+            AnyWidget::BoxFrame(widget) => widget.render(area, buffer),
+            AnyWidget::Canvas(widget) => widget.render(area, buffer),
+            AnyWidget::Chart(widget) => widget.render(area, buffer),
+            AnyWidget::CodeView(widget) => widget.render(area, buffer),
+            AnyWidget::Graph(widget) => widget.render(area, buffer),
+            AnyWidget::GraphView(widget) => widget.render(area, buffer),
+            AnyWidget::Markdown(widget) => widget.render(area, buffer),
+            AnyWidget::Paragraph(widget) => widget.render(area, buffer),
+            AnyWidget::Powerline(widget) => widget.render(area, buffer),
+            AnyWidget::Ring(widget) => widget.render(area, buffer),
+            AnyWidget::Scrollbar(widget) => widget.render(area, buffer),
+            AnyWidget::SegmentedControl(widget) => widget.render(area, buffer),
+            AnyWidget::Table(widget) => widget.render(area, buffer),
+            AnyWidget::TextInput(widget) => widget.render(area, buffer),
+            AnyWidget::TimeRuler(widget) => widget.render(area, buffer),
+            AnyWidget::Timeline(widget) => widget.render(area, buffer),
+        }
+    }
+}
+
+/// A widget that can say how much room it needs to draw all of its content, so that layout
+/// code can size its node without repeating the widget's own arithmetic.
+pub trait Measure {
+    /// The number of columns the widget takes up when nothing is cut off.
+    fn preferred_width(&self) -> u16;
+
+    /// The number of rows the widget takes up when nothing is cut off.
+    fn preferred_height(&self) -> u16 {
+        1
+    }
+}
+
+// Segmented controls
+
+/// A row of labels with rounded ends, one of which is selected and drawn inverted. Create one
+/// with `SegmentedControl::new(labels, selected_index, bg_color, fg_color)`. In less than its
+/// preferred width, the longest labels are cut short with an ellipsis, keeping both ends.
+pub struct SegmentedControl<'a> {
+    labels: &'a [&'a str],
+    selected_index: u32,
+    bg_color: Color,
+    fg_color: Color,
+    symbols: &'a SegmentedControlSymbols,
+}
+
+/// The symbols a `SegmentedControl` draws with. The ends must be a single column wide.
+pub struct SegmentedControlSymbols {
+    /// The cap before the first label.
+    pub left_end: &'static str,
+    /// The cap after the last label.
+    pub right_end: &'static str,
+    /// What ends a label that's been cut short.
+    pub ellipsis: &'static str,
+}
+
+impl<'a> Widget for SegmentedControl<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = clip_to_buffer(area, buf);
+        if area.width == 0 || area.height == 0 || self.labels.is_empty() {
+            return;
+        }
+
+        let mut x = area.x;
+        let left_edge_style = if self.selected_index == 0 {
+            Style::default().fg(self.fg_color)
+        } else {
+            Style::default().fg(self.bg_color)
+        };
+        x = set_string_clipped(buf, area, x, area.y, self.symbols.left_end, left_edge_style);
+
+        let label_widths = self.fitted_label_widths(area.width);
+        for (index, (label, &label_width)) in self.labels.iter().zip(&label_widths).enumerate() {
+            let style = if index == self.selected_index as usize {
+                Style::default().fg(self.bg_color).bg(self.fg_color)
+            } else {
+                Style::default().fg(self.fg_color).bg(self.bg_color)
+            };
+            if index > 0 {
+                x = set_string_clipped(buf, area, x, area.y, " ", style);
+            }
+            let label = truncate_spans(Spans::from(*label), label_width, self.symbols.ellipsis);
+            for span in label.0 {
+                x = set_string_clipped(buf, area, x, area.y, &span.content, style);
+            }
+            if index < self.labels.len() - 1 {
+                x = set_string_clipped(buf, area, x, area.y, " ", style);
+            }
+        }
+
+        let right_edge_style = if self.selected_index as usize == self.labels.len() - 1 {
+            Style::default().fg(self.fg_color)
+        } else {
+            Style::default().fg(self.bg_color)
+        };
+        set_string_clipped(
+            buf,
+            area,
+            x,
+            area.y,
+            self.symbols.right_end,
+            right_edge_style,
+        );
+    }
+}
+
+impl<'a> SegmentedControl<'a> {
+    /// Starts a control with the Nerd Font ends and ellipsis, which `symbols` changes.
+    pub fn new(
+        labels: &'a [&'a str],
+        selected_index: u32,
+        bg_color: Color,
+        fg_color: Color,
+    ) -> SegmentedControl<'a> {
+        SegmentedControl {
+            labels,
+            selected_index,
+            bg_color,
+            fg_color,
+            symbols: &SEGMENTED_CONTROL_SYMBOLS_NERD_FONT,
+        }
+    }
+
+    /// Sets the ends and ellipsis, which are Nerd Font glyphs unless changed.
+    pub fn symbols(mut self, symbols: &'a SegmentedControlSymbols) -> SegmentedControl<'a> {
+        self.symbols = symbols;
+        self
+    }
+
+    // How many columns each label gets in `width`. When they don't all fit, the longest are
+    // brought down together to the widest length that does, and what's left over goes to the
+    // first of them.
+    fn fitted_label_widths(&self, width: u16) -> Vec<u16> {
+        let mut label_widths: Vec<u16> = self
+            .labels
+            .iter()
+            .map(|label| display_width(label))
+            .collect();
+        let available_width = width.saturating_sub(2 * self.labels.len() as u16);
+        let labels_width = |label_widths: &[u16], limit: u16| -> u16 {
+            label_widths
+                .iter()
+                .map(|&label_width| label_width.min(limit))
+                .sum()
+        };
+        if labels_width(&label_widths, u16::MAX) <= available_width {
+            return label_widths;
+        }
+        let mut limit = 0;
+        while labels_width(&label_widths, limit + 1) <= available_width {
+            limit += 1;
+        }
+        let mut spare_width = available_width - labels_width(&label_widths, limit);
+        for label_width in &mut label_widths {
+            if *label_width > limit {
+                *label_width = if spare_width > 0 { limit + 1 } else { limit };
+                spare_width = spare_width.saturating_sub(1);
+            }
+        }
+        label_widths
+    }
+
+    /// The index of the segment drawn this many columns from the control's left edge, when the
+    /// control is drawn `width` columns wide. Each segment is its label, cut short as drawn, and
+    /// the space or rounded edge on either side of it.
+    pub fn index_at(&self, column: u16, width: u16) -> Option<usize> {
+        if column >= width {
+            return None;
+        }
+        let mut x = 0;
+        for (index, label_width) in self.fitted_label_widths(width).into_iter().enumerate() {
+            x += label_width + 2;
+            if column < x {
+                return Some(index);
+            }
+        }
+        None
+    }
+}
+
+/// Each label is padded by a space on either side, except at the ends, where the rounded
+/// edges are.
+impl<'a> Measure for SegmentedControl<'a> {
+    fn preferred_width(&self) -> u16 {
+        if self.labels.is_empty() {
+            return 0;
+        }
+        let labels_width: u16 = self.labels.iter().map(|label| display_width(label)).sum();
+        labels_width + 2 * self.labels.len() as u16
+    }
+}
+
+// Bar chart
+
+/// A bar chart of a series of samples, with the most recent at the right. Only as many samples
+/// as fit are drawn.
+pub struct BarChart<'a> {
+    data: &'a [f32],
+    y_range: YRange,
+    color: Color,
+    axis_color: Option<Color>,
+    baseline: Option<f32>,
+    thresholds: &'a [(f32, Color)],
+    symbols: ChartSymbols,
+    cache: Option<(&'a ChartCache, u64)>,
+}
+
+/// The bars a `BarChart` encoded when it was last drawn, kept from one frame to the next so that
+/// a chart of the same data can draw them again without encoding them. Each chart on screen
+/// needs a cache of its own.
+#[derive(Default)]
+pub struct ChartCache {
+    entry: Mutex<Option<ChartCacheEntry>>,
+}
+
+struct ChartCacheEntry {
+    key: ChartCacheKey,
+    rows: Vec<String>,
+}
+
+// Everything the bars are encoded from, with the data standing for itself by its generation.
+#[derive(PartialEq)]
+struct ChartCacheKey {
+    generation: u64,
+    width: u16,
+    height: u16,
+    min_y: f32,
+    max_y: f32,
+    baseline: Option<f32>,
+    symbols: ChartSymbols,
+}
+
+/// How a `BarChart` draws its bars.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ChartSymbols {
+    /// Two samples per cell, four dots tall.
+    Braille,
+    /// One sample per cell, drawn with the symbol for how full the cell is, from empty to full.
+    /// Each symbol must be a single column wide. For fonts where braille is missing or renders
+    /// poorly.
+    Bars(&'static [&'static str]),
+}
+
+impl ChartSymbols {
+    /// How many samples each cell of a chart shows.
+    pub fn samples_per_cell(self) -> usize {
+        match self {
+            ChartSymbols::Braille => 2,
+            ChartSymbols::Bars(_) => 1,
+        }
+    }
+}
+
+impl<'a> BarChart<'a> {
+    /// A chart whose Y axis runs from `min_y` to `max_y`. Samples outside of that are clamped.
+    pub fn new(data: &'a [f32], min_y: f32, max_y: f32, color: Color) -> BarChart<'a> {
+        BarChart::with_y_range(data, YRange::Fixed { min_y, max_y }, color)
+    }
+
+    /// A chart whose Y axis fits the samples on screen.
+    pub fn auto_scaled(data: &'a [f32], auto_scale: AutoScale, color: Color) -> BarChart<'a> {
+        BarChart::with_y_range(data, YRange::Auto(auto_scale), color)
+    }
+
+    fn with_y_range(data: &'a [f32], y_range: YRange, color: Color) -> BarChart<'a> {
+        BarChart {
+            data,
+            y_range,
+            color,
+            axis_color: None,
+            baseline: None,
+            thresholds: &[],
+            symbols: ChartSymbols::Braille,
+            cache: None,
+        }
+    }
+
+    /// Draws min/max Y labels down the left edge and a tick row along the bottom. Needs at
+    /// least three rows; shorter charts render without the axis.
+    pub fn axis(mut self, axis_color: Color) -> BarChart<'a> {
+        self.axis_color = Some(axis_color);
+        self
+    }
+
+    /// Grows bars up or down from `baseline` instead of from `min_y`, for series that cross
+    /// zero. The baseline snaps to the nearest row boundary so bars on either side of it
+    /// never share a row.
+    pub fn baseline(mut self, baseline: f32) -> BarChart<'a> {
+        self.baseline = Some(baseline);
+        self
+    }
+
+    /// Colors samples at or above each threshold with that threshold's color, falling back to
+    /// the chart color below the lowest one. Thresholds must be sorted in ascending order. A
+    /// braille cell takes the color of the higher of its two samples.
+    pub fn thresholds(mut self, thresholds: &'a [(f32, Color)]) -> BarChart<'a> {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Draws with `symbols` instead of braille.
+    pub fn symbols(mut self, symbols: ChartSymbols) -> BarChart<'a> {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Draws the bars kept in `cache` if they were encoded from data of the same `generation`,
+    /// at the same size and scale, and keeps the bars it encodes there otherwise. The generation
+    /// must change whenever the data does.
+    pub fn cached(mut self, cache: &'a ChartCache, generation: u64) -> BarChart<'a> {
+        self.cache = Some((cache, generation));
+        self
+    }
+
+    fn resolve_y_range(&self, data: &[f32]) -> (f32, f32) {
+        match self.y_range {
+            YRange::Fixed { min_y, max_y } => (min_y, max_y),
+            YRange::Auto(auto_scale) => {
+                auto_scale.y_range(data.iter().copied(), self.baseline.unwrap_or(0.0))
+            }
+        }
+    }
+
+    fn color_for_value(&self, value: f32) -> Color {
+        self.thresholds
+            .iter()
+            .rev()
+            .find(|&&(threshold, _)| value >= threshold)
+            .map_or(self.color, |&(_, color)| color)
+    }
+}
+
+impl<'a> Widget for BarChart<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = clip_to_buffer(area, buf);
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        // Auto-scaling looks at the samples that would fill the whole area; the axis may end up
+        // hiding a few of the oldest ones, which is close enough.
+        let samples_per_cell = self.symbols.samples_per_cell();
+        let (min_y, max_y) =
+            self.resolve_y_range(recent_samples(self.data, area.width, samples_per_cell));
+
+        let mut plot_area = area;
+        if let Some(axis_color) = self.axis_color {
+            let max_label = format_axis_value(max_y);
+            let min_label = format_axis_value(min_y);
+            let label_width = display_width(&max_label).max(display_width(&min_label));
+            if area.height >= 3 && area.width > label_width + 1 {
+                plot_area = Rect::new(
+                    area.x + label_width + 1,
+                    area.y,
+                    area.width - label_width - 1,
+                    area.height - 1,
+                );
+                render_axis(
+                    area,
+                    plot_area,
+                    &max_label,
+                    &min_label,
+                    Style::default().fg(axis_color),
+                    buf,
+                );
+            }
+        }
+
+        let data = recent_samples(self.data, plot_area.width, samples_per_cell);
+        let cell_styles: Vec<Style> = data
+            .chunks(samples_per_cell)
+            .map(|samples| {
+                let peak = samples.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+                Style::default().fg(self.color_for_value(peak))
+            })
+            .collect();
+        let encode_rows = || match self.symbols {
+            ChartSymbols::Braille => {
+                encode_braille_rows(data, min_y, max_y, self.baseline, plot_area.height)
+            }
+            ChartSymbols::Bars(levels) => {
+                encode_bar_rows(data, min_y, max_y, self.baseline, plot_area.height, levels)
+            }
+        };
+        match self.cache {
+            None => render_rows(plot_area, &encode_rows(), &cell_styles, buf),
+            Some((cache, generation)) => {
+                let key = ChartCacheKey {
+                    generation,
+                    width: plot_area.width,
+                    height: plot_area.height,
+                    min_y,
+                    max_y,
+                    baseline: self.baseline,
+                    symbols: self.symbols,
+                };
+                let mut entry = cache.entry.lock().unwrap();
+                if entry.as_ref().is_none_or(|entry| entry.key != key) {
+                    let rows = encode_rows();
+                    *entry = Some(ChartCacheEntry { key, rows });
+                }
+                let rows = &entry.as_ref().unwrap().rows;
+                render_rows(plot_area, rows, &cell_styles, buf);
+            }
+        }
+
+        fn render_rows(plot_area: Rect, rows: &[String], cell_styles: &[Style], buf: &mut Buffer) {
+            for (row_index, row) in rows.iter().enumerate() {
+                let y = plot_area.y + row_index as u16;
+                for (column, symbol) in row.chars().enumerate() {
+                    let mut symbol_buffer = [0; 4];
+                    buf.set_string(
+                        plot_area.x + column as u16,
+                        y,
+                        symbol.encode_utf8(&mut symbol_buffer),
+                        cell_styles[column],
+                    );
+                }
+            }
+        }
+
+        fn render_axis(
+            area: Rect,
+            plot_area: Rect,
+            max_label: &str,
+            min_label: &str,
+            style: Style,
+            buf: &mut Buffer,
+        ) {
+            let axis_x = plot_area.x - 1;
+            let min_y = plot_area.bottom() - 1;
+            buf.set_string(
+                axis_x - display_width(max_label),
+                plot_area.y,
+                max_label,
+                style,
+            );
+            buf.set_string(axis_x - display_width(min_label), min_y, min_label, style);
+            for y in plot_area.y..plot_area.bottom() {
+                let symbol = if y == plot_area.y || y == min_y {
+                    BAR_CHART_AXIS_TICK_SYMBOL
+                } else {
+                    BAR_CHART_AXIS_VERTICAL_SYMBOL
+                };
+                buf.set_string(axis_x, y, symbol, style);
+            }
+
+            let mut tick_row = BAR_CHART_AXIS_ORIGIN_SYMBOL.to_owned();
+            for column in 1..=plot_area.width {
+                tick_row.push_str(if column % BAR_CHART_AXIS_TICK_SPACING == 0 {
+                    BAR_CHART_AXIS_BASELINE_TICK_SYMBOL
+                } else {
+                    BAR_CHART_AXIS_HORIZONTAL_SYMBOL
+                });
+            }
+            buf.set_string(axis_x, area.bottom() - 1, tick_row, style);
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum YRange {
+    Fixed { min_y: f32, max_y: f32 },
+    Auto(AutoScale),
+}
+
+/// Derives a chart's Y range from the samples on screen. The range always includes zero (or a
+/// bar chart's baseline, if set) so bars keep a common origin.
+#[derive(Clone, Copy, Default)]
+pub struct AutoScale {
+    /// Headroom added beyond the data, as a fraction of the data's span.
+    pub padding: f32,
+    /// Floor for the top of the range, typically the previous frame's max, so the scale doesn't
+    /// jump back down as soon as a spike scrolls out of view.
+    pub sticky_max: Option<f32>,
+}
+
+impl AutoScale {
+    // The range that fits `values` and `anchor`, padded away from the anchor.
+    fn y_range<I>(self, values: I, anchor: f32) -> (f32, f32)
+    where
+        I: Iterator<Item = f32>,
+    {
+        let (mut min_y, mut max_y) = values.fold((anchor, anchor), |(min_y, max_y), value| {
+            (min_y.min(value), max_y.max(value))
+        });
+        if let Some(sticky_max) = self.sticky_max {
+            max_y = max_y.max(sticky_max);
+        }
+
+        let padding = (max_y - min_y) * self.padding;
+        if max_y > anchor {
+            max_y += padding;
+        }
+        if min_y < anchor {
+            min_y -= padding;
+        }
+        if max_y <= min_y {
+            max_y = min_y + 1.0;
+        }
+        (min_y, max_y)
+    }
+}
+
+// Show the most recent samples that fit.
+fn recent_samples(data: &[f32], width: u16, samples_per_cell: usize) -> &[f32] {
+    let visible_sample_count = data.len().min(width as usize * samples_per_cell);
+    &data[(data.len() - visible_sample_count)..]
+}
+
+/// Maps each sample to the range of steps it covers between the baseline and its value, where
+/// each row is `steps_per_row` steps tall. The baseline snaps to the nearest row boundary so
+/// bars on either side of it never share a row.
+fn bar_extents(
+    data: &[f32],
+    min_y: f32,
+    max_y: f32,
+    baseline: Option<f32>,
+    row_count: u16,
+    steps_per_row: u32,
+) -> Vec<(u32, u32)> {
+    let y_range = max_y - min_y;
+    let step_count = row_count as u32 * steps_per_row;
+    let baseline_step = match baseline {
+        None => 0,
+        Some(baseline) => {
+            let baseline_norm = clamp((baseline - min_y) / y_range, 0.0, 1.0);
+            (baseline_norm * row_count as f32).round() as u32 * steps_per_row
+        }
+    };
+
+    data.iter()
+        .map(|&value| {
+            let height_norm = clamp((value - min_y) / y_range, 0.0, 1.0);
+            let height = (height_norm * step_count as f32).round() as u32;
+            (height.min(baseline_step), height.max(baseline_step))
+        })
+        .collect()
+}
+
+/// Encodes `data` as rows of braille characters, top row first. Each character packs two
+/// adjacent samples into its left and right dot columns, and each row is four dots tall.
+fn encode_braille_rows(
+    data: &[f32],
+    min_y: f32,
+    max_y: f32,
+    baseline: Option<f32>,
+    row_count: u16,
+) -> Vec<String> {
+    let extents = bar_extents(data, min_y, max_y, baseline, row_count, 4);
+
+    let mut rows = vec![];
+    for row_index in (0..row_count as u32).rev() {
+        let row_bottom = row_index * 4;
+        let mut row = String::new();
+        for pair in extents.chunks(2) {
+            let left_mask = column_mask(pair[0], row_bottom);
+            let right_mask = pair
+                .get(1)
+                .map_or(0, |&extent| column_mask(extent, row_bottom));
+            row.push(DOTS[((right_mask << 4) | left_mask) as usize]);
+        }
+        rows.push(row);
+    }
+    return rows;
+
+    // Bit 0 is the bottom dot of a column and bit 3 is the top one.
+    fn column_mask((low, high): (u32, u32), row_bottom: u32) -> u32 {
+        let top = high.saturating_sub(row_bottom).min(4);
+        let bottom = low.saturating_sub(row_bottom).min(4);
+        ((1 << top) - 1) & !((1 << bottom) - 1)
+    }
+}
+
+/// Encodes `data` as rows of bar symbols, top row first, one sample per character. `levels`
+/// holds the symbols for a cell filled from the bottom to each level, from empty to full.
+///
+/// The symbols can only fill a cell from the bottom, so the lowest cell of a bar hanging below
+/// the baseline is drawn either full or empty, whichever is closer.
+fn encode_bar_rows(
+    data: &[f32],
+    min_y: f32,
+    max_y: f32,
+    baseline: Option<f32>,
+    row_count: u16,
+    levels: &[&str],
+) -> Vec<String> {
+    let steps_per_row = levels.len() as u32 - 1;
+    let extents = bar_extents(data, min_y, max_y, baseline, row_count, steps_per_row);
+
+    let mut rows = vec![];
+    for row_index in (0..row_count as u32).rev() {
+        let row_bottom = row_index * steps_per_row;
+        let mut row = String::new();
+        for &(low, high) in &extents {
+            let top = high.saturating_sub(row_bottom).min(steps_per_row);
+            let bottom = low.saturating_sub(row_bottom).min(steps_per_row);
+            let level = if bottom == 0 {
+                top
+            } else if (top - bottom) * 2 >= steps_per_row {
+                steps_per_row
+            } else {
+                0
+            };
+            row.push_str(levels[level as usize]);
+        }
+        rows.push(row);
+    }
+    rows
+}
+
+fn format_axis_value(value: f32) -> String {
+    if value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.1}", value)
+    }
+}
+
+// Graph
+
+/// Lines through one or more series of samples, drawn in braille with the most recent samples
+/// at the right, two to a cell. Where lines cross, a cell takes the color of the series listed
+/// last. Samples that aren't finite leave a gap in their line.
+pub struct Graph<'a> {
+    series: &'a [GraphSeries<'a>],
+    y_range: YRange,
+    axis_color: Option<Color>,
+    legend_color: Option<Color>,
+}
+
+/// One line on a `Graph`. Create one with `GraphSeries::new(label, data, color)`.
+#[derive(Clone, Copy, Constructor)]
+pub struct GraphSeries<'a> {
+    label: &'a str,
+    data: &'a [f32],
+    color: Color,
+}
+
+impl<'a> Graph<'a> {
+    /// A graph whose Y axis runs from `min_y` to `max_y`. Samples outside of that are clamped.
+    pub fn new(series: &'a [GraphSeries<'a>], min_y: f32, max_y: f32) -> Graph<'a> {
+        Graph::with_y_range(series, YRange::Fixed { min_y, max_y })
+    }
+
+    /// A graph whose Y axis fits the samples on screen, across every series.
+    pub fn auto_scaled(series: &'a [GraphSeries<'a>], auto_scale: AutoScale) -> Graph<'a> {
+        Graph::with_y_range(series, YRange::Auto(auto_scale))
+    }
+
+    fn with_y_range(series: &'a [GraphSeries<'a>], y_range: YRange) -> Graph<'a> {
+        Graph {
+            series,
+            y_range,
+            axis_color: None,
+            legend_color: None,
+        }
+    }
+
+    /// Draws the top and bottom of the Y range down the left edge, with the middle too where
+    /// there are at least five rows. Needs at least two rows; shorter graphs render without the
+    /// axis.
+    pub fn axis(mut self, axis_color: Color) -> Graph<'a> {
+        self.axis_color = Some(axis_color);
+        self
+    }
+
+    /// Names each series along the top row, after a marker in its color. Needs at least two
+    /// rows; shorter graphs render without the legend.
+    pub fn legend(mut self, legend_color: Color) -> Graph<'a> {
+        self.legend_color = Some(legend_color);
+        self
+    }
+
+    fn resolve_y_range(&self, width: u16) -> (f32, f32) {
+        match self.y_range {
+            YRange::Fixed { min_y, max_y } => (min_y, max_y),
+            YRange::Auto(auto_scale) => auto_scale.y_range(
+                self.series
+                    .iter()
+                    .flat_map(|series| recent_samples(series.data, width, 2))
+                    .copied()
+                    .filter(|value| value.is_finite()),
+                0.0,
+            ),
+        }
+    }
+}
+
+impl<'a> Widget for Graph<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = clip_to_buffer(area, buf);
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let mut plot_area = area;
+        if let Some(legend_color) = self.legend_color {
+            if area.height >= 2 {
+                let mut x = area.x;
+                for series in self.series {
+                    x = set_string_clipped(
+                        buf,
+                        area,
+                        x,
+                        area.y,
+                        GRAPH_LEGEND_MARKER_SYMBOL,
+                        Style::default().fg(series.color),
+                    );
+                    let label = format!(" {}  ", series.label);
+                    let style = Style::default().fg(legend_color);
+                    x = set_string_clipped(buf, area, x, area.y, &label, style);
+                }
+                plot_area.y += 1;
+                plot_area.height -= 1;
+            }
+        }
+
+        // As with bar charts, the range fits the samples that would fill the whole width.
+        let (min_y, max_y) = self.resolve_y_range(plot_area.width);
+        if let Some(axis_color) = self.axis_color {
+            let mut labels = vec![(0, format_axis_value(max_y))];
+            if plot_area.height >= 5 {
+                let middle_row = (plot_area.height - 1) / 2;
+                let middle_y =
+                    max_y - (max_y - min_y) * middle_row as f32 / (plot_area.height - 1) as f32;
+                labels.push((middle_row, format_axis_value(middle_y)));
+            }
+            labels.push((plot_area.height - 1, format_axis_value(min_y)));
+            let label_width = labels
+                .iter()
+                .map(|(_, label)| display_width(label))
+                .max()
+                .unwrap_or_default();
+            if plot_area.height >= 2 && plot_area.width > label_width + 1 {
+                let style = Style::default().fg(axis_color);
+                let axis_x = plot_area.x + label_width;
+                for row in 0..plot_area.height {
+                    let label = labels.iter().find(|&&(label_row, _)| label_row == row);
+                    let symbol = match label {
+                        Some((_, label)) => {
+                            let y = plot_area.y + row;
+                            buf.set_string(axis_x - display_width(label), y, label, style);
+                            BAR_CHART_AXIS_TICK_SYMBOL
+                        }
+                        None => BAR_CHART_AXIS_VERTICAL_SYMBOL,
+                    };
+                    buf.set_string(axis_x, plot_area.y + row, symbol, style);
+                }
+                plot_area.x = axis_x + 1;
+                plot_area.width -= label_width + 1;
+            }
+        }
+
+        let mut grid = DotGrid::new(CanvasSymbols::Braille, plot_area.width, plot_area.height);
+        let dot_height = grid.dot_height() as f32;
+        for series in self.series {
+            let data = recent_samples(series.data, plot_area.width, 2);
+            let first_x = grid.dot_width() - data.len();
+            let mut previous_dot = None;
+            for (index, &value) in data.iter().enumerate() {
+                if !value.is_finite() {
+                    previous_dot = None;
+                    continue;
+                }
+                let height_norm = clamp((value - min_y) / (max_y - min_y), 0.0, 1.0);
+                let dot_y = ((1.0 - height_norm) * (dot_height - 1.0)).round() as i32;
+                let dot = ((first_x + index) as i32, dot_y);
+                grid.line(previous_dot.unwrap_or(dot), dot, series.color);
+                previous_dot = Some(dot);
+            }
+        }
+        grid.render(plot_area, buf);
+    }
+}
+
+// Canvas
+
+/// Points, lines, and rectangles, drawn as dots over a grid of cells. Shapes are given in dots
+/// from the top left of the area, are drawn in the order they were added, and are clipped to
+/// the area. Where shapes share a cell, the cell takes the color of the one added last.
+pub struct Canvas {
+    symbols: CanvasSymbols,
+    shapes: Vec<(CanvasShape, Color)>,
+}
+
+/// How a `Canvas` draws its dots.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CanvasSymbols {
+    /// Two columns and four rows of dots to a cell.
+    Braille,
+    /// One column and two rows of dots to a cell, drawn with half blocks. For fonts where
+    /// braille is missing or renders poorly.
+    HalfBlocks,
+}
+
+#[derive(Clone, Copy)]
+enum CanvasShape {
+    Point((u16, u16)),
+    Line((u16, u16), (u16, u16)),
+    Rect { rect: Rect, filled: bool },
+}
+
+impl CanvasSymbols {
+    /// How many columns and rows of dots each cell has.
+    pub fn dots_per_cell(self) -> (u16, u16) {
+        match self {
+            CanvasSymbols::Braille => (2, 4),
+            CanvasSymbols::HalfBlocks => (1, 2),
+        }
+    }
+}
+
+impl Canvas {
+    /// An empty canvas that draws with `symbols`.
+    pub fn new(symbols: CanvasSymbols) -> Canvas {
+        Canvas {
+            symbols,
+            shapes: vec![],
+        }
+    }
+
+    /// Sets the dot at `(x, y)`.
+    pub fn point(mut self, point: (u16, u16), color: Color) -> Canvas {
+        self.shapes.push((CanvasShape::Point(point), color));
+        self
+    }
+
+    /// Sets the dots on the line from `start` to `end`, both ends included.
+    pub fn line(mut self, start: (u16, u16), end: (u16, u16), color: Color) -> Canvas {
+        self.shapes.push((CanvasShape::Line(start, end), color));
+        self
+    }
+
+    /// Sets the dots around the edge of `rect`.
+    pub fn rect(mut self, rect: Rect, color: Color) -> Canvas {
+        let shape = CanvasShape::Rect {
+            rect,
+            filled: false,
+        };
+        self.shapes.push((shape, color));
+        self
+    }
+
+    /// Sets every dot in `rect`.
+    pub fn filled_rect(mut self, rect: Rect, color: Color) -> Canvas {
+        let shape = CanvasShape::Rect { rect, filled: true };
+        self.shapes.push((shape, color));
+        self
+    }
+}
+
+impl Widget for Canvas {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = clip_to_buffer(area, buf);
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let mut grid = DotGrid::new(self.symbols, area.width, area.height);
+        let dot = |(x, y): (u16, u16)| (x as i32, y as i32);
+        for (shape, color) in self.shapes {
+            match shape {
+                CanvasShape::Point(point) => {
+                    let (x, y) = dot(point);
+                    grid.set(x, y, color);
+                }
+                CanvasShape::Line(start, end) => grid.line(dot(start), dot(end), color),
+                CanvasShape::Rect { rect, .. } if rect.area() == 0 => {}
+                CanvasShape::Rect { rect, filled } => {
+                    let (left, top) = dot((rect.x, rect.y));
+                    let (right, bottom) =
+                        (left + rect.width as i32 - 1, top + rect.height as i32 - 1);
+                    // Past the grid, rows would only be skipped one dot at a time.
+                    let bottom = bottom.min(grid.dot_height() as i32);
+                    if filled {
+                        for y in top..=bottom {
+                            grid.line((left, y), (right, y), color);
+                        }
+                    } else {
+                        grid.line((left, top), (right, top), color);
+                        grid.line((left, bottom), (right, bottom), color);
+                        grid.line((left, top), (left, bottom), color);
+                        grid.line((right, top), (right, bottom), color);
+                    }
+                }
+            }
+        }
+        grid.render(area, buf);
+    }
+}
+
+// Dots drawn over a grid of cells, with (0, 0) at the top left. Each cell takes the color of
+// the last dot set in it.
+struct DotGrid {
+    symbols: CanvasSymbols,
+    width: u16,
+    height: u16,
+    cells: Vec<(usize, Color)>,
+}
+
+impl DotGrid {
+    fn new(symbols: CanvasSymbols, width: u16, height: u16) -> DotGrid {
+        DotGrid {
+            symbols,
+            width,
+            height,
+            cells: vec![(0, Color::Reset); width as usize * height as usize],
+        }
+    }
+
+    fn dot_width(&self) -> usize {
+        self.width as usize * self.symbols.dots_per_cell().0 as usize
+    }
+
+    fn dot_height(&self) -> usize {
+        self.height as usize * self.symbols.dots_per_cell().1 as usize
+    }
+
+    // Dots off the grid are ignored.
+    fn set(&mut self, x: i32, y: i32, color: Color) {
+        if x < 0 || y < 0 || x as usize >= self.dot_width() || y as usize >= self.dot_height() {
+            return;
+        }
+        let (x, y) = (x as usize, y as usize);
+        let (cell_width, cell_height) = self.symbols.dots_per_cell();
+        let (cell_width, cell_height) = (cell_width as usize, cell_height as usize);
+        let cell = &mut self.cells[y / cell_height * self.width as usize + x / cell_width];
+        cell.0 |= match self.symbols {
+            // `DOTS` packs the left column into the low four bits, bottom dot first.
+            CanvasSymbols::Braille => 1 << (x % 2 * 4 + 3 - y % 4),
+            // `HALF_BLOCKS` has the top half in the low bit.
+            CanvasSymbols::HalfBlocks => 1 << (y % 2),
+        };
+        cell.1 = color;
+    }
+
+    // Sets the dots along the line from `start` to `end`, both included.
+    fn line(&mut self, start: (i32, i32), end: (i32, i32), color: Color) {
+        let (mut x, mut y) = start;
+        let (dx, dy) = ((end.0 - x).abs(), -(end.1 - y).abs());
+        let (step_x, step_y) = ((end.0 - x).signum(), (end.1 - y).signum());
+        let mut error = dx + dy;
+        loop {
+            self.set(x, y, color);
+            if (x, y) == end {
+                break;
+            }
+            // Both steps are decided on the error from before either is taken, or shallow lines
+            // can step past their end.
+            let doubled_error = error * 2;
+            if doubled_error >= dy {
+                error += dy;
+                x += step_x;
+            }
+            if doubled_error <= dx {
+                error += dx;
+                y += step_y;
+            }
+        }
+    }
+
+    // Draws the cells with dots in them, leaving the rest of `area` as it was.
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        for (index, &(dots, color)) in self.cells.iter().enumerate() {
+            if dots == 0 {
+                continue;
+            }
+            let x = area.x + (index % self.width as usize) as u16;
+            let y = area.y + (index / self.width as usize) as u16;
+            let mut symbol_buffer = [0; 4];
+            let symbol = match self.symbols {
+                CanvasSymbols::Braille => DOTS[dots].encode_utf8(&mut symbol_buffer),
+                CanvasSymbols::HalfBlocks => HALF_BLOCKS[dots],
+            };
+            buf.set_string(x, y, symbol, Style::default().fg(color));
+        }
+    }
+}
+
+// Ring
+
+/// A gauge showing how full something is, such as a runtime's utilization, as a percentage
+/// inside a ring that fills clockwise from the top. Given a single row, or two, the ring shrinks
+/// to a pie glyph in front of the percentage. Create one with `Ring::new(ratio, color)`, where
+/// `ratio` runs from 0 to 1.
+pub struct Ring {
+    ratio: f32,
+    color: Color,
+    track_color: Option<Color>,
+    symbols: &'static RingSymbols,
+}
+
+/// The symbols a `Ring` draws with.
+pub struct RingSymbols {
+    /// The single-row form's glyphs, from empty to full in quarters. Each must be a single
+    /// column wide.
+    pub pies: [&'static str; 5],
+    /// The lines of the part of the ring that's filled.
+    pub filled_border_set: BorderSet,
+    /// The lines of the part that isn't.
+    pub track_border_set: BorderSet,
+}
+
+impl Ring {
+    /// A ring filled `ratio` of the way around in `color`. Ratios outside 0 to 1 are clamped.
+    pub fn new(ratio: f32, color: Color) -> Ring {
+        Ring {
+            ratio,
+            color,
+            track_color: None,
+            symbols: &RING_SYMBOLS_UNICODE,
+        }
+    }
+
+    /// Sets the pies and the ring's lines, which need box-drawing glyphs unless changed.
+    pub fn symbols(mut self, symbols: &'static RingSymbols) -> Ring {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Colors the part of the ring that isn't filled, which otherwise keeps the area's color.
+    pub fn track_color(mut self, track_color: Color) -> Ring {
+        self.track_color = Some(track_color);
+        self
+    }
+
+    // Clamped to 0 to 1, with anything that isn't a number taken as empty.
+    fn clamped_ratio(&self) -> f32 {
+        if self.ratio.is_nan() {
+            0.0
+        } else {
+            clamp(self.ratio, 0.0, 1.0)
+        }
+    }
+
+    fn percent_label(&self) -> String {
+        format!("{:.0}%", self.clamped_ratio() * 100.0)
+    }
+
+    fn track_style(&self) -> Style {
+        match self.track_color {
+            Some(track_color) => Style::default().fg(track_color),
+            None => Style::default(),
+        }
+    }
+}
+
+impl Measure for Ring {
+    /// The width of the single-row form.
+    fn preferred_width(&self) -> u16 {
+        display_width(self.symbols.pies[0]) + 1 + display_width(&self.percent_label())
+    }
+}
+
+impl Widget for Ring {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = clip_to_buffer(area, buf);
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let ratio = self.clamped_ratio();
+        let label = self.percent_label();
+        let fill_style = Style::default().fg(self.color);
+        if area.height < 3 || area.width < display_width(&label) + 2 {
+            let last_pie = (self.symbols.pies.len() - 1) as f32;
+            let pie = self.symbols.pies[(ratio * last_pie).round() as usize];
+            let y = area.y + (area.height - 1) / 2;
+            let x = set_string_clipped(buf, area, area.x, y, pie, fill_style);
+            set_string_clipped(buf, area, x + 1, y, &label, Style::default());
+            return;
+        }
+
+        // The border's cells clockwise from the top left, then turned to start at the top
+        // middle, so that the ring fills the way a clock's hand sweeps.
+        let (left, top) = (area.x, area.y);
+        let (right, bottom) = (area.right() - 1, area.bottom() - 1);
+        let mut border = vec![];
+        border.extend((left..right).map(|x| (x, top)));
+        border.extend((top..bottom).map(|y| (right, y)));
+        border.extend((left + 1..=right).rev().map(|x| (x, bottom)));
+        border.extend((top + 1..=bottom).rev().map(|y| (left, y)));
+        border.rotate_left((area.width / 2) as usize);
+        let filled_count = (ratio * border.len() as f32).round() as usize;
+        for (index, &(x, y)) in border.iter().enumerate() {
+            let filled = index < filled_count;
+            let symbols = if filled {
+                self.symbols.filled_border_set.symbols()
+            } else {
+                self.symbols.track_border_set.symbols()
+            };
+            let symbol = if (x, y) == (left, top) {
+                symbols.upper_left
+            } else if (x, y) == (right, top) {
+                symbols.upper_right
+            } else if (x, y) == (right, bottom) {
+                symbols.lower_right
+            } else if (x, y) == (left, bottom) {
+                symbols.lower_left
+            } else if y == top || y == bottom {
+                symbols.horizontal
+            } else {
+                symbols.vertical
+            };
+            let style = if filled {
+                fill_style
+            } else {
+                self.track_style()
+            };
+            buf.set_string(x, y, symbol, style);
+        }
+
+        let inner_width = area.width - 2;
+        let label_x = left + 1 + (inner_width - display_width(&label)) / 2;
+        let label_y = top + (area.height - 1) / 2;
+        buf.set_string(label_x, label_y, &label, Style::default());
+    }
+}
+
+// Big text
+
+/// Text for reading from across a room, such as a measurement on a projector. Digits, and the
+/// punctuation numbers are written with, are drawn three rows tall in blocks. Anything else,
+/// such as a unit, is drawn at its usual size along the bottom row. In an area less than three
+/// rows tall, all of the text is drawn at its usual size.
+pub struct BigText<'a> {
+    text: &'a str,
+    style: Style,
+    alignment: Alignment,
+}
+
+// A character's rows, top first.
+type BigGlyph = [&'static str; 3];
+
+impl<'a> BigText<'a> {
+    /// Left-aligned text in the default style.
+    pub fn new(text: &'a str) -> BigText<'a> {
+        BigText {
+            text,
+            style: Style::default(),
+            alignment: Alignment::Left,
+        }
+    }
+
+    /// The style of the blocks and of the text drawn at its usual size alike.
+    pub fn style(mut self, style: Style) -> BigText<'a> {
+        self.style = style;
+        self
+    }
+
+    /// Where in the area's width the text goes.
+    pub fn alignment(mut self, alignment: Alignment) -> BigText<'a> {
+        self.alignment = alignment;
+        self
+    }
+
+    // Each character with the column it starts at, relative to the first, and the width of the
+    // whole text. Big glyphs are followed by a column of space.
+    fn layout(&self) -> (Vec<(u16, BigTextPiece<'a>)>, u16) {
+        let mut x = 0;
+        let mut pieces = vec![];
+        let mut after_big_glyph = false;
+        for (index, character) in self.text.char_indices() {
+            if after_big_glyph {
+                x += BIG_TEXT_GAP;
+            }
+            let piece = match big_glyph(character) {
+                Some(glyph) => BigTextPiece::Big(glyph),
+                None => BigTextPiece::Small(&self.text[index..index + character.len_utf8()]),
+            };
+            pieces.push((x, piece));
+            x += match piece {
+                BigTextPiece::Big(glyph) => display_width(glyph[0]),
+                BigTextPiece::Small(symbol) => display_width(symbol),
+            };
+            after_big_glyph = matches!(piece, BigTextPiece::Big(_));
+        }
+        (pieces, x)
+    }
+}
+
+// A character of big text, drawn either in blocks or at its usual size.
+#[derive(Clone, Copy)]
+enum BigTextPiece<'a> {
+    Big(&'static BigGlyph),
+    Small(&'a str),
+}
+
+fn big_glyph(character: char) -> Option<&'static BigGlyph> {
+    BIG_TEXT_GLYPHS
+        .iter()
+        .find(|&&(glyph_character, _)| glyph_character == character)
+        .map(|(_, glyph)| glyph)
+}
+
+fn aligned_x(area: Rect, width: u16, alignment: Alignment) -> u16 {
+    let padding = area.width.saturating_sub(width);
+    match alignment {
+        Alignment::Left => area.x,
+        Alignment::Center => area.x + padding / 2,
+        Alignment::Right => area.x + padding,
+    }
+}
+
+impl<'a> Measure for BigText<'a> {
+    fn preferred_width(&self) -> u16 {
+        self.layout().1
+    }
+
+    fn preferred_height(&self) -> u16 {
+        BIG_TEXT_HEIGHT
+    }
+}
+
+impl<'a> Widget for BigText<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = clip_to_buffer(area, buf);
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        if area.height < BIG_TEXT_HEIGHT {
+            let x = aligned_x(area, display_width(self.text), self.alignment);
+            set_string_clipped(buf, area, x, area.y, self.text, self.style);
+            return;
+        }
+
+        let (pieces, width) = self.layout();
+        let left = aligned_x(area, width, self.alignment);
+        let baseline = area.y + BIG_TEXT_HEIGHT - 1;
+        for (x, piece) in pieces {
+            match piece {
+                BigTextPiece::Big(glyph) => {
+                    for (y, row) in (area.y..).zip(glyph.iter()) {
+                        set_string_clipped(buf, area, left + x, y, row, self.style);
+                    }
+                }
+                BigTextPiece::Small(symbol) => {
+                    set_string_clipped(buf, area, left + x, baseline, symbol, self.style);
+                }
+            }
+        }
+    }
+}
+
+// Timeline
+
+/// Rows of bars along a time axis that runs left to right up to now, such as each task's life
+/// from when it was spawned, with the stretches it was busy drawn solid. Times are how long
+/// before now something happened. Create one with `Timeline::new(rows, column_span)`, where
+/// `column_span` is how long each column covers, and pan it back in time with `offset`.
+pub struct Timeline<'a> {
+    rows: Vec<TimelineRow<'a>>,
+    column_span: Duration,
+    offset: Duration,
+    header: Option<Spans<'a>>,
+    ruler: Option<(&'a (dyn Fn(Duration) -> String + Sync), Style)>,
+    label_width: u16,
+    idle_style: Style,
+    busy_style: Style,
+}
+
+/// One bar of a `Timeline`, with its label in front of it.
+pub struct TimelineRow<'a> {
+    label: Spans<'a>,
+    started_ago: Duration,
+    busy_periods: Vec<(Duration, Duration)>,
+    style: Style,
+}
+
+impl<'a> TimelineRow<'a> {
+    /// A bar from `started_ago` up to now, idle all the way.
+    pub fn new(label: Spans<'a>, started_ago: Duration) -> TimelineRow<'a> {
+        TimelineRow {
+            label,
+            started_ago,
+            busy_periods: vec![],
+            style: Style::default(),
+        }
+    }
+
+    /// The stretches of the bar that were busy, each as how long ago it started, then how long
+    /// ago it ended.
+    pub fn busy_periods(mut self, busy_periods: Vec<(Duration, Duration)>) -> TimelineRow<'a> {
+        self.busy_periods = busy_periods;
+        self
+    }
+
+    /// Styles the whole row, under the label's and the bar's own styles, such as to highlight
+    /// it.
+    pub fn style(mut self, style: Style) -> TimelineRow<'a> {
+        self.style = style;
+        self
+    }
+}
+
+impl<'a> Timeline<'a> {
+    /// `rows` from the top down, ending at now, with each column `column_span` long.
+    pub fn new(rows: Vec<TimelineRow<'a>>, column_span: Duration) -> Timeline<'a> {
+        Timeline {
+            rows,
+            column_span,
+            offset: Duration::from_secs(0),
+            header: None,
+            ruler: None,
+            label_width: 0,
+            idle_style: Style::default(),
+            busy_style: Style::default(),
+        }
+    }
+
+    /// Ends the timeline this long before now, rather than at now.
+    pub fn offset(mut self, offset: Duration) -> Timeline<'a> {
+        self.offset = offset;
+        self
+    }
+
+    /// A row above the bars, such as their labels' title. With a ruler, it only goes over the
+    /// labels.
+    pub fn header(mut self, header: Spans<'a>) -> Timeline<'a> {
+        self.header = Some(header);
+        self
+    }
+
+    /// A `TimeRuler` above the bars, with `label` writing its times.
+    pub fn ruler(
+        mut self,
+        label: &'a (dyn Fn(Duration) -> String + Sync),
+        style: Style,
+    ) -> Timeline<'a> {
+        self.ruler = Some((label, style));
+        self
+    }
+
+    /// How many columns the labels get, with one more between them and the bars. With none,
+    /// which is the default, the bars take up the whole width.
+    pub fn label_width(mut self, label_width: u16) -> Timeline<'a> {
+        self.label_width = label_width;
+        self
+    }
+
+    /// The styles of the bars' idle and busy stretches.
+    pub fn styles(mut self, idle_style: Style, busy_style: Style) -> Timeline<'a> {
+        self.idle_style = idle_style;
+        self.busy_style = busy_style;
+        self
+    }
+}
+
+impl<'a> Widget for Timeline<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = clip_to_buffer(area, buf);
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let label_width = self.label_width.min(area.width);
+        let bar_x = if label_width > 0 {
+            (area.x + label_width + 1).min(area.right())
+        } else {
+            area.x
+        };
+        let bar_width = area.right() - bar_x;
+
+        let mut y = area.y;
+        if let Some(header) = &self.header {
+            let header_width = match self.ruler {
+                Some(_) => label_width,
+                None => area.width,
+            };
+            buf.set_spans(area.x, y, header, header_width);
+        }
+        if let Some((label, style)) = self.ruler {
+            TimeRuler::new(self.column_span, label)
+                .offset(self.offset)
+                .style(style)
+                .render(Rect::new(bar_x, y, bar_width, 1), buf);
+        }
+        if self.header.is_some() || self.ruler.is_some() {
+            y += 1;
+        }
+
+        for row in self
+            .rows
+            .iter()
+            .take(area.bottom().saturating_sub(y) as usize)
+        {
+            buf.set_style(Rect::new(area.x, y, area.width, 1), row.style);
+            buf.set_spans(area.x, y, &row.label, label_width);
+            for column in 0..bar_width {
+                // How long ago the column's right and left edges are.
+                let columns_to_now = u32::from(bar_width - 1 - column);
+                let newer = self.offset + self.column_span.saturating_mul(columns_to_now);
+                let older = newer + self.column_span;
+                if row.started_ago <= newer {
+                    continue;
+                }
+                let busy = row
+                    .busy_periods
+                    .iter()
+                    .any(|&(started_ago, ended_ago)| started_ago > newer && ended_ago < older);
+                let (symbol, style) = if busy {
+                    (TIMELINE_BUSY_SYMBOL, self.busy_style)
+                } else if row.started_ago <= older {
+                    (TIMELINE_SPAWN_SYMBOL, self.idle_style)
+                } else {
+                    (TIMELINE_IDLE_SYMBOL, self.idle_style)
+                };
+                buf.get_mut(bar_x + column, y)
+                    .set_symbol(symbol)
+                    .set_style(style);
+            }
+            y += 1;
+        }
+    }
+}
+
+// Time ruler
+
+/// Tick marks along a row, each labelled with how long before now it is, for a time axis that
+/// runs left to right up to now like a `Timeline`'s. Ticks fall on round times, as far apart as
+/// their labels need. Create one with `TimeRuler::new(column_span, label)`, where `label` writes
+/// a time, given as how long ago it is, and pan it back in time with `offset`.
+pub struct TimeRuler<'a> {
+    column_span: Duration,
+    offset: Duration,
+    label: &'a (dyn Fn(Duration) -> String + Sync),
+    style: Style,
+}
+
+impl<'a> TimeRuler<'a> {
+    /// A ruler ending at now, with each column `column_span` long and each tick labelled by
+    /// `label`.
+    pub fn new(
+        column_span: Duration,
+        label: &'a (dyn Fn(Duration) -> String + Sync),
+    ) -> TimeRuler<'a> {
+        TimeRuler {
+            column_span,
+            offset: Duration::from_secs(0),
+            label,
+            style: Style::default(),
+        }
+    }
+
+    /// Ends the ruler this long before now, rather than at now.
+    pub fn offset(mut self, offset: Duration) -> TimeRuler<'a> {
+        self.offset = offset;
+        self
+    }
+
+    /// The style of the ticks and their labels.
+    pub fn style(mut self, style: Style) -> TimeRuler<'a> {
+        self.style = style;
+        self
+    }
+
+    // How far apart the ticks go on a ruler this wide: the shortest round interval that leaves
+    // room for the widest label, which is usually the oldest.
+    fn interval(&self, width: u16) -> Duration {
+        let oldest = self.offset + self.column_span.saturating_mul(u32::from(width));
+        let label_width = [self.offset, oldest]
+            .iter()
+            .map(|&ago| display_width(&(self.label)(ago)))
+            .max()
+            .unwrap_or_default();
+        let column_nanos = self.column_span.as_nanos().max(1);
+        TIME_RULER_INTERVALS
+            .iter()
+            .copied()
+            .find(|interval| interval.as_nanos() / column_nanos > u128::from(label_width + 1))
+            .unwrap_or(TIME_RULER_INTERVALS[TIME_RULER_INTERVALS.len() - 1])
+    }
+}
+
+impl<'a> Widget for TimeRuler<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = clip_to_buffer(area, buf);
+        if area.width == 0 || area.height == 0 || self.column_span.is_zero() {
+            return;
+        }
+
+        // Ticks from the newest, on the right, to the oldest. Each label goes before its tick,
+        // or after it where that would run off the left edge, and ticks whose labels would run
+        // into the one to their right are left out.
+        let interval = self.interval(area.width);
+        let interval_nanos = interval.as_nanos();
+        let mut tick_nanos = self.offset.as_nanos().div_ceil(interval_nanos) * interval_nanos;
+        let mut right = area.right();
+        loop {
+            let columns_to_now =
+                (tick_nanos - self.offset.as_nanos()) / self.column_span.as_nanos();
+            if columns_to_now >= u128::from(area.width) {
+                break;
+            }
+            let x = area.right() - 1 - columns_to_now as u16;
+            let ago = Duration::from_nanos(tick_nanos as u64);
+            let label = (self.label)(ago);
+            let label_width = display_width(&label);
+            if x >= area.x + label_width {
+                if x < right {
+                    buf.set_string(x, area.y, TIME_RULER_TICK_SYMBOL, self.style);
+                    set_string_clipped(buf, area, x - label_width, area.y, &label, self.style);
+                    right = (x - label_width).saturating_sub(1);
+                }
+            } else if x + 1 + label_width <= right {
+                buf.set_string(x, area.y, TIME_RULER_TICK_SYMBOL, self.style);
+                set_string_clipped(buf, area, x + 1, area.y, &label, self.style);
+                right = x.saturating_sub(1);
+            }
+            tick_nanos += interval_nanos;
+        }
+    }
+}
+
+// Graph view
+
+/// Nodes joined by arrows, such as tasks and the resources they wait on, laid out in layers
+/// from top to bottom so that arrows point down wherever they can. Arrows that skip layers, or
+/// close a cycle such as a deadlock's, go around along the right. Create one with
+/// `GraphView::new(nodes, edges)`, where each edge is the indices of the node it starts at and
+/// the node it points to.
+pub struct GraphView<'a> {
+    nodes: Vec<GraphNode<'a>>,
+    edges: Vec<(usize, usize)>,
+    edge_style: Style,
+}
+
+/// One node of a `GraphView`, drawn as its label.
+pub struct GraphNode<'a> {
+    label: Spans<'a>,
+    style: Style,
+}
+
+impl<'a> GraphNode<'a> {
+    /// A node drawn as `label`, unstyled apart from the label's own styles.
+    pub fn new(label: Spans<'a>) -> GraphNode<'a> {
+        GraphNode {
+            label,
+            style: Style::default(),
+        }
+    }
+
+    /// Styles the whole node, under its label's own styles, such as to highlight it.
+    pub fn style(mut self, style: Style) -> GraphNode<'a> {
+        self.style = style;
+        self
+    }
+}
+
+// Where a graph's nodes go, from its top left, and which of its edges close cycles. Edges to
+// nodes that aren't there are left out.
+struct GraphLayout {
+    positions: Vec<(u16, u16)>,
+    layers: Vec<usize>,
+    edges: Vec<(usize, usize)>,
+    back_edges: Vec<bool>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum GraphVisit {
+    Unvisited,
+    Visiting,
+    Visited,
+}
+
+impl<'a> GraphView<'a> {
+    /// `nodes` joined by `edges`, each the indices of the nodes it goes from and to.
+    pub fn new(nodes: Vec<GraphNode<'a>>, edges: Vec<(usize, usize)>) -> GraphView<'a> {
+        GraphView {
+            nodes,
+            edges,
+            edge_style: Style::default(),
+        }
+    }
+
+    /// The style of the arrows between the nodes.
+    pub fn edge_style(mut self, edge_style: Style) -> GraphView<'a> {
+        self.edge_style = edge_style;
+        self
+    }
+
+    fn layout(&self) -> GraphLayout {
+        let node_count = self.nodes.len();
+        let edges: Vec<_> = self
+            .edges
+            .iter()
+            .copied()
+            .filter(|&(from, to)| from < node_count && to < node_count)
+            .collect();
+
+        // An edge closes a cycle if it points back to a node that a depth-first walk is still
+        // visiting. The rest make a DAG.
+        let mut back_edges = vec![false; edges.len()];
+        let mut visit_states = vec![GraphVisit::Unvisited; node_count];
+        for root in 0..node_count {
+            if visit_states[root] != GraphVisit::Unvisited {
+                continue;
+            }
+            let mut stack = vec![(root, 0)];
+            visit_states[root] = GraphVisit::Visiting;
+            while let Some((node, next_edge)) = stack.pop() {
+                match edges[next_edge..]
+                    .iter()
+                    .position(|&(from, _)| from == node)
+                {
+                    Some(position) => {
+                        let edge = next_edge + position;
+                        stack.push((node, edge + 1));
+                        let to = edges[edge].1;
+                        match visit_states[to] {
+                            GraphVisit::Unvisited => {
+                                visit_states[to] = GraphVisit::Visiting;
+                                stack.push((to, 0));
+                            }
+                            GraphVisit::Visiting => back_edges[edge] = true,
+                            GraphVisit::Visited => {}
+                        }
+                    }
+                    None => visit_states[node] = GraphVisit::Visited,
+                }
+            }
+        }
+        let forward_edges = || {
+            edges
+                .iter()
+                .zip(&back_edges)
+                .filter(|&(_, &back)| !back)
+                .map(|(&edge, _)| edge)
+        };
+
+        // Each node goes one layer past the furthest node with an edge to it.
+        let mut layers = vec![0; node_count];
+        let mut incoming = vec![0; node_count];
+        for (_, to) in forward_edges() {
+            incoming[to] += 1;
+        }
+        let mut ready: Vec<_> = (0..node_count)
+            .filter(|&node| incoming[node] == 0)
+            .collect();
+        while let Some(node) = ready.pop() {
+            for (from, to) in forward_edges() {
+                if from == node {
+                    layers[to] = layers[to].max(layers[node] + 1);
+                    incoming[to] -= 1;
+                    if incoming[to] == 0 {
+                        ready.push(to);
+                    }
+                }
+            }
+        }
+
+        // Within a layer, nodes go in the order of the average rank of the nodes pointing to
+        // them, which keeps arrows from crossing where it's easy to. Arrows that loop back to
+        // the top layer need a row above it.
+        let layer_count = layers.iter().max().map_or(0, |&layer| layer + 1);
+        let top = if edges
+            .iter()
+            .zip(&back_edges)
+            .any(|(&(_, to), &back)| back && layers[to] == 0)
+        {
+            1
+        } else {
+            0
+        };
+        let mut ranks = vec![0; node_count];
+        let mut positions = vec![(0, 0); node_count];
+        for layer in 0..layer_count {
+            let mut layer_nodes: Vec<_> = (0..node_count)
+                .filter(|&node| layers[node] == layer)
+                .map(|node| {
+                    let from_ranks: Vec<_> = forward_edges()
+                        .filter(|&(_, to)| to == node)
+                        .map(|(from, _)| ranks[from] as f32)
+                        .collect();
+                    let average_rank = if from_ranks.is_empty() {
+                        node as f32
+                    } else {
+                        from_ranks.iter().sum::<f32>() / from_ranks.len() as f32
+                    };
+                    (node, average_rank)
+                })
+                .collect();
+            layer_nodes.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+            let y = top + (layer as u16).saturating_mul(GRAPH_VIEW_LAYER_GAP + 1);
+            let mut x = 0u16;
+            for (rank, &(node, _)) in layer_nodes.iter().enumerate() {
+                ranks[node] = rank;
+                positions[node] = (x, y);
+                x = x
+                    .saturating_add(self.nodes[node].label.width() as u16)
+                    .saturating_add(GRAPH_VIEW_NODE_GAP);
+            }
+        }
+
+        GraphLayout {
+            positions,
+            layers,
+            edges,
+            back_edges,
+        }
+    }
+}
+
+impl<'a> Widget for GraphView<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = clip_to_buffer(area, buf);
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        // Arrows are drawn as the directions their lines leave each cell in, so that lines that
+        // meet join up, then the nodes go over them. Each arrow leaves from under its node's
+        // second column and comes in from above the other's.
+        let layout = self.layout();
+        let mut line_masks = vec![0u8; area.width as usize * area.height as usize];
+        let mut mark = |(x, y): (i32, i32), mask: u8| {
+            if (0..i32::from(area.width)).contains(&x) && (0..i32::from(area.height)).contains(&y) {
+                line_masks[y as usize * area.width as usize + x as usize] |= mask;
+            }
+        };
+        let mut line = |(x0, y0): (i32, i32), (x1, y1): (i32, i32)| {
+            if y0 == y1 {
+                for x in x0.min(x1)..=x0.max(x1) {
+                    let left = if x > x0.min(x1) { GRAPH_VIEW_LEFT } else { 0 };
+                    let right = if x < x0.max(x1) { GRAPH_VIEW_RIGHT } else { 0 };
+                    mark((x, y0), left | right);
+                }
+            } else {
+                for y in y0.min(y1)..=y0.max(y1) {
+                    let up = if y > y0.min(y1) { GRAPH_VIEW_UP } else { 0 };
+                    let down = if y < y0.max(y1) { GRAPH_VIEW_DOWN } else { 0 };
+                    mark((x0, y), up | down);
+                }
+            }
+        };
+        let right = self
+            .nodes
+            .iter()
+            .zip(&layout.positions)
+            .map(|(node, &(x, _))| i32::from(x) + node.label.width() as i32)
+            .max()
+            .unwrap_or_default();
+        let mut starts = vec![];
+        let detours: Vec<_> = layout
+            .edges
+            .iter()
+            .zip(&layout.back_edges)
+            .map(|(&(from, to), &back)| back || layout.layers[to] > layout.layers[from] + 1)
+            .collect();
+        let detour_total = detours.iter().filter(|&&detour| detour).count() as i32;
+        let mut arrows = vec![];
+        let mut detour_count = 0;
+        for (&(from, to), &detour) in layout.edges.iter().zip(&detours) {
+            let (from_x, from_y) = layout.positions[from];
+            let (to_x, to_y) = layout.positions[to];
+            let start = (i32::from(from_x) + 1, i32::from(from_y) + 1);
+            let arrow = (i32::from(to_x) + 1, i32::from(to_y) - 1);
+            if detour {
+                // Each detour gets a lane of its own, inside the area even if labels aren't.
+                detour_count += 1;
+                let lane_x = (right + detour_count)
+                    .min(i32::from(area.width) - 1 - (detour_total - detour_count));
+                line(start, (lane_x, start.1));
+                line((lane_x, start.1), (lane_x, arrow.1));
+                line((lane_x, arrow.1), arrow);
+            } else {
+                line(start, (arrow.0, start.1));
+                line((arrow.0, start.1), arrow);
+            }
+            starts.push(start);
+            arrows.push(arrow);
+        }
+        for start in starts {
+            mark(start, GRAPH_VIEW_UP);
+        }
+
+        for (index, &mask) in line_masks.iter().enumerate() {
+            if mask != 0 {
+                let x = area.x + (index % area.width as usize) as u16;
+                let y = area.y + (index / area.width as usize) as u16;
+                buf.get_mut(x, y)
+                    .set_symbol(GRAPH_VIEW_LINE_SYMBOLS[mask as usize])
+                    .set_style(self.edge_style);
+            }
+        }
+        for (x, y) in arrows {
+            if (0..i32::from(area.width)).contains(&x) && (0..i32::from(area.height)).contains(&y) {
+                buf.get_mut(area.x + x as u16, area.y + y as u16)
+                    .set_symbol(GRAPH_VIEW_ARROW_SYMBOL)
+                    .set_style(self.edge_style);
+            }
+        }
+        for (node, &(x, y)) in self.nodes.iter().zip(&layout.positions) {
+            if x >= area.width || y >= area.height {
+                continue;
+            }
+            let width = (node.label.width() as u16).min(area.width - x);
+            buf.set_style(Rect::new(area.x + x, area.y + y, width, 1), node.style);
+            buf.set_spans(area.x + x, area.y + y, &node.label, width);
+        }
+    }
+}
+
+// Scrollbar
+
+/// Scroll position shared between a scrollable view and its scrollbar. The view reports its
+/// lengths each frame, and the scrollbar records where it was drawn so clicks can be routed
+/// back to it.
+#[derive(Clone, Copy, Default)]
+pub struct ScrollState {
+    offset: usize,
+    content_length: usize,
+    viewport_length: usize,
+    scrollbar_area: Rect,
+}
+
+impl ScrollState {
+    /// How many lines the view is scrolled down by.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Records how many lines there are and how many fit, pulling the offset back if the
+    /// content shrank.
+    pub fn set_lengths(&mut self, content_length: usize, viewport_length: usize) {
+        self.content_length = content_length;
+        self.viewport_length = viewport_length;
+        self.offset = self.offset.min(self.max_offset());
+    }
+
+    /// Scrolls down by `lines`, or up if it's negative, stopping at either end.
+    pub fn scroll_by(&mut self, lines: isize) {
+        let offset = self.offset as isize + lines;
+        self.offset = (offset.max(0) as usize).min(self.max_offset());
+    }
+
+    /// Scrolls just far enough that `line` is in view, if it isn't already.
+    pub fn reveal(&mut self, line: usize) {
+        if line < self.offset {
+            self.offset = line;
+        } else if line >= self.offset + self.viewport_length {
+            self.offset = (line + 1).saturating_sub(self.viewport_length);
+        }
+        self.offset = self.offset.min(self.max_offset());
+    }
+
+    /// Handles a click on the scrollbar: the arrows scroll one line and the trough pages
+    /// toward the click. Returns false if the click landed outside the scrollbar.
+    pub fn click(&mut self, column: u16, row: u16) -> bool {
+        let area = self.scrollbar_area;
+        if column < area.x || column >= area.right() || row < area.y || row >= area.bottom() {
+            return false;
+        }
+        if area.height < 2 {
+            return true;
+        }
+
+        let page_length = self.viewport_length.max(1) as isize;
+        let (thumb_start, thumb_end) = self.thumb_extent(area);
+        if row == area.y {
+            self.scroll_by(-1);
+        } else if row == area.bottom() - 1 {
+            self.scroll_by(1);
+        } else if row < thumb_start {
+            self.scroll_by(-page_length);
+        } else if row >= thumb_end {
+            self.scroll_by(page_length);
+        }
+        true
+    }
+
+    fn max_offset(&self) -> usize {
+        self.content_length.saturating_sub(self.viewport_length)
+    }
+
+    // Returns the rows covered by the thumb as a half-open range within the track between the
+    // two arrows. The thumb is as long relative to the track as the viewport is relative to the
+    // content, but never shorter than `SCROLLBAR_MIN_THUMB_LENGTH`.
+    fn thumb_extent(&self, area: Rect) -> (u16, u16) {
+        let track_start = area.y + 1;
+        let track_length = area.height.saturating_sub(2);
+        if self.content_length <= self.viewport_length {
+            return (track_start, track_start + track_length);
+        }
+
+        let visible_fraction = self.viewport_length as f32 / self.content_length as f32;
+        let thumb_length = ((visible_fraction * track_length as f32).round() as u16)
+            .max(SCROLLBAR_MIN_THUMB_LENGTH)
+            .min(track_length);
+        let scrolled_fraction = self.offset as f32 / self.max_offset() as f32;
+        let thumb_start = (scrolled_fraction * (track_length - thumb_length) as f32).round() as u16;
+        (
+            track_start + thumb_start,
+            track_start + thumb_start + thumb_length,
+        )
+    }
+}
+
+/// The symbols a `Scrollbar` draws with. Each must be a single column wide.
+pub struct ScrollbarSymbols {
+    /// The arrow at the top.
+    pub up: &'static str,
+    /// The arrow at the bottom.
+    pub down: &'static str,
+    /// The part of the trough the thumb isn't covering.
+    pub track: &'static str,
+    /// The part of the trough showing where the view is.
+    pub thumb: &'static str,
+}
+
+/// A vertical scrollbar, one column wide, with arrows at both ends. Create one with
+/// `Scrollbar::new(state, symbols, color)`; drawing it records its position in `state`, so that
+/// `ScrollState::click` can tell whether a later click hit it.
+#[derive(Constructor)]
+pub struct Scrollbar<'a> {
+    state: &'a mut ScrollState,
+    symbols: &'a ScrollbarSymbols,
+    color: Color,
+}
+
+impl<'a> Widget for Scrollbar<'a> {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        let area = clip_to_buffer(area, buffer);
+        self.state.scrollbar_area = area;
+        if area.width == 0 || area.height < 2 {
+            return;
+        }
+
+        let style = Style::default().fg(self.color);
+        let (thumb_start, thumb_end) = self.state.thumb_extent(area);
+        buffer.set_string(area.x, area.y, self.symbols.up, style);
+        for y in (area.y + 1)..(area.bottom() - 1) {
+            let string = if y >= thumb_start && y < thumb_end {
+                self.symbols.thumb
+            } else {
+                self.symbols.track
+            };
+            buffer.set_string(area.x, y, string, style);
+        }
+        buffer.set_string(area.x, area.bottom() - 1, self.symbols.down, style);
+    }
+}
+
+// Code view
+
+/// A snippet of Rust source with line numbers, highlighted by a small lexer rather than a full
+/// grammar: keywords, type names, literals, comments, and macros and attributes each get a
+/// style. One line can be marked, such as the line a task was spawned on. Create one with
+/// `CodeView::new(source, first_line_number, styles)`.
+pub struct CodeView<'a> {
+    source: &'a str,
+    first_line_number: u32,
+    marked_line_number: Option<u32>,
+    styles: CodeStyles,
+    scroll: usize,
+}
+
+/// The styles `CodeView` draws each kind of token in.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct CodeStyles {
+    /// For identifiers, punctuation, and anything else without a style of its own.
+    pub text: Style,
+    /// Rust's keywords, `self` and `Self` included.
+    pub keyword: Style,
+    /// Identifiers that start with a capital letter.
+    pub type_name: Style,
+    /// Strings, characters, and numbers.
+    pub literal: Style,
+    /// Line and block comments.
+    pub comment: Style,
+    /// Macro names, `!` included, and attributes.
+    pub macro_name: Style,
+    /// For the line numbers down the left.
+    pub line_number: Style,
+    /// Applied over the whole of the marked line, line number included.
+    pub marked_line: Style,
+}
+
+impl<'a> CodeView<'a> {
+    /// `source` starts at line `first_line_number` of its file.
+    pub fn new(source: &'a str, first_line_number: u32, styles: CodeStyles) -> CodeView<'a> {
+        CodeView {
+            source,
+            first_line_number,
+            marked_line_number: None,
+            styles,
+            scroll: 0,
+        }
+    }
+
+    /// Marks a line by its number in the file.
+    pub fn mark_line(mut self, line_number: u32) -> CodeView<'a> {
+        self.marked_line_number = Some(line_number);
+        self
+    }
+
+    /// Starts drawing at this line of the snippet, counting from 0.
+    pub fn scroll(mut self, first_line: usize) -> CodeView<'a> {
+        self.scroll = first_line;
+        self
+    }
+
+    // The line numbers, right-aligned, then a column for the mark and one of space.
+    fn gutter_width(&self) -> u16 {
+        let last_line_number = self.first_line_number as usize + self.source.lines().count();
+        last_line_number.saturating_sub(1).max(1).to_string().len() as u16 + 2
+    }
+}
+
+impl<'a> Measure for CodeView<'a> {
+    fn preferred_width(&self) -> u16 {
+        let code_width = self.source.lines().map(display_width).max().unwrap_or(0);
+        self.gutter_width() + code_width
+    }
+
+    fn preferred_height(&self) -> u16 {
+        self.source.lines().count() as u16
+    }
+}
+
+impl<'a> Widget for CodeView<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = clip_to_buffer(area, buf);
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let gutter_width = self.gutter_width();
+        let number_width = (gutter_width - 2) as usize;
+        // Lines are lexed from the top even when scrolled, since comments and strings can span
+        // lines.
+        let mut lexer = RustLexer::default();
+        let lines = self
+            .source
+            .lines()
+            .map(|line| lexer.tokens(line, &self.styles))
+            .collect::<Vec<_>>();
+        let rows = (area.y..area.bottom()).zip(lines.into_iter().enumerate().skip(self.scroll));
+        for (y, (index, tokens)) in rows {
+            let line_number = self.first_line_number + index as u32;
+            let marked = self.marked_line_number == Some(line_number);
+            let gutter = format!(
+                "{:>width$}{} ",
+                line_number,
+                if marked { CODE_VIEW_MARK_SYMBOL } else { " " },
+                width = number_width
+            );
+            let mut x = set_string_clipped(buf, area, area.x, y, &gutter, self.styles.line_number);
+            for (text, style) in tokens {
+                x = set_string_clipped(buf, area, x, y, text, style);
+            }
+            if marked {
+                buf.set_style(Rect::new(area.x, y, area.width, 1), self.styles.marked_line);
+            }
+        }
+    }
+}
+
+// What a line left open for the next one.
+#[derive(Default)]
+struct RustLexer {
+    in_block_comment: bool,
+    in_string: bool,
+}
+
+impl RustLexer {
+    // `line` cut into tokens, each with its style.
+    fn tokens<'a>(&mut self, line: &'a str, styles: &CodeStyles) -> Vec<(&'a str, Style)> {
+        let mut tokens = vec![];
+        let mut index = 0;
+        while index < line.len() {
+            let rest = &line[index..];
+            let (length, style) = if self.in_block_comment {
+                let length = match rest.find("*/") {
+                    Some(end) => {
+                        self.in_block_comment = false;
+                        end + 2
+                    }
+                    None => rest.len(),
+                };
+                (length, styles.comment)
+            } else if self.in_string {
+                let length = match string_end(rest) {
+                    Some(end) => {
+                        self.in_string = false;
+                        end
+                    }
+                    None => rest.len(),
+                };
+                (length, styles.literal)
+            } else if rest.starts_with("//") {
+                (rest.len(), styles.comment)
+            } else if rest.starts_with("/*") {
+                self.in_block_comment = true;
+                (2, styles.comment)
+            } else if rest.starts_with('"') {
+                self.in_string = true;
+                (1, styles.literal)
+            } else if let Some(length) = char_literal_length(rest) {
+                (length, styles.literal)
+            } else if rest.starts_with("#[") || rest.starts_with("#![") {
+                (
+                    rest.find(']').map_or(rest.len(), |end| end + 1),
+                    styles.macro_name,
+                )
+            } else if rest.starts_with(|character: char| character.is_ascii_digit()) {
+                let length = rest
+                    .find(|character: char| {
+                        !(character.is_alphanumeric() || "_.".contains(character))
+                    })
+                    .unwrap_or(rest.len());
+                (length, styles.literal)
+            } else if rest
+                .starts_with(|character: char| character.is_alphabetic() || character == '_')
+            {
+                let length = rest
+                    .find(|character: char| !(character.is_alphanumeric() || character == '_'))
+                    .unwrap_or(rest.len());
+                let word = &rest[..length];
+                if rest[length..].starts_with('!') && !rest[length..].starts_with("!=") {
+                    (length + 1, styles.macro_name)
+                } else if RUST_KEYWORDS.contains(&word) {
+                    (length, styles.keyword)
+                } else if word.starts_with(char::is_uppercase) {
+                    (length, styles.type_name)
+                } else {
+                    (length, styles.text)
+                }
+            } else {
+                (rest.chars().next().map_or(1, char::len_utf8), styles.text)
+            };
+            tokens.push((&rest[..length], style));
+            index += length;
+        }
+        tokens
+    }
+}
+
+// Where the string `text` is in stops, past its closing quote, skipping escaped quotes.
+fn string_end(text: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (index, character) in text.char_indices() {
+        match character {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => return Some(index + 1),
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+// How long the character literal `text` starts with is, if it starts with one rather than a
+// lifetime such as `'a`.
+fn char_literal_length(text: &str) -> Option<usize> {
+    let mut characters = text.char_indices();
+    if characters.next()?.1 != '\'' {
+        return None;
+    }
+    let (_, first) = characters.next()?;
+    if first == '\\' {
+        return text[2..].find('\'').map(|end| end + 3);
+    }
+    let (end, closing) = characters.next()?;
+    if closing == '\'' {
+        Some(end + 1)
+    } else {
+        None
+    }
+}
+
+// Markdown
+
+/// Text written in a small part of Markdown, for help and explanations that read better written
+/// out than built up span by span: `#` headings, list items starting with `-`, `*`, or a number,
+/// `**bold**`, and `` `code` ``. Anything else is drawn as written. Lines wrap between words,
+/// with the later rows of a list item lining up after its bullet. Create one with
+/// `Markdown::new(source, styles)`.
+pub struct Markdown<'a> {
+    source: &'a str,
+    styles: MarkdownStyles,
+    scroll: usize,
+}
+
+/// The styles `Markdown` draws each kind of text in.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct MarkdownStyles {
+    /// For paragraphs and list items.
+    pub text: Style,
+    /// For `#` headings, at any level.
+    pub heading: Style,
+    /// Applied over the style of the text around it, so that it works in headings too.
+    pub bold: Style,
+    /// Applied over the style of the text around it, like `bold`.
+    pub code: Style,
+    /// For list items' bullets and numbers.
+    pub bullet: Style,
+}
+
+impl<'a> Markdown<'a> {
+    /// `source` drawn in `styles`, from its first row.
+    pub fn new(source: &'a str, styles: MarkdownStyles) -> Markdown<'a> {
+        Markdown {
+            source,
+            styles,
+            scroll: 0,
+        }
+    }
+
+    /// Starts drawing at this row of the wrapped text.
+    pub fn scroll(mut self, first_row: usize) -> Markdown<'a> {
+        self.scroll = first_row;
+        self
+    }
+
+    /// The text wrapped to `width` columns, a row apiece. Blank lines are kept.
+    pub fn rows(&self, width: u16) -> Vec<Spans<'a>> {
+        let mut rows = vec![];
+        for line in self.source.lines() {
+            self.block(line).wrap(width.max(1), &mut rows);
+        }
+        rows
+    }
+
+    fn block(&self, line: &'a str) -> MarkdownBlock<'a> {
+        let content = line.trim_start();
+        let indent = &line[..line.len() - content.len()];
+        let styles = &self.styles;
+
+        let heading_level = content.bytes().take_while(|&byte| byte == b'#').count();
+        if (1..=6).contains(&heading_level) && content[heading_level..].starts_with(' ') {
+            let heading = content[heading_level..].trim_start();
+            return MarkdownBlock::new(vec![], inline_spans(heading, styles.heading, styles));
+        }
+
+        let number_length = content.bytes().take_while(u8::is_ascii_digit).count();
+        let bullet = if content.starts_with("- ") || content.starts_with("* ") {
+            Some((MARKDOWN_BULLET_SYMBOL, &content[2..]))
+        } else if number_length > 0 && content[number_length..].starts_with(". ") {
+            Some((&content[..number_length + 1], &content[number_length + 2..]))
+        } else {
+            None
+        };
+        let text_style = styles.text;
+        match bullet {
+            Some((bullet, item)) => MarkdownBlock::new(
+                vec![
+                    Span::raw(indent),
+                    Span::styled(bullet, styles.bullet),
+                    Span::raw(" "),
+                ],
+                inline_spans(item.trim_start(), text_style, styles),
+            ),
+            None => MarkdownBlock::new(
+                vec![Span::raw(indent)],
+                inline_spans(content, text_style, styles),
+            ),
+        }
+    }
+}
+
+// One line of the source, ready to wrap.
+struct MarkdownBlock<'a> {
+    // The indentation and bullet in front of the first row. The rows after it are indented as
+    // far.
+    prefix: Vec<Span<'a>>,
+    spans: Vec<(&'a str, Style)>,
+}
+
+impl<'a> MarkdownBlock<'a> {
+    fn new(prefix: Vec<Span<'a>>, spans: Vec<(&'a str, Style)>) -> MarkdownBlock<'a> {
+        MarkdownBlock { prefix, spans }
+    }
+
+    fn width(&self) -> u16 {
+        let prefix_width: u16 = self
+            .prefix
+            .iter()
+            .map(|span| display_width(&span.content))
+            .sum();
+        let text_width: u16 = self
+            .spans
+            .iter()
+            .map(|&(text, _)| display_width(text))
+            .sum();
+        prefix_width + text_width
+    }
+
+    // Breaks rows between words, and within words too wide for a row of their own. Spaces at
+    // the breaks are dropped.
+    fn wrap(self, width: u16, rows: &mut Vec<Spans<'a>>) {
+        let indent: u16 = self
+            .prefix
+            .iter()
+            .map(|span| display_width(&span.content))
+            .sum();
+        let mut row = self.prefix;
+        let mut row_width = indent;
+        let mut row_empty = true;
+        let mut pending_spaces: Vec<(&'a str, Style)> = vec![];
+        for word in words(&self.spans) {
+            if word[0].0.starts_with(char::is_whitespace) {
+                if !row_empty {
+                    pending_spaces = word;
+                }
+                continue;
+            }
+            let spaces_width: u16 = pending_spaces
+                .iter()
+                .map(|&(text, _)| display_width(text))
+                .sum();
+            let word_width: u16 = word.iter().map(|&(text, _)| display_width(text)).sum();
+            if !row_empty && row_width + spaces_width + word_width > width {
+                rows.push(Spans::from(mem::take(&mut row)));
+                row.push(Span::raw(" ".repeat(indent as usize)));
+                row_width = indent;
+                row_empty = true;
+                pending_spaces.clear();
+            }
+            for (text, style) in pending_spaces.drain(..) {
+                row.push(Span::styled(text, style));
+                row_width += display_width(text);
+            }
+            for (text, style) in word {
+                let mut start = 0;
+                for (index, character) in text.char_indices() {
+                    let character_width = character.width().unwrap_or(0) as u16;
+                    if !row_empty && row_width + character_width > width {
+                        if start < index {
+                            row.push(Span::styled(&text[start..index], style));
+                        }
+                        rows.push(Spans::from(mem::take(&mut row)));
+                        row.push(Span::raw(" ".repeat(indent as usize)));
+                        row_width = indent;
+                        start = index;
+                    }
+                    row_width += character_width;
+                    row_empty = false;
+                }
+                if start < text.len() {
+                    row.push(Span::styled(&text[start..], style));
+                }
+            }
+        }
+        rows.push(Spans::from(row));
+    }
+}
+
+// `text` split into plain, bold, and code spans. Markers with nothing to close them are drawn
+// as written.
+fn inline_spans<'a>(text: &'a str, base: Style, styles: &MarkdownStyles) -> Vec<(&'a str, Style)> {
+    let mut spans = vec![];
+    let mut bold = false;
+    let (mut start, mut index) = (0, 0);
+    let push_text = |spans: &mut Vec<_>, start: usize, end: usize, bold: bool| {
+        if start < end {
+            let style = if bold { base.patch(styles.bold) } else { base };
+            spans.push((&text[start..end], style));
+        }
+    };
+    while index < text.len() {
+        let rest = &text[index..];
+        if let Some(code_length) = rest.strip_prefix('`').and_then(|rest| rest.find('`')) {
+            push_text(&mut spans, start, index, bold);
+            let code = &rest[1..code_length + 1];
+            if !code.is_empty() {
+                spans.push((code, base.patch(styles.code)));
+            }
+            index += code_length + 2;
+            start = index;
+        } else if rest.starts_with("**") && (bold || rest[2..].contains("**")) {
+            push_text(&mut spans, start, index, bold);
+            bold = !bold;
+            index += 2;
+            start = index;
+        } else {
+            index += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    push_text(&mut spans, start, text.len(), bold);
+    spans
+}
+
+// Runs of spaces and runs of everything else, each made of the pieces of the spans it crosses.
+fn words<'a>(spans: &[(&'a str, Style)]) -> Vec<Vec<(&'a str, Style)>> {
+    let mut words: Vec<Vec<(&'a str, Style)>> = vec![];
+    let mut last_was_space = None;
+    for &(text, style) in spans {
+        let mut start = 0;
+        for (index, character) in text.char_indices() {
+            let is_space = character.is_whitespace();
+            if last_was_space != Some(is_space) {
+                if start < index {
+                    words.last_mut().unwrap().push((&text[start..index], style));
+                }
+                words.push(vec![]);
+                start = index;
+                last_was_space = Some(is_space);
+            }
+        }
+        if start < text.len() {
+            words.last_mut().unwrap().push((&text[start..], style));
+        }
+    }
+    words
+}
+
+impl<'a> Measure for Markdown<'a> {
+    /// The width of the widest line, unwrapped.
+    fn preferred_width(&self) -> u16 {
+        self.source
+            .lines()
+            .map(|line| self.block(line).width())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The number of lines, unwrapped.
+    fn preferred_height(&self) -> u16 {
+        self.source.lines().count() as u16
+    }
+}
+
+impl<'a> Widget for Markdown<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = clip_to_buffer(area, buf);
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let rows = self.rows(area.width);
+        for (y, row) in (area.y..area.bottom()).zip(rows.iter().skip(self.scroll)) {
+            buf.set_spans(area.x, y, row, area.width);
+        }
+    }
+}
+
+// Powerline
+
+/// A status line in the style of the Powerline shell prompt: labels in colored segments joined
+/// by arrow-shaped separators. The first label is the main segment, the second the sub segment,
+/// and the rest share the sub-sub colors. The separators need a Nerd Font or Powerline font
+/// unless they're changed with `symbols`.
+///
+/// Start from `Powerline::new(labels)`, which runs left to right in blues and grays, and change
+/// what's needed with the builder methods.
+#[derive(Clone, Copy)]
+pub struct Powerline<'a> {
+    labels: &'a [&'a str],
+    main_color: Color,
+    sub_color: Color,
+    sub_sub_bg_color: Color,
+    sub_sub_fg_color: Color,
+    sub_separator_color: Color,
+    direction: PowerlineDirection,
+    main_visibility: MainVisibility,
+    symbols: &'a PowerlineSymbols,
+}
+
+/// The separators a `Powerline` draws between its segments, for each direction it can run in.
+/// Each must be a single column wide.
+pub struct PowerlineSymbols {
+    /// After the main and sub segments, and the last segment, running left to right.
+    pub main_ltr: &'static str,
+    /// Between the remaining segments, running left to right.
+    pub sub_ltr: &'static str,
+    /// After the main and sub segments, and the last segment, running right to left.
+    pub main_rtl: &'static str,
+    /// Between the remaining segments, running right to left.
+    pub sub_rtl: &'static str,
+}
+
+impl<'a> Powerline<'a> {
+    /// A powerline with one segment per label, from the main one outward.
+    pub fn new(labels: &'a [&'a str]) -> Powerline<'a> {
+        Powerline {
+            labels,
+            main_color: Color::Blue,
+            sub_color: Color::Cyan,
+            sub_sub_bg_color: Color::DarkGray,
+            sub_sub_fg_color: Color::White,
+            sub_separator_color: Color::Gray,
+            direction: PowerlineDirection::LeftToRight,
+            main_visibility: MainVisibility::Visible,
+            symbols: &POWERLINE_SYMBOLS_NERD_FONT,
+        }
+    }
+
+    /// Sets the main segment's background.
+    pub fn main_color(mut self, main_color: Color) -> Powerline<'a> {
+        self.main_color = main_color;
+        self
+    }
+
+    /// Sets the sub segment's background.
+    pub fn sub_color(mut self, sub_color: Color) -> Powerline<'a> {
+        self.sub_color = sub_color;
+        self
+    }
+
+    /// Sets the remaining segments' background and text colors.
+    pub fn sub_sub_colors(mut self, bg_color: Color, fg_color: Color) -> Powerline<'a> {
+        self.sub_sub_bg_color = bg_color;
+        self.sub_sub_fg_color = fg_color;
+        self
+    }
+
+    /// Sets the color of the thin separators between the remaining segments.
+    pub fn sub_separator_color(mut self, sub_separator_color: Color) -> Powerline<'a> {
+        self.sub_separator_color = sub_separator_color;
+        self
+    }
+
+    /// Sets which edge of the area the segments start from.
+    pub fn direction(mut self, direction: PowerlineDirection) -> Powerline<'a> {
+        self.direction = direction;
+        self
+    }
+
+    /// Sets whether the first label gets the main segment's colors or the sub segment's.
+    pub fn main_visibility(mut self, main_visibility: MainVisibility) -> Powerline<'a> {
+        self.main_visibility = main_visibility;
+        self
+    }
+
+    /// Sets the separators, which are Nerd Font glyphs unless changed.
+    pub fn symbols(mut self, symbols: &'a PowerlineSymbols) -> Powerline<'a> {
+        self.symbols = symbols;
+        self
+    }
+}
+
+/// Which way a `Powerline`'s segments run.
+#[derive(Clone, Copy, PartialEq)]
+pub enum PowerlineDirection {
+    /// Starting from the left edge. Segments that don't fit are cut off at the right edge.
+    LeftToRight,
+    /// Starting from the right edge. Segments that don't fit are left out entirely.
+    RightToLeft,
+}
+
+/// Whether a `Powerline` has a main segment.
+#[derive(Clone, Copy, PartialEq)]
+pub enum MainVisibility {
+    /// The first label is drawn in the main color.
+    Visible,
+    /// The first label is drawn as the sub segment, for a powerline that continues one drawn
+    /// elsewhere.
+    Invisible,
+}
+
+/// Each segment is its label, padded by a space on either side, plus a separator.
+impl<'a> Measure for Powerline<'a> {
+    fn preferred_width(&self) -> u16 {
+        self.labels
+            .iter()
+            .map(|label| display_width(label) + 3)
+            .sum()
+    }
+}
+
+impl<'a> Widget for Powerline<'a> {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        let area = clip_to_buffer(area, buffer);
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let mut x = match self.direction {
+            PowerlineDirection::LeftToRight => area.x,
+            PowerlineDirection::RightToLeft => area.right(),
+        };
+        for (index, label) in self.labels.iter().enumerate() {
+            let style = match (index, self.main_visibility) {
+                (0, MainVisibility::Visible) => Style::default()
+                    .bg(self.main_color)
+                    .fg(Color::Black)
+                    .add_modifier(Modifier::BOLD),
+                (1, MainVisibility::Visible) | (0, MainVisibility::Invisible) => {
+                    Style::default().bg(self.sub_color).fg(Color::Black)
+                }
+                _ => Style::default()
+                    .bg(self.sub_sub_bg_color)
+                    .fg(self.sub_sub_fg_color),
+            };
+            write_and_advance(&mut x, area, " ", style, buffer, self.direction);
+            write_and_advance(&mut x, area, label, style, buffer, self.direction);
+            write_and_advance(&mut x, area, " ", style, buffer, self.direction);
+
+            let (separator_style, separator_is_sub);
+            match (index, self.main_visibility) {
+                (0, MainVisibility::Visible) => {
+                    separator_style = Style::default().bg(self.sub_color).fg(self.main_color);
+                    separator_is_sub = false;
+                }
+                (1, MainVisibility::Visible) | (0, MainVisibility::Invisible) => {
+                    separator_style = Style::default()
+                        .bg(self.sub_sub_bg_color)
+                        .fg(self.sub_color);
+                    separator_is_sub = false;
+                }
+                (index, _) if index < self.labels.len() - 1 => {
+                    separator_style = Style::default()
+                        .bg(self.sub_sub_bg_color)
+                        .fg(self.sub_separator_color);
+                    separator_is_sub = true;
+                }
+                _ => {
+                    separator_style = Style::default().fg(self.sub_sub_bg_color);
+                    separator_is_sub = false;
+                }
+            }
+
+            let separator_label = match (separator_is_sub, self.direction) {
+                (false, PowerlineDirection::LeftToRight) => self.symbols.main_ltr,
+                (true, PowerlineDirection::LeftToRight) => self.symbols.sub_ltr,
+                (false, PowerlineDirection::RightToLeft) => self.symbols.main_rtl,
+                (true, PowerlineDirection::RightToLeft) => self.symbols.sub_rtl,
+            };
+
+            write_and_advance(
+                &mut x,
+                area,
+                separator_label,
+                separator_style,
+                buffer,
+                self.direction,
+            );
+        }
+
+        // Segments that run past the end of the area are clipped. Right-to-left segments are
+        // dropped entirely once they would start before the left edge.
+        fn write_and_advance(
+            x: &mut u16,
+            area: Rect,
+            string: &str,
+            style: Style,
+            buffer: &mut Buffer,
+            direction: PowerlineDirection,
+        ) {
+            let string_length = display_width(string);
+            match direction {
+                PowerlineDirection::LeftToRight => {
+                    *x = set_string_clipped(buffer, area, *x, area.y, string, style);
+                }
+                PowerlineDirection::RightToLeft => {
+                    if *x < area.x + string_length {
+                        *x = area.x;
+                        return;
+                    }
+                    *x -= string_length;
+                    set_string_clipped(buffer, area, *x, area.y, string, style);
+                }
+            }
+        }
+    }
+}
+
+// Frame
+
+/// A border around an area, with labels set into it. The inside is left alone, for other
+/// widgets to fill; `inner` says where that is.
+///
+/// Start from `BoxFrame::new(label)`, which draws all four sides with rounded corners in gray,
+/// and change what's needed with the builder methods.
+#[derive(Clone, Copy)]
+pub struct BoxFrame<'a> {
+    label: &'a str,
+    label_alignment: Alignment,
+    aux_label: Option<&'a str>,
+    footer_label: Option<&'a str>,
+    border_color: Color,
+    text_color: Color,
+    border_set: BorderSet,
+    borders: Borders,
+    horizontal_padding: u16,
+    vertical_padding: u16,
+}
+
+impl<'a> BoxFrame<'a> {
+    /// A frame with `label` as its title, at the left of the top border.
+    pub fn new(label: &'a str) -> BoxFrame<'a> {
+        BoxFrame {
+            label,
+            label_alignment: Alignment::Left,
+            aux_label: None,
+            footer_label: None,
+            border_color: Color::Gray,
+            text_color: Color::White,
+            border_set: BorderSet::Rounded,
+            borders: Borders::ALL,
+            horizontal_padding: 0,
+            vertical_padding: 0,
+        }
+    }
+
+    /// Sets where the title goes along the top border.
+    pub fn label_alignment(mut self, label_alignment: Alignment) -> BoxFrame<'a> {
+        self.label_alignment = label_alignment;
+        self
+    }
+
+    /// Adds secondary text at the right of the top border.
+    pub fn aux_label(mut self, aux_label: &'a str) -> BoxFrame<'a> {
+        self.aux_label = Some(aux_label);
+        self
+    }
+
+    /// Adds text at the left of the bottom border.
+    pub fn footer_label(mut self, footer_label: &'a str) -> BoxFrame<'a> {
+        self.footer_label = Some(footer_label);
+        self
+    }
+
+    /// Sets the color of the border lines.
+    pub fn border_color(mut self, border_color: Color) -> BoxFrame<'a> {
+        self.border_color = border_color;
+        self
+    }
+
+    /// Sets the color of the labels.
+    pub fn text_color(mut self, text_color: Color) -> BoxFrame<'a> {
+        self.text_color = text_color;
+        self
+    }
+
+    /// Sets the line style.
+    pub fn border_set(mut self, border_set: BorderSet) -> BoxFrame<'a> {
+        self.border_set = border_set;
+        self
+    }
+
+    /// Sets which sides to draw. Labels only appear on sides that are drawn.
+    pub fn borders(mut self, borders: Borders) -> BoxFrame<'a> {
+        self.borders = borders;
+        self
+    }
+
+    /// Leaves `horizontal` columns on the left and right and `vertical` rows on the top and
+    /// bottom between the border and the area returned by `inner`.
+    pub fn padding(mut self, horizontal: u16, vertical: u16) -> BoxFrame<'a> {
+        self.horizontal_padding = horizontal;
+        self.vertical_padding = vertical;
+        self
+    }
+
+    /// Returns the part of `area` inside the border and padding, where the frame's content
+    /// goes.
+    pub fn inner(&self, area: Rect) -> Rect {
+        let side = |border, padding| u16::from(self.borders.contains(border)) + padding;
+        let left = side(Borders::LEFT, self.horizontal_padding);
+        let right = side(Borders::RIGHT, self.horizontal_padding);
+        let top = side(Borders::TOP, self.vertical_padding);
+        let bottom = side(Borders::BOTTOM, self.vertical_padding);
+        let x = area.x.saturating_add(left).min(area.right());
+        let y = area.y.saturating_add(top).min(area.bottom());
+        Rect::new(
+            x,
+            y,
+            area.width
+                .saturating_sub(left + right)
+                .min(area.right() - x),
+            area.height
+                .saturating_sub(top + bottom)
+                .min(area.bottom() - y),
+        )
+    }
+}
+
+/// The line style of a `BoxFrame`.
+#[derive(Clone, Copy, PartialEq)]
+pub enum BorderSet {
+    /// Thin lines with rounded corners.
+    Rounded,
+    /// Thin lines with square corners.
+    Square,
+    /// Double lines.
+    Double,
+    /// Heavy lines.
+    Thick,
+    /// `+`, `-`, and `|`, for terminals without box-drawing glyphs.
+    Ascii,
+}
+
+struct FrameSymbols {
+    upper_left: &'static str,
+    upper_right: &'static str,
+    lower_right: &'static str,
+    lower_left: &'static str,
+    horizontal: &'static str,
+    vertical: &'static str,
+}
+
+impl BorderSet {
+    fn symbols(self) -> &'static FrameSymbols {
+        match self {
+            BorderSet::Rounded => &FRAME_SYMBOLS_ROUNDED,
+            BorderSet::Square => &FRAME_SYMBOLS_SQUARE,
+            BorderSet::Double => &FRAME_SYMBOLS_DOUBLE,
+            BorderSet::Thick => &FRAME_SYMBOLS_THICK,
+            BorderSet::Ascii => &FRAME_SYMBOLS_ASCII,
+        }
+    }
+}
+
+impl<'a> Widget for BoxFrame<'a> {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        let area = clip_to_buffer(area, buffer);
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let symbols = self.border_set.symbols();
+        let (has_top, has_bottom) = (
+            self.borders.contains(Borders::TOP),
+            self.borders.contains(Borders::BOTTOM),
+        );
+        let (has_left, has_right) = (
+            self.borders.contains(Borders::LEFT),
+            self.borders.contains(Borders::RIGHT),
+        );
+
+        // Corners are only drawn where two borders meet; otherwise the horizontal line runs
+        // straight to the edge.
+        let border_style = Style::default().fg(self.border_color);
+        if has_top {
+            let top_string = horizontal_border_string(
+                area.width,
+                if has_left {
+                    symbols.upper_left
+                } else {
+                    symbols.horizontal
+                },
+                if has_right {
+                    symbols.upper_right
+                } else {
+                    symbols.horizontal
+                },
+                symbols.horizontal,
+            );
+            buffer.set_string(area.x, area.y, &top_string, border_style);
+        }
+        if has_bottom {
+            let bottom_string = horizontal_border_string(
+                area.width,
+                if has_left {
+                    symbols.lower_left
+                } else {
+                    symbols.horizontal
+                },
+                if has_right {
+                    symbols.lower_right
+                } else {
+                    symbols.horizontal
+                },
+                symbols.horizontal,
+            );
+            buffer.set_string(area.x, area.bottom() - 1, &bottom_string, border_style);
+        }
+
+        let vertical_start = if has_top { area.y + 1 } else { area.y };
+        let vertical_end = if has_bottom {
+            area.bottom() - 1
+        } else {
+            area.bottom()
+        };
+        for y in vertical_start..vertical_end {
+            if has_left {
+                buffer.set_string(area.x, y, symbols.vertical, border_style);
+            }
+            if has_right {
+                buffer.set_string(area.right() - 1, y, symbols.vertical, border_style);
+            }
+        }
+
+        // Titles are drawn as ` label `, inset two cells from the corners.
+        let aux_label_width = self
+            .aux_label
+            .map_or(0, |aux_label| display_width(aux_label) + 2);
+        let aux_label_x = area.right().saturating_sub(2 + aux_label_width);
+        if let Some(aux_label) = self.aux_label {
+            let aux_text_style = Style::default().fg(self.text_color);
+            render_title(area, aux_label_x, area.y, aux_label, aux_text_style, buffer);
+        }
+
+        let label_width = display_width(self.label) + 2;
+        let label_x = match self.label_alignment {
+            Alignment::Left => area.x + 2,
+            Alignment::Center => area.x + area.width.saturating_sub(label_width) / 2,
+            Alignment::Right if self.aux_label.is_some() => {
+                aux_label_x.saturating_sub(label_width + 1)
+            }
+            Alignment::Right => aux_label_x.saturating_sub(label_width),
+        };
+        let text_style = Style::default()
+            .fg(self.text_color)
+            .add_modifier(Modifier::BOLD);
+        render_title(area, label_x, area.y, self.label, text_style, buffer);
+
+        if let Some(footer_label) = self.footer_label {
+            let footer_text_style = Style::default().fg(self.text_color);
+            render_title(
+                area,
+                area.x + 2,
+                area.bottom() - 1,
+                footer_label,
+                footer_text_style,
+                buffer,
+            );
+        }
+
+        fn render_title(
+            area: Rect,
+            x: u16,
+            y: u16,
+            label: &str,
+            style: Style,
+            buffer: &mut Buffer,
+        ) {
+            let x = set_string_clipped(buffer, area, x, y, " ", style);
+            let x = set_string_clipped(buffer, area, x, y, label, style);
+            set_string_clipped(buffer, area, x, y, " ", style);
+        }
+
+        fn horizontal_border_string(
+            width: u16,
+            left_symbol: &str,
+            right_symbol: &str,
+            horizontal_symbol: &str,
+        ) -> String {
+            let mut string = left_symbol.to_owned();
+            for _ in 1..width.saturating_sub(1) {
+                string.push_str(horizontal_symbol);
+            }
+            if width > 1 {
+                string.push_str(right_symbol);
+            }
+            string
+        }
+    }
+}
+
+// Text input
+
+/// The text in a `TextInput` and where its cursor is. Whoever owns the input applies edits to
+/// this as keys arrive, and the input draws whatever it holds.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct TextInputState {
+    text: String,
+    /// Counted in characters, from the start of the text.
+    cursor: usize,
+}
+
+impl TextInputState {
+    /// What's been typed so far.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// How many characters come before the cursor.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Types a character at the cursor.
+    pub fn insert(&mut self, character: char) {
+        let index = self.byte_index(self.cursor);
+        self.text.insert(index, character);
+        self.cursor += 1;
+    }
+
+    /// Deletes the character before the cursor, like Backspace.
+    pub fn delete_backward(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            let index = self.byte_index(self.cursor);
+            self.text.remove(index);
+        }
+    }
+
+    /// Deletes the character after the cursor, like Delete.
+    pub fn delete_forward(&mut self) {
+        if self.cursor < self.text.chars().count() {
+            let index = self.byte_index(self.cursor);
+            self.text.remove(index);
+        }
+    }
+
+    /// Moves the cursor right by `characters`, or left if it's negative, stopping at either end.
+    pub fn move_cursor(&mut self, characters: isize) {
+        let cursor = (self.cursor as isize).saturating_add(characters);
+        self.cursor = (cursor.max(0) as usize).min(self.text.chars().count());
+    }
+
+    /// Moves the cursor to the start of the text, like Home.
+    pub fn move_to_start(&mut self) {
+        self.cursor = 0;
+    }
+
+    /// Moves the cursor past the end of the text, like End.
+    pub fn move_to_end(&mut self) {
+        self.cursor = self.text.chars().count();
+    }
+
+    /// Empties the input.
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    fn byte_index(&self, character_index: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(character_index)
+            .map_or(self.text.len(), |(index, _)| index)
+    }
+}
+
+/// A line of editable text with the cursor drawn as an inverted cell. Text too long for the
+/// input scrolls so that the cursor stays in view, and an empty input shows its placeholder in
+/// the placeholder color instead. Create one with
+/// `TextInput::new(state, placeholder, color, placeholder_color)`.
+#[derive(Constructor)]
+pub struct TextInput<'a> {
+    state: &'a TextInputState,
+    placeholder: &'a str,
+    color: Color,
+    placeholder_color: Color,
+}
+
+impl<'a> Widget for TextInput<'a> {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        let area = clip_to_buffer(area, buffer);
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let style = Style::default().fg(self.color);
+        let cursor_style = style.add_modifier(Modifier::REVERSED);
+        if self.state.text.is_empty() {
+            let placeholder_style = Style::default().fg(self.placeholder_color);
+            set_string_clipped(
+                buffer,
+                area,
+                area.x,
+                area.y,
+                self.placeholder,
+                placeholder_style,
+            );
+            let cursor_symbol = match self.placeholder.chars().next() {
+                Some(character) if display_width(self.placeholder) > 0 => character.to_string(),
+                _ => " ".to_owned(),
+            };
+            set_string_clipped(buffer, area, area.x, area.y, &cursor_symbol, cursor_style);
+            return;
+        }
+
+        // Scroll just far enough that the cursor's cell fits at the right edge.
+        let cursor_column: u16 = self
+            .state
+            .text
+            .chars()
+            .take(self.state.cursor)
+            .map(|character| display_width(&character.to_string()))
+            .sum();
+        let scroll = (cursor_column + 1).saturating_sub(area.width);
+        let mut column = 0;
+        for (index, character) in self.state.text.chars().enumerate() {
+            let symbol = character.to_string();
+            let width = display_width(&symbol);
+            if column >= scroll && column + width <= scroll + area.width {
+                let style = if index == self.state.cursor {
+                    cursor_style
+                } else {
+                    style
+                };
+                set_string_clipped(
+                    buffer,
+                    area,
+                    area.x + column - scroll,
+                    area.y,
+                    &symbol,
+                    style,
+                );
+            }
+            column += width;
+        }
+        if self.state.cursor == self.state.text.chars().count() {
+            set_string_clipped(
+                buffer,
+                area,
+                area.x + column - scroll,
+                area.y,
+                " ",
+                cursor_style,
+            );
+        }
+    }
+}
+
+/// Wide enough for the text or the placeholder, whichever is wider, with a cell for the cursor
+/// after it.
+impl<'a> Measure for TextInput<'a> {
+    fn preferred_width(&self) -> u16 {
+        display_width(&self.state.text).max(display_width(self.placeholder)) + 1
+    }
+}
+
+fn clip_to_buffer(area: Rect, buffer: &Buffer) -> Rect {
+    if area.intersects(buffer.area) {
+        area.intersection(buffer.area)
+    } else {
+        Rect::default()
+    }
+}
+
+// Like `Buffer::set_string`, but clips to `area` instead of the whole buffer and ignores
+// positions outside of it rather than panicking. Returns the column after the last one written.
+fn set_string_clipped(
+    buffer: &mut Buffer,
+    area: Rect,
+    x: u16,
+    y: u16,
+    string: &str,
+    style: Style,
+) -> u16 {
+    if x < area.x || x >= area.right() || y < area.y || y >= area.bottom() {
+        return x.saturating_add(display_width(string));
+    }
+    let (next_x, _) = buffer.set_stringn(x, y, string, (area.right() - x) as usize, style);
+    next_x
+}
+
+fn clamp(x: f32, min_val: f32, max_val: f32) -> f32 {
+    if x < min_val {
+        min_val
+    } else if x > max_val {
+        max_val
+    } else {
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::num::f32::ANY as ANY_F32;
+    use proptest::prelude::*;
+    use tui::buffer::Cell;
+
+    static TEST_LABELS: [&'static str; 3] = ["Alpha", "Beta", "Gamma"];
+    static TEST_DATA: [f32; 8] = [0.0, 1.0, 4.0, 2.0, 8.0, 3.0, 5.0, 7.0];
+    static UNTOUCHED_SYMBOL: &'static str = "@";
+
+    // Renders a widget into `area` inside a 10x6 buffer pre-filled with a sentinel, and checks
+    // that nothing outside of `area` was written to.
+    fn render_degenerate<F>(area: Rect, render: F)
+    where
+        F: Fn(Rect, &mut Buffer),
+    {
+        let buffer_area = Rect::new(0, 0, 10, 6);
+        let mut buffer = Buffer::filled(buffer_area, Cell::default().set_symbol(UNTOUCHED_SYMBOL));
+        render(area, &mut buffer);
+
+        let visible_area = clip_to_buffer(area, &buffer);
+        for y in buffer_area.top()..buffer_area.bottom() {
+            for x in buffer_area.left()..buffer_area.right() {
+                let inside = x >= visible_area.left()
+                    && x < visible_area.right()
+                    && y >= visible_area.top()
+                    && y < visible_area.bottom();
+                if !inside {
+                    assert_eq!(
+                        buffer.get(x, y).symbol,
+                        UNTOUCHED_SYMBOL,
+                        "cell ({}, {}) outside {:?} was drawn",
+                        x,
+                        y,
+                        area
+                    );
+                }
+            }
+        }
+    }
+
+    // Tiny areas at an offset inside the buffer, plus areas that hang off its edges or miss it
+    // entirely.
+    fn degenerate_areas() -> Vec<Rect> {
+        let mut areas = vec![];
+        for width in 0..4 {
+            for height in 0..4 {
+                areas.push(Rect::new(2, 2, width, height));
+            }
+        }
+        areas.push(Rect::new(8, 4, 6, 5));
+        areas.push(Rect::new(9, 5, 1, 1));
+        areas.push(Rect::new(10, 6, 3, 3));
+        areas.push(Rect::new(20, 20, 4, 4));
+        areas
+    }
+
+    #[test]
+    fn segmented_control_renders_into_degenerate_areas() {
+        for area in degenerate_areas() {
+            render_degenerate(area, |area, buffer| {
+                SegmentedControl::new(&TEST_LABELS, 1, Color::Blue, Color::White)
+                    .render(area, buffer)
+            });
+            render_degenerate(area, |area, buffer| {
+                SegmentedControl::new(&[], 0, Color::Blue, Color::White).render(area, buffer)
+            });
+        }
+    }
+
+    #[test]
+    fn bar_chart_renders_into_degenerate_areas() {
+        for area in degenerate_areas() {
+            render_degenerate(area, |area, buffer| {
+                BarChart::new(&TEST_DATA, 0.0, 8.0, Color::Green).render(area, buffer)
+            });
+            render_degenerate(area, |area, buffer| {
+                BarChart::auto_scaled(&TEST_DATA, AutoScale::default(), Color::Green)
+                    .axis(Color::Gray)
+                    .render(area, buffer)
+            });
+            render_degenerate(area, |area, buffer| {
+                BarChart::auto_scaled(&[], AutoScale::default(), Color::Green)
+                    .axis(Color::Gray)
+                    .render(area, buffer)
+            });
+            render_degenerate(area, |area, buffer| {
+                BarChart::new(&TEST_DATA, 0.0, 8.0, Color::Green)
+                    .symbols(CHART_SYMBOLS_BLOCKS)
+                    .render(area, buffer)
+            });
+        }
+    }
+
+    fn render_buffer<W>(widget: W, width: u16, height: u16) -> Buffer
+    where
+        W: Widget,
+    {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, width, height));
+        widget.render(buffer.area, &mut buffer);
+        buffer
+    }
+
+    fn render_rows<W>(widget: W, width: u16, height: u16) -> Vec<String>
+    where
+        W: Widget,
+    {
+        let buffer = render_buffer(widget, width, height);
+        (0..height)
+            .map(|y| (0..width).map(|x| &buffer.get(x, y).symbol[..]).collect())
+            .collect()
+    }
+
+    #[test]
+    fn cached_bar_charts_are_encoded_again_only_when_their_data_or_size_changes() {
+        let cache = ChartCache::default();
+        let chart = |data, generation| {
+            BarChart::new(data, 0.0, 8.0, Color::Green).cached(&cache, generation)
+        };
+        let rows = render_rows(chart(&TEST_DATA, 1), 4, 2);
+        assert_eq!(
+            rows,
+            render_rows(BarChart::new(&TEST_DATA, 0.0, 8.0, Color::Green), 4, 2)
+        );
+
+        // At the same generation, the data is taken to be the same, so the bars are too.
+        assert_eq!(render_rows(chart(&[8.0; 8], 1), 4, 2), rows);
+        assert_eq!(render_rows(chart(&[8.0; 8], 2), 4, 2), vec!["⣿⣿⣿⣿"; 2]);
+        assert_eq!(render_rows(chart(&[8.0; 8], 2), 2, 1), vec!["⣿⣿"]);
+    }
+
+    #[test]
+    fn bar_chart_falls_back_to_block_bars() {
+        let rows = render_rows(
+            BarChart::new(&[0.0, 2.0, 8.0, 12.0, 16.0], 0.0, 16.0, Color::Green)
+                .symbols(CHART_SYMBOLS_BLOCKS),
+            5,
+            2,
+        );
+        assert_eq!(rows, vec!["   ▄█", " ▂███"]);
+    }
+
+    #[test]
+    fn bar_chart_falls_back_to_ascii_bars() {
+        let rows = render_rows(
+            BarChart::new(&[0.0, 1.0, 2.0, 3.0, 6.0], 0.0, 6.0, Color::Green)
+                .symbols(CHART_SYMBOLS_ASCII),
+            5,
+            2,
+        );
+        assert_eq!(rows, vec!["    #", " _=##"]);
+    }
+
+    #[test]
+    fn bar_chart_bars_hang_below_baseline() {
+        let rows = render_rows(
+            BarChart::new(&[-4.0, -1.0, 4.0], -4.0, 4.0, Color::Green)
+                .baseline(0.0)
+                .symbols(CHART_SYMBOLS_BLOCKS),
+            3,
+            2,
+        );
+        assert_eq!(rows, vec!["  █", "█  "]);
+    }
+
+    #[test]
+    fn graph_renders_into_degenerate_areas() {
+        let series = [
+            GraphSeries::new("alpha", &TEST_DATA, Color::Green),
+            GraphSeries::new("beta", &[], Color::Blue),
+        ];
+        for area in degenerate_areas() {
+            render_degenerate(area, |area, buffer| {
+                Graph::new(&series, 0.0, 8.0).render(area, buffer)
+            });
+            render_degenerate(area, |area, buffer| {
+                Graph::auto_scaled(&series, AutoScale::default())
+                    .axis(Color::Gray)
+                    .legend(Color::White)
+                    .render(area, buffer)
+            });
+        }
+    }
+
+    #[test]
+    fn graph_joins_samples_with_lines() {
+        let flat = [GraphSeries::new(
+            "flat",
+            &[2.0, 2.0, 2.0, 2.0],
+            Color::Green,
+        )];
+        assert_eq!(render_rows(Graph::new(&flat, 0.0, 3.0), 2, 1), vec!["⠒⠒"]);
+
+        // The newest samples are at the right, and a gap breaks the line.
+        let rising = [GraphSeries::new("rising", &[0.0, 7.0], Color::Green)];
+        assert_eq!(
+            render_rows(Graph::new(&rising, 0.0, 7.0), 2, 2),
+            vec![" ⢸", " ⡇"]
+        );
+        let broken = [GraphSeries::new(
+            "broken",
+            &[0.0, f32::NAN, 7.0, 7.0],
+            Color::Green,
+        )];
+        assert_eq!(render_rows(Graph::new(&broken, 0.0, 7.0), 2, 1), vec!["⡀⠉"]);
+    }
+
+    #[test]
+    fn graph_labels_its_axis_and_series() {
+        let series = [
+            GraphSeries::new("p50", &[0.0, 0.0, 0.0, 0.0], Color::Green),
+            GraphSeries::new("p99", &[9.0, 9.0, 9.0, 9.0], Color::Red),
+        ];
+        let graph = Graph::new(&series, 0.0, 9.0)
+            .axis(Color::Gray)
+            .legend(Color::White);
+        let buffer = render_buffer(graph, 14, 6);
+        let rows: Vec<String> = (0..6)
+            .map(|y| (0..14).map(|x| &buffer.get(x, y).symbol[..]).collect())
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                "━ p50  ━ p99  ",
+                "  9┤        ⠉⠉",
+                "   │          ",
+                "4.5┤          ",
+                "   │          ",
+                "  0┤        ⣀⣀",
+            ]
+        );
+        assert_eq!(buffer.get(0, 0).fg, Color::Green);
+        assert_eq!(buffer.get(2, 0).fg, Color::White);
+        assert_eq!(buffer.get(7, 0).fg, Color::Red);
+        assert_eq!(buffer.get(13, 1).fg, Color::Red);
+        assert_eq!(buffer.get(13, 5).fg, Color::Green);
+    }
+
+    #[test]
+    fn big_text_renders_into_degenerate_areas() {
+        for area in degenerate_areas() {
+            render_degenerate(area, |area, buffer| {
+                BigText::new("12.5ms")
+                    .alignment(Alignment::Center)
+                    .render(area, buffer)
+            });
+        }
+    }
+
+    #[test]
+    fn big_text_draws_digits_big_and_units_small() {
+        let big_text = || BigText::new("1.5ms");
+        assert_eq!(big_text().preferred_width(), 12);
+        assert_eq!(big_text().preferred_height(), 3);
+        assert_eq!(
+            render_rows(big_text(), 13, 3),
+            vec!["▀█    █▀▀    ", " █    ▀▀█    ", "▄█▄ ▄ ▄▄█ ms "]
+        );
+        assert_eq!(
+            render_rows(big_text().alignment(Alignment::Right), 8, 2),
+            vec!["   1.5ms", "        "]
+        );
+    }
+
+    static TEST_CODE: &'static str = "#[derive(Debug)]\nfn main() {\n    let s = \"a\\\"b\"; // hi\n    println!(\"{}\", 'x');\n    /* Vec<u8>\n    */ let v: Vec<&'a str> = 42;\n}";
+
+    fn test_code_styles() -> CodeStyles {
+        CodeStyles {
+            text: Style::default().fg(Color::Gray),
+            keyword: Style::default().fg(Color::Magenta),
+            type_name: Style::default().fg(Color::Cyan),
+            literal: Style::default().fg(Color::Green),
+            comment: Style::default().fg(Color::DarkGray),
+            macro_name: Style::default().fg(Color::Yellow),
+            line_number: Style::default().fg(Color::Blue),
+            marked_line: Style::default().bg(Color::Black),
+        }
+    }
+
+    #[test]
+    fn code_view_renders_into_degenerate_areas() {
+        for area in degenerate_areas() {
+            render_degenerate(area, |area, buffer| {
+                CodeView::new(TEST_CODE, 98, test_code_styles())
+                    .mark_line(100)
+                    .scroll(2)
+                    .render(area, buffer)
+            });
+        }
+    }
+
+    #[test]
+    fn code_view_highlights_rust() {
+        let mut lexer = RustLexer::default();
+        let styles = test_code_styles();
+        let styled: Vec<Vec<(&str, Color)>> = TEST_CODE
+            .lines()
+            .map(|line| {
+                lexer
+                    .tokens(line, &styles)
+                    .into_iter()
+                    .filter(|(text, _)| !text.trim().is_empty())
+                    .map(|(text, style)| (text, style.fg.unwrap()))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(styled[0], vec![("#[derive(Debug)]", Color::Yellow)]);
+        assert_eq!(
+            styled[1][..2],
+            [("fn", Color::Magenta), ("main", Color::Gray)]
+        );
+        let literals = |line: &[(&str, Color)]| -> Vec<String> {
+            line.iter()
+                .filter(|&&(_, color)| color == Color::Green)
+                .map(|&(text, _)| text.to_owned())
+                .collect()
+        };
+        assert_eq!(literals(&styled[2]).concat(), "\"a\\\"b\"");
+        assert_eq!(styled[2].last(), Some(&("// hi", Color::DarkGray)));
+        assert_eq!(styled[3][0], ("println!", Color::Yellow));
+        assert!(styled[3].contains(&("'x'", Color::Green)));
+        assert_eq!(
+            styled[4],
+            vec![("/*", Color::DarkGray), (" Vec<u8>", Color::DarkGray)]
+        );
+        assert_eq!(styled[5][0], ("    */", Color::DarkGray));
+        assert!(styled[5].contains(&("Vec", Color::Cyan)));
+        // The lifetime isn't a character literal.
+        assert_eq!(literals(&styled[5]), vec!["42"]);
+    }
+
+    #[test]
+    fn code_view_numbers_lines_and_marks_one() {
+        let code_view = || CodeView::new("fn a() {}\nfn b() {}", 99, test_code_styles());
+        assert_eq!(code_view().preferred_width(), 14);
+        assert_eq!(
+            render_rows(code_view().mark_line(100), 14, 3),
+            vec![" 99  fn a() {}", "100▶ fn b() {}", "              "]
+        );
+        let buffer = render_buffer(code_view().mark_line(100), 14, 2);
+        assert_eq!(buffer.get(0, 1).bg, Color::Black);
+        assert_eq!(buffer.get(13, 1).bg, Color::Black);
+        assert_eq!(buffer.get(13, 0).bg, Color::Reset);
+        assert_eq!(buffer.get(0, 0).fg, Color::Blue);
+        assert_eq!(
+            render_rows(code_view().scroll(1), 14, 1),
+            vec!["100  fn b() {}"]
+        );
+    }
+
+    fn test_timeline(offset_secs: u64) -> Timeline<'static> {
+        let secs = Duration::from_secs;
+        let row =
+            TimelineRow::new(Spans::from("a"), secs(8)).busy_periods(vec![(secs(5), secs(3))]);
+        Timeline::new(vec![row], secs(1))
+            .offset(secs(offset_secs))
+            .header(Spans::from("task"))
+            .label_width(1)
+    }
+
+    #[test]
+    fn timeline_renders_into_degenerate_areas() {
+        for area in degenerate_areas() {
+            render_degenerate(area, |area, buffer| test_timeline(2).render(area, buffer));
+        }
+    }
+
+    #[test]
+    fn timeline_draws_lives_up_to_now() {
+        assert_eq!(
+            render_rows(test_timeline(0), 12, 2),
+            ["task        ", "a   ├──━━───"]
+        );
+        // Panned two seconds back, the bar moves right and now is off the edge.
+        assert_eq!(render_rows(test_timeline(2), 12, 2)[1], "a     ├──━━─");
+    }
+
+    // Two tasks, each holding the mutex the other waits on, and another waiting on the mutex
+    // and on the first task.
+    fn test_graph_view() -> GraphView<'static> {
+        let nodes = ["ta", "mu", "tb", "tc"]
+            .iter()
+            .map(|&label| GraphNode::new(Spans::from(label)))
+            .collect();
+        GraphView::new(nodes, vec![(0, 1), (1, 2), (2, 0), (1, 3), (3, 9), (0, 3)])
+    }
+
+    #[test]
+    fn graph_view_renders_into_degenerate_areas() {
+        for area in degenerate_areas() {
+            render_degenerate(area, |area, buffer| test_graph_view().render(area, buffer));
+        }
+    }
+
+    #[test]
+    fn graph_view_lays_out_layers_and_detours_along_the_right() {
+        assert_eq!(
+            render_rows(test_graph_view(), 10, 9),
+            [
+                " ▼──────┐ ",
+                "ta      │ ",
+                " ├──────┼┐",
+                " ▼      ││",
+                "mu      ││",
+                " ├────┐ ││",
+                " ▼    ▼─┼┘",
+                "tb   tc │ ",
+                " └──────┘ ",
+            ]
+        );
+    }
+
+    fn test_time_ruler_label(ago: Duration) -> String {
+        format!("{}s", ago.as_secs())
+    }
+
+    #[test]
+    fn time_ruler_renders_into_degenerate_areas() {
+        for area in degenerate_areas() {
+            render_degenerate(area, |area, buffer| {
+                TimeRuler::new(Duration::from_secs(1), &test_time_ruler_label).render(area, buffer)
+            });
+        }
+    }
+
+    #[test]
+    fn time_ruler_ticks_round_times_with_room_for_their_labels() {
+        let ruler = |offset_secs| {
+            TimeRuler::new(Duration::from_secs(1), &test_time_ruler_label)
+                .offset(Duration::from_secs(offset_secs))
+        };
+        assert_eq!(render_rows(ruler(0), 20, 1), [" 15s╵ 10s╵  5s╵  0s╵"]);
+        // Panned back, there's no room before the oldest tick for its label, nor after it.
+        assert_eq!(render_rows(ruler(2), 20, 1), ["   15s╵ 10s╵  5s╵   "]);
+        // Zoomed out, the ticks spread to keep the labels apart.
+        assert_eq!(
+            render_rows(
+                TimeRuler::new(Duration::from_secs(10), &test_time_ruler_label),
+                20,
+                1
+            ),
+            ["   120s╵  60s╵   0s╵"]
+        );
+    }
+
+    static TEST_MARKDOWN: &'static str =
+        "# Keys\n\n- `tab` moves **the focus**\n  1. nested `item`\n**unclosed `tick";
+
+    fn test_markdown_styles() -> MarkdownStyles {
+        MarkdownStyles {
+            text: Style::default().fg(Color::Gray),
+            heading: Style::default().fg(Color::Cyan),
+            bold: Style::default().add_modifier(Modifier::BOLD),
+            code: Style::default().fg(Color::Yellow),
+            bullet: Style::default().fg(Color::DarkGray),
+        }
+    }
+
+    #[test]
+    fn markdown_renders_into_degenerate_areas() {
+        for area in degenerate_areas() {
+            render_degenerate(area, |area, buffer| {
+                Markdown::new(TEST_MARKDOWN, test_markdown_styles())
+                    .scroll(1)
+                    .render(area, buffer)
+            });
+        }
+    }
+
+    #[test]
+    fn markdown_styles_headings_lists_bold_and_code() {
+        let markdown = || Markdown::new(TEST_MARKDOWN, test_markdown_styles());
+        assert_eq!(markdown().preferred_width(), 21);
+        assert_eq!(markdown().preferred_height(), 5);
+
+        let buffer = render_buffer(markdown(), 24, 5);
+        let rows: Vec<String> = (0..5)
+            .map(|y| (0..24).map(|x| &buffer.get(x, y).symbol[..]).collect())
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                "Keys                    ",
+                "                        ",
+                "• tab moves the focus   ",
+                "  1. nested item        ",
+                "**unclosed `tick        ",
+            ]
+        );
+        assert_eq!(buffer.get(0, 0).fg, Color::Cyan);
+        assert_eq!(buffer.get(0, 2).fg, Color::DarkGray);
+        assert_eq!(buffer.get(2, 2).fg, Color::Yellow);
+        assert_eq!(buffer.get(6, 2).modifier, Modifier::empty());
+        assert_eq!(buffer.get(12, 2).modifier, Modifier::BOLD);
+        assert_eq!(buffer.get(12, 2).fg, Color::Gray);
+        assert_eq!(buffer.get(12, 3).fg, Color::Yellow);
+    }
+
+    #[test]
+    fn markdown_wraps_list_items_under_their_text() {
+        let text = |rows: Vec<Spans>| -> Vec<String> {
+            rows.iter()
+                .map(|row| row.0.iter().map(|span| &span.content[..]).collect())
+                .collect()
+        };
+        let markdown = Markdown::new("- one two three\nabcdefghij", MarkdownStyles::default());
+        assert_eq!(
+            text(markdown.rows(9)),
+            vec!["• one two", "  three", "abcdefghi", "j"]
+        );
+        assert_eq!(text(markdown.rows(1)).len(), 21);
+        let rows = render_rows(markdown.scroll(1), 9, 2);
+        assert_eq!(rows, vec!["  three  ", "abcdefghi"]);
+    }
+
+    #[test]
+    fn ring_renders_into_degenerate_areas() {
+        for area in degenerate_areas() {
+            for &ratio in &[0.0, 0.42, 1.0, f32::NAN, -3.0, 7.0] {
+                render_degenerate(area, |area, buffer| {
+                    Ring::new(ratio, Color::Green)
+                        .track_color(Color::DarkGray)
+                        .render(area, buffer)
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn ring_fills_clockwise_from_the_top() {
+        assert_eq!(
+            render_rows(Ring::new(0.5, Color::Green), 6, 1),
+            vec!["◑ 50% "]
+        );
+        assert_eq!(
+            render_rows(Ring::new(0.9, Color::Green), 6, 1),
+            vec!["● 90% "]
+        );
+        assert_eq!(
+            render_rows(Ring::new(f32::NAN, Color::Green), 6, 1),
+            vec!["○ 0%  "]
+        );
+        assert_eq!(Ring::new(1.0, Color::Green).preferred_width(), 6);
+
+        let buffer = render_buffer(Ring::new(0.5, Color::Green).track_color(Color::Gray), 7, 3);
+        let rows: Vec<String> = (0..3)
+            .map(|y| (0..7).map(|x| &buffer.get(x, y).symbol[..]).collect())
+            .collect();
+        assert_eq!(rows, vec!["╭──━━━┓", "│ 50% ┃", "╰───━━┛"]);
+        assert_eq!(buffer.get(6, 1).fg, Color::Green);
+        assert_eq!(buffer.get(0, 1).fg, Color::Gray);
+    }
+
+    #[test]
+    fn ring_draws_with_its_symbols() {
+        let ring = |ratio| Ring::new(ratio, Color::Green).symbols(&RING_SYMBOLS_ASCII);
+        assert_eq!(render_rows(ring(0.5), 6, 1), vec!["o 50% "]);
+        assert_eq!(render_rows(ring(1.0), 7, 1), vec!["@ 100% "]);
+        assert_eq!(
+            render_rows(ring(0.5), 7, 3),
+            vec!["+-----+", "| 50% |", "+-----+"]
+        );
+    }
+
+    #[test]
+    fn canvas_renders_into_degenerate_areas() {
+        for area in degenerate_areas() {
+            for &symbols in &[CanvasSymbols::Braille, CanvasSymbols::HalfBlocks] {
+                render_degenerate(area, |area, buffer| {
+                    Canvas::new(symbols)
+                        .point((1, 1), Color::Green)
+                        .line((0, 0), (40, 9), Color::Green)
+                        .rect(Rect::new(1, 1, 30, 30), Color::Blue)
+                        .filled_rect(Rect::new(0, 2, 3, 3), Color::Red)
+                        .render(area, buffer)
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn canvas_draws_shapes_in_braille() {
+        let canvas = Canvas::new(CanvasSymbols::Braille)
+            .rect(Rect::new(0, 0, 4, 4), Color::Blue)
+            .line((4, 0), (7, 3), Color::Green)
+            .point((7, 0), Color::Red);
+        let buffer = render_buffer(canvas, 4, 1);
+        let row: String = (0..4).map(|x| &buffer.get(x, 0).symbol[..]).collect();
+        assert_eq!(row, "⣏⣹⠑⢌");
+        assert_eq!(buffer.get(0, 0).fg, Color::Blue);
+        assert_eq!(buffer.get(2, 0).fg, Color::Green);
+        assert_eq!(buffer.get(3, 0).fg, Color::Red);
+    }
+
+    #[test]
+    fn canvas_draws_shapes_in_half_blocks() {
+        let canvas = Canvas::new(CanvasSymbols::HalfBlocks)
+            .filled_rect(Rect::new(0, 1, 2, 2), Color::Blue)
+            .line((3, 0), (3, 3), Color::Green)
+            .point((5, 3), Color::Red);
+        assert_eq!(render_rows(canvas, 6, 2), vec!["▄▄ █  ", "▀▀ █ ▄"]);
+    }
+
+    #[test]
+    fn canvas_lines_reach_their_ends_at_any_slope() {
+        let canvas = Canvas::new(CanvasSymbols::HalfBlocks)
+            .line((0, 3), (5, 0), Color::Green)
+            .line((0, 0), (1, 3), Color::Green);
+        assert_eq!(render_rows(canvas, 6, 2), vec!["█  ▄▄▀", "▄█▀   "]);
+    }
+
+    // Unpacks braille rows back into the dots lit in each sample's column, bottom dot first.
+    fn decode_braille_columns(rows: &[String], sample_count: usize) -> Vec<Vec<bool>> {
+        let mut columns = vec![vec![]; sample_count];
+        for row in rows.iter().rev() {
+            for (cell_index, symbol) in row.chars().enumerate() {
+                let bits = DOTS.iter().position(|&dots| dots == symbol).unwrap();
+                for (half, mask) in [bits & 0xf, bits >> 4].iter().enumerate() {
+                    let sample_index = cell_index * 2 + half;
+                    if sample_index < sample_count {
+                        columns[sample_index].extend((0..4).map(|bit| mask & (1 << bit) != 0));
+                    } else {
+                        assert_eq!(*mask, 0, "padding column of an odd sample count was drawn");
+                    }
+                }
+            }
+        }
+        columns
+    }
+
+    fn lit_run(column: &[bool]) -> Option<(usize, usize)> {
+        let start = column.iter().position(|&lit| lit)?;
+        let end = column.iter().rposition(|&lit| lit)? + 1;
+        Some((start, end))
+    }
+
+    proptest! {
+        #[test]
+        fn braille_rows_have_one_cell_per_sample_pair(
+            data in vec(-100.0f32..100.0, 0..40),
+            row_count in 0u16..6,
+        ) {
+            let rows = encode_braille_rows(&data, -50.0, 50.0, None, row_count);
+            prop_assert_eq!(rows.len(), row_count as usize);
+            for row in &rows {
+                prop_assert_eq!(row.chars().count(), data.chunks(2).len());
+            }
+        }
+
+        // Each sample maps to its own dot column, in order, and a larger sample never lights
+        // fewer dots than a smaller one.
+        #[test]
+        fn braille_columns_follow_sample_order(
+            data in vec(-100.0f32..100.0, 0..40),
+            min_y in -50.0f32..0.0,
+            span in 1.0f32..100.0,
+            row_count in 1u16..6,
+        ) {
+            let max_y = min_y + span;
+            let rows = encode_braille_rows(&data, min_y, max_y, None, row_count);
+            let columns = decode_braille_columns(&rows, data.len());
+            let heights: Vec<usize> = columns
+                .iter()
+                .map(|column| column.iter().filter(|&&lit| lit).count())
+                .collect();
+            for (column, &height) in columns.iter().zip(&heights) {
+                if height > 0 {
+                    prop_assert_eq!(lit_run(column), Some((0, height)));
+                }
+            }
+            for a in 0..data.len() {
+                for b in 0..data.len() {
+                    if data[a] < data[b] {
+                        prop_assert!(heights[a] <= heights[b], "{:?} {:?}", data, heights);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn braille_columns_clamp_to_the_y_range(
+            data in vec(-100.0f32..100.0, 0..40),
+            min_y in -50.0f32..0.0,
+            span in 1.0f32..50.0,
+            row_count in 1u16..6,
+        ) {
+            let max_y = min_y + span;
+            let rows = encode_braille_rows(&data, min_y, max_y, None, row_count);
+            let columns = decode_braille_columns(&rows, data.len());
+            for (&value, column) in data.iter().zip(&columns) {
+                let height = column.iter().filter(|&&lit| lit).count();
+                if value >= max_y {
+                    prop_assert_eq!(height, row_count as usize * 4);
+                } else if value <= min_y {
+                    prop_assert_eq!(height, 0);
+                }
+            }
+        }
+
+        // Bars grow away from the baseline, so every lit run touches the baseline's row
+        // boundary.
+        #[test]
+        fn braille_columns_touch_the_baseline(
+            data in vec(-100.0f32..100.0, 0..40),
+            baseline in -100.0f32..100.0,
+            row_count in 1u16..6,
+        ) {
+            let rows = encode_braille_rows(&data, -50.0, 50.0, Some(baseline), row_count);
+            let columns = decode_braille_columns(&rows, data.len());
+            let baseline_norm = clamp((baseline + 50.0) / 100.0, 0.0, 1.0);
+            let baseline_dot = (baseline_norm * row_count as f32).round() as usize * 4;
+            for column in &columns {
+                if let Some((start, end)) = lit_run(column) {
+                    prop_assert!(start == baseline_dot || end == baseline_dot);
+                    prop_assert!(column[start..end].iter().all(|&lit| lit));
+                }
+            }
+        }
+
+        #[test]
+        fn graph_renders_arbitrary_data(
+            data in vec(ANY_F32, 0..40),
+            min_y in ANY_F32,
+            max_y in ANY_F32,
+            width in 0u16..12,
+            height in 0u16..8,
+        ) {
+            let area = Rect::new(0, 0, width, height);
+            let series = [
+                GraphSeries::new("a", &data, Color::Green),
+                GraphSeries::new("b", &data[data.len() / 2..], Color::Blue),
+            ];
+            let mut buffer = Buffer::empty(area);
+            Graph::new(&series, min_y, max_y).axis(Color::Gray).render(area, &mut buffer);
+
+            let mut buffer = Buffer::empty(area);
+            Graph::auto_scaled(&series, AutoScale::default())
+                .axis(Color::Gray)
+                .legend(Color::White)
+                .render(area, &mut buffer);
+        }
+
+        #[test]
+        fn bar_chart_renders_arbitrary_data(
+            data in vec(ANY_F32, 0..40),
+            min_y in ANY_F32,
+            max_y in ANY_F32,
+            baseline in proptest::option::of(ANY_F32),
+            width in 0u16..12,
+            height in 0u16..8,
+        ) {
+            let area = Rect::new(0, 0, width, height);
+            let mut buffer = Buffer::empty(area);
+            let mut chart = BarChart::new(&data, min_y, max_y, Color::Green);
+            if let Some(baseline) = baseline {
+                chart = chart.baseline(baseline);
+            }
+            chart.render(area, &mut buffer);
+
+            let mut buffer = Buffer::empty(area);
+            let mut chart = BarChart::auto_scaled(&data, AutoScale::default(), Color::Green)
+                .axis(Color::Gray);
+            if let Some(baseline) = baseline {
+                chart = chart.baseline(baseline);
+            }
+            chart.render(area, &mut buffer);
+        }
+    }
+
+    #[test]
+    fn scrollbar_renders_into_degenerate_areas() {
+        for area in degenerate_areas() {
+            render_degenerate(area, |area, buffer| {
+                let mut state = ScrollState::default();
+                state.set_lengths(100, 5);
+                state.scroll_by(50);
+                Scrollbar::new(&mut state, &SCROLLBAR_SYMBOLS_NERD_FONT, Color::Gray)
+                    .render(area, buffer)
+            });
+        }
+    }
+
+    #[test]
+    fn powerline_renders_into_degenerate_areas() {
+        for area in degenerate_areas() {
+            for &direction in &[
+                PowerlineDirection::LeftToRight,
+                PowerlineDirection::RightToLeft,
+            ] {
+                render_degenerate(area, |area, buffer| {
+                    test_powerline(direction, MainVisibility::Visible).render(area, buffer)
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn box_frame_renders_into_degenerate_areas() {
+        for area in degenerate_areas() {
+            render_degenerate(area, |area, buffer| {
+                BoxFrame::new("Tasks")
+                    .label_alignment(Alignment::Center)
+                    .aux_label("updated")
+                    .footer_label("3 tasks")
+                    .render(area, buffer)
+            });
+        }
+    }
+
+    fn test_powerline(
+        direction: PowerlineDirection,
+        main_visibility: MainVisibility,
+    ) -> Powerline<'static> {
+        Powerline::new(&TEST_LABELS)
+            .direction(direction)
+            .main_visibility(main_visibility)
+    }
+
+    #[test]
+    fn powerline_renders_left_to_right() {
+        let buffer = render_buffer(
+            test_powerline(PowerlineDirection::LeftToRight, MainVisibility::Visible),
+            30,
+            1,
+        );
+
+        let mut expected =
+            Buffer::with_lines(vec![" Alpha \u{e0b0} Beta \u{e0b0} Gamma \u{e0b0}       "]);
+        let main_style = Style::default()
+            .bg(Color::Blue)
+            .fg(Color::Black)
+            .add_modifier(Modifier::BOLD);
+        let sub_style = Style::default().bg(Color::Cyan).fg(Color::Black);
+        let sub_sub_style = Style::default().bg(Color::DarkGray).fg(Color::White);
+        expected.set_style(Rect::new(0, 0, 7, 1), main_style);
+        expected.set_style(
+            Rect::new(7, 0, 1, 1),
+            Style::default().bg(Color::Cyan).fg(Color::Blue),
+        );
+        expected.set_style(Rect::new(8, 0, 6, 1), sub_style);
+        expected.set_style(
+            Rect::new(14, 0, 1, 1),
+            Style::default().bg(Color::DarkGray).fg(Color::Cyan),
+        );
+        expected.set_style(Rect::new(15, 0, 7, 1), sub_sub_style);
+        expected.set_style(Rect::new(22, 0, 1, 1), Style::default().fg(Color::DarkGray));
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn powerline_renders_right_to_left() {
+        let buffer = render_buffer(
+            test_powerline(PowerlineDirection::RightToLeft, MainVisibility::Invisible),
+            30,
+            1,
+        );
+
+        let mut expected =
+            Buffer::with_lines(vec!["       \u{e0b2} Gamma \u{e0b3} Beta \u{e0b2} Alpha "]);
+        let sub_style = Style::default().bg(Color::Cyan).fg(Color::Black);
+        let sub_sub_style = Style::default().bg(Color::DarkGray).fg(Color::White);
+        expected.set_style(Rect::new(7, 0, 1, 1), Style::default().fg(Color::DarkGray));
+        expected.set_style(Rect::new(8, 0, 7, 1), sub_sub_style);
+        expected.set_style(
+            Rect::new(15, 0, 1, 1),
+            Style::default().bg(Color::DarkGray).fg(Color::Gray),
+        );
+        expected.set_style(Rect::new(16, 0, 6, 1), sub_sub_style);
+        expected.set_style(
+            Rect::new(22, 0, 1, 1),
+            Style::default().bg(Color::DarkGray).fg(Color::Cyan),
+        );
+        expected.set_style(Rect::new(23, 0, 7, 1), sub_style);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn powerline_drops_segments_that_start_past_the_left_edge() {
+        let rows = render_rows(
+            test_powerline(PowerlineDirection::RightToLeft, MainVisibility::Visible),
+            18,
+            1,
+        );
+        assert_eq!(rows, vec!["   \u{e0b2} Beta \u{e0b2} Alpha "]);
+    }
+
+    #[test]
+    fn powerline_draws_with_its_symbols() {
+        let powerline = |direction| {
+            test_powerline(direction, MainVisibility::Visible).symbols(&POWERLINE_SYMBOLS_ASCII)
+        };
+        assert_eq!(
+            render_rows(powerline(PowerlineDirection::LeftToRight), 24, 1),
+            vec![" Alpha > Beta > Gamma > "]
+        );
+        assert_eq!(
+            render_rows(powerline(PowerlineDirection::RightToLeft), 24, 1),
+            vec![" < Gamma < Beta < Alpha "]
+        );
+    }
+
+    // Each segment is drawn in the inverse colors when selected, and the rounded edges take the
+    // color of the segment next to them.
+    #[test]
+    fn segmented_control_renders_selection_states() {
+        let normal_style = Style::default().fg(Color::White).bg(Color::Blue);
+        let selected_style = Style::default().fg(Color::Blue).bg(Color::White);
+        let selected_ranges = [(1, 6), (7, 6), (13, 6)];
+        for (selected_index, &(selected_x, selected_width)) in selected_ranges.iter().enumerate() {
+            let buffer = render_buffer(
+                SegmentedControl::new(
+                    &TEST_LABELS,
+                    selected_index as u32,
+                    Color::Blue,
+                    Color::White,
+                ),
+                20,
+                1,
+            );
+
+            let mut expected = Buffer::with_lines(vec!["\u{e0b6}Alpha  Beta  Gamma\u{e0b4}"]);
+            expected.set_style(Rect::new(1, 0, 18, 1), normal_style);
+            expected.set_style(Rect::new(selected_x, 0, selected_width, 1), selected_style);
+            let edge_color = |selected| if selected { Color::White } else { Color::Blue };
+            expected.set_style(
+                Rect::new(0, 0, 1, 1),
+                Style::default().fg(edge_color(selected_index == 0)),
+            );
+            expected.set_style(
+                Rect::new(19, 0, 1, 1),
+                Style::default().fg(edge_color(selected_index == TEST_LABELS.len() - 1)),
+            );
+            assert_eq!(buffer, expected, "selected index {}", selected_index);
+        }
+    }
+
+    // Short of room, the longest labels are cut first, and the rounded edges stay.
+    #[test]
+    fn segmented_control_cuts_labels_to_fit() {
+        let rows = render_rows(
+            SegmentedControl::new(&TEST_LABELS, 0, Color::Blue, Color::White),
+            14,
+            1,
+        );
+        assert_eq!(rows, vec!["\u{e0b6}Al…  Be…  G…\u{e0b4}"]);
+    }
+
+    #[test]
+    fn segmented_control_draws_with_its_symbols() {
+        let control = |width| {
+            render_rows(
+                SegmentedControl::new(&TEST_LABELS, 0, Color::Blue, Color::White)
+                    .symbols(&SEGMENTED_CONTROL_SYMBOLS_ASCII),
+                width,
+                1,
+            )
+        };
+        assert_eq!(control(20), vec!["[Alpha  Beta  Gamma]"]);
+        assert_eq!(control(14), vec!["[Al~  Be~  G~]"]);
+    }
+
+    #[test]
+    fn segmented_control_finds_segments_by_column() {
+        let control = SegmentedControl::new(&TEST_LABELS, 0, Color::Blue, Color::White);
+        let indices: Vec<_> = (0..21).map(|column| control.index_at(column, 20)).collect();
+        assert_eq!(indices[..7], [Some(0); 7]);
+        assert_eq!(indices[7..13], [Some(1); 6]);
+        assert_eq!(indices[13..20], [Some(2); 7]);
+        assert_eq!(indices[20], None);
+    }
+
+    #[test]
+    fn segmented_control_finds_segments_cut_short() {
+        // Drawn as "\u{e0b6}Al…  Be…  G…\u{e0b4}".
+        let control = SegmentedControl::new(&TEST_LABELS, 0, Color::Blue, Color::White);
+        let indices: Vec<_> = (0..15).map(|column| control.index_at(column, 14)).collect();
+        assert_eq!(indices[..5], [Some(0); 5]);
+        assert_eq!(indices[5..10], [Some(1); 5]);
+        assert_eq!(indices[10..14], [Some(2); 4]);
+        assert_eq!(indices[14], None);
+    }
+
+    fn test_box_frame() -> BoxFrame<'static> {
+        BoxFrame::new("Tasks")
+    }
+
+    #[test]
+    fn box_frame_renders_at_various_sizes() {
+        assert_eq!(render_rows(test_box_frame(), 2, 2), vec!["╭╮", "╰╯"]);
+        assert_eq!(
+            render_rows(test_box_frame(), 12, 3),
+            vec!["╭─ Tasks ──╮", "│          │", "╰──────────╯"]
+        );
+        assert_eq!(
+            render_rows(
+                test_box_frame().aux_label("live").footer_label("3 tasks"),
+                24,
+                4
+            ),
+            vec![
+                "╭─ Tasks ─────── live ─╮",
+                "│                      │",
+                "│                      │",
+                "╰─ 3 tasks ────────────╯",
+            ]
+        );
+        assert_eq!(
+            render_rows(test_box_frame().label_alignment(Alignment::Center), 16, 3),
+            vec!["╭─── Tasks ────╮", "│              │", "╰──────────────╯"]
+        );
+    }
+
+    #[test]
+    fn box_frame_renders_partial_borders() {
+        assert_eq!(
+            render_rows(
+                test_box_frame()
+                    .label_alignment(Alignment::Right)
+                    .borders(Borders::TOP | Borders::BOTTOM),
+                16,
+                3
+            ),
+            vec!["─────── Tasks ──", "                ", "────────────────"]
+        );
+        assert_eq!(
+            render_rows(
+                test_box_frame()
+                    .border_set(BorderSet::Ascii)
+                    .borders(Borders::LEFT | Borders::TOP),
+                12,
+                3
+            ),
+            vec!["+- Tasks ---", "|           ", "|           "]
+        );
+    }
+
+    // The last column drawn into, plus one.
+    fn drawn_width(buffer: &Buffer) -> u16 {
+        (0..buffer.area.width)
+            .rev()
+            .find(|&x| buffer.get(x, 0) != &Cell::default())
+            .map_or(0, |x| x + 1)
+    }
+
+    #[test]
+    fn preferred_widths_match_what_is_drawn() {
+        for labels in &[&TEST_LABELS[..], &["漢字", ""], &[]] {
+            let powerline = Powerline::new(labels);
+            let buffer = render_buffer(powerline, 40, 1);
+            assert_eq!(
+                powerline.preferred_width(),
+                drawn_width(&buffer),
+                "{:?}",
+                labels
+            );
+
+            let segmented_control = SegmentedControl::new(labels, 0, Color::Black, Color::Gray);
+            let preferred_width = segmented_control.preferred_width();
+            let buffer = render_buffer(segmented_control, 40, 1);
+            assert_eq!(preferred_width, drawn_width(&buffer), "{:?}", labels);
+        }
+    }
+
+    #[test]
+    fn box_frame_inner_excludes_borders_and_padding() {
+        let area = Rect::new(2, 3, 20, 10);
+        assert_eq!(test_box_frame().inner(area), Rect::new(3, 4, 18, 8));
+        assert_eq!(
+            test_box_frame().padding(2, 1).inner(area),
+            Rect::new(5, 5, 14, 6)
+        );
+        assert_eq!(
+            test_box_frame()
+                .borders(Borders::TOP | Borders::LEFT)
+                .inner(area),
+            Rect::new(3, 4, 19, 9)
+        );
+        // Padding that doesn't fit leaves an empty area at the edge of the frame.
+        let inner = test_box_frame().padding(20, 0).inner(area);
+        assert_eq!((inner.width, inner.height), (0, 8));
+        assert!(inner.x <= area.right());
+    }
+
+    fn render_scrollbar(content_length: usize, viewport_length: usize, offset: isize) -> String {
+        let mut state = ScrollState::default();
+        state.set_lengths(content_length, viewport_length);
+        state.scroll_by(offset);
+        render_rows(
+            Scrollbar::new(&mut state, &SCROLLBAR_SYMBOLS_ASCII, Color::Gray),
+            1,
+            7,
+        )
+        .concat()
+    }
+
+    #[test]
+    fn scrollbar_renders_scroll_ranges() {
+        assert_eq!(render_scrollbar(3, 5, 0), "^#####v");
+        assert_eq!(render_scrollbar(100, 5, 0), "^#||||v");
+        assert_eq!(render_scrollbar(100, 5, 47), "^||#||v");
+        assert_eq!(render_scrollbar(100, 5, 1000), "^||||#v");
+        assert_eq!(render_scrollbar(10, 5, 5), "^||###v");
+    }
+
+    #[test]
+    fn text_input_renders_into_degenerate_areas() {
+        let mut state = TextInputState::default();
+        for character in "hello".chars() {
+            state.insert(character);
+        }
+        for area in degenerate_areas() {
+            render_degenerate(area, |area, buffer| {
+                TextInput::new(&state, "", Color::White, Color::Gray).render(area, buffer)
+            });
+            render_degenerate(area, |area, buffer| {
+                TextInput::new(
+                    &TextInputState::default(),
+                    "Type",
+                    Color::White,
+                    Color::Gray,
+                )
+                .render(area, buffer)
+            });
+        }
+    }
+
+    #[test]
+    fn text_input_edits_at_the_cursor() {
+        let mut state = TextInputState::default();
+        for character in "héllo".chars() {
+            state.insert(character);
+        }
+        state.move_cursor(-2);
+        state.delete_backward();
+        state.insert('L');
+        assert_eq!((state.text(), state.cursor()), ("héLlo", 3));
+        state.move_to_start();
+        state.delete_forward();
+        state.delete_backward();
+        assert_eq!((state.text(), state.cursor()), ("éLlo", 0));
+        state.move_cursor(isize::MAX);
+        assert_eq!(state.cursor(), 4);
+        state.move_cursor(isize::MIN);
+        assert_eq!(state.cursor(), 0);
+        state.clear();
+        assert_eq!((state.text(), state.cursor()), ("", 0));
+    }
+
+    // Text that doesn't fit scrolls to keep the cursor, drawn inverted, at the right edge.
+    #[test]
+    fn text_input_scrolls_to_the_cursor() {
+        let mut state = TextInputState::default();
+        for character in "abcdefgh".chars() {
+            state.insert(character);
+        }
+        let render = |state: &TextInputState| {
+            render_buffer(TextInput::new(state, "", Color::White, Color::Gray), 5, 1)
+        };
+        let buffer = render(&state);
+        let mut expected = Buffer::with_lines(vec!["efgh "]);
+        expected.set_style(Rect::new(0, 0, 5, 1), Style::default().fg(Color::White));
+        expected.set_style(
+            Rect::new(4, 0, 1, 1),
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::REVERSED),
+        );
+        assert_eq!(buffer, expected);
+
+        state.move_to_start();
+        assert_eq!(render(&state).content[0].modifier, Modifier::REVERSED);
+        assert_eq!(
+            render_rows(TextInput::new(&state, "", Color::White, Color::Gray), 5, 1),
+            vec!["abcde"]
+        );
+    }
+
+    #[test]
+    fn revealing_a_line_scrolls_no_further_than_needed() {
+        let mut state = ScrollState::default();
+        state.set_lengths(100, 5);
+        state.reveal(3);
+        assert_eq!(state.offset(), 0);
+        state.reveal(9);
+        assert_eq!(state.offset(), 5);
+        state.reveal(7);
+        assert_eq!(state.offset(), 5);
+        state.reveal(2);
+        assert_eq!(state.offset(), 2);
+        state.reveal(1000);
+        assert_eq!(state.offset(), 95);
+    }
+}